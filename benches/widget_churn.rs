@@ -0,0 +1,97 @@
+// Widget churn benchmarks
+// The bookkeeping and style work behind every CreateWidget / SetWidgetStyle /
+// RemoveWidget, without a window. `vellum --stress` covers the same paths
+// end to end, including layout and paint.
+
+use std::hint::black_box;
+
+use criterion::{BatchSize, Criterion, criterion_group, criterion_main};
+use masonry::core::WidgetId;
+use vellum::ipc::{BoxStyle, WidgetKind};
+use vellum::ui::styles::{build_box_properties, build_text_styles};
+use vellum::ui::widget_manager::{WidgetInfo, WidgetManager};
+
+const WIDGETS: usize = 1_000;
+
+const STYLE_JSON: &str = r##"{
+    "fontSize": 14,
+    "fontWeight": 600,
+    "color": "#e0e0e0",
+    "background": "rgba(30, 40, 60, 0.9)",
+    "borderColor": "#445566",
+    "borderWidth": 1,
+    "cornerRadius": 6,
+    "padding": 8,
+    "direction": "row",
+    "gap": 4
+}"##;
+
+/// A root list of `WIDGETS` rows, each with one child.
+fn populate(manager: &mut WidgetManager) {
+    for index in 0..WIDGETS {
+        let row = format!("row-{index}");
+        manager.register_widget(
+            row.clone(),
+            WidgetInfo {
+                widget_id: WidgetId::next(),
+                kind: WidgetKind::Flex,
+                parent_id: None,
+                child_index: index,
+            },
+        );
+        manager.register_widget(
+            format!("label-{index}"),
+            WidgetInfo {
+                widget_id: WidgetId::next(),
+                kind: WidgetKind::Label,
+                parent_id: Some(row),
+                child_index: 0,
+            },
+        );
+    }
+}
+
+fn widget_manager(c: &mut Criterion) {
+    c.bench_function("register 1k rows", |b| {
+        b.iter(|| {
+            let mut manager = WidgetManager::new();
+            populate(&mut manager);
+            black_box(manager)
+        })
+    });
+
+    c.bench_function("remove 1k rows", |b| {
+        b.iter_batched(
+            || {
+                let mut manager = WidgetManager::new();
+                populate(&mut manager);
+                manager
+            },
+            |mut manager| {
+                // Newest first, as a list being cleared from the end.
+                for index in (0..WIDGETS).rev() {
+                    black_box(manager.remove_widget_subtree(&format!("row-{index}")));
+                }
+                manager
+            },
+            BatchSize::SmallInput,
+        )
+    });
+}
+
+fn style_pipeline(c: &mut Criterion) {
+    c.bench_function("parse style", |b| {
+        b.iter(|| serde_json::from_str::<BoxStyle>(black_box(STYLE_JSON)).unwrap())
+    });
+
+    let style: BoxStyle = serde_json::from_str(STYLE_JSON).unwrap();
+    c.bench_function("build box properties", |b| {
+        b.iter(|| build_box_properties(black_box(&style)))
+    });
+    c.bench_function("build text styles", |b| {
+        b.iter(|| build_text_styles(black_box(&style)))
+    });
+}
+
+criterion_group!(benches, widget_manager, style_pipeline);
+criterion_main!(benches);
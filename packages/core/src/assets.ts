@@ -0,0 +1,39 @@
+import process from "node:process";
+import fs from "node:fs";
+import path from "node:path";
+
+const SCHEME = "asset://";
+
+/**
+ * Directory `asset://` URLs resolve against. The vellum binary sets
+ * `VELLUM_ASSETS_DIR` when it hosts the app (including packaged apps, whose
+ * assets are unpacked at startup); otherwise `./assets` is used.
+ */
+export function assetsDir(): string {
+    return process.env.VELLUM_ASSETS_DIR ?? path.resolve("assets");
+}
+
+/** Absolute path for `asset://images/logo.png`, e.g. for `await import(...)`. */
+export function assetPath(url: string): string {
+    if (!url.startsWith(SCHEME)) {
+        throw new Error(`Vellum.assetPath: expected an ${SCHEME} URL, got "${url}"`);
+    }
+    const relative = url.slice(SCHEME.length);
+    const root = path.resolve(assetsDir());
+    const resolved = path.resolve(root, relative);
+    if (path.isAbsolute(relative) || !resolved.startsWith(root + path.sep)) {
+        throw new Error(`Vellum.assetPath: "${url}" points outside the asset directory`);
+    }
+    return resolved;
+}
+
+/** Read an asset's bytes. */
+export function readAsset(url: string): Uint8Array {
+    return fs.readFileSync(assetPath(url));
+}
+
+export const assets = {
+    dir: assetsDir,
+    path: assetPath,
+    read: readAsset,
+};
@@ -6,10 +6,44 @@ import path from "node:path";
 import crypto from "node:crypto";
 import { spawn, type ChildProcess } from "node:child_process";
 import { decode, encode } from "@msgpack/msgpack";
+import type {
+    AudioDevice,
+    ClickButton,
+    DockMenuItem,
+    ErrorCode,
+    ExitReason,
+    FontFamily,
+    Metrics,
+    PaintOp,
+    RecentCommand,
+} from "./types.ts";
+import { formatError } from "./errors.ts";
+import { appId } from "./paths.ts";
+
+/** Version of the socket messages; must match `PROTOCOL_VERSION` in socket.rs. */
+export const PROTOCOL_VERSION = 1;
+
+// When launched by `vellum app.ts` the binary already owns the window and the
+// socket, so the bridge only connects instead of spawning a second binary.
+// Either way the parent process picks the socket and passes it down in
+// VELLUM_SOCKET, along with its VELLUM_PROTOCOL_VERSION.
+const IS_HOSTED = process.env.VELLUM_HOSTED === "1" && !!process.env.VELLUM_SOCKET;
+
+if (IS_HOSTED) {
+    const binaryProtocol = process.env.VELLUM_PROTOCOL_VERSION;
+    if (binaryProtocol && binaryProtocol !== String(PROTOCOL_VERSION)) {
+        process.stderr.write(
+            `[Vellum bridge] The binary speaks protocol version ${binaryProtocol}, ` +
+                `@vellum/core ${PROTOCOL_VERSION}; update them together\n`,
+        );
+    }
+}
 
-const SOCKET_PATH = process.platform === "win32"
-    ? `${os.tmpdir()}\\Vellum_${crypto.randomUUID()}.sock`
-    : `/tmp/Vellum_${crypto.randomUUID()}.sock`;
+const SOCKET_PATH = IS_HOSTED
+    ? process.env.VELLUM_SOCKET!
+    : process.platform === "win32"
+        ? `${os.tmpdir()}\\Vellum_${crypto.randomUUID()}.sock`
+        : `/tmp/Vellum_${crypto.randomUUID()}.sock`;
 
 function findVellumBinary(): string {
     const isWin = process.platform === "win32";
@@ -39,13 +73,60 @@ export type BridgeEvent = {
     widgetId?: string;
     action?: string;
     value?: string | number | boolean;
+    low?: number;
+    high?: number;
+    from?: number;
+    to?: number;
     source?: string;
+    code?: ErrorCode;
+    command?: string;
     message?: string;
     fatal?: boolean;
+    url?: string;
+    paths?: string[];
+    hasVisibleWindows?: boolean;
+    itemId?: string;
+    requestId?: string;
+    families?: FontFamily[];
+    commands?: RecentCommand[];
+    metrics?: Metrics;
+    devices?: AudioDevice[];
+    device?: AudioDevice | null;
+    result?: string;
+    error?: string;
+    pages?: number;
+    snapshot?: Uint8Array;
+    canUndo?: boolean;
+    canRedo?: boolean;
+    ratio?: number;
+    width?: number;
+    height?: number;
+    version?: string;
+    notes?: string;
+    downloaded?: number;
+    total?: number;
+    x?: number;
+    y?: number;
+    /** Milliseconds since the UI started (monotonic), taken when Rust sent the event. */
+    timestamp?: number;
+    /** Send order of the event among all events, for ordering across awaits. */
+    seq?: number;
+    button?: ClickButton;
+    clickCount?: number;
+    shift?: boolean;
+    ctrl?: boolean;
+    alt?: boolean;
+    meta?: boolean;
+    exitCode?: number;
+    reason?: ExitReason;
+    timeoutMs?: number;
 };
 
+export type BeforeExitHandler = (event: BridgeEvent) => void | Promise<void>;
+
 export type JsToRustMessage =
     | { type: "setTitle"; title: string }
+    | { type: "setContentProtection"; enabled: boolean }
     | {
         type: "createWidget";
         id: string;
@@ -57,28 +138,100 @@ export type JsToRustMessage =
         data: Uint8Array | null;
     }
     | { type: "removeWidget"; id: string }
+    | { type: "validateTree"; repair: boolean }
+    | { type: "showErrorOverlay"; title: string; details: string }
+    | { type: "setSplash"; image: Uint8Array | null; message: string | null; progress: number | null }
     | { type: "setWidgetText"; id: string; text: string }
     | { type: "setWidgetVisible"; id: string; visible: boolean }
+    | { type: "setWidgetEnabled"; id: string; enabled: boolean }
     | { type: "setWidgetValue"; id: string; value: number }
+    | { type: "setProgressIndeterminate"; id: string; indeterminate: boolean }
+    | { type: "setProgressBuffer"; id: string; buffer: number | null }
+    | { type: "setWidgetRange"; id: string; low: number; high: number }
+    | { type: "setBadge"; id: string; badge_json: string | null }
+    | { type: "setAvatarStatus"; id: string; status: string | null }
+    | { type: "registerIconPack"; prefix: string; icons: Record<string, string> }
     | { type: "setWidgetChecked"; id: string; checked: boolean }
     | { type: "setWidgetStyle"; id: string; style_json: string }
     | { type: "setStyleProperty"; id: string; property: string; value: string }
     | { type: "resizeWindow"; width: number; height: number }
     | { type: "closeWindow" }
-    | { type: "exitApp" }
+    | { type: "exitApp"; code: number }
+    | { type: "setDockMenu"; items: DockMenuItem[] }
+    | { type: "inhibitSleep"; token: string; reason: string }
+    | { type: "releaseSleep"; token: string }
+    | { type: "setLayoutDirection"; direction: "ltr" | "rtl" }
+    | { type: "setRawProperty"; id: string; name: string; json: string }
+    | { type: "setPaintOps"; id: string; ops: PaintOp[] }
+    | { type: "setShader"; id: string; wgsl: string }
+    | { type: "setShaderUniforms"; id: string; values: number[] }
+    | { type: "setUiScale"; factor: number }
+    | { type: "listFonts"; request_id: string }
+    | { type: "hitTest"; request_id: string; x: number; y: number }
+    | { type: "dumpRecentCommands"; request_id: string }
+    | { type: "getMetrics"; request_id: string }
+    | { type: "printWindow"; request_id: string }
+    | { type: "exportPdf"; request_id: string; path: string; widget_id: string | null }
+    | { type: "serializeTree"; request_id: string }
+    | { type: "restoreTree"; snapshot: Uint8Array }
+    | { type: "setJournal"; enabled: boolean; limit: number | null }
+    | { type: "beginUndoGroup" }
+    | { type: "endUndoGroup" }
+    | { type: "undo" }
+    | { type: "redo" }
+    | { type: "observeVisibility"; id: string; threshold: number }
+    | { type: "unobserveVisibility"; id: string }
+    | { type: "observeResize"; id: string }
+    | { type: "unobserveResize"; id: string }
+    | { type: "checkForUpdate"; request_id: string; url: string; current_version: string }
+    | { type: "downloadUpdate" }
+    | { type: "restartToUpdate" }
+    | { type: "setFrameTicks"; enabled: boolean }
+    | { type: "setGestureTiming"; double_click_ms: number | null; long_press_ms: number | null }
+    | { type: "bindValue"; id: string; property: string; channel: string }
+    | { type: "unbindValue"; id: string; property: string }
+    | { type: "pushChannel"; channel: string; value: number | string | boolean }
+    | {
+        type: "animateValue";
+        id: string;
+        property: string;
+        from: number;
+        to: number;
+        duration_ms: number;
+        easing: string;
+        repeat: number;
+    }
+    | { type: "stopAnimation"; id: string; property: string | null }
+    | { type: "callNativeOp"; request_id: string; name: string; args_json: string }
     | { type: "setImageData"; id: string; data: Uint8Array }
     | { type: "playVideo"; id: string }
     | { type: "pauseVideo"; id: string }
-    | { type: "seekVideo"; id: string; time_secs: number };
+    | { type: "seekVideo"; id: string; time_secs: number }
+    | { type: "setVideoVolume"; id: string; volume: number }
+    | { type: "setVideoMuted"; id: string; muted: boolean }
+    | { type: "listAudioDevices"; request_id: string }
+    | { type: "setAudioOutput"; device: string | null }
+    | { type: "endRefresh"; id: string };
 
 type RustToJsMessage =
-    | { type: "uiEvent"; event: unknown }
-    | { type: "runtimeError"; source: string; message: string; fatal: boolean }
+    | { type: "uiEvent"; event: unknown; seq: number; timestamp: number }
+    | {
+        type: "runtimeError";
+        source: string;
+        code: ErrorCode;
+        message: string;
+        fatal: boolean;
+        widget_id: string | null;
+        command: string | null;
+        seq: number | null;
+        timestamp: number | null;
+    }
     | { type: "shutdown" };
 
 export type Bridge = {
     send(message: JsToRustMessage): void;
     onEvent(callback: (event: BridgeEvent) => void): () => void;
+    onBeforeExit(handler: BeforeExitHandler): () => void;
 };
 
 type VellumGlobal = typeof globalThis & {
@@ -93,17 +246,345 @@ function writeFrame(socket: net.Socket, message: JsToRustMessage): void {
     socket.write(frame);
 }
 
+type MouseButton = "Primary" | "Secondary" | "Auxiliary" | "Back" | "Forward" | "Other";
+type KeyModifiers = { shift: boolean; ctrl: boolean; alt: boolean; meta: boolean };
+
+const CLICK_BUTTONS: Record<MouseButton, ClickButton> = {
+    Primary: "primary",
+    Secondary: "secondary",
+    Auxiliary: "auxiliary",
+    Back: "back",
+    Forward: "forward",
+    Other: "other",
+};
+
+const EXIT_REASONS = {
+    App: "app",
+    Quit: "quit",
+    RuntimeExited: "runtimeExited",
+    RuntimeFailed: "runtimeFailed",
+    Timeout: "timeout",
+} satisfies Record<string, ExitReason>;
+
+type RustAudioDevice = { id: string; name: string; is_default: boolean };
+
+function toAudioDevice(device: RustAudioDevice): AudioDevice {
+    return { id: device.id, name: device.name, isDefault: device.is_default };
+}
+
 function mapUiEvent(event: unknown): BridgeEvent {
+    const openUrl = (event as { OpenUrl?: { url: string } })?.OpenUrl;
+    if (openUrl) {
+        return { type: "openUrl", url: openUrl.url };
+    }
+
+    const openFiles = (event as { OpenFiles?: { paths: string[] } })?.OpenFiles;
+    if (openFiles) {
+        return { type: "openFiles", paths: openFiles.paths };
+    }
+
+    if (event === "CloseRequested") {
+        return { type: "closeRequested" };
+    }
+
+    const appReopen = (event as { AppReopen?: { has_visible_windows: boolean } })?.AppReopen;
+    if (appReopen) {
+        return { type: "reopen", hasVisibleWindows: appReopen.has_visible_windows };
+    }
+
+    const beforeExit = (event as {
+        BeforeExit?: { code: number; reason: keyof typeof EXIT_REASONS; timeout_ms: number };
+    })?.BeforeExit;
+    if (beforeExit) {
+        return {
+            type: "beforeExit",
+            exitCode: beforeExit.code,
+            reason: EXIT_REASONS[beforeExit.reason],
+            timeoutMs: beforeExit.timeout_ms,
+        };
+    }
+
+    const dockMenuAction = (event as { DockMenuAction?: { id: string } })?.DockMenuAction;
+    if (dockMenuAction) {
+        return { type: "dockMenu", itemId: dockMenuAction.id };
+    }
+
+    const fontList = (event as { FontList?: { request_id: string; families: FontFamily[] } })?.FontList;
+    if (fontList) {
+        return { type: "fontList", requestId: fontList.request_id, families: fontList.families };
+    }
+
+    const hitTest = (event as {
+        HitTestResult?: { request_id: string; widget_id: string | null; x: number; y: number };
+    })?.HitTestResult;
+    if (hitTest) {
+        return {
+            type: "hitTestResult",
+            requestId: hitTest.request_id,
+            widgetId: hitTest.widget_id ?? undefined,
+            x: hitTest.x,
+            y: hitTest.y,
+        };
+    }
+
+    const recent = (event as {
+        RecentCommands?: {
+            request_id: string;
+            commands: { seq: number; name: string; widget_id: string | null; timestamp: number }[];
+        };
+    })?.RecentCommands;
+    if (recent) {
+        return {
+            type: "recentCommands",
+            requestId: recent.request_id,
+            commands: recent.commands.map((command) => ({
+                seq: command.seq,
+                name: command.name,
+                widgetId: command.widget_id ?? undefined,
+                timestamp: command.timestamp,
+            })),
+        };
+    }
+
+    const metrics = (event as {
+        Metrics?: {
+            request_id: string;
+            widget_count: number;
+            videos: { id: string; decoder: { name: string; hardware: boolean } | null }[];
+        };
+    })?.Metrics;
+    if (metrics) {
+        return {
+            type: "metrics",
+            requestId: metrics.request_id,
+            metrics: {
+                widgetCount: metrics.widget_count,
+                videos: metrics.videos.map((video) => ({
+                    id: video.id,
+                    decoder: video.decoder ?? undefined,
+                })),
+            },
+        };
+    }
+
+    const videoError = (event as { VideoError?: { id: string; message: string } })?.VideoError;
+    if (videoError) {
+        return {
+            type: "widgetAction",
+            widgetId: videoError.id,
+            action: "videoError",
+            message: videoError.message,
+        };
+    }
+
+    const visibility = (event as {
+        VisibilityChanged?: { id: string; ratio: number };
+    })?.VisibilityChanged;
+    if (visibility) {
+        return {
+            type: "widgetAction",
+            widgetId: visibility.id,
+            action: "visibilityChanged",
+            ratio: visibility.ratio,
+        };
+    }
+
+    const resized = (event as {
+        WidgetResized?: { id: string; width: number; height: number };
+    })?.WidgetResized;
+    if (resized) {
+        return {
+            type: "widgetAction",
+            widgetId: resized.id,
+            action: "resized",
+            width: resized.width,
+            height: resized.height,
+        };
+    }
+
+    const updateCheck = (event as {
+        UpdateCheckResult?: {
+            request_id: string;
+            version: string | null;
+            notes: string | null;
+            error: string | null;
+        };
+    })?.UpdateCheckResult;
+    if (updateCheck) {
+        return {
+            type: "updateCheckResult",
+            requestId: updateCheck.request_id,
+            version: updateCheck.version ?? undefined,
+            notes: updateCheck.notes ?? undefined,
+            error: updateCheck.error ?? undefined,
+        };
+    }
+
+    const updateProgress = (event as {
+        UpdateProgress?: { downloaded: number; total: number | null };
+    })?.UpdateProgress;
+    if (updateProgress) {
+        return {
+            type: "updateProgress",
+            downloaded: updateProgress.downloaded,
+            total: updateProgress.total ?? undefined,
+        };
+    }
+
+    const updateReady = (event as { UpdateReady?: { version: string } })?.UpdateReady;
+    if (updateReady) {
+        return { type: "updateReady", version: updateReady.version };
+    }
+
+    const updateFailed = (event as { UpdateFailed?: { error: string } })?.UpdateFailed;
+    if (updateFailed) {
+        return { type: "updateFailed", error: updateFailed.error };
+    }
+
+    const frameTick = (event as { FrameTick?: { timestamp: number } })?.FrameTick;
+    if (frameTick) {
+        return { type: "frameTick", timestamp: frameTick.timestamp };
+    }
+
+    const nativeOp = (event as {
+        NativeOpResult?: { request_id: string; result: string | null; error: string | null };
+    })?.NativeOpResult;
+    if (nativeOp) {
+        return {
+            type: "nativeOpResult",
+            requestId: nativeOp.request_id,
+            result: nativeOp.result ?? undefined,
+            error: nativeOp.error ?? undefined,
+        };
+    }
+
+    const printResult = (event as {
+        PrintResult?: { request_id: string; pages: number; error: string | null };
+    })?.PrintResult;
+    if (printResult) {
+        return {
+            type: "printResult",
+            requestId: printResult.request_id,
+            pages: printResult.pages,
+            error: printResult.error ?? undefined,
+        };
+    }
+
+    const treeSnapshot = (event as {
+        TreeSnapshot?: { request_id: string; snapshot: Uint8Array };
+    })?.TreeSnapshot;
+    if (treeSnapshot) {
+        return {
+            type: "treeSnapshot",
+            requestId: treeSnapshot.request_id,
+            snapshot: treeSnapshot.snapshot,
+        };
+    }
+
+    const audioDevices = (event as {
+        AudioDevices?: { request_id: string; devices: RustAudioDevice[] };
+    })?.AudioDevices;
+    if (audioDevices) {
+        return {
+            type: "audioDevices",
+            requestId: audioDevices.request_id,
+            devices: audioDevices.devices.map(toAudioDevice),
+        };
+    }
+
+    const journal = (event as {
+        JournalChanged?: { can_undo: boolean; can_redo: boolean };
+    })?.JournalChanged;
+    if (journal) {
+        return { type: "journalChanged", canUndo: journal.can_undo, canRedo: journal.can_redo };
+    }
+
+    const defaultDevice = (event as {
+        DefaultDeviceChanged?: { device: RustAudioDevice | null };
+    })?.DefaultDeviceChanged;
+    if (defaultDevice) {
+        return {
+            type: "defaultDeviceChanged",
+            device: defaultDevice.device ? toAudioDevice(defaultDevice.device) : null,
+        };
+    }
+
+    const power = (event as { Power?: string | { LowPowerMode: boolean } })?.Power;
+    if (power !== undefined) {
+        if (typeof power === "object") {
+            return { type: "lowPowerMode", value: power.LowPowerMode };
+        }
+        const powerTypes: Record<string, string> = {
+            Suspend: "suspend",
+            Resume: "resume",
+            Lock: "lock",
+            Unlock: "unlock",
+        };
+        return { type: powerTypes[power] ?? "unknown" };
+    }
+
     const widgetAction = (event as { WidgetAction?: { widget_id?: string; action?: unknown } })?.WidgetAction;
     if (!widgetAction) {
         return { type: "unknown" };
     }
 
-    if (widgetAction.action === "Click") {
+    const click = (widgetAction.action as {
+        Click?: { button: MouseButton | null; count: number; modifiers: KeyModifiers };
+    } | undefined)?.Click;
+    if (click) {
         return {
             type: "widgetAction",
             widgetId: widgetAction.widget_id,
             action: "click",
+            button: click.button === null ? "keyboard" : CLICK_BUTTONS[click.button],
+            clickCount: click.count,
+            ...click.modifiers,
+        };
+    }
+
+    const doubleClick = (widgetAction.action as {
+        DoubleClick?: { button: MouseButton; modifiers: KeyModifiers; x: number; y: number };
+    } | undefined)?.DoubleClick;
+    if (doubleClick) {
+        return {
+            type: "widgetAction",
+            widgetId: widgetAction.widget_id,
+            action: "doubleClick",
+            button: CLICK_BUTTONS[doubleClick.button],
+            clickCount: 2,
+            x: doubleClick.x,
+            y: doubleClick.y,
+            ...doubleClick.modifiers,
+        };
+    }
+
+    const longPress = (widgetAction.action as {
+        LongPress?: { modifiers: KeyModifiers; x: number; y: number };
+    } | undefined)?.LongPress;
+    if (longPress) {
+        return {
+            type: "widgetAction",
+            widgetId: widgetAction.widget_id,
+            action: "longPress",
+            x: longPress.x,
+            y: longPress.y,
+            ...longPress.modifiers,
+        };
+    }
+
+    if (widgetAction.action === "RefreshRequested") {
+        return {
+            type: "widgetAction",
+            widgetId: widgetAction.widget_id,
+            action: "refreshRequested",
+        };
+    }
+
+    if (widgetAction.action === "EnteredViewport") {
+        return {
+            type: "widgetAction",
+            widgetId: widgetAction.widget_id,
+            action: "enteredViewport",
         };
     }
 
@@ -127,6 +608,41 @@ function mapUiEvent(event: unknown): BridgeEvent {
         };
     }
 
+    const rangeChanged = (widgetAction.action as { RangeChanged?: { low: number; high: number } } | undefined)?.RangeChanged;
+    if (rangeChanged !== undefined) {
+        return {
+            type: "widgetAction",
+            widgetId: widgetAction.widget_id,
+            action: "rangeChanged",
+            low: rangeChanged.low,
+            high: rangeChanged.high,
+        };
+    }
+
+    const itemMoved = (widgetAction.action as { ItemMoved?: { from: number; to: number } } | undefined)?.ItemMoved;
+    if (itemMoved !== undefined) {
+        return {
+            type: "widgetAction",
+            widgetId: widgetAction.widget_id,
+            action: "itemMoved",
+            from: itemMoved.from,
+            to: itemMoved.to,
+        };
+    }
+
+    const pointer = (widgetAction.action as {
+        Pointer?: { phase: "Down" | "Move" | "Up"; x: number; y: number };
+    } | undefined)?.Pointer;
+    if (pointer !== undefined) {
+        return {
+            type: "widgetAction",
+            widgetId: widgetAction.widget_id,
+            action: `pointer${pointer.phase}`,
+            x: pointer.x,
+            y: pointer.y,
+        };
+    }
+
     return { type: "unknown" };
 }
 
@@ -137,6 +653,7 @@ export function initVellumBridge(): Bridge {
     }
 
     const listeners = new Set<(event: BridgeEvent) => void>();
+    const beforeExitHandlers = new Set<BeforeExitHandler>();
     let readBuffer = Buffer.alloc(0);
     const messageQueue: JsToRustMessage[] = [];
     let isConnected = false;
@@ -156,28 +673,66 @@ export function initVellumBridge(): Bridge {
                 listeners.delete(callback);
             };
         },
+        onBeforeExit(handler) {
+            beforeExitHandlers.add(handler);
+            return () => {
+                beforeExitHandlers.delete(handler);
+            };
+        },
+    };
+
+    // The UI waits for a second exitApp (up to `timeoutMs`) after beforeExit,
+    // so always confirm once every handler has settled.
+    const runBeforeExit = async (event: BridgeEvent) => {
+        const results = await Promise.allSettled(
+            [...beforeExitHandlers].map(async (handler) => handler(event)),
+        );
+        for (const result of results) {
+            if (result.status === "rejected") {
+                process.stderr.write(`[Vellum bridge] onBeforeExit handler failed: ${String(result.reason)}\n`);
+            }
+        }
+        bridge.send({ type: "exitApp", code: event.exitCode ?? 0 });
     };
 
     globalScope.__Vellum_BRIDGE__ = bridge;
 
-    const binPath = findVellumBinary();
-    const VellumProcess = spawn(binPath, [], {
-        env: { ...process.env, VELLUM_SOCKET: SOCKET_PATH },
-        stdio: "inherit",
-    });
+    if (!IS_HOSTED) {
+        const binPath = findVellumBinary();
+        const VellumProcess = spawn(binPath, [], {
+            env: {
+                ...process.env,
+                VELLUM_SOCKET: SOCKET_PATH,
+                VELLUM_PROTOCOL_VERSION: String(PROTOCOL_VERSION),
+                // Crash reports go under the same data directory as ours.
+                VELLUM_APP_ID: appId(),
+            },
+            stdio: "inherit",
+        });
 
-    VellumProcess.on("error", (err) => {
-        process.stderr.write(`[Vellum bridge] Failed to start Vellum binary: ${String(err)}\n`);
-        process.exit(1);
-    });
+        VellumProcess.on("error", (err) => {
+            process.stderr.write(`[Vellum bridge] Failed to start Vellum binary: ${String(err)}\n`);
+            process.exit(1);
+        });
 
-    VellumProcess.on("exit", (code) => {
-        process.exit(code ?? 0);
-    });
+        VellumProcess.on("exit", (code) => {
+            process.exit(code ?? 0);
+        });
 
-    process.on("exit", () => {
-        VellumProcess.kill();
-    });
+        process.on("exit", () => {
+            VellumProcess.kill();
+        });
+    }
+
+    // Keep the window open with a crash report instead of letting the process
+    // exit (which closes the window) on an uncaught exception.
+    const reportUncaught = (error: unknown) => {
+        const { title, details } = formatError(error);
+        process.stderr.write(`[Vellum bridge] Uncaught ${title}\n${details}\n`);
+        bridge.send({ type: "showErrorOverlay", title, details });
+    };
+    process.on("uncaughtException", reportUncaught);
+    process.on("unhandledRejection", reportUncaught);
 
     const emitEvent = (event: BridgeEvent) => {
         for (const listener of listeners) {
@@ -193,18 +748,28 @@ export function initVellumBridge(): Bridge {
         try {
             const message = decode(frame) as RustToJsMessage;
             if (message?.type === "uiEvent") {
-                emitEvent(mapUiEvent(message.event));
+                // Events with their own timestamp (frame ticks) keep it.
+                const event = { seq: message.seq, timestamp: message.timestamp, ...mapUiEvent(message.event) };
+                emitEvent(event);
+                if (event.type === "beforeExit") {
+                    void runBeforeExit(event);
+                }
                 return;
             }
             if (message?.type === "runtimeError") {
                 emitEvent({
                     type: "runtimeError",
                     source: message.source,
+                    code: message.code,
+                    widgetId: message.widget_id ?? undefined,
+                    command: message.command ?? undefined,
                     message: message.message,
                     fatal: message.fatal,
+                    seq: message.seq ?? undefined,
+                    timestamp: message.timestamp ?? undefined,
                 });
                 process.stderr.write(
-                    `[Vellum bridge] Rust runtime error (${message.source}, fatal=${String(message.fatal)}): ${message.message}\n`,
+                    `[Vellum bridge] Rust runtime error (${message.source}, ${message.code}, fatal=${String(message.fatal)}): ${message.message}\n`,
                 );
                 return;
             }
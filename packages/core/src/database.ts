@@ -0,0 +1,31 @@
+import fs from "node:fs";
+import path from "node:path";
+import { Database } from "bun:sqlite";
+import { appDataDir } from "./paths.ts";
+
+const openDatabases = new Map<string, Database>();
+
+/**
+ * Open (or create) the app's SQLite database `<appDataDir>/<name>.sqlite`.
+ *
+ * Backed by Bun's built-in `bun:sqlite`, so prepared statements
+ * (`db.query(sql).all(...params)`) and transactions (`db.transaction(fn)`) work
+ * without native addons. Repeated calls with the same name share a handle.
+ */
+export function openDatabase(name = "app"): Database {
+    if (name.includes("/") || name.includes("\\") || name.startsWith(".")) {
+        throw new Error(`Vellum.openDatabase: invalid database name "${name}"`);
+    }
+    const existing = openDatabases.get(name);
+    if (existing) return existing;
+
+    const dir = appDataDir();
+    fs.mkdirSync(dir, { recursive: true });
+    const db = new Database(path.join(dir, `${name}.sqlite`), { create: true });
+    // WAL keeps readers from blocking the writer and survives crashes mid-write.
+    db.exec("PRAGMA journal_mode = WAL;");
+    openDatabases.set(name, db);
+    return db;
+}
+
+export type { Database };
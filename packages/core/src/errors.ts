@@ -0,0 +1,66 @@
+import fs from "node:fs";
+import { fileURLToPath } from "node:url";
+
+/** Lines of source shown above and below the failing line. */
+const FRAME_CONTEXT = 2;
+
+type StackLocation = { file: string; line: number; column: number };
+
+/**
+ * First stack frame that points at a readable file. Bun already maps stacks
+ * through its transpiler source maps, so these are the original TS/TSX lines.
+ */
+function firstLocation(stack: string): StackLocation | null {
+    const framePattern = /\(?((?:file:\/\/)?[^\s()]+):(\d+):(\d+)\)?\s*$/;
+    for (const line of stack.split("\n").slice(1)) {
+        const match = framePattern.exec(line.trim());
+        if (!match) continue;
+        const file = match[1].startsWith("file://") ? fileURLToPath(match[1]) : match[1];
+        if (file.includes("node_modules") || !fs.existsSync(file)) continue;
+        return { file, line: Number(match[2]), column: Number(match[3]) };
+    }
+    return null;
+}
+
+function codeFrame({ file, line, column }: StackLocation): string | null {
+    let source: string[];
+    try {
+        source = fs.readFileSync(file, "utf8").split("\n");
+    } catch {
+        return null;
+    }
+    const first = Math.max(1, line - FRAME_CONTEXT);
+    const last = Math.min(source.length, line + FRAME_CONTEXT);
+    const gutter = String(last).length;
+    const out: string[] = [];
+    for (let n = first; n <= last; n++) {
+        const marker = n === line ? ">" : " ";
+        out.push(`${marker} ${String(n).padStart(gutter)} | ${source[n - 1]}`);
+        if (n === line) {
+            out.push(`  ${" ".repeat(gutter)} | ${" ".repeat(Math.max(0, column - 1))}^`);
+        }
+    }
+    return out.join("\n");
+}
+
+/**
+ * Turn an uncaught value into a one-line title plus a report with the
+ * `file:line:column` location, a code frame, and the full stack.
+ */
+export function formatError(error: unknown): { title: string; details: string } {
+    if (!(error instanceof Error)) {
+        return { title: "Uncaught exception", details: String(error) };
+    }
+    const title = `${error.name}: ${error.message}`;
+    const stack = error.stack ?? title;
+    const location = firstLocation(stack);
+    if (!location) {
+        return { title, details: stack };
+    }
+    const frame = codeFrame(location);
+    const header = `${location.file}:${location.line}:${location.column}`;
+    return {
+        title,
+        details: [header, frame, "", stack].filter((part) => part !== null).join("\n"),
+    };
+}
@@ -1,34 +1,111 @@
 import type {
+    AudioDevice,
+    AvatarParams,
+    BadgeParam,
+    BadgeValue,
+    DockMenuItem,
+    ErrorCode,
+    ExitReason,
+    FontFamily,
+    Metrics,
+    PaintOp,
+    RecentCommand,
     VellumEvent,
     VellumStyle,
     BoxStyle,
     CheckboxParams,
+    CustomPaintParams,
+    GpuSurfaceParams,
     ImageParams,
+    PortalParams,
     ProgressBarParams,
+    RangeSliderParams,
+    QrCodeParams,
+    SkeletonParams,
     SliderParams,
     SvgParams,
     TextInputParams,
+    VideoMetrics,
     VideoParams,
 } from "./types.ts";
 import {
     closeWindow,
     createWidget,
     exit,
+    onBeforeExit,
+    setDockMenu,
+    preventSleep,
+    setLayoutDirection,
+    setRawProperty,
+    setPaintOps,
+    setShader,
+    setShaderUniforms,
+    setUiScale,
+    setSplash,
+    listFonts,
+    hitTest,
+    dumpRecentCommands,
+    getMetrics,
+    printWindow,
+    exportPdf,
+    serializeTree,
+    restoreTree,
+    setJournal,
+    undo,
+    redo,
+    undoGroup,
+    observeVisibility,
+    unobserveVisibility,
+    observeResize,
+    unobserveResize,
+    checkForUpdate,
+    downloadUpdate,
+    restartToUpdate,
+    setGestureTiming,
+    onFrame,
+    requestAnimationFrame,
+    cancelAnimationFrame,
+    bindValue,
+    unbindValue,
+    pushChannel,
+    animateValue,
+    stopAnimation,
+    callNativeOp,
     removeWidget,
+    validateTree,
     resizeWindow,
     setImageData,
     setStyleProperty,
     setTitle,
+    setContentProtection,
+    setProgressBuffer,
+    setProgressIndeterminate,
+    registerIconPack,
+    setAvatarStatus,
+    setBadge,
     setWidgetChecked,
+    setWidgetRange,
     setWidgetStyle,
     setWidgetText,
     setWidgetValue,
     setWidgetVisible,
+    setWidgetEnabled,
     playVideo,
     pauseVideo,
     seekVideo,
+    setVideoVolume,
+    setVideoMuted,
+    listAudioDevices,
+    setAudioOutput,
+    endRefresh,
 } from "./ops.ts";
 import { events } from "./events.ts";
+import { storage } from "./storage.ts";
+import { openDatabase } from "./database.ts";
+import { system, argv } from "./paths.ts";
+import { assets } from "./assets.ts";
+import { shell } from "./shell.ts";
+import { i18n } from "./locale.ts";
 
 let widgetIdCounter = 0;
 
@@ -38,16 +115,99 @@ export function nextId(): string {
 
 export const window = {
     setTitle,
+    setSplash,
+    setZoom: setUiScale,
     resize: resizeWindow,
     close: closeWindow,
+    setContentProtection,
+    print: printWindow,
+    exportPdf,
+};
+
+/** macOS dock integration; no-ops elsewhere. */
+export const dock = {
+    setMenu: setDockMenu,
+};
+
+/** System power: `suspend`/`resume`/`lock`/`unlock`/`lowPowerMode` events, plus sleep inhibition. */
+export const power = {
+    preventSleep,
+};
+
+/**
+ * Frame-synced timing for JS animations: callbacks run as the UI starts each
+ * frame instead of on a timer.
+ */
+export const frames = {
+    onFrame,
+    requestAnimationFrame,
+    cancelAnimationFrame,
+};
+
+/**
+ * Audio outputs. Videos play through the chosen one (the system default
+ * until `setOutput`); a `defaultDeviceChanged` event reports a new default.
+ */
+export const audio = {
+    devices: listAudioDevices,
+    setOutput: setAudioOutput,
+};
+
+/**
+ * Structural undo for editor-style apps: once enabled, widget creation and
+ * removal, text and style changes are journaled on the UI thread, and
+ * `undo`/`redo` reverse and re-apply them. A `journalChanged` event reports
+ * `canUndo`/`canRedo` for menu items.
+ */
+export const journal = {
+    setEnabled: setJournal,
+    undo,
+    redo,
+    group: undoGroup,
+};
+
+/** Installed system fonts. */
+export const fonts = {
+    list: listFonts,
+};
+
+/**
+ * Self-update for packaged apps: `check` a feed, `download` what it offers
+ * (also emitting `updateProgress`, `updateReady` and `updateFailed` events),
+ * then `restart` into it, or let the next launch install it.
+ */
+export const updater = {
+    check: checkForUpdate,
+    download: downloadUpdate,
+    restart: restartToUpdate,
+};
+
+/** Ops registered by native plugins. */
+export const native = {
+    call: callNativeOp,
 };
 
 export const body = {
+    setDirection: setLayoutDirection,
     setStyle: (style: VellumStyle): void => setWidgetStyle("__root__", style),
     setStyleProperty: (property: string, value: string | number): void =>
         setStyleProperty("__root__", property, String(value)),
 };
 
+/**
+ * Reserved parent ids of the layers stacked above the body, bottom to top.
+ * Widgets created in a layer draw over everything below it, and clicks that
+ * miss them fall through. `setStyle` on a layer takes `mainAxisAlignment`
+ * (vertical) and `crossAxisAlignment` (horizontal) to place its children.
+ */
+export const layers = {
+    overlay: "__overlay__",
+    toast: "__toast__",
+    debug: "__debug__",
+    setStyle: (layer: "__overlay__" | "__toast__" | "__debug__", style: VellumStyle): void =>
+        setWidgetStyle(layer, style),
+} as const;
+
 export const ui = {
     createWidget: (
         id: string,
@@ -59,22 +219,59 @@ export const ui = {
         data?: Uint8Array | null
     ): void => createWidget(id, kind, parentId ?? null, text ?? null, style ?? null, params ?? null, data ?? null),
     removeWidget,
+    validateTree,
     setText: setWidgetText,
     setVisible: setWidgetVisible,
+    setEnabled: setWidgetEnabled,
     setValue: setWidgetValue,
+    setRange: setWidgetRange,
+    setBadge,
+    setAvatarStatus,
+    registerIconPack,
+    setIndeterminate: setProgressIndeterminate,
+    setBuffer: setProgressBuffer,
     setChecked: setWidgetChecked,
     setStyle: setWidgetStyle,
     setStyleProperty,
+    setRawProperty,
+    setPaintOps,
+    setShader,
+    setShaderUniforms,
+    hitTest,
+    dumpRecentCommands,
+    getMetrics,
+    serializeTree,
+    restoreTree,
+    observeVisibility,
+    unobserveVisibility,
+    observeResize,
+    unobserveResize,
+    setGestureTiming,
+    bindValue,
+    unbindValue,
+    pushChannel,
+    animate: animateValue,
+    stopAnimation,
     setImageData,
     playVideo,
     pauseVideo,
     seekVideo,
+    setVideoVolume,
+    setVideoMuted,
+    endRefresh,
 
     setWidgetText,
     setWidgetVisible,
+    setWidgetEnabled,
 };
 
 export { events };
+export { storage, FileStorage } from "./storage.ts";
+export { system, appId, permissions, argv, appConfigDir, appDataDir, appCacheDir } from "./paths.ts";
+export { assets, assetPath, readAsset } from "./assets.ts";
+export { shell, openExternal, revealInFolder } from "./shell.ts";
+export { i18n, locales, locale, textDirection, regionFormats, type RegionFormats } from "./locale.ts";
+export { openDatabase, type Database } from "./database.ts";
 export type * from "./widgets/types.ts";
 
 export function label(id: string, parentId: string | null, text: string, style?: VellumStyle): string {
@@ -106,8 +303,14 @@ export function button(
     return id;
 }
 
-export function svg(id: string, parentId: string | null, svgData: string, style?: VellumStyle): string {
-    const params: SvgParams = { svgData };
+export function svg(
+    id: string,
+    parentId: string | null,
+    svgData: string,
+    style?: VellumStyle,
+    options?: Omit<SvgParams, "svgData">
+): string {
+    const params: SvgParams = { ...options, svgData };
     ui.createWidget(id, "svg", parentId, null, style ?? null, params);
     return id;
 }
@@ -160,13 +363,21 @@ export function prose(id: string, parentId: string | null, text?: string, style?
     return id;
 }
 
+/**
+ * Create a progress bar. Pass `progress: null` for an indeterminate bar.
+ */
 export function progressBar(
     id: string,
     parentId: string | null,
-    progress?: number,
-    style?: VellumStyle
+    progress?: number | null,
+    style?: VellumStyle,
+    buffer?: number
 ): string {
-    const params: ProgressBarParams = { progress: progress ?? 0 };
+    const params: ProgressBarParams =
+        progress === null ? { indeterminate: true } : { progress: progress ?? 0 };
+    if (buffer !== undefined) {
+        params.buffer = buffer;
+    }
     ui.createWidget(id, "progressBar", parentId, null, style ?? null, params);
     return id;
 }
@@ -176,6 +387,48 @@ export function spinner(id: string, parentId: string | null, style?: VellumStyle
     return id;
 }
 
+/**
+ * Create an icon from a `pack:name` reference (e.g. `"lucide:check"`). Bare names
+ * resolve in the bundled Lucide pack; register others with `ui.registerIconPack`.
+ * The icon follows `style.color`.
+ */
+export function icon(
+    id: string,
+    parentId: string | null,
+    name: string,
+    style?: VellumStyle
+): string {
+    ui.createWidget(id, "icon", parentId, name, style ?? null, null);
+    return id;
+}
+
+/**
+ * Create a QR code (or Code128 barcode) for `data`. Update it with `ui.setText`.
+ */
+export function qrCode(
+    id: string,
+    parentId: string | null,
+    data: string,
+    style?: VellumStyle,
+    params?: QrCodeParams
+): string {
+    ui.createWidget(id, "qrCode", parentId, data, style ?? null, params ?? null);
+    return id;
+}
+
+/**
+ * Create an animated shimmer placeholder, sized by `style.width` / `style.height`.
+ */
+export function skeleton(
+    id: string,
+    parentId: string | null,
+    style?: VellumStyle,
+    params?: SkeletonParams
+): string {
+    ui.createWidget(id, "skeleton", parentId, null, style ?? null, params ?? null);
+    return id;
+}
+
 export function slider(
     id: string,
     parentId: string | null,
@@ -195,36 +448,130 @@ export function slider(
     return id;
 }
 
+export function rangeSlider(
+    id: string,
+    parentId: string | null,
+    min: number,
+    max: number,
+    low: number,
+    high: number,
+    style?: VellumStyle,
+    step?: number,
+): string {
+    const params: RangeSliderParams = {
+        minValue: min,
+        maxValue: max,
+        low,
+        high,
+        ...(step !== undefined ? { step } : {}),
+    };
+    ui.createWidget(id, "rangeSlider", parentId, null, style ?? null, params);
+    return id;
+}
+
+/**
+ * Create a canvas drawn from a display list (see `PaintOp`); replace it with
+ * `ui.setPaintOps`. Pointer input arrives as `pointerDown` / `pointerMove` /
+ * `pointerUp` widget actions with local `x`/`y`.
+ */
+export function customPaint(
+    id: string,
+    parentId: string | null,
+    ops: PaintOp[],
+    style?: VellumStyle
+): string {
+    const params: CustomPaintParams = { ops };
+    ui.createWidget(id, "customPaint", parentId, null, style ?? null, params);
+    return id;
+}
+
+/**
+ * Create a wgpu-backed surface drawn by a WGSL fragment shader (see
+ * `GpuSurfaceParams`) or by a renderer a Rust plugin registered. Update it
+ * with `ui.setShader` / `ui.setShaderUniforms`; pointer input arrives like
+ * `customPaint`'s, in surface pixels.
+ */
+export function gpuSurface(
+    id: string,
+    parentId: string | null,
+    params: GpuSurfaceParams,
+    style?: VellumStyle
+): string {
+    ui.createWidget(id, "gpuSurface", parentId, null, style ?? null, params);
+    return id;
+}
+
 export function zstack(id: string, parentId: string | null, style?: VellumStyle): string {
     ui.createWidget(id, "zstack", parentId, null, style ?? null);
     return id;
 }
 
-export function portal(id: string, parentId: string | null, style?: VellumStyle): string {
-    ui.createWidget(id, "portal", parentId, null, style ?? null);
+/**
+ * A column whose children can be dragged into a new order. Drops emit
+ * `itemMoved` with `from`/`to` indices; the row has already moved, so only
+ * the app's model needs updating. `gap` spaces rows and `fillColor` colors
+ * the drop indicator.
+ */
+export function reorderableList(id: string, parentId: string | null, style?: VellumStyle): string {
+    ui.createWidget(id, "reorderableList", parentId, null, style ?? null);
     return id;
 }
 
-export { exit };
-export type { VellumStyle, VellumEvent, BoxStyle };
+export function portal(
+    id: string,
+    parentId: string | null,
+    style?: VellumStyle,
+    params?: PortalParams
+): string {
+    ui.createWidget(id, "portal", parentId, null, style ?? null, params);
+    return id;
+}
 
+export { exit, onBeforeExit };
+export type {
+    VellumStyle,
+    VellumEvent,
+    ErrorCode,
+    ExitReason,
+    BoxStyle,
+    BadgeParam,
+    BadgeValue,
+    DockMenuItem,
+    FontFamily,
+    PaintOp,
+    RecentCommand,
+    Metrics,
+    VideoMetrics,
+    AudioDevice,
+};
+export type { AnimationOptions, UpdateInfo } from "./ops.ts";
+
+/**
+ * Create an image from encoded bytes, or from an `asset://` URL that the
+ * runtime loads itself (e.g. `image("logo", null, "asset://images/logo.png")`).
+ */
 export function image(
     id: string,
     parentId: string | null,
-    data: Uint8Array,
-    style?: VellumStyle & { objectFit?: string }
+    source: Uint8Array | string,
+    style?: VellumStyle & { objectFit?: string },
+    alt?: string
 ): string {
     const objectFit = style?.objectFit;
     const { objectFit: _of, ...restStyle } = style ?? {};
-    const paramsJson: ImageParams | null = objectFit ? { object_fit: objectFit } : null;
+    const params: ImageParams = {
+        ...(objectFit ? { object_fit: objectFit } : {}),
+        ...(typeof source === "string" ? { src: source } : {}),
+        ...(alt ? { alt } : {}),
+    };
     ui.createWidget(
         id,
         "image",
         parentId,
         null,
         Object.keys(restStyle).length > 0 ? restStyle : null,
-        paramsJson,
-        data
+        Object.keys(params).length > 0 ? params : null,
+        typeof source === "string" ? null : source
     );
     return id;
 }
@@ -247,17 +594,50 @@ export function video(
     return id;
 }
 
+/**
+ * Create a circular avatar. `name` provides the initials shown when no image
+ * is set; pass image bytes via `data` (or later via `ui.setImageData`).
+ */
+export function avatar(
+    id: string,
+    parentId: string | null,
+    name: string,
+    style?: VellumStyle,
+    params?: AvatarParams,
+    data?: Uint8Array
+): string {
+    ui.createWidget(id, "avatar", parentId, name, style ?? null, params ?? null, data ?? null);
+    return id;
+}
+
 export const app = {
     window,
+    dock,
+    power,
+    audio,
+    frames,
+    journal,
+    fonts,
+    native,
+    updater,
     body,
+    layers,
     ui,
     events,
+    storage,
+    openDatabase,
+    system,
+    argv,
+    assets,
+    shell,
+    i18n,
     nextId,
     label,
     button,
     svg,
     image,
     video,
+    avatar,
     flex,
     row,
     column,
@@ -267,10 +647,18 @@ export const app = {
     prose,
     progressBar,
     spinner,
+    skeleton,
+    qrCode,
+    customPaint,
+    gpuSurface,
+    icon,
     slider,
+    rangeSlider,
     zstack,
     portal,
+    reorderableList,
     exit,
+    onBeforeExit,
 };
 
 export default app;
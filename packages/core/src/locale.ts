@@ -0,0 +1,79 @@
+import process from "node:process";
+
+/** Regional formatting conventions for a locale. */
+export interface RegionFormats {
+    locale: string;
+    decimalSeparator: string;
+    groupSeparator: string;
+    /** `"h12"`/`"h23"` etc., as reported by `Intl.DateTimeFormat`. */
+    hourCycle: string;
+    /** 1 = Monday … 7 = Sunday. */
+    firstDayOfWeek: number;
+    /** Order of the numeric date fields, e.g. `["month", "day", "year"]`. */
+    dateOrder: Array<"day" | "month" | "year">;
+}
+
+/**
+ * The user's preferred locales (BCP 47), most preferred first. The runtime
+ * reads them from the OS and hands them over as `VELLUM_LOCALES`.
+ */
+export function locales(): string[] {
+    const fromEnv = (process.env.VELLUM_LOCALES ?? "").split(",").filter(Boolean);
+    if (fromEnv.length > 0) return fromEnv;
+    return [Intl.DateTimeFormat().resolvedOptions().locale];
+}
+
+/** The most preferred locale. */
+export function locale(): string {
+    return locales()[0] ?? "en-US";
+}
+
+/** `"rtl"` for right-to-left scripts (Arabic, Hebrew, …). */
+export function textDirection(tag: string = locale()): "ltr" | "rtl" {
+    const intlLocale = new Intl.Locale(tag) as Intl.Locale & {
+        textInfo?: { direction?: string };
+        getTextInfo?: () => { direction?: string };
+    };
+    const info = intlLocale.getTextInfo?.() ?? intlLocale.textInfo;
+    if (info?.direction) return info.direction === "rtl" ? "rtl" : "ltr";
+    const script = intlLocale.maximize().script ?? "";
+    return ["Arab", "Hebr", "Nkoo", "Rohg", "Syrc", "Thaa"].includes(script) ? "rtl" : "ltr";
+}
+
+function firstDayOfWeek(intlLocale: Intl.Locale): number {
+    const withWeek = intlLocale as Intl.Locale & {
+        weekInfo?: { firstDay?: number };
+        getWeekInfo?: () => { firstDay?: number };
+    };
+    const info = withWeek.getWeekInfo?.() ?? withWeek.weekInfo;
+    return info?.firstDay ?? 1;
+}
+
+/** Number, time and date conventions for `tag` (defaults to the preferred locale). */
+export function regionFormats(tag: string = locale()): RegionFormats {
+    const intlLocale = new Intl.Locale(tag);
+    const numberParts = new Intl.NumberFormat(tag).formatToParts(12345.6);
+    const part = (type: string) => numberParts.find((p) => p.type === type)?.value ?? "";
+    const dateOrder = new Intl.DateTimeFormat(tag)
+        .formatToParts(new Date(2000, 10, 22))
+        .map((p) => p.type)
+        .filter((type): type is "day" | "month" | "year" =>
+            type === "day" || type === "month" || type === "year"
+        );
+    return {
+        locale: intlLocale.toString(),
+        decimalSeparator: part("decimal") || ".",
+        groupSeparator: part("group"),
+        hourCycle: new Intl.DateTimeFormat(tag, { hour: "numeric" }).resolvedOptions().hourCycle ?? "h23",
+        firstDayOfWeek: firstDayOfWeek(intlLocale),
+        dateOrder,
+    };
+}
+
+/** Locale preferences and regional formats. */
+export const i18n = {
+    locales,
+    locale,
+    textDirection,
+    regionFormats,
+};
@@ -1,4 +1,13 @@
-import type { VellumStyle } from "./types.ts";
+import type {
+    AudioDevice,
+    BadgeValue,
+    DockMenuItem,
+    FontFamily,
+    Metrics,
+    PaintOp,
+    RecentCommand,
+    VellumStyle,
+} from "./types.ts";
 import { ensureBridge, type BridgeEvent, type Bridge, type JsToRustMessage } from "./bun_bridge.ts";
 
 const bridge: Bridge = ensureBridge();
@@ -13,6 +22,29 @@ export function setTitle(title: string): void {
     bridge.send({ type: "setTitle", title });
 }
 
+/**
+ * Keep the window's contents out of screenshots, screen recordings and
+ * screen sharing, e.g. while showing a password or medical record. macOS and
+ * Windows only; elsewhere enabling it reports a runtime error.
+ */
+export function setContentProtection(enabled: boolean): void {
+    bridge.send({ type: "setContentProtection", enabled });
+}
+
+/**
+ * Show or update the loading splash (an image, a message, and a progress bar
+ * from 0 to 1 or a spinner) until the first widget is mounted on the body.
+ * Omitted parts stay as they are, so progress updates don't resend the image.
+ */
+export function setSplash(splash: { image?: Uint8Array; message?: string; progress?: number }): void {
+    bridge.send({
+        type: "setSplash",
+        image: splash.image ?? null,
+        message: splash.message ?? null,
+        progress: splash.progress ?? null,
+    });
+}
+
 export function resizeWindow(width: number, height: number): void {
     bridge.send({ type: "resizeWindow", width, height });
 }
@@ -21,6 +53,11 @@ export function closeWindow(): void {
     bridge.send({ type: "closeWindow" });
 }
 
+/** Style JSON replacer: bytes (`backgroundImage`) go as a plain number array. */
+function encodeBytes(_key: string, value: unknown): unknown {
+    return value instanceof Uint8Array ? Array.from(value) : value;
+}
+
 export function createWidget(
     id: string,
     kind: string,
@@ -36,7 +73,7 @@ export function createWidget(
         kind,
         parent_id: parentId ?? null,
         text: text ?? null,
-        style_json: style ? JSON.stringify(style) : null,
+        style_json: style ? JSON.stringify(style, encodeBytes) : null,
         widget_params_json: params ? JSON.stringify(params) : null,
         data: data ?? null,
     });
@@ -46,6 +83,18 @@ export function removeWidget(id: string): void {
     bridge.send({ type: "removeWidget", id });
 }
 
+/**
+ * Ask the UI thread to cross-check its widget bookkeeping. Problems are reported
+ * as a `runtimeError` event with code `"TreeInconsistent"`; nothing is sent when the
+ * tree is consistent. With `repair`, child lists and indices are rebuilt from each
+ * widget's recorded index rather than read back from the widget tree.
+ *
+ * Set `VELLUM_AUDIT_TREE=1` to run the same check after every create and remove.
+ */
+export function validateTree(repair = false): void {
+    bridge.send({ type: "validateTree", repair });
+}
+
 export function setWidgetText(id: string, text: string): void {
     bridge.send({ type: "setWidgetText", id, text });
 }
@@ -54,16 +103,48 @@ export function setWidgetVisible(id: string, visible: boolean): void {
     bridge.send({ type: "setWidgetVisible", id, visible });
 }
 
+/**
+ * Enable or disable a widget and everything inside it. Disabled widgets
+ * ignore input and paint with their `disabledColor` / `disabledBackground`.
+ */
+export function setWidgetEnabled(id: string, enabled: boolean): void {
+    bridge.send({ type: "setWidgetEnabled", id, enabled });
+}
+
 export function setWidgetValue(id: string, value: number): void {
     bridge.send({ type: "setWidgetValue", id, value });
 }
 
+export function setProgressIndeterminate(id: string, indeterminate: boolean): void {
+    bridge.send({ type: "setProgressIndeterminate", id, indeterminate });
+}
+
+export function setProgressBuffer(id: string, buffer: number | null): void {
+    bridge.send({ type: "setProgressBuffer", id, buffer });
+}
+
+export function setWidgetRange(id: string, low: number, high: number): void {
+    bridge.send({ type: "setWidgetRange", id, low, high });
+}
+
+export function setBadge(id: string, value: BadgeValue): void {
+    bridge.send({ type: "setBadge", id, badge_json: value == null ? null : JSON.stringify(value) });
+}
+
+export function setAvatarStatus(id: string, status: string | null): void {
+    bridge.send({ type: "setAvatarStatus", id, status });
+}
+
+export function registerIconPack(prefix: string, icons: Record<string, string>): void {
+    bridge.send({ type: "registerIconPack", prefix, icons });
+}
+
 export function setWidgetChecked(id: string, checked: boolean): void {
     bridge.send({ type: "setWidgetChecked", id, checked });
 }
 
 export function setWidgetStyle(id: string, style: VellumStyle): void {
-    bridge.send({ type: "setWidgetStyle", id, style_json: JSON.stringify(style) });
+    bridge.send({ type: "setWidgetStyle", id, style_json: JSON.stringify(style, encodeBytes) });
 }
 
 export function setStyleProperty(
@@ -74,8 +155,419 @@ export function setStyleProperty(
     bridge.send({ type: "setStyleProperty", id, property, value: String(value) });
 }
 
-export function exit(): void {
-    bridge.send({ type: "exitApp" });
+/**
+ * Close the app; the process exits with `code`. `onBeforeExit` handlers run
+ * first.
+ */
+export function exit(code = 0): void {
+    bridge.send({ type: "exitApp", code: Math.trunc(code) });
+}
+
+/**
+ * Run `handler` before the app exits, whether from `exit()` or the user
+ * quitting. Returned promises are awaited, up to the event's `timeoutMs`
+ * (3 seconds), so state can be flushed. Returns an unsubscribe function.
+ */
+export function onBeforeExit(handler: (event: BridgeEvent) => void | Promise<void>): () => void {
+    return bridge.onBeforeExit(handler);
+}
+
+let sleepTokenCounter = 0;
+
+/**
+ * Keep the system awake (e.g. during playback or an export) until the
+ * returned function is called.
+ */
+export function preventSleep(reason = "Vellum app is busy"): () => void {
+    const token = `sleep_${++sleepTokenCounter}`;
+    bridge.send({ type: "inhibitSleep", token, reason });
+    let released = false;
+    return () => {
+        if (released) return;
+        released = true;
+        bridge.send({ type: "releaseSleep", token });
+    };
+}
+
+/**
+ * Lay the UI out right-to-left (rows run right to left, start/end alignment,
+ * left/right padding and text alignment are mirrored) or left-to-right. The
+ * default follows the OS locale. Applies to widgets created afterwards, so call
+ * it before mounting.
+ */
+export function setLayoutDirection(direction: "ltr" | "rtl"): void {
+    bridge.send({ type: "setLayoutDirection", direction });
+}
+
+/** Replace the display list of a `customPaint` widget. */
+export function setPaintOps(id: string, ops: PaintOp[]): void {
+    bridge.send({ type: "setPaintOps", id, ops });
+}
+
+/** Replace the WGSL fragment shader of a `gpuSurface` widget. */
+export function setShader(id: string, wgsl: string): void {
+    bridge.send({ type: "setShader", id, wgsl });
+}
+
+/** Set the values a `gpuSurface` shader reads with `uniform(i)` (at most 64). */
+export function setShaderUniforms(id: string, values: number[]): void {
+    bridge.send({ type: "setShaderUniforms", id, values });
+}
+
+/**
+ * Escape hatch for masonry properties without a style key yet, e.g.
+ * `setRawProperty("save", "ActiveBackground", "#1d4ed8")`. Supported names:
+ * ActiveBackground, DisabledBackground, DisabledContentColor,
+ * FocusedBorderColor, HoveredBorderColor.
+ */
+export function setRawProperty(id: string, name: string, value: unknown): void {
+    bridge.send({ type: "setRawProperty", id, name, json: JSON.stringify(value) });
+}
+
+/**
+ * Interface zoom (Ctrl+/- style): every style length and font size is
+ * multiplied by `factor` (0.25–5), including on existing widgets.
+ */
+export function setUiScale(factor: number): void {
+    bridge.send({ type: "setUiScale", factor });
+}
+
+let fontRequestCounter = 0;
+
+/** Installed font families and their faces, sorted by name (for font pickers). */
+export function listFonts(): Promise<FontFamily[]> {
+    const requestId = `fonts_${++fontRequestCounter}`;
+    return new Promise((resolve) => {
+        const unsubscribe = bridge.onEvent((event) => {
+            if (event.type === "fontList" && event.requestId === requestId) {
+                unsubscribe();
+                resolve(event.families ?? []);
+            }
+        });
+        bridge.send({ type: "listFonts", request_id: requestId });
+    });
+}
+
+/**
+ * Change how `doubleClick` and `longPress` are recognized, in milliseconds:
+ * the longest gap between the two clicks and the hold before a long press.
+ * Omitted values stay as they are; 0 turns the gesture off.
+ */
+export function setGestureTiming(timing: { doubleClick?: number; longPress?: number }): void {
+    const ms = (value: number | undefined) =>
+        value === undefined ? null : Math.max(0, Math.round(value));
+    bridge.send({
+        type: "setGestureTiming",
+        double_click_ms: ms(timing.doubleClick),
+        long_press_ms: ms(timing.longPress),
+    });
+}
+
+let hitTestCounter = 0;
+
+/**
+ * The widget under a window point (logical pixels) and the point in that
+ * widget's local coordinates, or null over empty space.
+ */
+export function hitTest(x: number, y: number): Promise<{ widgetId: string; x: number; y: number } | null> {
+    const requestId = `hit_${++hitTestCounter}`;
+    return new Promise((resolve) => {
+        const unsubscribe = bridge.onEvent((event) => {
+            if (event.type === "hitTestResult" && event.requestId === requestId) {
+                unsubscribe();
+                resolve(
+                    event.widgetId === undefined
+                        ? null
+                        : { widgetId: event.widgetId, x: event.x ?? x, y: event.y ?? y }
+                );
+            }
+        });
+        bridge.send({ type: "hitTest", request_id: requestId, x, y });
+    });
+}
+
+let recentCommandsCounter = 0;
+
+/**
+ * The last commands the UI thread handled, oldest first (the same log a crash
+ * report ends with). Attach it to bug reports about a corrupted widget tree.
+ */
+export function dumpRecentCommands(): Promise<RecentCommand[]> {
+    const requestId = `recent_${++recentCommandsCounter}`;
+    return new Promise((resolve) => {
+        const unsubscribe = bridge.onEvent((event) => {
+            if (event.type === "recentCommands" && event.requestId === requestId) {
+                unsubscribe();
+                resolve(event.commands ?? []);
+            }
+        });
+        bridge.send({ type: "dumpRecentCommands", request_id: requestId });
+    });
+}
+
+let metricsCounter = 0;
+
+/**
+ * Runtime counters: the widget count, and the decoder each video is playing
+ * with (check `hardware` when 4K playback runs hot).
+ */
+export function getMetrics(): Promise<Metrics> {
+    const requestId = `metrics_${++metricsCounter}`;
+    return new Promise((resolve) => {
+        const unsubscribe = bridge.onEvent((event) => {
+            if (event.type === "metrics" && event.requestId === requestId) {
+                unsubscribe();
+                resolve(event.metrics ?? { widgetCount: 0, videos: [] });
+            }
+        });
+        bridge.send({ type: "getMetrics", request_id: requestId });
+    });
+}
+
+let printCounter = 0;
+
+function awaitPrint(requestId: string, message: JsToRustMessage): Promise<number> {
+    return new Promise((resolve, reject) => {
+        const unsubscribe = bridge.onEvent((event) => {
+            if (event.type === "printResult" && event.requestId === requestId) {
+                unsubscribe();
+                if (event.error !== undefined) {
+                    reject(new Error(event.error));
+                } else {
+                    resolve(event.pages ?? 0);
+                }
+            }
+        });
+        bridge.send(message);
+    });
+}
+
+/**
+ * Print the window: it is rendered to a PDF and the platform's print dialog
+ * opens on it (Preview on macOS, Okular or the GNOME previewer on Linux, the
+ * default PDF viewer on Windows). Resolves with the page count once the dialog
+ * is closed.
+ */
+export function printWindow(): Promise<number> {
+    const requestId = `print_${++printCounter}`;
+    return awaitPrint(requestId, { type: "printWindow", request_id: requestId });
+}
+
+/**
+ * Render the window, or the widget `widgetId`, to a PDF at `path`. A portal
+ * is paged through its whole content, one viewport per page. Resolves with
+ * the page count.
+ */
+export function exportPdf(path: string, widgetId?: string): Promise<number> {
+    const requestId = `print_${++printCounter}`;
+    return awaitPrint(requestId, {
+        type: "exportPdf",
+        request_id: requestId,
+        path,
+        widget_id: widgetId ?? null,
+    });
+}
+
+let treeCounter = 0;
+
+/**
+ * Capture the widget tree as it was declared (kinds, text, styles, params)
+ * with later text and style updates folded in. Scroll positions, bindings
+ * and animations are not included.
+ */
+export function serializeTree(): Promise<Uint8Array> {
+    const requestId = `tree_${++treeCounter}`;
+    return new Promise((resolve) => {
+        const unsubscribe = bridge.onEvent((event) => {
+            if (event.type === "treeSnapshot" && event.requestId === requestId) {
+                unsubscribe();
+                resolve(event.snapshot ?? new Uint8Array());
+            }
+        });
+        bridge.send({ type: "serializeTree", request_id: requestId });
+    });
+}
+
+/**
+ * Replace the widget tree with one from `serializeTree`, keeping its widget
+ * ids. An invalid snapshot is reported as a `ParseError` runtime error and
+ * leaves the tree alone.
+ */
+export function restoreTree(snapshot: Uint8Array): void {
+    bridge.send({ type: "restoreTree", snapshot });
+}
+
+/**
+ * Start (or stop) journaling tree mutations: widgets created and removed,
+ * `setText` and `setStyle`. Each can then be reversed with `undo()` on the UI
+ * thread. Keeps up to `limit` steps (100 by default); stopping drops them.
+ */
+export function setJournal(enabled: boolean, limit?: number): void {
+    bridge.send({ type: "setJournal", enabled, limit: limit ?? null });
+}
+
+export function undo(): void {
+    bridge.send({ type: "undo" });
+}
+
+export function redo(): void {
+    bridge.send({ type: "redo" });
+}
+
+/** Run `fn` and journal the mutations it makes as one undo step. */
+export function undoGroup<T>(fn: () => T): T {
+    bridge.send({ type: "beginUndoGroup" });
+    try {
+        return fn();
+    } finally {
+        bridge.send({ type: "endUndoGroup" });
+    }
+}
+
+/**
+ * Feed a widget property from a named channel: `value`, `text`, `visible`,
+ * `checked`, or a style key such as `width` or `background`. Every
+ * `pushChannel` on the channel then updates all widgets bound to it in one
+ * small message, for values that change every frame.
+ */
+export function bindValue(id: string, property: string, channel: string): void {
+    bridge.send({ type: "bindValue", id, property, channel });
+}
+
+export function unbindValue(id: string, property: string): void {
+    bridge.send({ type: "unbindValue", id, property });
+}
+
+export function pushChannel(channel: string, value: number | string | boolean): void {
+    bridge.send({ type: "pushChannel", channel, value });
+}
+
+export type AnimationOptions = {
+    /** Milliseconds per run. */
+    duration: number;
+    easing?: "linear" | "easeIn" | "easeOut" | "easeInOut";
+    /** Extra runs after the first; `Infinity` loops until stopped. */
+    repeat?: number;
+};
+
+/** Rust reads `u32::MAX` as "loop forever". */
+const REPEAT_FOREVER = 0xffffffff;
+
+/**
+ * Tween a numeric property (`value`, or a style key such as `width`) from
+ * `from` to `to` on the UI thread. Replaces any animation already running on
+ * that property.
+ */
+export function animateValue(
+    id: string,
+    property: string,
+    from: number,
+    to: number,
+    { duration, easing = "linear", repeat = 0 }: AnimationOptions,
+): void {
+    bridge.send({
+        type: "animateValue",
+        id,
+        property,
+        from,
+        to,
+        duration_ms: duration,
+        easing,
+        repeat: Number.isFinite(repeat) ? Math.max(0, Math.floor(repeat)) : REPEAT_FOREVER,
+    });
+}
+
+/** Stop a property's animation where it is (all of the widget's when omitted). */
+export function stopAnimation(id: string, property?: string): void {
+    bridge.send({ type: "stopAnimation", id, property: property ?? null });
+}
+
+type FrameCallback = (timestamp: number) => void;
+
+const frameListeners = new Set<FrameCallback>();
+let unsubscribeFrames: (() => void) | null = null;
+
+function updateFrameTicks(): void {
+    if (frameListeners.size > 0 && !unsubscribeFrames) {
+        unsubscribeFrames = bridge.onEvent((event) => {
+            if (event.type !== "frameTick") return;
+            const timestamp = event.timestamp ?? 0;
+            for (const callback of [...frameListeners]) {
+                try {
+                    callback(timestamp);
+                } catch (err) {
+                    console.error("[Vellum] Error in frame callback:", err);
+                }
+            }
+        });
+        bridge.send({ type: "setFrameTicks", enabled: true });
+    } else if (frameListeners.size === 0 && unsubscribeFrames) {
+        unsubscribeFrames();
+        unsubscribeFrames = null;
+        bridge.send({ type: "setFrameTicks", enabled: false });
+    }
+}
+
+/**
+ * Call `callback` at the start of every frame the UI renders, with a
+ * millisecond timestamp, until the returned function is called. Ticks only
+ * flow while something is subscribed.
+ */
+export function onFrame(callback: FrameCallback): () => void {
+    const listener: FrameCallback = (timestamp) => callback(timestamp);
+    frameListeners.add(listener);
+    updateFrameTicks();
+    return () => {
+        frameListeners.delete(listener);
+        updateFrameTicks();
+    };
+}
+
+let frameRequestCounter = 0;
+const frameRequests = new Map<number, () => void>();
+
+/** Like the browser's: call `callback` once, on the next frame. */
+export function requestAnimationFrame(callback: FrameCallback): number {
+    const handle = ++frameRequestCounter;
+    const unsubscribe = onFrame((timestamp) => {
+        cancelAnimationFrame(handle);
+        callback(timestamp);
+    });
+    frameRequests.set(handle, unsubscribe);
+    return handle;
+}
+
+export function cancelAnimationFrame(handle: number): void {
+    frameRequests.get(handle)?.();
+    frameRequests.delete(handle);
+}
+
+let nativeOpCounter = 0;
+
+/**
+ * Call an op registered by a native plugin (see `plugins` in vellum.toml).
+ * `args` and the result travel as JSON; a plugin error rejects the promise.
+ */
+export function callNativeOp(name: string, args: unknown = null): Promise<unknown> {
+    const requestId = `native_${++nativeOpCounter}`;
+    return new Promise((resolve, reject) => {
+        const unsubscribe = bridge.onEvent((event) => {
+            if (event.type === "nativeOpResult" && event.requestId === requestId) {
+                unsubscribe();
+                if (event.error !== undefined) {
+                    reject(new Error(event.error));
+                } else {
+                    resolve(JSON.parse(event.result ?? "null"));
+                }
+            }
+        });
+        bridge.send({ type: "callNativeOp", request_id: requestId, name, args_json: JSON.stringify(args) });
+    });
+}
+
+/** macOS only; choosing an item emits a `dockMenu` event with its `itemId`. */
+export function setDockMenu(items: DockMenuItem[]): void {
+    bridge.send({ type: "setDockMenu", items });
 }
 
 export function setImageData(id: string, data: Uint8Array): void {
@@ -93,3 +585,123 @@ export function pauseVideo(id: string): void {
 export function seekVideo(id: string, timeSecs: number): void {
     bridge.send({ type: "seekVideo", id, time_secs: timeSecs });
 }
+
+/** `volume` runs from 0 (silent) to 1 (full). */
+export function setVideoVolume(id: string, volume: number): void {
+    bridge.send({ type: "setVideoVolume", id, volume });
+}
+
+export function setVideoMuted(id: string, muted: boolean): void {
+    bridge.send({ type: "setVideoMuted", id, muted });
+}
+
+let audioDevicesCounter = 0;
+
+/** Audio outputs, the system default flagged with `isDefault`. */
+export function listAudioDevices(): Promise<AudioDevice[]> {
+    const requestId = `audio_${++audioDevicesCounter}`;
+    return new Promise((resolve) => {
+        const unsubscribe = bridge.onEvent((event) => {
+            if (event.type === "audioDevices" && event.requestId === requestId) {
+                unsubscribe();
+                resolve(event.devices ?? []);
+            }
+        });
+        bridge.send({ type: "listAudioDevices", request_id: requestId });
+    });
+}
+
+/** Play audio through the output with `deviceId`, or the system default for null. */
+export function setAudioOutput(deviceId: string | null): void {
+    bridge.send({ type: "setAudioOutput", device: deviceId });
+}
+
+/**
+ * Fire a `visibilityChanged` widget action, with the fraction of the widget
+ * that shows as `ratio`, each time it crosses `threshold`: 0 (the default)
+ * for any part of it, 1 for all of it. Clipping by the window and enclosing
+ * Portals counts; the first report comes right after observing.
+ */
+export function observeVisibility(id: string, threshold = 0): void {
+    bridge.send({ type: "observeVisibility", id, threshold });
+}
+
+export function unobserveVisibility(id: string): void {
+    bridge.send({ type: "unobserveVisibility", id });
+}
+
+/**
+ * Fire a `resized` widget action with the widget's `width` and `height` each
+ * time a layout changes its size, starting with its current size. Lets a
+ * container adapt to its own space rather than the window's.
+ */
+export function observeResize(id: string): void {
+    bridge.send({ type: "observeResize", id });
+}
+
+export function unobserveResize(id: string): void {
+    bridge.send({ type: "unobserveResize", id });
+}
+
+export type UpdateInfo = {
+    version: string;
+    notes?: string;
+};
+
+let updateCheckCounter = 0;
+
+/**
+ * Fetch the update feed at `url` (see "Auto-Update" in the README) and resolve
+ * with the version it offers if that is newer than `currentVersion`, else
+ * null. Only packaged apps can go on to download it.
+ */
+export function checkForUpdate(url: string, currentVersion: string): Promise<UpdateInfo | null> {
+    const requestId = `update_${++updateCheckCounter}`;
+    return new Promise((resolve, reject) => {
+        const unsubscribe = bridge.onEvent((event) => {
+            if (event.type === "updateCheckResult" && event.requestId === requestId) {
+                unsubscribe();
+                if (event.error !== undefined) {
+                    reject(new Error(event.error));
+                } else if (event.version !== undefined) {
+                    resolve({ version: event.version, notes: event.notes });
+                } else {
+                    resolve(null);
+                }
+            }
+        });
+        bridge.send({ type: "checkForUpdate", request_id: requestId, url, current_version: currentVersion });
+    });
+}
+
+/**
+ * Download and verify the version the last check found and stage it for the
+ * next launch. `onProgress` gets the bytes downloaded and, when the feed
+ * lists sizes, the total. Resolves with the staged version.
+ */
+export function downloadUpdate(onProgress?: (downloaded: number, total?: number) => void): Promise<string> {
+    return new Promise((resolve, reject) => {
+        const unsubscribe = bridge.onEvent((event) => {
+            if (event.type === "updateProgress") {
+                onProgress?.(event.downloaded ?? 0, event.total);
+            } else if (event.type === "updateReady") {
+                unsubscribe();
+                resolve(event.version ?? "");
+            } else if (event.type === "updateFailed") {
+                unsubscribe();
+                reject(new Error(event.error ?? "update failed"));
+            }
+        });
+        bridge.send({ type: "downloadUpdate" });
+    });
+}
+
+/** Exit, running `onBeforeExit` handlers, and relaunch into the staged update. */
+export function restartToUpdate(): void {
+    bridge.send({ type: "restartToUpdate" });
+}
+
+/** Hide a pull-to-refresh Portal's spinner once the reload is done. */
+export function endRefresh(id: string): void {
+    bridge.send({ type: "endRefresh", id });
+}
@@ -0,0 +1,99 @@
+import process from "node:process";
+import os from "node:os";
+import path from "node:path";
+
+/**
+ * Identifier used to namespace per-app data on disk. Set `VELLUM_APP_ID` (or
+ * `id` in `vellum.toml`) to pin it; otherwise the entry script's directory name is used.
+ * When the binary starts Bun it passes the id it resolved the same way.
+ */
+export function appId(): string {
+    const fromEnv = process.env.VELLUM_APP_ID?.trim();
+    if (fromEnv) return fromEnv;
+    const entry = process.argv[1];
+    return entry ? path.basename(path.dirname(path.resolve(entry))) : "vellum-app";
+}
+
+function platformDir(kind: "config" | "data" | "cache"): string {
+    const home = os.homedir();
+    if (process.platform === "win32") {
+        const roaming = process.env.APPDATA ?? path.join(home, "AppData", "Roaming");
+        const local = process.env.LOCALAPPDATA ?? path.join(home, "AppData", "Local");
+        return kind === "cache" ? local : roaming;
+    }
+    if (process.platform === "darwin") {
+        return kind === "cache"
+            ? path.join(home, "Library", "Caches")
+            : path.join(home, "Library", "Application Support");
+    }
+    switch (kind) {
+        case "config":
+            return process.env.XDG_CONFIG_HOME ?? path.join(home, ".config");
+        case "data":
+            return process.env.XDG_DATA_HOME ?? path.join(home, ".local", "share");
+        case "cache":
+            return process.env.XDG_CACHE_HOME ?? path.join(home, ".cache");
+    }
+}
+
+/** Settings and small state (e.g. `~/.config/<appId>` on Linux). */
+export function appConfigDir(): string {
+    return path.join(platformDir("config"), appId());
+}
+
+/** User documents and databases (e.g. `~/.local/share/<appId>` on Linux). */
+export function appDataDir(): string {
+    return path.join(platformDir("data"), appId());
+}
+
+/** Disposable files the app can regenerate (e.g. `~/.cache/<appId>` on Linux). */
+export function appCacheDir(): string {
+    return path.join(platformDir("cache"), appId());
+}
+
+/** Permissions declared in the app's `vellum.toml`; empty without a manifest. */
+export function permissions(): string[] {
+    return (process.env.VELLUM_PERMISSIONS ?? "").split(",").filter(Boolean);
+}
+
+/** Platform facts and standard directories, so apps don't hardcode paths. */
+/**
+ * The app's own arguments: what follows the entry on the command line, with a
+ * leading `--` dropped (`vellum app.ts -- --verbose` gives `["--verbose"]`).
+ * A packaged app gets all of its arguments. When the binary started Bun they
+ * come from `VELLUM_ARGV`, untouched by Bun's own option parsing.
+ */
+export const argv: readonly string[] = (() => {
+    const forwarded = process.env.VELLUM_ARGV;
+    if (forwarded) {
+        try {
+            const parsed: unknown = JSON.parse(forwarded);
+            if (Array.isArray(parsed)) return Object.freeze(parsed.map(String));
+        } catch {
+            // Fall back to the process arguments.
+        }
+    }
+    const args = process.argv.slice(2);
+    return Object.freeze(args[0] === "--" ? args.slice(1) : args);
+})();
+
+export const system = {
+    platform: process.platform,
+    arch: process.arch,
+    homeDir: os.homedir,
+    tempDir: os.tmpdir,
+    appId,
+    permissions,
+    argv,
+    configDir: appConfigDir,
+    dataDir: appDataDir,
+    cacheDir: appCacheDir,
+    getEnv: (name: string): string | undefined => process.env[name],
+    setEnv: (name: string, value: string | undefined): void => {
+        if (value === undefined) {
+            delete process.env[name];
+        } else {
+            process.env[name] = value;
+        }
+    },
+};
@@ -0,0 +1,71 @@
+import process from "node:process";
+import path from "node:path";
+import { pathToFileURL } from "node:url";
+import { spawn } from "node:child_process";
+
+function launch(command: string, args: string[]): Promise<void> {
+    return new Promise((resolve, reject) => {
+        const child = spawn(command, args, { detached: true, stdio: "ignore" });
+        child.once("error", reject);
+        child.once("spawn", () => {
+            child.unref();
+            resolve();
+        });
+    });
+}
+
+/**
+ * Open a URL in the default browser, or a file with its default application.
+ * Only http(s), mailto and file targets (or plain paths) are accepted so app
+ * data can't be used to launch arbitrary URL handlers.
+ */
+export function openExternal(target: string): Promise<void> {
+    let url: URL;
+    try {
+        url = new URL(target);
+    } catch {
+        url = pathToFileURL(path.resolve(target));
+    }
+    if (!["http:", "https:", "mailto:", "file:"].includes(url.protocol)) {
+        return Promise.reject(new Error(`Vellum.openExternal: unsupported scheme "${url.protocol}"`));
+    }
+    const resolved = url.protocol === "file:" ? decodeURIComponent(url.pathname) : url.href;
+
+    switch (process.platform) {
+        case "darwin":
+            return launch("open", [resolved]);
+        case "win32":
+            // The empty string is `start`'s window title argument.
+            return launch("cmd", ["/c", "start", "", resolved.replace(/&/g, "^&")]);
+        default:
+            return launch("xdg-open", [resolved]);
+    }
+}
+
+/** Show `filePath` selected in the platform file manager. */
+export function revealInFolder(filePath: string): Promise<void> {
+    const resolved = path.resolve(filePath);
+    switch (process.platform) {
+        case "darwin":
+            return launch("open", ["-R", resolved]);
+        case "win32":
+            return launch("explorer", [`/select,${resolved}`]);
+        default:
+            // FileManager1 selects the item in Nautilus/Dolphin/Nemo; fall back
+            // to opening the containing directory when no such service exists.
+            return launch("dbus-send", [
+                "--session",
+                "--print-reply",
+                "--dest=org.freedesktop.FileManager1",
+                "/org/freedesktop/FileManager1",
+                "org.freedesktop.FileManager1.ShowItems",
+                `array:string:${pathToFileURL(resolved).href}`,
+                "string:",
+            ]).catch(() => launch("xdg-open", [path.dirname(resolved)]));
+    }
+}
+
+export const shell = {
+    openExternal,
+    revealInFolder,
+};
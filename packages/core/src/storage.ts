@@ -0,0 +1,79 @@
+import process from "node:process";
+import fs from "node:fs";
+import path from "node:path";
+import { appConfigDir } from "./paths.ts";
+
+/**
+ * Synchronous key-value store with the Web `Storage` interface, persisted as a
+ * JSON file. Every mutation is written through (temp file + rename), so state
+ * survives crashes as well as clean restarts.
+ */
+export class FileStorage implements Storage {
+    #file: string;
+    #items: Map<string, string> | null = null;
+
+    constructor(file: string) {
+        this.#file = file;
+    }
+
+    #load(): Map<string, string> {
+        if (this.#items) return this.#items;
+        this.#items = new Map();
+        try {
+            const parsed = JSON.parse(fs.readFileSync(this.#file, "utf8")) as Record<string, unknown>;
+            for (const [key, value] of Object.entries(parsed)) {
+                if (typeof value === "string") this.#items.set(key, value);
+            }
+        } catch (err) {
+            if ((err as NodeJS.ErrnoException).code !== "ENOENT") {
+                process.stderr.write(`[Vellum storage] Ignoring unreadable ${this.#file}: ${String(err)}\n`);
+            }
+        }
+        return this.#items;
+    }
+
+    #persist(): void {
+        const items = this.#load();
+        fs.mkdirSync(path.dirname(this.#file), { recursive: true });
+        const tmp = `${this.#file}.${process.pid}.tmp`;
+        fs.writeFileSync(tmp, JSON.stringify(Object.fromEntries(items)));
+        fs.renameSync(tmp, this.#file);
+    }
+
+    get length(): number {
+        return this.#load().size;
+    }
+
+    key(index: number): string | null {
+        return [...this.#load().keys()][index] ?? null;
+    }
+
+    getItem(key: string): string | null {
+        return this.#load().get(String(key)) ?? null;
+    }
+
+    setItem(key: string, value: string): void {
+        this.#load().set(String(key), String(value));
+        this.#persist();
+    }
+
+    removeItem(key: string): void {
+        if (this.#load().delete(String(key))) this.#persist();
+    }
+
+    clear(): void {
+        if (this.#load().size === 0) return;
+        this.#load().clear();
+        this.#persist();
+    }
+}
+
+/** App-wide store at `<appConfigDir>/storage.json`. */
+export const storage = new FileStorage(path.join(appConfigDir(), "storage.json"));
+
+// Bun has no built-in localStorage; provide the persistent one so libraries that
+// feature-detect it keep their state across restarts.
+const globalScope = globalThis as typeof globalThis & { localStorage?: Storage };
+if (globalScope.localStorage === undefined) {
+    globalScope.localStorage = storage;
+}
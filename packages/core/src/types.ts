@@ -26,35 +26,186 @@ export interface BoxStyle {
     borderColor?: string;
     hoveredBorderColor?: string;
     hoverBorderColor?: string;
+    /** Text and icon color while the widget is disabled (`enabled={false}`). */
+    disabledColor?: string;
+    /** Background while the widget is disabled. */
+    disabledBackground?: string;
     borderWidth?: number;
     cornerRadius?: number;
     borderRadius?: number;
+    /** `"hidden"` clips children to this widget's bounds and corner radius. Set it at creation. */
+    overflow?: "visible" | "hidden";
     padding?: number | { top: number; right: number; bottom: number; left: number };
     width?: number;
     height?: number;
 
     flex?: number;
+    /** Pin this Portal child to the top while its section scrolls (section headers). */
+    sticky?: boolean;
+    /** Stacking order among `<zstack>` / layer siblings; higher paints and takes clicks on top. */
+    zIndex?: number;
     direction?: FlexDirection;
     crossAxisAlignment?: CrossAlign;
     mainAxisAlignment?: MainAlign;
     gap?: number;
     mustFillMainAxis?: boolean;
 
+    trackColor?: string;
+    fillColor?: string;
+
+    /** `<image>` scaling, as CSS `object-fit`. */
+    objectFit?: "contain" | "cover" | "fill" | "none" | "scale-down";
+    /** `<image>` placement, as CSS `object-position` (`"top left"`, `"25% 75%"`). */
+    objectPosition?: string;
+    /** Tile the `<image>` across the widget from where it sits. */
+    repeat?: "no-repeat" | "repeat" | "repeat-x" | "repeat-y";
+
+    /**
+     * Container image (`asset://` URL or encoded bytes) painted over
+     * `background` and beneath the children. Set it at creation.
+     */
+    backgroundImage?: string | Uint8Array;
+    /** How the background image is scaled, as `objectFit` (default `"cover"`). */
+    backgroundFit?: "contain" | "cover" | "fill" | "none" | "scale-down";
+    /** Where the background image sits, as CSS `background-position`. */
+    backgroundPosition?: string;
+    backgroundRepeat?: "no-repeat" | "repeat" | "repeat-x" | "repeat-y";
+
+    /** Portal scrollbar thickness; `0` hides the bars but keeps scrolling. */
+    scrollbarWidth?: number;
+    scrollbarThumbColor?: string;
+    scrollbarTrackColor?: string;
+    /** Fade the Portal's scrollbars out while idle. */
+    scrollbarAutoHide?: boolean;
+    /** `false` fits Portal content to its width and disables sideways scrolling. */
+    horizontalScroll?: boolean;
+
+    /**
+     * Root only: `"transparent"` and `"systemMaterial"` need a window started
+     * with `--transparent`. `background` sets the solid fill or material tint.
+     */
+    backgroundMode?: "solid" | "transparent" | "systemMaterial";
+
     [key: string]: unknown;
 }
 
 export type VellumStyle = BoxStyle;
 
+/** Badge content: a count, `true`/`"dot"` for a dot, or `0`/`false`/`null` to hide it. */
+export type BadgeValue = number | boolean | "dot" | null;
+
+export type BadgePosition = "topRight" | "topLeft" | "bottomRight" | "bottomLeft";
+
+/**
+ * `badge` widget param, accepted by every widget kind.
+ * The widget is wrapped in an internal ZStack with the badge pinned to a corner.
+ */
+export type BadgeParam =
+    | BadgeValue
+    | {
+        value?: BadgeValue;
+        color?: string;
+        textColor?: string;
+        position?: BadgePosition;
+    };
+
 export interface SvgParams {
     svgData?: string;
+    /** `asset://` URL of an SVG file, used when no `svgData` is given. */
+    src?: string;
+    /** Base directory for relative `<image href>` and `<?xml-stylesheet?>` references. */
+    resourcesDir?: string;
+    /** Extra CSS applied after any stylesheets the SVG links to. */
+    styleSheet?: string;
+    /** Read by screen readers; without it the SVG is treated as decorative. */
+    alt?: string;
 }
 
 export interface ImageParams {
     object_fit?: string;
+    /** `asset://` URL loaded by the runtime when no bytes are sent. */
+    src?: string;
+    /** Read by screen readers in place of the image. */
+    alt?: string;
 }
 
 export interface VideoParams {
     src?: string;
+    /** Image path or `asset://` URL shown until the first frame. */
+    poster?: string;
+}
+
+export interface AvatarParams {
+    /** `online`, `away`, `busy`, `offline`, or any color string. */
+    status?: string;
+    /** Background behind the initials; derived from the name when omitted. */
+    color?: string;
+}
+
+export interface SkeletonParams {
+    /** Render as a circle, e.g. an avatar placeholder. */
+    circle?: boolean;
+    color?: string;
+    highlightColor?: string;
+}
+
+export interface QrCodeParams {
+    symbology?: "qr" | "code128";
+    /** Light margin around the symbol, in modules. Defaults to 4 (QR) / 10 (Code128). */
+    quietZone?: number;
+    /** Module color; falls back to `style.color`. */
+    color?: string;
+    /** Quiet-zone / light module color; falls back to `style.background`. */
+    background?: string;
+}
+
+/**
+ * One entry of a `customPaint` display list. Coordinates are logical pixels
+ * in the widget; `save`/`restore` bracket `translate`/`scale`/`rotate`.
+ */
+export type PaintOp =
+    | { op: "fillRect"; x: number; y: number; width: number; height: number; color: string; radius?: number }
+    | {
+        op: "strokeRect";
+        x: number;
+        y: number;
+        width: number;
+        height: number;
+        color: string;
+        lineWidth?: number;
+        radius?: number;
+    }
+    | { op: "fillCircle"; cx: number; cy: number; r: number; color: string }
+    | { op: "strokeCircle"; cx: number; cy: number; r: number; color: string; lineWidth?: number }
+    | { op: "line"; x1: number; y1: number; x2: number; y2: number; color: string; lineWidth?: number }
+    /** `d` is SVG path data. */
+    | { op: "path"; d: string; fill?: string; stroke?: string; lineWidth?: number }
+    | { op: "save" }
+    | { op: "restore" }
+    | { op: "translate"; x: number; y: number }
+    | { op: "scale"; x: number; y: number }
+    | { op: "rotate"; radians: number };
+
+export interface CustomPaintParams {
+    ops?: PaintOp[];
+}
+
+export interface GpuSurfaceParams {
+    /**
+     * WGSL defining `@fragment fn fs_main(@builtin(position) pos: vec4<f32>) -> @location(0) vec4<f32>`;
+     * it may read `surface.resolution`, `surface.time` and `uniform(i)`.
+     */
+    shader?: string;
+    /** Name of a renderer registered by a Rust plugin; takes precedence over `shader`. */
+    renderer?: string;
+    uniforms?: number[];
+    /** Repaint every frame, advancing `surface.time`. */
+    animate?: boolean;
+}
+
+export interface PortalParams {
+    /** Pulling past the top emits `refreshRequested`; call `ui.endRefresh(id)` when done. */
+    pullToRefresh?: boolean;
 }
 
 export interface CheckboxParams {
@@ -67,6 +218,8 @@ export interface TextInputParams {
 
 export interface ProgressBarParams {
     progress?: number;
+    buffer?: number;
+    indeterminate?: boolean;
 }
 
 export interface SliderParams {
@@ -76,11 +229,24 @@ export interface SliderParams {
     step?: number;
 }
 
+export interface RangeSliderParams {
+    minValue: number;
+    maxValue: number;
+    low: number;
+    high: number;
+    step?: number;
+}
+
 export interface VellumEvent {
     type: string;
     widgetId?: string;
     action?: string;
     value?: string | number | boolean;
+    low?: number;
+    high?: number;
+    /** itemMoved only: the row's old and new index. */
+    from?: number;
+    to?: number;
     width?: number;
     height?: number;
     x?: number;
@@ -88,4 +254,120 @@ export interface VellumEvent {
     key?: string;
     text?: string;
     focused?: boolean;
+    /** runtimeError only: which part of the runtime reported it. */
+    source?: string;
+    /** runtimeError only: machine-readable failure category. */
+    code?: ErrorCode;
+    /** runtimeError only: the command that failed, e.g. `"SetWidgetValue"`. */
+    command?: string;
+    message?: string;
+    fatal?: boolean;
+    /** openUrl only: the deep link the app was opened with. */
+    url?: string;
+    /** openFiles only (macOS): documents the app was asked to open. */
+    paths?: string[];
+    /** reopen only (macOS): whether a window was visible when the dock icon was clicked. */
+    hasVisibleWindows?: boolean;
+    /** dockMenu only (macOS): id of the chosen dock menu item. */
+    itemId?: string;
+    /**
+     * Milliseconds since the UI started (monotonic), taken when the UI thread
+     * emitted the event; for frameTick, when the frame began.
+     */
+    timestamp?: number;
+    /** Emission order across all events; compare to order events handled after an `await`. */
+    seq?: number;
+    /** click only: the pointer button, or `"keyboard"` for Space / Enter activation. */
+    button?: ClickButton;
+    /** click only: 2 for the second click of a double click, and so on. */
+    clickCount?: number;
+    /** click only: modifier keys held during the click (`meta` is Cmd on macOS). */
+    shift?: boolean;
+    ctrl?: boolean;
+    alt?: boolean;
+    meta?: boolean;
+    /** beforeExit only: the code the process will exit with. */
+    exitCode?: number;
+    /** beforeExit only: why the app is exiting. */
+    reason?: ExitReason;
+    /** beforeExit only: how long handlers have before the UI exits anyway. */
+    timeoutMs?: number;
+    /** defaultDeviceChanged only: the new default output, or null when there is none. */
+    device?: AudioDevice | null;
+    /** journalChanged only: whether `journal.undo()` / `journal.redo()` have a step to apply. */
+    canUndo?: boolean;
+    canRedo?: boolean;
+    /** visibilityChanged only: the fraction of the widget that shows, 0 to 1. */
+    ratio?: number;
+    /** updateReady only: the version that was staged. */
+    version?: string;
+    /** updateProgress only: bytes downloaded so far, and in all when the feed lists sizes. */
+    downloaded?: number;
+    total?: number;
+    /** updateFailed only: why nothing was staged. */
+    error?: string;
+}
+
+export type ExitReason = "app" | "quit" | "runtimeExited" | "runtimeFailed" | "timeout";
+
+export type ClickButton = "primary" | "secondary" | "auxiliary" | "back" | "forward" | "other" | "keyboard";
+
+export interface DockMenuItem {
+    id: string;
+    title: string;
+    /** Shortcut that also chooses the item while the window has focus, e.g. `"CmdOrCtrl+N"`. */
+    accelerator?: string;
 }
+
+/** An installed font family, as returned by `fonts.list()`. */
+export interface FontFamily {
+    name: string;
+    /** Available faces; `weight` is the CSS weight (100–900). */
+    faces: Array<{ weight: number; style: "normal" | "italic" | "oblique" }>;
+}
+
+/** A command the UI thread handled, from `dumpRecentCommands()`. */
+export interface RecentCommand {
+    /** Position among all commands handled this run. */
+    seq: number;
+    /** Rust command name, e.g. `SetWidgetText`. */
+    name: string;
+    widgetId?: string;
+    /** Milliseconds since the UI started, the clock of event timestamps. */
+    timestamp: number;
+}
+
+/** Runtime counters from `getMetrics()`. */
+export interface Metrics {
+    widgetCount: number;
+    videos: VideoMetrics[];
+}
+
+export interface VideoMetrics {
+    id: string;
+    /** Undefined until the first frame has been decoded. */
+    decoder?: {
+        /** GStreamer element such as `vah264dec` or `avdec_h264`, or `ffmpeg`. */
+        name: string;
+        hardware: boolean;
+    };
+}
+
+/** An audio output, from `audio.devices()` and the `defaultDeviceChanged` event. */
+export interface AudioDevice {
+    /** Pass to `audio.setOutput()`; stable while the device is connected. */
+    id: string;
+    name: string;
+    isDefault: boolean;
+}
+
+/** Machine-readable category attached to every `runtimeError` event. */
+export type ErrorCode =
+    | "WidgetNotFound"
+    | "UnsupportedOperation"
+    | "InvalidHierarchy"
+    | "InvalidValue"
+    | "ParseError"
+    | "TreeInconsistent"
+    | "DispatchFailed"
+    | "Internal";
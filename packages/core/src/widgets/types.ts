@@ -13,6 +13,13 @@ export type ProseStyle = BoxStyle;
 export type ProgressBarStyle = BoxStyle;
 export type SpinnerStyle = BoxStyle;
 export type SliderStyle = BoxStyle;
+export type RangeSliderStyle = BoxStyle;
+export type AvatarStyle = BoxStyle;
+export type SkeletonStyle = BoxStyle;
+export type QrCodeStyle = BoxStyle;
+export type CustomPaintStyle = BoxStyle;
+export type GpuSurfaceStyle = BoxStyle;
+export type IconStyle = BoxStyle;
 export type ZStackStyle = BoxStyle;
 export type PortalStyle = BoxStyle;
 
@@ -34,6 +41,8 @@ export interface TextInputData {
 
 export interface ProgressBarData {
     progress?: number;
+    buffer?: number;
+    indeterminate?: boolean;
 }
 
 export interface SliderData {
@@ -42,3 +51,11 @@ export interface SliderData {
     value: number;
     step?: number;
 }
+
+export interface RangeSliderData {
+    minValue: number;
+    maxValue: number;
+    low: number;
+    high: number;
+    step?: number;
+}
@@ -1,4 +1,4 @@
-import { HostElement, VellumRuntime, VellumStyle } from "./types";
+import { HostElement, VellumBadgeValue, VellumPaintOp, VellumRuntime, VellumStyle } from "./types";
 import {
   isEventProp,
   normalizeWidgetKind,
@@ -36,9 +36,11 @@ export function collectInitialWidgetState(node: HostElement): {
     if (name === "id") continue;
     if (name === "type") continue;
     if (name === "visible") continue;
+    if (name === "enabled") continue;
     if (name === "data") continue;
     if (name === "objectFit") continue;
     if (name === "src" || name === "playing" || name === "position") continue;
+    if (name === "volume" || name === "muted") continue;
 
     if (name === "text") {
       if (kind === "button") {
@@ -66,24 +68,172 @@ export function collectInitialWidgetState(node: HostElement): {
       continue;
     }
 
-    if (name === "min" && typeof value === "number" && kind === "slider") {
+    if (name === "min" && typeof value === "number" && (kind === "slider" || kind === "rangeSlider")) {
       params.minValue = value;
       hasParams = true;
       continue;
     }
 
-    if (name === "max" && typeof value === "number" && kind === "slider") {
+    if (name === "max" && typeof value === "number" && (kind === "slider" || kind === "rangeSlider")) {
       params.maxValue = value;
       hasParams = true;
       continue;
     }
 
-    if (name === "step" && typeof value === "number" && kind === "slider") {
+    if ((name === "low" || name === "high") && typeof value === "number" && kind === "rangeSlider") {
+      params[name] = value;
+      hasParams = true;
+      continue;
+    }
+
+    if (name === "step" && typeof value === "number" && (kind === "slider" || kind === "rangeSlider")) {
       params.step = value;
       hasParams = true;
       continue;
     }
 
+    if (name === "indeterminate" && kind === "progressBar") {
+      params.indeterminate = Boolean(value);
+      hasParams = true;
+      continue;
+    }
+
+    if (name === "buffer" && typeof value === "number" && kind === "progressBar") {
+      params.buffer = value;
+      hasParams = true;
+      continue;
+    }
+
+    if (name === "badge") {
+      params.badge = value;
+      hasParams = true;
+      continue;
+    }
+
+    if (kind === "avatar") {
+      if (name === "name") {
+        text = String(value);
+        continue;
+      }
+      if (name === "status" && typeof value === "string") {
+        params.status = value;
+        hasParams = true;
+        continue;
+      }
+      if (name === "fallbackColor" && typeof value === "string") {
+        params.color = value;
+        hasParams = true;
+        continue;
+      }
+    }
+
+    if (kind === "svg" && (name === "resourcesDir" || name === "styleSheet") && typeof value === "string") {
+      params[name] = value;
+      hasParams = true;
+      continue;
+    }
+
+    if ((kind === "svg" || kind === "image") && name === "alt" && typeof value === "string") {
+      params.alt = value;
+      hasParams = true;
+      continue;
+    }
+
+    if (kind === "icon" && name === "name") {
+      text = String(value);
+      continue;
+    }
+
+    if (kind === "qrCode") {
+      if (name === "value") {
+        text = String(value ?? "");
+        continue;
+      }
+      if (name === "symbology" && typeof value === "string") {
+        params.symbology = value;
+        hasParams = true;
+        continue;
+      }
+      if (name === "quietZone" && typeof value === "number") {
+        params.quietZone = value;
+        hasParams = true;
+        continue;
+      }
+    }
+
+    if (kind === "customPaint" && name === "ops" && Array.isArray(value)) {
+      params.ops = value;
+      hasParams = true;
+      continue;
+    }
+
+    if (kind === "gpuSurface") {
+      if ((name === "shader" || name === "renderer") && typeof value === "string") {
+        params[name] = value;
+        hasParams = true;
+        continue;
+      }
+      if (name === "uniforms" && Array.isArray(value)) {
+        params.uniforms = value;
+        hasParams = true;
+        continue;
+      }
+      if (name === "animate") {
+        params.animate = Boolean(value);
+        hasParams = true;
+        continue;
+      }
+    }
+
+    if (kind === "button") {
+      if ((name === "accelerator" || name === "variant") && typeof value === "string") {
+        params[name] = value;
+        hasParams = true;
+        continue;
+      }
+      if ((name === "hoverLighten" || name === "pressDarken") && typeof value === "number") {
+        params[name] = value;
+        hasParams = true;
+        continue;
+      }
+      if (name === "ripple") {
+        params.ripple = Boolean(value);
+        hasParams = true;
+        continue;
+      }
+    }
+
+    if (kind === "label" && name === "selectable") {
+      params.selectable = Boolean(value);
+      hasParams = true;
+      continue;
+    }
+
+    if (kind === "video" && name === "poster" && typeof value === "string") {
+      params.poster = value;
+      hasParams = true;
+      continue;
+    }
+
+    if (kind === "portal" && name === "pullToRefresh") {
+      params.pullToRefresh = Boolean(value);
+      hasParams = true;
+      continue;
+    }
+
+    if (kind === "skeleton") {
+      if (name === "circle") {
+        params.circle = Boolean(value);
+        hasParams = true;
+        continue;
+      }
+      if ((name === "baseColor" || name === "highlightColor") && typeof value === "string") {
+        params[name === "baseColor" ? "color" : "highlightColor"] = value;
+        hasParams = true;
+        continue;
+      }
+    }
+
     if (name === "placeholder" && typeof value === "string" && kind === "textInput") {
       params.placeholder = value;
       hasParams = true;
@@ -96,6 +246,12 @@ export function collectInitialWidgetState(node: HostElement): {
       continue;
     }
 
+    if (name === "backgroundImage" && value instanceof Uint8Array) {
+      style.backgroundImage = value;
+      hasStyle = true;
+      continue;
+    }
+
     if (isPrimitiveStyleValue(value)) {
       style[mapStyleKey(name)] = value;
       hasStyle = true;
@@ -105,11 +261,14 @@ export function collectInitialWidgetState(node: HostElement): {
   // Extract image-specific props
   let data: Uint8Array | null = null;
 
-  if (kind === "image") {
+  if (kind === "image" || kind === "avatar") {
     const rawData = node.props.data;
     if (rawData instanceof Uint8Array) {
       data = rawData;
     }
+  }
+
+  if (kind === "image") {
     const objectFit = node.props.objectFit;
     if (typeof objectFit === "string") {
       params.object_fit = objectFit;
@@ -117,7 +276,7 @@ export function collectInitialWidgetState(node: HostElement): {
     }
   }
 
-  if (kind === "video") {
+  if (kind === "video" || kind === "image" || kind === "svg") {
     const src = node.props.src;
     if (typeof src === "string") {
       params.src = src;
@@ -141,7 +300,7 @@ export function collectInitialWidgetState(node: HostElement): {
 
 export function applyMountedProperty(runtime: VellumRuntime, node: HostElement, name: string, value: unknown): void {
   if (name === "children" || name === "ref" || name === "key" || name === "id") return;
-  if (name === "type" || name === "src") return;
+  if (name === "type" || name === "src" || name === "alt" || name === "poster") return;
   if (isEventProp(name)) return;
 
   if (name === "playing" && typeof value === "boolean") {
@@ -158,6 +317,16 @@ export function applyMountedProperty(runtime: VellumRuntime, node: HostElement,
     return;
   }
 
+  if (name === "volume" && typeof value === "number") {
+    runtime.ui.setVideoVolume?.(node.widgetId, value);
+    return;
+  }
+
+  if (name === "muted" && typeof value === "boolean") {
+    runtime.ui.setVideoMuted?.(node.widgetId, value);
+    return;
+  }
+
   if (name === "style") {
     if (value && typeof value === "object") {
       runtime.ui.setStyle(node.widgetId, value as VellumStyle);
@@ -175,6 +344,11 @@ export function applyMountedProperty(runtime: VellumRuntime, node: HostElement,
     return;
   }
 
+  if (name === "enabled") {
+    runtime.ui.setEnabled?.(node.widgetId, Boolean(value));
+    return;
+  }
+
   if (name === "checked") {
     runtime.ui.setChecked(node.widgetId, Boolean(value));
     return;
@@ -185,10 +359,89 @@ export function applyMountedProperty(runtime: VellumRuntime, node: HostElement,
     return;
   }
 
+  if (name === "indeterminate") {
+    runtime.ui.setIndeterminate?.(node.widgetId, Boolean(value));
+    return;
+  }
+
+  if (name === "buffer") {
+    runtime.ui.setBuffer?.(node.widgetId, typeof value === "number" ? value : null);
+    return;
+  }
+
+  if (node.tag === "avatar") {
+    if (name === "name") {
+      runtime.ui.setText(node.widgetId, String(value ?? ""));
+      return;
+    }
+    if (name === "status") {
+      runtime.ui.setAvatarStatus?.(node.widgetId, typeof value === "string" ? value : null);
+      return;
+    }
+    if (name === "fallbackColor") {
+      return;
+    }
+  }
+
+  if (node.tag === "icon" && name === "name") {
+    runtime.ui.setText(node.widgetId, String(value ?? ""));
+    return;
+  }
+
+  if (node.tag === "qrCode") {
+    if (name === "value") {
+      runtime.ui.setText(node.widgetId, String(value ?? ""));
+      return;
+    }
+    if (name === "symbology" || name === "quietZone") return;
+  }
+
+  if (node.tag === "customPaint" && name === "ops") {
+    runtime.ui.setPaintOps?.(node.widgetId, Array.isArray(value) ? (value as VellumPaintOp[]) : []);
+    return;
+  }
+
+  if (node.tag === "gpuSurface") {
+    if (name === "shader") {
+      runtime.ui.setShader?.(node.widgetId, String(value ?? ""));
+      return;
+    }
+    if (name === "uniforms") {
+      runtime.ui.setShaderUniforms?.(node.widgetId, Array.isArray(value) ? (value as number[]) : []);
+      return;
+    }
+    if (name === "renderer" || name === "animate") return;
+  }
+
+  if (node.tag === "skeleton" && (name === "circle" || name === "baseColor" || name === "highlightColor")) {
+    return;
+  }
+
+  if (name === "badge") {
+    // Object form without `value` shows a dot, matching creation-time parsing.
+    const badgeValue = value !== null && typeof value === "object"
+      ? ((value as { value?: VellumBadgeValue }).value ?? "dot")
+      : (value ?? null);
+    runtime.ui.setBadge?.(node.widgetId, badgeValue as VellumBadgeValue);
+    return;
+  }
+
+  if ((name === "low" || name === "high") && typeof value === "number") {
+    const low = typeof node.props.low === "number" ? node.props.low : value;
+    const high = typeof node.props.high === "number" ? node.props.high : value;
+    runtime.ui.setRange?.(node.widgetId, low, high);
+    return;
+  }
+
   if (name === "min" || name === "max" || name === "step" || name === "placeholder") {
     return;
   }
 
+  if (name === "backgroundImage" && value instanceof Uint8Array) {
+    runtime.ui.setStyle(node.widgetId, { backgroundImage: value });
+    return;
+  }
+
   if (isPrimitiveStyleValue(value)) {
     runtime.ui.setStyleProperty(node.widgetId, mapStyleKey(name), value);
   }
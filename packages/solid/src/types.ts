@@ -4,11 +4,28 @@ import type { JSX as SolidJSX } from "solid-js";
 
 export type VellumStyle = Record<string, unknown>;
 
+/** Badge content: a count, `true`/`"dot"` for a dot, or `0`/`false`/`null` to hide it. */
+export type VellumBadgeValue = number | boolean | "dot" | null;
+
+export type VellumBadge =
+  | VellumBadgeValue
+  | {
+    value?: VellumBadgeValue;
+    color?: string;
+    textColor?: string;
+    position?: "topRight" | "topLeft" | "bottomRight" | "bottomLeft";
+  };
+
 export interface VellumEvent {
   type: string;
   widgetId?: string;
   action?: string;
   value?: string | number | boolean;
+  low?: number;
+  high?: number;
+  /** itemMoved only: the row's old and new index. */
+  from?: number;
+  to?: number;
   width?: number;
   height?: number;
   x?: number;
@@ -16,6 +33,15 @@ export interface VellumEvent {
   key?: string;
   text?: string;
   focused?: boolean;
+  timestamp?: number;
+  seq?: number;
+  /** click only: pointer button, or `"keyboard"` for Space / Enter. */
+  button?: "primary" | "secondary" | "auxiliary" | "back" | "forward" | "other" | "keyboard";
+  clickCount?: number;
+  shift?: boolean;
+  ctrl?: boolean;
+  alt?: boolean;
+  meta?: boolean;
 }
 
 export interface VellumRuntime {
@@ -33,7 +59,17 @@ export interface VellumRuntime {
     removeWidget: (id: string) => void;
     setText: (id: string, text: string) => void;
     setVisible: (id: string, visible: boolean) => void;
+    setEnabled?: (id: string, enabled: boolean) => void;
     setValue: (id: string, value: number) => void;
+    setRange?: (id: string, low: number, high: number) => void;
+    setIndeterminate?: (id: string, indeterminate: boolean) => void;
+    setBuffer?: (id: string, buffer: number | null) => void;
+    setBadge?: (id: string, value: VellumBadgeValue) => void;
+    setAvatarStatus?: (id: string, status: string | null) => void;
+    setPaintOps?: (id: string, ops: VellumPaintOp[]) => void;
+    setShader?: (id: string, wgsl: string) => void;
+    setShaderUniforms?: (id: string, values: number[]) => void;
+    registerIconPack?: (prefix: string, icons: Record<string, string>) => void;
     setChecked: (id: string, checked: boolean) => void;
     setStyle: (id: string, style: VellumStyle) => void;
     setStyleProperty: (id: string, property: string, value: string | number | boolean) => void;
@@ -41,6 +77,8 @@ export interface VellumRuntime {
     playVideo?: (id: string) => void;
     pauseVideo?: (id: string) => void;
     seekVideo?: (id: string, timeSecs: number) => void;
+    setVideoVolume?: (id: string, volume: number) => void;
+    setVideoMuted?: (id: string, muted: boolean) => void;
   };
   events: {
     on: (type: string, callback: (event: VellumEvent) => void) => () => void;
@@ -134,10 +172,25 @@ export interface VellumCommonProps {
   value?: number | (() => number);
   checked?: boolean | (() => boolean);
   visible?: boolean | (() => boolean);
+  /** `false` disables the widget and its children, painting `disabledColor` / `disabledBackground`. */
+  enabled?: boolean | (() => boolean);
+  // A badge must be present at mount (use 0 to start hidden) to be updated later.
+  badge?: VellumBadge | (() => VellumBadge);
   onClick?: WidgetActionHandler;
+  /** Two quick clicks on the widget; `x`/`y` are local to it. */
+  onDoubleClick?: WidgetActionHandler;
+  /** The primary button held still on the widget (500ms by default). */
+  onLongPress?: WidgetActionHandler;
   onValueChanged?: WidgetActionHandler;
   onHover?: WidgetActionHandler;
+  onRangeChanged?: WidgetActionHandler;
   onTextChanged?: WidgetActionHandler;
+  /** Direct children of a `<portal>` only: the row scrolled into view. */
+  onEnteredViewport?: WidgetActionHandler;
+  /** Needs `Vellum.ui.observeVisibility(id, threshold)`; `event.ratio` is the fraction shown. */
+  onVisibilityChanged?: WidgetActionHandler;
+  /** Needs `Vellum.ui.observeResize(id)`; `event.width` and `event.height` are the new size. */
+  onResized?: WidgetActionHandler;
   onWidgetAction?: WidgetActionHandler;
   [key: string]: unknown;
 }
@@ -149,12 +202,22 @@ export interface SliderProps extends VellumCommonProps {
   value?: number | (() => number);
 }
 
+export interface RangeSliderProps extends VellumCommonProps {
+  min?: number | (() => number);
+  max?: number | (() => number);
+  step?: number | (() => number);
+  low?: number | (() => number);
+  high?: number | (() => number);
+}
+
 export interface CheckboxProps extends VellumCommonProps {
   checked?: boolean | (() => boolean);
 }
 
 export interface ProgressBarProps extends VellumCommonProps {
   value?: number | (() => number);
+  buffer?: number | (() => number);
+  indeterminate?: boolean | (() => boolean);
 }
 
 export interface TextInputProps extends VellumCommonProps {
@@ -163,11 +226,124 @@ export interface TextInputProps extends VellumCommonProps {
 
 export interface ImageProps extends VellumCommonProps {
   data?: Uint8Array | (() => Uint8Array);
+  /** `asset://` URL loaded by the runtime when no `data` is given. */
+  src?: string;
   objectFit?: string | (() => string);
+  /** CSS `object-position`, e.g. `"top left"` or `"25% 75%"`. */
+  objectPosition?: string | (() => string);
+  repeat?: "no-repeat" | "repeat" | "repeat-x" | "repeat-y" | (() => "no-repeat" | "repeat" | "repeat-x" | "repeat-y");
+  /** Read by screen readers in place of the image. */
+  alt?: string;
+}
+
+export interface AvatarProps extends VellumCommonProps {
+  /** Used for the initials fallback and the accessible label. */
+  name?: string | (() => string);
+  data?: Uint8Array | (() => Uint8Array);
+  /** `online`, `away`, `busy`, `offline`, or any color string. */
+  status?: string | null | (() => string | null);
+  /** Background behind the initials; derived from the name when omitted. */
+  fallbackColor?: string | (() => string);
+}
+
+export interface SkeletonProps extends VellumCommonProps {
+  circle?: boolean | (() => boolean);
+  baseColor?: string | (() => string);
+  highlightColor?: string | (() => string);
+}
+
+export interface SvgProps extends VellumCommonProps {
+  svg_data?: string | (() => string);
+  /** `asset://` URL of an SVG file, used when no markup is given. */
+  src?: string;
+  /** Base directory for relative `<image href>` and `<?xml-stylesheet?>` references. */
+  resourcesDir?: string;
+  /** Extra CSS applied after any stylesheets the SVG links to. */
+  styleSheet?: string;
+  /** Read by screen readers; without it the SVG is treated as decorative. */
+  alt?: string;
+}
+
+export interface IconProps extends VellumCommonProps {
+  /** `pack:name`, e.g. `"lucide:check"`; bare names use the bundled Lucide pack. */
+  name?: string | (() => string);
+}
+
+export interface QrCodeProps extends VellumCommonProps {
+  /** The string to encode. */
+  value?: string | (() => string);
+  symbology?: "qr" | "code128";
+  quietZone?: number;
+}
+
+/** A display list entry, e.g. `{ op: "fillRect", x, y, width, height, color }` (see `PaintOp` in @vellum/core). */
+export type VellumPaintOp = { op: string; [key: string]: unknown };
+
+export interface CustomPaintProps extends VellumCommonProps {
+  ops?: VellumPaintOp[] | (() => VellumPaintOp[]);
+  onPointerDown?: WidgetActionHandler;
+  onPointerMove?: WidgetActionHandler;
+  onPointerUp?: WidgetActionHandler;
+}
+
+export interface GpuSurfaceProps extends VellumCommonProps {
+  /** WGSL defining `fs_main`; reads `surface.resolution`, `surface.time` and `uniform(i)`. */
+  shader?: string | (() => string);
+  /** Renderer registered by a Rust plugin (fixed at creation). */
+  renderer?: string;
+  uniforms?: number[] | (() => number[]);
+  /** Fixed at creation. */
+  animate?: boolean;
+  onPointerDown?: WidgetActionHandler;
+  onPointerMove?: WidgetActionHandler;
+  onPointerUp?: WidgetActionHandler;
 }
 
 export interface VideoProps extends VellumCommonProps {
   src?: string | (() => string);
+  /** Image path or `asset://` URL shown until the first frame. Fixed at creation. */
+  poster?: string;
+  /** The source could not be opened or stopped playing; `event.message` says why. */
+  onVideoError?: WidgetActionHandler;
+}
+
+export interface PortalProps extends VellumCommonProps {
+  /** Fixed at creation. Pulling past the top fires `onRefreshRequested`. */
+  pullToRefresh?: boolean;
+  /** Call `Vellum.ui.endRefresh(id)` when the reload is done to hide the spinner. */
+  onRefreshRequested?: WidgetActionHandler;
+}
+
+export interface LabelProps extends VellumCommonProps {
+  /**
+   * Fixed at creation. Lets the user select the text with the mouse and copy
+   * it with Ctrl/Cmd+C, like `<prose>`.
+   */
+  selectable?: boolean;
+}
+
+export interface ButtonProps extends VellumCommonProps {
+  /**
+   * Fixed at creation. A shortcut such as `"CmdOrCtrl+S"`, shown at the end
+   * of the button and pressing it while the window has focus fires `onClick`.
+   */
+  accelerator?: string;
+  /**
+   * Fixed at creation. Preset look: `"filled"`, `"outline"` or `"ghost"`.
+   * Styles you set yourself win over the variant's.
+   */
+  variant?: "filled" | "outline" | "ghost";
+  /** Fixed at creation. Opacity (0..1) of the hover overlay; 0 turns it off. */
+  hoverLighten?: number;
+  /** Fixed at creation. Opacity (0..1) of the black overlay while pressed; 0 turns it off. */
+  pressDarken?: number;
+  /** Fixed at creation. Spread a ripple from the press point. */
+  ripple?: boolean;
+}
+
+export interface ReorderableListProps extends VellumCommonProps {
+  /** Receives `{ from, to }` after a row is dropped in a new slot. */
+  onItemMoved?: WidgetActionHandler;
 }
 
 export interface HoverableProps extends VellumCommonProps {
@@ -188,24 +364,35 @@ export interface VellumIntrinsicElements {
   column: VellumCommonProps;
   container: VellumCommonProps;
   sizedBox: VellumCommonProps;
-  button: VellumCommonProps;
-  label: VellumCommonProps;
+  button: ButtonProps;
+  label: LabelProps;
   textInput: TextInputProps;
   textArea: VellumCommonProps;
   checkbox: CheckboxProps;
   progressBar: ProgressBarProps;
   spinner: VellumCommonProps;
+  skeleton: SkeletonProps;
+  qrCode: QrCodeProps;
+  customPaint: CustomPaintProps;
+  gpuSurface: GpuSurfaceProps;
+  icon: IconProps;
   slider: SliderProps;
-  svg: VellumCommonProps;
+  rangeSlider: RangeSliderProps;
+  svg: SvgProps;
   image: ImageProps;
+  avatar: AvatarProps;
   prose: VellumCommonProps;
   grid: VellumCommonProps;
   stack: VellumCommonProps;
   hoverable: VellumCommonProps;
-  video: VellumCommonProps & {
-    src?: string | (() => string);
+  portal: PortalProps;
+  reorderableList: ReorderableListProps;
+  video: VideoProps & {
     playing?: boolean | (() => boolean);
     position?: number | (() => number);
+    /** 0.0 (silent) to 1.0 (full). */
+    volume?: number | (() => number);
+    muted?: boolean | (() => boolean);
   };
   [tag: string]: unknown;
 }
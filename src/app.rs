@@ -0,0 +1,410 @@
+// App Startup
+// Everything the `vellum` binary does, from reading its arguments to shutting
+// down after the window closes, as a library entry point. An application that
+// embeds the runtime adds its own widget kinds, GPU renderers and hooks, then
+// hands over the main thread:
+//
+//   fn main() {
+//       let extensions = vellum::Extensions::new()
+//           .widget_factory("chart", ChartFactory)
+//           .hooks(Telemetry::default());
+//       std::process::exit(vellum::run(std::env::args().skip(1).collect(), extensions));
+//   }
+
+use std::path::{Path, PathBuf};
+use std::thread;
+
+use crate::cli::{self, WindowOptions};
+use crate::hooks::{self, RuntimeHooks};
+use crate::ipc::server::run_ipc_server;
+use crate::ipc::{self, IpcChannels, UiEvent};
+#[cfg(target_os = "macos")]
+use crate::macos;
+use crate::runtime::{self, BunSupervisor};
+#[cfg(feature = "test-support")]
+use crate::test_support;
+use crate::ui::gpu_renderer::{GpuRenderer, register_gpu_renderer};
+use crate::ui::widget_factory::{WidgetFactory, register_widget_factory};
+use crate::ui::{self, prepare_ui, run_ui_blocking};
+use crate::{
+    assets, bundle, crash, deeplink, locale, manifest, plugins, power, socket, stress, update,
+};
+
+/// Widget factories, GPU renderers and runtime hooks to register before the
+/// runtime starts. The `vellum` binary runs with none.
+#[derive(Default)]
+pub struct Extensions {
+    registrations: Vec<Box<dyn FnOnce()>>,
+}
+
+impl Extensions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build `WidgetKind::Custom(kind)` widgets with `factory`.
+    pub fn widget_factory(
+        mut self,
+        kind: impl Into<String>,
+        factory: impl WidgetFactory + 'static,
+    ) -> Self {
+        let kind = kind.into();
+        self.registrations
+            .push(Box::new(move || register_widget_factory(kind, factory)));
+        self
+    }
+
+    /// Draw GpuSurface widgets created with `renderer: name` with renderers
+    /// from `constructor`.
+    pub fn gpu_renderer(
+        mut self,
+        name: impl Into<String>,
+        constructor: impl Fn() -> Box<dyn GpuRenderer> + Send + Sync + 'static,
+    ) -> Self {
+        let name = name.into();
+        self.registrations
+            .push(Box::new(move || register_gpu_renderer(name, constructor)));
+        self
+    }
+
+    /// Call `hooks` as the runtime works (see hooks.rs).
+    pub fn hooks(mut self, hooks: impl RuntimeHooks + 'static) -> Self {
+        self.registrations
+            .push(Box::new(move || hooks::register(hooks)));
+        self
+    }
+
+    fn install(self) {
+        for register in self.registrations {
+            register();
+        }
+    }
+}
+
+/// The id that names the app's data directories: `VELLUM_APP_ID`, else the
+/// manifest's `id`, else the executable's name for a packaged app or the
+/// entry's directory name otherwise (what `appId()` in paths.ts falls back
+/// to when Bun starts on its own).
+fn resolve_app_id(manifest_id: Option<&str>, entry: Option<&Path>, packaged: bool) -> String {
+    let from_env = std::env::var("VELLUM_APP_ID")
+        .ok()
+        .map(|id| id.trim().to_string())
+        .filter(|id| !id.is_empty());
+    let fallback = if packaged {
+        std::env::current_exe()
+            .ok()
+            .and_then(|exe| Some(exe.file_stem()?.to_string_lossy().into_owned()))
+    } else {
+        entry
+            .and_then(|entry| std::path::absolute(entry).ok())
+            .and_then(|entry| Some(entry.parent()?.file_name()?.to_string_lossy().into_owned()))
+    };
+    from_env
+        .or_else(|| manifest_id.map(str::to_string))
+        .or(fallback)
+        .unwrap_or_else(|| "vellum-app".to_string())
+}
+
+/// Run the runtime the way the `vellum` binary does, with `args` as its
+/// command line (without the program name). Must be called on the main
+/// thread, which it keeps until the window closes. Returns the exit code,
+/// and leaves this process's environment alone: what Bun needs is passed to
+/// its process only.
+pub fn run(args: Vec<String>, extensions: Extensions) -> i32 {
+    println!("Vellum Starting...");
+    crash::install();
+    extensions.install();
+
+    println!("[Main] Operating in Client-Server Socket IPC Mode");
+
+    // An update downloaded by an earlier run is installed before anything
+    // else; this restarts into it.
+    if let Some(code) = update::install_staged() {
+        return code;
+    }
+
+    // A packaged app (see `vellum build`) runs its embedded entry; otherwise
+    // `vellum app.ts [args...]` hosts the Bun runtime itself, and with no entry
+    // the binary waits for a client that spawned it (the bun_bridge.ts path).
+    // Packaged apps pass every argument through to the app.
+    let embedded = bundle::read_embedded();
+    let packaged = embedded.is_some();
+    let mut record_path = None;
+    let mut replay_path = None;
+    let mut stress_plan = None;
+    let mut preload = Vec::new();
+    let (cli_window, hosted_entry, entry_args, manifest_dir) = if let Some(embedded) = embedded {
+        let dir = match bundle::extract(&embedded) {
+            Ok(dir) => dir,
+            Err(e) => {
+                eprintln!("[Main] Failed to unpack the embedded app: {e}");
+                return 1;
+            }
+        };
+        assets::init(dir.join("assets"));
+        let entry = dir.join(bundle::ENTRY_PATH);
+        (
+            WindowOptions::default(),
+            Some(entry.to_string_lossy().into_owned()),
+            args,
+            Some(dir),
+        )
+    } else {
+        if args.first().map(String::as_str) == Some("build") {
+            return bundle::run_build(&args[1..]);
+        }
+        #[cfg(feature = "test-support")]
+        if args.first().map(String::as_str) == Some("golden") {
+            return test_support::run_golden(&args[1..]);
+        }
+        let parsed = match cli::parse(args) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                eprintln!("[Main] {e}");
+                return 2;
+            }
+        };
+        let manifest_dir = parsed.entry.as_deref().map(|entry| {
+            Path::new(entry)
+                .parent()
+                .unwrap_or(Path::new(""))
+                .to_path_buf()
+        });
+        record_path = parsed.record;
+        replay_path = parsed.replay;
+        stress_plan = parsed.stress;
+        preload = parsed.preload;
+        (parsed.window, parsed.entry, parsed.entry_args, manifest_dir)
+    };
+
+    // An optional vellum.toml beside the entry supplies window defaults (flags
+    // win), the app id and permissions for Bun, the single-instance policy,
+    // deep link schemes, native plugins, the crash dialog and the update key.
+    let mut window_options = cli_window;
+    let mut instance_lock = None;
+    let mut launch_url = None;
+    let mut bun_env = Vec::new();
+    let manifest = match manifest_dir.as_deref().map(manifest::load) {
+        Some(Err(e)) => {
+            eprintln!("[Main] Invalid app manifest {e}");
+            return 2;
+        }
+        Some(Ok(manifest)) => manifest,
+        None => None,
+    };
+    // Bun is handed the id the runtime resolved, so `system.dataDir()` and
+    // the crash reports agree on where the app's data lives.
+    let app_id = resolve_app_id(
+        manifest
+            .as_ref()
+            .and_then(|manifest| manifest.id.as_deref()),
+        hosted_entry.as_deref().map(Path::new),
+        packaged,
+    );
+    crash::set_app_id(app_id.clone());
+    bun_env.push(("VELLUM_APP_ID", app_id));
+    if let Some(dir) = &manifest_dir
+        && let Some(manifest) = manifest
+    {
+        window_options = window_options.or(manifest.window_options(dir));
+        ui::theme::set_widget_defaults(manifest.widget_defaults());
+        bun_env.extend(manifest.bun_env());
+        crash::set_show_dialog(manifest.crash_dialog);
+        if let Some(key) = &manifest.update_public_key
+            && let Ok(key) = update::parse_public_key(key)
+        {
+            update::set_public_key(key);
+        }
+        // A packaged app's manifest directory is where it was unpacked;
+        // `vellum build` ships its plugins beside the executable instead.
+        let plugin_dir = match std::env::current_exe() {
+            Ok(exe) if packaged => exe.parent().unwrap_or(Path::new("")).to_path_buf(),
+            _ => dir.clone(),
+        };
+        plugins::load_all(&manifest.plugin_paths(&plugin_dir));
+        launch_url =
+            deeplink::find_url(&entry_args, &manifest.deep_link_schemes).map(str::to_string);
+        if manifest.single_instance
+            && let Some(id) = &manifest.id
+        {
+            match manifest::claim_instance(id, launch_url.as_deref()) {
+                Ok(Some(lock)) => instance_lock = Some(lock),
+                Ok(None) => {
+                    println!("[Main] {id} is already running");
+                    return 0;
+                }
+                Err(e) => eprintln!("[Main] Could not claim the single-instance lock: {e}"),
+            }
+        }
+        if let Some(id) = &manifest.id {
+            // Packaged apps are relaunched as-is; hosted apps need their entry.
+            let entry = hosted_entry.as_deref().filter(|_| !packaged).map(Path::new);
+            deeplink::register(id, &manifest.deep_link_schemes, entry);
+        }
+    }
+
+    // Until a client connects and mounts its UI, the window shows a splash.
+    if replay_path.is_none() && stress_plan.is_none() {
+        window_options.splash.get_or_insert(true);
+    }
+
+    // Bun reads the locale list from its environment; right-to-left locales
+    // mirror row layouts.
+    let locales = locale::preferred_locales();
+    ui::global_state::set_right_to_left(locale::is_rtl(&locales[0]));
+    bun_env.push(("VELLUM_LOCALES", locales.join(",")));
+
+    let bun_path = match hosted_entry.as_ref().map(|_| runtime::locate_bun()) {
+        Some(None) => {
+            eprintln!("[Main] Could not find bun; install it or set VELLUM_BUN");
+            return 1;
+        }
+        Some(found) => found,
+        None => None,
+    };
+    // Hosted, this binary starts Bun and picks the socket; otherwise the
+    // client that spawned it did (see socket.rs).
+    let socket_path = if hosted_entry.is_some() {
+        socket::hosted_socket_path()
+    } else {
+        socket::check_protocol_version();
+        socket::get_socket_path()
+    };
+
+    // Phase 1: Build the EventLoop and extract EventLoopProxy (non-blocking).
+    // This must happen before spawning the JS thread so the proxy can be shared.
+    let (ui_setup, event_loop) = prepare_ui();
+
+    // Phase 2: Create IPC channels with the EventLoopProxy.
+    // JS→UI commands use EventLoopProxy (immediately wakes the event loop, zero polling).
+    // UI→JS events use mpsc channels.
+    let channels = IpcChannels::new(ui_setup.proxy, ui_setup.window_id);
+
+    let ui_channels = channels.ui;
+    let js_channels = channels.ipc_server;
+    let supervisor_commands = js_channels.command_sender.clone();
+    let bridge_commands = js_channels.command_sender.clone();
+    let accept_reconnects = hosted_entry.is_some();
+    let server_socket_path = socket_path.clone();
+
+    // Deep links: the one this launch was opened with is queued for the first
+    // client; later ones arrive through the single-instance socket.
+    if let Some(url) = launch_url {
+        let _ = ui_channels.event_sender.send(UiEvent::OpenUrl { url });
+    }
+    if let Some(lock) = &instance_lock {
+        lock.listen(ui_channels.event_sender.clone());
+    }
+    power::start_monitor(ui_channels.event_sender.clone());
+    #[cfg(target_os = "macos")]
+    macos::install(
+        ui_channels.event_sender.clone(),
+        supervisor_commands.clone(),
+    );
+
+    if let Some(path) = &record_path
+        && let Err(e) = ipc::session::start_recording(path)
+    {
+        eprintln!("[Main] Cannot record to {}: {e}", path.display());
+        return 1;
+    }
+
+    // Phase 3: Spawn the IPC server thread with EventLoopProxy-based command sender.
+    // A replay or stress run stands in for the server and plays the client's part.
+    let ipc_server_handle = thread::Builder::new()
+        .name("ipc-server".to_string())
+        .spawn(move || {
+            crash::guard(|| {
+                println!("[Main] IPC server thread started");
+                match (replay_path, stress_plan) {
+                    (Some(path), _) => ipc::session::run_replay(js_channels, &path),
+                    (None, Some(plan)) => stress::run_stress(js_channels, &plan),
+                    (None, None) => {
+                        run_ipc_server(js_channels, server_socket_path, accept_reconnects)
+                    }
+                }
+                println!("[Main] IPC server thread finished");
+            })
+        })
+        .unwrap_or_else(|e| panic!("Fatal: failed to spawn IPC server thread: {e}"));
+
+    // Phase 3b: In hosted mode, launch and supervise the Bun process.
+    let supervisor = match (hosted_entry, bun_path) {
+        (Some(entry), Some(bun)) => {
+            match BunSupervisor::start(
+                bun,
+                PathBuf::from(entry),
+                preload,
+                entry_args,
+                socket_path,
+                bun_env,
+                supervisor_commands,
+            ) {
+                Ok(supervisor) => Some(supervisor),
+                Err(e) => {
+                    eprintln!("[Main] Failed to start the Bun supervisor: {e}");
+                    return 1;
+                }
+            }
+        }
+        _ => None,
+    };
+
+    // Phase 4: Run the UI event loop on the main thread (blocks forever).
+    // The main thread MUST run the UI due to platform requirements (macOS, etc.).
+    println!("[Main] Starting UI on main thread");
+    crash::guard(|| {
+        run_ui_blocking(
+            event_loop,
+            ui_setup.window_id,
+            ui_channels.event_sender,
+            &window_options,
+        )
+    });
+
+    // Monitor threads keep UI event senders alive, so tell the bridge directly.
+    bridge_commands.close();
+
+    if let Some(supervisor) = supervisor {
+        println!("[Main] UI closed, stopping Bun...");
+        supervisor.shutdown();
+    }
+
+    // Wait for the IPC server thread to finish after the UI closes
+    println!("[Main] UI closed, waiting for IPC server thread to finish...");
+    if let Err(e) = ipc_server_handle.join() {
+        eprintln!("[Main] IPC server thread panicked: {:?}", e);
+    }
+
+    drop(instance_lock);
+    update::relaunch_if_requested();
+    let code = ui::global_state::exit_code();
+    println!("[Main] Vellum shutdown complete (exit code {code})");
+    code
+}
+
+#[cfg(test)]
+mod tests {
+    use masonry::core::{NewWidget, Widget};
+    use masonry::widgets::Label;
+
+    use super::*;
+    use crate::ui::widget_factory::{CreateContext, factory_for};
+
+    struct Chart;
+
+    impl WidgetFactory for Chart {
+        fn create(&self, _ctx: &CreateContext<'_>) -> NewWidget<dyn Widget> {
+            NewWidget::new(Label::new("chart")).erased()
+        }
+    }
+
+    #[test]
+    fn test_extensions_register_on_install() {
+        let extensions = Extensions::new().widget_factory("test-embedded-chart", Chart);
+        assert!(factory_for("test-embedded-chart").is_none());
+
+        extensions.install();
+        assert!(factory_for("test-embedded-chart").is_some());
+    }
+}
@@ -0,0 +1,86 @@
+// App Assets
+// Resolves `asset://` URLs against the app's asset directory: `assets/` next to
+// the executable, or the assets unpacked from a packaged app (see bundle.rs).
+
+use std::path::{Component, Path, PathBuf};
+use std::sync::OnceLock;
+
+pub const SCHEME: &str = "asset://";
+
+static ASSET_ROOT: OnceLock<PathBuf> = OnceLock::new();
+
+/// Use `root` for all asset lookups. Called once at startup for packaged apps.
+pub fn init(root: PathBuf) {
+    if ASSET_ROOT.set(root).is_err() {
+        eprintln!("[Assets] Asset root already initialized; ignoring");
+    }
+}
+
+/// `VELLUM_ASSETS_DIR`, else the root set by `init`, else `assets/` beside the binary.
+pub fn root() -> PathBuf {
+    if let Some(dir) = std::env::var_os("VELLUM_ASSETS_DIR") {
+        return PathBuf::from(dir);
+    }
+    ASSET_ROOT
+        .get_or_init(|| {
+            std::env::current_exe()
+                .ok()
+                .and_then(|exe| exe.parent().map(|dir| dir.join("assets")))
+                .unwrap_or_else(|| PathBuf::from("assets"))
+        })
+        .clone()
+}
+
+/// Map `asset://images/logo.png` to a path under `root`. Returns None for other
+/// schemes and for paths that would escape the asset directory.
+fn resolve_in(root: &Path, url: &str) -> Option<PathBuf> {
+    let relative = Path::new(url.strip_prefix(SCHEME)?);
+    if relative
+        .components()
+        .any(|c| !matches!(c, Component::Normal(_)))
+    {
+        eprintln!("[Assets] Rejecting asset URL outside the asset directory: {url}");
+        return None;
+    }
+    Some(root.join(relative))
+}
+
+pub fn resolve(url: &str) -> Option<PathBuf> {
+    resolve_in(&root(), url)
+}
+
+/// Read an asset's bytes, logging why it could not be loaded.
+pub fn load(url: &str) -> Option<Vec<u8>> {
+    let path = resolve(url)?;
+    match std::fs::read(&path) {
+        Ok(bytes) => Some(bytes),
+        Err(e) => {
+            eprintln!("[Assets] Failed to read {url} ({}): {e}", path.display());
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_in_root() {
+        let root = Path::new("/opt/app/assets");
+        assert_eq!(
+            resolve_in(root, "asset://images/logo.png"),
+            Some(root.join("images").join("logo.png"))
+        );
+        assert_eq!(resolve_in(root, "https://example.com/a.png"), None);
+        assert_eq!(resolve_in(root, "images/logo.png"), None);
+    }
+
+    #[test]
+    fn test_resolve_rejects_escapes() {
+        let root = Path::new("/opt/app/assets");
+        assert_eq!(resolve_in(root, "asset://../secret.txt"), None);
+        assert_eq!(resolve_in(root, "asset:///etc/passwd"), None);
+        assert_eq!(resolve_in(root, "asset://images/../../x"), None);
+    }
+}
@@ -0,0 +1,223 @@
+// Audio Output
+// Lists the audio outputs GStreamer's device monitor sees, picks the one
+// videos play through (`SetAudioOutput`), and reports `DefaultDeviceChanged`
+// when the system default moves (headphones plugged in, a Bluetooth speaker
+// connecting). Probing devices means PulseAudio/PipeWire round trips, so it
+// happens on worker threads, and the monitor only starts with the first audio
+// command; apps that never ask don't load GStreamer for it. Volume and mute
+// are per video widget. Without the `gstreamer`
+// feature there is no audio to route, since the ffmpeg fallback plays video
+// only, so the device list is empty.
+
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::ipc::{AudioDevice, UiEventSender};
+
+/// `AudioDevice::id` chosen with `SetAudioOutput`; None plays through the
+/// system default.
+static SELECTED: Mutex<Option<String>> = Mutex::new(None);
+/// `watch` has been called.
+static WATCHING: AtomicBool = AtomicBool::new(false);
+
+/// Sent to the driver once `select_output` switched the output, so playing
+/// videos reopen their pipelines on the new one.
+#[derive(Clone, Copy, Debug)]
+pub struct OutputChanged;
+
+pub fn selected_output() -> Option<String> {
+    SELECTED.lock().unwrap_or_else(|p| p.into_inner()).clone()
+}
+
+/// Route audio started from now on to `device`, which must be listed. Probes
+/// the devices, so call it off the UI thread.
+pub fn select_output(device: Option<String>) -> Result<(), String> {
+    if let Some(id) = &device
+        && !devices().iter().any(|listed| &listed.id == id)
+    {
+        return Err(format!("No audio output '{id}'"));
+    }
+    *SELECTED.lock().unwrap_or_else(|p| p.into_inner()) = device;
+    Ok(())
+}
+
+/// The new default if it differs from `previous` (by id).
+#[cfg(any(feature = "gstreamer", test))]
+fn default_changed(previous: Option<&str>, devices: &[AudioDevice]) -> Option<Option<AudioDevice>> {
+    let current = devices.iter().find(|device| device.is_default);
+    (current.map(|device| device.id.as_str()) != previous).then(|| current.cloned())
+}
+
+#[cfg(feature = "gstreamer")]
+mod backend {
+    use std::sync::OnceLock;
+
+    use gstreamer as gst;
+    use gstreamer::prelude::*;
+
+    use crate::ipc::AudioDevice;
+
+    static MONITOR: OnceLock<Option<gst::DeviceMonitor>> = OnceLock::new();
+
+    pub fn monitor() -> Option<&'static gst::DeviceMonitor> {
+        MONITOR
+            .get_or_init(|| {
+                if let Err(e) = gst::init() {
+                    eprintln!("[Audio] GStreamer unavailable ({e}); no audio devices");
+                    return None;
+                }
+                let monitor = gst::DeviceMonitor::new();
+                monitor.add_filter(Some("Audio/Sink"), None);
+                Some(monitor)
+            })
+            .as_ref()
+    }
+
+    /// PipeWire and PulseAudio name their nodes; other providers only have a
+    /// display name.
+    fn device_id(device: &gst::Device) -> String {
+        device
+            .properties()
+            .and_then(|props| {
+                ["node.name", "device.name", "device.id", "device.guid"]
+                    .iter()
+                    .find_map(|key| props.get::<String>(*key).ok())
+            })
+            .unwrap_or_else(|| device.display_name().to_string())
+    }
+
+    fn describe(device: &gst::Device) -> AudioDevice {
+        AudioDevice {
+            id: device_id(device),
+            name: device.display_name().to_string(),
+            is_default: device
+                .properties()
+                .and_then(|props| props.get::<bool>("is-default").ok())
+                .unwrap_or(false),
+        }
+    }
+
+    pub fn devices() -> Vec<AudioDevice> {
+        monitor()
+            .map(|monitor| monitor.devices().iter().map(describe).collect())
+            .unwrap_or_default()
+    }
+
+    /// A sink for the output with `id`, if it is still connected.
+    pub fn sink(id: &str) -> Option<gst::Element> {
+        monitor()?
+            .devices()
+            .iter()
+            .find(|device| device_id(device) == id)?
+            .create_element(None)
+            .ok()
+    }
+}
+
+#[cfg(feature = "gstreamer")]
+pub use backend::devices;
+
+#[cfg(not(feature = "gstreamer"))]
+pub fn devices() -> Vec<AudioDevice> {
+    Vec::new()
+}
+
+/// The sink a new video pipeline should play through; None for the default.
+#[cfg(feature = "gstreamer")]
+pub fn output_sink() -> Option<gstreamer::Element> {
+    let id = selected_output()?;
+    let sink = backend::sink(&id);
+    if sink.is_none() {
+        eprintln!("[Audio] Output '{id}' is gone; using the default");
+    }
+    sink
+}
+
+/// Start watching for default output changes, forwarded as
+/// `UiEvent::DefaultDeviceChanged`; later calls do nothing. Blocks while the
+/// monitor starts, so call it off the UI thread.
+pub fn watch(event_sender: &UiEventSender) {
+    if WATCHING.swap(true, Ordering::AcqRel) {
+        return;
+    }
+    #[cfg(feature = "gstreamer")]
+    {
+        use gstreamer as gst;
+
+        use crate::ipc::UiEvent;
+
+        let Some(monitor) = backend::monitor() else {
+            return;
+        };
+        if let Err(e) = monitor.start() {
+            eprintln!("[Audio] Cannot watch audio devices: {e}");
+            return;
+        }
+        let Some(bus) = monitor.bus() else {
+            return;
+        };
+        let event_sender = event_sender.clone();
+        let spawned = std::thread::Builder::new()
+            .name("audio-monitor".to_string())
+            .spawn(move || {
+                let mut current = backend::devices()
+                    .into_iter()
+                    .find(|device| device.is_default)
+                    .map(|device| device.id);
+                while bus
+                    .timed_pop_filtered(
+                        gst::ClockTime::NONE,
+                        &[
+                            gst::MessageType::DeviceAdded,
+                            gst::MessageType::DeviceRemoved,
+                            gst::MessageType::DeviceChanged,
+                        ],
+                    )
+                    .is_some()
+                {
+                    let Some(device) = default_changed(current.as_deref(), &backend::devices())
+                    else {
+                        continue;
+                    };
+                    current = device.as_ref().map(|device| device.id.clone());
+                    if event_sender
+                        .send(UiEvent::DefaultDeviceChanged { device })
+                        .is_err()
+                    {
+                        break;
+                    }
+                }
+            });
+        if let Err(e) = spawned {
+            eprintln!("[Audio] Failed to spawn the audio device monitor: {e}");
+        }
+    }
+    #[cfg(not(feature = "gstreamer"))]
+    let _ = event_sender;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn device(id: &str, is_default: bool) -> AudioDevice {
+        AudioDevice {
+            id: id.to_string(),
+            name: id.to_string(),
+            is_default,
+        }
+    }
+
+    #[test]
+    fn test_default_changed() {
+        let devices = [device("speakers", false), device("headset", true)];
+        assert_eq!(default_changed(Some("headset"), &devices), None);
+        assert_eq!(
+            default_changed(Some("speakers"), &devices),
+            Some(Some(device("headset", true)))
+        );
+        // The last output going away is a change to no default.
+        assert_eq!(default_changed(Some("headset"), &[]), Some(None));
+        assert_eq!(default_changed(None, &[]), None);
+    }
+}
@@ -0,0 +1,520 @@
+// Single-File App Bundles
+// `vellum build` appends the JS bundle and static assets to a copy of this
+// executable; at startup the archive is detected and the app runs from it.
+//
+// Layout appended after the executable:
+//   entries:  [u32 path_len][path utf-8][u64 data_len][data] ...
+//   trailer:  [u64 entries_len][MAGIC]
+//
+// `vellum build --bundle-only` writes just that archive, without a runtime in
+// front, for the auto-updater (see update.rs) to download.
+
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const MAGIC: &[u8; 8] = b"VLMBNDL1";
+const TRAILER_LEN: u64 = 16;
+
+/// Archive path of the bundled JS entry point.
+pub const ENTRY_PATH: &str = "main.js";
+/// Archive prefix for files copied from `--assets`.
+pub const ASSET_PREFIX: &str = "assets/";
+
+/// Files embedded in an executable, keyed by archive path.
+#[derive(Debug, Default)]
+pub struct Bundle {
+    entries: HashMap<String, Vec<u8>>,
+}
+
+impl Bundle {
+    pub fn get(&self, path: &str) -> Option<&[u8]> {
+        self.entries.get(path).map(Vec::as_slice)
+    }
+
+    /// Serialize the entries (sorted, so builds are reproducible) plus trailer.
+    fn encode(&self) -> Vec<u8> {
+        let mut paths: Vec<&String> = self.entries.keys().collect();
+        paths.sort();
+        let mut out = Vec::new();
+        for path in paths {
+            let data = &self.entries[path];
+            out.extend_from_slice(&(path.len() as u32).to_le_bytes());
+            out.extend_from_slice(path.as_bytes());
+            out.extend_from_slice(&(data.len() as u64).to_le_bytes());
+            out.extend_from_slice(data);
+        }
+        let entries_len = out.len() as u64;
+        out.extend_from_slice(&entries_len.to_le_bytes());
+        out.extend_from_slice(MAGIC);
+        out
+    }
+
+    fn decode(mut bytes: &[u8]) -> io::Result<Self> {
+        fn take<'a>(bytes: &mut &'a [u8], len: usize) -> io::Result<&'a [u8]> {
+            if bytes.len() < len {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "truncated app bundle",
+                ));
+            }
+            let (head, tail) = bytes.split_at(len);
+            *bytes = tail;
+            Ok(head)
+        }
+
+        let mut entries = HashMap::new();
+        while !bytes.is_empty() {
+            let path_len = u32::from_le_bytes(take(&mut bytes, 4)?.try_into().unwrap_or_default());
+            let path = String::from_utf8(take(&mut bytes, path_len as usize)?.to_vec())
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            let data_len = u64::from_le_bytes(take(&mut bytes, 8)?.try_into().unwrap_or_default());
+            let data = take(&mut bytes, data_len as usize)?.to_vec();
+            entries.insert(path, data);
+        }
+        Ok(Self { entries })
+    }
+}
+
+/// Read the bundle appended to `exe`, if there is one.
+pub fn read_appended(exe: &Path) -> io::Result<Option<Bundle>> {
+    let mut file = File::open(exe)?;
+    let file_len = file.metadata()?.len();
+    if file_len < TRAILER_LEN {
+        return Ok(None);
+    }
+    let mut trailer = [0u8; TRAILER_LEN as usize];
+    file.seek(SeekFrom::End(-(TRAILER_LEN as i64)))?;
+    file.read_exact(&mut trailer)?;
+    if &trailer[8..] != MAGIC {
+        return Ok(None);
+    }
+    let entries_len = u64::from_le_bytes(trailer[..8].try_into().unwrap_or_default());
+    if entries_len > file_len - TRAILER_LEN {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "app bundle is larger than the executable",
+        ));
+    }
+    file.seek(SeekFrom::End(-((TRAILER_LEN + entries_len) as i64)))?;
+    let mut bytes = vec![0u8; entries_len as usize];
+    file.read_exact(&mut bytes)?;
+    Bundle::decode(&bytes).map(Some)
+}
+
+/// Split an executable image into the bare runtime and the archive appended
+/// to it (trailer included), if there is one.
+pub fn split_appended(image: &[u8]) -> io::Result<(&[u8], Option<&[u8]>)> {
+    let len = image.len() as u64;
+    if len < TRAILER_LEN || &image[image.len() - MAGIC.len()..] != MAGIC {
+        return Ok((image, None));
+    }
+    let trailer = &image[image.len() - TRAILER_LEN as usize..];
+    let entries_len = u64::from_le_bytes(trailer[..8].try_into().unwrap_or_default());
+    if entries_len > len - TRAILER_LEN {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "app bundle is larger than the executable",
+        ));
+    }
+    let start = (len - TRAILER_LEN - entries_len) as usize;
+    Ok((&image[..start], Some(&image[start..])))
+}
+
+/// Decode an archive written by `vellum build --bundle-only`.
+pub fn read_archive(bytes: &[u8]) -> io::Result<Bundle> {
+    let (runtime, Some(archive)) = split_appended(bytes)? else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "not an app bundle",
+        ));
+    };
+    if !runtime.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "expected a bare app bundle, not an executable",
+        ));
+    }
+    let bundle = Bundle::decode(&archive[..archive.len() - TRAILER_LEN as usize])?;
+    if bundle.get(ENTRY_PATH).is_none() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "app bundle has no main.js",
+        ));
+    }
+    Ok(bundle)
+}
+
+/// The bundle embedded in the running executable, if any.
+pub fn read_embedded() -> Option<Bundle> {
+    let exe = std::env::current_exe().ok()?;
+    match read_appended(&exe) {
+        Ok(bundle) => bundle,
+        Err(e) => {
+            eprintln!(
+                "[Bundle] Ignoring corrupt app bundle in {}: {e}",
+                exe.display()
+            );
+            None
+        }
+    }
+}
+
+/// The user's cache directory: `$XDG_CACHE_HOME` (`~/.cache`),
+/// `~/Library/Caches` or `%LOCALAPPDATA%`.
+fn user_cache_dir() -> Option<PathBuf> {
+    let home = std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .map(PathBuf::from);
+    if cfg!(windows) {
+        std::env::var_os("LOCALAPPDATA")
+            .map(PathBuf::from)
+            .or_else(|| home.map(|h| h.join("AppData").join("Local")))
+    } else if cfg!(target_os = "macos") {
+        home.map(|h| h.join("Library").join("Caches"))
+    } else {
+        std::env::var_os("XDG_CACHE_HOME")
+            .map(PathBuf::from)
+            .or_else(|| home.map(|h| h.join(".cache")))
+    }
+}
+
+/// Create `dir` (and missing parents) readable by the current user only.
+fn create_private_dir(dir: &Path) -> io::Result<()> {
+    let mut builder = fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+    builder.create(dir)
+}
+
+/// Fail unless `dir` is a real directory that only the current user can
+/// write to, so nobody else can swap the files Bun is about to run.
+fn check_private_dir(dir: &Path) -> io::Result<()> {
+    let meta = fs::symlink_metadata(dir)?;
+    if !meta.is_dir() {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!("{} is not a directory", dir.display()),
+        ));
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        // SAFETY: geteuid has no preconditions and cannot fail.
+        let uid = unsafe { libc::geteuid() };
+        if meta.uid() != uid || meta.mode() & 0o022 != 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                format!(
+                    "{} is not owned by and private to the current user",
+                    dir.display()
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Replace `path` with `data`. The file is written beside it under a fresh
+/// name and renamed over it, so a symlink already at `path` is replaced
+/// rather than followed, and a concurrent launch never sees half a file.
+fn replace_file(path: &Path, data: &[u8]) -> io::Result<()> {
+    let mut temp = path.as_os_str().to_owned();
+    temp.push(format!(".{}.tmp", std::process::id()));
+    let temp = PathBuf::from(temp);
+    let _ = fs::remove_file(&temp);
+    let written = fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&temp)
+        .and_then(|mut file| file.write_all(data));
+    if let Err(e) = written.and_then(|()| fs::rename(&temp, path)) {
+        let _ = fs::remove_file(&temp);
+        return Err(e);
+    }
+    Ok(())
+}
+
+/// Unpack every entry into a per-build directory under the user's cache
+/// directory (`main.js` for Bun, `assets/` as the asset root) and return that
+/// directory. The directory must belong to the current user and be closed to
+/// everyone else, and every file is rewritten on each launch rather than
+/// trusted because it is already there.
+pub fn extract(bundle: &Bundle) -> io::Result<PathBuf> {
+    if bundle.get(ENTRY_PATH).is_none() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "app bundle has no main.js",
+        ));
+    }
+    let encoded = bundle.encode();
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    std::hash::Hash::hash(&encoded, &mut hasher);
+    let cache = user_cache_dir()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no home directory"))?;
+    let dir = cache
+        .join("vellum")
+        .join(format!("app-{:016x}", std::hash::Hasher::finish(&hasher)));
+    create_private_dir(&dir)?;
+    check_private_dir(&dir)?;
+    for (name, data) in &bundle.entries {
+        let relative = Path::new(name);
+        if relative
+            .components()
+            .any(|c| !matches!(c, std::path::Component::Normal(_)))
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("app bundle entry escapes its directory: {name}"),
+            ));
+        }
+        let path = dir.join(relative);
+        if let Some(parent) = path.parent() {
+            create_private_dir(parent)?;
+            check_private_dir(parent)?;
+        }
+        replace_file(&path, data)?;
+    }
+    Ok(dir)
+}
+
+fn collect_assets(
+    root: &Path,
+    dir: &Path,
+    entries: &mut HashMap<String, Vec<u8>>,
+) -> io::Result<()> {
+    for item in fs::read_dir(dir)? {
+        let path = item?.path();
+        if path.is_dir() {
+            collect_assets(root, &path, entries)?;
+        } else {
+            let relative = path
+                .strip_prefix(root)
+                .unwrap_or(&path)
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            entries.insert(format!("{ASSET_PREFIX}{relative}"), fs::read(&path)?);
+        }
+    }
+    Ok(())
+}
+
+/// `vellum build <entry> [--assets DIR] [-o OUT] [--bundle-only]`
+///
+/// Bundles `entry` with `bun build --target bun` and writes a copy of this
+/// executable with the bundle, assets and `vellum.toml` (if present beside the
+/// entry) appended, and the manifest's plugins copied beside it; with
+/// `--bundle-only`, just the archive (`<entry>.vlm`).
+/// Returns the process exit code.
+pub fn run_build(args: &[String]) -> i32 {
+    let mut entry = None;
+    let mut assets = None;
+    let mut output = None;
+    let mut bundle_only = false;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--assets" => assets = iter.next().map(PathBuf::from),
+            "--bundle-only" => bundle_only = true,
+            "-o" | "--output" => output = iter.next().map(PathBuf::from),
+            other if entry.is_none() && !other.starts_with('-') => {
+                entry = Some(PathBuf::from(other))
+            }
+            other => {
+                eprintln!("[Build] Unexpected argument '{other}'");
+                return 2;
+            }
+        }
+    }
+    let Some(entry) = entry else {
+        eprintln!("usage: vellum build <entry> [--assets DIR] [-o OUT] [--bundle-only]");
+        return 2;
+    };
+    let output = output.unwrap_or_else(|| {
+        let stem = entry.file_stem().unwrap_or_default().to_string_lossy();
+        let suffix = if bundle_only {
+            ".vlm"
+        } else {
+            std::env::consts::EXE_SUFFIX
+        };
+        PathBuf::from(format!("{stem}{suffix}"))
+    });
+
+    match build(&entry, assets.as_deref(), &output, bundle_only) {
+        Ok(()) => {
+            println!("[Build] Wrote {}", output.display());
+            0
+        }
+        Err(e) => {
+            eprintln!("[Build] {e}");
+            1
+        }
+    }
+}
+
+fn build(entry: &Path, assets: Option<&Path>, output: &Path, bundle_only: bool) -> io::Result<()> {
+    let bun = crate::runtime::locate_bun().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            "could not find bun; set VELLUM_BUN",
+        )
+    })?;
+    // Validate the manifest up front; it ships beside main.js in the bundle.
+    let entry_dir = entry.parent().unwrap_or(Path::new(""));
+    let manifest = crate::manifest::load(entry_dir)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    let has_manifest = manifest.is_some();
+    let plugins = manifest.map_or_else(Vec::new, |manifest| manifest.plugins);
+    if let Some(missing) = plugins
+        .iter()
+        .find(|plugin| !entry_dir.join(plugin).is_file())
+    {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("plugin {missing} is listed in the manifest but not built"),
+        ));
+    }
+    let staging = std::env::temp_dir().join(format!("vellum-build-{}", std::process::id()));
+    fs::create_dir_all(&staging)?;
+    let bundled = staging.join(ENTRY_PATH);
+    let status = Command::new(bun)
+        .arg("build")
+        .arg(entry)
+        .args(["--target", "bun", "--outfile"])
+        .arg(&bundled)
+        .status()?;
+    if !status.success() {
+        return Err(io::Error::other(format!("bun build failed ({status})")));
+    }
+
+    let mut entries = HashMap::new();
+    entries.insert(ENTRY_PATH.to_string(), fs::read(&bundled)?);
+    let _ = fs::remove_dir_all(&staging);
+    if has_manifest {
+        entries.insert(
+            crate::manifest::FILE_NAME.to_string(),
+            fs::read(entry_dir.join(crate::manifest::FILE_NAME))?,
+        );
+    }
+    if let Some(assets) = assets {
+        collect_assets(assets, assets, &mut entries)?;
+    }
+
+    if bundle_only {
+        return fs::write(output, Bundle { entries }.encode());
+    }
+
+    // Start from the bare runtime, even if this binary is itself a packaged app.
+    let exe = std::env::current_exe()?;
+    let mut runtime = fs::read(&exe)?;
+    if let Some(existing) = read_appended(&exe)? {
+        runtime.truncate(runtime.len() - existing.encode().len());
+    }
+    runtime.extend_from_slice(&Bundle { entries }.encode());
+
+    let mut file = File::create(output)?;
+    file.write_all(&runtime)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(fs::Permissions::from_mode(0o755))?;
+    }
+    // Plugins are loaded from beside the executable, never from the
+    // unpacked bundle (see app.rs).
+    let out_dir = output.parent().unwrap_or(Path::new(""));
+    for plugin in &plugins {
+        let target = out_dir.join(plugin);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(entry_dir.join(plugin), &target)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_appended_bundle_round_trip() {
+        let bundle = Bundle {
+            entries: HashMap::from([
+                (ENTRY_PATH.to_string(), b"console.log(1)".to_vec()),
+                ("assets/logo.png".to_string(), vec![0x89, b'P', b'N', b'G']),
+            ]),
+        };
+        let path = std::env::temp_dir().join(format!("vellum-bundle-test-{}", std::process::id()));
+        let mut bytes = b"\x7fELF fake executable".to_vec();
+        bytes.extend_from_slice(&bundle.encode());
+        fs::write(&path, bytes).unwrap();
+
+        let read = read_appended(&path).unwrap().unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(read.get(ENTRY_PATH), Some(&b"console.log(1)"[..]));
+        assert_eq!(read.get("assets/logo.png").map(<[u8]>::len), Some(4));
+        assert!(read.get("missing").is_none());
+    }
+
+    #[test]
+    fn test_plain_executable_has_no_bundle() {
+        let path = std::env::temp_dir().join(format!("vellum-plain-test-{}", std::process::id()));
+        fs::write(&path, b"just a regular binary without a trailer").unwrap();
+        let read = read_appended(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert!(read.is_none());
+    }
+
+    #[test]
+    fn test_truncated_bundle_is_rejected() {
+        let encoded = Bundle {
+            entries: HashMap::from([(ENTRY_PATH.to_string(), vec![1, 2, 3])]),
+        }
+        .encode();
+        let entries = &encoded[..encoded.len() - TRAILER_LEN as usize];
+        assert!(Bundle::decode(entries).is_ok());
+        assert!(Bundle::decode(&entries[..entries.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn test_split_appended_finds_the_archive() {
+        let encoded = Bundle {
+            entries: HashMap::from([(ENTRY_PATH.to_string(), vec![1, 2, 3])]),
+        }
+        .encode();
+        let mut image = b"runtime".to_vec();
+        image.extend_from_slice(&encoded);
+        let (runtime, archive) = split_appended(&image).unwrap();
+        assert_eq!(runtime, b"runtime");
+        assert_eq!(archive, Some(&encoded[..]));
+        assert_eq!(split_appended(b"runtime").unwrap(), (&b"runtime"[..], None));
+
+        assert!(read_archive(&encoded).is_ok());
+        assert!(read_archive(&image).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_shared_directories_are_refused() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("vellum-private-test-{}", std::process::id()));
+        create_private_dir(&dir.join("assets")).unwrap();
+        assert!(check_private_dir(&dir).is_ok());
+
+        let planted = dir.join("assets").join("logo.png");
+        std::os::unix::fs::symlink(dir.join("elsewhere"), &planted).unwrap();
+        replace_file(&planted, b"png").unwrap();
+        assert!(!fs::symlink_metadata(&planted).unwrap().is_symlink());
+        assert!(!dir.join("elsewhere").exists());
+
+        fs::set_permissions(&dir, fs::Permissions::from_mode(0o777)).unwrap();
+        assert!(check_private_dir(&dir).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
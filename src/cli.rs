@@ -0,0 +1,321 @@
+// Command-Line Arguments
+// `vellum [window flags] [entry [app args...]]` — flags before the entry
+// configure the shell, everything after it is passed through to the app.
+
+use std::path::PathBuf;
+
+use crate::ipc::ColorValue;
+use crate::stress::{self, Step};
+
+/// Window configuration overrides; `None` keeps the built-in default.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WindowOptions {
+    pub title: Option<String>,
+    pub width: Option<f64>,
+    pub height: Option<f64>,
+    pub min_size: Option<(f64, f64)>,
+    pub resizable: Option<bool>,
+    pub fullscreen: Option<bool>,
+    /// Create the window with an alpha channel so the root's `backgroundMode`
+    /// can let the desktop show through.
+    pub transparent: Option<bool>,
+    pub icon: Option<PathBuf>,
+    /// Clear color painted from the first frame, and the default fill of a
+    /// solid `__root__`.
+    pub background: Option<ColorValue>,
+    /// Show the app title and a spinner until the first widget is mounted
+    /// (on by default when serving a client).
+    pub splash: Option<bool>,
+    /// Point-of-sale and signage deployments: fullscreen without decorations
+    /// or window buttons, closing only through the app, screensaver off.
+    pub kiosk: Option<bool>,
+}
+
+impl WindowOptions {
+    /// Fill every unset option from `defaults` (e.g. the app manifest).
+    pub fn or(self, defaults: WindowOptions) -> WindowOptions {
+        WindowOptions {
+            title: self.title.or(defaults.title),
+            width: self.width.or(defaults.width),
+            height: self.height.or(defaults.height),
+            min_size: self.min_size.or(defaults.min_size),
+            resizable: self.resizable.or(defaults.resizable),
+            fullscreen: self.fullscreen.or(defaults.fullscreen),
+            transparent: self.transparent.or(defaults.transparent),
+            icon: self.icon.or(defaults.icon),
+            background: self.background.or(defaults.background),
+            splash: self.splash.or(defaults.splash),
+            kiosk: self.kiosk.or(defaults.kiosk),
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct CliArgs {
+    pub window: WindowOptions,
+    pub entry: Option<String>,
+    pub entry_args: Vec<String>,
+    /// Scripts Bun evaluates before the entry, in order (`--preload`)
+    pub preload: Vec<PathBuf>,
+    /// Log client messages to this file (`--record`)
+    pub record: Option<PathBuf>,
+    /// Play a recording back instead of serving a client (`--replay`)
+    pub replay: Option<PathBuf>,
+    /// Drive the UI with generated commands instead of serving a client (`--stress`)
+    pub stress: Option<Vec<Step>>,
+}
+
+pub const USAGE: &str = "usage: vellum [--title T] [--width W] [--height H] [--min-size WxH] \
+[--resizable=BOOL] [--fullscreen] [--transparent] [--icon PATH] [--background COLOR] [--splash] \
+[--kiosk] [--preload FILE]... [--record FILE] [entry [--] [args...]]
+       vellum [window flags] --replay FILE
+       vellum [window flags] --stress create:N[,update:N][,remove:N]
+       vellum build <entry> [--assets DIR] [-o OUT]";
+
+fn parse_number(flag: &str, value: &str) -> Result<f64, String> {
+    value
+        .parse::<f64>()
+        .ok()
+        .filter(|v| v.is_finite() && *v > 0.0)
+        .ok_or_else(|| format!("{flag} expects a positive number, got '{value}'"))
+}
+
+fn parse_size(flag: &str, value: &str) -> Result<(f64, f64), String> {
+    let (w, h) = value
+        .split_once(['x', 'X'])
+        .ok_or_else(|| format!("{flag} expects WIDTHxHEIGHT, got '{value}'"))?;
+    Ok((parse_number(flag, w)?, parse_number(flag, h)?))
+}
+
+fn parse_bool(flag: &str, value: &str) -> Result<bool, String> {
+    match value {
+        "true" | "1" | "yes" => Ok(true),
+        "false" | "0" | "no" => Ok(false),
+        _ => Err(format!("{flag} expects true or false, got '{value}'")),
+    }
+}
+
+/// Parse everything after the program name.
+pub fn parse(args: impl IntoIterator<Item = String>) -> Result<CliArgs, String> {
+    let mut parsed = CliArgs::default();
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        if !arg.starts_with("--") {
+            parsed.entry = Some(arg);
+            parsed.entry_args = args.collect();
+            // `entry -- args` keeps the app's own flags apart from ours.
+            if parsed.entry_args.first().is_some_and(|arg| arg == "--") {
+                parsed.entry_args.remove(0);
+            }
+            break;
+        }
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) => (flag.to_string(), Some(value.to_string())),
+            None => (arg.clone(), None),
+        };
+        let mut value = || {
+            inline
+                .clone()
+                .or_else(|| args.next())
+                .ok_or_else(|| format!("{flag} expects a value"))
+        };
+        let window = &mut parsed.window;
+        match flag.as_str() {
+            "--title" => window.title = Some(value()?),
+            "--width" => window.width = Some(parse_number(&flag, &value()?)?),
+            "--height" => window.height = Some(parse_number(&flag, &value()?)?),
+            "--min-size" => window.min_size = Some(parse_size(&flag, &value()?)?),
+            "--icon" => window.icon = Some(PathBuf::from(value()?)),
+            "--background" => {
+                let color = value()?;
+                window.background = Some(
+                    ColorValue::parse(&color)
+                        .ok_or_else(|| format!("{flag} expects a color, got '{color}'"))?,
+                )
+            }
+            "--preload" => parsed.preload.push(PathBuf::from(value()?)),
+            "--record" => parsed.record = Some(PathBuf::from(value()?)),
+            "--replay" => parsed.replay = Some(PathBuf::from(value()?)),
+            "--stress" => parsed.stress = Some(stress::parse_plan(&value()?)?),
+            // Boolean flags only take inline values so a following entry isn't consumed.
+            "--resizable" => {
+                window.resizable = Some(match &inline {
+                    Some(v) => parse_bool(&flag, v)?,
+                    None => true,
+                })
+            }
+            "--fullscreen" => {
+                window.fullscreen = Some(match &inline {
+                    Some(v) => parse_bool(&flag, v)?,
+                    None => true,
+                })
+            }
+            "--transparent" => {
+                window.transparent = Some(match &inline {
+                    Some(v) => parse_bool(&flag, v)?,
+                    None => true,
+                })
+            }
+            "--splash" => {
+                window.splash = Some(match &inline {
+                    Some(v) => parse_bool(&flag, v)?,
+                    None => true,
+                })
+            }
+            "--kiosk" => {
+                window.kiosk = Some(match &inline {
+                    Some(v) => parse_bool(&flag, v)?,
+                    None => true,
+                })
+            }
+            _ => return Err(format!("unknown option '{flag}'\n{USAGE}")),
+        }
+    }
+    if !parsed.preload.is_empty() && parsed.entry.is_none() {
+        return Err("--preload runs scripts before an entry; give one".into());
+    }
+    if parsed.replay.is_some() && (parsed.entry.is_some() || parsed.record.is_some()) {
+        return Err("--replay runs without an entry and cannot be combined with --record".into());
+    }
+    if parsed.stress.is_some()
+        && (parsed.entry.is_some() || parsed.record.is_some() || parsed.replay.is_some())
+    {
+        return Err(
+            "--stress runs without an entry and cannot be combined with --record or --replay"
+                .into(),
+        );
+    }
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_window_flags_and_entry() {
+        let parsed = parse(args(&[
+            "--title",
+            "Notes",
+            "--width=1024",
+            "--height",
+            "768",
+            "--min-size",
+            "320x240",
+            "--resizable=false",
+            "--fullscreen",
+            "--transparent",
+            "--icon",
+            "icon.png",
+            "--background",
+            "#1e1e2e",
+            "--splash",
+            "app.ts",
+            "--verbose",
+        ]))
+        .unwrap();
+        assert_eq!(
+            parsed.window,
+            WindowOptions {
+                title: Some("Notes".to_string()),
+                width: Some(1024.0),
+                height: Some(768.0),
+                min_size: Some((320.0, 240.0)),
+                resizable: Some(false),
+                fullscreen: Some(true),
+                transparent: Some(true),
+                icon: Some(PathBuf::from("icon.png")),
+                background: ColorValue::parse("#1e1e2e"),
+                splash: Some(true),
+                kiosk: None,
+            }
+        );
+        assert_eq!(parsed.entry.as_deref(), Some("app.ts"));
+        // Flags after the entry belong to the app.
+        assert_eq!(parsed.entry_args, args(&["--verbose"]));
+    }
+
+    #[test]
+    fn test_no_arguments() {
+        assert_eq!(parse(Vec::new()).unwrap(), CliArgs::default());
+    }
+
+    #[test]
+    fn test_flags_override_defaults() {
+        let flags = parse(args(&["--title", "Flag", "--fullscreen=false"])).unwrap();
+        let merged = flags.window.or(WindowOptions {
+            title: Some("Manifest".to_string()),
+            width: Some(640.0),
+            fullscreen: Some(true),
+            ..WindowOptions::default()
+        });
+        assert_eq!(merged.title.as_deref(), Some("Flag"));
+        assert_eq!(merged.width, Some(640.0));
+        assert_eq!(merged.fullscreen, Some(false));
+    }
+
+    #[test]
+    fn test_preload_and_app_arguments() {
+        let parsed = parse(args(&[
+            "--preload",
+            "polyfills.ts",
+            "--preload=telemetry.ts",
+            "app.ts",
+            "--",
+            "--verbose",
+            "--",
+        ]))
+        .unwrap();
+        assert_eq!(
+            parsed.preload,
+            [PathBuf::from("polyfills.ts"), PathBuf::from("telemetry.ts")]
+        );
+        // Only the separator right after the entry is dropped.
+        assert_eq!(parsed.entry_args, args(&["--verbose", "--"]));
+    }
+
+    #[test]
+    fn test_kiosk_flag() {
+        let parsed = parse(args(&["--kiosk", "pos.ts"])).unwrap();
+        assert_eq!(parsed.window.kiosk, Some(true));
+        assert_eq!(parsed.entry.as_deref(), Some("pos.ts"));
+        let parsed = parse(args(&["--kiosk=false"])).unwrap();
+        assert_eq!(parsed.window.kiosk, Some(false));
+    }
+
+    #[test]
+    fn test_record_and_replay() {
+        let parsed = parse(args(&["--record", "bug.mpk", "app.ts"])).unwrap();
+        assert_eq!(parsed.record, Some(PathBuf::from("bug.mpk")));
+        assert_eq!(parsed.entry.as_deref(), Some("app.ts"));
+
+        let parsed = parse(args(&["--width", "400", "--replay=bug.mpk"])).unwrap();
+        assert_eq!(parsed.replay, Some(PathBuf::from("bug.mpk")));
+        assert!(parse(args(&["--replay", "bug.mpk", "app.ts"])).is_err());
+        assert!(parse(args(&["--record", "a.mpk", "--replay", "b.mpk"])).is_err());
+    }
+
+    #[test]
+    fn test_stress_plan() {
+        let parsed = parse(args(&["--stress", "create:100,remove:100"])).unwrap();
+        assert_eq!(parsed.stress.map(|plan| plan.len()), Some(2));
+        assert!(parse(args(&["--stress=create:100", "app.ts"])).is_err());
+        assert!(parse(args(&["--stress", "create:100", "--replay", "b.mpk"])).is_err());
+        assert!(parse(args(&["--stress", "grow:100"])).is_err());
+    }
+
+    #[test]
+    fn test_invalid_values() {
+        assert!(parse(args(&["--width", "wide"])).is_err());
+        assert!(parse(args(&["--min-size", "320"])).is_err());
+        assert!(parse(args(&["--resizable=maybe"])).is_err());
+        assert!(parse(args(&["--title"])).is_err());
+        assert!(parse(args(&["--frameless"])).is_err());
+        assert!(parse(args(&["--background", "#12"])).is_err());
+    }
+}
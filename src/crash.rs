@@ -0,0 +1,335 @@
+// Crash Reports
+// A panic that takes down the runtime (one that unwinds out of the UI loop or
+// out of the IPC server thread that talks to JS) writes a report to
+// `<app data dir>/crashes/`: the panic message and location, a backtrace, the
+// widget count and the last commands the handler ran (also available to JS
+// through `DumpRecentCommands`, and appended to `ValidateTree` failures).
+// Panics that something catches further up the stack leave no report. With
+// `crash-dialog = true` in the manifest the user is also told where the report
+// went, using whatever dialog tool the platform ships (osascript,
+// zenity/kdialog, PowerShell).
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::panic::{AssertUnwindSafe, catch_unwind, resume_unwind};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::ipc::{ClientCommand, CommandRecord};
+
+/// How many of the most recent commands are kept.
+const RECENT_COMMAND_LIMIT: usize = 32;
+
+/// Ring buffer of handled commands, and the seq the next one gets.
+struct RecentCommands {
+    records: VecDeque<CommandRecord>,
+    next_seq: u64,
+}
+
+static RECENT_COMMANDS: Mutex<RecentCommands> = Mutex::new(RecentCommands {
+    records: VecDeque::new(),
+    next_seq: 0,
+});
+static WIDGET_COUNT: AtomicUsize = AtomicUsize::new(0);
+static SHOW_DIALOG: AtomicBool = AtomicBool::new(false);
+/// The app id the runtime resolved at launch (see app.rs).
+static APP_ID: Mutex<Option<String>> = Mutex::new(None);
+
+thread_local! {
+    /// The report for this thread's latest panic, written only if the panic
+    /// reaches a `guard`. The backtrace has to be captured while the panicking
+    /// frames are still on the stack, so it is rendered in the hook.
+    static PENDING_REPORT: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Note a command the handler is about to run, and the widget count before it.
+/// `timestamp` is on the clock of event timestamps.
+pub fn record_command(command: &ClientCommand, widget_count: usize, timestamp: f64) {
+    WIDGET_COUNT.store(widget_count, Ordering::Relaxed);
+    let mut recent = RECENT_COMMANDS.lock().unwrap_or_else(|p| p.into_inner());
+    let seq = recent.next_seq;
+    recent.next_seq += 1;
+    if recent.records.len() == RECENT_COMMAND_LIMIT {
+        recent.records.pop_front();
+    }
+    recent.records.push_back(CommandRecord {
+        seq,
+        name: command.name().to_string(),
+        widget_id: command.widget_id().map(str::to_string),
+        timestamp,
+    });
+}
+
+/// The recent-command log, oldest first.
+pub fn recent_commands() -> Vec<CommandRecord> {
+    let recent = RECENT_COMMANDS.lock().unwrap_or_else(|p| p.into_inner());
+    recent.records.iter().cloned().collect()
+}
+
+/// One log line: "#12 @1532.4ms SetWidgetText title".
+pub fn describe(record: &CommandRecord) -> String {
+    let mut line = format!("#{} @{:.1}ms {}", record.seq, record.timestamp, record.name);
+    if let Some(id) = &record.widget_id {
+        line.push(' ');
+        line.push_str(id);
+    }
+    line
+}
+
+/// The app id naming the data directory reports go to; Bun is given the same.
+pub fn set_app_id(id: String) {
+    *APP_ID.lock().unwrap_or_else(|p| p.into_inner()) = Some(id);
+}
+
+/// Whether a crash also shows a dialog pointing at the report.
+pub fn set_show_dialog(show: bool) {
+    SHOW_DIALOG.store(show, Ordering::Relaxed);
+}
+
+/// Chain a report-rendering hook in front of the default panic hook.
+pub fn install() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let thread = std::thread::current();
+        let location = info
+            .location()
+            .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()))
+            .unwrap_or_else(|| "unknown".to_string());
+        let message = info
+            .payload()
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| info.payload().downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "Box<dyn Any>".to_string());
+        let report = capture(thread.name().unwrap_or("<unnamed>"), &message, &location);
+        if cfg!(panic = "abort") {
+            // Nothing can catch it, and no guard will get the chance.
+            write_and_notify(&report);
+        } else {
+            stash_report(report);
+        }
+        default_hook(info);
+    }));
+}
+
+/// Run `f`, one of the runtime's thread bodies. A panic that unwinds out of it
+/// ends the runtime, so its report is written before the unwind continues.
+pub fn guard<R>(f: impl FnOnce() -> R) -> R {
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(value) => value,
+        Err(payload) => {
+            if let Some(report) = take_report() {
+                write_and_notify(&report);
+            }
+            resume_unwind(payload)
+        }
+    }
+}
+
+/// Write a report for a failure that ends the process without a panic.
+pub fn report_fatal(message: &str) {
+    let thread = std::thread::current();
+    let report = capture(thread.name().unwrap_or("<unnamed>"), message, "n/a");
+    write_and_notify(&report);
+}
+
+fn stash_report(report: String) {
+    PENDING_REPORT.with(|pending| *pending.borrow_mut() = Some(report));
+}
+
+fn take_report() -> Option<String> {
+    PENDING_REPORT.with(|pending| pending.borrow_mut().take())
+}
+
+fn capture(thread: &str, message: &str, location: &str) -> String {
+    // The panicking thread may hold the lock; report what we can without it.
+    let commands: Vec<String> = match RECENT_COMMANDS.try_lock() {
+        Ok(recent) => recent.records.iter().map(describe).collect(),
+        Err(_) => Vec::new(),
+    };
+    render_report(
+        thread,
+        message,
+        location,
+        &std::backtrace::Backtrace::force_capture().to_string(),
+        WIDGET_COUNT.load(Ordering::Relaxed),
+        &commands,
+    )
+}
+
+fn write_and_notify(report: &str) {
+    match write_report(report) {
+        Ok(path) => {
+            eprintln!("[Crash] Report written to {}", path.display());
+            if SHOW_DIALOG.load(Ordering::Relaxed) {
+                show_dialog(&path);
+            }
+        }
+        Err(e) => eprintln!("[Crash] Could not write a crash report: {e}"),
+    }
+}
+
+fn render_report(
+    thread: &str,
+    message: &str,
+    location: &str,
+    backtrace: &str,
+    widget_count: usize,
+    commands: &[String],
+) -> String {
+    let mut report = String::new();
+    let _ = writeln!(report, "Vellum {} crash report", env!("CARGO_PKG_VERSION"));
+    let _ = writeln!(report, "thread: {thread}");
+    let _ = writeln!(report, "message: {message}");
+    let _ = writeln!(report, "location: {location}");
+    let _ = writeln!(report, "widgets: {widget_count}");
+    let _ = writeln!(report, "\nrecent commands (oldest first):");
+    if commands.is_empty() {
+        let _ = writeln!(report, "  (none)");
+    }
+    for command in commands {
+        let _ = writeln!(report, "  {command}");
+    }
+    let _ = writeln!(report, "\nbacktrace:\n{backtrace}");
+    report
+}
+
+/// `<data dir>/<app id>/crashes`, the same directory as `system.dataDir()` on
+/// the JS side, since both use the id resolved at launch.
+fn crash_dir() -> Option<PathBuf> {
+    let home = std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .map(PathBuf::from);
+    let data_dir = if cfg!(windows) {
+        std::env::var_os("APPDATA")
+            .map(PathBuf::from)
+            .or_else(|| home.map(|h| h.join("AppData").join("Roaming")))
+    } else if cfg!(target_os = "macos") {
+        home.map(|h| h.join("Library").join("Application Support"))
+    } else {
+        std::env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| home.map(|h| h.join(".local").join("share")))
+    }?;
+    let app_id = APP_ID
+        .try_lock()
+        .ok()
+        .and_then(|id| id.clone())
+        .unwrap_or_else(|| "vellum-app".to_string());
+    Some(data_dir.join(app_id).join("crashes"))
+}
+
+fn write_report(report: &str) -> std::io::Result<PathBuf> {
+    let dir = crash_dir().ok_or_else(|| std::io::Error::other("no home directory"))?;
+    std::fs::create_dir_all(&dir)?;
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let path = dir.join(format!("crash-{secs}-{}.txt", std::process::id()));
+    std::fs::write(&path, report)?;
+    Ok(path)
+}
+
+/// Best effort: a missing dialog tool just means no dialog.
+fn show_dialog(report: &Path) {
+    let text = format!(
+        "The app stopped unexpectedly. A crash report was saved to {}",
+        report.display()
+    );
+    let attempts: Vec<(&str, Vec<String>)> = if cfg!(target_os = "macos") {
+        let script = format!(
+            "display alert \"Application crashed\" message \"{}\" as critical",
+            text.replace('\\', "\\\\").replace('"', "\\\"")
+        );
+        vec![("osascript", vec!["-e".to_string(), script])]
+    } else if cfg!(windows) {
+        let script = format!(
+            "Add-Type -AssemblyName PresentationFramework; \
+             [System.Windows.MessageBox]::Show('{}', 'Application crashed', 'OK', 'Error')",
+            text.replace('\'', "''")
+        );
+        vec![(
+            "powershell",
+            vec!["-NoProfile".to_string(), "-Command".to_string(), script],
+        )]
+    } else {
+        vec![
+            (
+                "zenity",
+                vec!["--error".to_string(), format!("--text={text}")],
+            ),
+            ("kdialog", vec!["--error".to_string(), text.clone()]),
+        ]
+    };
+    for (program, args) in attempts {
+        if std::process::Command::new(program)
+            .args(&args)
+            .status()
+            .is_ok()
+        {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_lists_recent_commands() {
+        let report = render_report(
+            "main",
+            "index out of bounds",
+            "src/ui/handler.rs:10:5",
+            "<backtrace>",
+            7,
+            &[
+                "#0 @1.0ms CreateWidget".to_string(),
+                "#1 @2.0ms SetTitle".to_string(),
+            ],
+        );
+        assert!(report.contains("thread: main"));
+        assert!(report.contains("message: index out of bounds"));
+        assert!(report.contains("widgets: 7"));
+        assert!(report.contains("  #0 @1.0ms CreateWidget\n  #1 @2.0ms SetTitle\n"));
+        assert!(report.ends_with("<backtrace>\n"));
+        assert!(render_report("main", "", "", "", 0, &[]).contains("(none)"));
+    }
+
+    #[test]
+    fn test_recent_commands_are_capped() {
+        for _ in 0..RECENT_COMMAND_LIMIT + 5 {
+            record_command(&ClientCommand::SetTitle("Notes".to_string()), 3, 1.0);
+        }
+        let remove = ClientCommand::RemoveWidget {
+            id: "row_7".to_string(),
+        };
+        record_command(&remove, 2, 2.5);
+        // Other tests may run commands concurrently, so look the entry up.
+        let recent = recent_commands();
+        assert_eq!(recent.len(), RECENT_COMMAND_LIMIT);
+        let removed = recent
+            .iter()
+            .find(|record| record.widget_id.as_deref() == Some("row_7"))
+            .unwrap();
+        assert_eq!(
+            describe(removed),
+            format!("#{} @2.5ms RemoveWidget row_7", removed.seq)
+        );
+    }
+
+    #[test]
+    fn test_pending_report_stays_on_its_thread() {
+        stash_report("caught panic".to_string());
+        let elsewhere = std::thread::spawn(take_report).join().unwrap();
+        assert_eq!(elsewhere, None);
+        assert_eq!(take_report().as_deref(), Some("caught panic"));
+        assert_eq!(take_report(), None);
+        assert_eq!(guard(|| 7), 7);
+    }
+}
@@ -0,0 +1,166 @@
+// Deep Links
+// Registers the manifest's `deep-link-schemes` with the OS so that opening
+// `notes://...` launches the app with the URL as an argument. The URL reaches
+// JS as `UiEvent::OpenUrl`, forwarded to the running instance when the app is
+// single-instance (see manifest.rs).
+
+use std::path::Path;
+
+/// The first argument whose scheme is one of `schemes`.
+pub fn find_url<'a>(args: &'a [String], schemes: &[String]) -> Option<&'a str> {
+    args.iter().map(String::as_str).find(|arg| {
+        arg.split_once(':').is_some_and(|(scheme, _)| {
+            schemes
+                .iter()
+                .any(|candidate| candidate.eq_ignore_ascii_case(scheme))
+        })
+    })
+}
+
+/// Make this executable (plus `entry` in hosted mode) the handler for `schemes`.
+/// Registration is per-user and idempotent, so it runs on every launch.
+pub fn register(id: &str, schemes: &[String], entry: Option<&Path>) {
+    if schemes.is_empty() {
+        return;
+    }
+    let exe = match std::env::current_exe() {
+        Ok(exe) => exe,
+        Err(e) => {
+            eprintln!("[DeepLink] Cannot locate the executable to register: {e}");
+            return;
+        }
+    };
+    let mut command = vec![exe.to_string_lossy().into_owned()];
+    if let Some(entry) = entry {
+        let entry = std::fs::canonicalize(entry).unwrap_or_else(|_| entry.to_path_buf());
+        command.push(entry.to_string_lossy().into_owned());
+    }
+    if let Err(e) = register_for_platform(id, schemes, &command) {
+        eprintln!("[DeepLink] Failed to register {}: {e}", schemes.join(", "));
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn run(program: &str, args: &[&str]) -> std::io::Result<()> {
+    let status = std::process::Command::new(program).args(args).status()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(std::io::Error::other(format!(
+            "{program} exited with {status}"
+        )))
+    }
+}
+
+/// freedesktop: a hidden `.desktop` entry claiming `x-scheme-handler/<scheme>`.
+#[cfg(all(unix, not(target_os = "macos")))]
+fn register_for_platform(id: &str, schemes: &[String], command: &[String]) -> std::io::Result<()> {
+    let data_home = std::env::var_os("XDG_DATA_HOME")
+        .map(std::path::PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".local/share")))
+        .ok_or_else(|| std::io::Error::other("neither XDG_DATA_HOME nor HOME is set"))?;
+    let applications = data_home.join("applications");
+    let desktop_name = format!("{id}.desktop");
+    let desktop_path = applications.join(&desktop_name);
+    let contents = desktop_entry(id, schemes, command);
+    if std::fs::read_to_string(&desktop_path).ok().as_deref() == Some(contents.as_str()) {
+        return Ok(());
+    }
+    std::fs::create_dir_all(&applications)?;
+    std::fs::write(&desktop_path, contents)?;
+    for scheme in schemes {
+        run(
+            "xdg-mime",
+            &[
+                "default",
+                &desktop_name,
+                &format!("x-scheme-handler/{scheme}"),
+            ],
+        )?;
+    }
+    println!("[DeepLink] Registered {}", desktop_path.display());
+    Ok(())
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn desktop_entry(id: &str, schemes: &[String], command: &[String]) -> String {
+    let exec = command
+        .iter()
+        .map(|part| format!("\"{}\"", part.replace('\\', "\\\\").replace('"', "\\\"")))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let mime_types: String = schemes
+        .iter()
+        .map(|scheme| format!("x-scheme-handler/{scheme};"))
+        .collect();
+    format!(
+        "[Desktop Entry]\nType=Application\nName={id}\nExec={exec} %u\nNoDisplay=true\nMimeType={mime_types}\n"
+    )
+}
+
+/// Windows: `HKCU\Software\Classes\<scheme>` with a `shell\open\command`.
+#[cfg(windows)]
+fn register_for_platform(_id: &str, schemes: &[String], command: &[String]) -> std::io::Result<()> {
+    let open = command
+        .iter()
+        .map(|part| format!("\"{part}\""))
+        .chain(std::iter::once("\"%1\"".to_string()))
+        .collect::<Vec<_>>()
+        .join(" ");
+    for scheme in schemes {
+        let key = format!("HKCU\\Software\\Classes\\{scheme}");
+        run(
+            "reg",
+            &["add", &key, "/ve", "/d", &format!("URL:{scheme}"), "/f"],
+        )?;
+        run("reg", &["add", &key, "/v", "URL Protocol", "/d", "", "/f"])?;
+        let command_key = format!("{key}\\shell\\open\\command");
+        run("reg", &["add", &command_key, "/ve", "/d", &open, "/f"])?;
+    }
+    Ok(())
+}
+
+/// macOS binds schemes through `CFBundleURLTypes` in the app bundle's
+/// Info.plist and delivers URLs as Apple Events rather than arguments.
+#[cfg(target_os = "macos")]
+fn register_for_platform(
+    _id: &str,
+    schemes: &[String],
+    _command: &[String],
+) -> std::io::Result<()> {
+    println!(
+        "[DeepLink] On macOS, declare {} under CFBundleURLTypes in the app's Info.plist",
+        schemes.join(", ")
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_find_url_matches_declared_schemes() {
+        let schemes = strings(&["notes"]);
+        let args = strings(&["--verbose", "https://example.com", "Notes://auth?code=1"]);
+        assert_eq!(find_url(&args, &schemes), Some("Notes://auth?code=1"));
+        assert_eq!(find_url(&strings(&["notes.txt"]), &schemes), None);
+        assert_eq!(find_url(&args, &[]), None);
+    }
+
+    #[cfg(all(unix, not(target_os = "macos")))]
+    #[test]
+    fn test_desktop_entry() {
+        let entry = desktop_entry(
+            "com.example.notes",
+            &strings(&["notes", "notes-dev"]),
+            &strings(&["/opt/notes/vellum", "/opt/notes/my \"app\".ts"]),
+        );
+        assert!(entry.contains("Exec=\"/opt/notes/vellum\" \"/opt/notes/my \\\"app\\\".ts\" %u\n"));
+        assert!(entry.contains("MimeType=x-scheme-handler/notes;x-scheme-handler/notes-dev;\n"));
+    }
+}
@@ -0,0 +1,188 @@
+// Runtime Hooks
+// Applications embedding the runtime can watch it from Rust, to feed their own
+// metrics or telemetry pipelines, without patching the handler. Pass a
+// `RuntimeHooks` implementation to `vellum::run` through `Extensions::hooks`
+// (see app.rs); it is then called:
+//
+// - on the UI thread after each client command is applied, with how long it took;
+// - on the UI thread as each display frame starts, before it is laid out and
+//   painted, if `wants_frames` says so;
+// - on whichever thread raises a `RuntimeError`, as it is sent to JS.
+//
+// Hooks run inline, so they should queue their work rather than block. Asking
+// for frames is not free: like `SetFrameTicks` does for JS, it keeps the window
+// redrawing at the display's refresh rate for as long as the process runs,
+// even when nothing on screen changes, which costs CPU, GPU and battery.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use crate::ipc::ErrorCode;
+
+/// A client command the UI thread finished applying.
+#[derive(Debug, Clone, Copy)]
+pub struct CommandApplied<'a> {
+    /// The command's variant name, e.g. `"SetWidgetText"`
+    pub name: &'static str,
+    pub widget_id: Option<&'a str>,
+    pub duration: Duration,
+}
+
+/// A display frame starting.
+#[derive(Debug, Clone, Copy)]
+pub struct FrameStarted {
+    /// Time since the previous frame
+    pub interval: Duration,
+    /// Milliseconds since the UI started, the clock of event timestamps
+    pub timestamp: f64,
+}
+
+/// A runtime error on its way to JS.
+#[derive(Debug, Clone, Copy)]
+pub struct ErrorRaised<'a> {
+    /// The part of the runtime that raised it, e.g. `"ui-handler"`
+    pub source: &'a str,
+    pub code: ErrorCode,
+    pub message: &'a str,
+    pub fatal: bool,
+    pub widget_id: Option<&'a str>,
+    pub command: Option<&'a str>,
+}
+
+/// Callbacks for embedders; every method defaults to doing nothing.
+pub trait RuntimeHooks: Send + Sync {
+    fn command_applied(&self, _command: &CommandApplied<'_>) {}
+
+    /// Whether to call `frame_started`; frames only keep coming if a hook
+    /// says so when it is registered. Answering true keeps the window
+    /// redrawing every display refresh from then on, so leave it false unless
+    /// the hook really samples frames.
+    fn wants_frames(&self) -> bool {
+        false
+    }
+
+    fn frame_started(&self, _frame: &FrameStarted) {}
+
+    fn error_raised(&self, _error: &ErrorRaised<'_>) {}
+}
+
+static HOOKS: RwLock<Vec<Arc<dyn RuntimeHooks>>> = RwLock::new(Vec::new());
+/// Some hooks are registered.
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+/// Some registered hook wants frames.
+static FRAMES: AtomicBool = AtomicBool::new(false);
+
+/// Add `hooks`; they stay registered for the life of the process.
+pub fn register(hooks: impl RuntimeHooks + 'static) {
+    if hooks.wants_frames() {
+        FRAMES.store(true, Ordering::Relaxed);
+    }
+    HOOKS
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(Arc::new(hooks));
+    ACTIVE.store(true, Ordering::Release);
+}
+
+/// Whether any hooks are registered, so callers can skip gathering details.
+pub fn is_active() -> bool {
+    ACTIVE.load(Ordering::Acquire)
+}
+
+/// Whether the frame clock should keep frames coming for the hooks.
+pub fn wants_frames() -> bool {
+    FRAMES.load(Ordering::Relaxed)
+}
+
+/// Call `f` with each hook. The list is copied first, so a hook may register
+/// another.
+fn each(f: impl Fn(&dyn RuntimeHooks)) {
+    if !is_active() {
+        return;
+    }
+    let hooks = HOOKS.read().unwrap_or_else(|e| e.into_inner()).clone();
+    for hook in hooks {
+        f(hook.as_ref());
+    }
+}
+
+pub fn command_applied(command: &CommandApplied<'_>) {
+    each(|hook| hook.command_applied(command));
+}
+
+pub fn frame_started(frame: &FrameStarted) {
+    each(|hook| {
+        if hook.wants_frames() {
+            hook.frame_started(frame);
+        }
+    });
+}
+
+pub fn error_raised(error: &ErrorRaised<'_>) {
+    each(|hook| hook.error_raised(error));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct Recorder {
+        seen: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl RuntimeHooks for Recorder {
+        fn command_applied(&self, command: &CommandApplied<'_>) {
+            self.seen.lock().unwrap().push(format!(
+                "{} {}",
+                command.name,
+                command.widget_id.unwrap_or("-")
+            ));
+        }
+
+        fn frame_started(&self, _frame: &FrameStarted) {
+            self.seen.lock().unwrap().push("frame".to_string());
+        }
+
+        fn error_raised(&self, error: &ErrorRaised<'_>) {
+            self.seen
+                .lock()
+                .unwrap()
+                .push(format!("{:?} {}", error.code, error.message));
+        }
+    }
+
+    #[test]
+    fn test_registered_hooks_are_called() {
+        let recorder = Recorder::default();
+        let seen = recorder.seen.clone();
+        register(recorder);
+        assert!(is_active());
+
+        command_applied(&CommandApplied {
+            name: "SetWidgetText",
+            widget_id: Some("greeting"),
+            duration: Duration::from_micros(40),
+        });
+        // Frames go only to hooks that asked for them.
+        frame_started(&FrameStarted {
+            interval: Duration::from_millis(16),
+            timestamp: 16.0,
+        });
+        error_raised(&ErrorRaised {
+            source: "hooks-test",
+            code: ErrorCode::WidgetNotFound,
+            message: "no such widget",
+            fatal: false,
+            widget_id: None,
+            command: None,
+        });
+
+        let seen = seen.lock().unwrap();
+        assert!(seen.contains(&"SetWidgetText greeting".to_string()));
+        assert!(seen.contains(&"WidgetNotFound no such widget".to_string()));
+        assert!(!seen.contains(&"frame".to_string()));
+    }
+}
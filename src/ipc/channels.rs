@@ -1,37 +1,177 @@
-use std::sync::mpsc::{self, Receiver, Sender};
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, SendError, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
 
 use masonry::core::ErasedAction;
 use masonry_winit::app::{EventLoopProxy, MasonryUserEvent, WindowId};
 
+use super::UiEvent;
 use super::commands::ClientCommand;
-use super::{ClientCommandAction, UiEvent};
 
-/// Sender for UI events (UI thread holds this)
-pub type UiEventSender = Sender<UiEvent>;
+/// A `UiEvent` stamped with when, and in which order, it was sent
+#[derive(Debug, Clone)]
+pub struct StampedEvent {
+    /// Position among all events of this run, starting at 0
+    pub seq: u64,
+    /// Milliseconds since the channel was created (at startup), monotonic
+    pub timestamp: f64,
+    pub event: UiEvent,
+}
+
+/// Sender for UI events (UI thread holds this). Every clone shares one
+/// clock and counter, so `seq` orders events across all senders.
+#[derive(Clone)]
+pub struct UiEventSender {
+    sender: Sender<StampedEvent>,
+    started: Instant,
+    next_seq: Arc<Mutex<u64>>,
+}
+
+impl UiEventSender {
+    /// Create a sender and the receiver it feeds.
+    pub fn channel() -> (UiEventSender, UiEventReceiver) {
+        let (sender, receiver) = mpsc::channel::<StampedEvent>();
+        let sender = UiEventSender {
+            sender,
+            started: Instant::now(),
+            next_seq: Arc::new(Mutex::new(0)),
+        };
+        (sender, receiver)
+    }
+
+    /// Milliseconds since the channel was created, the clock of `timestamp`.
+    pub fn elapsed_ms(&self) -> f64 {
+        self.started.elapsed().as_secs_f64() * 1000.0
+    }
+
+    /// Stamp and send `event`. Fails, handing the event back, once the
+    /// receiver is gone.
+    pub fn send(&self, event: UiEvent) -> Result<(), SendError<UiEvent>> {
+        if let UiEvent::RuntimeError {
+            source,
+            code,
+            message,
+            fatal,
+            widget_id,
+            command,
+        } = &event
+        {
+            crate::hooks::error_raised(&crate::hooks::ErrorRaised {
+                source,
+                code: *code,
+                message,
+                fatal: *fatal,
+                widget_id: widget_id.as_deref(),
+                command: command.as_deref(),
+            });
+        }
+        // Hold the counter while sending so seq order is channel order.
+        let mut next_seq = self
+            .next_seq
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let stamped = StampedEvent {
+            seq: *next_seq,
+            timestamp: self.elapsed_ms(),
+            event,
+        };
+        self.sender
+            .send(stamped)
+            .map_err(|SendError(stamped)| SendError(stamped.event))?;
+        *next_seq += 1;
+        Ok(())
+    }
+}
 
 /// Receiver for UI events (JS thread holds this)
-pub type UiEventReceiver = Receiver<UiEvent>;
+pub type UiEventReceiver = Receiver<StampedEvent>;
+
+/// Commands on their way to the UI thread, handled in order.
+#[derive(Default)]
+pub struct CommandQueue {
+    commands: Mutex<VecDeque<ClientCommand>>,
+}
+
+impl CommandQueue {
+    /// Queue `cmd`; true when the queue was empty, i.e. the UI thread needs
+    /// waking to drain it.
+    fn push(&self, cmd: ClientCommand) -> bool {
+        let mut commands = self.commands.lock().unwrap_or_else(|p| p.into_inner());
+        commands.push_back(cmd);
+        commands.len() == 1
+    }
+
+    /// Everything queued so far, oldest first.
+    pub fn drain(&self) -> Vec<ClientCommand> {
+        self.commands
+            .lock()
+            .unwrap_or_else(|p| p.into_inner())
+            .drain(..)
+            .collect()
+    }
+}
+
+/// Wakes the UI thread to drain a `CommandQueue`.
+pub struct DrainCommands(pub Arc<CommandQueue>);
+
+impl fmt::Debug for DrainCommands {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("DrainCommands")
+    }
+}
 
 /// Sender that wraps EventLoopProxy to send ClientCommands directly to the UI event loop.
-/// This is held by the client thread and wakes the event loop on each send (zero polling).
+/// Commands are queued and the event loop is woken once per batch: a burst of
+/// hundreds of commands costs one user event, not hundreds.
 #[derive(Clone)]
 pub struct ClientCommandSender {
     proxy: EventLoopProxy,
     window_id: WindowId,
+    queue: Arc<CommandQueue>,
+    /// Set once the event loop is gone; every clone sees it.
+    closed: Arc<AtomicBool>,
 }
 
 impl ClientCommandSender {
     pub fn new(proxy: EventLoopProxy, window_id: WindowId) -> Self {
-        Self { proxy, window_id }
+        Self {
+            proxy,
+            window_id,
+            queue: Arc::new(CommandQueue::default()),
+            closed: Arc::new(AtomicBool::new(false)),
+        }
     }
 
-    /// Send a ClientCommand to the UI thread by wrapping it in MasonryUserEvent::Action.
-    /// This immediately wakes the winit event loop — no polling needed.
+    /// Queue a ClientCommand for the UI thread, waking the winit event loop
+    /// if it isn't already due to drain the queue — no polling needed. Fails
+    /// once the event loop has exited.
     pub fn send(&self, cmd: ClientCommand) -> Result<(), String> {
-        let action: ErasedAction = Box::new(ClientCommandAction(cmd));
+        if self.is_closed() {
+            return Err("the UI event loop has exited".to_string());
+        }
+        if !self.queue.push(cmd) {
+            return Ok(());
+        }
+        let action: ErasedAction = Box::new(DrainCommands(self.queue.clone()));
         self.proxy
             .send_event(MasonryUserEvent::AsyncAction(self.window_id, action))
-            .map_err(|e| format!("EventLoopProxy send failed: {e:?}"))
+            .map_err(|e| {
+                self.close();
+                format!("EventLoopProxy send failed: {e:?}")
+            })
+    }
+
+    /// Mark the UI as gone, so the bridge winds down instead of queueing
+    /// commands nobody will handle.
+    pub fn close(&self) {
+        self.closed.store(true, Ordering::Release);
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::Acquire)
     }
 }
 
@@ -61,7 +201,7 @@ impl IpcChannels {
     /// Create a new set of IPC channels for communication between threads.
     /// The `proxy` and `window_id` are needed so JS commands can wake the UI event loop.
     pub fn new(proxy: EventLoopProxy, window_id: WindowId) -> Self {
-        let (ui_event_tx, ui_event_rx) = mpsc::channel::<UiEvent>();
+        let (ui_event_tx, ui_event_rx) = UiEventSender::channel();
 
         IpcChannels {
             ui: UiChannels {
@@ -74,3 +214,40 @@ impl IpcChannels {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_queue_wakes_once_per_batch() {
+        let queue = CommandQueue::default();
+        assert!(queue.push(ClientCommand::ResetTree));
+        assert!(!queue.push(ClientCommand::SetTitle("a".to_string())));
+        let drained = queue.drain();
+        assert_eq!(drained.len(), 2);
+        assert!(matches!(drained[0], ClientCommand::ResetTree));
+        // Drained: the next command needs a new wakeup.
+        assert!(queue.push(ClientCommand::ResetTree));
+    }
+
+    #[test]
+    fn test_events_are_stamped_in_send_order() {
+        let (sender, receiver) = UiEventSender::channel();
+        let clone = sender.clone();
+        sender.send(UiEvent::FrameTick { timestamp: 0.0 }).unwrap();
+        clone
+            .send(UiEvent::OpenUrl {
+                url: "notes://a".to_string(),
+            })
+            .unwrap();
+        let first = receiver.recv().unwrap();
+        let second = receiver.recv().unwrap();
+        assert_eq!((first.seq, second.seq), (0, 1));
+        assert!(second.timestamp >= first.timestamp);
+        assert!(matches!(second.event, UiEvent::OpenUrl { .. }));
+
+        drop(receiver);
+        assert!(sender.send(UiEvent::FrameTick { timestamp: 1.0 }).is_err());
+    }
+}
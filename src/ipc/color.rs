@@ -1,7 +1,7 @@
-use serde::{Deserialize, Deserializer, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 /// Represents a parsed color value
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum ColorValue {
     /// RGBA color (0-255 per channel)
     Rgba { r: u8, g: u8, b: u8, a: u8 },
@@ -132,6 +132,22 @@ impl ColorValue {
     }
 }
 
+/// Written back as the string it parses from, so styles survive a round trip
+/// (tree snapshots store them).
+impl Serialize for ColorValue {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            ColorValue::Rgba { r, g, b, a } => {
+                serializer.serialize_str(&format!("#{r:02x}{g:02x}{b:02x}{a:02x}"))
+            }
+            ColorValue::Named(name) => serializer.serialize_str(name),
+        }
+    }
+}
+
 impl<'de> Deserialize<'de> for ColorValue {
     fn deserialize<D>(deserializer: D) -> Result<ColorValue, D::Error>
     where
@@ -202,4 +218,12 @@ mod tests {
             panic!("Deserialization failed");
         }
     }
+
+    #[test]
+    fn test_color_serialize_round_trip() {
+        for json in ["\"#00ff0080\"", "\"chartreuse\""] {
+            let color: ColorValue = serde_json::from_str(json).unwrap();
+            assert_eq!(serde_json::to_string(&color).unwrap(), json);
+        }
+    }
 }
@@ -1,5 +1,7 @@
 use super::color::ColorValue;
+use super::paint::PaintOp;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fmt;
 
 /// Commands generated by the runtime and sent to the UI thread
@@ -8,6 +10,10 @@ pub enum ClientCommand {
     /// Set the window title
     SetTitle(String),
 
+    /// Keep the window out of screenshots and screen sharing (macOS and
+    /// Windows)
+    SetContentProtection(bool),
+
     /// Create a new widget with optional styling and widget-specific data
     CreateWidget {
         id: String,
@@ -16,17 +22,41 @@ pub enum ClientCommand {
         text: Option<String>,
         style: Option<BoxStyle>,
         data: Option<WidgetData>,
+        /// Optional badge overlay; the widget is wrapped in an internal ZStack when set
+        badge: Option<BadgeSpec>,
     },
 
     /// Remove a widget
     RemoveWidget { id: String },
 
+    /// Cross-check widget bookkeeping against the masonry tree and report
+    /// divergence; with `repair`, rebuild child lists and indices afterwards
+    ValidateTree { repair: bool },
+
+    /// Replace the window content with a crash report after the client hit an
+    /// uncaught exception
+    ShowErrorOverlay { title: String, details: String },
+
+    /// Show or update the loading splash before the first widget is mounted;
+    /// None keeps a part as it is
+    SetSplash {
+        /// Encoded image bytes (PNG/JPEG/WebP/etc.)
+        image: Option<Vec<u8>>,
+        message: Option<String>,
+        /// 0.0 to 1.0; a spinner shows until one is given
+        progress: Option<f64>,
+    },
+
     /// Set widget text content
     SetWidgetText { id: String, text: String },
 
     /// Set widget visibility
     SetWidgetVisible { id: String, visible: bool },
 
+    /// Enable or disable a widget; a disabled widget and its descendants
+    /// ignore input and paint with their `disabled*` styles
+    SetWidgetEnabled { id: String, enabled: bool },
+
     /// Apply style to an existing widget
     SetWidgetStyle { id: String, style: BoxStyle },
 
@@ -37,9 +67,50 @@ pub enum ClientCommand {
         value: String,
     },
 
+    /// Replace the display list of a CustomPaint widget
+    SetPaintOps { id: String, ops: Vec<PaintOp> },
+
+    /// Replace the WGSL fragment shader of a GpuSurface widget
+    SetShader { id: String, wgsl: String },
+
+    /// Set the values a GpuSurface shader reads with `uniform(i)`
+    SetShaderUniforms { id: String, values: Vec<f32> },
+
+    /// Set a masonry property not modeled by `BoxStyle`, by type name, with a
+    /// JSON value (see `ui::raw_properties`)
+    SetRawProperty {
+        id: String,
+        name: String,
+        json: String,
+    },
+
     /// Set progress on a ProgressBar (0.0 to 1.0)
     SetWidgetValue { id: String, value: f64 },
 
+    /// Toggle a ProgressBar's indeterminate animation
+    SetProgressIndeterminate { id: String, indeterminate: bool },
+
+    /// Set the secondary "buffered" value of a ProgressBar (0.0 to 1.0)
+    SetProgressBuffer { id: String, buffer: Option<f64> },
+
+    /// Set both thumbs of a RangeSlider
+    SetWidgetRange { id: String, low: f64, high: f64 },
+
+    /// Update the badge overlay of a widget created with a `badge` param
+    SetBadge { id: String, value: BadgeValue },
+
+    /// Register (or extend) an icon pack resolvable by Icon widgets as `prefix:name`
+    RegisterIconPack {
+        prefix: String,
+        icons: HashMap<String, String>,
+    },
+
+    /// Set or clear the status dot of an Avatar
+    SetAvatarStatus {
+        id: String,
+        status: Option<ColorValue>,
+    },
+
     /// Play the video
     PlayVideo { id: String },
 
@@ -49,6 +120,22 @@ pub enum ClientCommand {
     /// Seek the video to a specific time
     SeekVideo { id: String, time_secs: f64 },
 
+    /// Set a video's volume, 0.0 (silent) to 1.0 (full)
+    SetVideoVolume { id: String, volume: f64 },
+
+    /// Mute or unmute a video, keeping its volume
+    SetVideoMuted { id: String, muted: bool },
+
+    /// Enumerate audio output devices; answered with `UiEvent::AudioDevices`
+    ListAudioDevices { request_id: String },
+
+    /// Play audio through the output with this `AudioDevice::id`, or None for
+    /// the system default
+    SetAudioOutput { device: Option<String> },
+
+    /// Hide a Portal's pull-to-refresh spinner after `RefreshRequested`
+    EndRefresh { id: String },
+
     /// Set image data on an Image widget (raw file bytes)
     SetImageData { id: String, data: Vec<u8> },
 
@@ -61,8 +148,287 @@ pub enum ClientCommand {
     /// Request window close
     CloseWindow,
 
-    /// Request to exit the application
-    ExitApp,
+    /// Request to exit the application with process exit `code`. The first
+    /// request for a reason that leaves JS running sends
+    /// `UiEvent::BeforeExit` and waits for a second one (or the timeout).
+    ExitApp { code: i32, reason: ExitReason },
+
+    /// Replace the items of the macOS dock menu (ignored on other platforms)
+    SetDockMenu { items: Vec<DockMenuItem> },
+
+    /// Keep the system awake until `ReleaseSleep` is sent with the same token
+    InhibitSleep { token: String, reason: String },
+
+    /// Drop a sleep inhibition taken with `InhibitSleep`
+    ReleaseSleep { token: String },
+
+    /// Lay out widgets created from now on left-to-right or right-to-left
+    SetLayoutDirection { direction: LayoutDirection },
+
+    /// Interface zoom: multiply every style length and font size by `factor`
+    SetUiScale { factor: f64 },
+
+    /// Enumerate installed fonts; answered with `UiEvent::FontList`
+    ListFonts { request_id: String },
+
+    /// Find the widget under a window point (logical pixels); answered with
+    /// `UiEvent::HitTestResult`
+    HitTest { request_id: String, x: f64, y: f64 },
+
+    /// Start or stop sending `UiEvent::FrameTick` on every animation frame
+    SetFrameTicks { enabled: bool },
+
+    /// Change the double-click and long-press thresholds in milliseconds
+    /// (`None` keeps the current value, 0 turns the gesture off)
+    SetGestureTiming {
+        double_click_ms: Option<u64>,
+        long_press_ms: Option<u64>,
+    },
+
+    /// Feed a widget property from a data channel (replacing an earlier
+    /// binding of the same property)
+    BindValue {
+        id: String,
+        property: String,
+        channel: String,
+    },
+
+    /// Drop a binding made with `BindValue`
+    UnbindValue { id: String, property: String },
+
+    /// Apply a value to every property bound to `channel`
+    PushChannel {
+        channel: String,
+        value: ChannelValue,
+    },
+
+    /// Tween a numeric property from `from` to `to` over `duration_ms` on the
+    /// UI thread, `repeat` more times (`u32::MAX` loops until stopped)
+    AnimateValue {
+        id: String,
+        property: String,
+        from: f64,
+        to: f64,
+        duration_ms: f64,
+        easing: String,
+        repeat: u32,
+    },
+
+    /// Stop an `AnimateValue` tween where it is (every tween on the widget
+    /// when `property` is None)
+    StopAnimation {
+        id: String,
+        property: Option<String>,
+    },
+
+    /// Call an op registered by a native plugin with JSON arguments; answered
+    /// with `UiEvent::NativeOpResult`
+    CallNativeOp {
+        request_id: String,
+        name: String,
+        args_json: String,
+    },
+
+    /// Drop every JS-created widget (sent internally when a restarted client
+    /// runtime reconnects, before it rebuilds the UI)
+    ResetTree,
+
+    /// Progress of the wait for the first client, shown on the splash (sent
+    /// internally by the socket server)
+    Handshake(HandshakeStatus),
+
+    /// Reply with the most recently handled commands, oldest first
+    /// (`UiEvent::RecentCommands`)
+    DumpRecentCommands { request_id: String },
+
+    /// Report runtime counters, such as the decoder each video is using
+    /// (`UiEvent::Metrics`)
+    GetMetrics { request_id: String },
+
+    /// Render the window to a PDF and open the platform print dialog on it
+    /// (`UiEvent::PrintResult`)
+    PrintWindow { request_id: String },
+
+    /// Render the window, or one widget, to a PDF at `path`. A Portal is paged
+    /// through its whole content (`UiEvent::PrintResult`)
+    ExportPdf {
+        request_id: String,
+        path: String,
+        widget_id: Option<String>,
+    },
+
+    /// Capture the declarative tree (kinds, text, styles) as a MsgPack
+    /// snapshot (`UiEvent::TreeSnapshot`)
+    SerializeTree { request_id: String },
+
+    /// Replace the tree with one captured by `SerializeTree`
+    RestoreTree { snapshot: Vec<u8> },
+
+    /// Start or stop journaling tree mutations for `Undo`/`Redo`, keeping up
+    /// to `limit` undo steps (100 when None)
+    SetJournal { enabled: bool, limit: Option<usize> },
+
+    /// Journal the mutations until the matching `EndUndoGroup` as one step
+    BeginUndoGroup,
+
+    /// Close the group opened by the matching `BeginUndoGroup`
+    EndUndoGroup,
+
+    /// Reverse the latest journaled step (`UiEvent::JournalChanged`)
+    Undo,
+
+    /// Re-apply the latest undone step (`UiEvent::JournalChanged`)
+    Redo,
+
+    /// Report when the visible fraction of widget `id` crosses `threshold`
+    /// (0 means any part, 1 all of it) as `UiEvent::VisibilityChanged`
+    ObserveVisibility { id: String, threshold: f64 },
+
+    /// Stop reporting the visibility of widget `id`
+    UnobserveVisibility { id: String },
+
+    /// Report the size of widget `id` each time a layout changes it
+    /// (`UiEvent::WidgetResized`)
+    ObserveResize { id: String },
+
+    /// Stop reporting the size of widget `id`
+    UnobserveResize { id: String },
+
+    /// Fetch the update feed at `url` and compare it with `current_version`;
+    /// answered with `UiEvent::UpdateCheckResult`
+    CheckForUpdate {
+        request_id: String,
+        url: String,
+        current_version: String,
+    },
+
+    /// Download and stage the version the last check found, reporting
+    /// `UiEvent::UpdateProgress` and then `UpdateReady` or `UpdateFailed`
+    DownloadUpdate,
+
+    /// Exit (with the `BeforeExit` grace period) and relaunch into the staged
+    /// update
+    RestartToUpdate,
+}
+
+impl ClientCommand {
+    /// Variant name, used to tag runtime errors with the command that caused them.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ClientCommand::SetTitle(_) => "SetTitle",
+            ClientCommand::SetContentProtection(_) => "SetContentProtection",
+            ClientCommand::CreateWidget { .. } => "CreateWidget",
+            ClientCommand::RemoveWidget { .. } => "RemoveWidget",
+            ClientCommand::ValidateTree { .. } => "ValidateTree",
+            ClientCommand::ShowErrorOverlay { .. } => "ShowErrorOverlay",
+            ClientCommand::SetSplash { .. } => "SetSplash",
+            ClientCommand::SetWidgetText { .. } => "SetWidgetText",
+            ClientCommand::SetWidgetVisible { .. } => "SetWidgetVisible",
+            ClientCommand::SetWidgetEnabled { .. } => "SetWidgetEnabled",
+            ClientCommand::SetWidgetStyle { .. } => "SetWidgetStyle",
+            ClientCommand::SetStyleProperty { .. } => "SetStyleProperty",
+            ClientCommand::SetPaintOps { .. } => "SetPaintOps",
+            ClientCommand::SetShader { .. } => "SetShader",
+            ClientCommand::SetShaderUniforms { .. } => "SetShaderUniforms",
+            ClientCommand::SetRawProperty { .. } => "SetRawProperty",
+            ClientCommand::SetWidgetValue { .. } => "SetWidgetValue",
+            ClientCommand::SetProgressIndeterminate { .. } => "SetProgressIndeterminate",
+            ClientCommand::SetProgressBuffer { .. } => "SetProgressBuffer",
+            ClientCommand::SetWidgetRange { .. } => "SetWidgetRange",
+            ClientCommand::SetBadge { .. } => "SetBadge",
+            ClientCommand::RegisterIconPack { .. } => "RegisterIconPack",
+            ClientCommand::SetAvatarStatus { .. } => "SetAvatarStatus",
+            ClientCommand::PlayVideo { .. } => "PlayVideo",
+            ClientCommand::PauseVideo { .. } => "PauseVideo",
+            ClientCommand::SeekVideo { .. } => "SeekVideo",
+            ClientCommand::SetVideoVolume { .. } => "SetVideoVolume",
+            ClientCommand::SetVideoMuted { .. } => "SetVideoMuted",
+            ClientCommand::ListAudioDevices { .. } => "ListAudioDevices",
+            ClientCommand::SetAudioOutput { .. } => "SetAudioOutput",
+            ClientCommand::EndRefresh { .. } => "EndRefresh",
+            ClientCommand::SetImageData { .. } => "SetImageData",
+            ClientCommand::SetWidgetChecked { .. } => "SetWidgetChecked",
+            ClientCommand::ResizeWindow { .. } => "ResizeWindow",
+            ClientCommand::CloseWindow => "CloseWindow",
+            ClientCommand::ExitApp { .. } => "ExitApp",
+            ClientCommand::SetDockMenu { .. } => "SetDockMenu",
+            ClientCommand::InhibitSleep { .. } => "InhibitSleep",
+            ClientCommand::ReleaseSleep { .. } => "ReleaseSleep",
+            ClientCommand::SetLayoutDirection { .. } => "SetLayoutDirection",
+            ClientCommand::SetUiScale { .. } => "SetUiScale",
+            ClientCommand::ListFonts { .. } => "ListFonts",
+            ClientCommand::HitTest { .. } => "HitTest",
+            ClientCommand::SetFrameTicks { .. } => "SetFrameTicks",
+            ClientCommand::SetGestureTiming { .. } => "SetGestureTiming",
+            ClientCommand::BindValue { .. } => "BindValue",
+            ClientCommand::UnbindValue { .. } => "UnbindValue",
+            ClientCommand::PushChannel { .. } => "PushChannel",
+            ClientCommand::AnimateValue { .. } => "AnimateValue",
+            ClientCommand::StopAnimation { .. } => "StopAnimation",
+            ClientCommand::CallNativeOp { .. } => "CallNativeOp",
+            ClientCommand::ResetTree => "ResetTree",
+            ClientCommand::Handshake(_) => "Handshake",
+            ClientCommand::DumpRecentCommands { .. } => "DumpRecentCommands",
+            ClientCommand::GetMetrics { .. } => "GetMetrics",
+            ClientCommand::PrintWindow { .. } => "PrintWindow",
+            ClientCommand::ExportPdf { .. } => "ExportPdf",
+            ClientCommand::SerializeTree { .. } => "SerializeTree",
+            ClientCommand::RestoreTree { .. } => "RestoreTree",
+            ClientCommand::SetJournal { .. } => "SetJournal",
+            ClientCommand::BeginUndoGroup => "BeginUndoGroup",
+            ClientCommand::EndUndoGroup => "EndUndoGroup",
+            ClientCommand::Undo => "Undo",
+            ClientCommand::Redo => "Redo",
+            ClientCommand::ObserveVisibility { .. } => "ObserveVisibility",
+            ClientCommand::UnobserveVisibility { .. } => "UnobserveVisibility",
+            ClientCommand::ObserveResize { .. } => "ObserveResize",
+            ClientCommand::UnobserveResize { .. } => "UnobserveResize",
+            ClientCommand::CheckForUpdate { .. } => "CheckForUpdate",
+            ClientCommand::DownloadUpdate => "DownloadUpdate",
+            ClientCommand::RestartToUpdate => "RestartToUpdate",
+        }
+    }
+
+    /// The widget the command acts on, for the recent-command log.
+    pub fn widget_id(&self) -> Option<&str> {
+        match self {
+            ClientCommand::CreateWidget { id, .. }
+            | ClientCommand::RemoveWidget { id }
+            | ClientCommand::SetWidgetText { id, .. }
+            | ClientCommand::SetWidgetVisible { id, .. }
+            | ClientCommand::SetWidgetEnabled { id, .. }
+            | ClientCommand::SetWidgetStyle { id, .. }
+            | ClientCommand::SetStyleProperty { id, .. }
+            | ClientCommand::SetPaintOps { id, .. }
+            | ClientCommand::SetShader { id, .. }
+            | ClientCommand::SetShaderUniforms { id, .. }
+            | ClientCommand::SetRawProperty { id, .. }
+            | ClientCommand::SetWidgetValue { id, .. }
+            | ClientCommand::SetProgressIndeterminate { id, .. }
+            | ClientCommand::SetProgressBuffer { id, .. }
+            | ClientCommand::SetWidgetRange { id, .. }
+            | ClientCommand::SetBadge { id, .. }
+            | ClientCommand::SetAvatarStatus { id, .. }
+            | ClientCommand::PlayVideo { id }
+            | ClientCommand::PauseVideo { id }
+            | ClientCommand::SeekVideo { id, .. }
+            | ClientCommand::SetVideoVolume { id, .. }
+            | ClientCommand::SetVideoMuted { id, .. }
+            | ClientCommand::EndRefresh { id }
+            | ClientCommand::SetImageData { id, .. }
+            | ClientCommand::SetWidgetChecked { id, .. }
+            | ClientCommand::BindValue { id, .. }
+            | ClientCommand::UnbindValue { id, .. }
+            | ClientCommand::AnimateValue { id, .. }
+            | ClientCommand::StopAnimation { id, .. }
+            | ClientCommand::ObserveVisibility { id, .. }
+            | ClientCommand::UnobserveVisibility { id }
+            | ClientCommand::ObserveResize { id }
+            | ClientCommand::UnobserveResize { id } => Some(id),
+            ClientCommand::ExportPdf { widget_id, .. } => widget_id.as_deref(),
+            _ => None,
+        }
+    }
 }
 
 /// Widget types that can be created
@@ -81,12 +447,20 @@ pub enum WidgetKind {
     ProgressBar,
     Spinner,
     Slider,
+    RangeSlider,
     Prose,
     Grid,
     ZStack,
     Portal,
+    ReorderableList,
     Hoverable,
     Video,
+    Avatar,
+    Skeleton,
+    QrCode,
+    Icon,
+    CustomPaint,
+    GpuSurface,
     Custom(String),
 }
 
@@ -95,16 +469,37 @@ pub enum WidgetKind {
 /// ensuring type safety and preventing nonsensical combinations.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum WidgetData {
-    /// Label — no extra data (text comes from the `text` field)
-    Label,
+    /// Label — text comes from the `text` field
+    Label {
+        /// Mouse selection and copy; backs the label with a read-only Prose
+        selectable: bool,
+    },
 
-    /// SVG widget
-    Svg { svg_data: Option<String> },
+    /// Button — optional keyboard shortcut ("CmdOrCtrl+S"), shown as a hint
+    /// at the end of the button and registered to click it, a preset look,
+    /// and the hover / press overlays
+    Button {
+        accelerator: Option<String>,
+        variant: Option<ButtonVariant>,
+        feedback: ButtonFeedbackParams,
+    },
+
+    /// SVG widget — markup plus optional base directory for relative
+    /// `<image href>` / stylesheet references and extra CSS
+    Svg {
+        svg_data: Option<String>,
+        resources_dir: Option<String>,
+        style_sheet: Option<String>,
+        /// Text read by screen readers in place of the graphic
+        alt: Option<String>,
+    },
 
     /// Image widget — raw image bytes + display mode
     Image {
         data: Vec<u8>,
         object_fit: Option<String>,
+        /// Text read by screen readers in place of the image
+        alt: Option<String>,
     },
 
     /// Flex / Container layout (all layout fields come from BoxStyle)
@@ -125,8 +520,12 @@ pub enum WidgetData {
     /// Prose — no extra data (text comes from the `text` field)
     Prose,
 
-    /// ProgressBar initial value
-    ProgressBar { progress: Option<f64> },
+    /// ProgressBar initial value, buffered value and mode
+    ProgressBar {
+        progress: Option<f64>,
+        buffer: Option<f64>,
+        indeterminate: bool,
+    },
 
     /// Spinner — no extra data
     Spinner,
@@ -139,11 +538,23 @@ pub enum WidgetData {
         step: Option<f64>,
     },
 
+    /// RangeSlider bounds and initial low/high thumb values
+    RangeSlider {
+        min: f64,
+        max: f64,
+        low: f64,
+        high: f64,
+        step: Option<f64>,
+    },
+
     /// ZStack — no extra data
     ZStack,
 
-    /// Portal (scroll container) — no extra data
-    Portal,
+    /// Portal (scroll container)
+    Portal {
+        /// Pulling past the top requests a refresh
+        pull_to_refresh: bool,
+    },
 
     /// Grid — no extra data (fallback to Flex)
     Grid,
@@ -151,8 +562,54 @@ pub enum WidgetData {
     /// Hoverable — container that tracks hover state
     Hoverable,
 
+    /// ReorderableList — column whose children can be dragged into a new order
+    ReorderableList,
+
     /// Video player — src is a file path or HTTP URL
-    Video { src: String },
+    Video {
+        src: String,
+        /// Encoded image shown until the first frame arrives
+        poster: Option<Vec<u8>>,
+    },
+
+    /// Avatar — optional image bytes, fallback background and status dot color
+    /// (the name used for initials comes from the `text` field)
+    Avatar {
+        image: Option<Vec<u8>>,
+        color: Option<ColorValue>,
+        status: Option<ColorValue>,
+    },
+
+    /// Skeleton placeholder — shape and optional base/shimmer colors
+    Skeleton {
+        circle: bool,
+        color: Option<ColorValue>,
+        highlight_color: Option<ColorValue>,
+    },
+
+    /// QR code / barcode — the encoded string comes from the `text` field
+    QrCode {
+        symbology: Symbology,
+        quiet_zone: Option<u32>,
+        color: Option<ColorValue>,
+        background: Option<ColorValue>,
+    },
+
+    /// Named icon — the `pack:name` reference comes from the `text` field;
+    /// raw markup in `svg_data` is accepted for the legacy `icon` alias of Svg
+    Icon { svg_data: Option<String> },
+
+    /// JS-drawn canvas — the initial display list
+    CustomPaint { ops: Vec<PaintOp> },
+
+    /// wgpu-backed surface — drawn by a WGSL `shader`, or by the Rust
+    /// `renderer` registered under that name
+    GpuSurface {
+        shader: Option<String>,
+        renderer: Option<String>,
+        uniforms: Vec<f32>,
+        animate: bool,
+    },
 
     /// Custom widget
     Custom(String),
@@ -180,16 +637,63 @@ pub struct BoxStyle {
     pub background: Option<ColorValue>,
     pub border_color: Option<ColorValue>,
     pub hover_border_color: Option<ColorValue>,
+    /// Text and icon color while the widget is disabled
+    pub disabled_color: Option<ColorValue>,
+    /// Background while the widget is disabled
+    pub disabled_background: Option<ColorValue>,
     pub border_width: Option<f64>,
     pub corner_radius: Option<f64>,
     pub padding: Option<PaddingValue>,
     pub width: Option<f64>,
     pub height: Option<f64>,
     pub icon_size: Option<f64>,
+    pub track_color: Option<ColorValue>,
+    pub fill_color: Option<ColorValue>,
+    /// `hidden` clips descendants to the widget's (rounded) bounds
+    pub overflow: Option<OverflowValue>,
+    /// How the root paints behind everything (`__root__` only)
+    pub background_mode: Option<BackgroundMode>,
+
+    // -- Image styles (`<image>`) --
+    /// How the image is scaled into the widget
+    pub object_fit: Option<ObjectFitValue>,
+    /// Where the image sits in the widget, as CSS `object-position`
+    /// ("center", "top left", "25% 75%")
+    pub object_position: Option<String>,
+    /// Tile the image across the widget, starting from where it sits
+    pub repeat: Option<ImageRepeat>,
+
+    // -- Background image styles (Flex/Container/SizedBox) --
+    /// Painted over `background` and beneath the children; set when the
+    /// container is created
+    pub background_image: Option<ImageSource>,
+    /// How the background image is scaled into the box (default `cover`)
+    pub background_fit: Option<ObjectFitValue>,
+    /// Where the background image sits, as CSS `background-position`
+    pub background_position: Option<String>,
+    pub background_repeat: Option<ImageRepeat>,
+
+    // -- Scroll container styles (Portal) --
+    /// Scrollbar thickness; 0 hides the scrollbars but keeps scrolling
+    pub scrollbar_width: Option<f64>,
+    pub scrollbar_thumb_color: Option<ColorValue>,
+    pub scrollbar_track_color: Option<ColorValue>,
+    /// Fade the scrollbars out while the pointer is idle
+    pub scrollbar_auto_hide: Option<bool>,
+    /// `false` lays content out at the viewport width and never scrolls sideways
+    pub horizontal_scroll: Option<bool>,
 
     // -- Flex-child property (how this widget behaves inside a Flex parent) --
     pub flex: Option<f64>,
 
+    // -- Portal-child property --
+    /// Pin this child to the top of its Portal while its section scrolls
+    pub sticky: Option<bool>,
+
+    // -- Stack-child property (ZStack and layer children) --
+    /// Paint and hit-test order among siblings; higher is on top (default 0)
+    pub z_index: Option<i32>,
+
     // -- Flex container styles (for Flex/Container/Button inner layout) --
     pub direction: Option<FlexDirection>,
     pub cross_axis_alignment: Option<CrossAlign>,
@@ -206,6 +710,59 @@ pub enum FontStyleValue {
     Italic,
 }
 
+/// Overflow behaviour for descendants painted outside a widget's bounds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OverflowValue {
+    Visible,
+    #[serde(alias = "clip")]
+    Hidden,
+}
+
+/// How an image is scaled into its box, as CSS `object-fit`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ObjectFitValue {
+    #[default]
+    Contain,
+    Cover,
+    #[serde(alias = "stretch")]
+    Fill,
+    None,
+    #[serde(alias = "scaledown", alias = "scale_down")]
+    ScaleDown,
+}
+
+/// Which directions an image repeats in, as CSS `background-repeat`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ImageRepeat {
+    #[default]
+    NoRepeat,
+    Repeat,
+    RepeatX,
+    RepeatY,
+}
+
+/// An image given by `asset://` URL or as encoded file bytes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ImageSource {
+    Url(String),
+    Bytes(Vec<u8>),
+}
+
+/// Root background: an opaque fill, nothing at all (the desktop shows
+/// through a transparent window), or a translucent material tint
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BackgroundMode {
+    Solid,
+    Transparent,
+    #[serde(alias = "system-material")]
+    SystemMaterial,
+}
+
 /// Text alignment
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -216,6 +773,14 @@ pub enum TextAlignValue {
     Justify,
 }
 
+/// Reading direction of the whole UI
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LayoutDirection {
+    Ltr,
+    Rtl,
+}
+
 /// Flex direction
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -260,6 +825,197 @@ pub enum PaddingValue {
     },
 }
 
+/// A value pushed to a data binding channel
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ChannelValue {
+    Bool(bool),
+    Number(f64),
+    Text(String),
+}
+
+impl ChannelValue {
+    pub fn as_number(&self) -> Option<f64> {
+        match self {
+            ChannelValue::Number(n) => Some(*n),
+            ChannelValue::Bool(b) => Some(if *b { 1.0 } else { 0.0 }),
+            ChannelValue::Text(s) => s.trim().parse().ok(),
+        }
+    }
+
+    /// JS truthiness: false, 0, NaN and "" are false.
+    pub fn is_truthy(&self) -> bool {
+        match self {
+            ChannelValue::Bool(b) => *b,
+            ChannelValue::Number(n) => *n != 0.0 && !n.is_nan(),
+            ChannelValue::Text(s) => !s.is_empty(),
+        }
+    }
+}
+
+impl std::fmt::Display for ChannelValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChannelValue::Bool(b) => write!(f, "{b}"),
+            ChannelValue::Number(n) => write!(f, "{n}"),
+            ChannelValue::Text(s) => f.write_str(s),
+        }
+    }
+}
+
+/// What a badge overlay displays
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BadgeValue {
+    /// Nothing is drawn, but the overlay stays in place for later updates
+    Hidden,
+    /// A small notification dot
+    Dot,
+    /// An unread count pill
+    Count(u32),
+}
+
+/// Corner of the host widget a badge is pinned to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BadgePosition {
+    #[default]
+    TopRight,
+    TopLeft,
+    BottomRight,
+    BottomLeft,
+}
+
+/// Symbol format rendered by a QrCode widget
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Symbology {
+    #[default]
+    Qr,
+    Code128,
+}
+
+impl Symbology {
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_lowercase().as_str() {
+            "qr" | "qrcode" => Some(Self::Qr),
+            "code128" | "barcode" => Some(Self::Code128),
+            _ => None,
+        }
+    }
+
+    /// Quiet zone width (in modules) recommended by each spec.
+    pub fn default_quiet_zone(self) -> u32 {
+        match self {
+            Self::Qr => 4,
+            Self::Code128 => 10,
+        }
+    }
+}
+
+/// Resolve an Avatar status: a preset name (`online`, `away`, `busy`, `offline`)
+/// or any color string. Empty / `none` clears the status dot.
+pub fn parse_avatar_status(raw: &str) -> Option<ColorValue> {
+    let rgb = |r, g, b| Some(ColorValue::Rgba { r, g, b, a: 255 });
+    match raw.trim().to_lowercase().as_str() {
+        "" | "none" => None,
+        "online" => rgb(34, 197, 94),
+        "away" | "idle" => rgb(245, 158, 11),
+        "busy" | "dnd" => rgb(239, 68, 68),
+        "offline" => rgb(156, 163, 175),
+        other => ColorValue::parse(other),
+    }
+}
+
+/// Preset look of a Button, filling in the styles it wasn't given
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ButtonVariant {
+    /// Solid accent background with white text
+    Filled,
+    /// Transparent with an accent border and text
+    Outline,
+    /// Accent text only; the hover overlay shows it is clickable
+    Ghost,
+}
+
+/// Resolve a Button `variant` param; unknown names are None.
+pub fn parse_button_variant(raw: &str) -> Option<ButtonVariant> {
+    match raw.trim().to_lowercase().as_str() {
+        "filled" | "solid" | "primary" => Some(ButtonVariant::Filled),
+        "outline" | "outlined" => Some(ButtonVariant::Outline),
+        "ghost" | "text" => Some(ButtonVariant::Ghost),
+        _ => None,
+    }
+}
+
+/// Button feedback params as given; unset ones come from the variant.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ButtonFeedbackParams {
+    /// Opacity (0..1) of the white overlay while hovered
+    pub hover_lighten: Option<f64>,
+    /// Opacity (0..1) of the black overlay while pressed
+    pub press_darken: Option<f64>,
+    /// Spread a ripple from the press point
+    pub ripple: Option<bool>,
+}
+
+impl ButtonFeedbackParams {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Badge overlay configuration from the `badge` widget param
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BadgeSpec {
+    pub value: BadgeValue,
+    pub color: Option<ColorValue>,
+    pub text_color: Option<ColorValue>,
+    pub position: BadgePosition,
+}
+
+/// Why the application is exiting, reported to JS with `UiEvent::BeforeExit`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExitReason {
+    /// JS called `exit()`
+    App,
+    /// The user quit from the OS (Cmd-Q, the dock or app menu)
+    Quit,
+    /// The JS runtime finished or disconnected
+    RuntimeExited,
+    /// The JS runtime couldn't be started or kept crashing, or a replay
+    /// couldn't be read
+    RuntimeFailed,
+    /// JS didn't confirm within the `BeforeExit` grace period
+    Timeout,
+}
+
+impl ExitReason {
+    /// Whether JS is still around to run its `onBeforeExit` handlers.
+    pub fn has_grace_period(self) -> bool {
+        matches!(self, ExitReason::App | ExitReason::Quit)
+    }
+}
+
+/// Where the wait for the first client stands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HandshakeStatus {
+    /// No client yet; the wait times out in `remaining_secs`
+    Waiting { remaining_secs: u64 },
+    /// No client connected within `timeout_secs`; a retry starts the wait over
+    TimedOut { timeout_secs: u64 },
+    /// A client connected
+    Connected,
+}
+
+/// Entry of the macOS dock menu; choosing it emits `UiEvent::DockMenuAction`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DockMenuItem {
+    pub id: String,
+    pub title: String,
+    /// Keyboard shortcut ("CmdOrCtrl+N") that also chooses the item
+    #[serde(default)]
+    pub accelerator: Option<String>,
+}
+
 /// Wrapper around ClientCommand that can be sent as an ErasedAction through the EventLoopProxy.
 /// ErasedAction = Box<dyn AnyDebug + Send>, where AnyDebug is auto-implemented for Any + Debug.
 /// So this type just needs Debug + Send + 'static (ClientCommand is Clone+Debug, all String fields).
@@ -273,6 +1029,65 @@ impl fmt::Debug for ClientCommandAction {
 
 // ── Helpers for parsing style from JSON-like data ──
 
+/// Parse a badge value: a count, `true`/`"dot"` for a dot, or `false`/`null`/`0` to hide it.
+pub fn parse_badge_value(value: &serde_json::Value) -> Option<BadgeValue> {
+    match value {
+        serde_json::Value::Null | serde_json::Value::Bool(false) => Some(BadgeValue::Hidden),
+        serde_json::Value::Bool(true) => Some(BadgeValue::Dot),
+        serde_json::Value::Number(n) => {
+            let count = n.as_f64()?.max(0.0).round() as u32;
+            Some(if count == 0 {
+                BadgeValue::Hidden
+            } else {
+                BadgeValue::Count(count)
+            })
+        }
+        serde_json::Value::String(s) => match s.trim() {
+            "dot" => Some(BadgeValue::Dot),
+            "" | "none" | "hidden" => Some(BadgeValue::Hidden),
+            other => parse_badge_value(&serde_json::Value::from(other.parse::<f64>().ok()?)),
+        },
+        _ => None,
+    }
+}
+
+/// Parse the `badge` widget param: either a bare value (see [`parse_badge_value`])
+/// or an object `{ value, color, textColor, position }`.
+pub fn parse_badge_spec(value: &serde_json::Value) -> Option<BadgeSpec> {
+    let Some(obj) = value.as_object() else {
+        return Some(BadgeSpec {
+            value: parse_badge_value(value)?,
+            color: None,
+            text_color: None,
+            position: BadgePosition::default(),
+        });
+    };
+
+    let badge_value = obj
+        .get("value")
+        .or_else(|| obj.get("count"))
+        .map(parse_badge_value)
+        .unwrap_or(Some(BadgeValue::Dot))?;
+    let color_of = |key: &str| {
+        obj.get(key)
+            .and_then(|v| v.as_str())
+            .and_then(ColorValue::parse)
+    };
+    let position = match obj.get("position").and_then(|v| v.as_str()) {
+        Some("topLeft" | "top-left" | "top_left") => BadgePosition::TopLeft,
+        Some("bottomRight" | "bottom-right" | "bottom_right") => BadgePosition::BottomRight,
+        Some("bottomLeft" | "bottom-left" | "bottom_left") => BadgePosition::BottomLeft,
+        _ => BadgePosition::TopRight,
+    };
+
+    Some(BadgeSpec {
+        value: badge_value,
+        color: color_of("color"),
+        text_color: color_of("textColor").or_else(|| color_of("text_color")),
+        position,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -305,6 +1120,7 @@ mod tests {
                 ..Default::default()
             }),
             data: None,
+            badge: None,
         };
 
         let serialized = serde_json::to_string(&cmd).unwrap();
@@ -321,9 +1137,94 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_badge_value() {
+        use serde_json::json;
+
+        assert_eq!(parse_badge_value(&json!(3)), Some(BadgeValue::Count(3)));
+        assert_eq!(parse_badge_value(&json!("12")), Some(BadgeValue::Count(12)));
+        assert_eq!(parse_badge_value(&json!(0)), Some(BadgeValue::Hidden));
+        assert_eq!(parse_badge_value(&json!(null)), Some(BadgeValue::Hidden));
+        assert_eq!(parse_badge_value(&json!(true)), Some(BadgeValue::Dot));
+        assert_eq!(parse_badge_value(&json!("dot")), Some(BadgeValue::Dot));
+        assert_eq!(parse_badge_value(&json!("lots")), None);
+    }
+
+    #[test]
+    fn test_parse_badge_spec_object() {
+        let spec = parse_badge_spec(&serde_json::json!({
+            "value": 5,
+            "color": "#00ff00",
+            "position": "bottomLeft"
+        }))
+        .unwrap();
+        assert_eq!(spec.value, BadgeValue::Count(5));
+        assert_eq!(spec.position, BadgePosition::BottomLeft);
+        assert!(matches!(
+            spec.color,
+            Some(ColorValue::Rgba {
+                r: 0,
+                g: 255,
+                b: 0,
+                a: 255
+            })
+        ));
+        assert!(spec.text_color.is_none());
+
+        let dot = parse_badge_spec(&serde_json::json!({ "position": "topLeft" })).unwrap();
+        assert_eq!(dot.value, BadgeValue::Dot);
+        assert_eq!(dot.position, BadgePosition::TopLeft);
+    }
+
+    #[test]
+    fn test_parse_avatar_status() {
+        assert!(matches!(
+            parse_avatar_status("Online"),
+            Some(ColorValue::Rgba {
+                r: 34,
+                g: 197,
+                b: 94,
+                a: 255
+            })
+        ));
+        assert!(matches!(
+            parse_avatar_status("#112233"),
+            Some(ColorValue::Rgba {
+                r: 0x11,
+                g: 0x22,
+                b: 0x33,
+                a: 255
+            })
+        ));
+        assert!(parse_avatar_status("none").is_none());
+        assert!(parse_avatar_status("").is_none());
+    }
+
+    #[test]
+    fn test_parse_button_variant() {
+        assert_eq!(parse_button_variant("Filled"), Some(ButtonVariant::Filled));
+        assert_eq!(
+            parse_button_variant(" outline"),
+            Some(ButtonVariant::Outline)
+        );
+        assert_eq!(parse_button_variant("ghost"), Some(ButtonVariant::Ghost));
+        assert_eq!(parse_button_variant("fancy"), None);
+    }
+
+    #[test]
+    fn test_parse_symbology() {
+        assert_eq!(Symbology::parse("QR"), Some(Symbology::Qr));
+        assert_eq!(Symbology::parse("code128"), Some(Symbology::Code128));
+        assert_eq!(Symbology::parse("barcode"), Some(Symbology::Code128));
+        assert_eq!(Symbology::parse("ean13"), None);
+    }
+
     #[test]
     fn test_client_command_action_debug() {
-        let cmd = ClientCommand::ExitApp;
+        let cmd = ClientCommand::ExitApp {
+            code: 0,
+            reason: ExitReason::App,
+        };
         let action = ClientCommandAction(cmd);
         let debug_str = format!("{:?}", action);
         assert!(debug_str.contains("ClientCommandAction"));
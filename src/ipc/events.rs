@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use super::ExitReason;
+
 /// Events generated by the UI thread and sent to the JS runtime
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum UiEvent {
@@ -11,28 +13,332 @@ pub enum UiEvent {
     /// Runtime error emitted by Rust side and forwarded to JS.
     RuntimeError {
         source: String,
+        code: ErrorCode,
         message: String,
         fatal: bool,
+        /// JS id of the widget the failing command targeted, if any
+        widget_id: Option<String>,
+        /// Name of the failing `ClientCommand`, if the error came from one
+        command: Option<String>,
+    },
+    /// The app is about to exit; JS has `timeout_ms` to flush its state and
+    /// confirm with `ExitApp` before the UI exits anyway
+    BeforeExit {
+        code: i32,
+        reason: ExitReason,
+        timeout_ms: u64,
+    },
+    /// The user tried to close or quit a kiosk (`--kiosk`), which stays open
+    CloseRequested,
+    /// The app was opened with one of its deep link schemes (`notes://...`)
+    OpenUrl { url: String },
+    /// macOS asked the app to open documents (Finder "Open With", dock drop)
+    OpenFiles { paths: Vec<String> },
+    /// The dock icon was clicked while the app was already running (macOS)
+    AppReopen { has_visible_windows: bool },
+    /// An item set with `SetDockMenu` was chosen (macOS)
+    DockMenuAction { id: String },
+    /// System power or login session change
+    Power(PowerEventKind),
+    /// Reply to `ListFonts` with the installed font families
+    FontList {
+        request_id: String,
+        families: Vec<FontFamilyInfo>,
+    },
+    /// Reply to `HitTest`: the JS widget under the point and the point in its
+    /// local coordinates, or no widget and the point unchanged
+    HitTestResult {
+        request_id: String,
+        widget_id: Option<String>,
+        x: f64,
+        y: f64,
+    },
+    /// An animation frame is starting (while `SetFrameTicks` is on);
+    /// `timestamp` is in milliseconds since the UI started
+    FrameTick { timestamp: f64 },
+    /// Reply to `CallNativeOp`: the op's JSON result, or its error message
+    NativeOpResult {
+        request_id: String,
+        result: Option<String>,
+        error: Option<String>,
+    },
+    /// Reply to `DumpRecentCommands`, oldest first
+    RecentCommands {
+        request_id: String,
+        commands: Vec<CommandRecord>,
+    },
+    /// A video widget could not open or keep playing its source
+    VideoError { id: String, message: String },
+    /// Reply to `GetMetrics`
+    Metrics {
+        request_id: String,
+        widget_count: usize,
+        videos: Vec<VideoMetrics>,
+    },
+    /// Reply to `ListAudioDevices`
+    AudioDevices {
+        request_id: String,
+        devices: Vec<AudioDevice>,
     },
+    /// The system's default audio output changed (None when there is none)
+    DefaultDeviceChanged { device: Option<AudioDevice> },
+    /// Reply to `PrintWindow` and `ExportPdf`: the pages written, or why
+    /// nothing was
+    PrintResult {
+        request_id: String,
+        pages: usize,
+        error: Option<String>,
+    },
+    /// Reply to `SerializeTree`: a MsgPack snapshot for `RestoreTree`
+    TreeSnapshot {
+        request_id: String,
+        #[serde(with = "serde_bytes")]
+        snapshot: Vec<u8>,
+    },
+    /// Whether the journal has steps to undo or redo changed
+    JournalChanged { can_undo: bool, can_redo: bool },
+    /// An observed widget crossed its `ObserveVisibility` threshold; `ratio`
+    /// is the fraction of it that shows
+    VisibilityChanged { id: String, ratio: f64 },
+    /// A layout changed the size of a widget observed with `ObserveResize`
+    WidgetResized { id: String, width: f64, height: f64 },
+    /// Reply to `CheckForUpdate`: the newer version and its notes, no version
+    /// when the app is up to date, or why the feed couldn't be read
+    UpdateCheckResult {
+        request_id: String,
+        version: Option<String>,
+        notes: Option<String>,
+        error: Option<String>,
+    },
+    /// Bytes of the update downloaded so far; `total` is known when the feed
+    /// lists the sizes
+    UpdateProgress { downloaded: u64, total: Option<u64> },
+    /// The update is downloaded, verified and staged; `RestartToUpdate`
+    /// installs it, as does the next launch
+    UpdateReady { version: String },
+    /// `DownloadUpdate` failed; nothing was staged
+    UpdateFailed { error: String },
+}
+
+/// An audio output, as listed by `ListAudioDevices`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AudioDevice {
+    /// Stable for the device while it is connected; pass to `SetAudioOutput`
+    pub id: String,
+    pub name: String,
+    pub is_default: bool,
+}
+
+/// A video widget's entry in `UiEvent::Metrics`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VideoMetrics {
+    pub id: String,
+    /// None until the first frame has been decoded
+    pub decoder: Option<VideoDecoder>,
+}
+
+/// The decoder a video widget is playing with
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VideoDecoder {
+    /// GStreamer element name such as "vah264dec" or "avdec_h264", or "ffmpeg"
+    pub name: String,
+    pub hardware: bool,
+}
+
+/// A command the UI thread handled, as kept in the recent-command log
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CommandRecord {
+    /// Position among all commands handled this run, starting at 0
+    pub seq: u64,
+    /// `ClientCommand::name`
+    pub name: String,
+    pub widget_id: Option<String>,
+    /// When it was handled, on the clock of event timestamps
+    pub timestamp: f64,
+}
+
+/// An installed font family and the faces it provides
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FontFamilyInfo {
+    pub name: String,
+    pub faces: Vec<FontFace>,
+}
+
+/// One face of a font family
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FontFace {
+    /// CSS weight, 100 (thin) to 900 (black)
+    pub weight: f32,
+    /// `"normal"`, `"italic"` or `"oblique"`
+    pub style: String,
+}
+
+/// Machine-readable category of a `RuntimeError`, so JS can react to
+/// specific failures without matching on message text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ErrorCode {
+    /// The command referenced a widget id that isn't registered
+    WidgetNotFound,
+    /// The command isn't supported for the target widget's kind
+    UnsupportedOperation,
+    /// A child can't be attached to the requested parent
+    InvalidHierarchy,
+    /// A value in the command couldn't be applied (bad icon name, unencodable data, …)
+    InvalidValue,
+    /// A message from JS couldn't be decoded
+    ParseError,
+    /// Widget bookkeeping diverged from the masonry tree
+    TreeInconsistent,
+    /// A command couldn't be forwarded to the UI thread
+    DispatchFailed,
+    /// Any other failure inside the runtime
+    Internal,
+}
+
+/// Kind of power or session change
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PowerEventKind {
+    /// The system is about to sleep
+    Suspend,
+    /// The system woke up
+    Resume,
+    /// The user's session was locked
+    Lock,
+    /// The user's session was unlocked
+    Unlock,
+    /// Low-power / power-saver mode was switched on or off
+    LowPowerMode(bool),
 }
 
 /// Kind of widget action
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum WidgetActionKind {
-    Click,
+    /// Activation of a button-like widget; `button` is None when it came
+    /// from the keyboard (Space / Enter)
+    Click {
+        button: Option<MouseButton>,
+        /// 2 for the second click of a double click, and so on
+        count: u8,
+        modifiers: KeyModifiers,
+    },
     ValueChanged(f64),
     HoverChanged(bool),
+    RangeChanged {
+        low: f64,
+        high: f64,
+    },
+    /// A Portal with pull-to-refresh was pulled past its threshold; answer
+    /// with `EndRefresh` when the reload is done
+    RefreshRequested,
+    /// A ReorderableList row was dragged from index `from` to `to`
+    ItemMoved {
+        from: usize,
+        to: usize,
+    },
+    /// Two clicks in quick succession at about the same spot; `x`/`y` are
+    /// in the widget's local coordinates
+    DoubleClick {
+        button: MouseButton,
+        modifiers: KeyModifiers,
+        x: f64,
+        y: f64,
+    },
+    /// The primary button was held still past the long-press threshold
+    LongPress {
+        modifiers: KeyModifiers,
+        x: f64,
+        y: f64,
+    },
+    /// Pointer input on a CustomPaint widget, in its local coordinates
+    Pointer {
+        phase: PointerPhase,
+        x: f64,
+        y: f64,
+    },
+    /// A Portal's row scrolled into view (again)
+    EnteredViewport,
+}
+
+/// Pointer button that produced a click
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MouseButton {
+    Primary,
+    Secondary,
+    Auxiliary,
+    Back,
+    Forward,
+    Other,
+}
+
+/// Keyboard modifiers held during an input
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyModifiers {
+    pub shift: bool,
+    pub ctrl: bool,
+    pub alt: bool,
+    /// Command on macOS, the Windows / Super key elsewhere
+    pub meta: bool,
+}
+
+/// How the latest click or key press was made, attached to `Click`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClickInput {
+    pub button: Option<MouseButton>,
+    pub count: u8,
+    pub modifiers: KeyModifiers,
+}
+
+impl ClickInput {
+    /// A plain primary click, assumed when no input was seen.
+    pub const PRIMARY: ClickInput = ClickInput {
+        button: Some(MouseButton::Primary),
+        count: 1,
+        modifiers: KeyModifiers {
+            shift: false,
+            ctrl: false,
+            alt: false,
+            meta: false,
+        },
+    };
+}
+
+impl WidgetActionKind {
+    pub fn click(input: ClickInput) -> Self {
+        WidgetActionKind::Click {
+            button: input.button,
+            count: input.count,
+            modifiers: input.modifiers,
+        }
+    }
+}
+
+/// Stage of a pointer interaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PointerPhase {
+    Down,
+    Move,
+    Up,
 }
 
 #[cfg(test)]
 mod tests {
+    use serde_json::json;
+
     use super::*;
+    use crate::ipc::msgpack::ServerMessage;
 
     #[test]
     fn test_ui_event_serialization() {
         let event = UiEvent::WidgetAction {
             widget_id: "btn_1".to_string(),
-            action: WidgetActionKind::Click,
+            action: WidgetActionKind::click(ClickInput {
+                button: Some(MouseButton::Secondary),
+                count: 2,
+                modifiers: KeyModifiers {
+                    shift: true,
+                    ..KeyModifiers::default()
+                },
+            }),
         };
 
         let serialized = serde_json::to_string(&event).unwrap();
@@ -44,7 +350,14 @@ mod tests {
         match deserialized {
             UiEvent::WidgetAction { widget_id, action } => {
                 assert_eq!(widget_id, "btn_1");
-                assert!(matches!(action, WidgetActionKind::Click));
+                assert!(matches!(
+                    action,
+                    WidgetActionKind::Click {
+                        button: Some(MouseButton::Secondary),
+                        count: 2,
+                        modifiers: KeyModifiers { shift: true, .. },
+                    }
+                ));
             }
             _ => panic!("Expected WidgetAction"),
         }
@@ -54,8 +367,11 @@ mod tests {
     fn test_runtime_error_serialization() {
         let event = UiEvent::RuntimeError {
             source: "js".to_string(),
+            code: ErrorCode::WidgetNotFound,
             message: "Syntax Error".to_string(),
             fatal: true,
+            widget_id: Some("btn_1".to_string()),
+            command: Some("SetWidgetText".to_string()),
         };
 
         let serialized = serde_json::to_string(&event).unwrap();
@@ -64,15 +380,232 @@ mod tests {
         let deserialized: UiEvent = serde_json::from_str(&serialized).unwrap();
         if let UiEvent::RuntimeError {
             source,
+            code,
             message,
             fatal,
+            widget_id,
+            command,
         } = deserialized
         {
             assert_eq!(source, "js");
+            assert_eq!(code, ErrorCode::WidgetNotFound);
             assert_eq!(message, "Syntax Error");
             assert!(fatal);
+            assert_eq!(widget_id.as_deref(), Some("btn_1"));
+            assert_eq!(command.as_deref(), Some("SetWidgetText"));
         } else {
             panic!("Expected RuntimeError");
         }
     }
+
+    /// Encode `event` the way the socket writer does and decode it the way
+    /// bun_bridge.ts sees it.
+    fn wire_layout(event: UiEvent) -> serde_json::Value {
+        let message = ServerMessage::UiEvent {
+            event,
+            seq: 7,
+            timestamp: 1.5,
+        };
+        let bytes = rmp_serde::to_vec_named(&message).unwrap();
+        let decoded: ServerMessage = rmp_serde::from_slice(&bytes).unwrap();
+        assert_eq!(rmp_serde::to_vec_named(&decoded).unwrap(), bytes);
+        rmp_serde::from_slice(&bytes).unwrap()
+    }
+
+    #[test]
+    fn test_events_keep_the_layout_the_bridge_reads() {
+        let cases = [
+            (
+                UiEvent::WidgetAction {
+                    widget_id: "price".to_string(),
+                    action: WidgetActionKind::RangeChanged {
+                        low: 10.0,
+                        high: 250.0,
+                    },
+                },
+                json!({"WidgetAction": {"widget_id": "price", "action": {"RangeChanged": {"low": 10.0, "high": 250.0}}}}),
+            ),
+            (
+                UiEvent::WidgetAction {
+                    widget_id: "canvas".to_string(),
+                    action: WidgetActionKind::Pointer {
+                        phase: PointerPhase::Down,
+                        x: 12.5,
+                        y: 4.0,
+                    },
+                },
+                json!({"WidgetAction": {"widget_id": "canvas", "action": {"Pointer": {"phase": "Down", "x": 12.5, "y": 4.0}}}}),
+            ),
+            (
+                UiEvent::WidgetAction {
+                    widget_id: "list".to_string(),
+                    action: WidgetActionKind::ItemMoved { from: 2, to: 0 },
+                },
+                json!({"WidgetAction": {"widget_id": "list", "action": {"ItemMoved": {"from": 2, "to": 0}}}}),
+            ),
+            (
+                UiEvent::BeforeExit {
+                    code: 3,
+                    reason: ExitReason::Quit,
+                    timeout_ms: 2000,
+                },
+                json!({"BeforeExit": {"code": 3, "reason": "Quit", "timeout_ms": 2000}}),
+            ),
+            (UiEvent::CloseRequested, json!("CloseRequested")),
+            (
+                UiEvent::OpenUrl {
+                    url: "notes://auth?code=42".to_string(),
+                },
+                json!({"OpenUrl": {"url": "notes://auth?code=42"}}),
+            ),
+            (
+                UiEvent::Power(PowerEventKind::Suspend),
+                json!({"Power": "Suspend"}),
+            ),
+            (
+                UiEvent::Power(PowerEventKind::LowPowerMode(true)),
+                json!({"Power": {"LowPowerMode": true}}),
+            ),
+            (
+                UiEvent::FontList {
+                    request_id: "fonts_1".to_string(),
+                    families: vec![FontFamilyInfo {
+                        name: "Inter".to_string(),
+                        faces: vec![FontFace {
+                            weight: 400.0,
+                            style: "italic".to_string(),
+                        }],
+                    }],
+                },
+                json!({"FontList": {"request_id": "fonts_1", "families": [{"name": "Inter", "faces": [{"weight": 400.0, "style": "italic"}]}]}}),
+            ),
+            (
+                UiEvent::HitTestResult {
+                    request_id: "hit_1".to_string(),
+                    widget_id: Some("card".to_string()),
+                    x: 8.0,
+                    y: 2.5,
+                },
+                json!({"HitTestResult": {"request_id": "hit_1", "widget_id": "card", "x": 8.0, "y": 2.5}}),
+            ),
+            (
+                UiEvent::FrameTick { timestamp: 16.5 },
+                json!({"FrameTick": {"timestamp": 16.5}}),
+            ),
+            (
+                UiEvent::NativeOpResult {
+                    request_id: "op_1".to_string(),
+                    result: None,
+                    error: Some("No native op named 'cad.open'".to_string()),
+                },
+                json!({"NativeOpResult": {"request_id": "op_1", "result": null, "error": "No native op named 'cad.open'"}}),
+            ),
+            (
+                UiEvent::RecentCommands {
+                    request_id: "recent_1".to_string(),
+                    commands: vec![CommandRecord {
+                        seq: 4,
+                        name: "SetWidgetText".to_string(),
+                        widget_id: Some("title".to_string()),
+                        timestamp: 12.5,
+                    }],
+                },
+                json!({"RecentCommands": {"request_id": "recent_1", "commands": [{"seq": 4, "name": "SetWidgetText", "widget_id": "title", "timestamp": 12.5}]}}),
+            ),
+            (
+                UiEvent::Metrics {
+                    request_id: "metrics_1".to_string(),
+                    widget_count: 12,
+                    videos: vec![VideoMetrics {
+                        id: "intro".to_string(),
+                        decoder: Some(VideoDecoder {
+                            name: "vah264dec".to_string(),
+                            hardware: true,
+                        }),
+                    }],
+                },
+                json!({"Metrics": {"request_id": "metrics_1", "widget_count": 12, "videos": [{"id": "intro", "decoder": {"name": "vah264dec", "hardware": true}}]}}),
+            ),
+            (
+                UiEvent::DefaultDeviceChanged {
+                    device: Some(AudioDevice {
+                        id: "alsa_output.usb-headset".to_string(),
+                        name: "USB Headset".to_string(),
+                        is_default: true,
+                    }),
+                },
+                json!({"DefaultDeviceChanged": {"device": {"id": "alsa_output.usb-headset", "name": "USB Headset", "is_default": true}}}),
+            ),
+            (
+                UiEvent::DefaultDeviceChanged { device: None },
+                json!({"DefaultDeviceChanged": {"device": null}}),
+            ),
+            (
+                UiEvent::PrintResult {
+                    request_id: "print_1".to_string(),
+                    pages: 3,
+                    error: None,
+                },
+                json!({"PrintResult": {"request_id": "print_1", "pages": 3, "error": null}}),
+            ),
+            (
+                UiEvent::JournalChanged {
+                    can_undo: true,
+                    can_redo: false,
+                },
+                json!({"JournalChanged": {"can_undo": true, "can_redo": false}}),
+            ),
+            (
+                UiEvent::VisibilityChanged {
+                    id: "hero".to_string(),
+                    ratio: 0.5,
+                },
+                json!({"VisibilityChanged": {"id": "hero", "ratio": 0.5}}),
+            ),
+            (
+                UiEvent::WidgetResized {
+                    id: "gallery".to_string(),
+                    width: 640.0,
+                    height: 480.5,
+                },
+                json!({"WidgetResized": {"id": "gallery", "width": 640.0, "height": 480.5}}),
+            ),
+            (
+                UiEvent::UpdateCheckResult {
+                    request_id: "update_1".to_string(),
+                    version: Some("1.4.0".to_string()),
+                    notes: None,
+                    error: None,
+                },
+                json!({"UpdateCheckResult": {"request_id": "update_1", "version": "1.4.0", "notes": null, "error": null}}),
+            ),
+            (
+                UiEvent::UpdateProgress {
+                    downloaded: 4096,
+                    total: None,
+                },
+                json!({"UpdateProgress": {"downloaded": 4096, "total": null}}),
+            ),
+        ];
+
+        for (event, expected) in cases {
+            let name = format!("{event:?}");
+            assert_eq!(
+                wire_layout(event),
+                json!({"type": "uiEvent", "event": expected, "seq": 7, "timestamp": 1.5}),
+                "{name}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_tree_snapshot_is_sent_as_binary() {
+        // msgpack `bin`, which the bridge decodes to a Uint8Array.
+        let event = UiEvent::TreeSnapshot {
+            request_id: "tree_1".to_string(),
+            snapshot: vec![0x81, 0xa1],
+        };
+        let bytes = rmp_serde::to_vec_named(&event).unwrap();
+        assert!(bytes.windows(4).any(|w| w == [0xc4, 2, 0x81, 0xa1]));
+    }
 }
@@ -6,9 +6,12 @@ pub mod color;
 pub mod commands;
 pub mod events;
 pub mod msgpack;
+pub mod paint;
 pub mod server;
+pub mod session;
 
 pub use channels::*;
 pub use color::ColorValue;
 pub use commands::*;
 pub use events::*;
+pub use paint::PaintOp;
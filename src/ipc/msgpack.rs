@@ -1,20 +1,30 @@
+use std::collections::HashMap;
 use std::io::{self, Read, Write};
 
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 
-use super::UiEvent;
+use super::{ChannelValue, DockMenuItem, ErrorCode, LayoutDirection, PaintOp, UiEvent};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "camelCase")]
 pub enum ServerMessage {
     UiEvent {
         event: UiEvent,
+        seq: u64,
+        timestamp: f64,
     },
     RuntimeError {
         source: String,
+        code: ErrorCode,
         message: String,
         fatal: bool,
+        widget_id: Option<String>,
+        command: Option<String>,
+        /// Set when the error was sent as a `UiEvent`; the bridge's own
+        /// socket errors have no place in the event order
+        seq: Option<u64>,
+        timestamp: Option<f64>,
     },
     Shutdown,
 }
@@ -25,6 +35,9 @@ pub enum ClientMessage {
     SetTitle {
         title: String,
     },
+    SetContentProtection {
+        enabled: bool,
+    },
     CreateWidget {
         id: String,
         kind: String,
@@ -38,6 +51,22 @@ pub enum ClientMessage {
     RemoveWidget {
         id: String,
     },
+    ValidateTree {
+        #[serde(default)]
+        repair: bool,
+    },
+    ShowErrorOverlay {
+        title: String,
+        details: String,
+    },
+    SetSplash {
+        #[serde(default, with = "serde_bytes")]
+        image: Option<Vec<u8>>,
+        #[serde(default)]
+        message: Option<String>,
+        #[serde(default)]
+        progress: Option<f64>,
+    },
     SetWidgetText {
         id: String,
         text: String,
@@ -46,6 +75,10 @@ pub enum ClientMessage {
         id: String,
         visible: bool,
     },
+    SetWidgetEnabled {
+        id: String,
+        enabled: bool,
+    },
     SetWidgetStyle {
         id: String,
         style_json: String,
@@ -55,10 +88,52 @@ pub enum ClientMessage {
         property: String,
         value: String,
     },
+    SetPaintOps {
+        id: String,
+        ops: Vec<PaintOp>,
+    },
+    SetShader {
+        id: String,
+        wgsl: String,
+    },
+    SetShaderUniforms {
+        id: String,
+        values: Vec<f32>,
+    },
+    SetRawProperty {
+        id: String,
+        name: String,
+        json: String,
+    },
     SetWidgetValue {
         id: String,
         value: f64,
     },
+    SetProgressIndeterminate {
+        id: String,
+        indeterminate: bool,
+    },
+    SetProgressBuffer {
+        id: String,
+        buffer: Option<f64>,
+    },
+    SetWidgetRange {
+        id: String,
+        low: f64,
+        high: f64,
+    },
+    SetBadge {
+        id: String,
+        badge_json: Option<String>,
+    },
+    SetAvatarStatus {
+        id: String,
+        status: Option<String>,
+    },
+    RegisterIconPack {
+        prefix: String,
+        icons: HashMap<String, String>,
+    },
     SetWidgetChecked {
         id: String,
         checked: bool,
@@ -68,7 +143,127 @@ pub enum ClientMessage {
         height: u32,
     },
     CloseWindow,
-    ExitApp,
+    ExitApp {
+        #[serde(default)]
+        code: i32,
+    },
+    SetDockMenu {
+        items: Vec<DockMenuItem>,
+    },
+    InhibitSleep {
+        token: String,
+        reason: String,
+    },
+    ReleaseSleep {
+        token: String,
+    },
+    SetLayoutDirection {
+        direction: LayoutDirection,
+    },
+    SetUiScale {
+        factor: f64,
+    },
+    ListFonts {
+        request_id: String,
+    },
+    HitTest {
+        request_id: String,
+        x: f64,
+        y: f64,
+    },
+    DumpRecentCommands {
+        request_id: String,
+    },
+    GetMetrics {
+        request_id: String,
+    },
+    PrintWindow {
+        request_id: String,
+    },
+    ExportPdf {
+        request_id: String,
+        path: String,
+        #[serde(default)]
+        widget_id: Option<String>,
+    },
+    SerializeTree {
+        request_id: String,
+    },
+    RestoreTree {
+        #[serde(with = "serde_bytes")]
+        snapshot: Vec<u8>,
+    },
+    SetJournal {
+        enabled: bool,
+        #[serde(default)]
+        limit: Option<usize>,
+    },
+    BeginUndoGroup,
+    EndUndoGroup,
+    Undo,
+    Redo,
+    ObserveVisibility {
+        id: String,
+        #[serde(default)]
+        threshold: f64,
+    },
+    UnobserveVisibility {
+        id: String,
+    },
+    ObserveResize {
+        id: String,
+    },
+    UnobserveResize {
+        id: String,
+    },
+    CheckForUpdate {
+        request_id: String,
+        url: String,
+        current_version: String,
+    },
+    DownloadUpdate,
+    RestartToUpdate,
+    SetFrameTicks {
+        enabled: bool,
+    },
+    SetGestureTiming {
+        double_click_ms: Option<u64>,
+        long_press_ms: Option<u64>,
+    },
+    BindValue {
+        id: String,
+        property: String,
+        channel: String,
+    },
+    UnbindValue {
+        id: String,
+        property: String,
+    },
+    PushChannel {
+        channel: String,
+        value: ChannelValue,
+    },
+    AnimateValue {
+        id: String,
+        property: String,
+        from: f64,
+        to: f64,
+        duration_ms: f64,
+        #[serde(default)]
+        easing: Option<String>,
+        #[serde(default)]
+        repeat: u32,
+    },
+    StopAnimation {
+        id: String,
+        #[serde(default)]
+        property: Option<String>,
+    },
+    CallNativeOp {
+        request_id: String,
+        name: String,
+        args_json: String,
+    },
     SetImageData {
         id: String,
         #[serde(with = "serde_bytes")]
@@ -84,6 +279,24 @@ pub enum ClientMessage {
         id: String,
         time_secs: f64,
     },
+    SetVideoVolume {
+        id: String,
+        volume: f64,
+    },
+    SetVideoMuted {
+        id: String,
+        muted: bool,
+    },
+    ListAudioDevices {
+        request_id: String,
+    },
+    SetAudioOutput {
+        #[serde(default)]
+        device: Option<String>,
+    },
+    EndRefresh {
+        id: String,
+    },
 }
 
 pub fn write_msgpack_frame<W, T>(writer: &mut W, value: &T) -> io::Result<()>
@@ -0,0 +1,117 @@
+use serde::{Deserialize, Serialize};
+
+use super::color::ColorValue;
+
+fn default_line_width() -> f64 {
+    1.0
+}
+
+/// One entry of a CustomPaint display list. Coordinates are logical pixels in
+/// the widget's local space; transforms apply to the ops that follow them, up
+/// to the matching `Restore`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum PaintOp {
+    FillRect {
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+        color: ColorValue,
+        #[serde(default)]
+        radius: f64,
+    },
+    StrokeRect {
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+        color: ColorValue,
+        #[serde(default = "default_line_width")]
+        line_width: f64,
+        #[serde(default)]
+        radius: f64,
+    },
+    FillCircle {
+        cx: f64,
+        cy: f64,
+        r: f64,
+        color: ColorValue,
+    },
+    StrokeCircle {
+        cx: f64,
+        cy: f64,
+        r: f64,
+        color: ColorValue,
+        #[serde(default = "default_line_width")]
+        line_width: f64,
+    },
+    Line {
+        x1: f64,
+        y1: f64,
+        x2: f64,
+        y2: f64,
+        color: ColorValue,
+        #[serde(default = "default_line_width")]
+        line_width: f64,
+    },
+    /// SVG path data (`M0 0 L10 10 Z`), filled and/or stroked
+    Path {
+        d: String,
+        fill: Option<ColorValue>,
+        stroke: Option<ColorValue>,
+        #[serde(default = "default_line_width")]
+        line_width: f64,
+    },
+    /// Push the current transform
+    Save,
+    /// Pop back to the transform at the matching `Save`
+    Restore,
+    Translate {
+        x: f64,
+        y: f64,
+    },
+    Scale {
+        x: f64,
+        y: f64,
+    },
+    Rotate {
+        radians: f64,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paint_ops_deserialize() {
+        let ops: Vec<PaintOp> = serde_json::from_str(
+            r##"[
+                {"op": "fillRect", "x": 0, "y": 0, "width": 10, "height": 5, "color": "#ff0000"},
+                {"op": "line", "x1": 0, "y1": 0, "x2": 4, "y2": 4, "color": "white", "lineWidth": 2},
+                {"op": "path", "d": "M0 0 L4 4", "stroke": "black"},
+                {"op": "save"},
+                {"op": "rotate", "radians": 1.5},
+                {"op": "restore"}
+            ]"##,
+        )
+        .unwrap();
+        assert_eq!(ops.len(), 6);
+        assert!(matches!(ops[0], PaintOp::FillRect { radius, .. } if radius == 0.0));
+        assert!(matches!(ops[1], PaintOp::Line { line_width, .. } if line_width == 2.0));
+        assert!(matches!(
+            &ops[2],
+            PaintOp::Path { fill: None, stroke: Some(_), line_width, .. } if *line_width == 1.0
+        ));
+        assert!(matches!(ops[3], PaintOp::Save));
+    }
+
+    #[test]
+    fn test_unknown_op_is_rejected() {
+        assert!(serde_json::from_str::<PaintOp>(r#"{"op": "fillText", "text": "hi"}"#).is_err());
+        assert!(
+            serde_json::from_str::<PaintOp>(r#"{"op": "fillCircle", "cx": 1, "cy": 1}"#).is_err()
+        );
+    }
+}
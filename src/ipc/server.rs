@@ -4,59 +4,168 @@
 // removed pub mod style_parser;
 
 use std::io::ErrorKind;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc::{self, RecvTimeoutError};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
+use crate::assets;
 use crate::ipc::msgpack::{ClientMessage, ServerMessage, read_msgpack_frame, write_msgpack_frame};
-use crate::ipc::{BoxStyle, ClientCommand, IpcServerChannels, UiEvent, WidgetData, WidgetKind};
-use crate::socket::{bind_socket, get_socket_path};
+use crate::ipc::session;
+use crate::ipc::{
+    BadgeValue, BoxStyle, ButtonFeedbackParams, ClientCommand, ClientCommandSender, ColorValue,
+    ErrorCode, ExitReason, HandshakeStatus, IpcServerChannels, StampedEvent, Symbology, UiEvent,
+    UiEventReceiver, WidgetData, WidgetKind, parse_avatar_status, parse_badge_spec,
+    parse_badge_value, parse_button_variant,
+};
+use crate::socket::{UnixStream, bind_socket};
 
 /// Run the JS runtime bridge on a background thread.
 ///
 /// This binds a Unix Domain Socket and communicates via
-/// length-prefixed MsgPack frames.
-pub fn run_ipc_server(channels: IpcServerChannels) {
-    if let Err(e) = run_socket_server(channels) {
+/// length-prefixed MsgPack frames. With `accept_reconnects` (hosted mode) a
+/// restarted client runtime may connect again after the previous one exits.
+pub fn run_ipc_server(channels: IpcServerChannels, socket_path: String, accept_reconnects: bool) {
+    if let Err(e) = run_socket_server(channels, socket_path, accept_reconnects) {
         eprintln!("[IPC] Runtime socket error: {e}");
     }
 }
 
+/// How long to wait for the first client before offering a retry.
+const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+/// A client that connects within this long never sees the countdown.
+const COUNTDOWN_DELAY: Duration = Duration::from_secs(3);
+/// How long a closing connection waits for queued frames to go out.
+const SHUTDOWN_FLUSH: Duration = Duration::from_secs(1);
+
+/// Set by the splash's Retry button.
+static RETRY_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+/// Start the wait for the first client over after it timed out.
+pub fn retry_handshake() {
+    RETRY_REQUESTED.store(true, Ordering::Release);
+}
+
+/// `VELLUM_CONNECT_TIMEOUT` in seconds; 0 waits forever.
+fn connect_timeout() -> Option<Duration> {
+    match std::env::var("VELLUM_CONNECT_TIMEOUT") {
+        Ok(value) => parse_connect_timeout(&value),
+        Err(_) => Some(DEFAULT_CONNECT_TIMEOUT),
+    }
+}
+
+fn parse_connect_timeout(value: &str) -> Option<Duration> {
+    match value.trim().parse::<f64>() {
+        Ok(secs) if secs > 0.0 && secs.is_finite() => Some(Duration::from_secs_f64(secs)),
+        Ok(_) => None,
+        Err(_) => {
+            eprintln!("[IPC] Ignoring invalid VELLUM_CONNECT_TIMEOUT '{value}'");
+            Some(DEFAULT_CONNECT_TIMEOUT)
+        }
+    }
+}
+
+/// The wait for the first client: a countdown on the splash, then a timeout
+/// the user can retry.
+struct HandshakeWait {
+    timeout: Duration,
+    started: Instant,
+    /// Last countdown value sent, so each second is sent once
+    shown: Option<u64>,
+    timed_out: bool,
+}
+
+impl HandshakeWait {
+    fn new(timeout: Duration, now: Instant) -> Self {
+        Self {
+            timeout,
+            started: now,
+            shown: None,
+            timed_out: false,
+        }
+    }
+
+    /// The splash update due at `now`, if any.
+    fn poll(&mut self, now: Instant) -> Option<HandshakeStatus> {
+        if self.timed_out {
+            return None;
+        }
+        let waited = now.saturating_duration_since(self.started);
+        if waited >= self.timeout {
+            self.timed_out = true;
+            return Some(HandshakeStatus::TimedOut {
+                timeout_secs: self.timeout.as_secs_f64().round() as u64,
+            });
+        }
+        if waited < COUNTDOWN_DELAY {
+            return None;
+        }
+        let remaining_secs = (self.timeout - waited).as_secs_f64().ceil() as u64;
+        if self.shown == Some(remaining_secs) {
+            return None;
+        }
+        self.shown = Some(remaining_secs);
+        Some(HandshakeStatus::Waiting { remaining_secs })
+    }
+
+    /// Whether the splash shows anything that a connection should clear.
+    fn visible(&self) -> bool {
+        self.shown.is_some() || self.timed_out
+    }
+}
+
 #[derive(Debug)]
 struct RuntimeErrorReport {
     source: String,
+    code: ErrorCode,
     message: String,
     fatal: bool,
 }
 
-fn write_runtime_error(
-    stream: &mut impl std::io::Write,
-    source: impl Into<String>,
-    message: impl Into<String>,
-    fatal: bool,
-) -> std::io::Result<()> {
-    write_msgpack_frame(
-        stream,
-        &ServerMessage::RuntimeError {
-            source: source.into(),
-            message: message.into(),
-            fatal,
-        },
-    )
+impl RuntimeErrorReport {
+    fn into_message(self) -> ServerMessage {
+        ServerMessage::RuntimeError {
+            source: self.source,
+            code: self.code,
+            message: self.message,
+            fatal: self.fatal,
+            widget_id: None,
+            command: None,
+            seq: None,
+            timestamp: None,
+        }
+    }
 }
 
-fn runtime_error_from_ui_event(event: UiEvent) -> ServerMessage {
+fn runtime_error_from_ui_event(stamped: StampedEvent) -> ServerMessage {
+    let StampedEvent {
+        seq,
+        timestamp,
+        event,
+    } = stamped;
     match event {
         UiEvent::RuntimeError {
             source,
+            code,
             message,
             fatal,
+            widget_id,
+            command,
         } => ServerMessage::RuntimeError {
             source,
+            code,
             message,
             fatal,
+            widget_id,
+            command,
+            seq: Some(seq),
+            timestamp: Some(timestamp),
+        },
+        other => ServerMessage::UiEvent {
+            event: other,
+            seq,
+            timestamp,
         },
-        other => ServerMessage::UiEvent { event: other },
     }
 }
 
@@ -64,7 +173,8 @@ fn parse_widget_kind(kind: &str) -> WidgetKind {
     match kind {
         "Label" | "label" => WidgetKind::Label,
         "Button" | "button" => WidgetKind::Button,
-        "Svg" | "svg" | "svgIcon" | "svg_icon" | "icon" => WidgetKind::Svg,
+        "Svg" | "svg" | "svgIcon" | "svg_icon" => WidgetKind::Svg,
+        "Icon" | "icon" => WidgetKind::Icon,
         "TextInput" | "textInput" | "text_input" => WidgetKind::TextInput,
         "TextArea" | "textArea" | "text_area" => WidgetKind::TextArea,
         "Checkbox" | "checkbox" => WidgetKind::Checkbox,
@@ -74,17 +184,34 @@ fn parse_widget_kind(kind: &str) -> WidgetKind {
         "ProgressBar" | "progressBar" | "progress_bar" | "progress" => WidgetKind::ProgressBar,
         "Spinner" | "spinner" | "loading" => WidgetKind::Spinner,
         "Slider" | "slider" | "range" => WidgetKind::Slider,
+        "RangeSlider" | "rangeSlider" | "range_slider" => WidgetKind::RangeSlider,
         "Image" | "image" | "img" => WidgetKind::Image,
         "Prose" | "prose" => WidgetKind::Prose,
         "Grid" | "grid" => WidgetKind::Grid,
         "ZStack" | "zstack" | "z_stack" | "stack" => WidgetKind::ZStack,
         "Portal" | "portal" | "scroll" => WidgetKind::Portal,
+        "ReorderableList" | "reorderableList" | "reorderable_list" => WidgetKind::ReorderableList,
         "Hoverable" | "hoverable" => WidgetKind::Hoverable,
         "Video" | "video" => WidgetKind::Video,
+        "Avatar" | "avatar" => WidgetKind::Avatar,
+        "Skeleton" | "skeleton" | "placeholder" => WidgetKind::Skeleton,
+        "QrCode" | "qrCode" | "qrcode" => WidgetKind::QrCode,
+        "CustomPaint" | "customPaint" | "custom_paint" | "canvas" => WidgetKind::CustomPaint,
+        "GpuSurface" | "gpuSurface" | "gpu_surface" | "shader" => WidgetKind::GpuSurface,
         other => WidgetKind::Custom(other.to_string()),
     }
 }
 
+/// Replace an `asset://` reference with the SVG markup it points to.
+fn load_svg_asset(source: String) -> String {
+    if !source.starts_with(assets::SCHEME) {
+        return source;
+    }
+    assets::load(&source)
+        .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+        .unwrap_or_default()
+}
+
 fn parse_padding_shorthand(raw: &str) -> Option<serde_json::Value> {
     let values: Vec<f64> = raw
         .split(',')
@@ -134,9 +261,12 @@ fn parse_box_style_lossy(style_json: &str) -> Option<BoxStyle> {
     serde_json::from_value::<BoxStyle>(value).ok()
 }
 
-fn handle_client_message(message: ClientMessage) -> Option<ClientCommand> {
+pub fn handle_client_message(message: ClientMessage) -> Option<ClientCommand> {
     match message {
         ClientMessage::SetTitle { title } => Some(ClientCommand::SetTitle(title)),
+        ClientMessage::SetContentProtection { enabled } => {
+            Some(ClientCommand::SetContentProtection(enabled))
+        }
         ClientMessage::CreateWidget {
             id,
             kind,
@@ -147,6 +277,14 @@ fn handle_client_message(message: ClientMessage) -> Option<ClientCommand> {
             data,
         } => {
             let parsed_kind = parse_widget_kind(&kind);
+            let text = match parsed_kind {
+                WidgetKind::Svg => text.map(load_svg_asset),
+                _ => text,
+            };
+            let badge = widget_params_json
+                .as_deref()
+                .and_then(|json| serde_json::from_str::<serde_json::Value>(json).ok())
+                .and_then(|params| params.get("badge").and_then(parse_badge_spec));
             let widget_data = build_widget_data(
                 &parsed_kind,
                 style_json.as_deref(),
@@ -160,15 +298,32 @@ fn handle_client_message(message: ClientMessage) -> Option<ClientCommand> {
                 text,
                 style: style_json.as_deref().and_then(parse_box_style_lossy),
                 data: widget_data,
+                badge,
             })
         }
         ClientMessage::RemoveWidget { id } => Some(ClientCommand::RemoveWidget { id }),
+        ClientMessage::ValidateTree { repair } => Some(ClientCommand::ValidateTree { repair }),
+        ClientMessage::ShowErrorOverlay { title, details } => {
+            Some(ClientCommand::ShowErrorOverlay { title, details })
+        }
+        ClientMessage::SetSplash {
+            image,
+            message,
+            progress,
+        } => Some(ClientCommand::SetSplash {
+            image,
+            message,
+            progress,
+        }),
         ClientMessage::SetWidgetText { id, text } => {
             Some(ClientCommand::SetWidgetText { id, text })
         }
         ClientMessage::SetWidgetVisible { id, visible } => {
             Some(ClientCommand::SetWidgetVisible { id, visible })
         }
+        ClientMessage::SetWidgetEnabled { id, enabled } => {
+            Some(ClientCommand::SetWidgetEnabled { id, enabled })
+        }
         ClientMessage::SetWidgetStyle { id, style_json } => Some(ClientCommand::SetWidgetStyle {
             id,
             style: parse_box_style_lossy(&style_json).unwrap_or_default(),
@@ -182,9 +337,49 @@ fn handle_client_message(message: ClientMessage) -> Option<ClientCommand> {
             property,
             value,
         }),
+        ClientMessage::SetPaintOps { id, ops } => Some(ClientCommand::SetPaintOps { id, ops }),
+        ClientMessage::SetShader { id, wgsl } => Some(ClientCommand::SetShader { id, wgsl }),
+        ClientMessage::SetShaderUniforms { id, values } => {
+            Some(ClientCommand::SetShaderUniforms { id, values })
+        }
+        ClientMessage::SetRawProperty { id, name, json } => {
+            Some(ClientCommand::SetRawProperty { id, name, json })
+        }
         ClientMessage::SetWidgetValue { id, value } => {
             Some(ClientCommand::SetWidgetValue { id, value })
         }
+        ClientMessage::SetProgressIndeterminate { id, indeterminate } => {
+            Some(ClientCommand::SetProgressIndeterminate { id, indeterminate })
+        }
+        ClientMessage::SetProgressBuffer { id, buffer } => {
+            Some(ClientCommand::SetProgressBuffer { id, buffer })
+        }
+        ClientMessage::SetWidgetRange { id, low, high } => {
+            Some(ClientCommand::SetWidgetRange { id, low, high })
+        }
+        ClientMessage::SetAvatarStatus { id, status } => Some(ClientCommand::SetAvatarStatus {
+            id,
+            status: status.as_deref().and_then(parse_avatar_status),
+        }),
+        ClientMessage::RegisterIconPack { prefix, icons } => {
+            Some(ClientCommand::RegisterIconPack { prefix, icons })
+        }
+        ClientMessage::SetBadge { id, badge_json } => {
+            let value = match badge_json
+                .as_deref()
+                .and_then(|json| serde_json::from_str::<serde_json::Value>(json).ok())
+            {
+                Some(value) => match parse_badge_value(&value) {
+                    Some(parsed) => parsed,
+                    None => {
+                        eprintln!("[IPC] Ignoring invalid badge value {value} for widget '{id}'");
+                        return None;
+                    }
+                },
+                None => BadgeValue::Hidden,
+            };
+            Some(ClientCommand::SetBadge { id, value })
+        }
         ClientMessage::SetWidgetChecked { id, checked } => {
             Some(ClientCommand::SetWidgetChecked { id, checked })
         }
@@ -192,13 +387,138 @@ fn handle_client_message(message: ClientMessage) -> Option<ClientCommand> {
             Some(ClientCommand::ResizeWindow { width, height })
         }
         ClientMessage::CloseWindow => Some(ClientCommand::CloseWindow),
-        ClientMessage::ExitApp => Some(ClientCommand::ExitApp),
+        ClientMessage::ExitApp { code } => Some(ClientCommand::ExitApp {
+            code,
+            reason: ExitReason::App,
+        }),
+        ClientMessage::SetDockMenu { items } => Some(ClientCommand::SetDockMenu { items }),
+        ClientMessage::InhibitSleep { token, reason } => {
+            Some(ClientCommand::InhibitSleep { token, reason })
+        }
+        ClientMessage::ReleaseSleep { token } => Some(ClientCommand::ReleaseSleep { token }),
+        ClientMessage::SetLayoutDirection { direction } => {
+            Some(ClientCommand::SetLayoutDirection { direction })
+        }
+        ClientMessage::SetUiScale { factor } => Some(ClientCommand::SetUiScale { factor }),
+        ClientMessage::ListFonts { request_id } => Some(ClientCommand::ListFonts { request_id }),
+        ClientMessage::HitTest { request_id, x, y } => {
+            Some(ClientCommand::HitTest { request_id, x, y })
+        }
+        ClientMessage::DumpRecentCommands { request_id } => {
+            Some(ClientCommand::DumpRecentCommands { request_id })
+        }
+        ClientMessage::GetMetrics { request_id } => Some(ClientCommand::GetMetrics { request_id }),
+        ClientMessage::PrintWindow { request_id } => {
+            Some(ClientCommand::PrintWindow { request_id })
+        }
+        ClientMessage::ExportPdf {
+            request_id,
+            path,
+            widget_id,
+        } => Some(ClientCommand::ExportPdf {
+            request_id,
+            path,
+            widget_id,
+        }),
+        ClientMessage::SerializeTree { request_id } => {
+            Some(ClientCommand::SerializeTree { request_id })
+        }
+        ClientMessage::RestoreTree { snapshot } => Some(ClientCommand::RestoreTree { snapshot }),
+        ClientMessage::SetJournal { enabled, limit } => {
+            Some(ClientCommand::SetJournal { enabled, limit })
+        }
+        ClientMessage::BeginUndoGroup => Some(ClientCommand::BeginUndoGroup),
+        ClientMessage::EndUndoGroup => Some(ClientCommand::EndUndoGroup),
+        ClientMessage::Undo => Some(ClientCommand::Undo),
+        ClientMessage::Redo => Some(ClientCommand::Redo),
+        ClientMessage::ObserveVisibility { id, threshold } => {
+            Some(ClientCommand::ObserveVisibility { id, threshold })
+        }
+        ClientMessage::UnobserveVisibility { id } => {
+            Some(ClientCommand::UnobserveVisibility { id })
+        }
+        ClientMessage::ObserveResize { id } => Some(ClientCommand::ObserveResize { id }),
+        ClientMessage::UnobserveResize { id } => Some(ClientCommand::UnobserveResize { id }),
+        ClientMessage::CheckForUpdate {
+            request_id,
+            url,
+            current_version,
+        } => Some(ClientCommand::CheckForUpdate {
+            request_id,
+            url,
+            current_version,
+        }),
+        ClientMessage::DownloadUpdate => Some(ClientCommand::DownloadUpdate),
+        ClientMessage::RestartToUpdate => Some(ClientCommand::RestartToUpdate),
+        ClientMessage::SetFrameTicks { enabled } => Some(ClientCommand::SetFrameTicks { enabled }),
+        ClientMessage::SetGestureTiming {
+            double_click_ms,
+            long_press_ms,
+        } => Some(ClientCommand::SetGestureTiming {
+            double_click_ms,
+            long_press_ms,
+        }),
+        ClientMessage::BindValue {
+            id,
+            property,
+            channel,
+        } => Some(ClientCommand::BindValue {
+            id,
+            property,
+            channel,
+        }),
+        ClientMessage::UnbindValue { id, property } => {
+            Some(ClientCommand::UnbindValue { id, property })
+        }
+        ClientMessage::PushChannel { channel, value } => {
+            Some(ClientCommand::PushChannel { channel, value })
+        }
+        ClientMessage::AnimateValue {
+            id,
+            property,
+            from,
+            to,
+            duration_ms,
+            easing,
+            repeat,
+        } => Some(ClientCommand::AnimateValue {
+            id,
+            property,
+            from,
+            to,
+            duration_ms,
+            easing: easing.unwrap_or_else(|| "linear".to_string()),
+            repeat,
+        }),
+        ClientMessage::StopAnimation { id, property } => {
+            Some(ClientCommand::StopAnimation { id, property })
+        }
+        ClientMessage::CallNativeOp {
+            request_id,
+            name,
+            args_json,
+        } => Some(ClientCommand::CallNativeOp {
+            request_id,
+            name,
+            args_json,
+        }),
         ClientMessage::SetImageData { id, data } => Some(ClientCommand::SetImageData { id, data }),
         ClientMessage::PlayVideo { id } => Some(ClientCommand::PlayVideo { id }),
         ClientMessage::PauseVideo { id } => Some(ClientCommand::PauseVideo { id }),
         ClientMessage::SeekVideo { id, time_secs } => {
             Some(ClientCommand::SeekVideo { id, time_secs })
         }
+        ClientMessage::SetVideoVolume { id, volume } => {
+            Some(ClientCommand::SetVideoVolume { id, volume })
+        }
+        ClientMessage::SetVideoMuted { id, muted } => {
+            Some(ClientCommand::SetVideoMuted { id, muted })
+        }
+        ClientMessage::ListAudioDevices { request_id } => {
+            Some(ClientCommand::ListAudioDevices { request_id })
+        }
+        ClientMessage::SetAudioOutput { device } => Some(ClientCommand::SetAudioOutput { device }),
+        ClientMessage::EndRefresh { id } => Some(ClientCommand::EndRefresh { id }),
     }
 }
 
@@ -272,23 +592,54 @@ fn build_widget_data(
     };
 
     match kind {
-        WidgetKind::Label => Some(WidgetData::Label),
+        WidgetKind::Label => Some(WidgetData::Label {
+            selectable: get_bool("selectable").unwrap_or(false),
+        }),
 
-        WidgetKind::Button => None,
+        WidgetKind::Button => Some(WidgetData::Button {
+            accelerator: get_string("accelerator"),
+            variant: get_string("variant").and_then(|raw| {
+                let variant = parse_button_variant(&raw);
+                if variant.is_none() {
+                    eprintln!("[IPC] Ignoring unknown button variant '{raw}'");
+                }
+                variant
+            }),
+            feedback: ButtonFeedbackParams {
+                hover_lighten: get_f64("hoverLighten").map(|amount| amount.clamp(0.0, 1.0)),
+                press_darken: get_f64("pressDarken").map(|amount| amount.clamp(0.0, 1.0)),
+                ripple: get_bool("ripple"),
+            },
+        }),
 
         WidgetKind::Svg => {
             let svg_data = get_string("svgData")
                 .or_else(|| get_string("svg_data"))
-                .or_else(|| get_string("svg"));
-            Some(WidgetData::Svg { svg_data })
+                .or_else(|| get_string("svg"))
+                .or_else(|| get_string("src"))
+                .map(load_svg_asset);
+            let resources_dir = get_string("resourcesDir")
+                .or_else(|| get_string("resources_dir"))
+                .or_else(|| get_string("baseDir"));
+            let style_sheet = get_string("styleSheet")
+                .or_else(|| get_string("style_sheet"))
+                .or_else(|| get_string("css"));
+            Some(WidgetData::Svg {
+                svg_data,
+                resources_dir,
+                style_sheet,
+                alt: get_string("alt"),
+            })
         }
 
         WidgetKind::Image => {
-            let image_data = data?;
+            let image_data =
+                data.or_else(|| get_string("src").and_then(|src| assets::load(&src)))?;
             let object_fit = get_string("object_fit").or_else(|| get_string("objectFit"));
             Some(WidgetData::Image {
                 data: image_data,
                 object_fit,
+                alt: get_string("alt"),
             })
         }
 
@@ -311,7 +662,13 @@ fn build_widget_data(
 
         WidgetKind::ProgressBar => {
             let progress = get_f64("progress").or_else(|| get_f64("value"));
-            Some(WidgetData::ProgressBar { progress })
+            let buffer = get_f64("buffer").or_else(|| get_f64("buffered"));
+            let indeterminate = get_bool("indeterminate").unwrap_or(false);
+            Some(WidgetData::ProgressBar {
+                progress,
+                buffer,
+                indeterminate,
+            })
         }
 
         WidgetKind::Spinner => Some(WidgetData::Spinner),
@@ -337,38 +694,253 @@ fn build_widget_data(
             })
         }
 
+        WidgetKind::RangeSlider => {
+            let min = get_f64("minValue")
+                .or_else(|| get_f64("min_value"))
+                .or_else(|| get_f64("min"))
+                .unwrap_or(0.0);
+            let max = get_f64("maxValue")
+                .or_else(|| get_f64("max_value"))
+                .or_else(|| get_f64("max"))
+                .unwrap_or(1.0);
+            let low = get_f64("low").unwrap_or(min);
+            let high = get_f64("high").unwrap_or(max);
+            let step = get_f64("step");
+            Some(WidgetData::RangeSlider {
+                min,
+                max,
+                low,
+                high,
+                step,
+            })
+        }
+
         WidgetKind::ZStack => Some(WidgetData::ZStack),
-        WidgetKind::Portal => Some(WidgetData::Portal),
+        WidgetKind::Portal => Some(WidgetData::Portal {
+            pull_to_refresh: get_bool("pullToRefresh")
+                .or_else(|| get_bool("pull_to_refresh"))
+                .unwrap_or(false),
+        }),
         WidgetKind::Grid => Some(WidgetData::Grid),
         WidgetKind::Hoverable => Some(WidgetData::Hoverable),
+        WidgetKind::ReorderableList => Some(WidgetData::ReorderableList),
 
         WidgetKind::Video => {
             let src = get_string("src").unwrap_or_default();
-            Some(WidgetData::Video { src })
+            // The decoders need a real file, so asset URLs become paths.
+            let src = match assets::resolve(&src) {
+                Some(path) => path.to_string_lossy().into_owned(),
+                None => src,
+            };
+            let poster = get_string("poster").and_then(|poster| assets::load(&poster));
+            Some(WidgetData::Video { src, poster })
+        }
+
+        WidgetKind::Avatar => {
+            let color = get_string("color")
+                .or_else(|| get_string("avatarColor"))
+                .and_then(|c| ColorValue::parse(&c));
+            let status = get_string("status").and_then(|s| parse_avatar_status(&s));
+            Some(WidgetData::Avatar {
+                image: data,
+                color,
+                status,
+            })
+        }
+
+        WidgetKind::Skeleton => {
+            let circle = get_bool("circle")
+                .or_else(|| get_string("shape").map(|shape| shape == "circle"))
+                .unwrap_or(false);
+            let color = get_string("color").and_then(|c| ColorValue::parse(&c));
+            let highlight_color = get_string("highlightColor")
+                .or_else(|| get_string("highlight_color"))
+                .and_then(|c| ColorValue::parse(&c));
+            Some(WidgetData::Skeleton {
+                circle,
+                color,
+                highlight_color,
+            })
+        }
+
+        WidgetKind::Icon => {
+            let svg_data = get_string("svgData").or_else(|| get_string("svg_data"));
+            Some(WidgetData::Icon { svg_data })
+        }
+
+        WidgetKind::QrCode => {
+            let symbology = get_string("symbology")
+                .or_else(|| get_string("format"))
+                .and_then(|s| Symbology::parse(&s))
+                .unwrap_or_default();
+            let quiet_zone = get_f64("quietZone")
+                .or_else(|| get_f64("quiet_zone"))
+                .map(|q| q.max(0.0).round() as u32);
+            let color = get_string("color").and_then(|c| ColorValue::parse(&c));
+            let background = get_string("background")
+                .or_else(|| get_string("backgroundColor"))
+                .and_then(|c| ColorValue::parse(&c));
+            Some(WidgetData::QrCode {
+                symbology,
+                quiet_zone,
+                color,
+                background,
+            })
+        }
+
+        WidgetKind::CustomPaint => {
+            let ops = params_value
+                .as_ref()
+                .and_then(|v| v.get("ops"))
+                .and_then(|ops| serde_json::from_value(ops.clone()).ok())
+                .unwrap_or_default();
+            Some(WidgetData::CustomPaint { ops })
+        }
+
+        WidgetKind::GpuSurface => {
+            let uniforms = params_value
+                .as_ref()
+                .and_then(|v| v.get("uniforms"))
+                .and_then(|values| serde_json::from_value(values.clone()).ok())
+                .unwrap_or_default();
+            Some(WidgetData::GpuSurface {
+                shader: get_string("shader"),
+                renderer: get_string("renderer"),
+                uniforms,
+                animate: get_bool("animate").unwrap_or(false),
+            })
         }
 
         WidgetKind::Custom(name) => Some(WidgetData::Custom(name.clone())),
     }
 }
 
+/// Why a client connection stopped being served.
+enum ConnectionEnd {
+    /// The UI thread is gone; the server should stop.
+    UiClosed,
+    /// The client runtime disconnected (exited or crashed).
+    ClientGone,
+    /// A fatal error was reported to the client.
+    Fatal,
+}
+
 fn run_socket_server(
     channels: IpcServerChannels,
+    socket_path: String,
+    accept_reconnects: bool,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     let command_sender = channels.command_sender;
     let event_receiver = channels.event_receiver;
 
-    let socket_path = get_socket_path();
     println!("[IPC] Binding socket to {}", socket_path);
 
     let listener = bind_socket(&socket_path).map_err(|e| format!("Failed to bind socket: {e}"))?;
+    // Poll for clients so the thread notices the UI closing while nobody is connected.
+    listener.set_nonblocking(true)?;
+
+    let mut connections = 0;
+    let mut early_events = Vec::new();
+    // Only the first client is waited on with a timeout: a Bun process that
+    // fails to start would otherwise leave the splash up forever.
+    let mut handshake =
+        connect_timeout().map(|timeout| HandshakeWait::new(timeout, Instant::now()));
+    let end = loop {
+        println!("[IPC] Waiting for client connection...");
+        let stream = loop {
+            match listener.accept() {
+                Ok((stream, _)) => break Some(stream),
+                Err(e) if e.kind() == ErrorKind::WouldBlock => {
+                    match event_receiver.recv_timeout(Duration::from_millis(50)) {
+                        // Events raised before the first client connects are delivered
+                        // once it does; those meant for a crashed client are stale.
+                        Ok(event) if connections == 0 => early_events.push(event),
+                        Ok(_) | Err(RecvTimeoutError::Timeout) => {}
+                        Err(RecvTimeoutError::Disconnected) => break None,
+                    }
+                    if command_sender.is_closed() {
+                        break None;
+                    }
+                    let Some(wait) = handshake.as_mut() else {
+                        continue;
+                    };
+                    if RETRY_REQUESTED.swap(false, Ordering::AcqRel) {
+                        println!("[IPC] Retrying; waiting for client connection...");
+                        *wait = HandshakeWait::new(wait.timeout, Instant::now());
+                    }
+                    if let Some(status) = wait.poll(Instant::now()) {
+                        if let HandshakeStatus::TimedOut { timeout_secs } = status {
+                            eprintln!("[IPC] No client connected within {timeout_secs}s");
+                        }
+                        let _ = command_sender.send(ClientCommand::Handshake(status));
+                    }
+                }
+                Err(e) => return Err(e.into()),
+            }
+        };
+        let Some(stream) = stream else {
+            break ConnectionEnd::UiClosed;
+        };
+        stream.set_nonblocking(false)?;
+        if let Some(wait) = handshake.take()
+            && wait.visible()
+        {
+            let _ = command_sender.send(ClientCommand::Handshake(HandshakeStatus::Connected));
+        }
 
-    println!("[IPC] Waiting for client connection...");
+        if connections > 0 {
+            // A restarted runtime rebuilds the UI from scratch.
+            let _ = command_sender.send(ClientCommand::ResetTree);
+            session::record_reconnect();
+        }
+        connections += 1;
+        println!("[IPC] Client connected");
+
+        let early_events = std::mem::take(&mut early_events);
+        match serve_connection(stream, early_events, &command_sender, &event_receiver)? {
+            ConnectionEnd::ClientGone if accept_reconnects => continue,
+            end => break end,
+        }
+    };
+
+    let _ = std::fs::remove_file(socket_path);
 
-    // We just wait for the first client for now
-    let (mut stream, _) = listener.accept()?;
+    if !matches!(end, ConnectionEnd::UiClosed) {
+        // JS runtime disconnected, exit the UI thread cleanly
+        let _ = command_sender.send(ClientCommand::ExitApp {
+            code: 0,
+            reason: ExitReason::RuntimeExited,
+        });
+    }
+
+    Ok(())
+}
+
+fn serve_connection(
+    stream: UnixStream,
+    early_events: Vec<StampedEvent>,
+    command_sender: &ClientCommandSender,
+    event_receiver: &UiEventReceiver,
+) -> Result<ConnectionEnd, Box<dyn std::error::Error + Send + Sync>> {
     let mut read_stream = stream.try_clone()?;
+    let mut write_stream = stream.try_clone()?;
 
-    println!("[IPC] Client connected");
+    // Frames go out on their own thread, so a client that is slow to read
+    // never holds up error reports or the notice that the UI closed.
+    let (frame_tx, frame_rx) = mpsc::channel::<ServerMessage>();
+    let write_thread = thread::Builder::new()
+        .name("js-bridge-write".to_string())
+        .spawn(move || {
+            for frame in frame_rx {
+                if let Err(e) = write_msgpack_frame(&mut write_stream, &frame) {
+                    eprintln!("[IPC] Socket bridge write failed: {e}");
+                    break;
+                }
+            }
+        })?;
+    for event in early_events {
+        let _ = frame_tx.send(runtime_error_from_ui_event(event));
+    }
 
     let command_sender_clone = command_sender.clone();
     let (error_tx, error_rx) = mpsc::channel::<RuntimeErrorReport>();
@@ -378,16 +950,12 @@ fn run_socket_server(
             loop {
                 match read_msgpack_frame::<_, ClientMessage>(&mut read_stream) {
                     Ok(message) => {
+                        session::record_message(&message);
                         if let Some(cmd) = handle_client_message(message)
-                            && let Err(send_err) = command_sender_clone.send(cmd)
+                            && command_sender_clone.send(cmd).is_err()
                         {
-                            let _ = error_tx.send(RuntimeErrorReport {
-                                source: "ui-thread".to_string(),
-                                message: format!(
-                                    "Failed to dispatch JS command to UI thread: {send_err}"
-                                ),
-                                fatal: true,
-                            });
+                            // The UI event loop exited; the serve loop sees
+                            // the sender closed and says goodbye.
                             break;
                         }
                     }
@@ -397,6 +965,7 @@ fn run_socket_server(
                     Err(e) => {
                         let _ = error_tx.send(RuntimeErrorReport {
                             source: "socket-read".to_string(),
+                            code: ErrorCode::ParseError,
                             message: format!("Failed to decode MsgPack command from JS: {e}"),
                             fatal: false,
                         });
@@ -405,25 +974,16 @@ fn run_socket_server(
             }
         })?;
 
-    let mut should_stop = false;
-
-    while !should_stop {
+    let end = 'serve: loop {
         loop {
             match error_rx.try_recv() {
                 Ok(report) => {
-                    if let Err(write_err) = write_runtime_error(
-                        &mut stream,
-                        report.source,
-                        report.message,
-                        report.fatal,
-                    ) {
-                        eprintln!("[IPC] Failed to send runtimeError frame to JS: {write_err}");
-                        should_stop = true;
-                        break;
+                    let fatal = report.fatal;
+                    if frame_tx.send(report.into_message()).is_err() {
+                        break 'serve ConnectionEnd::ClientGone;
                     }
-                    if report.fatal {
-                        should_stop = true;
-                        break;
+                    if fatal {
+                        break 'serve ConnectionEnd::Fatal;
                     }
                 }
                 Err(mpsc::TryRecvError::Empty) => break,
@@ -431,32 +991,77 @@ fn run_socket_server(
             }
         }
 
-        if should_stop {
-            break;
+        if command_sender.is_closed() {
+            break ConnectionEnd::UiClosed;
+        }
+        // The writer only stops early when a write failed.
+        if read_thread.is_finished() || write_thread.is_finished() {
+            break ConnectionEnd::ClientGone;
         }
 
         match event_receiver.recv_timeout(Duration::from_millis(50)) {
             Ok(event) => {
-                let frame = runtime_error_from_ui_event(event);
-                if let Err(e) = write_msgpack_frame(&mut stream, &frame) {
-                    eprintln!("[IPC] Socket bridge write failed: {e}");
-                    break;
+                if frame_tx.send(runtime_error_from_ui_event(event)).is_err() {
+                    break ConnectionEnd::ClientGone;
                 }
             }
             Err(RecvTimeoutError::Timeout) => {}
-            Err(RecvTimeoutError::Disconnected) => break,
+            Err(RecvTimeoutError::Disconnected) => break ConnectionEnd::UiClosed,
         }
-    }
-
-    let _ = write_msgpack_frame(&mut stream, &ServerMessage::Shutdown);
+    };
 
+    // Flush what is queued, then Shutdown, but don't wait on a client that
+    // stopped reading.
+    let _ = frame_tx.send(ServerMessage::Shutdown);
+    drop(frame_tx);
+    let deadline = Instant::now() + SHUTDOWN_FLUSH;
+    while !write_thread.is_finished() && Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(10));
+    }
+    if !matches!(end, ConnectionEnd::ClientGone) || !write_thread.is_finished() {
+        // Unblock the reader and writer so they can be joined.
+        let _ = stream.shutdown(std::net::Shutdown::Both);
+    }
+    let _ = write_thread.join();
     let _ = read_thread.join();
-    let _ = std::fs::remove_file(socket_path);
 
     println!("[IPC] Socket connection closed");
 
-    // JS runtime disconnected, exit the UI thread cleanly
-    let _ = command_sender.send(ClientCommand::ExitApp);
+    Ok(end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    Ok(())
+    #[test]
+    fn test_parse_connect_timeout() {
+        assert_eq!(parse_connect_timeout("10"), Some(Duration::from_secs(10)));
+        assert_eq!(
+            parse_connect_timeout(" 2.5 "),
+            Some(Duration::from_millis(2500))
+        );
+        assert_eq!(parse_connect_timeout("0"), None);
+        assert_eq!(parse_connect_timeout("soon"), Some(DEFAULT_CONNECT_TIMEOUT));
+    }
+
+    #[test]
+    fn test_handshake_wait_counts_down_then_times_out() {
+        let start = Instant::now();
+        let mut wait = HandshakeWait::new(Duration::from_secs(10), start);
+        assert_eq!(wait.poll(start + Duration::from_secs(1)), None);
+        assert!(!wait.visible());
+        assert_eq!(
+            wait.poll(start + Duration::from_millis(3500)),
+            Some(HandshakeStatus::Waiting { remaining_secs: 7 })
+        );
+        // Each second is sent once.
+        assert_eq!(wait.poll(start + Duration::from_millis(3900)), None);
+        assert_eq!(
+            wait.poll(start + Duration::from_secs(10)),
+            Some(HandshakeStatus::TimedOut { timeout_secs: 10 })
+        );
+        assert_eq!(wait.poll(start + Duration::from_secs(20)), None);
+        assert!(wait.visible());
+    }
 }
@@ -0,0 +1,201 @@
+// Session Record & Replay
+// `vellum --record session.mpk app.ts` logs every client message with its
+// arrival time; `vellum --replay session.mpk` plays the log back into a window
+// without a JS client, for reproducing user-reported rendering bugs and for
+// benchmarking the UI side alone.
+//
+// The file is a sequence of length-prefixed MsgPack frames (the socket's own
+// framing), one `Entry` each, so a recording cut short by a crash is still
+// readable up to the last complete frame.
+
+use std::fs::File;
+use std::io::{BufReader, BufWriter, ErrorKind, Write};
+use std::path::Path;
+use std::sync::mpsc::RecvTimeoutError;
+use std::sync::{Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use super::msgpack::{ClientMessage, read_msgpack_frame, write_msgpack_frame};
+use super::server::handle_client_message;
+use super::{ClientCommand, ExitReason, IpcServerChannels, UiEvent};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Entry {
+    /// A message from the client, `at_ms` after recording started
+    Message { at_ms: u64, message: ClientMessage },
+    /// A restarted client connected; the tree was reset
+    Reconnect { at_ms: u64 },
+}
+
+impl Entry {
+    fn at_ms(&self) -> u64 {
+        match self {
+            Entry::Message { at_ms, .. } | Entry::Reconnect { at_ms } => *at_ms,
+        }
+    }
+}
+
+struct Recorder {
+    started: Instant,
+    file: Mutex<BufWriter<File>>,
+}
+
+static RECORDER: OnceLock<Recorder> = OnceLock::new();
+
+/// Record every client message from now on to `path` (truncated).
+pub fn start_recording(path: &Path) -> std::io::Result<()> {
+    let file = BufWriter::new(File::create(path)?);
+    let _ = RECORDER.set(Recorder {
+        started: Instant::now(),
+        file: Mutex::new(file),
+    });
+    println!("[Session] Recording to {}", path.display());
+    Ok(())
+}
+
+fn write_entry(entry: impl FnOnce(u64) -> Entry) {
+    let Some(recorder) = RECORDER.get() else {
+        return;
+    };
+    let at_ms = recorder.started.elapsed().as_millis() as u64;
+    let mut file = recorder.file.lock().unwrap_or_else(|e| e.into_inner());
+    // Flushed per entry so the log survives the process being killed.
+    if let Err(e) = write_msgpack_frame(&mut *file, &entry(at_ms)).and_then(|_| file.flush()) {
+        eprintln!("[Session] Failed to record message: {e}");
+    }
+}
+
+/// Log a message if recording is on.
+pub fn record_message(message: &ClientMessage) {
+    write_entry(|at_ms| Entry::Message {
+        at_ms,
+        message: message.clone(),
+    });
+}
+
+/// Log a client reconnect if recording is on.
+pub fn record_reconnect() {
+    write_entry(|at_ms| Entry::Reconnect { at_ms });
+}
+
+/// Every complete entry in a recording.
+pub fn read_session(path: &Path) -> std::io::Result<Vec<Entry>> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut entries = Vec::new();
+    loop {
+        match read_msgpack_frame::<_, Entry>(&mut reader) {
+            Ok(entry) => entries.push(entry),
+            Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok(entries),
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Replay a recording at its original pace in place of the IPC server, then
+/// keep the window up for inspection until it is closed.
+pub fn run_replay(channels: IpcServerChannels, path: &Path) {
+    let entries = match read_session(path) {
+        Ok(entries) => entries,
+        Err(e) => {
+            eprintln!("[Session] Failed to read {}: {e}", path.display());
+            let _ = channels.command_sender.send(ClientCommand::ExitApp {
+                code: 1,
+                reason: ExitReason::RuntimeFailed,
+            });
+            return;
+        }
+    };
+    println!(
+        "[Session] Replaying {} entries from {}",
+        entries.len(),
+        path.display()
+    );
+
+    let started = Instant::now();
+    let mut commands = 0;
+    for entry in entries {
+        let due = Duration::from_millis(entry.at_ms());
+        if let Some(wait) = due.checked_sub(started.elapsed()) {
+            thread::sleep(wait);
+        }
+        let command = match entry {
+            Entry::Message { message, .. } => handle_client_message(message),
+            Entry::Reconnect { .. } => Some(ClientCommand::ResetTree),
+        };
+        if let Some(command) = command {
+            if let Err(e) = channels.command_sender.send(command) {
+                eprintln!("[Session] UI closed during replay: {e}");
+                return;
+            }
+            commands += 1;
+        }
+        drain_events(&channels, Duration::ZERO);
+    }
+    println!(
+        "[Session] Replayed {commands} commands in {:.1} ms",
+        started.elapsed().as_secs_f64() * 1000.0
+    );
+
+    while drain_events(&channels, Duration::from_millis(100)) {}
+}
+
+/// Log runtime errors raised by replayed commands (there is no client to
+/// deliver them to), waiting up to `wait` for the first. Returns false once
+/// the UI is gone.
+fn drain_events(channels: &IpcServerChannels, wait: Duration) -> bool {
+    let first = match channels.event_receiver.recv_timeout(wait) {
+        Ok(event) => event,
+        Err(RecvTimeoutError::Timeout) => return true,
+        Err(RecvTimeoutError::Disconnected) => return false,
+    };
+    for event in std::iter::once(first).chain(channels.event_receiver.try_iter()) {
+        if let UiEvent::RuntimeError {
+            message, command, ..
+        } = event.event
+        {
+            eprintln!(
+                "[Session] {}: {message}",
+                command.as_deref().unwrap_or("runtime")
+            );
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_round_trip_tolerates_truncation() {
+        let path = std::env::temp_dir().join(format!("vellum-session-{}.mpk", std::process::id()));
+        let mut file = File::create(&path).unwrap();
+        let entries = [
+            Entry::Message {
+                at_ms: 0,
+                message: ClientMessage::SetTitle {
+                    title: "Replay".to_string(),
+                },
+            },
+            Entry::Reconnect { at_ms: 40 },
+        ];
+        for entry in &entries {
+            write_msgpack_frame(&mut file, entry).unwrap();
+        }
+        // A frame cut off mid-write by a crash.
+        file.write_all(&[200, 0, 0, 0, 1, 2]).unwrap();
+        drop(file);
+
+        let read = read_session(&path).unwrap();
+        assert_eq!(read.len(), 2);
+        assert!(matches!(
+            &read[0],
+            Entry::Message { message: ClientMessage::SetTitle { title }, .. } if title == "Replay"
+        ));
+        assert_eq!(read[1].at_ms(), 40);
+        let _ = std::fs::remove_file(&path);
+    }
+}
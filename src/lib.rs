@@ -0,0 +1,32 @@
+// Vellum runtime library
+// Everything the `vellum` binary is built from. Exposed as a library so the
+// benches and the test-support harness can drive the command handler, widget
+// bookkeeping and style pipeline directly, without a JS client, and so
+// applications can embed the runtime with their own widget kinds, renderers
+// and hooks (see app.rs).
+
+pub mod app;
+pub mod assets;
+pub mod audio;
+pub mod bundle;
+pub mod cli;
+pub mod crash;
+pub mod deeplink;
+pub mod hooks;
+pub mod ipc;
+pub mod locale;
+#[cfg(target_os = "macos")]
+pub mod macos;
+pub mod manifest;
+pub mod pdf;
+pub mod plugins;
+pub mod power;
+pub mod runtime;
+pub mod socket;
+pub mod stress;
+#[cfg(feature = "test-support")]
+pub mod test_support;
+pub mod ui;
+pub mod update;
+
+pub use app::{Extensions, run};
@@ -0,0 +1,128 @@
+// Locale Detection
+// The user's preferred locales (BCP 47 tags, most preferred first) and whether
+// the primary one is written right-to-left. Handed to Bun as `VELLUM_LOCALES`
+// and used to mirror row layouts for RTL languages.
+
+/// Languages written right-to-left when no script subtag says otherwise.
+const RTL_LANGUAGES: &[&str] = &[
+    "ar", "arc", "ckb", "dv", "fa", "he", "iw", "ks", "ku", "ps", "sd", "syr", "ug", "ur", "yi",
+];
+/// Right-to-left script subtags (ISO 15924).
+const RTL_SCRIPTS: &[&str] = &["arab", "hebr", "nkoo", "rohg", "syrc", "thaa"];
+
+const FALLBACK_LOCALE: &str = "en-US";
+
+/// `en_US.UTF-8@euro` → `en-US`; `C`/`POSIX` mean "no preference".
+fn normalize(value: &str) -> Option<String> {
+    let tag = value.split(['.', '@']).next()?.trim().replace('_', "-");
+    if tag.is_empty() || tag == "C" || tag == "POSIX" {
+        return None;
+    }
+    Some(tag)
+}
+
+/// `LANGUAGE` (a colon list), then the first of `LC_ALL`/`LC_MESSAGES`/`LANG`.
+fn from_env(get: impl Fn(&str) -> Option<String>) -> Vec<String> {
+    let mut locales: Vec<String> = get("LANGUAGE")
+        .map(|list| list.split(':').filter_map(normalize).collect())
+        .unwrap_or_default();
+    if let Some(tag) = ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .into_iter()
+        .find_map(|name| get(name).and_then(|value| normalize(&value)))
+    {
+        locales.push(tag);
+    }
+    locales
+}
+
+#[cfg(target_os = "macos")]
+fn from_system() -> Vec<String> {
+    // Prints a plist array: (\n    "en-US",\n    "ar-SA"\n)
+    std::process::Command::new("defaults")
+        .args(["read", "-g", "AppleLanguages"])
+        .output()
+        .map(|output| {
+            String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .map(|line| line.trim().trim_end_matches(',').trim_matches('"'))
+                .filter(|tag| !tag.is_empty() && *tag != "(" && *tag != ")")
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(windows)]
+#[link(name = "kernel32")]
+unsafe extern "system" {
+    fn GetUserDefaultLocaleName(locale_name: *mut u16, len: i32) -> i32;
+}
+
+#[cfg(windows)]
+fn from_system() -> Vec<String> {
+    let mut buffer = [0u16; 85];
+    let len = unsafe { GetUserDefaultLocaleName(buffer.as_mut_ptr(), buffer.len() as i32) };
+    if len <= 1 {
+        return Vec::new();
+    }
+    vec![String::from_utf16_lossy(&buffer[..len as usize - 1])]
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+fn from_system() -> Vec<String> {
+    Vec::new()
+}
+
+/// Preferred locales, most preferred first; never empty.
+pub fn preferred_locales() -> Vec<String> {
+    let mut locales = from_env(|name| std::env::var(name).ok());
+    locales.extend(from_system());
+    let mut seen = std::collections::HashSet::new();
+    locales.retain(|tag| seen.insert(tag.to_ascii_lowercase()));
+    if locales.is_empty() {
+        locales.push(FALLBACK_LOCALE.to_string());
+    }
+    locales
+}
+
+/// Whether text in `tag` runs right-to-left, from its script or language subtag.
+pub fn is_rtl(tag: &str) -> bool {
+    let subtags: Vec<String> = tag.split(['-', '_']).map(str::to_ascii_lowercase).collect();
+    let Some(language) = subtags.first() else {
+        return false;
+    };
+    if let Some(script) = subtags.iter().skip(1).find(|s| s.len() == 4) {
+        return RTL_SCRIPTS.contains(&script.as_str());
+    }
+    RTL_LANGUAGES.contains(&language.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locales_from_env() {
+        let env = |name: &str| match name {
+            "LANGUAGE" => Some("fr_CA:fr".to_string()),
+            "LC_ALL" => Some("".to_string()),
+            "LANG" => Some("de_DE.UTF-8".to_string()),
+            _ => None,
+        };
+        assert_eq!(from_env(env), ["fr-CA", "fr", "de-DE"]);
+        assert!(from_env(|name| (name == "LANG").then(|| "C.UTF-8".to_string())).is_empty());
+    }
+
+    #[test]
+    fn test_is_rtl() {
+        assert!(is_rtl("ar"));
+        assert!(is_rtl("he-IL"));
+        assert!(is_rtl("fa_IR"));
+        assert!(is_rtl("pa-Arab-PK"));
+        assert!(!is_rtl("en-US"));
+        assert!(!is_rtl("az-Latn"));
+        // Kurdish written in Latin script runs left-to-right.
+        assert!(!is_rtl("ku-Latn-TR"));
+        assert!(!is_rtl(""));
+    }
+}
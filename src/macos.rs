@@ -0,0 +1,293 @@
+// macOS Application Integration
+// winit's NSApplicationDelegate doesn't handle document opening, dock clicks,
+// the dock menu, Cmd-Q or power notifications, so the missing methods are
+// added to its class at startup and forwarded through the UiEvent /
+// ClientCommand channels.
+
+use std::ffi::CString;
+use std::sync::{Mutex, OnceLock};
+
+use objc2::encode::Encode;
+use objc2::rc::Retained;
+use objc2::runtime::{AnyClass, AnyObject, Bool, Sel};
+use objc2::{class, msg_send, sel};
+use objc2_app_kit::{NSApplication, NSApplicationDelegateReply, NSMenu, NSMenuItem};
+use objc2_foundation::{MainThreadMarker, NSArray, NSString, NSURL};
+
+use crate::ipc::{
+    ClientCommand, ClientCommandSender, DockMenuItem, ExitReason, PowerEventKind, UiEvent,
+    UiEventSender,
+};
+
+/// `NSApplicationTerminateReply::NSTerminateCancel`
+const TERMINATE_CANCEL: usize = 0;
+
+struct Channels {
+    events: Mutex<UiEventSender>,
+    commands: Mutex<ClientCommandSender>,
+}
+
+static CHANNELS: OnceLock<Channels> = OnceLock::new();
+static DOCK_MENU: Mutex<Vec<DockMenuItem>> = Mutex::new(Vec::new());
+
+fn send(event: UiEvent) {
+    if let Some(channels) = CHANNELS.get()
+        && let Ok(events) = channels.events.lock()
+    {
+        let _ = events.send(event);
+    }
+}
+
+/// Replace the items shown when the dock icon is right-clicked.
+pub fn set_dock_menu(items: Vec<DockMenuItem>) {
+    match DOCK_MENU.lock() {
+        Ok(mut menu) => *menu = items,
+        Err(poisoned) => *poisoned.into_inner() = items,
+    }
+}
+
+/// Hook the delegate methods into winit's application delegate. Must run on
+/// the main thread after the event loop is built and before it starts, so
+/// documents passed at launch are delivered too.
+pub fn install(event_sender: UiEventSender, command_sender: ClientCommandSender) {
+    let Some(mtm) = MainThreadMarker::new() else {
+        eprintln!("[macOS] App integration must be installed on the main thread");
+        return;
+    };
+    let _ = CHANNELS.set(Channels {
+        events: Mutex::new(event_sender),
+        commands: Mutex::new(command_sender),
+    });
+
+    let app = NSApplication::sharedApplication(mtm);
+    let delegate: *mut AnyObject = unsafe { msg_send![&app, delegate] };
+    let Some(delegate) = (unsafe { delegate.as_ref() }) else {
+        eprintln!("[macOS] NSApplication has no delegate; dock and file events disabled");
+        return;
+    };
+    let class = delegate.class();
+
+    let object = <&AnyObject>::ENCODING;
+    let selector = Sel::ENCODING;
+    let void = <()>::ENCODING;
+    let boolean = Bool::ENCODING;
+    let reply = usize::ENCODING;
+    unsafe {
+        add_method(
+            class,
+            sel!(application:openFiles:),
+            open_files as unsafe extern "C" fn(_, _, _, _),
+            &format!("{void}{object}{selector}{object}{object}"),
+        );
+        add_method(
+            class,
+            sel!(application:openURLs:),
+            open_urls as unsafe extern "C" fn(_, _, _, _),
+            &format!("{void}{object}{selector}{object}{object}"),
+        );
+        add_method(
+            class,
+            sel!(applicationShouldHandleReopen:hasVisibleWindows:),
+            should_handle_reopen as unsafe extern "C" fn(_, _, _, _) -> _,
+            &format!("{boolean}{object}{selector}{object}{boolean}"),
+        );
+        add_method(
+            class,
+            sel!(applicationDockMenu:),
+            dock_menu as unsafe extern "C" fn(_, _, _) -> _,
+            &format!("{object}{object}{selector}{object}"),
+        );
+        add_method(
+            class,
+            sel!(vellumDockMenuItem:),
+            dock_menu_item as unsafe extern "C" fn(_, _, _),
+            &format!("{void}{object}{selector}{object}"),
+        );
+        add_method(
+            class,
+            sel!(applicationShouldTerminate:),
+            should_terminate as unsafe extern "C" fn(_, _, _) -> _,
+            &format!("{reply}{object}{selector}{object}"),
+        );
+
+        let notification = format!("{void}{object}{selector}{object}");
+        add_method(
+            class,
+            sel!(vellumPowerNotification:),
+            power_notification as unsafe extern "C" fn(_, _, _),
+            &notification,
+        );
+        let workspace: *mut AnyObject = msg_send![class!(NSWorkspace), sharedWorkspace];
+        let workspace_center: *mut AnyObject = msg_send![workspace, notificationCenter];
+        let distributed_center: *mut AnyObject =
+            msg_send![class!(NSDistributedNotificationCenter), defaultCenter];
+        let default_center: *mut AnyObject = msg_send![class!(NSNotificationCenter), defaultCenter];
+        for (center, name) in [
+            (workspace_center, "NSWorkspaceWillSleepNotification"),
+            (workspace_center, "NSWorkspaceDidWakeNotification"),
+            (distributed_center, "com.apple.screenIsLocked"),
+            (distributed_center, "com.apple.screenIsUnlocked"),
+            (
+                default_center,
+                "NSProcessInfoPowerStateDidChangeNotification",
+            ),
+        ] {
+            let name = NSString::from_str(name);
+            let _: () = msg_send![
+                center,
+                addObserver: delegate,
+                selector: sel!(vellumPowerNotification:),
+                name: &*name,
+                object: std::ptr::null::<AnyObject>()
+            ];
+        }
+    }
+}
+
+/// `class_addMethod` for an `extern "C"` function; a no-op if the delegate
+/// already implements `name` (winit may grow these over time).
+unsafe fn add_method<F: Copy>(class: &AnyClass, name: Sel, imp: F, types: &str) {
+    let types = CString::new(types).unwrap_or_default();
+    let added = unsafe {
+        let imp: unsafe extern "C" fn() = std::mem::transmute_copy(&imp);
+        objc2::ffi::class_addMethod(
+            class as *const AnyClass as *mut _,
+            name.as_ptr(),
+            Some(imp),
+            types.as_ptr(),
+        )
+    };
+    if !Bool::from_raw(added).as_bool() {
+        eprintln!("[macOS] Delegate already implements {name}; leaving it alone");
+    }
+}
+
+unsafe extern "C" fn open_files(
+    _this: &AnyObject,
+    _cmd: Sel,
+    _app: &AnyObject,
+    files: &NSArray<NSString>,
+) {
+    let paths = (0..files.count())
+        .map(|i| unsafe { files.objectAtIndex(i) }.to_string())
+        .collect();
+    send(UiEvent::OpenFiles { paths });
+    if let Some(mtm) = MainThreadMarker::new() {
+        NSApplication::sharedApplication(mtm)
+            .replyToOpenOrPrint(NSApplicationDelegateReply::NSApplicationDelegateReplySuccess);
+    }
+}
+
+/// URLs for the schemes declared in the app's Info.plist (see deeplink.rs).
+unsafe extern "C" fn open_urls(
+    _this: &AnyObject,
+    _cmd: Sel,
+    _app: &AnyObject,
+    urls: &NSArray<NSURL>,
+) {
+    for i in 0..urls.count() {
+        let url = unsafe { urls.objectAtIndex(i) };
+        if let Some(url) = unsafe { url.absoluteString() } {
+            send(UiEvent::OpenUrl {
+                url: url.to_string(),
+            });
+        }
+    }
+}
+
+unsafe extern "C" fn should_handle_reopen(
+    _this: &AnyObject,
+    _cmd: Sel,
+    _app: &AnyObject,
+    has_visible_windows: Bool,
+) -> Bool {
+    send(UiEvent::AppReopen {
+        has_visible_windows: has_visible_windows.as_bool(),
+    });
+    Bool::YES
+}
+
+unsafe extern "C" fn dock_menu(this: &AnyObject, _cmd: Sel, _app: &AnyObject) -> *mut NSMenu {
+    let Some(mtm) = MainThreadMarker::new() else {
+        return std::ptr::null_mut();
+    };
+    let items = DOCK_MENU
+        .lock()
+        .map(|items| items.clone())
+        .unwrap_or_default();
+    if items.is_empty() {
+        return std::ptr::null_mut();
+    }
+    let menu = NSMenu::new(mtm);
+    for (index, item) in items.iter().enumerate() {
+        let menu_item = unsafe {
+            NSMenuItem::initWithTitle_action_keyEquivalent(
+                mtm.alloc(),
+                &NSString::from_str(&item.title),
+                Some(sel!(vellumDockMenuItem:)),
+                &NSString::from_str(""),
+            )
+        };
+        unsafe {
+            menu_item.setTarget(Some(this));
+        }
+        menu_item.setTag(index as isize);
+        menu.addItem(&menu_item);
+    }
+    Retained::autorelease_return(menu)
+}
+
+unsafe extern "C" fn dock_menu_item(_this: &AnyObject, _cmd: Sel, sender: &NSMenuItem) {
+    let index = sender.tag();
+    let id = DOCK_MENU.lock().ok().and_then(|items| {
+        usize::try_from(index)
+            .ok()
+            .and_then(|index| items.get(index))
+            .map(|item| item.id.clone())
+    });
+    if let Some(id) = id {
+        send(UiEvent::DockMenuAction { id });
+    }
+}
+
+/// `NSProcessInfo.lowPowerModeEnabled`, which only exists on macOS 12+.
+fn low_power_mode_enabled() -> bool {
+    unsafe {
+        let info: *mut AnyObject = msg_send![class!(NSProcessInfo), processInfo];
+        let supported: Bool = msg_send![info, respondsToSelector: sel!(isLowPowerModeEnabled)];
+        if !supported.as_bool() {
+            return false;
+        }
+        let enabled: Bool = msg_send![info, isLowPowerModeEnabled];
+        enabled.as_bool()
+    }
+}
+
+unsafe extern "C" fn power_notification(_this: &AnyObject, _cmd: Sel, notification: &AnyObject) {
+    let name: *mut NSString = unsafe { msg_send![notification, name] };
+    let Some(name) = (unsafe { name.as_ref() }) else {
+        return;
+    };
+    let kind = match name.to_string().as_str() {
+        "NSWorkspaceWillSleepNotification" => PowerEventKind::Suspend,
+        "NSWorkspaceDidWakeNotification" => PowerEventKind::Resume,
+        "com.apple.screenIsLocked" => PowerEventKind::Lock,
+        "com.apple.screenIsUnlocked" => PowerEventKind::Unlock,
+        _ => PowerEventKind::LowPowerMode(low_power_mode_enabled()),
+    };
+    send(UiEvent::Power(kind));
+}
+
+/// Cmd-Q and "Quit" in the app menu: close through the event loop so the Bun
+/// process is stopped and the IPC server shuts down, instead of terminating.
+unsafe extern "C" fn should_terminate(_this: &AnyObject, _cmd: Sel, _app: &AnyObject) -> usize {
+    if let Some(channels) = CHANNELS.get()
+        && let Ok(commands) = channels.commands.lock()
+    {
+        let _ = commands.send(ClientCommand::ExitApp {
+            code: 0,
+            reason: ExitReason::Quit,
+        });
+    }
+    TERMINATE_CANCEL
+}
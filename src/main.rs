@@ -10,65 +10,15 @@
 // On Windows platform, don't show a console when opening the app.
 // #![windows_subsystem = "windows"]
 
-mod ipc;
-mod socket;
-mod ui;
-
-use std::thread;
-
-use ipc::IpcChannels;
-use ipc::server::run_ipc_server;
-use ui::{prepare_ui, run_ui_blocking};
-
 fn main() {
-    println!("Vellum Starting...");
-
+    // Quiet the dependencies' logging unless it was asked for. Nothing else
+    // runs yet, so changing the environment is safe here.
     let rust_log = std::env::var("RUST_LOG").ok();
-    let should_override_log = match rust_log.as_deref() {
-        Some(value) => value.contains("debug") || value.contains("trace"),
-        None => true,
-    };
-    if should_override_log {
+    if rust_log.is_none_or(|value| value.contains("debug") || value.contains("trace")) {
         unsafe {
             std::env::set_var("RUST_LOG", "warn");
         }
-        println!("[Main] RUST_LOG set to info");
     }
-
-    println!("[Main] Operating in Client-Server Socket IPC Mode");
-
-    // Phase 1: Build the EventLoop and extract EventLoopProxy (non-blocking).
-    // This must happen before spawning the JS thread so the proxy can be shared.
-    let (ui_setup, event_loop) = prepare_ui();
-
-    // Phase 2: Create IPC channels with the EventLoopProxy.
-    // JS→UI commands use EventLoopProxy (immediately wakes the event loop, zero polling).
-    // UI→JS events use mpsc channels.
-    let channels = IpcChannels::new(ui_setup.proxy, ui_setup.window_id);
-
-    let ui_channels = channels.ui;
-    let js_channels = channels.ipc_server;
-
-    // Phase 3: Spawn the IPC server thread with EventLoopProxy-based command sender.
-    let ipc_server_handle = thread::Builder::new()
-        .name("ipc-server".to_string())
-        .spawn(move || {
-            println!("[Main] IPC server thread started");
-            run_ipc_server(js_channels);
-            println!("[Main] IPC server thread finished");
-        })
-        .unwrap_or_else(|e| panic!("Fatal: failed to spawn IPC server thread: {e}"));
-
-    // Phase 4: Run the UI event loop on the main thread (blocks forever).
-    // The main thread MUST run the UI due to platform requirements (macOS, etc.).
-    println!("[Main] Starting UI on main thread");
-    run_ui_blocking(event_loop, ui_setup.window_id, ui_channels.event_sender);
-
-    // Wait for the IPC server thread to finish after the UI closes
-    println!("[Main] UI closed, waiting for IPC server thread to finish...");
-    if let Err(e) = ipc_server_handle.join() {
-        eprintln!("[Main] IPC server thread panicked: {:?}", e);
-    }
-
-    println!("[Main] Vellum shutdown complete");
+    let args = std::env::args().skip(1).collect();
+    std::process::exit(vellum::run(args, vellum::Extensions::default()));
 }
@@ -0,0 +1,394 @@
+// App Manifest
+// An optional `vellum.toml` beside the app entry (embedded by `vellum build`)
+// declaring the app id, window defaults, permissions and OS integration:
+//
+//   id = "com.example.notes"
+//   icon = "asset://icon.png"
+//   permissions = ["network", "shell"]
+//   single-instance = true
+//   deep-link-schemes = ["notes"]
+//   plugins = ["native/libcadview.so"]
+//   crash-dialog = true
+//   update-public-key = "d75a9801…"
+//
+//   [window]
+//   title = "Notes"
+//   width = 1024
+//   height = 768
+//   min-size = [320, 240]
+//   resizable = true
+//   fullscreen = false
+//   transparent = false
+//   background = "#1e1e2e"
+//   splash = true
+//   kiosk = false
+//
+//   [theme]
+//   font-size = 16
+//   text-color = "#cdd6f4"
+//   gap = 4
+
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+
+use serde::Deserialize;
+
+use crate::cli::WindowOptions;
+use crate::ipc::{ColorValue, UiEvent, UiEventSender};
+use crate::socket::{self, UnixListener, UnixStream};
+use crate::ui::styles::color_value_to_peniko;
+use crate::ui::theme::WidgetDefaults;
+
+pub const FILE_NAME: &str = "vellum.toml";
+
+/// `[window]` defaults; command-line flags take precedence.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct WindowSection {
+    pub title: Option<String>,
+    pub width: Option<f64>,
+    pub height: Option<f64>,
+    pub min_size: Option<(f64, f64)>,
+    pub resizable: Option<bool>,
+    pub fullscreen: Option<bool>,
+    pub transparent: Option<bool>,
+    pub background: Option<ColorValue>,
+    pub splash: Option<bool>,
+    pub kiosk: Option<bool>,
+}
+
+/// `[theme]`: what widgets look like before JS styles them (see ui/theme.rs).
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct ThemeSection {
+    /// Start from masonry's default widget properties; true unless set.
+    pub masonry_defaults: Option<bool>,
+    pub font_size: Option<f32>,
+    pub text_color: Option<ColorValue>,
+    pub gap: Option<f64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Manifest {
+    /// Reverse-DNS style identifier; namespaces app data and the instance lock.
+    pub id: Option<String>,
+    /// Window icon: a path relative to the manifest, or an `asset://` URL.
+    pub icon: Option<String>,
+    pub window: WindowSection,
+    pub theme: ThemeSection,
+    /// Capabilities the app declares, exposed to JS as `system.permissions`.
+    pub permissions: Vec<String>,
+    /// When set, launching the app again exits instead of opening a second
+    /// window, passing any deep link on to the running instance.
+    pub single_instance: bool,
+    /// Custom URL schemes the app handles (`notes://...`).
+    pub deep_link_schemes: Vec<String>,
+    /// Native extension libraries loaded at startup (see plugins.rs), relative
+    /// to the manifest.
+    pub plugins: Vec<String>,
+    /// Tell the user where the crash report went when a panic ends the app.
+    pub crash_dialog: bool,
+    /// Ed25519 public key, in hex, that downloaded updates must be signed with
+    /// (see update.rs).
+    pub update_public_key: Option<String>,
+}
+
+fn valid_id(id: &str) -> bool {
+    !id.is_empty()
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'))
+}
+
+/// RFC 3986 scheme syntax, excluding schemes the OS or runtime already own.
+fn valid_scheme(scheme: &str) -> bool {
+    let mut chars = scheme.chars();
+    chars.next().is_some_and(|c| c.is_ascii_lowercase())
+        && chars
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '+' | '-' | '.'))
+        && !matches!(scheme, "http" | "https" | "file" | "mailto" | "asset")
+}
+
+/// Parse and validate manifest text.
+pub fn parse(text: &str) -> Result<Manifest, String> {
+    let manifest: Manifest = toml::from_str(text).map_err(|e| e.to_string())?;
+
+    if let Some(id) = &manifest.id
+        && !valid_id(id)
+    {
+        return Err(format!(
+            "id '{id}' may only contain letters, digits, '.', '-' and '_'"
+        ));
+    }
+    if manifest.single_instance && manifest.id.is_none() {
+        return Err("single-instance requires an id".to_string());
+    }
+    if !manifest.deep_link_schemes.is_empty() && manifest.id.is_none() {
+        return Err("deep-link-schemes requires an id".to_string());
+    }
+    if let Some(scheme) = manifest
+        .deep_link_schemes
+        .iter()
+        .find(|scheme| !valid_scheme(scheme))
+    {
+        return Err(format!("'{scheme}' is not a usable deep link scheme"));
+    }
+    if manifest.plugins.iter().any(|path| path.trim().is_empty()) {
+        return Err("plugin paths must not be empty".to_string());
+    }
+    if let Some(key) = &manifest.update_public_key {
+        crate::update::parse_public_key(key)?;
+    }
+    let window = &manifest.window;
+    let (min_width, min_height) = window
+        .min_size
+        .map_or((None, None), |(w, h)| (Some(w), Some(h)));
+    if [window.width, window.height, min_width, min_height]
+        .into_iter()
+        .flatten()
+        .any(|v| !v.is_finite() || v <= 0.0)
+    {
+        return Err("window sizes must be positive numbers".to_string());
+    }
+    let theme = &manifest.theme;
+    if theme
+        .font_size
+        .is_some_and(|size| !size.is_finite() || size <= 0.0)
+    {
+        return Err("theme font-size must be a positive number".to_string());
+    }
+    if theme.gap.is_some_and(|gap| !gap.is_finite() || gap < 0.0) {
+        return Err("theme gap must not be negative".to_string());
+    }
+    Ok(manifest)
+}
+
+/// Load `vellum.toml` from `dir`. A missing file is not an error.
+pub fn load(dir: &Path) -> Result<Option<Manifest>, String> {
+    let path = dir.join(FILE_NAME);
+    match std::fs::read_to_string(&path) {
+        Ok(text) => parse(&text)
+            .map(Some)
+            .map_err(|e| format!("{}: {e}", path.display())),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(format!("{}: {e}", path.display())),
+    }
+}
+
+impl Manifest {
+    /// Window defaults, with the icon resolved relative to the manifest in `dir`.
+    pub fn window_options(&self, dir: &Path) -> WindowOptions {
+        let icon = self.icon.as_deref().and_then(|icon| {
+            if icon.starts_with(crate::assets::SCHEME) {
+                crate::assets::resolve(icon)
+            } else {
+                Some(dir.join(icon))
+            }
+        });
+        WindowOptions {
+            title: self.window.title.clone(),
+            width: self.window.width,
+            height: self.window.height,
+            min_size: self.window.min_size,
+            resizable: self.window.resizable,
+            fullscreen: self.window.fullscreen,
+            transparent: self.window.transparent,
+            icon,
+            background: self.window.background.clone(),
+            splash: self.window.splash,
+            kiosk: self.window.kiosk,
+        }
+    }
+
+    /// Widget defaults from `[theme]`.
+    pub fn widget_defaults(&self) -> WidgetDefaults {
+        WidgetDefaults {
+            masonry_defaults: self.theme.masonry_defaults.unwrap_or(true),
+            font_size: self.theme.font_size,
+            text_color: self.theme.text_color.as_ref().map(color_value_to_peniko),
+            gap: self.theme.gap,
+        }
+    }
+
+    /// Plugin library paths, resolved against `dir`: the manifest directory,
+    /// or the executable's for a packaged app.
+    pub fn plugin_paths(&self, dir: &Path) -> Vec<PathBuf> {
+        self.plugins.iter().map(|path| dir.join(path)).collect()
+    }
+
+    /// The permissions, as the Bun process reads them from its environment.
+    pub fn bun_env(&self) -> Vec<(&'static str, String)> {
+        vec![("VELLUM_PERMISSIONS", self.permissions.join(","))]
+    }
+}
+
+/// Held by the running instance of a single-instance app; removes its socket on drop.
+pub struct InstanceLock {
+    listener: UnixListener,
+    path: PathBuf,
+}
+
+impl InstanceLock {
+    /// Deliver deep links forwarded by later launches as `UiEvent::OpenUrl`.
+    pub fn listen(&self, event_sender: UiEventSender) {
+        let listener = match self.listener.try_clone() {
+            Ok(listener) => listener,
+            Err(e) => {
+                eprintln!("[Instance] Cannot listen for forwarded links: {e}");
+                return;
+            }
+        };
+        let spawned = thread::Builder::new()
+            .name("instance-listener".to_string())
+            .spawn(move || {
+                for stream in listener.incoming().flatten() {
+                    for url in BufReader::new(stream).lines().map_while(Result::ok) {
+                        if event_sender.send(UiEvent::OpenUrl { url }).is_err() {
+                            return;
+                        }
+                    }
+                }
+            });
+        if let Err(e) = spawned {
+            eprintln!("[Instance] Failed to spawn instance listener: {e}");
+        }
+    }
+}
+
+impl Drop for InstanceLock {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Claim the per-app instance socket. Returns `None` if another instance of
+/// `id` is already running, after handing it `url`; a stale socket left by a
+/// crash is replaced.
+pub fn claim_instance(id: &str, url: Option<&str>) -> io::Result<Option<InstanceLock>> {
+    let path = std::env::temp_dir().join(format!("Vellum_{id}.instance.sock"));
+    if let Ok(mut running) = UnixStream::connect(&path) {
+        if let Some(url) = url {
+            writeln!(running, "{url}")?;
+        }
+        return Ok(None);
+    }
+    let listener = socket::bind_socket(&path)?;
+    Ok(Some(InstanceLock { listener, path }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_full_manifest() {
+        let manifest = parse(
+            r##"
+            id = "com.example.notes"
+            icon = "icon.png"
+            permissions = ["network", "shell"]
+            single-instance = true
+            deep-link-schemes = ["notes"]
+            plugins = ["native/libcadview.so"]
+            update-public-key = "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a"
+
+            [window]
+            title = "Notes"
+            width = 1024
+            height = 768.5
+            min-size = [320, 240]
+            resizable = false
+            transparent = true
+            background = "#1e1e2e"
+            splash = true
+            "##,
+        )
+        .unwrap();
+        assert_eq!(manifest.id.as_deref(), Some("com.example.notes"));
+        assert_eq!(manifest.permissions, ["network", "shell"]);
+        assert!(manifest.single_instance);
+        assert_eq!(manifest.deep_link_schemes, ["notes"]);
+        assert!(manifest.update_public_key.is_some());
+        assert_eq!(
+            manifest.plugin_paths(Path::new("/opt/notes")),
+            [PathBuf::from("/opt/notes/native/libcadview.so")]
+        );
+
+        let options = manifest.window_options(Path::new("/opt/notes"));
+        assert_eq!(
+            options,
+            WindowOptions {
+                title: Some("Notes".to_string()),
+                width: Some(1024.0),
+                height: Some(768.5),
+                min_size: Some((320.0, 240.0)),
+                resizable: Some(false),
+                fullscreen: None,
+                transparent: Some(true),
+                icon: Some(PathBuf::from("/opt/notes/icon.png")),
+                background: ColorValue::parse("#1e1e2e"),
+                splash: Some(true),
+                kiosk: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_theme_section() {
+        let manifest = parse(
+            r##"
+            [theme]
+            masonry-defaults = false
+            font-size = 16
+            text-color = "#000000"
+            gap = 4
+            "##,
+        )
+        .unwrap();
+        let defaults = manifest.widget_defaults();
+        assert!(!defaults.masonry_defaults);
+        assert_eq!(defaults.font_size, Some(16.0));
+        assert_eq!(defaults.gap, Some(4.0));
+        assert!(defaults.text_color.is_some());
+        assert_eq!(
+            parse("").unwrap().widget_defaults(),
+            WidgetDefaults::default()
+        );
+    }
+
+    #[test]
+    fn test_empty_manifest_uses_defaults() {
+        let manifest = parse("").unwrap();
+        assert!(manifest.id.is_none());
+        assert!(!manifest.single_instance);
+        assert_eq!(
+            manifest.window_options(Path::new(".")),
+            WindowOptions::default()
+        );
+    }
+
+    #[test]
+    fn test_invalid_manifests() {
+        assert!(parse("id = \"has spaces\"").is_err());
+        assert!(parse("single-instance = true").is_err());
+        assert!(parse("deep-link-schemes = [\"notes\"]").is_err());
+        assert!(parse("id = \"a\"\ndeep-link-schemes = [\"https\"]").is_err());
+        assert!(parse("id = \"a\"\ndeep-link-schemes = [\"Notes\"]").is_err());
+        assert!(parse("[window]\nwidth = -1").is_err());
+        assert!(parse("[window]\nmin-size = [0, 240]").is_err());
+        assert!(parse("[window]\nbackground = \"#12\"").is_err());
+        assert!(parse("plugins = [\" \"]").is_err());
+        assert!(parse("update-public-key = \"d75a98\"").is_err());
+        assert!(parse("[theme]\nfont-size = 0").is_err());
+        assert!(parse("[theme]\ngap = -4").is_err());
+        assert!(parse("colour = \"red\"").is_err());
+    }
+
+    #[test]
+    fn test_missing_manifest_is_not_an_error() {
+        let dir = std::env::temp_dir().join(format!("vellum-no-manifest-{}", std::process::id()));
+        assert!(load(&dir).unwrap().is_none());
+    }
+}
@@ -0,0 +1,165 @@
+// PDF Writer
+// Just enough of PDF 1.4 to lay out full-page images: each page holds one
+// JPEG (DCTDecode, so the encoded bytes go in as they are) drawn over the
+// whole MediaBox. Used by `ExportPdf` and `PrintWindow`.
+
+use std::fmt::Write as _;
+
+/// One page: a baseline JPEG and the page size in points (1/72 inch).
+pub struct PdfPage {
+    pub jpeg: Vec<u8>,
+    /// Pixel size of the JPEG
+    pub pixel_width: u32,
+    pub pixel_height: u32,
+    pub width_pt: f64,
+    pub height_pt: f64,
+}
+
+/// Serializes objects while remembering where each one starts, for the
+/// cross-reference table.
+struct Writer {
+    out: Vec<u8>,
+    offsets: Vec<usize>,
+}
+
+impl Writer {
+    /// Start object `number` (objects must be written in order, from 1).
+    fn begin(&mut self, number: usize) {
+        debug_assert_eq!(number, self.offsets.len() + 1);
+        self.offsets.push(self.out.len());
+        self.out
+            .extend_from_slice(format!("{number} 0 obj\n").as_bytes());
+    }
+
+    fn object(&mut self, number: usize, body: &str) {
+        self.begin(number);
+        self.out.extend_from_slice(body.as_bytes());
+        self.out.extend_from_slice(b"\nendobj\n");
+    }
+
+    fn stream(&mut self, number: usize, dict: &str, data: &[u8]) {
+        self.begin(number);
+        self.out
+            .extend_from_slice(format!("<< {dict} /Length {} >>\nstream\n", data.len()).as_bytes());
+        self.out.extend_from_slice(data);
+        self.out.extend_from_slice(b"\nendstream\nendobj\n");
+    }
+}
+
+/// Page `index` is object `first_page_object(index)`, followed by its content
+/// stream and its image.
+fn first_page_object(index: usize) -> usize {
+    3 + index * 3
+}
+
+/// Serialize `pages` as a PDF document.
+pub fn write(pages: &[PdfPage]) -> Vec<u8> {
+    let mut writer = Writer {
+        // The binary comment marks the file as binary for transfer tools.
+        out: b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n".to_vec(),
+        offsets: Vec::new(),
+    };
+
+    let kids = (0..pages.len()).fold(String::new(), |mut kids, index| {
+        let _ = write!(kids, "{} 0 R ", first_page_object(index));
+        kids
+    });
+    writer.object(1, "<< /Type /Catalog /Pages 2 0 R >>");
+    writer.object(
+        2,
+        &format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            kids.trim_end(),
+            pages.len()
+        ),
+    );
+
+    for (index, page) in pages.iter().enumerate() {
+        let number = first_page_object(index);
+        let (width, height) = (page.width_pt, page.height_pt);
+        writer.object(
+            number,
+            &format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {width:.2} {height:.2}] \
+                 /Resources << /XObject << /Im0 {} 0 R >> >> /Contents {} 0 R >>",
+                number + 2,
+                number + 1
+            ),
+        );
+        // Scale the unit-square image up to the page.
+        let content = format!("q {width:.2} 0 0 {height:.2} 0 0 cm /Im0 Do Q");
+        writer.stream(number + 1, "", content.as_bytes());
+        writer.stream(
+            number + 2,
+            &format!(
+                "/Type /XObject /Subtype /Image /Width {} /Height {} \
+                 /ColorSpace /DeviceRGB /BitsPerComponent 8 /Filter /DCTDecode",
+                page.pixel_width, page.pixel_height
+            ),
+            &page.jpeg,
+        );
+    }
+
+    let xref = writer.out.len();
+    let mut table = format!(
+        "xref\n0 {}\n0000000000 65535 f \n",
+        writer.offsets.len() + 1
+    );
+    for offset in &writer.offsets {
+        let _ = writeln!(table, "{offset:010} 00000 n ");
+    }
+    let _ = write!(
+        table,
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref}\n%%EOF\n",
+        writer.offsets.len() + 1
+    );
+    writer.out.extend_from_slice(table.as_bytes());
+    writer.out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(jpeg: &[u8]) -> PdfPage {
+        PdfPage {
+            jpeg: jpeg.to_vec(),
+            pixel_width: 2,
+            pixel_height: 1,
+            width_pt: 1.5,
+            height_pt: 0.75,
+        }
+    }
+
+    #[test]
+    fn test_xref_points_at_each_object() {
+        let pdf = write(&[page(b"first"), page(b"second")]);
+        // One char per byte, so string offsets are file offsets.
+        let text: String = pdf
+            .iter()
+            .map(|&byte| if byte.is_ascii() { byte as char } else { '?' })
+            .collect();
+        assert!(text.starts_with("%PDF-1.4"));
+        assert!(text.contains("/Kids [3 0 R 6 0 R] /Count 2"));
+        assert_eq!(text.matches("/Type /Page ").count(), 2);
+
+        let xref = text.find("\nxref\n").unwrap() + 1;
+        let start: usize = text[text.rfind("startxref\n").unwrap() + 10..]
+            .lines()
+            .next()
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert_eq!(start, xref);
+        let offsets: Vec<usize> = text[xref..]
+            .lines()
+            .skip(3)
+            .take(8)
+            .map(|line| line[..10].parse().unwrap())
+            .collect();
+        for (index, offset) in offsets.iter().enumerate() {
+            assert!(text[*offset..].starts_with(&format!("{} 0 obj", index + 1)));
+        }
+        assert!(text.contains("/Filter /DCTDecode /Length 6 >>\nstream\nsecond\nendstream"));
+    }
+}
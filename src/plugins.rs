@@ -0,0 +1,306 @@
+// Native Plugins
+// Shared libraries listed under `plugins` in vellum.toml are opened at startup
+// and may register custom widget kinds and ops through a C ABI, so native
+// widgets can ship without rebuilding the runtime. Each library exports
+//
+//   int vellum_plugin_init(const VellumRegistrar *registrar);
+//
+// returning 0 on success. The registrar and vtable layouts below are the ABI;
+// bump `ABI_VERSION` whenever they change.
+//
+// Widget callbacks run on the UI thread. Ops run on a worker thread and may be
+// called concurrently, so they must be thread-safe.
+
+use std::ffi::{CStr, CString, c_char, c_void};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use libloading::Library;
+
+use crate::ui::widget_factory::register_widget_factory;
+use crate::ui::widgets::native_widget_impl::NativeWidgetFactory;
+
+pub const ABI_VERSION: u32 = 1;
+const INIT_SYMBOL: &[u8] = b"vellum_plugin_init\0";
+
+/// `pointer` event kinds.
+pub const POINTER_DOWN: u32 = 0;
+pub const POINTER_MOVE: u32 = 1;
+pub const POINTER_UP: u32 = 2;
+
+/// Flags returned by `pointer`.
+pub const POINTER_REPAINT: u32 = 1;
+pub const POINTER_CLICK: u32 = 2;
+pub const POINTER_VALUE_CHANGED: u32 = 4;
+
+/// Callbacks for one custom widget kind. `create` and `paint` are required.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct WidgetVTable {
+    /// Passed back to `create`.
+    pub user_data: *mut c_void,
+    /// Size (logical pixels) used when the parent doesn't constrain the widget.
+    pub preferred_width: f64,
+    pub preferred_height: f64,
+    /// `text` is null when the widget was created without one.
+    pub create: Option<
+        unsafe extern "C" fn(
+            user_data: *mut c_void,
+            id: *const c_char,
+            text: *const c_char,
+        ) -> *mut c_void,
+    >,
+    pub destroy: Option<unsafe extern "C" fn(instance: *mut c_void)>,
+    /// Draw into a `width * height * 4` byte RGBA8 buffer (unpremultiplied,
+    /// rows top to bottom, no padding).
+    pub paint:
+        Option<unsafe extern "C" fn(instance: *mut c_void, width: u32, height: u32, rgba: *mut u8)>,
+    /// Return nonzero if the text was applied.
+    pub set_text: Option<unsafe extern "C" fn(instance: *mut c_void, text: *const c_char) -> i32>,
+    /// Return nonzero if the value was applied.
+    pub set_value: Option<unsafe extern "C" fn(instance: *mut c_void, value: f64) -> i32>,
+    /// Pointer input in widget-local logical pixels. Returns `POINTER_*` flags;
+    /// with `POINTER_VALUE_CHANGED`, the new value is written to `value_out`.
+    pub pointer: Option<
+        unsafe extern "C" fn(
+            instance: *mut c_void,
+            kind: u32,
+            x: f64,
+            y: f64,
+            value_out: *mut f64,
+        ) -> u32,
+    >,
+}
+
+// The pointers are only handed back to the plugin, and widget callbacks are
+// only invoked from the UI thread.
+unsafe impl Send for WidgetVTable {}
+unsafe impl Sync for WidgetVTable {}
+
+/// Returns a NUL-terminated JSON string (owned by the plugin, released with the
+/// matching `FreeFn`); sets `*is_error` to nonzero if it is an error message.
+pub type OpFn = unsafe extern "C" fn(
+    user_data: *mut c_void,
+    args_json: *const c_char,
+    is_error: *mut i32,
+) -> *mut c_char;
+pub type FreeFn = unsafe extern "C" fn(result: *mut c_char);
+
+#[repr(C)]
+pub struct Registrar {
+    pub abi_version: u32,
+    pub host: *mut c_void,
+    /// Returns 0 on success.
+    pub register_widget: unsafe extern "C" fn(
+        host: *mut c_void,
+        kind: *const c_char,
+        vtable: *const WidgetVTable,
+    ) -> i32,
+    /// Returns 0 on success.
+    pub register_op: unsafe extern "C" fn(
+        host: *mut c_void,
+        name: *const c_char,
+        op: Option<OpFn>,
+        free: Option<FreeFn>,
+        user_data: *mut c_void,
+    ) -> i32,
+}
+
+struct NativeOp {
+    name: String,
+    op: OpFn,
+    free: Option<FreeFn>,
+    user_data: *mut c_void,
+}
+
+unsafe impl Send for NativeOp {}
+unsafe impl Sync for NativeOp {}
+
+/// Loaded libraries stay open for the life of the process: registered
+/// factories and ops point into them.
+static LIBRARIES: Mutex<Vec<Library>> = Mutex::new(Vec::new());
+static OPS: Mutex<Vec<Arc<NativeOp>>> = Mutex::new(Vec::new());
+
+/// What `register_widget` / `register_op` know about the calling plugin.
+struct Host {
+    name: String,
+}
+
+fn host_name(host: *mut c_void) -> String {
+    unsafe { (host as *const Host).as_ref() }
+        .map(|host| host.name.clone())
+        .unwrap_or_default()
+}
+
+unsafe fn c_string(ptr: *const c_char) -> Option<String> {
+    if ptr.is_null() {
+        return None;
+    }
+    Some(
+        unsafe { CStr::from_ptr(ptr) }
+            .to_string_lossy()
+            .into_owned(),
+    )
+}
+
+unsafe extern "C" fn register_widget(
+    host: *mut c_void,
+    kind: *const c_char,
+    vtable: *const WidgetVTable,
+) -> i32 {
+    let plugin = host_name(host);
+    let (Some(kind), Some(vtable)) = (unsafe { c_string(kind) }, unsafe { vtable.as_ref() }) else {
+        eprintln!("[Plugins] {plugin}: register_widget needs a kind and a vtable");
+        return -1;
+    };
+    if vtable.create.is_none() || vtable.paint.is_none() {
+        eprintln!("[Plugins] {plugin}: widget '{kind}' is missing create or paint");
+        return -1;
+    }
+    println!("[Plugins] {plugin}: registered widget '{kind}'");
+    register_widget_factory(kind.clone(), NativeWidgetFactory::new(kind, *vtable));
+    0
+}
+
+unsafe extern "C" fn register_op(
+    host: *mut c_void,
+    name: *const c_char,
+    op: Option<OpFn>,
+    free: Option<FreeFn>,
+    user_data: *mut c_void,
+) -> i32 {
+    let plugin = host_name(host);
+    let (Some(name), Some(op)) = (unsafe { c_string(name) }, op) else {
+        eprintln!("[Plugins] {plugin}: register_op needs a name and a function");
+        return -1;
+    };
+    let mut ops = OPS.lock().unwrap_or_else(|e| e.into_inner());
+    ops.retain(|existing| existing.name != name);
+    println!("[Plugins] {plugin}: registered op '{name}'");
+    ops.push(Arc::new(NativeOp {
+        name,
+        op,
+        free,
+        user_data,
+    }));
+    0
+}
+
+fn load(path: &Path) -> Result<(), String> {
+    let library = unsafe { Library::new(path) }.map_err(|e| e.to_string())?;
+    let mut host = Host {
+        name: path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+    };
+    let registrar = Registrar {
+        abi_version: ABI_VERSION,
+        host: &mut host as *mut Host as *mut c_void,
+        register_widget,
+        register_op,
+    };
+    let status = {
+        let init =
+            unsafe { library.get::<unsafe extern "C" fn(*const Registrar) -> i32>(INIT_SYMBOL) }
+                .map_err(|e| format!("missing vellum_plugin_init: {e}"))?;
+        unsafe { init(&registrar) }
+    };
+    // Kept open even when init fails: it may have registered something first.
+    LIBRARIES
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(library);
+    if status != 0 {
+        return Err(format!("vellum_plugin_init returned {status}"));
+    }
+    Ok(())
+}
+
+/// Open each plugin and let it register its widgets and ops. A plugin that
+/// fails to load is reported and skipped. Must run before the UI starts.
+pub fn load_all(paths: &[impl AsRef<Path>]) {
+    for path in paths {
+        let path = path.as_ref();
+        match load(path) {
+            Ok(()) => println!("[Plugins] Loaded {}", path.display()),
+            Err(e) => eprintln!("[Plugins] Failed to load {}: {e}", path.display()),
+        }
+    }
+}
+
+/// Call the op registered as `name` with a JSON argument string, returning
+/// its JSON result.
+pub fn call_op(name: &str, args_json: &str) -> Result<String, String> {
+    let op = OPS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .find(|op| op.name == name)
+        .cloned()
+        .ok_or_else(|| format!("No native op named '{name}'"))?;
+    let args = CString::new(args_json).map_err(|e| e.to_string())?;
+    let mut is_error = 0;
+    let result = unsafe { (op.op)(op.user_data, args.as_ptr(), &mut is_error) };
+    let text = unsafe { c_string(result) }.unwrap_or_else(|| "null".to_string());
+    if !result.is_null()
+        && let Some(free) = op.free
+    {
+        unsafe { free(result) };
+    }
+    if is_error != 0 { Err(text) } else { Ok(text) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    unsafe extern "C" fn echo(
+        _user_data: *mut c_void,
+        args_json: *const c_char,
+        is_error: *mut i32,
+    ) -> *mut c_char {
+        let args = unsafe { CStr::from_ptr(args_json) };
+        if args.to_bytes() == b"\"fail\"" {
+            unsafe { *is_error = 1 };
+        }
+        CString::new(args.to_bytes()).unwrap().into_raw()
+    }
+
+    unsafe extern "C" fn free_echo(result: *mut c_char) {
+        drop(unsafe { CString::from_raw(result) });
+    }
+
+    #[test]
+    fn test_registered_op_round_trip() {
+        let mut host = Host {
+            name: "test".to_string(),
+        };
+        let host = &mut host as *mut Host as *mut c_void;
+        let status = unsafe {
+            register_op(
+                host,
+                c"test.echo".as_ptr(),
+                Some(echo),
+                Some(free_echo),
+                std::ptr::null_mut(),
+            )
+        };
+        assert_eq!(status, 0);
+        assert_eq!(call_op("test.echo", "[1,2]"), Ok("[1,2]".to_string()));
+        assert_eq!(
+            call_op("test.echo", "\"fail\""),
+            Err("\"fail\"".to_string())
+        );
+        assert!(call_op("test.missing", "null").is_err());
+        assert_eq!(
+            unsafe { register_op(host, c"test.bad".as_ptr(), None, None, std::ptr::null_mut()) },
+            -1
+        );
+    }
+
+    #[test]
+    fn test_missing_library_fails() {
+        assert!(load(Path::new("/nonexistent/libvellum-missing.so")).is_err());
+    }
+}
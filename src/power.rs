@@ -0,0 +1,321 @@
+// Power and Session
+// System suspend/resume, session lock/unlock and low-power mode become
+// `UiEvent::Power`, and JS can keep the machine awake while a long task runs.
+// Linux listens to logind and power-profiles-daemon over D-Bus (via `gdbus`);
+// macOS observes NSWorkspace notifications in macos.rs.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::ipc::UiEventSender;
+#[cfg(all(unix, not(target_os = "macos")))]
+use crate::ipc::{PowerEventKind, UiEvent};
+
+/// Outstanding `InhibitSleep` requests keyed by token, plus the active guard.
+struct Inhibitor {
+    reasons: HashMap<String, String>,
+    guard: Option<SleepGuard>,
+}
+
+static INHIBITOR: Mutex<Option<Inhibitor>> = Mutex::new(None);
+
+/// Keep the system awake until every token passed here has been released.
+pub fn inhibit_sleep(token: String, reason: String) {
+    let mut state = INHIBITOR.lock().unwrap_or_else(|p| p.into_inner());
+    let inhibitor = state.get_or_insert_with(|| Inhibitor {
+        reasons: HashMap::new(),
+        guard: None,
+    });
+    inhibitor.reasons.insert(token, reason.clone());
+    if inhibitor.guard.is_none() {
+        inhibitor.guard = SleepGuard::acquire(&reason, false);
+    }
+}
+
+pub fn release_sleep(token: &str) {
+    let mut state = INHIBITOR.lock().unwrap_or_else(|p| p.into_inner());
+    if let Some(inhibitor) = state.as_mut() {
+        inhibitor.reasons.remove(token);
+        if inhibitor.reasons.is_empty() {
+            inhibitor.guard = None;
+        }
+    }
+}
+
+/// Taken by `keep_display_on` and held until the process exits.
+static DISPLAY_GUARD: Mutex<Option<SleepGuard>> = Mutex::new(None);
+
+/// Keep the display on and the screensaver off for the rest of the run
+/// (kiosk mode), on top of any `InhibitSleep`.
+pub fn keep_display_on(reason: &str) {
+    let mut guard = DISPLAY_GUARD.lock().unwrap_or_else(|p| p.into_inner());
+    if guard.is_none() {
+        *guard = SleepGuard::acquire(reason, true);
+    }
+}
+
+/// Holds the platform's sleep inhibition; released on drop.
+#[cfg(unix)]
+struct SleepGuard(std::process::Child);
+
+#[cfg(unix)]
+impl SleepGuard {
+    /// A helper process that blocks sleep (and with `display`, the display
+    /// sleeping and the screensaver) until it is killed or this process exits.
+    /// Linux's idle inhibitor covers both.
+    fn acquire(reason: &str, display: bool) -> Option<Self> {
+        use std::process::{Command, Stdio};
+
+        let pid = std::process::id().to_string();
+        let mut command = if cfg!(target_os = "macos") {
+            let mut command = Command::new("caffeinate");
+            command.arg(if display { "-di" } else { "-i" });
+            command.args(["-w", &pid]);
+            command
+        } else {
+            let mut command = Command::new("systemd-inhibit");
+            command
+                .args(["--what=sleep:idle", "--who=vellum", "--mode=block"])
+                .arg(format!("--why={reason}"))
+                .args(["tail", &format!("--pid={pid}"), "-f", "/dev/null"]);
+            command
+        };
+        command
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null());
+        match command.spawn() {
+            Ok(child) => Some(Self(child)),
+            Err(e) => {
+                eprintln!("[Power] Cannot inhibit sleep: {e}");
+                None
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+impl Drop for SleepGuard {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+#[cfg(windows)]
+#[link(name = "kernel32")]
+unsafe extern "system" {
+    fn SetThreadExecutionState(flags: u32) -> u32;
+}
+
+#[cfg(windows)]
+const ES_CONTINUOUS: u32 = 0x8000_0000;
+#[cfg(windows)]
+const ES_SYSTEM_REQUIRED: u32 = 0x0000_0001;
+#[cfg(windows)]
+const ES_DISPLAY_REQUIRED: u32 = 0x0000_0002;
+
+/// Set once the display is kept on. The execution state is a single set of
+/// flags, so sleep guards coming and going must keep it.
+#[cfg(windows)]
+static DISPLAY_REQUIRED: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+#[cfg(windows)]
+fn display_flags() -> u32 {
+    if DISPLAY_REQUIRED.load(std::sync::atomic::Ordering::Relaxed) {
+        ES_SYSTEM_REQUIRED | ES_DISPLAY_REQUIRED
+    } else {
+        0
+    }
+}
+
+/// `SetThreadExecutionState` is per thread; inhibit commands run on the UI thread.
+#[cfg(windows)]
+struct SleepGuard;
+
+#[cfg(windows)]
+impl SleepGuard {
+    fn acquire(_reason: &str, display: bool) -> Option<Self> {
+        if display {
+            DISPLAY_REQUIRED.store(true, std::sync::atomic::Ordering::Relaxed);
+        }
+        let flags = ES_CONTINUOUS | ES_SYSTEM_REQUIRED | display_flags();
+        let previous = unsafe { SetThreadExecutionState(flags) };
+        if previous == 0 {
+            eprintln!("[Power] Cannot inhibit sleep: SetThreadExecutionState failed");
+            return None;
+        }
+        Some(Self)
+    }
+}
+
+#[cfg(windows)]
+impl Drop for SleepGuard {
+    fn drop(&mut self) {
+        unsafe {
+            SetThreadExecutionState(ES_CONTINUOUS | display_flags());
+        }
+    }
+}
+
+/// Start forwarding power and session events. A no-op where they come from
+/// elsewhere (macOS) or aren't supported yet (Windows).
+pub fn start_monitor(event_sender: UiEventSender) {
+    #[cfg(all(unix, not(target_os = "macos")))]
+    {
+        let session = std::env::var("XDG_SESSION_ID")
+            .ok()
+            .map(|id| format!("/org/freedesktop/login1/session/{}", bus_path_escape(&id)));
+        watch_gdbus("org.freedesktop.login1", session, event_sender.clone());
+        watch_gdbus("net.hadess.PowerProfiles", None, event_sender);
+    }
+    #[cfg(not(all(unix, not(target_os = "macos"))))]
+    let _ = event_sender;
+}
+
+/// Run `gdbus monitor` for `dest` on the system bus and forward what it reports.
+#[cfg(all(unix, not(target_os = "macos")))]
+fn watch_gdbus(dest: &'static str, session: Option<String>, event_sender: UiEventSender) {
+    use std::io::{BufRead, BufReader};
+    use std::process::{Command, Stdio};
+
+    let child = Command::new("gdbus")
+        .args(["monitor", "--system", "--dest", dest])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn();
+    let mut child = match child {
+        Ok(child) => child,
+        Err(e) => {
+            eprintln!("[Power] Cannot watch {dest} (gdbus: {e}); power events disabled");
+            return;
+        }
+    };
+    let Some(stdout) = child.stdout.take() else {
+        return;
+    };
+    let spawned = std::thread::Builder::new()
+        .name("power-monitor".to_string())
+        .spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                if let Some(kind) = parse_gdbus_line(&line, session.as_deref())
+                    && event_sender.send(UiEvent::Power(kind)).is_err()
+                {
+                    break;
+                }
+            }
+            let _ = child.kill();
+            let _ = child.wait();
+        });
+    if let Err(e) = spawned {
+        eprintln!("[Power] Failed to spawn power monitor: {e}");
+    }
+}
+
+/// logind's object path encoding: every byte outside `[A-Za-z0-9]`, and a
+/// leading digit, becomes `_xx`.
+#[cfg(all(unix, not(target_os = "macos")))]
+fn bus_path_escape(id: &str) -> String {
+    id.bytes()
+        .enumerate()
+        .map(|(i, b)| {
+            if b.is_ascii_alphabetic() || (b.is_ascii_digit() && i > 0) {
+                (b as char).to_string()
+            } else {
+                format!("_{b:02x}")
+            }
+        })
+        .collect()
+}
+
+/// Map one line of `gdbus monitor` output to an event. Lock/Unlock are only
+/// reported for `session` (our login session) when it is known.
+#[cfg(all(unix, not(target_os = "macos")))]
+fn parse_gdbus_line(line: &str, session: Option<&str>) -> Option<PowerEventKind> {
+    let (path, signal) = line.split_once(": ")?;
+    if let Some(args) = signal.strip_prefix("org.freedesktop.login1.Manager.PrepareForSleep ") {
+        return Some(if args.starts_with("(true") {
+            PowerEventKind::Suspend
+        } else {
+            PowerEventKind::Resume
+        });
+    }
+    if session.is_some_and(|session| session != path) {
+        return None;
+    }
+    if signal.starts_with("org.freedesktop.login1.Session.Lock ") {
+        return Some(PowerEventKind::Lock);
+    }
+    if signal.starts_with("org.freedesktop.login1.Session.Unlock ") {
+        return Some(PowerEventKind::Unlock);
+    }
+    let profile = signal
+        .split_once("'ActiveProfile': <'")?
+        .1
+        .split('\'')
+        .next()?;
+    Some(PowerEventKind::LowPowerMode(profile == "power-saver"))
+}
+
+#[cfg(all(test, unix, not(target_os = "macos")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_logind_signals() {
+        let session = Some("/org/freedesktop/login1/session/_32");
+        assert!(matches!(
+            parse_gdbus_line(
+                "/org/freedesktop/login1: org.freedesktop.login1.Manager.PrepareForSleep (true,)",
+                session
+            ),
+            Some(PowerEventKind::Suspend)
+        ));
+        assert!(matches!(
+            parse_gdbus_line(
+                "/org/freedesktop/login1: org.freedesktop.login1.Manager.PrepareForSleep (false,)",
+                session
+            ),
+            Some(PowerEventKind::Resume)
+        ));
+        assert!(matches!(
+            parse_gdbus_line(
+                "/org/freedesktop/login1/session/_32: org.freedesktop.login1.Session.Lock ()",
+                session
+            ),
+            Some(PowerEventKind::Lock)
+        ));
+        // Another user's session locking is not ours to report.
+        assert!(
+            parse_gdbus_line(
+                "/org/freedesktop/login1/session/_35: org.freedesktop.login1.Session.Lock ()",
+                session
+            )
+            .is_none()
+        );
+        assert!(parse_gdbus_line("Monitoring signals on object", session).is_none());
+    }
+
+    #[test]
+    fn test_parse_power_profile_change() {
+        let line = "/net/hadess/PowerProfiles: org.freedesktop.DBus.Properties.PropertiesChanged \
+            ('net.hadess.PowerProfiles', {'ActiveProfile': <'power-saver'>}, @as [])";
+        assert!(matches!(
+            parse_gdbus_line(line, None),
+            Some(PowerEventKind::LowPowerMode(true))
+        ));
+        let line = line.replace("power-saver", "balanced");
+        assert!(matches!(
+            parse_gdbus_line(&line, None),
+            Some(PowerEventKind::LowPowerMode(false))
+        ));
+    }
+
+    #[test]
+    fn test_bus_path_escape() {
+        assert_eq!(bus_path_escape("2"), "_32");
+        assert_eq!(bus_path_escape("c2"), "c2");
+        assert_eq!(bus_path_escape("a-b"), "a_2db");
+    }
+}
@@ -0,0 +1,326 @@
+// Bun Runtime Supervisor
+// In hosted mode (`vellum app.ts`) the binary launches the Bun process itself
+// instead of being spawned by it, forwards its output, and restarts it on crash.
+
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::ipc::{ClientCommand, ClientCommandSender, ExitReason};
+use crate::socket;
+
+/// Delay before the first restart; doubled after each quick crash.
+const INITIAL_BACKOFF: Duration = Duration::from_millis(250);
+const MAX_BACKOFF: Duration = Duration::from_secs(8);
+/// A run longer than this counts as healthy and resets the backoff.
+const HEALTHY_RUN: Duration = Duration::from_secs(10);
+/// Give up after this many consecutive quick crashes.
+const MAX_QUICK_CRASHES: u32 = 5;
+
+/// Set when the user asks to retry after the runtime failed to connect.
+static RESTART_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+/// Kill the running Bun process and start a fresh one right away, without
+/// counting it as a crash. Does nothing unless a supervisor is running.
+pub fn request_restart() {
+    RESTART_REQUESTED.store(true, Ordering::SeqCst);
+}
+
+/// Find the bun executable: `VELLUM_BUN`, then `PATH`, then `~/.bun/bin`.
+pub fn locate_bun() -> Option<PathBuf> {
+    if let Some(path) = std::env::var_os("VELLUM_BUN") {
+        return Some(PathBuf::from(path));
+    }
+    let exe_name = if cfg!(windows) { "bun.exe" } else { "bun" };
+    let on_path = std::env::var_os("PATH").and_then(|paths| {
+        std::env::split_paths(&paths)
+            .map(|dir| dir.join(exe_name))
+            .find(|candidate| candidate.is_file())
+    });
+    on_path.or_else(|| {
+        let home = std::env::var_os(if cfg!(windows) { "USERPROFILE" } else { "HOME" })?;
+        let candidate = Path::new(&home).join(".bun").join("bin").join(exe_name);
+        candidate.is_file().then_some(candidate)
+    })
+}
+
+/// When to restart a crashed Bun process: after a delay that doubles with
+/// each quick crash, giving up after too many in a row.
+struct RestartPolicy {
+    backoff: Duration,
+    quick_crashes: u32,
+}
+
+impl RestartPolicy {
+    fn new() -> Self {
+        Self {
+            backoff: INITIAL_BACKOFF,
+            quick_crashes: 0,
+        }
+    }
+
+    /// Start counting afresh, e.g. after a restart the user asked for.
+    fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    /// A run that lasted `ran_for` ended in failure. Returns how long to wait
+    /// before restarting, or `None` to give up.
+    fn crashed(&mut self, ran_for: Duration) -> Option<Duration> {
+        if ran_for >= HEALTHY_RUN {
+            self.reset();
+        }
+        self.quick_crashes += 1;
+        if self.quick_crashes > MAX_QUICK_CRASHES {
+            return None;
+        }
+        let backoff = self.backoff;
+        self.backoff = (self.backoff * 2).min(MAX_BACKOFF);
+        Some(backoff)
+    }
+}
+
+/// Owns the Bun child process for the lifetime of the UI.
+pub struct BunSupervisor {
+    child: Arc<Mutex<Option<Child>>>,
+    stopping: Arc<AtomicBool>,
+    thread: Option<thread::JoinHandle<()>>,
+}
+
+impl BunSupervisor {
+    /// Start `bun run [--preload <script>]... <entry> [args]` connected to
+    /// `socket_path`, with `env` added to its environment, restarting it with
+    /// exponential backoff if it exits with a failure status. A clean exit
+    /// (status 0) closes the app.
+    #[allow(clippy::too_many_arguments)]
+    pub fn start(
+        bun: PathBuf,
+        entry: PathBuf,
+        preload: Vec<PathBuf>,
+        args: Vec<String>,
+        socket_path: String,
+        env: Vec<(&'static str, String)>,
+        command_sender: ClientCommandSender,
+    ) -> std::io::Result<Self> {
+        let child = Arc::new(Mutex::new(None));
+        let stopping = Arc::new(AtomicBool::new(false));
+        let argv = serde_json::to_string(&args).unwrap_or_else(|_| "[]".to_string());
+
+        let thread_child = child.clone();
+        let thread_stopping = stopping.clone();
+        let thread = thread::Builder::new()
+            .name("bun-supervisor".to_string())
+            .spawn(move || {
+                let mut policy = RestartPolicy::new();
+                loop {
+                    if thread_stopping.load(Ordering::SeqCst) {
+                        return;
+                    }
+                    let started = Instant::now();
+                    let mut command = Command::new(&bun);
+                    command.arg("run");
+                    for script in &preload {
+                        command.arg("--preload").arg(script);
+                    }
+                    command
+                        .arg(&entry)
+                        .args(&args)
+                        .env(socket::SOCKET_ENV, &socket_path)
+                        .env(socket::PROTOCOL_ENV, socket::PROTOCOL_VERSION.to_string())
+                        .env(socket::ARGV_ENV, &argv)
+                        .env("VELLUM_HOSTED", "1")
+                        .env("VELLUM_ASSETS_DIR", crate::assets::root())
+                        .envs(env.iter().cloned())
+                        .stdin(Stdio::null())
+                        .stdout(Stdio::piped())
+                        .stderr(Stdio::piped());
+
+                    let mut spawned = match command.spawn() {
+                        Ok(spawned) => spawned,
+                        Err(e) => {
+                            eprintln!("[Runtime] Failed to start {}: {e}", bun.display());
+                            let _ = command_sender.send(ClientCommand::ExitApp {
+                                code: 1,
+                                reason: ExitReason::RuntimeFailed,
+                            });
+                            return;
+                        }
+                    };
+                    println!("[Runtime] Started bun (pid {})", spawned.id());
+                    if let Some(stdout) = spawned.stdout.take() {
+                        forward_output(stdout, false);
+                    }
+                    if let Some(stderr) = spawned.stderr.take() {
+                        forward_output(stderr, true);
+                    }
+                    *lock(&thread_child) = Some(spawned);
+                    // A restart asked for while this one was starting is moot.
+                    RESTART_REQUESTED.store(false, Ordering::SeqCst);
+
+                    let mut restarting = false;
+                    let status = loop {
+                        if let Some(running) = lock(&thread_child).as_mut() {
+                            // `shutdown` may have run before the child was stored,
+                            // finding nothing to kill.
+                            if thread_stopping.load(Ordering::SeqCst) {
+                                let _ = running.kill();
+                                let _ = running.wait();
+                                break None;
+                            }
+                            if RESTART_REQUESTED.swap(false, Ordering::SeqCst) {
+                                println!("[Runtime] Restarting bun on request");
+                                let _ = running.kill();
+                                restarting = true;
+                            }
+                            match running.try_wait() {
+                                Ok(Some(status)) => break Some(status),
+                                Ok(None) => {}
+                                Err(e) => {
+                                    eprintln!("[Runtime] Lost track of bun process: {e}");
+                                    break None;
+                                }
+                            }
+                        } else {
+                            // Taken by `shutdown`.
+                            break None;
+                        }
+                        thread::sleep(Duration::from_millis(50));
+                    };
+                    lock(&thread_child).take();
+
+                    if thread_stopping.load(Ordering::SeqCst) {
+                        return;
+                    }
+                    if restarting {
+                        policy.reset();
+                        continue;
+                    }
+                    match status {
+                        Some(status) if status.success() => {
+                            println!("[Runtime] bun exited cleanly; closing");
+                            let _ = command_sender.send(ClientCommand::ExitApp {
+                                code: 0,
+                                reason: ExitReason::RuntimeExited,
+                            });
+                            return;
+                        }
+                        Some(status) => eprintln!("[Runtime] bun exited with {status}"),
+                        None => {}
+                    }
+
+                    let Some(backoff) = policy.crashed(started.elapsed()) else {
+                        eprintln!(
+                            "[Runtime] bun crashed {MAX_QUICK_CRASHES} times in a row; giving up"
+                        );
+                        let _ = command_sender.send(ClientCommand::ExitApp {
+                            code: 1,
+                            reason: ExitReason::RuntimeFailed,
+                        });
+                        return;
+                    };
+                    eprintln!("[Runtime] Restarting bun in {:?}", backoff);
+                    let resume_at = Instant::now() + backoff;
+                    while Instant::now() < resume_at {
+                        if thread_stopping.load(Ordering::SeqCst) {
+                            return;
+                        }
+                        thread::sleep(Duration::from_millis(50));
+                    }
+                }
+            })?;
+
+        Ok(Self {
+            child,
+            stopping,
+            thread: Some(thread),
+        })
+    }
+
+    /// Kill the Bun process and stop restarting it. Called once the UI has exited.
+    pub fn shutdown(mut self) {
+        self.stopping.store(true, Ordering::SeqCst);
+        if let Some(mut child) = lock(&self.child).take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn lock(child: &Mutex<Option<Child>>) -> std::sync::MutexGuard<'_, Option<Child>> {
+    child
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Re-emit each line of the child's output with a `[Bun]` prefix.
+fn forward_output(stream: impl Read + Send + 'static, is_stderr: bool) {
+    let spawned = thread::Builder::new()
+        .name("bun-output".to_string())
+        .spawn(move || {
+            for line in BufReader::new(stream).lines() {
+                match line {
+                    Ok(line) if is_stderr => eprintln!("[Bun] {line}"),
+                    Ok(line) => println!("[Bun] {line}"),
+                    Err(_) => break,
+                }
+            }
+        });
+    if let Err(e) = spawned {
+        eprintln!("[Runtime] Failed to spawn output forwarder: {e}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const QUICK: Duration = Duration::from_millis(100);
+
+    #[test]
+    fn test_quick_crashes_back_off_then_give_up() {
+        let mut policy = RestartPolicy::new();
+        let delays: Vec<u64> = (0..MAX_QUICK_CRASHES)
+            .map(|_| policy.crashed(QUICK).unwrap().as_millis() as u64)
+            .collect();
+        assert_eq!(delays, [250, 500, 1000, 2000, 4000]);
+        assert_eq!(policy.crashed(QUICK), None);
+    }
+
+    #[test]
+    fn test_backoff_is_capped() {
+        let mut policy = RestartPolicy::new();
+        for _ in 0..MAX_QUICK_CRASHES {
+            policy.crashed(QUICK);
+        }
+        // Clear only the count; the delay keeps doubling up to the cap.
+        policy.quick_crashes = 0;
+        assert_eq!(policy.crashed(QUICK), Some(MAX_BACKOFF));
+        assert_eq!(policy.crashed(QUICK), Some(MAX_BACKOFF));
+    }
+
+    #[test]
+    fn test_healthy_run_resets_the_count() {
+        let mut policy = RestartPolicy::new();
+        for _ in 0..MAX_QUICK_CRASHES {
+            policy.crashed(QUICK);
+        }
+        assert_eq!(policy.crashed(HEALTHY_RUN), Some(INITIAL_BACKOFF));
+        assert_eq!(policy.crashed(QUICK), Some(INITIAL_BACKOFF * 2));
+    }
+
+    #[test]
+    fn test_reset_after_requested_restart() {
+        let mut policy = RestartPolicy::new();
+        policy.crashed(QUICK);
+        policy.crashed(QUICK);
+        policy.reset();
+        assert_eq!(policy.crashed(QUICK), Some(INITIAL_BACKOFF));
+    }
+}
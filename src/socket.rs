@@ -1,15 +1,47 @@
+// Client Socket and Handshake Environment
+// Whichever process starts the other (Bun spawning the binary from
+// bun_bridge.ts, or `vellum app.ts` spawning Bun) picks the socket and hands
+// it over in the child's environment, so only the parent decides the path:
+//
+//   VELLUM_SOCKET            socket the child listens on or connects to
+//   VELLUM_PROTOCOL_VERSION  the parent's `PROTOCOL_VERSION`
+//   VELLUM_HOSTED=1          the binary started Bun and owns the window
+//   VELLUM_ARGV              the app's arguments as a JSON array (hosted)
+
 use std::io;
 use std::path::Path;
 
 #[cfg(unix)]
-pub use std::os::unix::net::UnixListener;
+pub use std::os::unix::net::{UnixListener, UnixStream};
 
 #[cfg(windows)]
-pub use uds_windows::UnixListener;
+pub use uds_windows::{UnixListener, UnixStream};
+
+pub const SOCKET_ENV: &str = "VELLUM_SOCKET";
+pub const PROTOCOL_ENV: &str = "VELLUM_PROTOCOL_VERSION";
+pub const ARGV_ENV: &str = "VELLUM_ARGV";
+
+/// Version of the MsgPack messages on the socket; bumped on changes that an
+/// older client or binary would misread. Mirrored in bun_bridge.ts.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Warn when the process that started this one speaks another protocol
+/// version. Starting without a handshake (e.g. by hand) is fine.
+pub fn check_protocol_version() {
+    let Ok(theirs) = std::env::var(PROTOCOL_ENV) else {
+        return;
+    };
+    if theirs.trim() != PROTOCOL_VERSION.to_string() {
+        eprintln!(
+            "[Socket] The client speaks protocol version {theirs}, this binary \
+             {PROTOCOL_VERSION}; update @vellum/core and the binary together"
+        );
+    }
+}
 
-/// Returns the platform-specific socket path
+/// The socket the parent chose, or the platform default when started alone.
 pub fn get_socket_path() -> String {
-    if let Ok(path) = std::env::var("Vellum_SOCKET") {
+    if let Ok(path) = std::env::var(SOCKET_ENV) {
         return path;
     }
 
@@ -27,6 +59,14 @@ pub fn get_socket_path() -> String {
     }
 }
 
+/// Per-process socket path used when this binary launches the client runtime
+/// itself, so several hosted apps can run side by side.
+pub fn hosted_socket_path() -> String {
+    let mut path = std::env::temp_dir();
+    path.push(format!("Vellum_{}.sock", std::process::id()));
+    path.to_string_lossy().to_string()
+}
+
 pub fn bind_socket<P: AsRef<Path>>(path: P) -> io::Result<UnixListener> {
     let path = path.as_ref();
     if path.exists() {
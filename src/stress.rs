@@ -0,0 +1,428 @@
+// Synthetic Stress Mode
+// `vellum --stress create:10000,update:10000,remove:10000` stands in for the
+// IPC server and drives the real command handler with a burst of generated
+// commands per phase, then prints commands/sec and frame times. Used to catch
+// regressions in WidgetManager bookkeeping and the style pipeline at a scale
+// no example app reaches.
+//
+// Frame times come from a zero-sized probe widget that keeps requesting
+// animation frames and records the interval between them.
+
+use std::sync::Mutex;
+use std::sync::mpsc::RecvTimeoutError;
+use std::time::{Duration, Instant};
+
+use masonry::accesskit::{Node, Role};
+use masonry::core::{
+    AccessCtx, ChildrenIds, LayoutCtx, MeasureCtx, NewWidget, NoAction, PaintCtx, PropertiesMut,
+    PropertiesRef, RegisterCtx, Update, UpdateCtx, Widget,
+};
+use masonry::vello::Scene;
+
+use crate::ipc::{
+    BoxStyle, ClientCommand, ColorValue, ExitReason, IpcServerChannels, PaddingValue, UiEvent,
+    WidgetKind,
+};
+use crate::ui::widget_factory::{CreateContext, WidgetFactory, register_widget_factory};
+
+const ROOT_ID: &str = "__stress_root__";
+const PROBE_ID: &str = "__stress_probe__";
+const PROBE_KIND: &str = "vellum.stress.frameProbe";
+/// How long to wait for the UI to catch up with a phase before giving up.
+const BARRIER_TIMEOUT: Duration = Duration::from_secs(120);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    /// Create labels under the stress root
+    Create,
+    /// Alternate text and style updates over the live labels
+    Update,
+    /// Remove live labels, newest first
+    Remove,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Step {
+    pub phase: Phase,
+    pub count: usize,
+}
+
+/// Parse a plan like `create:10000,update:10000,remove:10000`.
+pub fn parse_plan(spec: &str) -> Result<Vec<Step>, String> {
+    let steps = spec
+        .split(',')
+        .map(|part| {
+            let (phase, count) = part
+                .trim()
+                .split_once(':')
+                .ok_or_else(|| format!("--stress expects PHASE:COUNT, got '{part}'"))?;
+            let phase = match phase {
+                "create" => Phase::Create,
+                "update" => Phase::Update,
+                "remove" => Phase::Remove,
+                other => {
+                    return Err(format!(
+                        "unknown stress phase '{other}' (expected create, update or remove)"
+                    ));
+                }
+            };
+            let count = count
+                .parse::<usize>()
+                .ok()
+                .filter(|count| *count > 0)
+                .ok_or_else(|| format!("--stress expects a positive count, got '{count}'"))?;
+            Ok(Step { phase, count })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    if steps.is_empty() {
+        return Err("--stress expects at least one phase".into());
+    }
+    Ok(steps)
+}
+
+/// Frame intervals (ns) recorded by the probe since the last `take_intervals`.
+static FRAME_INTERVALS: Mutex<Vec<u64>> = Mutex::new(Vec::new());
+
+fn take_intervals() -> Vec<u64> {
+    std::mem::take(&mut *FRAME_INTERVALS.lock().unwrap_or_else(|e| e.into_inner()))
+}
+
+/// Invisible widget that animates forever so every frame is timed.
+struct FrameProbe;
+
+impl Widget for FrameProbe {
+    type Action = NoAction;
+
+    fn accepts_pointer_interaction(&self) -> bool {
+        false
+    }
+
+    fn register_children(&mut self, _ctx: &mut RegisterCtx<'_>) {}
+
+    fn on_anim_frame(
+        &mut self,
+        ctx: &mut UpdateCtx<'_>,
+        _props: &mut PropertiesMut<'_>,
+        interval: u64,
+    ) {
+        FRAME_INTERVALS
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(interval);
+        ctx.request_anim_frame();
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx<'_>, _props: &mut PropertiesMut<'_>, event: &Update) {
+        if event == &Update::WidgetAdded {
+            ctx.request_anim_frame();
+        }
+    }
+
+    fn measure(
+        &mut self,
+        _ctx: &mut MeasureCtx<'_>,
+        _props: &PropertiesRef<'_>,
+        _axis: masonry::kurbo::Axis,
+        _len_req: masonry::layout::LenReq,
+        _cross_length: Option<f64>,
+    ) -> f64 {
+        0.0
+    }
+
+    fn layout(
+        &mut self,
+        _ctx: &mut LayoutCtx<'_>,
+        _props: &PropertiesRef<'_>,
+        _size: masonry::kurbo::Size,
+    ) {
+    }
+
+    fn paint(&mut self, _ctx: &mut PaintCtx<'_>, _props: &PropertiesRef<'_>, _scene: &mut Scene) {}
+
+    fn accessibility_role(&self) -> Role {
+        Role::GenericContainer
+    }
+
+    fn accessibility(
+        &mut self,
+        _ctx: &mut AccessCtx<'_>,
+        _props: &PropertiesRef<'_>,
+        _node: &mut Node,
+    ) {
+    }
+
+    fn children_ids(&self) -> ChildrenIds {
+        ChildrenIds::new()
+    }
+}
+
+struct FrameProbeFactory;
+
+impl WidgetFactory for FrameProbeFactory {
+    fn create(&self, _ctx: &CreateContext<'_>) -> NewWidget<dyn Widget> {
+        NewWidget::new(FrameProbe).erased()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrameStats {
+    pub count: usize,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub max_ms: f64,
+}
+
+/// Percentiles of a set of frame intervals in nanoseconds.
+pub fn summarize(mut intervals: Vec<u64>) -> Option<FrameStats> {
+    if intervals.is_empty() {
+        return None;
+    }
+    intervals.sort_unstable();
+    let ms = |ns: u64| ns as f64 / 1_000_000.0;
+    let percentile = |p: usize| intervals[(intervals.len() - 1) * p / 100];
+    Some(FrameStats {
+        count: intervals.len(),
+        p50_ms: ms(percentile(50)),
+        p95_ms: ms(percentile(95)),
+        max_ms: ms(intervals[intervals.len() - 1]),
+    })
+}
+
+fn label_id(index: usize) -> String {
+    format!("stress-{index}")
+}
+
+fn label_style(index: usize) -> BoxStyle {
+    let shade = (index % 200) as u8 + 40;
+    BoxStyle {
+        font_size: Some(12.0 + (index % 4) as f32),
+        color: ColorValue::parse("#e0e0e0"),
+        background: Some(ColorValue::Rgba {
+            r: shade,
+            g: 64,
+            b: 255 - shade,
+            a: 255,
+        }),
+        padding: Some(PaddingValue::Uniform(2.0 + (index % 3) as f64)),
+        corner_radius: Some(4.0),
+        ..BoxStyle::default()
+    }
+}
+
+/// Runs a plan against the UI thread, counting the runtime errors it raises.
+struct Runner {
+    channels: IpcServerChannels,
+    /// Labels currently in the tree are `stress-0..live`.
+    live: usize,
+    next_request: u64,
+    errors: usize,
+}
+
+impl Runner {
+    fn send(&self, command: ClientCommand) -> Result<(), String> {
+        self.channels
+            .command_sender
+            .send(command)
+            .map_err(|e| format!("UI closed: {e}"))
+    }
+
+    /// Wait for the UI thread to work through everything sent so far.
+    /// Commands are handled in order, so the reply to a hit test sent last
+    /// arrives only after all of them.
+    fn barrier(&mut self) -> Result<(), String> {
+        self.next_request += 1;
+        let request_id = format!("stress-barrier-{}", self.next_request);
+        self.send(ClientCommand::HitTest {
+            request_id: request_id.clone(),
+            x: 0.0,
+            y: 0.0,
+        })?;
+        let deadline = Instant::now() + BARRIER_TIMEOUT;
+        loop {
+            let wait = deadline.saturating_duration_since(Instant::now());
+            let received = self.channels.event_receiver.recv_timeout(wait);
+            match received.map(|stamped| stamped.event) {
+                Ok(UiEvent::HitTestResult { request_id: id, .. }) if id == request_id => {
+                    return Ok(());
+                }
+                Ok(UiEvent::RuntimeError {
+                    message, command, ..
+                }) => {
+                    if self.errors == 0 {
+                        eprintln!(
+                            "[Stress] {}: {message}",
+                            command.as_deref().unwrap_or("runtime")
+                        );
+                    }
+                    self.errors += 1;
+                }
+                Ok(_) => {}
+                Err(RecvTimeoutError::Timeout) => {
+                    return Err("timed out waiting for the UI thread".into());
+                }
+                Err(RecvTimeoutError::Disconnected) => return Err("UI closed".into()),
+            }
+        }
+    }
+
+    fn run_step(&mut self, step: Step) -> Result<usize, String> {
+        let mut sent = 0;
+        match step.phase {
+            Phase::Create => {
+                for index in self.live..self.live + step.count {
+                    self.send(ClientCommand::CreateWidget {
+                        id: label_id(index),
+                        kind: WidgetKind::Label,
+                        parent_id: Some(ROOT_ID.to_string()),
+                        text: Some(format!("Item {index}")),
+                        style: Some(label_style(index)),
+                        data: None,
+                        badge: None,
+                    })?;
+                    sent += 1;
+                }
+                self.live += step.count;
+            }
+            Phase::Update => {
+                if self.live == 0 {
+                    return Err("update phase needs a create phase before it".into());
+                }
+                for n in 0..step.count {
+                    let index = n % self.live;
+                    let command = if n % 2 == 0 {
+                        ClientCommand::SetWidgetText {
+                            id: label_id(index),
+                            text: format!("Item {index} (update {n})"),
+                        }
+                    } else {
+                        ClientCommand::SetWidgetStyle {
+                            id: label_id(index),
+                            style: label_style(index + n),
+                        }
+                    };
+                    self.send(command)?;
+                    sent += 1;
+                }
+            }
+            Phase::Remove => {
+                let count = step.count.min(self.live);
+                for index in (self.live - count..self.live).rev() {
+                    self.send(ClientCommand::RemoveWidget {
+                        id: label_id(index),
+                    })?;
+                    sent += 1;
+                }
+                self.live -= count;
+            }
+        }
+        Ok(sent)
+    }
+}
+
+/// Run `plan` in place of the IPC server, print a report per phase and close
+/// the window.
+pub fn run_stress(channels: IpcServerChannels, plan: &[Step]) {
+    register_widget_factory(PROBE_KIND, FrameProbeFactory);
+    let mut runner = Runner {
+        channels,
+        live: 0,
+        next_request: 0,
+        errors: 0,
+    };
+    if let Err(e) = run_plan(&mut runner, plan) {
+        eprintln!("[Stress] Aborted: {e}");
+    }
+    if runner.errors > 0 {
+        eprintln!("[Stress] {} commands raised runtime errors", runner.errors);
+    }
+    let _ = runner.send(ClientCommand::ExitApp {
+        code: i32::from(runner.errors > 0),
+        reason: ExitReason::RuntimeExited,
+    });
+}
+
+fn run_plan(runner: &mut Runner, plan: &[Step]) -> Result<(), String> {
+    let setup = [
+        (ROOT_ID, WidgetKind::Flex, None),
+        (
+            PROBE_ID,
+            WidgetKind::Custom(PROBE_KIND.to_string()),
+            Some(ROOT_ID),
+        ),
+    ];
+    for (id, kind, parent) in setup {
+        runner.send(ClientCommand::CreateWidget {
+            id: id.to_string(),
+            kind,
+            parent_id: parent.map(str::to_string),
+            text: None,
+            style: None,
+            data: None,
+            badge: None,
+        })?;
+    }
+    runner.barrier()?;
+
+    for step in plan {
+        take_intervals();
+        let started = Instant::now();
+        let sent = runner.run_step(*step)?;
+        runner.barrier()?;
+        let elapsed = started.elapsed().as_secs_f64();
+        println!(
+            "[Stress] {:?} x{sent}: {:.1} ms, {:.0} commands/sec",
+            step.phase,
+            elapsed * 1000.0,
+            sent as f64 / elapsed.max(f64::EPSILON)
+        );
+        match summarize(take_intervals()) {
+            Some(frames) => println!(
+                "[Stress]   {} frames: p50 {:.2} ms, p95 {:.2} ms, max {:.2} ms",
+                frames.count, frames.p50_ms, frames.p95_ms, frames.max_ms
+            ),
+            None => println!("[Stress]   no frames rendered during the phase"),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_plan() {
+        assert_eq!(
+            parse_plan("create:10000, update:500,remove:10").unwrap(),
+            vec![
+                Step {
+                    phase: Phase::Create,
+                    count: 10000
+                },
+                Step {
+                    phase: Phase::Update,
+                    count: 500
+                },
+                Step {
+                    phase: Phase::Remove,
+                    count: 10
+                },
+            ]
+        );
+        assert!(parse_plan("create").is_err());
+        assert!(parse_plan("create:0").is_err());
+        assert!(parse_plan("resize:10").is_err());
+        assert!(parse_plan("create:many").is_err());
+    }
+
+    #[test]
+    fn test_summarize_frames() {
+        assert_eq!(summarize(Vec::new()), None);
+        let intervals: Vec<u64> = (1..=100).map(|ms| ms * 1_000_000).collect();
+        let stats = summarize(intervals).unwrap();
+        assert_eq!(stats.count, 100);
+        assert_eq!(stats.p50_ms, 50.0);
+        assert_eq!(stats.p95_ms, 95.0);
+        assert_eq!(stats.max_ms, 100.0);
+    }
+}
@@ -0,0 +1,326 @@
+// Visual Regression Testing (feature "test-support")
+// `vellum golden <script.jsonl> <golden.png>` mounts a command script
+// headlessly, renders it and compares the result against a golden image, so
+// the runtime and the apps built on it can catch rendering regressions in CI.
+//
+// A script is one client message per line, in the JSON shape bun_bridge.ts
+// sends over the socket:
+//
+//   {"type": "createWidget", "id": "title", "kind": "label", "text": "Hello"}
+//   {"type": "setWidgetStyle", "id": "title", "style_json": "{\"fontSize\": 24}"}
+//
+// Missing goldens are written from the render; set VELLUM_UPDATE_GOLDENS=1 to
+// rewrite existing ones. On a mismatch `<golden>.actual.png` and
+// `<golden>.diff.png` are written beside the golden.
+
+use std::path::{Path, PathBuf};
+
+use image::{Rgba, RgbaImage};
+use masonry::kurbo::Size;
+use masonry_testing::TestHarness;
+use masonry_winit::app::WindowId;
+
+use crate::ipc::msgpack::ClientMessage;
+use crate::ipc::server::handle_client_message;
+use crate::ipc::{ClientCommand, UiEvent, UiEventSender};
+use crate::ui::handler::handle_client_command;
+use crate::ui::layout::create_initial_ui;
+use crate::ui::theme::widget_defaults;
+use crate::ui::widget_manager::WidgetManager;
+
+const DEFAULT_SIZE: (f64, f64) = (800.0, 600.0);
+const UPDATE_ENV: &str = "VELLUM_UPDATE_GOLDENS";
+
+/// How far a render may drift from its golden and still pass.
+#[derive(Debug, Clone, Copy)]
+pub struct Tolerance {
+    /// Largest per-channel difference (0–255) a pixel may have and still match.
+    pub channel: u8,
+    /// Fraction of pixels (0–1) allowed to exceed `channel`.
+    pub max_differing: f64,
+}
+
+impl Default for Tolerance {
+    /// Absorbs antialiasing differences between GPU drivers.
+    fn default() -> Self {
+        Self {
+            channel: 2,
+            max_differing: 0.001,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageDiff {
+    pub differing: usize,
+    pub total: usize,
+    pub max_delta: u8,
+}
+
+impl ImageDiff {
+    pub fn within(&self, tolerance: &Tolerance) -> bool {
+        self.total == 0 || self.differing as f64 / self.total as f64 <= tolerance.max_differing
+    }
+}
+
+fn channel_delta(a: &Rgba<u8>, b: &Rgba<u8>) -> u8 {
+    a.0.iter()
+        .zip(b.0.iter())
+        .map(|(a, b)| a.abs_diff(*b))
+        .max()
+        .unwrap_or(0)
+}
+
+/// Parse a script, skipping blank lines. Errors name the offending line.
+pub fn parse_script(text: &str) -> Result<Vec<ClientCommand>, String> {
+    let mut commands = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let message: ClientMessage =
+            serde_json::from_str(line).map_err(|e| format!("line {}: {e}", index + 1))?;
+        commands.extend(handle_client_message(message));
+    }
+    Ok(commands)
+}
+
+/// Run `commands` against a fresh widget tree and render it. Also returns the
+/// runtime errors the commands raised, which usually mean a broken script.
+pub fn render_script(commands: Vec<ClientCommand>, size: Size) -> (RgbaImage, Vec<String>) {
+    let root = create_initial_ui(None);
+    let mut harness = TestHarness::create_with_size(widget_defaults().property_set(), root, size);
+    let mut widget_manager = WidgetManager::new();
+    let (event_sender, event_receiver) = UiEventSender::channel();
+    let window_id = WindowId::next();
+    for command in commands {
+        handle_client_command(
+            command,
+            window_id,
+            harness.render_root(),
+            &mut widget_manager,
+            &event_sender,
+        );
+    }
+    let errors = event_receiver
+        .try_iter()
+        .filter_map(|stamped| match stamped.event {
+            UiEvent::RuntimeError {
+                message, command, ..
+            } => Some(match command {
+                Some(command) => format!("{command}: {message}"),
+                None => message,
+            }),
+            _ => None,
+        })
+        .collect();
+    (harness.render(), errors)
+}
+
+/// Compare two renders pixel by pixel. Fails if their sizes differ.
+pub fn compare(
+    actual: &RgbaImage,
+    expected: &RgbaImage,
+    tolerance: &Tolerance,
+) -> Result<ImageDiff, String> {
+    if actual.dimensions() != expected.dimensions() {
+        return Err(format!(
+            "size {:?} does not match the golden's {:?}",
+            actual.dimensions(),
+            expected.dimensions()
+        ));
+    }
+    let mut diff = ImageDiff {
+        differing: 0,
+        total: actual.pixels().len(),
+        max_delta: 0,
+    };
+    for (a, b) in actual.pixels().zip(expected.pixels()) {
+        let delta = channel_delta(a, b);
+        diff.max_delta = diff.max_delta.max(delta);
+        if delta > tolerance.channel {
+            diff.differing += 1;
+        }
+    }
+    Ok(diff)
+}
+
+/// Differing pixels in red over a faded copy of the golden.
+fn diff_image(actual: &RgbaImage, expected: &RgbaImage, tolerance: &Tolerance) -> RgbaImage {
+    RgbaImage::from_fn(actual.width(), actual.height(), |x, y| {
+        let a = actual.get_pixel(x, y);
+        let b = expected.get_pixel(x, y);
+        if channel_delta(a, b) > tolerance.channel {
+            Rgba([255, 0, 0, 255])
+        } else {
+            let [r, g, b, _] = b.0;
+            Rgba([r / 4 + 191, g / 4 + 191, b / 4 + 191, 255])
+        }
+    })
+}
+
+fn sibling(golden: &Path, suffix: &str) -> PathBuf {
+    let stem = golden.file_stem().unwrap_or_default().to_string_lossy();
+    golden.with_file_name(format!("{stem}.{suffix}.png"))
+}
+
+/// Check `actual` against the golden at `golden`, writing it if missing (or
+/// when `VELLUM_UPDATE_GOLDENS` is set).
+pub fn check_golden(
+    actual: &RgbaImage,
+    golden: &Path,
+    tolerance: &Tolerance,
+) -> Result<(), String> {
+    let update = std::env::var_os(UPDATE_ENV).is_some_and(|value| value != "0");
+    if update || !golden.exists() {
+        actual
+            .save(golden)
+            .map_err(|e| format!("could not write {}: {e}", golden.display()))?;
+        println!("[Golden] Wrote {}", golden.display());
+        return Ok(());
+    }
+    let expected = image::open(golden)
+        .map_err(|e| format!("could not read {}: {e}", golden.display()))?
+        .to_rgba8();
+    let failure = match compare(actual, &expected, tolerance) {
+        Ok(diff) if diff.within(tolerance) => return Ok(()),
+        Ok(diff) => {
+            let _ = diff_image(actual, &expected, tolerance).save(sibling(golden, "diff"));
+            format!(
+                "{} of {} pixels differ (max channel delta {})",
+                diff.differing, diff.total, diff.max_delta
+            )
+        }
+        Err(e) => e,
+    };
+    let _ = actual.save(sibling(golden, "actual"));
+    Err(format!("{} does not match: {failure}", golden.display()))
+}
+
+fn parse_size(value: &str) -> Option<Size> {
+    let (width, height) = value.split_once('x')?;
+    Some(Size::new(width.parse().ok()?, height.parse().ok()?))
+}
+
+/// `vellum golden <script.jsonl> <golden.png> [--size WxH] [--tolerance N]
+/// [--max-differing F]`. Returns the process exit code.
+pub fn run_golden(args: &[String]) -> i32 {
+    const USAGE: &str = "usage: vellum golden <script.jsonl> <golden.png> [--size WxH] \
+[--tolerance N] [--max-differing F]";
+    let mut paths = Vec::new();
+    let mut size = Size::new(DEFAULT_SIZE.0, DEFAULT_SIZE.1);
+    let mut tolerance = Tolerance::default();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let parsed = match arg.as_str() {
+            "--size" => iter.next().and_then(|v| parse_size(v)).map(|v| size = v),
+            "--tolerance" => iter
+                .next()
+                .and_then(|v| v.parse().ok())
+                .map(|v| tolerance.channel = v),
+            "--max-differing" => iter
+                .next()
+                .and_then(|v| v.parse().ok())
+                .map(|v| tolerance.max_differing = v),
+            other if !other.starts_with('-') => {
+                paths.push(PathBuf::from(other));
+                Some(())
+            }
+            _ => None,
+        };
+        if parsed.is_none() {
+            eprintln!("[Golden] Bad argument '{arg}'\n{USAGE}");
+            return 2;
+        }
+    }
+    let [script, golden] = paths.as_slice() else {
+        eprintln!("{USAGE}");
+        return 2;
+    };
+
+    let commands = match std::fs::read_to_string(script)
+        .map_err(|e| e.to_string())
+        .and_then(|text| parse_script(&text))
+    {
+        Ok(commands) => commands,
+        Err(e) => {
+            eprintln!("[Golden] {}: {e}", script.display());
+            return 1;
+        }
+    };
+    let (image, errors) = render_script(commands, size);
+    for error in &errors {
+        eprintln!("[Golden] {}: {error}", script.display());
+    }
+    if !errors.is_empty() {
+        return 1;
+    }
+    match check_golden(&image, golden, &tolerance) {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("[Golden] {e}");
+            1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solid(width: u32, height: u32, value: u8) -> RgbaImage {
+        RgbaImage::from_pixel(width, height, Rgba([value, value, value, 255]))
+    }
+
+    #[test]
+    fn test_compare_within_tolerance() {
+        let golden = solid(10, 10, 100);
+        let mut actual = solid(10, 10, 101);
+        actual.put_pixel(3, 3, Rgba([200, 100, 100, 255]));
+
+        let tolerance = Tolerance {
+            channel: 2,
+            max_differing: 0.01,
+        };
+        let diff = compare(&actual, &golden, &tolerance).unwrap();
+        assert_eq!(diff.differing, 1);
+        assert_eq!(diff.max_delta, 100);
+        assert!(diff.within(&tolerance));
+        assert!(!diff.within(&Tolerance {
+            channel: 2,
+            max_differing: 0.0,
+        }));
+        assert!(compare(&solid(10, 9, 100), &golden, &tolerance).is_err());
+    }
+
+    #[test]
+    fn test_check_golden_writes_then_compares() {
+        let dir = std::env::temp_dir().join(format!("vellum-golden-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let golden = dir.join("panel.png");
+        let tolerance = Tolerance::default();
+
+        check_golden(&solid(4, 4, 50), &golden, &tolerance).unwrap();
+        assert!(check_golden(&solid(4, 4, 51), &golden, &tolerance).is_ok());
+        assert!(check_golden(&solid(4, 4, 90), &golden, &tolerance).is_err());
+        assert!(dir.join("panel.actual.png").exists());
+        assert!(dir.join("panel.diff.png").exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_parse_script() {
+        let commands = parse_script(
+            r#"{"type": "setTitle", "title": "Golden"}
+
+{"type": "createWidget", "id": "title", "kind": "label", "text": "Hello"}"#,
+        )
+        .unwrap();
+        assert_eq!(commands.len(), 2);
+        assert!(matches!(&commands[1], ClientCommand::CreateWidget { id, .. } if id == "title"));
+
+        let err = parse_script("{\"type\": \"setTitle\"}").unwrap_err();
+        assert!(err.starts_with("line 1:"));
+    }
+}
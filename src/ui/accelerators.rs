@@ -0,0 +1,226 @@
+// Keyboard Accelerators
+// A Button's `accelerator` param (and a dock menu item's) names a shortcut
+// such as "CmdOrCtrl+S". The same parsed value draws the hint at the end of
+// the button and is matched against key presses the frame clock reports, so
+// the hint and the behavior can't drift apart.
+//
+// A matched Button emits `Click` (with no pointer button, like Space/Enter);
+// a matched dock menu item emits `DockMenuAction`.
+
+use crate::ipc::KeyModifiers;
+
+/// Keys with a name rather than a character, as spelled by masonry's
+/// `NamedKey` (aliases accepted when parsing map to these).
+const NAMED_KEYS: &[&str] = &[
+    "Enter",
+    "Escape",
+    "Tab",
+    "Backspace",
+    "Delete",
+    "Insert",
+    "Home",
+    "End",
+    "PageUp",
+    "PageDown",
+    "ArrowUp",
+    "ArrowDown",
+    "ArrowLeft",
+    "ArrowRight",
+];
+
+/// A key press seen by the frame clock, sent to the driver to look up.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyChord {
+    /// Lowercase character, or a `NamedKey` name such as "ArrowUp"
+    pub key: String,
+    pub modifiers: KeyModifiers,
+}
+
+/// A parsed shortcut.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Accelerator {
+    /// Same spelling as `KeyChord::key`
+    pub key: String,
+    pub modifiers: KeyModifiers,
+}
+
+impl Accelerator {
+    /// Parse "Ctrl+Shift+P", "CmdOrCtrl+S", "Alt+F4", "Shift+Enter".
+    /// Modifier names are case-insensitive; `CmdOrCtrl` (or `Mod`) is Cmd
+    /// on macOS and Ctrl elsewhere.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut parts: Vec<&str> = text.split('+').map(str::trim).collect();
+        // "Ctrl++" binds the plus key itself.
+        if text.ends_with("++") {
+            parts.truncate(parts.len() - 2);
+            parts.push("+");
+        }
+        let key = parts
+            .pop()
+            .filter(|key| !key.is_empty())
+            .ok_or_else(|| format!("accelerator '{text}' has no key"))?;
+
+        let mut modifiers = KeyModifiers::default();
+        for part in parts {
+            match part.to_ascii_lowercase().as_str() {
+                "shift" => modifiers.shift = true,
+                "ctrl" | "control" => modifiers.ctrl = true,
+                "alt" | "option" => modifiers.alt = true,
+                "cmd" | "command" | "meta" | "super" | "win" => modifiers.meta = true,
+                "cmdorctrl" | "commandorcontrol" | "mod" => {
+                    if cfg!(target_os = "macos") {
+                        modifiers.meta = true;
+                    } else {
+                        modifiers.ctrl = true;
+                    }
+                }
+                other => return Err(format!("unknown modifier '{other}' in '{text}'")),
+            }
+        }
+
+        Ok(Self {
+            key: normalize_key(key).ok_or_else(|| format!("unknown key '{key}' in '{text}'"))?,
+            modifiers,
+        })
+    }
+
+    pub fn matches(&self, chord: &KeyChord) -> bool {
+        self.key == chord.key && self.modifiers == chord.modifiers
+    }
+
+    /// The hint shown to users: "⇧⌘S" on macOS, "Ctrl+Shift+S" elsewhere.
+    pub fn label(&self) -> String {
+        let key = key_label(&self.key);
+        let m = self.modifiers;
+        if cfg!(target_os = "macos") {
+            let mut label = String::new();
+            for (held, symbol) in [(m.ctrl, "⌃"), (m.alt, "⌥"), (m.shift, "⇧"), (m.meta, "⌘")]
+            {
+                if held {
+                    label.push_str(symbol);
+                }
+            }
+            label + &key
+        } else {
+            let meta = if cfg!(windows) { "Win" } else { "Super" };
+            let mut parts: Vec<&str> = [
+                (m.ctrl, "Ctrl"),
+                (m.alt, "Alt"),
+                (m.shift, "Shift"),
+                (m.meta, meta),
+            ]
+            .into_iter()
+            .filter_map(|(held, name)| held.then_some(name))
+            .collect();
+            parts.push(&key);
+            parts.join("+")
+        }
+    }
+}
+
+/// Canonical spelling of a key name, or None if it isn't one we know.
+fn normalize_key(key: &str) -> Option<String> {
+    let lower = key.to_ascii_lowercase();
+    let alias = match lower.as_str() {
+        "return" => Some("Enter"),
+        "esc" => Some("Escape"),
+        "del" => Some("Delete"),
+        "up" => Some("ArrowUp"),
+        "down" => Some("ArrowDown"),
+        "left" => Some("ArrowLeft"),
+        "right" => Some("ArrowRight"),
+        "space" => return Some(" ".to_string()),
+        "plus" => return Some("+".to_string()),
+        _ => None,
+    };
+    if let Some(name) = alias {
+        return Some(name.to_string());
+    }
+    if let Some(name) = NAMED_KEYS
+        .iter()
+        .find(|name| name.eq_ignore_ascii_case(key))
+    {
+        return Some(name.to_string());
+    }
+    if let Some(n) = lower.strip_prefix('f').and_then(|n| n.parse::<u8>().ok())
+        && (1..=24).contains(&n)
+    {
+        return Some(format!("F{n}"));
+    }
+    let mut chars = key.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Some(c.to_lowercase().collect()),
+        _ => None,
+    }
+}
+
+fn key_label(key: &str) -> String {
+    let mac = cfg!(target_os = "macos");
+    let named = match key {
+        " " => "Space",
+        "Enter" if mac => "↩",
+        "Escape" if mac => "⎋",
+        "Escape" => "Esc",
+        "Tab" if mac => "⇥",
+        "Backspace" if mac => "⌫",
+        "Delete" if mac => "⌦",
+        "ArrowUp" => "↑",
+        "ArrowDown" => "↓",
+        "ArrowLeft" => "←",
+        "ArrowRight" => "→",
+        "PageUp" if !mac => "PgUp",
+        "PageDown" if !mac => "PgDn",
+        _ => return key.to_uppercase(),
+    };
+    named.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chord(key: &str, modifiers: KeyModifiers) -> KeyChord {
+        KeyChord {
+            key: key.to_string(),
+            modifiers,
+        }
+    }
+
+    #[test]
+    fn test_parse_accelerator() {
+        let save = Accelerator::parse("Ctrl+Shift+S").unwrap();
+        assert_eq!(save.key, "s");
+        assert!(save.modifiers.ctrl && save.modifiers.shift);
+        assert!(save.matches(&chord("s", save.modifiers)));
+        assert!(!save.matches(&chord("s", KeyModifiers::default())));
+
+        assert_eq!(Accelerator::parse("alt+up").unwrap().key, "ArrowUp");
+        assert_eq!(Accelerator::parse("Shift+enter").unwrap().key, "Enter");
+        assert_eq!(Accelerator::parse("F5").unwrap().key, "F5");
+        assert_eq!(Accelerator::parse("Ctrl++").unwrap().key, "+");
+
+        let mod_key = Accelerator::parse("CmdOrCtrl+N").unwrap().modifiers;
+        assert_eq!(mod_key.meta, cfg!(target_os = "macos"));
+        assert_eq!(mod_key.ctrl, !cfg!(target_os = "macos"));
+
+        assert!(Accelerator::parse("Hyper+S").is_err());
+        assert!(Accelerator::parse("Ctrl+").is_err());
+        assert!(Accelerator::parse("Ctrl+Banana").is_err());
+    }
+
+    #[test]
+    fn test_accelerator_label() {
+        let label = Accelerator::parse("Ctrl+Shift+S").unwrap().label();
+        if cfg!(target_os = "macos") {
+            assert_eq!(label, "⌃⇧S");
+        } else {
+            assert_eq!(label, "Ctrl+Shift+S");
+        }
+        assert!(
+            Accelerator::parse("Alt+Left")
+                .unwrap()
+                .label()
+                .ends_with('←')
+        );
+    }
+}
@@ -0,0 +1,172 @@
+// UI-Thread Animations
+// `AnimateValue` tweens a numeric widget property (a progress bar's value, a
+// width) on the UI thread, paced by the window's frame clock, so JS starts an
+// animation with one command instead of streaming a value every frame.
+//
+// Each frame applies the current value through the same commands a data
+// binding uses (see bindings.rs), so anything bindable can be animated.
+
+use std::time::{Duration, Instant};
+
+use masonry::app::RenderRoot;
+use masonry_winit::app::WindowId;
+
+use crate::ipc::{ChannelValue, UiEventSender};
+use crate::ui::bindings::command_for;
+use crate::ui::handler::handle_client_command;
+use crate::ui::widget_manager::WidgetManager;
+use crate::ui::widgets::frame_clock_impl::{FrameClock, edit_frame_clock};
+
+/// `repeat` value that loops until the animation is stopped or replaced.
+pub const REPEAT_FOREVER: u32 = u32::MAX;
+
+/// Sent by the frame clock every frame while animations are running.
+#[derive(Clone, Copy, Debug)]
+pub struct AnimationFrame;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Easing {
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+}
+
+impl Easing {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "linear" => Some(Easing::Linear),
+            "easeIn" | "ease-in" | "ease_in" => Some(Easing::EaseIn),
+            "easeOut" | "ease-out" | "ease_out" => Some(Easing::EaseOut),
+            "easeInOut" | "ease-in-out" | "ease_in_out" | "ease" => Some(Easing::EaseInOut),
+            _ => None,
+        }
+    }
+
+    /// Map linear progress `t` (0–1) onto the curve (cubic).
+    pub fn apply(self, t: f64) -> f64 {
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t * t,
+            Easing::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Easing::EaseInOut => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+                }
+            }
+        }
+    }
+}
+
+/// A running tween of one widget property.
+#[derive(Debug, Clone)]
+pub struct Animation {
+    pub id: String,
+    pub property: String,
+    pub from: f64,
+    pub to: f64,
+    pub duration: Duration,
+    pub easing: Easing,
+    /// Extra runs after the first; `REPEAT_FOREVER` loops.
+    pub repeat: u32,
+    pub started: Instant,
+}
+
+impl Animation {
+    /// The value `elapsed` after the start, and whether the last run is over.
+    pub fn sample(&self, elapsed: Duration) -> (f64, bool) {
+        let runs = elapsed.as_secs_f64() / self.duration.as_secs_f64();
+        let finished = self.repeat != REPEAT_FOREVER && runs >= f64::from(self.repeat) + 1.0;
+        let t = if finished { 1.0 } else { runs.fract() };
+        let eased = self.easing.apply(t);
+        (self.from + (self.to - self.from) * eased, finished)
+    }
+}
+
+/// Apply the current value of every running animation and drop finished ones.
+/// Stops the frame clock's animation pacing once none are left.
+pub fn step(
+    window_id: WindowId,
+    render_root: &mut RenderRoot,
+    widget_manager: &mut WidgetManager,
+    event_sender: &UiEventSender,
+) {
+    let now = Instant::now();
+    let running = std::mem::take(&mut widget_manager.animations);
+    let mut remaining = Vec::with_capacity(running.len());
+    widget_manager.journal.pause();
+    for animation in running {
+        let (value, finished) = animation.sample(now.duration_since(animation.started));
+        // Validated when the animation started.
+        if let Ok(cmd) = command_for(
+            &animation.id,
+            &animation.property,
+            &ChannelValue::Number(value),
+        ) {
+            handle_client_command(cmd, window_id, render_root, widget_manager, event_sender);
+        }
+        if !finished {
+            remaining.push(animation);
+        }
+    }
+    widget_manager.journal.resume();
+    // Animations started by the commands above are already in the list.
+    remaining.append(&mut widget_manager.animations);
+    widget_manager.animations = remaining;
+
+    if widget_manager.animations.is_empty() {
+        edit_frame_clock(render_root, |clock| FrameClock::set_animating(clock, false));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn animation(repeat: u32) -> Animation {
+        Animation {
+            id: "progress".to_string(),
+            property: "value".to_string(),
+            from: 0.0,
+            to: 100.0,
+            duration: Duration::from_millis(1000),
+            easing: Easing::Linear,
+            repeat,
+            started: Instant::now(),
+        }
+    }
+
+    #[test]
+    fn test_easing_endpoints() {
+        for easing in [
+            Easing::Linear,
+            Easing::EaseIn,
+            Easing::EaseOut,
+            Easing::EaseInOut,
+        ] {
+            assert_eq!(easing.apply(0.0), 0.0);
+            assert_eq!(easing.apply(1.0), 1.0);
+        }
+        assert!(Easing::EaseIn.apply(0.5) < 0.5);
+        assert!(Easing::EaseOut.apply(0.5) > 0.5);
+        assert_eq!(Easing::EaseInOut.apply(0.5), 0.5);
+        assert_eq!(Easing::parse("ease-out"), Some(Easing::EaseOut));
+        assert_eq!(Easing::parse("bounce"), None);
+    }
+
+    #[test]
+    fn test_sample_runs_and_repeats() {
+        let once = animation(0);
+        assert_eq!(once.sample(Duration::from_millis(250)), (25.0, false));
+        assert_eq!(once.sample(Duration::from_millis(1500)), (100.0, true));
+
+        let twice = animation(1);
+        assert_eq!(twice.sample(Duration::from_millis(1250)), (25.0, false));
+        assert_eq!(twice.sample(Duration::from_millis(2000)), (100.0, true));
+
+        let forever = animation(REPEAT_FOREVER);
+        assert_eq!(forever.sample(Duration::from_secs(3600)), (0.0, false));
+    }
+}
@@ -0,0 +1,105 @@
+// Data Bindings
+// `BindValue` ties a widget property to a named channel; every `PushChannel`
+// on that channel is applied to all bound properties on the UI thread. One
+// small frame per value then drives any number of widgets, which keeps
+// 60fps updates (meters, scrubbers, live counters) cheap over IPC.
+//
+// A push is applied by re-dispatching the command JS would otherwise have
+// sent, so bound updates behave exactly like direct ones.
+
+use crate::ipc::{BoxStyle, ChannelValue, ClientCommand};
+
+/// A widget property fed by a channel.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Binding {
+    /// JS widget id
+    pub id: String,
+    /// `value`, `text`, `visible`, `checked`, or a style key such as `width`
+    pub property: String,
+}
+
+/// The command that sets `property` on widget `id` to `value`.
+pub fn command_for(
+    id: &str,
+    property: &str,
+    value: &ChannelValue,
+) -> Result<ClientCommand, String> {
+    let id = id.to_string();
+    let command = match property {
+        "value" => ClientCommand::SetWidgetValue {
+            id,
+            value: value
+                .as_number()
+                .ok_or_else(|| format!("'value' expects a number, got {value}"))?,
+        },
+        "text" => ClientCommand::SetWidgetText {
+            id,
+            text: value.to_string(),
+        },
+        "visible" => ClientCommand::SetWidgetVisible {
+            id,
+            visible: value.is_truthy(),
+        },
+        "enabled" => ClientCommand::SetWidgetEnabled {
+            id,
+            enabled: value.is_truthy(),
+        },
+        "checked" => ClientCommand::SetWidgetChecked {
+            id,
+            checked: value.is_truthy(),
+        },
+        key => {
+            let partial = serde_json::json!({ key: value });
+            let style: BoxStyle = serde_json::from_value(partial)
+                .map_err(|e| format!("invalid value {value} for style '{key}': {e}"))?;
+            // Unknown keys deserialize to an empty style; catch them here
+            // rather than silently dropping every push.
+            let applied = serde_json::to_value(&style).map_err(|e| e.to_string())?;
+            if applied.get(key).is_none_or(|v| v.is_null()) {
+                return Err(format!("'{key}' is not a bindable property"));
+            }
+            ClientCommand::SetWidgetStyle { id, style }
+        }
+    };
+    Ok(command)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_widget_properties() {
+        assert!(matches!(
+            command_for("volume", "value", &ChannelValue::Number(0.5)),
+            Ok(ClientCommand::SetWidgetValue { value, .. }) if value == 0.5
+        ));
+        assert!(matches!(
+            command_for("fps", "text", &ChannelValue::Number(60.0)),
+            Ok(ClientCommand::SetWidgetText { text, .. }) if text == "60"
+        ));
+        assert!(matches!(
+            command_for("hint", "visible", &ChannelValue::Number(0.0)),
+            Ok(ClientCommand::SetWidgetVisible { visible: false, .. })
+        ));
+        assert!(matches!(
+            command_for("save", "enabled", &ChannelValue::Bool(true)),
+            Ok(ClientCommand::SetWidgetEnabled { enabled: true, .. })
+        ));
+        assert!(command_for("volume", "value", &ChannelValue::Text("loud".into())).is_err());
+    }
+
+    #[test]
+    fn test_style_properties() {
+        assert!(matches!(
+            command_for("meter", "width", &ChannelValue::Number(120.0)),
+            Ok(ClientCommand::SetWidgetStyle { style, .. }) if style.width == Some(120.0)
+        ));
+        assert!(matches!(
+            command_for("meter", "background", &ChannelValue::Text("#ff0000".into())),
+            Ok(ClientCommand::SetWidgetStyle { style, .. }) if style.background.is_some()
+        ));
+        assert!(command_for("meter", "wobble", &ChannelValue::Number(1.0)).is_err());
+        assert!(command_for("meter", "width", &ChannelValue::Text("wide".into())).is_err());
+    }
+}
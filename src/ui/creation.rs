@@ -1,8 +1,10 @@
 use masonry::app::RenderRoot;
 
+use super::styles::scaled;
 use super::widget_manager::WidgetManager;
 use super::widgets;
-use crate::ipc::{BoxStyle, WidgetData, WidgetKind};
+use super::widgets::utils::WrapperOptions;
+use crate::ipc::{BadgeSpec, BoxStyle, OverflowValue, WidgetData, WidgetKind};
 
 #[allow(clippy::too_many_arguments)]
 pub fn create_and_add_widget(
@@ -12,8 +14,9 @@ pub fn create_and_add_widget(
     kind: WidgetKind,
     parent_id: Option<String>,
     text: Option<String>,
-    style: Option<BoxStyle>,
+    mut style: Option<BoxStyle>,
     data: Option<WidgetData>,
+    badge: Option<BadgeSpec>,
 ) {
     println!(
         "[UI] Creating widget: id={}, kind={:?}, parent={:?}",
@@ -22,9 +25,41 @@ pub fn create_and_add_widget(
 
     let parent_key = parent_id.as_deref().unwrap_or("__root__").to_string();
     let child_index = widget_manager.next_child_index(&parent_key);
+    let mut wrappers = WrapperOptions {
+        badge,
+        clip: style
+            .as_ref()
+            .filter(|s| matches!(s.overflow, Some(OverflowValue::Hidden)))
+            .map(|s| scaled(s.corner_radius.unwrap_or(0.0))),
+        sticky: style.as_ref().and_then(|s| s.sticky).unwrap_or(false),
+        ..WrapperOptions::default()
+    };
+    if let Some(style) = style.as_mut().filter(|s| s.background_image.is_some()) {
+        if widgets::background::supports(&kind) {
+            wrappers.background = widgets::background::take_spec(style);
+        } else {
+            eprintln!("[UI] Ignoring backgroundImage on {kind:?} '{id}'; only containers take one");
+        }
+    }
+    // Content of a variant Button takes the variant's text color by default.
+    if let Some(color) = widget_manager
+        .widgets
+        .get(&parent_key)
+        .and_then(|parent| widget_manager.button_text_colors.get(&parent.widget_id))
+    {
+        let style = style.get_or_insert_with(BoxStyle::default);
+        if style.color.is_none() {
+            style.color = Some(color.clone());
+        }
+    }
+    let z_index = style
+        .as_ref()
+        .and_then(|s| s.z_index)
+        .map(|z| (id.clone(), z));
 
     match kind {
         WidgetKind::Label => {
+            let selectable = matches!(data, Some(WidgetData::Label { selectable: true }));
             widgets::label::create(
                 render_root,
                 widget_manager,
@@ -32,7 +67,9 @@ pub fn create_and_add_widget(
                 parent_id,
                 text,
                 style,
+                selectable,
                 child_index,
+                wrappers,
             );
         }
         WidgetKind::Button => {
@@ -42,7 +79,9 @@ pub fn create_and_add_widget(
                 id,
                 parent_id,
                 style,
+                data,
                 child_index,
+                wrappers,
             );
         }
         WidgetKind::Svg => {
@@ -55,6 +94,7 @@ pub fn create_and_add_widget(
                 style,
                 data,
                 child_index,
+                wrappers,
             );
         }
         WidgetKind::Flex | WidgetKind::Container => {
@@ -67,6 +107,7 @@ pub fn create_and_add_widget(
                 style,
                 data,
                 child_index,
+                wrappers,
             );
         }
         WidgetKind::SizedBox => {
@@ -77,6 +118,7 @@ pub fn create_and_add_widget(
                 parent_id,
                 style,
                 child_index,
+                wrappers,
             );
         }
         WidgetKind::Checkbox => {
@@ -89,6 +131,7 @@ pub fn create_and_add_widget(
                 style,
                 data,
                 child_index,
+                wrappers,
             );
         }
         WidgetKind::TextInput => {
@@ -101,6 +144,7 @@ pub fn create_and_add_widget(
                 style,
                 data,
                 child_index,
+                wrappers,
             );
         }
         WidgetKind::TextArea => {
@@ -112,6 +156,7 @@ pub fn create_and_add_widget(
                 text,
                 style,
                 child_index,
+                wrappers,
             );
         }
         WidgetKind::Prose => {
@@ -123,6 +168,7 @@ pub fn create_and_add_widget(
                 text,
                 style,
                 child_index,
+                wrappers,
             );
         }
         WidgetKind::ProgressBar => {
@@ -134,6 +180,7 @@ pub fn create_and_add_widget(
                 style,
                 data,
                 child_index,
+                wrappers,
             );
         }
         WidgetKind::Spinner => {
@@ -144,6 +191,7 @@ pub fn create_and_add_widget(
                 parent_id,
                 style,
                 child_index,
+                wrappers,
             );
         }
         WidgetKind::Slider => {
@@ -155,6 +203,19 @@ pub fn create_and_add_widget(
                 style,
                 data,
                 child_index,
+                wrappers,
+            );
+        }
+        WidgetKind::RangeSlider => {
+            widgets::range_slider::create(
+                render_root,
+                widget_manager,
+                id,
+                parent_id,
+                style,
+                data,
+                child_index,
+                wrappers,
             );
         }
         WidgetKind::ZStack => {
@@ -165,6 +226,7 @@ pub fn create_and_add_widget(
                 parent_id,
                 style,
                 child_index,
+                wrappers,
             );
         }
         WidgetKind::Portal => {
@@ -174,7 +236,9 @@ pub fn create_and_add_widget(
                 id,
                 parent_id,
                 style,
+                data,
                 child_index,
+                wrappers,
             );
         }
         WidgetKind::Grid => {
@@ -185,6 +249,18 @@ pub fn create_and_add_widget(
                 parent_id,
                 style,
                 child_index,
+                wrappers,
+            );
+        }
+        WidgetKind::ReorderableList => {
+            widgets::reorderable_list::create(
+                render_root,
+                widget_manager,
+                id,
+                parent_id,
+                style,
+                child_index,
+                wrappers,
             );
         }
         WidgetKind::Hoverable => {
@@ -195,6 +271,82 @@ pub fn create_and_add_widget(
                 parent_id,
                 style,
                 child_index,
+                wrappers,
+            );
+        }
+        WidgetKind::Skeleton => {
+            widgets::skeleton::create(
+                render_root,
+                widget_manager,
+                id,
+                parent_id,
+                style,
+                data,
+                child_index,
+                wrappers,
+            );
+        }
+        WidgetKind::Icon => {
+            widgets::icon::create(
+                render_root,
+                widget_manager,
+                id,
+                parent_id,
+                text,
+                style,
+                data,
+                child_index,
+                wrappers,
+            );
+        }
+        WidgetKind::QrCode => {
+            widgets::qr_code::create(
+                render_root,
+                widget_manager,
+                id,
+                parent_id,
+                text,
+                style,
+                data,
+                child_index,
+                wrappers,
+            );
+        }
+        WidgetKind::Avatar => {
+            widgets::avatar::create(
+                render_root,
+                widget_manager,
+                id,
+                parent_id,
+                text,
+                style,
+                data,
+                child_index,
+                wrappers,
+            );
+        }
+        WidgetKind::CustomPaint => {
+            widgets::custom_paint::create(
+                render_root,
+                widget_manager,
+                id,
+                parent_id,
+                style,
+                data,
+                child_index,
+                wrappers,
+            );
+        }
+        WidgetKind::GpuSurface => {
+            widgets::gpu_surface::create(
+                render_root,
+                widget_manager,
+                id,
+                parent_id,
+                style,
+                data,
+                child_index,
+                wrappers,
             );
         }
         WidgetKind::Custom(_) => {
@@ -207,6 +359,7 @@ pub fn create_and_add_widget(
                 text,
                 style,
                 child_index,
+                wrappers,
             );
         }
         WidgetKind::Image => {
@@ -218,6 +371,7 @@ pub fn create_and_add_widget(
                 style,
                 data,
                 child_index,
+                wrappers,
             );
         }
         WidgetKind::Video => {
@@ -229,7 +383,16 @@ pub fn create_and_add_widget(
                 style,
                 data,
                 child_index,
+                wrappers,
             );
         }
     }
+
+    if let Some((id, z_index)) = z_index
+        && widget_manager.widgets.contains_key(&id)
+        && let Err(message) =
+            widgets::zstack::set_z_index(render_root, widget_manager, &id, z_index)
+    {
+        eprintln!("[UI] {message}");
+    }
 }
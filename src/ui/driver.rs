@@ -1,25 +1,48 @@
+use std::time::Instant;
+
 use masonry::core::{ErasedAction, WidgetId};
 use masonry::widgets::{ButtonPress, Checkbox, CheckboxToggled};
 use masonry_winit::app::{AppDriver, DriverCtx, WindowId};
 
-use crate::ipc::{ClientCommandAction, UiEvent, UiEventSender, WidgetActionKind};
+use crate::audio::OutputChanged;
+use crate::hooks::{self, CommandApplied};
+use crate::ipc::{
+    ClickInput, ClientCommand, ClientCommandAction, DrainCommands, ErrorCode, ExitReason, UiEvent,
+    UiEventSender, WidgetActionKind, WidgetKind,
+};
 
-use super::global_state::set_global_wgpu;
-use super::handler::handle_client_command;
+use super::accelerators::KeyChord;
+use super::animations::{self, AnimationFrame};
+use super::global_state::{last_click_input, set_global_wgpu};
+use super::handler::{collect_tree_issues, handle_client_command, hit_test};
+use super::widget_factory::factory_for;
 use super::widget_manager::{WidgetInfo, WidgetManager};
+use super::widgets::custom_paint_impl::PaintPointer;
+use super::widgets::frame_clock_impl::{PressGesture, PressGestureKind};
+use super::widgets::gpu_surface_impl::{GpuSurfaceAction, GpuSurfaceWidget};
 use super::widgets::hoverable::HoverAction;
+use super::widgets::range_slider_impl::RangeChanged;
+use super::widgets::reorderable_list_impl::ItemMoved;
+use super::widgets::scroll_area_impl::RefreshRequested;
+use super::widgets::splash::{self, RETRY_TAG};
+use super::widgets::video_registry::{self, SweepVideos};
 use super::widgets::video_widget_impl::{VideoAction, VideoWidget};
+use super::widgets::viewport::{self, SweepViewport};
 use masonry_winit::app::WgpuContext;
 
 /// Application driver that bridges JS runtime commands with the masonry UI.
 ///
-/// When on_action is called with a ClientCommandAction (sent via EventLoopProxy from the JS thread),
-/// it mutates the widget tree to create, update, or remove widgets.
+/// When on_async_action is called with a batch of commands (queued by the JS thread, which
+/// wakes the event loop through EventLoopProxy), it mutates the widget tree to create, update,
+/// or remove widgets.
 pub struct VellumDriver {
     /// Sender for UI events back to the JS thread
     pub event_sender: UiEventSender,
     /// Manages JS widget ID → masonry WidgetId mapping
     pub widget_manager: WidgetManager,
+    /// Audit bookkeeping after every create and remove (`VELLUM_AUDIT_TREE`).
+    /// Each audit walks every widget, so it is off by default.
+    audit_tree: bool,
 }
 
 impl VellumDriver {
@@ -27,6 +50,7 @@ impl VellumDriver {
         Self {
             event_sender,
             widget_manager: WidgetManager::new(),
+            audit_tree: std::env::var_os("VELLUM_AUDIT_TREE").is_some_and(|v| v != "0"),
         }
     }
 
@@ -39,11 +63,102 @@ impl VellumDriver {
             .map(|(id, _): (&String, &WidgetInfo)| id.clone())
     }
 
-    fn report_runtime_error(&self, source: &str, message: String, fatal: bool) {
+    /// Apply `cmd`, telling any runtime hooks how long it took.
+    fn handle_command(
+        &mut self,
+        window_id: WindowId,
+        ctx: &mut DriverCtx<'_, '_>,
+        cmd: ClientCommand,
+    ) {
+        if !hooks::is_active() {
+            self.apply_command(window_id, ctx, cmd);
+            return;
+        }
+        let name = cmd.name();
+        let widget_id = cmd.widget_id().map(str::to_string);
+        let started = Instant::now();
+        self.apply_command(window_id, ctx, cmd);
+        hooks::command_applied(&CommandApplied {
+            name,
+            widget_id: widget_id.as_deref(),
+            duration: started.elapsed(),
+        });
+    }
+
+    fn apply_command(
+        &mut self,
+        window_id: WindowId,
+        ctx: &mut DriverCtx<'_, '_>,
+        cmd: ClientCommand,
+    ) {
+        if let ClientCommand::SetContentProtection(enabled) = cmd {
+            self.set_content_protection(window_id, ctx, enabled);
+            return;
+        }
+        let structural = self.audit_tree
+            && matches!(
+                cmd,
+                ClientCommand::CreateWidget { .. } | ClientCommand::RemoveWidget { .. }
+            );
+        let render_root = ctx.render_root(window_id);
+        handle_client_command(
+            cmd,
+            window_id,
+            render_root,
+            &mut self.widget_manager,
+            &self.event_sender,
+        );
+        if let Some((can_undo, can_redo)) = self.widget_manager.journal.take_change()
+            && let Err(send_err) = self
+                .event_sender
+                .send(UiEvent::JournalChanged { can_undo, can_redo })
+        {
+            eprintln!("[UI] Failed to send journal state to JS thread: {send_err}");
+        }
+        if structural {
+            let issues = collect_tree_issues(render_root, &self.widget_manager);
+            if !issues.is_empty() {
+                self.report_runtime_error(
+                    "tree-audit",
+                    ErrorCode::TreeInconsistent,
+                    format!("Widget bookkeeping diverged:\n  {}", issues.join("\n  ")),
+                );
+            }
+        }
+    }
+
+    /// Keep the window's contents out of screenshots, screen recordings and
+    /// screen sharing. winit can only do so on macOS and Windows.
+    fn set_content_protection(
+        &self,
+        window_id: WindowId,
+        ctx: &mut DriverCtx<'_, '_>,
+        enabled: bool,
+    ) {
+        if cfg!(any(target_os = "macos", target_os = "windows")) {
+            ctx.window_handle(window_id).set_content_protected(enabled);
+        } else if enabled
+            && let Err(send_err) = self.event_sender.send(UiEvent::RuntimeError {
+                source: "ui-driver".to_string(),
+                code: ErrorCode::UnsupportedOperation,
+                message: "Content protection is only available on macOS and Windows".to_string(),
+                fatal: false,
+                widget_id: None,
+                command: Some("SetContentProtection".to_string()),
+            })
+        {
+            eprintln!("[UI] Failed to report runtime error to JS thread: {send_err}");
+        }
+    }
+
+    fn report_runtime_error(&self, source: &str, code: ErrorCode, message: String) {
         if let Err(send_err) = self.event_sender.send(UiEvent::RuntimeError {
             source: source.to_string(),
+            code,
             message,
-            fatal,
+            fatal: false,
+            widget_id: None,
+            command: None,
         }) {
             eprintln!("[UI] Failed to report runtime error to JS thread: {send_err}");
         }
@@ -60,6 +175,24 @@ impl AppDriver for VellumDriver {
     ) {
         let type_name = action.type_name();
 
+        // Custom widgets registered through a factory map their own actions.
+        if let Some((id, info)) = self
+            .widget_manager
+            .widgets
+            .iter()
+            .find(|(_, info)| info.widget_id == widget_id)
+            && let WidgetKind::Custom(name) = &info.kind
+            && let Some(kind) = factory_for(name).and_then(|factory| factory.map_action(&action))
+        {
+            if let Err(send_err) = self.event_sender.send(UiEvent::WidgetAction {
+                widget_id: id.clone(),
+                action: kind,
+            }) {
+                eprintln!("[UI] Failed to forward custom widget action to JS thread: {send_err}");
+            }
+            return;
+        }
+
         // Handle CheckboxToggled: auto-toggle + dispatch event
         if let Some(toggled) = action.downcast_ref::<CheckboxToggled>() {
             // Auto-toggle the checkbox visual state
@@ -93,6 +226,21 @@ impl AppDriver for VellumDriver {
             return;
         }
 
+        if let Some(range) = action.downcast_ref::<RangeChanged>() {
+            if let Some(id) = self.find_client_id(widget_id)
+                && let Err(send_err) = self.event_sender.send(UiEvent::WidgetAction {
+                    widget_id: id,
+                    action: WidgetActionKind::RangeChanged {
+                        low: range.low,
+                        high: range.high,
+                    },
+                })
+            {
+                eprintln!("[UI] Failed to forward range change to JS thread: {send_err}");
+            }
+            return;
+        }
+
         // Handle Slider value change (Action = f64)
         if let Some(&value) = action.downcast_ref::<f64>() {
             if let Some(id) = self.find_client_id(widget_id)
@@ -106,12 +254,26 @@ impl AppDriver for VellumDriver {
             return;
         }
 
+        // The splash's Retry button, after no client connected in time.
+        if action.is::<ButtonPress>()
+            && ctx
+                .render_root(window_id)
+                .get_widget_with_tag(RETRY_TAG)
+                .is_some_and(|button| button.id() == widget_id)
+        {
+            let render_root = ctx.render_root(window_id);
+            splash::set_status(render_root, String::new(), false);
+            crate::runtime::request_restart();
+            crate::ipc::server::retry_handshake();
+            return;
+        }
+
         // Handle button presses exactly as Masonry examples do.
         if action.is::<ButtonPress>() {
             if let Some(id) = self.find_client_id(widget_id)
                 && let Err(send_err) = self.event_sender.send(UiEvent::WidgetAction {
                     widget_id: id,
-                    action: WidgetActionKind::Click,
+                    action: WidgetActionKind::click(last_click_input()),
                 })
             {
                 eprintln!("[UI] Failed to forward button click to JS thread: {send_err}");
@@ -119,6 +281,48 @@ impl AppDriver for VellumDriver {
             return;
         }
 
+        if let Some(&ItemMoved { from, to }) = action.downcast_ref::<ItemMoved>() {
+            if let Some(id) = self.find_client_id(widget_id) {
+                // The list already moved the row; keep child indices in step.
+                self.widget_manager.move_child(&id, from, to);
+                if let Err(send_err) = self.event_sender.send(UiEvent::WidgetAction {
+                    widget_id: id,
+                    action: WidgetActionKind::ItemMoved { from, to },
+                }) {
+                    eprintln!("[UI] Failed to forward item move to JS thread: {send_err}");
+                }
+            }
+            return;
+        }
+
+        if action.is::<RefreshRequested>() {
+            if let Some(id) = self.find_client_id(widget_id)
+                && let Err(send_err) = self.event_sender.send(UiEvent::WidgetAction {
+                    widget_id: id,
+                    action: WidgetActionKind::RefreshRequested,
+                })
+            {
+                eprintln!("[UI] Failed to forward refresh request to JS thread: {send_err}");
+            }
+            return;
+        }
+
+        if let Some(pointer) = action.downcast_ref::<PaintPointer>() {
+            if let Some(id) = self.find_client_id(widget_id)
+                && let Err(send_err) = self.event_sender.send(UiEvent::WidgetAction {
+                    widget_id: id,
+                    action: WidgetActionKind::Pointer {
+                        phase: pointer.phase,
+                        x: pointer.x,
+                        y: pointer.y,
+                    },
+                })
+            {
+                eprintln!("[UI] Failed to forward canvas pointer event to JS thread: {send_err}");
+            }
+            return;
+        }
+
         // Unknown action
         println!(
             "[UI] Unhandled widget action on {:?}: {}",
@@ -126,8 +330,8 @@ impl AppDriver for VellumDriver {
         );
         self.report_runtime_error(
             "ui-driver",
+            ErrorCode::Internal,
             format!("Unhandled widget action on {widget_id:?}: {type_name}"),
-            false,
         );
     }
 
@@ -137,17 +341,17 @@ impl AppDriver for VellumDriver {
         ctx: &mut DriverCtx<'_, '_>,
         action: ErasedAction,
     ) {
-        // Check if this action is a ClientCommandAction sent via EventLoopProxy
+        // Commands from the JS thread, queued and drained a batch at a time.
+        if let Some(DrainCommands(queue)) = action.downcast_ref::<DrainCommands>() {
+            for cmd in queue.drain() {
+                self.handle_command(window_id, ctx, cmd);
+            }
+            return;
+        }
+
+        // A single command sent straight through the EventLoopProxy.
         if let Some(client_action) = action.downcast_ref::<ClientCommandAction>() {
-            let cmd = client_action.0.clone();
-            let render_root = ctx.render_root(window_id);
-            handle_client_command(
-                cmd,
-                window_id,
-                render_root,
-                &mut self.widget_manager,
-                &self.event_sender,
-            );
+            self.handle_command(window_id, ctx, client_action.0.clone());
             return;
         }
 
@@ -171,8 +375,149 @@ impl AppDriver for VellumDriver {
                             VideoWidget::on_frame_ready(&mut video);
                         });
                 }
+                VideoAction::Failed(widget_id, message) => {
+                    ctx.render_root(window_id)
+                        .edit_widget(*widget_id, |mut widget| {
+                            let mut video = widget.downcast::<VideoWidget>();
+                            VideoWidget::set_error(&mut video, message);
+                        });
+                    if let Some(id) = self.find_client_id(*widget_id) {
+                        let event = UiEvent::VideoError {
+                            id,
+                            message: message.clone(),
+                        };
+                        if let Err(send_err) = self.event_sender.send(event) {
+                            eprintln!("[UI] Failed to send VideoError to JS thread: {send_err}");
+                        }
+                    }
+                }
+            }
+        }
+
+        if action.is::<OutputChanged>() {
+            // Playing videos switch over by reopening their pipelines.
+            let render_root = ctx.render_root(window_id);
+            for info in self.widget_manager.widgets.values() {
+                if matches!(info.kind, WidgetKind::Video) {
+                    render_root.edit_widget(info.widget_id, |mut widget| {
+                        VideoWidget::reload(&mut widget.downcast::<VideoWidget>());
+                    });
+                }
             }
+            return;
         }
+
+        if action.is::<SweepVideos>() {
+            video_registry::sweep(ctx.render_root(window_id));
+            return;
+        }
+
+        if action.is::<SweepViewport>() {
+            let events = viewport::sweep(ctx.render_root(window_id), &mut self.widget_manager);
+            for event in events {
+                if let Err(send_err) = self.event_sender.send(event) {
+                    eprintln!("[UI] Failed to forward a viewport change to JS thread: {send_err}");
+                }
+            }
+            return;
+        }
+
+        if action.is::<AnimationFrame>() {
+            let render_root = ctx.render_root(window_id);
+            animations::step(
+                window_id,
+                render_root,
+                &mut self.widget_manager,
+                &self.event_sender,
+            );
+            return;
+        }
+
+        // A key press matching an accelerator clicks its button or chooses
+        // its dock menu item, the same as doing so by hand.
+        if let Some(chord) = action.downcast_ref::<KeyChord>() {
+            let event = if let Some((&widget_id, _)) = self
+                .widget_manager
+                .accelerators
+                .iter()
+                .find(|(_, accelerator)| accelerator.matches(chord))
+            {
+                self.find_client_id(widget_id)
+                    .map(|id| UiEvent::WidgetAction {
+                        widget_id: id,
+                        action: WidgetActionKind::click(ClickInput {
+                            button: None,
+                            count: 1,
+                            modifiers: chord.modifiers,
+                        }),
+                    })
+            } else {
+                self.widget_manager
+                    .menu_accelerators
+                    .iter()
+                    .find(|(_, accelerator)| accelerator.matches(chord))
+                    .map(|(id, _)| UiEvent::DockMenuAction { id: id.clone() })
+            };
+            if let Some(event) = event
+                && let Err(send_err) = self.event_sender.send(event)
+            {
+                eprintln!("[UI] Failed to forward accelerator to JS thread: {send_err}");
+            }
+            return;
+        }
+
+        // Gestures are recognized at the root; the JS widget under the press gets them.
+        if let Some(gesture) = action.downcast_ref::<PressGesture>() {
+            let render_root = ctx.render_root(window_id);
+            if let Some((id, local)) = hit_test(render_root, &self.widget_manager, gesture.position)
+            {
+                let action = match gesture.kind {
+                    PressGestureKind::DoubleClick => WidgetActionKind::DoubleClick {
+                        button: gesture.button,
+                        modifiers: gesture.modifiers,
+                        x: local.x,
+                        y: local.y,
+                    },
+                    PressGestureKind::LongPress => WidgetActionKind::LongPress {
+                        modifiers: gesture.modifiers,
+                        x: local.x,
+                        y: local.y,
+                    },
+                };
+                if let Err(send_err) = self.event_sender.send(UiEvent::WidgetAction {
+                    widget_id: id,
+                    action,
+                }) {
+                    eprintln!("[UI] Failed to forward press gesture to JS thread: {send_err}");
+                }
+            }
+            return;
+        }
+
+        if let Some(GpuSurfaceAction::OverrideReady(widget_id)) =
+            action.downcast_ref::<GpuSurfaceAction>()
+        {
+            ctx.render_root(window_id)
+                .edit_widget(*widget_id, |mut widget| {
+                    let mut surface = widget.downcast::<GpuSurfaceWidget>();
+                    GpuSurfaceWidget::on_override_ready(&mut surface);
+                });
+        }
+    }
+
+    /// The close button, Alt-F4 or the window menu: a quit like any other, so
+    /// JS gets its `BeforeExit` grace period, or just `closeRequested` in a kiosk.
+    fn on_close_requested(&mut self, window_id: WindowId, ctx: &mut DriverCtx<'_, '_>) {
+        handle_client_command(
+            ClientCommand::ExitApp {
+                code: 0,
+                reason: ExitReason::Quit,
+            },
+            window_id,
+            ctx.render_root(window_id),
+            &mut self.widget_manager,
+            &self.event_sender,
+        );
     }
 
     fn on_wgpu_ready(&mut self, wgpu: &WgpuContext<'_>) {
@@ -0,0 +1,66 @@
+use masonry::parley::fontique::{Collection, CollectionOptions, FontStyle};
+
+use crate::ipc::{FontFace, FontFamilyInfo};
+
+/// Enumerate installed font families and their faces, sorted by family name.
+/// Scans the system font directories, so call it off the UI thread.
+pub fn list_system_fonts() -> Vec<FontFamilyInfo> {
+    let mut collection = Collection::new(CollectionOptions {
+        shared: false,
+        system_fonts: true,
+    });
+    let mut names: Vec<String> = collection.family_names().map(str::to_string).collect();
+    names.sort_by_key(|name| name.to_lowercase());
+    names.dedup();
+
+    names
+        .into_iter()
+        .filter_map(|name| {
+            let family = collection.family_by_name(&name)?;
+            let mut faces: Vec<FontFace> = family
+                .fonts()
+                .iter()
+                .map(|font| FontFace {
+                    weight: font.weight().value(),
+                    style: style_name(font.style()).to_string(),
+                })
+                .collect();
+            faces.sort_by(|a, b| a.weight.total_cmp(&b.weight).then(a.style.cmp(&b.style)));
+            faces.dedup();
+            Some(FontFamilyInfo { name, faces })
+        })
+        .collect()
+}
+
+/// CSS `font-style` keyword for a face.
+fn style_name(style: FontStyle) -> &'static str {
+    match style {
+        FontStyle::Normal => "normal",
+        FontStyle::Italic => "italic",
+        FontStyle::Oblique(_) => "oblique",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_style_name() {
+        assert_eq!(style_name(FontStyle::Normal), "normal");
+        assert_eq!(style_name(FontStyle::Italic), "italic");
+        assert_eq!(style_name(FontStyle::Oblique(Some(10.0))), "oblique");
+    }
+
+    #[test]
+    fn test_system_fonts_are_sorted() {
+        // The set of installed fonts varies by machine; only the shape is checked.
+        let families = list_system_fonts();
+        assert!(
+            families
+                .windows(2)
+                .all(|pair| pair[0].name.to_lowercase() <= pair[1].name.to_lowercase())
+        );
+        assert!(families.iter().all(|family| !family.faces.is_empty()));
+    }
+}
@@ -1,6 +1,10 @@
+use masonry::peniko::Color;
 use masonry::vello::wgpu;
 use masonry_winit::app::{EventLoopProxy, WindowId};
-use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, AtomicI32, AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+use crate::ipc::ClickInput;
 
 #[derive(Clone)]
 pub struct ClonedWgpu {
@@ -45,3 +49,94 @@ pub fn get_event_loop_proxy() -> Option<(EventLoopProxy, WindowId)> {
         .and_then(|state| state.lock().ok())
         .map(|lock| (lock.proxy.clone(), lock.window_id))
 }
+
+/// Whether the UI is laid out right-to-left (set at startup from the locale).
+static RIGHT_TO_LEFT: AtomicBool = AtomicBool::new(false);
+
+pub fn set_right_to_left(rtl: bool) {
+    RIGHT_TO_LEFT.store(rtl, Ordering::Relaxed);
+}
+
+pub fn is_right_to_left() -> bool {
+    RIGHT_TO_LEFT.load(Ordering::Relaxed)
+}
+
+/// Interface zoom applied to every style length and font size, stored as f64 bits.
+static UI_SCALE: AtomicU64 = AtomicU64::new(0x3FF0_0000_0000_0000); // 1.0
+
+pub fn set_ui_scale(factor: f64) {
+    UI_SCALE.store(factor.to_bits(), Ordering::Relaxed);
+}
+
+pub fn ui_scale() -> f64 {
+    f64::from_bits(UI_SCALE.load(Ordering::Relaxed))
+}
+
+/// Whether the window was created transparent (`--transparent`), letting
+/// non-solid root backgrounds show the desktop.
+static WINDOW_TRANSPARENT: AtomicBool = AtomicBool::new(false);
+
+pub fn set_window_transparent(transparent: bool) {
+    WINDOW_TRANSPARENT.store(transparent, Ordering::Relaxed);
+}
+
+pub fn is_window_transparent() -> bool {
+    WINDOW_TRANSPARENT.load(Ordering::Relaxed)
+}
+
+/// Whether the app runs in kiosk mode (`--kiosk`): the user can't close the
+/// window or quit, only the app can exit.
+static KIOSK: AtomicBool = AtomicBool::new(false);
+
+pub fn set_kiosk(kiosk: bool) {
+    KIOSK.store(kiosk, Ordering::Relaxed);
+}
+
+pub fn is_kiosk() -> bool {
+    KIOSK.load(Ordering::Relaxed)
+}
+
+/// The window's clear color (`--background`), also the fill of a solid root
+/// that names no `background`, so nothing flashes before JS styles the root.
+static WINDOW_BACKGROUND: Mutex<Color> = Mutex::new(Color::BLACK);
+
+pub fn set_window_background(color: Color) {
+    *WINDOW_BACKGROUND.lock().unwrap_or_else(|e| e.into_inner()) = color;
+}
+
+pub fn window_background() -> Color {
+    *WINDOW_BACKGROUND.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// The latest pointer press/release or key press, recorded by the frame clock
+/// as input bubbles to the root. Widget actions are handled after the event
+/// that caused them, so a `Click` reads the input that produced it.
+static LAST_CLICK_INPUT: Mutex<ClickInput> = Mutex::new(ClickInput::PRIMARY);
+
+pub fn set_last_click_input(input: ClickInput) {
+    *LAST_CLICK_INPUT.lock().unwrap_or_else(|e| e.into_inner()) = input;
+}
+
+pub fn last_click_input() -> ClickInput {
+    *LAST_CLICK_INPUT.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Process exit code from the latest `ExitApp`, used once the UI has closed.
+static EXIT_CODE: AtomicI32 = AtomicI32::new(0);
+
+pub fn set_exit_code(code: i32) {
+    EXIT_CODE.store(code, Ordering::Relaxed);
+}
+
+pub fn exit_code() -> i32 {
+    EXIT_CODE.load(Ordering::Relaxed)
+}
+
+/// Set once `UiEvent::BeforeExit` has been sent, so the next `ExitApp`
+/// exits instead of starting another grace period.
+static EXIT_PENDING: AtomicBool = AtomicBool::new(false);
+
+/// Start the `BeforeExit` grace period; false if it had already started.
+pub fn begin_exit() -> bool {
+    !EXIT_PENDING.swap(true, Ordering::Relaxed)
+}
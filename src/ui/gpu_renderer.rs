@@ -0,0 +1,340 @@
+// GPU Surface Renderers
+// A `GpuSurface` widget owns a wgpu texture and asks a renderer to draw into
+// it. Renderers are either the built-in `ShaderRenderer` (a WGSL fragment
+// shader driven by uniforms from JS) or Rust code registered by name with
+// `register_gpu_renderer` and selected with the widget's `renderer` param.
+
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+use masonry::vello::wgpu;
+
+/// Uniform slots available to shaders (`uniform(i)` in WGSL).
+pub const MAX_UNIFORMS: usize = 64;
+/// `resolution`, `time`, padding, then the slots.
+const UNIFORM_BYTES: usize = 16 + MAX_UNIFORMS * 4;
+
+/// Declarations prepended to every `ShaderRenderer` source. User shaders only
+/// provide `fs_main`.
+const SHADER_PRELUDE: &str = r#"
+struct Surface {
+    resolution: vec2<f32>,
+    time: f32,
+    _pad: f32,
+    values: array<vec4<f32>, 16>,
+}
+
+@group(0) @binding(0) var<uniform> surface: Surface;
+
+fn uniform(i: u32) -> f32 {
+    return surface.values[i / 4u][i % 4u];
+}
+
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> @builtin(position) vec4<f32> {
+    let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+    return vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+}
+"#;
+
+/// What a renderer draws into for one frame.
+pub struct RenderTarget<'a> {
+    pub device: &'a wgpu::Device,
+    pub queue: &'a wgpu::Queue,
+    /// `Rgba8Unorm`, usable as a render attachment or copy destination.
+    pub texture: &'a wgpu::Texture,
+    pub view: &'a wgpu::TextureView,
+    /// Values last sent with `SetShaderUniforms`.
+    pub uniforms: &'a [f32],
+    /// Seconds since the widget started animating (0 when `animate` is off).
+    pub time: f64,
+}
+
+/// Draws one GpuSurface. Called on the UI thread from `paint`, so keep frames
+/// cheap; errors are logged and leave the previous contents in place.
+pub trait GpuRenderer: Send {
+    fn render(&mut self, target: &RenderTarget<'_>) -> Result<(), String>;
+
+    /// Swap in a new WGSL shader. Renderers that don't use shaders reject it.
+    fn set_shader(&mut self, _device: &wgpu::Device, _wgsl: &str) -> Result<(), String> {
+        Err("this renderer does not accept shaders".to_string())
+    }
+}
+
+type RendererConstructor = Arc<dyn Fn() -> Box<dyn GpuRenderer> + Send + Sync>;
+
+static RENDERERS: Mutex<Vec<(String, RendererConstructor)>> = Mutex::new(Vec::new());
+
+/// Register a renderer constructor under `name`, replacing any earlier one.
+/// Each GpuSurface created with `renderer: name` gets its own instance.
+pub fn register_gpu_renderer(
+    name: impl Into<String>,
+    constructor: impl Fn() -> Box<dyn GpuRenderer> + Send + Sync + 'static,
+) {
+    let name = name.into();
+    let constructor: RendererConstructor = Arc::new(constructor);
+    let mut renderers = RENDERERS.lock().unwrap_or_else(|e| e.into_inner());
+    match renderers.iter_mut().find(|(existing, _)| *existing == name) {
+        Some(entry) => entry.1 = constructor,
+        None => renderers.push((name, constructor)),
+    }
+}
+
+/// A new instance of the renderer registered as `name`, if any.
+pub fn renderer_for(name: &str) -> Option<Box<dyn GpuRenderer>> {
+    let constructor = RENDERERS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .find(|(existing, _)| existing == name)
+        .map(|(_, constructor)| constructor.clone())?;
+    Some(constructor())
+}
+
+/// Uniform buffer contents: `resolution`, `time`, padding, then `values`
+/// (truncated to `MAX_UNIFORMS`, zero-filled after).
+fn pack_uniforms(width: u32, height: u32, time: f64, values: &[f32]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(UNIFORM_BYTES);
+    for header in [width as f32, height as f32, time as f32, 0.0] {
+        bytes.extend_from_slice(&header.to_le_bytes());
+    }
+    for index in 0..MAX_UNIFORMS {
+        let value = values.get(index).copied().unwrap_or(0.0);
+        bytes.extend_from_slice(&value.to_le_bytes());
+    }
+    bytes
+}
+
+fn shader_source(wgsl: &str) -> String {
+    format!("{SHADER_PRELUDE}\n{wgsl}")
+}
+
+/// Error scopes resolve immediately on native backends; don't block if one
+/// doesn't.
+fn now_or_never<F: Future>(future: F) -> Option<F::Output> {
+    let mut future = std::pin::pin!(future);
+    match future
+        .as_mut()
+        .poll(&mut Context::from_waker(Waker::noop()))
+    {
+        Poll::Ready(output) => Some(output),
+        Poll::Pending => None,
+    }
+}
+
+struct ShaderPipeline {
+    pipeline: wgpu::RenderPipeline,
+    uniforms: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+}
+
+impl ShaderPipeline {
+    fn build(device: &wgpu::Device, wgsl: &str) -> Result<Self, String> {
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("GpuSurface_Shader"),
+            source: wgpu::ShaderSource::Wgsl(shader_source(wgsl).into()),
+        });
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("GpuSurface_Uniforms"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+        });
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("GpuSurface_Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("GpuSurface_Pipeline"),
+            layout: Some(&layout),
+            vertex: wgpu::VertexState {
+                module: &module,
+                entry_point: Some("vs_main"),
+                compilation_options: Default::default(),
+                buffers: &[],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &module,
+                entry_point: Some("fs_main"),
+                compilation_options: Default::default(),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: wgpu::TextureFormat::Rgba8Unorm,
+                    blend: None,
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+        if let Some(Some(error)) = now_or_never(device.pop_error_scope()) {
+            return Err(error.to_string());
+        }
+
+        let uniforms = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("GpuSurface_UniformBuffer"),
+            size: UNIFORM_BYTES as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("GpuSurface_BindGroup"),
+            layout: &bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: uniforms.as_entire_binding(),
+            }],
+        });
+        Ok(Self {
+            pipeline,
+            uniforms,
+            bind_group,
+        })
+    }
+}
+
+/// Runs a WGSL fragment shader over the whole surface. The shader defines
+///
+/// ```wgsl
+/// @fragment
+/// fn fs_main(@builtin(position) pos: vec4<f32>) -> @location(0) vec4<f32>
+/// ```
+///
+/// and may read `surface.resolution`, `surface.time` and `uniform(i)`.
+pub struct ShaderRenderer {
+    wgsl: String,
+    /// Built on first render when the shader came in before wgpu was ready.
+    pipeline: Option<ShaderPipeline>,
+}
+
+impl ShaderRenderer {
+    pub fn new(wgsl: impl Into<String>) -> Self {
+        Self {
+            wgsl: wgsl.into(),
+            pipeline: None,
+        }
+    }
+}
+
+impl GpuRenderer for ShaderRenderer {
+    fn render(&mut self, target: &RenderTarget<'_>) -> Result<(), String> {
+        if self.pipeline.is_none() {
+            self.pipeline = Some(ShaderPipeline::build(target.device, &self.wgsl)?);
+        }
+        let Some(shader) = &self.pipeline else {
+            return Ok(());
+        };
+        target.queue.write_buffer(
+            &shader.uniforms,
+            0,
+            &pack_uniforms(
+                target.texture.width(),
+                target.texture.height(),
+                target.time,
+                target.uniforms,
+            ),
+        );
+        let mut encoder = target
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("GpuSurface_Encoder"),
+            });
+        {
+            let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("GpuSurface_Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: target.view,
+                    depth_slice: None,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            pass.set_pipeline(&shader.pipeline);
+            pass.set_bind_group(0, &shader.bind_group, &[]);
+            pass.draw(0..3, 0..1);
+        }
+        target.queue.submit([encoder.finish()]);
+        Ok(())
+    }
+
+    /// Compiles right away so JS hears about errors; the old shader stays on
+    /// failure.
+    fn set_shader(&mut self, device: &wgpu::Device, wgsl: &str) -> Result<(), String> {
+        self.pipeline = Some(ShaderPipeline::build(device, wgsl)?);
+        self.wgsl = wgsl.to_string();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Blank;
+
+    impl GpuRenderer for Blank {
+        fn render(&mut self, _target: &RenderTarget<'_>) -> Result<(), String> {
+            Ok(())
+        }
+    }
+
+    fn f32_at(bytes: &[u8], index: usize) -> f32 {
+        f32::from_le_bytes(bytes[index * 4..index * 4 + 4].try_into().unwrap())
+    }
+
+    #[test]
+    fn test_pack_uniforms_layout() {
+        let bytes = pack_uniforms(640, 480, 1.5, &[0.25, -1.0]);
+        assert_eq!(bytes.len(), UNIFORM_BYTES);
+        assert_eq!(f32_at(&bytes, 0), 640.0);
+        assert_eq!(f32_at(&bytes, 1), 480.0);
+        assert_eq!(f32_at(&bytes, 2), 1.5);
+        assert_eq!(f32_at(&bytes, 4), 0.25);
+        assert_eq!(f32_at(&bytes, 5), -1.0);
+        assert_eq!(f32_at(&bytes, 6), 0.0);
+
+        let overflow = vec![1.0; MAX_UNIFORMS + 8];
+        assert_eq!(pack_uniforms(1, 1, 0.0, &overflow).len(), UNIFORM_BYTES);
+    }
+
+    #[test]
+    fn test_shader_source_includes_prelude() {
+        let source = shader_source("@fragment fn fs_main() -> @location(0) vec4<f32> {}");
+        assert!(source.contains("fn vs_main"));
+        assert!(source.contains("fn uniform(i: u32)"));
+        assert!(source.ends_with("{}"));
+    }
+
+    #[test]
+    fn test_register_renderer() {
+        assert!(renderer_for("test-blank").is_none());
+        register_gpu_renderer("test-blank", || Box::new(Blank));
+        register_gpu_renderer("test-blank", || Box::new(Blank));
+        assert!(renderer_for("test-blank").is_some());
+        let renderers = RENDERERS.lock().unwrap();
+        assert_eq!(
+            renderers
+                .iter()
+                .filter(|(name, _)| name == "test-blank")
+                .count(),
+            1
+        );
+    }
+}
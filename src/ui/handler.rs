@@ -1,31 +1,235 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
 use masonry::app::{RenderRoot, RenderRootSignal};
+use masonry::core::{Widget, WidgetId, WidgetRef};
+use masonry::kurbo::Point;
 use masonry::widgets::TextArea;
-use masonry::widgets::{
-    Button, Checkbox, Flex, Label, ProgressBar, Prose, SizedBox, Slider, TextInput, ZStack,
-};
-use masonry_winit::app::WindowId;
+use masonry::widgets::{Button, Checkbox, Flex, Label, Prose, SizedBox, Slider, TextInput};
+use masonry_winit::app::{MasonryUserEvent, WindowId};
 use winit::dpi::PhysicalSize;
 
-use crate::ipc::{BoxStyle, ClientCommand, UiEventSender, WidgetKind};
+use crate::ipc::{
+    BackgroundMode, BoxStyle, ChannelValue, ClientCommand, ClientCommandAction, ErrorCode,
+    ExitReason, HandshakeStatus, LayoutDirection, OverflowValue, UiEventSender, WidgetKind,
+};
 
+use super::accelerators::Accelerator;
+use super::animations::{Animation, Easing};
+use super::bindings;
 use super::creation::create_and_add_widget;
+use super::global_state::{
+    begin_exit, exit_code, get_event_loop_proxy, is_kiosk, is_right_to_left, is_window_transparent,
+    set_exit_code, set_right_to_left, set_ui_scale, ui_scale,
+};
+use super::gpu_renderer::MAX_UNIFORMS;
+use super::journal;
+use super::print;
+use super::raw_properties;
+use super::snapshot::{self, WidgetRecord};
 use super::styles::{
     apply_box_props_to_widget, apply_flex_style, build_text_styles, color_value_to_peniko,
+    cross_axis_alignment, main_axis_alignment, px, remember_sizes, root_background, scaled,
 };
-use super::widget_manager::{ROOT_FLEX_TAG, WidgetManager};
+use super::widget_factory::factory_for;
+use super::widget_manager::{ROOT_FLEX_TAG, ROOT_STACK_TAG, WidgetManager, is_root_key, layer_tag};
+use super::widgets::avatar_impl::AvatarWidget;
+use super::widgets::background;
+use super::widgets::clip_box_impl::ClipBox;
+use super::widgets::custom_paint_impl::CustomPaintWidget;
+use super::widgets::error_overlay;
+use super::widgets::frame_clock_impl::{FrameClock, edit_frame_clock};
+use super::widgets::gpu_surface_impl::GpuSurfaceWidget;
+use super::widgets::icon;
+use super::widgets::layer_impl::Layer;
+use super::widgets::press_feedback_impl::PressFeedback;
+use super::widgets::progress_bar_impl::ProgressBarWidget;
+use super::widgets::qr_code_impl::QrCodeWidget;
+use super::widgets::range_slider_impl::RangeSlider;
+use super::widgets::reorderable_list_impl::ReorderableList;
+use super::widgets::scroll_area_impl::{ScrollArea, ScrollbarStyle};
+use super::widgets::skeleton_impl::SkeletonWidget;
+use super::widgets::splash;
 use super::widgets::svg_widget_impl::SvgWidget;
+use super::widgets::utils::{check_single_child_slot, clear_root};
 use super::widgets::video_widget_impl::VideoWidget;
+use super::widgets::viewport::{self, VisibilityObserver};
+use super::widgets::zstack;
 
-fn report_runtime_error(event_sender: &UiEventSender, source: &str, message: String, fatal: bool) {
+/// How long JS gets after `UiEvent::BeforeExit` before the UI exits anyway.
+const BEFORE_EXIT_TIMEOUT: Duration = Duration::from_secs(3);
+
+fn report_runtime_error(
+    event_sender: &UiEventSender,
+    command: &str,
+    code: ErrorCode,
+    widget_id: Option<&str>,
+    message: String,
+) {
     if let Err(send_err) = event_sender.send(crate::ipc::UiEvent::RuntimeError {
-        source: source.to_string(),
+        source: "ui-handler".to_string(),
+        code,
         message,
-        fatal,
+        fatal: false,
+        widget_id: widget_id.map(String::from),
+        command: Some(command.to_string()),
     }) {
         eprintln!("[UI] Failed to report runtime error to JS thread: {send_err}");
     }
 }
 
+/// `WidgetManager::audit` plus a check that every tracked widget still exists
+/// in the masonry tree.
+pub fn collect_tree_issues(
+    render_root: &RenderRoot,
+    widget_manager: &WidgetManager,
+) -> Vec<String> {
+    let mut issues = widget_manager.audit();
+    let mut detached: Vec<&String> = widget_manager
+        .widgets
+        .iter()
+        .filter(|(_, info)| render_root.get_widget(info.widget_id).is_none())
+        .map(|(id, _)| id)
+        .collect();
+    detached.sort();
+    issues.extend(
+        detached
+            .into_iter()
+            .map(|id| format!("'{id}' is tracked but no longer in the widget tree")),
+    );
+    issues
+}
+
+/// Collect the path from `widget` down to `target`, deepest first.
+fn path_to<'w>(
+    widget: WidgetRef<'w, dyn Widget>,
+    target: WidgetId,
+    path: &mut Vec<WidgetRef<'w, dyn Widget>>,
+) -> bool {
+    let found = widget.id() == target
+        || widget
+            .children()
+            .into_iter()
+            .any(|child| path_to(child, target, path));
+    if found {
+        path.push(widget);
+    }
+    found
+}
+
+/// The JS widget under `point` (window coordinates) and `point` in its local
+/// coordinates. Masonry hits the innermost widget (a Button's label, an
+/// Image's SizedBox); this reports the nearest ancestor JS knows about.
+pub(crate) fn hit_test(
+    render_root: &RenderRoot,
+    widget_manager: &WidgetManager,
+    point: Point,
+) -> Option<(String, Point)> {
+    let root_id = render_root.get_widget_with_tag(ROOT_STACK_TAG)?.id();
+    let root = render_root.get_widget(root_id)?;
+    let hit = root.find_widget_under_pointer(point)?.id();
+    let mut path = Vec::new();
+    path_to(root, hit, &mut path);
+
+    let client_ids: HashMap<WidgetId, &String> = widget_manager
+        .widgets
+        .iter()
+        .map(|(id, info)| (info.widget_id, id))
+        .collect();
+    path.iter().find_map(|widget| {
+        let id = client_ids.get(&widget.id())?;
+        let local = widget.ctx().window_transform().inverse() * point;
+        Some(((*id).clone(), local))
+    })
+}
+
+/// Encode and hand off rendered pages on a worker thread, then answer with
+/// `UiEvent::PrintResult`. Rendering has to happen on the UI thread; the JPEG
+/// encoding and file or printer I/O don't.
+fn finish_print(
+    event_sender: &UiEventSender,
+    command: &str,
+    request_id: String,
+    pages: Result<Vec<print::PageImage>, String>,
+    deliver: impl FnOnce(Vec<print::PageImage>) -> Result<(), String> + Send + 'static,
+) {
+    let reply = |request_id, pages, error| crate::ipc::UiEvent::PrintResult {
+        request_id,
+        pages,
+        error,
+    };
+    let pages = match pages {
+        Ok(pages) => pages,
+        Err(error) => {
+            let _ = event_sender.send(reply(request_id, 0, Some(error)));
+            return;
+        }
+    };
+    let sender = event_sender.clone();
+    let spawned = std::thread::Builder::new()
+        .name("print".to_string())
+        .spawn(move || {
+            let count = pages.len();
+            let event = match deliver(pages) {
+                Ok(()) => reply(request_id, count, None),
+                Err(error) => reply(request_id, 0, Some(error)),
+            };
+            let _ = sender.send(event);
+        });
+    if let Err(e) = spawned {
+        report_runtime_error(
+            event_sender,
+            command,
+            ErrorCode::Internal,
+            None,
+            format!("Failed to spawn the print thread: {e}"),
+        );
+    }
+}
+
+/// Kinds `SetWidgetStyle` can update in place.
+fn restylable(kind: &WidgetKind) -> bool {
+    matches!(
+        kind,
+        WidgetKind::Label
+            | WidgetKind::Button
+            | WidgetKind::Svg
+            | WidgetKind::Icon
+            | WidgetKind::Flex
+            | WidgetKind::Container
+            | WidgetKind::Portal
+            | WidgetKind::ReorderableList
+            | WidgetKind::ProgressBar
+            | WidgetKind::Slider
+            | WidgetKind::RangeSlider
+            | WidgetKind::Avatar
+            | WidgetKind::Skeleton
+            | WidgetKind::QrCode
+            | WidgetKind::CustomPaint
+            | WidgetKind::GpuSurface
+            | WidgetKind::SizedBox
+            | WidgetKind::Image
+            | WidgetKind::Video
+            | WidgetKind::Prose
+    )
+}
+
+/// Handle commands derived from another one without journaling them.
+fn replay_unjournaled(
+    commands: Vec<ClientCommand>,
+    window_id: WindowId,
+    render_root: &mut RenderRoot,
+    widget_manager: &mut WidgetManager,
+    event_sender: &UiEventSender,
+) {
+    widget_manager.journal.pause();
+    for cmd in commands {
+        handle_client_command(cmd, window_id, render_root, widget_manager, event_sender);
+    }
+    widget_manager.journal.resume();
+}
+
 /// Process a single ClientCommand by mutating the widget tree.
 pub fn handle_client_command(
     cmd: ClientCommand,
@@ -34,12 +238,26 @@ pub fn handle_client_command(
     widget_manager: &mut WidgetManager,
     _event_sender: &UiEventSender,
 ) {
+    let command = cmd.name();
+    crate::crash::record_command(
+        &cmd,
+        widget_manager.widgets.len(),
+        _event_sender.elapsed_ms(),
+    );
+    if widget_manager.journal.is_recording()
+        && let Some(inverse) = journal::inverse_of(&cmd, widget_manager)
+    {
+        widget_manager.journal.push(cmd.clone(), inverse);
+    }
     match cmd {
         ClientCommand::SetTitle(title) => {
             println!("[UI] Setting window title: {}", title);
             render_root.emit_signal(RenderRootSignal::SetTitle(title));
         }
 
+        // Applied by the driver, which owns the winit window.
+        ClientCommand::SetContentProtection(_) => {}
+
         ClientCommand::CreateWidget {
             id,
             kind,
@@ -47,20 +265,134 @@ pub fn handle_client_command(
             text,
             style,
             data,
+            badge,
         } => {
+            if let Err(message) = check_single_child_slot(widget_manager, &parent_id) {
+                eprintln!("[UI] Rejected CreateWidget '{}': {}", id, message);
+                report_runtime_error(
+                    _event_sender,
+                    command,
+                    ErrorCode::InvalidHierarchy,
+                    Some(&id),
+                    format!("Cannot add '{id}': {message}"),
+                );
+                return;
+            }
+            if let Some(style) = &style {
+                remember_sizes(
+                    widget_manager.sized_styles.entry(id.clone()).or_default(),
+                    style,
+                );
+            }
+            // The app's content has started to arrive.
+            if parent_id
+                .as_deref()
+                .is_none_or(|parent| parent == "__root__")
+            {
+                splash::dismiss(render_root);
+            }
+            let record = WidgetRecord {
+                kind: kind.clone(),
+                text: text.clone(),
+                style: style.clone(),
+                data: data.clone(),
+                badge: badge.clone(),
+            };
             create_and_add_widget(
                 render_root,
                 widget_manager,
-                id,
+                id.clone(),
                 kind,
                 parent_id,
                 text,
                 style,
                 data,
+                badge,
+            );
+            if widget_manager.widgets.contains_key(&id) {
+                widget_manager.records.insert(id, record);
+            }
+        }
+
+        ClientCommand::ValidateTree { repair } => {
+            let issues = collect_tree_issues(render_root, widget_manager);
+            if issues.is_empty() {
+                println!("[UI] ValidateTree: widget bookkeeping is consistent");
+                return;
+            }
+            let mut message = format!(
+                "ValidateTree found {} issue(s):\n  {}",
+                issues.len(),
+                issues.join("\n  ")
+            );
+            if repair {
+                widget_manager.repair();
+                let remaining = collect_tree_issues(render_root, widget_manager);
+                message.push_str(&format!(
+                    "\nAfter repair, {} issue(s) remain. Child order was rebuilt from \
+                     each widget's recorded index, not read back from the widget tree",
+                    remaining.len()
+                ));
+            }
+            message.push_str("\nRecent commands (oldest first):");
+            for record in crate::crash::recent_commands() {
+                message.push_str(&format!("\n  {}", crate::crash::describe(&record)));
+            }
+            eprintln!("[UI] {}", message);
+            report_runtime_error(
+                _event_sender,
+                command,
+                ErrorCode::TreeInconsistent,
+                None,
+                message,
             );
         }
 
+        ClientCommand::ShowErrorOverlay { title, details } => {
+            eprintln!("[UI] Showing error overlay: {}", title);
+            splash::dismiss(render_root);
+            error_overlay::show(render_root, widget_manager, title, details);
+        }
+
+        ClientCommand::SetSplash {
+            image,
+            message,
+            progress,
+        } => {
+            if widget_manager.current_child_count("__root__") > 0 {
+                report_runtime_error(
+                    _event_sender,
+                    command,
+                    ErrorCode::UnsupportedOperation,
+                    None,
+                    "SetSplash must come before the first widget is mounted under __root__"
+                        .to_string(),
+                );
+                return;
+            }
+            let image = match image
+                .as_deref()
+                .map(super::widgets::image::decode_image_bytes)
+            {
+                Some(None) => {
+                    report_runtime_error(
+                        _event_sender,
+                        command,
+                        ErrorCode::InvalidValue,
+                        None,
+                        "SetSplash image could not be decoded".to_string(),
+                    );
+                    return;
+                }
+                decoded => decoded.flatten(),
+            };
+            splash::show(render_root, image, message, progress);
+        }
+
         ClientCommand::SetWidgetText { id, text } => {
+            if let Some(record) = widget_manager.records.get_mut(&id) {
+                record.text = Some(text.clone());
+            }
             if let Some(info) = widget_manager.widgets.get(&id) {
                 let widget_id = info.widget_id;
                 match &info.kind {
@@ -87,12 +419,51 @@ pub fn handle_client_command(
                     WidgetKind::Button => {
                         report_runtime_error(
                             _event_sender,
-                            "ui-handler",
+                            command,
+                            ErrorCode::UnsupportedOperation,
+                            Some(&id),
                             "SetWidgetText on Button is not supported. Use a child label widget instead."
                                 .to_string(),
-                            false,
                         );
                     }
+                    WidgetKind::Avatar => {
+                        render_root.edit_widget(widget_id, |mut widget| {
+                            let mut avatar = widget.downcast::<AvatarWidget>();
+                            AvatarWidget::set_name(&mut avatar, text.clone());
+                        });
+                    }
+                    WidgetKind::Icon => {
+                        if let Some(svg_source) = icon::resolve_source(widget_manager, &text) {
+                            render_root.edit_widget(widget_id, |mut widget| {
+                                let mut svg_widget = widget.downcast::<SvgWidget>();
+                                SvgWidget::set_svg_source(&mut svg_widget, svg_source);
+                            });
+                        } else {
+                            report_runtime_error(
+                                _event_sender,
+                                command,
+                                ErrorCode::InvalidValue,
+                                Some(&id),
+                                format!("Unknown icon '{text}' for widget '{id}'"),
+                            );
+                        }
+                    }
+                    WidgetKind::QrCode => {
+                        let mut result = Ok(());
+                        render_root.edit_widget(widget_id, |mut widget| {
+                            let mut qr_code = widget.downcast::<QrCodeWidget>();
+                            result = QrCodeWidget::set_data(&mut qr_code, text.clone());
+                        });
+                        if let Err(err) = result {
+                            report_runtime_error(
+                                _event_sender,
+                                command,
+                                ErrorCode::InvalidValue,
+                                Some(&id),
+                                format!("QrCode '{id}' could not encode its data: {err}"),
+                            );
+                        }
+                    }
                     WidgetKind::Svg => {
                         let svg_markup = text.clone();
                         render_root.edit_widget(widget_id, |mut widget| {
@@ -100,15 +471,36 @@ pub fn handle_client_command(
                             SvgWidget::set_svg_source(&mut svg_widget, svg_markup);
                         });
                     }
+                    WidgetKind::Custom(name) => {
+                        let mut handled = false;
+                        if let Some(factory) = factory_for(name) {
+                            render_root.edit_widget(widget_id, |mut widget| {
+                                handled = factory.set_text(&mut widget, &text);
+                            });
+                        }
+                        if !handled {
+                            report_runtime_error(
+                                _event_sender,
+                                command,
+                                ErrorCode::UnsupportedOperation,
+                                Some(&id),
+                                format!(
+                                    "SetWidgetText on {:?} is not supported for widget '{id}'",
+                                    info.kind
+                                ),
+                            );
+                        }
+                    }
                     _ => {
                         report_runtime_error(
                             _event_sender,
-                            "ui-handler",
+                            command,
+                            ErrorCode::UnsupportedOperation,
+                            Some(&id),
                             format!(
                                 "SetWidgetText on {:?} is not supported for widget '{id}'",
                                 info.kind
                             ),
-                            false,
                         );
                     }
                 }
@@ -116,9 +508,10 @@ pub fn handle_client_command(
                 eprintln!("[UI] Widget '{}' not found for SetWidgetText", id);
                 report_runtime_error(
                     _event_sender,
-                    "ui-handler",
+                    command,
+                    ErrorCode::WidgetNotFound,
+                    Some(&id),
                     format!("Widget '{id}' not found for SetWidgetText"),
-                    false,
                 );
             }
         }
@@ -129,8 +522,8 @@ pub fn handle_client_command(
                 match &info.kind {
                     WidgetKind::ProgressBar => {
                         render_root.edit_widget(widget_id, |mut widget| {
-                            let mut pbar = widget.downcast::<ProgressBar>();
-                            ProgressBar::set_progress(&mut pbar, Some(value));
+                            let mut pbar = widget.downcast::<ProgressBarWidget>();
+                            ProgressBarWidget::set_progress(&mut pbar, Some(value));
                         });
                     }
                     WidgetKind::Slider => {
@@ -139,15 +532,36 @@ pub fn handle_client_command(
                             Slider::set_value(&mut slider, value);
                         });
                     }
+                    WidgetKind::Custom(name) => {
+                        let mut handled = false;
+                        if let Some(factory) = factory_for(name) {
+                            render_root.edit_widget(widget_id, |mut widget| {
+                                handled = factory.set_value(&mut widget, value);
+                            });
+                        }
+                        if !handled {
+                            report_runtime_error(
+                                _event_sender,
+                                command,
+                                ErrorCode::UnsupportedOperation,
+                                Some(&id),
+                                format!(
+                                    "SetWidgetValue on {:?} is not supported for widget '{id}'",
+                                    info.kind
+                                ),
+                            );
+                        }
+                    }
                     _ => {
                         report_runtime_error(
                             _event_sender,
-                            "ui-handler",
+                            command,
+                            ErrorCode::UnsupportedOperation,
+                            Some(&id),
                             format!(
                                 "SetWidgetValue on {:?} is not supported for widget '{id}'",
                                 info.kind
                             ),
-                            false,
                         );
                     }
                 }
@@ -155,9 +569,180 @@ pub fn handle_client_command(
                 eprintln!("[UI] Widget '{}' not found for SetWidgetValue", id);
                 report_runtime_error(
                     _event_sender,
-                    "ui-handler",
+                    command,
+                    ErrorCode::WidgetNotFound,
+                    Some(&id),
                     format!("Widget '{id}' not found for SetWidgetValue"),
-                    false,
+                );
+            }
+        }
+
+        ClientCommand::SetProgressIndeterminate { id, indeterminate } => {
+            if let Some(info) = widget_manager.widgets.get(&id) {
+                let widget_id = info.widget_id;
+                if matches!(info.kind, WidgetKind::ProgressBar) {
+                    render_root.edit_widget(widget_id, |mut widget| {
+                        let mut pbar = widget.downcast::<ProgressBarWidget>();
+                        ProgressBarWidget::set_indeterminate(&mut pbar, indeterminate);
+                    });
+                } else {
+                    report_runtime_error(
+                        _event_sender,
+                        command,
+                        ErrorCode::UnsupportedOperation,
+                        Some(&id),
+                        format!(
+                            "SetProgressIndeterminate on {:?} is not supported for widget '{id}'",
+                            info.kind
+                        ),
+                    );
+                }
+            } else {
+                eprintln!(
+                    "[UI] Widget '{}' not found for SetProgressIndeterminate",
+                    id
+                );
+                report_runtime_error(
+                    _event_sender,
+                    command,
+                    ErrorCode::WidgetNotFound,
+                    Some(&id),
+                    format!("Widget '{id}' not found for SetProgressIndeterminate"),
+                );
+            }
+        }
+
+        ClientCommand::SetProgressBuffer { id, buffer } => {
+            if let Some(info) = widget_manager.widgets.get(&id) {
+                let widget_id = info.widget_id;
+                if matches!(info.kind, WidgetKind::ProgressBar) {
+                    render_root.edit_widget(widget_id, |mut widget| {
+                        let mut pbar = widget.downcast::<ProgressBarWidget>();
+                        ProgressBarWidget::set_buffer(&mut pbar, buffer);
+                    });
+                } else {
+                    report_runtime_error(
+                        _event_sender,
+                        command,
+                        ErrorCode::UnsupportedOperation,
+                        Some(&id),
+                        format!(
+                            "SetProgressBuffer on {:?} is not supported for widget '{id}'",
+                            info.kind
+                        ),
+                    );
+                }
+            } else {
+                eprintln!("[UI] Widget '{}' not found for SetProgressBuffer", id);
+                report_runtime_error(
+                    _event_sender,
+                    command,
+                    ErrorCode::WidgetNotFound,
+                    Some(&id),
+                    format!("Widget '{id}' not found for SetProgressBuffer"),
+                );
+            }
+        }
+
+        ClientCommand::SetWidgetRange { id, low, high } => {
+            if let Some(info) = widget_manager.widgets.get(&id) {
+                let widget_id = info.widget_id;
+                if matches!(info.kind, WidgetKind::RangeSlider) {
+                    render_root.edit_widget(widget_id, |mut widget| {
+                        let mut slider = widget.downcast::<RangeSlider>();
+                        RangeSlider::set_range(&mut slider, low, high);
+                    });
+                } else {
+                    report_runtime_error(
+                        _event_sender,
+                        command,
+                        ErrorCode::UnsupportedOperation,
+                        Some(&id),
+                        format!(
+                            "SetWidgetRange on {:?} is not supported for widget '{id}'",
+                            info.kind
+                        ),
+                    );
+                }
+            } else {
+                eprintln!("[UI] Widget '{}' not found for SetWidgetRange", id);
+                report_runtime_error(
+                    _event_sender,
+                    command,
+                    ErrorCode::WidgetNotFound,
+                    Some(&id),
+                    format!("Widget '{id}' not found for SetWidgetRange"),
+                );
+            }
+        }
+
+        ClientCommand::RegisterIconPack { prefix, icons } => {
+            println!(
+                "[UI] Registering {} icon(s) in pack '{}'",
+                icons.len(),
+                prefix
+            );
+            widget_manager.icons.register_pack(&prefix, icons);
+        }
+
+        ClientCommand::SetAvatarStatus { id, status } => {
+            if let Some(info) = widget_manager.widgets.get(&id) {
+                let widget_id = info.widget_id;
+                if matches!(info.kind, WidgetKind::Avatar) {
+                    let status = status.as_ref().map(color_value_to_peniko);
+                    render_root.edit_widget(widget_id, |mut widget| {
+                        let mut avatar = widget.downcast::<AvatarWidget>();
+                        AvatarWidget::set_status(&mut avatar, status);
+                    });
+                } else {
+                    report_runtime_error(
+                        _event_sender,
+                        command,
+                        ErrorCode::UnsupportedOperation,
+                        Some(&id),
+                        format!(
+                            "SetAvatarStatus on {:?} is not supported for widget '{id}'",
+                            info.kind
+                        ),
+                    );
+                }
+            } else {
+                eprintln!("[UI] Widget '{}' not found for SetAvatarStatus", id);
+                report_runtime_error(
+                    _event_sender,
+                    command,
+                    ErrorCode::WidgetNotFound,
+                    Some(&id),
+                    format!("Widget '{id}' not found for SetAvatarStatus"),
+                );
+            }
+        }
+
+        ClientCommand::SetBadge { id, value } => {
+            if let Some(info) = widget_manager.widgets.get(&id) {
+                let widget_id = info.widget_id;
+                if let Some(overlay) = widget_manager.badges.get_mut(&widget_id) {
+                    overlay.spec.value = value;
+                    super::widgets::badge::update(render_root, overlay.badge_id, &overlay.spec);
+                } else {
+                    report_runtime_error(
+                        _event_sender,
+                        command,
+                        ErrorCode::UnsupportedOperation,
+                        Some(&id),
+                        format!(
+                            "SetBadge on widget '{id}' requires a `badge` param at creation (use 0 or false for an initially hidden badge)"
+                        ),
+                    );
+                }
+            } else {
+                eprintln!("[UI] Widget '{}' not found for SetBadge", id);
+                report_runtime_error(
+                    _event_sender,
+                    command,
+                    ErrorCode::WidgetNotFound,
+                    Some(&id),
+                    format!("Widget '{id}' not found for SetBadge"),
                 );
             }
         }
@@ -173,12 +758,13 @@ pub fn handle_client_command(
                 } else {
                     report_runtime_error(
                         _event_sender,
-                        "ui-handler",
+                        command,
+                        ErrorCode::UnsupportedOperation,
+                        Some(&id),
                         format!(
                             "PlayVideo on {:?} is not supported for widget '{id}'",
                             info.kind
                         ),
-                        false,
                     );
                 }
             } else {
@@ -197,12 +783,13 @@ pub fn handle_client_command(
                 } else {
                     report_runtime_error(
                         _event_sender,
-                        "ui-handler",
+                        command,
+                        ErrorCode::UnsupportedOperation,
+                        Some(&id),
                         format!(
                             "PauseVideo on {:?} is not supported for widget '{id}'",
                             info.kind
                         ),
-                        false,
                     );
                 }
             } else {
@@ -221,12 +808,13 @@ pub fn handle_client_command(
                 } else {
                     report_runtime_error(
                         _event_sender,
-                        "ui-handler",
+                        command,
+                        ErrorCode::UnsupportedOperation,
+                        Some(&id),
                         format!(
                             "SeekVideo on {:?} is not supported for widget '{id}'",
                             info.kind
                         ),
-                        false,
                     );
                 }
             } else {
@@ -234,60 +822,337 @@ pub fn handle_client_command(
             }
         }
 
-        ClientCommand::SetWidgetChecked { id, checked } => {
+        ClientCommand::SetVideoVolume { id, volume } => {
             if let Some(info) = widget_manager.widgets.get(&id) {
                 let widget_id = info.widget_id;
-                if matches!(info.kind, WidgetKind::Checkbox) {
+                if matches!(info.kind, WidgetKind::Video) {
                     render_root.edit_widget(widget_id, |mut widget| {
-                        let mut cb = widget.downcast::<Checkbox>();
-                        Checkbox::set_checked(&mut cb, checked);
+                        let mut video = widget.downcast::<VideoWidget>();
+                        VideoWidget::set_volume(&mut video, volume);
                     });
                 } else {
                     report_runtime_error(
                         _event_sender,
-                        "ui-handler",
+                        command,
+                        ErrorCode::UnsupportedOperation,
+                        Some(&id),
                         format!(
-                            "SetWidgetChecked on {:?} is not supported for widget '{id}'",
+                            "SetVideoVolume on {:?} is not supported for widget '{id}'",
                             info.kind
                         ),
-                        false,
                     );
                 }
             } else {
-                eprintln!("[UI] Widget '{}' not found for SetWidgetChecked", id);
+                eprintln!("[UI] Widget '{}' not found for SetVideoVolume", id);
+            }
+        }
+
+        ClientCommand::SetVideoMuted { id, muted } => {
+            if let Some(info) = widget_manager.widgets.get(&id) {
+                let widget_id = info.widget_id;
+                if matches!(info.kind, WidgetKind::Video) {
+                    render_root.edit_widget(widget_id, |mut widget| {
+                        let mut video = widget.downcast::<VideoWidget>();
+                        VideoWidget::set_muted(&mut video, muted);
+                    });
+                } else {
+                    report_runtime_error(
+                        _event_sender,
+                        command,
+                        ErrorCode::UnsupportedOperation,
+                        Some(&id),
+                        format!(
+                            "SetVideoMuted on {:?} is not supported for widget '{id}'",
+                            info.kind
+                        ),
+                    );
+                }
+            } else {
+                eprintln!("[UI] Widget '{}' not found for SetVideoMuted", id);
+            }
+        }
+
+        ClientCommand::ListAudioDevices { request_id } => {
+            // Probing devices waits on the sound server; keep it off the UI thread.
+            let event_sender = _event_sender.clone();
+            let spawned = std::thread::Builder::new()
+                .name("audio-devices".to_string())
+                .spawn(move || {
+                    crate::audio::watch(&event_sender);
+                    let _ = event_sender.send(crate::ipc::UiEvent::AudioDevices {
+                        request_id,
+                        devices: crate::audio::devices(),
+                    });
+                });
+            if let Err(e) = spawned {
                 report_runtime_error(
                     _event_sender,
-                    "ui-handler",
-                    format!("Widget '{id}' not found for SetWidgetChecked"),
-                    false,
+                    command,
+                    ErrorCode::Internal,
+                    None,
+                    format!("Failed to spawn audio device listing: {e}"),
                 );
             }
         }
 
-        ClientCommand::SetWidgetStyle { id, style } => {
-            // Special handling for root flex (the "body" element)
-            if id == "__root__" {
-                render_root.edit_widget_with_tag(ROOT_FLEX_TAG, |mut widget| {
-                    let mut flex = widget.downcast::<Flex>();
-                    apply_flex_style(&mut flex, &style);
+        ClientCommand::SetAudioOutput { device } => {
+            // Checking the device is listed probes them too; the driver reloads
+            // playing videos once `OutputChanged` comes back.
+            let event_sender = _event_sender.clone();
+            let spawned = std::thread::Builder::new()
+                .name("audio-output".to_string())
+                .spawn(move || {
+                    crate::audio::watch(&event_sender);
+                    match crate::audio::select_output(device) {
+                        Ok(()) => {
+                            if let Some((proxy, win_id)) = get_event_loop_proxy() {
+                                let _ = proxy.send_event(MasonryUserEvent::AsyncAction(
+                                    win_id,
+                                    Box::new(crate::audio::OutputChanged),
+                                ));
+                            }
+                        }
+                        Err(message) => report_runtime_error(
+                            &event_sender,
+                            command,
+                            ErrorCode::InvalidValue,
+                            None,
+                            message,
+                        ),
+                    }
                 });
-                return;
+            if let Err(e) = spawned {
+                report_runtime_error(
+                    _event_sender,
+                    command,
+                    ErrorCode::Internal,
+                    None,
+                    format!("Failed to spawn audio output switch: {e}"),
+                );
             }
+        }
 
+        ClientCommand::EndRefresh { id } => {
             if let Some(info) = widget_manager.widgets.get(&id) {
-                let widget_id = info.widget_id;
-                match &info.kind {
-                    WidgetKind::Label => {
-                        let text_styles = build_text_styles(&style);
-                        render_root.edit_widget(widget_id, |mut widget| {
-                            let mut label = widget.downcast::<Label>();
-                            for s in &text_styles {
-                                Label::insert_style(&mut label, s.clone());
-                            }
+                if matches!(info.kind, WidgetKind::Portal) {
+                    render_root.edit_widget(info.widget_id, |mut widget| {
+                        let mut portal = widget.downcast::<ScrollArea>();
+                        ScrollArea::end_refresh(&mut portal);
+                    });
+                } else {
+                    report_runtime_error(
+                        _event_sender,
+                        command,
+                        ErrorCode::UnsupportedOperation,
+                        Some(&id),
+                        format!(
+                            "EndRefresh on {:?} is not supported for widget '{id}'",
+                            info.kind
+                        ),
+                    );
+                }
+            } else {
+                report_runtime_error(
+                    _event_sender,
+                    command,
+                    ErrorCode::WidgetNotFound,
+                    Some(&id),
+                    format!("Widget '{id}' not found for EndRefresh"),
+                );
+            }
+        }
+
+        ClientCommand::SetWidgetChecked { id, checked } => {
+            if let Some(info) = widget_manager.widgets.get(&id) {
+                let widget_id = info.widget_id;
+                if matches!(info.kind, WidgetKind::Checkbox) {
+                    render_root.edit_widget(widget_id, |mut widget| {
+                        let mut cb = widget.downcast::<Checkbox>();
+                        Checkbox::set_checked(&mut cb, checked);
+                    });
+                } else {
+                    report_runtime_error(
+                        _event_sender,
+                        command,
+                        ErrorCode::UnsupportedOperation,
+                        Some(&id),
+                        format!(
+                            "SetWidgetChecked on {:?} is not supported for widget '{id}'",
+                            info.kind
+                        ),
+                    );
+                }
+            } else {
+                eprintln!("[UI] Widget '{}' not found for SetWidgetChecked", id);
+                report_runtime_error(
+                    _event_sender,
+                    command,
+                    ErrorCode::WidgetNotFound,
+                    Some(&id),
+                    format!("Widget '{id}' not found for SetWidgetChecked"),
+                );
+            }
+        }
+
+        ClientCommand::SetWidgetStyle { id, mut style } => {
+            // Recorded before the wrappers below take fields out of `style`.
+            if is_root_key(&id) {
+                let mut current = widget_manager.root_styles.remove(&id);
+                snapshot::merge_style(&mut current, &style);
+                if let Some(current) = current {
+                    widget_manager.root_styles.insert(id.clone(), current);
+                }
+            } else if let Some(record) = widget_manager.records.get_mut(&id) {
+                snapshot::merge_style(&mut record.style, &style);
+            }
+            remember_sizes(
+                widget_manager.sized_styles.entry(id.clone()).or_default(),
+                &style,
+            );
+
+            // Special handling for root flex (the "body" element)
+            if id == "__root__" {
+                if let Some(mode) = style.background_mode
+                    && mode != BackgroundMode::Solid
+                    && !is_window_transparent()
+                {
+                    report_runtime_error(
+                        _event_sender,
+                        command,
+                        ErrorCode::UnsupportedOperation,
+                        Some(&id),
+                        "backgroundMode needs a transparent window (--transparent or \
+                         `transparent = true` under [window] in vellum.toml)"
+                            .to_string(),
+                    );
+                } else if style.background_mode.is_some() {
+                    widget_manager.root_background_mode = style.background_mode;
+                }
+                let mode = widget_manager.root_background_mode;
+                render_root.edit_widget_with_tag(ROOT_FLEX_TAG, |mut widget| {
+                    let mut flex = widget.downcast::<Flex>();
+                    apply_flex_style(&mut flex, &style, false, is_right_to_left());
+                    // The mode decides what the root may paint over the window.
+                    if let Some(mode) = mode {
+                        let fill = root_background(mode, style.background.as_ref());
+                        flex.insert_prop(masonry::properties::Background::Color(fill));
+                    }
+                });
+                return;
+            }
+
+            // Layers only take the alignment of their children.
+            if let Some(tag) = layer_tag(&id) {
+                let rtl = is_right_to_left();
+                render_root.edit_widget_with_tag(tag, |mut layer| {
+                    Layer::set_alignment(
+                        &mut layer,
+                        style
+                            .main_axis_alignment
+                            .as_ref()
+                            .map(|align| main_axis_alignment(align, false)),
+                        style
+                            .cross_axis_alignment
+                            .as_ref()
+                            .map(|align| cross_axis_alignment(align, rtl)),
+                    );
+                });
+                return;
+            }
+
+            if let Some(info) = widget_manager.widgets.get(&id) {
+                let widget_id = info.widget_id;
+                if let Some(&clip_id) = widget_manager.clips.get(&widget_id) {
+                    render_root.edit_widget(clip_id, |mut widget| {
+                        let mut clip = widget.downcast::<ClipBox>();
+                        if let Some(overflow) = style.overflow {
+                            ClipBox::set_enabled(&mut clip, overflow == OverflowValue::Hidden);
+                        }
+                        if let Some(radius) = style.corner_radius {
+                            ClipBox::set_corner_radius(&mut clip, scaled(radius));
+                        }
+                    });
+                } else if style.overflow == Some(OverflowValue::Hidden) {
+                    report_runtime_error(
+                        _event_sender,
+                        command,
+                        ErrorCode::UnsupportedOperation,
+                        Some(&id),
+                        format!(
+                            "overflow: hidden on widget '{id}' must be set when the widget is created"
+                        ),
+                    );
+                }
+                if let Some(&feedback_id) = widget_manager.feedbacks.get(&widget_id)
+                    && let Some(radius) = style.corner_radius
+                {
+                    render_root.edit_widget(feedback_id, |mut widget| {
+                        let mut feedback = widget.downcast::<PressFeedback>();
+                        PressFeedback::set_corner_radius(&mut feedback, scaled(radius));
+                    });
+                }
+                if let Some(&background_id) = widget_manager.backgrounds.get(&widget_id) {
+                    background::update(render_root, background_id, &mut style);
+                } else if style.background_image.is_some() {
+                    let message = if background::supports(&info.kind) {
+                        format!(
+                            "backgroundImage on widget '{id}' must be set when the widget is created"
+                        )
+                    } else {
+                        format!(
+                            "backgroundImage only applies to containers, not {:?} '{id}'",
+                            info.kind
+                        )
+                    };
+                    report_runtime_error(
+                        _event_sender,
+                        command,
+                        ErrorCode::UnsupportedOperation,
+                        Some(&id),
+                        message,
+                    );
+                }
+                if let Some(z_index) = style.z_index
+                    && let Err(message) =
+                        zstack::set_z_index(render_root, widget_manager, &id, z_index)
+                {
+                    report_runtime_error(
+                        _event_sender,
+                        command,
+                        ErrorCode::UnsupportedOperation,
+                        Some(&id),
+                        message,
+                    );
+                }
+                match &info.kind {
+                    WidgetKind::Label => {
+                        let text_styles = build_text_styles(&style);
+                        render_root.edit_widget(widget_id, |mut widget| {
+                            let mut label = widget.downcast::<Label>();
+                            for s in &text_styles {
+                                Label::insert_style(&mut label, s.clone());
+                            }
                             apply_box_props_to_widget(&mut label, &style);
                         });
                     }
+                    WidgetKind::Prose => {
+                        let text_styles = build_text_styles(&style);
+                        render_root.edit_widget(widget_id, |mut widget| {
+                            let mut prose = widget.downcast::<Prose>();
+                            apply_box_props_to_widget(&mut prose, &style);
+                            let mut area = Prose::text_mut(&mut prose);
+                            for s in &text_styles {
+                                TextArea::<false>::insert_style(&mut area, s.clone());
+                            }
+                            if let Some(ref color) = style.color {
+                                area.insert_prop(masonry::properties::ContentColor::new(
+                                    color_value_to_peniko(color),
+                                ));
+                            }
+                        });
+                    }
                     WidgetKind::Button => {
+                        let hinted = widget_manager.accelerators.contains_key(&widget_id);
                         render_root.edit_widget(widget_id, |mut widget| {
                             // Apply box properties to the button itself
                             let mut button = widget.downcast::<Button>();
@@ -296,9 +1161,11 @@ pub fn handle_client_command(
                             // Apply flex styles to the inner flex container
                             let mut child = Button::child_mut(&mut button);
                             let mut flex = child.downcast::<Flex>();
-                            apply_flex_style(&mut flex, &style);
+                            apply_flex_style(&mut flex, &style, false, false);
 
-                            let child_count = masonry::core::CollectionWidget::len(&*flex.widget);
+                            // The accelerator hint keeps its own dimmed color.
+                            let child_count = masonry::core::CollectionWidget::len(&*flex.widget)
+                                - usize::from(hinted);
                             for index in 0..child_count {
                                 let mut inner =
                                     masonry::core::CollectionWidget::get_mut(&mut flex, index);
@@ -309,6 +1176,15 @@ pub fn handle_client_command(
                                             color_value_to_peniko(color),
                                         ));
                                     }
+                                    if let Some(ref color) = style.disabled_color {
+                                        label.insert_prop(
+                                            masonry::properties::DisabledContentColor(
+                                                masonry::properties::ContentColor::new(
+                                                    color_value_to_peniko(color),
+                                                ),
+                                            ),
+                                        );
+                                    }
                                     continue;
                                 }
 
@@ -321,31 +1197,59 @@ pub fn handle_client_command(
 
                                     if let Some(icon_size) = style.icon_size {
                                         svg.insert_prop(masonry::properties::Dimensions::fixed(
-                                            masonry::layout::Length::px(icon_size),
-                                            masonry::layout::Length::px(icon_size),
+                                            px(icon_size),
+                                            px(icon_size),
                                         ));
                                     }
                                 }
                             }
                         });
                     }
-                    WidgetKind::Svg => {
+                    WidgetKind::Svg | WidgetKind::Icon => {
                         render_root.edit_widget(widget_id, |mut widget| {
                             let mut svg_widget = widget.downcast::<SvgWidget>();
                             apply_box_props_to_widget(&mut svg_widget, &style);
                         });
                     }
                     WidgetKind::Flex | WidgetKind::Container => {
+                        let mirror_main = widget_manager.mirrored_rows.contains(&widget_id);
+                        let mirror_cross = widget_manager.mirrored_columns.contains(&widget_id);
                         render_root.edit_widget(widget_id, |mut widget| {
                             let mut flex = widget.downcast::<Flex>();
-                            apply_flex_style(&mut flex, &style);
+                            apply_flex_style(&mut flex, &style, mirror_main, mirror_cross);
+                        });
+                    }
+                    WidgetKind::Portal => {
+                        let scrollbar = ScrollbarStyle::from_style(&style);
+                        render_root.edit_widget(widget_id, |mut widget| {
+                            let mut portal = widget.downcast::<ScrollArea>();
+                            apply_box_props_to_widget(&mut portal, &style);
+                            ScrollArea::set_style(&mut portal, &scrollbar);
+                        });
+                    }
+                    WidgetKind::ReorderableList => {
+                        render_root.edit_widget(widget_id, |mut widget| {
+                            let mut list = widget.downcast::<ReorderableList>();
+                            apply_box_props_to_widget(&mut list, &style);
+                            if let Some(gap) = style.gap {
+                                ReorderableList::set_gap(&mut list, scaled(gap));
+                            }
+                            if let Some(color) = &style.fill_color {
+                                ReorderableList::set_indicator_color(
+                                    &mut list,
+                                    color_value_to_peniko(color),
+                                );
+                            }
                         });
                     }
                     WidgetKind::ProgressBar => {
                         // ProgressBar value changes are handled via SetWidgetValue
+                        let track = style.track_color.as_ref().map(color_value_to_peniko);
+                        let fill = style.fill_color.as_ref().map(color_value_to_peniko);
                         render_root.edit_widget(widget_id, |mut widget| {
-                            let mut pbar = widget.downcast::<ProgressBar>();
+                            let mut pbar = widget.downcast::<ProgressBarWidget>();
                             apply_box_props_to_widget(&mut pbar, &style);
+                            ProgressBarWidget::set_colors(&mut pbar, track, fill);
                         });
                     }
                     WidgetKind::Slider => {
@@ -354,16 +1258,67 @@ pub fn handle_client_command(
                             apply_box_props_to_widget(&mut slider, &style);
                         });
                     }
+                    WidgetKind::RangeSlider => {
+                        render_root.edit_widget(widget_id, |mut widget| {
+                            let mut slider = widget.downcast::<RangeSlider>();
+                            apply_box_props_to_widget(&mut slider, &style);
+                        });
+                    }
+                    WidgetKind::Avatar => {
+                        render_root.edit_widget(widget_id, |mut widget| {
+                            let mut avatar = widget.downcast::<AvatarWidget>();
+                            apply_box_props_to_widget(&mut avatar, &style);
+                        });
+                    }
+                    WidgetKind::Skeleton => {
+                        render_root.edit_widget(widget_id, |mut widget| {
+                            let mut skeleton = widget.downcast::<SkeletonWidget>();
+                            apply_box_props_to_widget(&mut skeleton, &style);
+                            if let Some(radius) = style.corner_radius {
+                                SkeletonWidget::set_corner_radius(&mut skeleton, scaled(radius));
+                            }
+                        });
+                    }
+                    WidgetKind::QrCode => {
+                        render_root.edit_widget(widget_id, |mut widget| {
+                            let mut qr_code = widget.downcast::<QrCodeWidget>();
+                            apply_box_props_to_widget(&mut qr_code, &style);
+                            if let Some(color) = &style.color {
+                                QrCodeWidget::set_foreground(
+                                    &mut qr_code,
+                                    color_value_to_peniko(color),
+                                );
+                            }
+                        });
+                    }
+                    WidgetKind::CustomPaint => {
+                        render_root.edit_widget(widget_id, |mut widget| {
+                            let mut canvas = widget.downcast::<CustomPaintWidget>();
+                            apply_box_props_to_widget(&mut canvas, &style);
+                        });
+                    }
+                    WidgetKind::GpuSurface => {
+                        render_root.edit_widget(widget_id, |mut widget| {
+                            let mut surface = widget.downcast::<GpuSurfaceWidget>();
+                            apply_box_props_to_widget(&mut surface, &style);
+                        });
+                    }
                     WidgetKind::SizedBox => {
                         render_root.edit_widget(widget_id, |mut widget| {
                             let mut sbox = widget.downcast::<SizedBox>();
                             apply_box_props_to_widget(&mut sbox, &style);
                         });
                     }
+                    WidgetKind::ZStack => {
+                        render_root.edit_widget(widget_id, |mut widget| {
+                            let mut stack = widget.downcast::<Layer>();
+                            apply_box_props_to_widget(&mut stack, &style);
+                        });
+                    }
                     WidgetKind::Image => {
-                        // Images in masonry do not support arbitrary box styles natively like HTML.
-                        // Width/height are handled by wrapping them in SizedBox (done in image.rs).
-                        // We silently ignore box styles on the inner image here to prevent log spam.
+                        // Only fit, position and tiling change in place; other box
+                        // styles are ignored here to prevent log spam.
+                        super::widgets::image::update_style(render_root, widget_id, &style);
                     }
                     WidgetKind::Video => {
                         render_root.edit_widget(widget_id, |mut widget| {
@@ -384,12 +1339,13 @@ pub fn handle_client_command(
                     _ => {
                         report_runtime_error(
                             _event_sender,
-                            "ui-handler",
+                            command,
+                            ErrorCode::UnsupportedOperation,
+                            Some(&id),
                             format!(
                                 "SetWidgetStyle was not fully supported for {:?} widget '{id}'",
                                 info.kind
                             ),
-                            false,
                         );
                     }
                 }
@@ -397,9 +1353,10 @@ pub fn handle_client_command(
                 eprintln!("[UI] Widget '{}' not found for SetWidgetStyle", id);
                 report_runtime_error(
                     _event_sender,
-                    "ui-handler",
+                    command,
+                    ErrorCode::WidgetNotFound,
+                    Some(&id),
                     format!("Widget '{id}' not found for SetWidgetStyle"),
-                    false,
                 );
             }
         }
@@ -460,17 +1417,193 @@ pub fn handle_client_command(
             );
         }
 
+        ClientCommand::SetPaintOps { id, ops } => {
+            if let Some(info) = widget_manager.widgets.get(&id) {
+                if matches!(info.kind, WidgetKind::CustomPaint) {
+                    let mut result = Ok(());
+                    render_root.edit_widget(info.widget_id, |mut widget| {
+                        let mut canvas = widget.downcast::<CustomPaintWidget>();
+                        result = CustomPaintWidget::set_ops(&mut canvas, &ops);
+                    });
+                    if let Err(err) = result {
+                        report_runtime_error(
+                            _event_sender,
+                            command,
+                            ErrorCode::InvalidValue,
+                            Some(&id),
+                            format!("CustomPaint '{id}' rejected its paint ops: {err}"),
+                        );
+                    }
+                } else {
+                    report_runtime_error(
+                        _event_sender,
+                        command,
+                        ErrorCode::UnsupportedOperation,
+                        Some(&id),
+                        format!(
+                            "SetPaintOps on {:?} is not supported for widget '{id}'",
+                            info.kind
+                        ),
+                    );
+                }
+            } else {
+                report_runtime_error(
+                    _event_sender,
+                    command,
+                    ErrorCode::WidgetNotFound,
+                    Some(&id),
+                    format!("Widget '{id}' not found for SetPaintOps"),
+                );
+            }
+        }
+
+        ClientCommand::SetShader { id, wgsl } => {
+            if let Some(info) = widget_manager.widgets.get(&id) {
+                if matches!(info.kind, WidgetKind::GpuSurface) {
+                    let mut result = Ok(());
+                    render_root.edit_widget(info.widget_id, |mut widget| {
+                        let mut surface = widget.downcast::<GpuSurfaceWidget>();
+                        result = GpuSurfaceWidget::set_shader(&mut surface, &wgsl);
+                    });
+                    if let Err(err) = result {
+                        report_runtime_error(
+                            _event_sender,
+                            command,
+                            ErrorCode::InvalidValue,
+                            Some(&id),
+                            format!("GpuSurface '{id}' rejected its shader: {err}"),
+                        );
+                    }
+                } else {
+                    report_runtime_error(
+                        _event_sender,
+                        command,
+                        ErrorCode::UnsupportedOperation,
+                        Some(&id),
+                        format!(
+                            "SetShader on {:?} is not supported for widget '{id}'",
+                            info.kind
+                        ),
+                    );
+                }
+            } else {
+                report_runtime_error(
+                    _event_sender,
+                    command,
+                    ErrorCode::WidgetNotFound,
+                    Some(&id),
+                    format!("Widget '{id}' not found for SetShader"),
+                );
+            }
+        }
+
+        ClientCommand::SetShaderUniforms { id, values } => {
+            let Some(info) = widget_manager.widgets.get(&id) else {
+                report_runtime_error(
+                    _event_sender,
+                    command,
+                    ErrorCode::WidgetNotFound,
+                    Some(&id),
+                    format!("Widget '{id}' not found for SetShaderUniforms"),
+                );
+                return;
+            };
+            if !matches!(info.kind, WidgetKind::GpuSurface) {
+                report_runtime_error(
+                    _event_sender,
+                    command,
+                    ErrorCode::UnsupportedOperation,
+                    Some(&id),
+                    format!(
+                        "SetShaderUniforms on {:?} is not supported for widget '{id}'",
+                        info.kind
+                    ),
+                );
+                return;
+            }
+            if values.len() > MAX_UNIFORMS {
+                report_runtime_error(
+                    _event_sender,
+                    command,
+                    ErrorCode::InvalidValue,
+                    Some(&id),
+                    format!(
+                        "GpuSurface '{id}' takes at most {MAX_UNIFORMS} uniforms, got {}",
+                        values.len()
+                    ),
+                );
+                return;
+            }
+            render_root.edit_widget(info.widget_id, |mut widget| {
+                let mut surface = widget.downcast::<GpuSurfaceWidget>();
+                GpuSurfaceWidget::set_uniforms(&mut surface, values);
+            });
+        }
+
+        ClientCommand::SetRawProperty { id, name, json } => {
+            let Some(info) = widget_manager.widgets.get(&id) else {
+                report_runtime_error(
+                    _event_sender,
+                    command,
+                    ErrorCode::WidgetNotFound,
+                    Some(&id),
+                    format!("Widget '{id}' not found for SetRawProperty"),
+                );
+                return;
+            };
+            match raw_properties::parse(&name, &json) {
+                Ok(property) => {
+                    render_root.edit_widget(info.widget_id, |mut widget| {
+                        raw_properties::apply(&mut widget, property);
+                    });
+                }
+                Err(message) => report_runtime_error(
+                    _event_sender,
+                    command,
+                    ErrorCode::InvalidValue,
+                    Some(&id),
+                    message,
+                ),
+            }
+        }
+
         ClientCommand::SetWidgetVisible { id, visible } => {
             report_runtime_error(
                 _event_sender,
-                "ui-handler",
+                command,
+                ErrorCode::UnsupportedOperation,
+                Some(&id),
                 format!(
                     "SetWidgetVisible is not implemented for widget '{id}' (requested visible={visible})"
                 ),
-                false,
             );
         }
 
+        ClientCommand::SetWidgetEnabled { id, enabled } => {
+            if let Some(info) = widget_manager.widgets.get(&id) {
+                // Masonry carries the disabled state down to the descendants,
+                // which then paint with their Disabled* properties. A Button's
+                // feedback wrapper is disabled with it so it stops tinting.
+                let target = widget_manager
+                    .feedbacks
+                    .get(&info.widget_id)
+                    .copied()
+                    .unwrap_or(info.widget_id);
+                render_root.edit_widget(target, |mut widget| {
+                    widget.ctx.set_disabled(!enabled);
+                });
+            } else {
+                eprintln!("[UI] Widget '{}' not found for SetWidgetEnabled", id);
+                report_runtime_error(
+                    _event_sender,
+                    command,
+                    ErrorCode::WidgetNotFound,
+                    Some(&id),
+                    format!("Widget '{id}' not found for SetWidgetEnabled"),
+                );
+            }
+        }
+
         ClientCommand::RemoveWidget { id } => {
             if let Some(info) = widget_manager.widgets.get(&id).cloned() {
                 let parent_key = info.parent_id.as_deref().unwrap_or("__root__");
@@ -484,11 +1617,12 @@ pub fn handle_client_command(
                     );
                     report_runtime_error(
                         _event_sender,
-                        "ui-handler",
+                        command,
+                        ErrorCode::TreeInconsistent,
+                        Some(&id),
                         format!(
                             "RemoveWidget for '{id}' found no siblings under parent '{parent_key}'; metadata was synced only"
                         ),
-                        false,
                     );
                     widget_manager.remove_widget_subtree(&id);
                     return;
@@ -503,11 +1637,12 @@ pub fn handle_client_command(
                     );
                     report_runtime_error(
                         _event_sender,
-                        "ui-handler",
+                        command,
+                        ErrorCode::TreeInconsistent,
+                        Some(&id),
                         format!(
                             "RemoveWidget for '{id}' had stale index {child_index}; clamped within parent '{parent_key}'"
                         ),
-                        false,
                     );
                     sibling_count - 1
                 };
@@ -516,13 +1651,23 @@ pub fn handle_client_command(
                     render_root.edit_widget_with_tag(ROOT_FLEX_TAG, |mut flex| {
                         masonry::core::CollectionWidget::remove(&mut flex, safe_index);
                     });
+                } else if let Some(tag) = layer_tag(parent_key) {
+                    render_root.edit_widget_with_tag(tag, |mut layer| {
+                        Layer::remove_child(&mut layer, safe_index);
+                    });
                 } else if let Some(parent_info) = widget_manager.widgets.get(parent_key) {
                     let parent_wid = parent_info.widget_id;
                     match parent_info.kind {
                         WidgetKind::Flex | WidgetKind::Container => {
+                            // Mirrored rows hold their children in reverse order.
+                            let index = if widget_manager.mirrored_rows.contains(&parent_wid) {
+                                sibling_count - 1 - safe_index
+                            } else {
+                                safe_index
+                            };
                             render_root.edit_widget(parent_wid, |mut parent_widget| {
                                 let mut flex = parent_widget.downcast::<Flex>();
-                                masonry::core::CollectionWidget::remove(&mut flex, safe_index);
+                                masonry::core::CollectionWidget::remove(&mut flex, index);
                             });
                         }
                         WidgetKind::Button => {
@@ -533,6 +1678,18 @@ pub fn handle_client_command(
                                 masonry::core::CollectionWidget::remove(&mut flex, safe_index);
                             });
                         }
+                        WidgetKind::Portal => {
+                            render_root.edit_widget(parent_wid, |mut parent_widget| {
+                                let mut portal = parent_widget.downcast::<ScrollArea>();
+                                ScrollArea::remove_child(&mut portal, safe_index);
+                            });
+                        }
+                        WidgetKind::ReorderableList => {
+                            render_root.edit_widget(parent_wid, |mut parent_widget| {
+                                let mut list = parent_widget.downcast::<ReorderableList>();
+                                ReorderableList::remove_child(&mut list, safe_index);
+                            });
+                        }
                         WidgetKind::SizedBox => {
                             render_root.edit_widget(parent_wid, |mut parent_widget| {
                                 let mut sbox = parent_widget.downcast::<SizedBox>();
@@ -541,8 +1698,8 @@ pub fn handle_client_command(
                         }
                         WidgetKind::ZStack => {
                             render_root.edit_widget(parent_wid, |mut parent_widget| {
-                                let mut zstack = parent_widget.downcast::<ZStack>();
-                                masonry::core::CollectionWidget::remove(&mut zstack, safe_index);
+                                let mut stack = parent_widget.downcast::<Layer>();
+                                Layer::remove_child(&mut stack, safe_index);
                             });
                         }
                         _ => {
@@ -552,12 +1709,13 @@ pub fn handle_client_command(
                             );
                             report_runtime_error(
                                 _event_sender,
-                                "ui-handler",
+                                command,
+                                ErrorCode::UnsupportedOperation,
+                                Some(&id),
                                 format!(
                                     "Parent '{parent_key}' of kind {:?} does not support child removal for '{id}'",
                                     parent_info.kind
                                 ),
-                                false,
                             );
                         }
                     }
@@ -568,11 +1726,12 @@ pub fn handle_client_command(
                     );
                     report_runtime_error(
                         _event_sender,
-                        "ui-handler",
+                        command,
+                        ErrorCode::TreeInconsistent,
+                        Some(&id),
                         format!(
                             "Parent widget '{parent_key}' not found for RemoveWidget '{id}'; metadata was synced only"
                         ),
-                        false,
                     );
                 }
 
@@ -600,34 +1759,682 @@ pub fn handle_client_command(
             render_root.emit_signal(RenderRootSignal::Exit);
         }
 
-        ClientCommand::ExitApp => {
-            println!("[UI] Exiting application");
+        // A kiosk can't be quit from the OS (Cmd-Q, the dock); JS decides.
+        ClientCommand::ExitApp {
+            reason: ExitReason::Quit,
+            ..
+        } if is_kiosk() => {
+            let _ = _event_sender.send(crate::ipc::UiEvent::CloseRequested);
+        }
+
+        ClientCommand::ExitApp { code, reason } => {
+            set_exit_code(code);
+            if reason.has_grace_period() && begin_exit() {
+                println!("[UI] Exit requested ({reason:?}); waiting for JS to finish up");
+                let _ = _event_sender.send(crate::ipc::UiEvent::BeforeExit {
+                    code,
+                    reason,
+                    timeout_ms: BEFORE_EXIT_TIMEOUT.as_millis() as u64,
+                });
+                // The hard limit: exit even if JS never confirms.
+                let spawned = std::thread::Builder::new()
+                    .name("exit-timeout".to_string())
+                    .spawn(move || {
+                        std::thread::sleep(BEFORE_EXIT_TIMEOUT);
+                        if let Some((proxy, win_id)) = get_event_loop_proxy() {
+                            let _ = proxy.send_event(MasonryUserEvent::AsyncAction(
+                                win_id,
+                                Box::new(ClientCommandAction(ClientCommand::ExitApp {
+                                    code: exit_code(),
+                                    reason: ExitReason::Timeout,
+                                })),
+                            ));
+                        }
+                    });
+                if spawned.is_ok() {
+                    return;
+                }
+            }
+            println!("[UI] Exiting application ({reason:?}, code {code})");
             render_root.emit_signal(RenderRootSignal::Exit);
         }
 
+        ClientCommand::SetDockMenu { items } => {
+            widget_manager.menu_accelerators.clear();
+            for item in &items {
+                let Some(text) = &item.accelerator else {
+                    continue;
+                };
+                match Accelerator::parse(text) {
+                    Ok(accelerator) => widget_manager
+                        .menu_accelerators
+                        .push((item.id.clone(), accelerator)),
+                    Err(err) => report_runtime_error(
+                        _event_sender,
+                        command,
+                        ErrorCode::InvalidValue,
+                        None,
+                        format!("Dock menu item '{}': {err}", item.id),
+                    ),
+                }
+            }
+            #[cfg(target_os = "macos")]
+            crate::macos::set_dock_menu(items);
+            #[cfg(not(target_os = "macos"))]
+            let _ = items;
+        }
+
+        ClientCommand::InhibitSleep { token, reason } => {
+            println!("[UI] Inhibiting sleep: {}", reason);
+            crate::power::inhibit_sleep(token, reason);
+        }
+
+        ClientCommand::ReleaseSleep { token } => {
+            crate::power::release_sleep(&token);
+        }
+
+        ClientCommand::SetLayoutDirection { direction } => {
+            if !widget_manager.widgets.is_empty() {
+                println!(
+                    "[UI] Layout direction changed after widgets were created; existing widgets keep their layout"
+                );
+            }
+            set_right_to_left(direction == LayoutDirection::Rtl);
+        }
+
+        ClientCommand::SetUiScale { factor } => {
+            if !(0.25..=5.0).contains(&factor) {
+                report_runtime_error(
+                    _event_sender,
+                    command,
+                    ErrorCode::InvalidValue,
+                    None,
+                    format!("UI scale {factor} is outside 0.25..=5"),
+                );
+                return;
+            }
+            if factor == ui_scale() {
+                return;
+            }
+            println!("[UI] Setting UI scale to {}", factor);
+            set_ui_scale(factor);
+            // Re-apply every remembered size so existing widgets pick up the new scale.
+            let known = &widget_manager.widgets;
+            widget_manager.sized_styles.retain(|id, _| {
+                is_root_key(id) || known.get(id).is_some_and(|info| restylable(&info.kind))
+            });
+            let sized = widget_manager
+                .sized_styles
+                .iter()
+                .map(|(id, style)| ClientCommand::SetWidgetStyle {
+                    id: id.clone(),
+                    style: style.clone(),
+                })
+                .collect();
+            replay_unjournaled(
+                sized,
+                _window_id,
+                render_root,
+                widget_manager,
+                _event_sender,
+            );
+        }
+
+        ClientCommand::ListFonts { request_id } => {
+            // Scanning system font directories can take a while; keep it off the UI thread.
+            let event_sender = _event_sender.clone();
+            let spawned = std::thread::Builder::new()
+                .name("font-list".to_string())
+                .spawn(move || {
+                    let families = super::fonts::list_system_fonts();
+                    let _ = event_sender.send(crate::ipc::UiEvent::FontList {
+                        request_id,
+                        families,
+                    });
+                });
+            if let Err(e) = spawned {
+                report_runtime_error(
+                    _event_sender,
+                    command,
+                    ErrorCode::Internal,
+                    None,
+                    format!("Failed to spawn font enumeration: {e}"),
+                );
+            }
+        }
+
+        ClientCommand::HitTest { request_id, x, y } => {
+            let hit = hit_test(render_root, widget_manager, Point::new(x, y));
+            let (widget_id, local) = match hit {
+                Some((id, local)) => (Some(id), local),
+                None => (None, Point::new(x, y)),
+            };
+            if let Err(send_err) = _event_sender.send(crate::ipc::UiEvent::HitTestResult {
+                request_id,
+                widget_id,
+                x: local.x,
+                y: local.y,
+            }) {
+                eprintln!("[UI] Failed to send hit test result to JS thread: {send_err}");
+            }
+        }
+
+        ClientCommand::DumpRecentCommands { request_id } => {
+            if let Err(send_err) = _event_sender.send(crate::ipc::UiEvent::RecentCommands {
+                request_id,
+                commands: crate::crash::recent_commands(),
+            }) {
+                eprintln!("[UI] Failed to send recent commands to JS thread: {send_err}");
+            }
+        }
+
+        ClientCommand::GetMetrics { request_id } => {
+            let mut videos: Vec<crate::ipc::VideoMetrics> = widget_manager
+                .widgets
+                .iter()
+                .filter(|(_, info)| matches!(info.kind, WidgetKind::Video))
+                .map(|(id, info)| crate::ipc::VideoMetrics {
+                    id: id.clone(),
+                    decoder: render_root
+                        .get_widget(info.widget_id)
+                        .and_then(|widget| widget.downcast::<VideoWidget>())
+                        .and_then(|video| video.inner().decoder()),
+                })
+                .collect();
+            videos.sort_by(|a, b| a.id.cmp(&b.id));
+            if let Err(send_err) = _event_sender.send(crate::ipc::UiEvent::Metrics {
+                request_id,
+                widget_count: widget_manager.widgets.len(),
+                videos,
+            }) {
+                eprintln!("[UI] Failed to send metrics to JS thread: {send_err}");
+            }
+        }
+
+        ClientCommand::PrintWindow { request_id } => {
+            let pages = print::render_pages(render_root, widget_manager, None);
+            finish_print(_event_sender, command, request_id, pages, print::print);
+        }
+
+        ClientCommand::ExportPdf {
+            request_id,
+            path,
+            widget_id,
+        } => {
+            let pages = print::render_pages(render_root, widget_manager, widget_id.as_deref());
+            finish_print(_event_sender, command, request_id, pages, move |pages| {
+                print::write_pdf(pages, Path::new(&path))
+            });
+        }
+
+        ClientCommand::SerializeTree { request_id } => match snapshot::serialize(widget_manager) {
+            Ok(snapshot) => {
+                if let Err(send_err) = _event_sender.send(crate::ipc::UiEvent::TreeSnapshot {
+                    request_id,
+                    snapshot,
+                }) {
+                    eprintln!("[UI] Failed to send tree snapshot to JS thread: {send_err}");
+                }
+            }
+            Err(message) => {
+                report_runtime_error(_event_sender, command, ErrorCode::Internal, None, message)
+            }
+        },
+
+        ClientCommand::RestoreTree { snapshot } => {
+            let commands = match snapshot::restore_commands(&snapshot) {
+                Ok(commands) => commands,
+                Err(message) => {
+                    report_runtime_error(
+                        _event_sender,
+                        command,
+                        ErrorCode::ParseError,
+                        None,
+                        message,
+                    );
+                    return;
+                }
+            };
+            println!(
+                "[UI] Restoring {} widget(s) from a snapshot",
+                commands.len()
+            );
+            clear_root(render_root, widget_manager);
+            replay_unjournaled(
+                commands,
+                _window_id,
+                render_root,
+                widget_manager,
+                _event_sender,
+            );
+        }
+
+        ClientCommand::SetJournal { enabled, limit } => {
+            widget_manager.journal.set_enabled(enabled, limit);
+        }
+
+        ClientCommand::BeginUndoGroup => widget_manager.journal.begin_group(),
+
+        ClientCommand::EndUndoGroup => widget_manager.journal.end_group(),
+
+        // Nothing to undo (or redo) is not an error; menus may lag behind.
+        ClientCommand::Undo => {
+            if let Some(commands) = widget_manager.journal.undo() {
+                replay_unjournaled(
+                    commands,
+                    _window_id,
+                    render_root,
+                    widget_manager,
+                    _event_sender,
+                );
+            }
+        }
+
+        ClientCommand::Redo => {
+            if let Some(commands) = widget_manager.journal.redo() {
+                replay_unjournaled(
+                    commands,
+                    _window_id,
+                    render_root,
+                    widget_manager,
+                    _event_sender,
+                );
+            }
+        }
+
+        ClientCommand::ObserveVisibility { id, threshold } => {
+            if !(0.0..=1.0).contains(&threshold) {
+                report_runtime_error(
+                    _event_sender,
+                    command,
+                    ErrorCode::InvalidValue,
+                    Some(&id),
+                    format!("Visibility threshold {threshold} is not between 0 and 1"),
+                );
+            } else if widget_manager.widgets.contains_key(&id) {
+                // The first sweep reports where the widget stands.
+                widget_manager.visibility_observers.insert(
+                    id,
+                    VisibilityObserver {
+                        threshold,
+                        visible: None,
+                    },
+                );
+                viewport::set_observing(true);
+            } else {
+                report_runtime_error(
+                    _event_sender,
+                    command,
+                    ErrorCode::WidgetNotFound,
+                    Some(&id),
+                    format!("Widget '{id}' not found for ObserveVisibility"),
+                );
+            }
+        }
+
+        ClientCommand::UnobserveVisibility { id } => {
+            widget_manager.visibility_observers.remove(&id);
+            viewport::set_observing(widget_manager.is_observing());
+        }
+
+        ClientCommand::ObserveResize { id } => {
+            if widget_manager.widgets.contains_key(&id) {
+                // The first sweep reports the current size.
+                widget_manager.resize_observers.insert(id, None);
+                viewport::set_observing(true);
+            } else {
+                report_runtime_error(
+                    _event_sender,
+                    command,
+                    ErrorCode::WidgetNotFound,
+                    Some(&id),
+                    format!("Widget '{id}' not found for ObserveResize"),
+                );
+            }
+        }
+
+        ClientCommand::UnobserveResize { id } => {
+            widget_manager.resize_observers.remove(&id);
+            viewport::set_observing(widget_manager.is_observing());
+        }
+
+        ClientCommand::CheckForUpdate {
+            request_id,
+            url,
+            current_version,
+        } => {
+            // Fetching the feed blocks on the network; keep it off the UI thread.
+            let event_sender = _event_sender.clone();
+            let spawned = std::thread::Builder::new()
+                .name("update-check".to_string())
+                .spawn(move || {
+                    let (version, notes, error) = match crate::update::check(&url, &current_version)
+                    {
+                        Ok(Some((version, notes))) => (Some(version), notes, None),
+                        Ok(None) => (None, None, None),
+                        Err(error) => (None, None, Some(error)),
+                    };
+                    let _ = event_sender.send(crate::ipc::UiEvent::UpdateCheckResult {
+                        request_id,
+                        version,
+                        notes,
+                        error,
+                    });
+                });
+            if let Err(e) = spawned {
+                report_runtime_error(
+                    _event_sender,
+                    command,
+                    ErrorCode::Internal,
+                    None,
+                    format!("Failed to spawn update check: {e}"),
+                );
+            }
+        }
+
+        ClientCommand::DownloadUpdate => {
+            let event_sender = _event_sender.clone();
+            let spawned = std::thread::Builder::new()
+                .name("update-download".to_string())
+                .spawn(move || {
+                    let event = match crate::update::download(&event_sender) {
+                        Ok(version) => crate::ipc::UiEvent::UpdateReady { version },
+                        Err(error) => crate::ipc::UiEvent::UpdateFailed { error },
+                    };
+                    let _ = event_sender.send(event);
+                });
+            if let Err(e) = spawned {
+                report_runtime_error(
+                    _event_sender,
+                    command,
+                    ErrorCode::Internal,
+                    None,
+                    format!("Failed to spawn update download: {e}"),
+                );
+            }
+        }
+
+        ClientCommand::RestartToUpdate => {
+            if !crate::update::is_staged() {
+                report_runtime_error(
+                    _event_sender,
+                    command,
+                    ErrorCode::UnsupportedOperation,
+                    None,
+                    "No update is staged; wait for UpdateReady".to_string(),
+                );
+                return;
+            }
+            crate::update::request_relaunch();
+            handle_client_command(
+                ClientCommand::ExitApp {
+                    code: 0,
+                    reason: ExitReason::App,
+                },
+                _window_id,
+                render_root,
+                widget_manager,
+                _event_sender,
+            );
+        }
+
+        ClientCommand::SetFrameTicks { enabled } => {
+            let ticking = edit_frame_clock(render_root, |clock| {
+                FrameClock::set_running(clock, enabled);
+            });
+            if !ticking && enabled {
+                report_runtime_error(
+                    _event_sender,
+                    command,
+                    ErrorCode::UnsupportedOperation,
+                    None,
+                    "Frame ticks need a window; this UI has no frame clock".to_string(),
+                );
+            }
+        }
+
+        ClientCommand::SetGestureTiming {
+            double_click_ms,
+            long_press_ms,
+        } => {
+            let timed = edit_frame_clock(render_root, |clock| {
+                FrameClock::set_gesture_timing(
+                    clock,
+                    double_click_ms.map(|ms| ms as f64),
+                    long_press_ms.map(|ms| ms as f64),
+                );
+            });
+            if !timed {
+                report_runtime_error(
+                    _event_sender,
+                    command,
+                    ErrorCode::UnsupportedOperation,
+                    None,
+                    "Gesture timing needs a window; this UI has no frame clock".to_string(),
+                );
+            }
+        }
+
+        ClientCommand::BindValue {
+            id,
+            property,
+            channel,
+        } => {
+            if widget_manager.widgets.contains_key(&id) {
+                widget_manager.bind(&id, &property, &channel);
+            } else {
+                report_runtime_error(
+                    _event_sender,
+                    command,
+                    ErrorCode::WidgetNotFound,
+                    Some(&id),
+                    format!("Widget '{id}' not found for BindValue"),
+                );
+            }
+        }
+
+        ClientCommand::UnbindValue { id, property } => {
+            widget_manager.unbind(&id, &property);
+        }
+
+        ClientCommand::PushChannel { channel, value } => {
+            // Values pushed before anything is bound are dropped.
+            let Some(bound) = widget_manager.bindings.get(&channel).cloned() else {
+                return;
+            };
+            // Streamed values are not undo steps.
+            widget_manager.journal.pause();
+            for binding in bound {
+                match bindings::command_for(&binding.id, &binding.property, &value) {
+                    Ok(cmd) => handle_client_command(
+                        cmd,
+                        _window_id,
+                        render_root,
+                        widget_manager,
+                        _event_sender,
+                    ),
+                    Err(message) => report_runtime_error(
+                        _event_sender,
+                        command,
+                        ErrorCode::InvalidValue,
+                        Some(&binding.id),
+                        format!("Channel '{channel}' bound to '{}': {message}", binding.id),
+                    ),
+                }
+            }
+            widget_manager.journal.resume();
+        }
+
+        ClientCommand::AnimateValue {
+            id,
+            property,
+            from,
+            to,
+            duration_ms,
+            easing,
+            repeat,
+        } => {
+            if !widget_manager.widgets.contains_key(&id) {
+                report_runtime_error(
+                    _event_sender,
+                    command,
+                    ErrorCode::WidgetNotFound,
+                    Some(&id),
+                    format!("Widget '{id}' not found for AnimateValue"),
+                );
+                return;
+            }
+            let checked = if !(duration_ms.is_finite() && duration_ms > 0.0) {
+                Err(format!("duration must be positive, got {duration_ms}"))
+            } else if !(from.is_finite() && to.is_finite()) {
+                Err(format!("cannot animate from {from} to {to}"))
+            } else {
+                Easing::parse(&easing)
+                    .ok_or_else(|| format!("unknown easing '{easing}'"))
+                    .and_then(|easing| {
+                        let first =
+                            bindings::command_for(&id, &property, &ChannelValue::Number(from))?;
+                        Ok((easing, first))
+                    })
+            };
+            let (easing, first) = match checked {
+                Ok(checked) => checked,
+                Err(message) => {
+                    report_runtime_error(
+                        _event_sender,
+                        command,
+                        ErrorCode::InvalidValue,
+                        Some(&id),
+                        format!("AnimateValue '{property}' on '{id}': {message}"),
+                    );
+                    return;
+                }
+            };
+            widget_manager.journal.pause();
+            handle_client_command(
+                first,
+                _window_id,
+                render_root,
+                widget_manager,
+                _event_sender,
+            );
+            widget_manager.journal.resume();
+            let animation = Animation {
+                id,
+                property,
+                from,
+                to,
+                duration: Duration::from_secs_f64(duration_ms / 1000.0),
+                easing,
+                repeat,
+                started: Instant::now(),
+            };
+            if edit_frame_clock(render_root, |clock| FrameClock::set_animating(clock, true)) {
+                widget_manager.animate(animation);
+            } else if let Ok(last) = bindings::command_for(
+                &animation.id,
+                &animation.property,
+                &ChannelValue::Number(to),
+            ) {
+                // Nothing to pace frames (headless); jump to the end.
+                widget_manager.journal.pause();
+                handle_client_command(last, _window_id, render_root, widget_manager, _event_sender);
+                widget_manager.journal.resume();
+            }
+        }
+
+        ClientCommand::StopAnimation { id, property } => {
+            widget_manager.stop_animation(&id, property.as_deref());
+        }
+
+        ClientCommand::CallNativeOp {
+            request_id,
+            name,
+            args_json,
+        } => {
+            // Plugin ops may block (file or device access); keep them off the UI thread.
+            let event_sender = _event_sender.clone();
+            let spawned = std::thread::Builder::new()
+                .name("native-op".to_string())
+                .spawn(move || {
+                    let (result, error) = match crate::plugins::call_op(&name, &args_json) {
+                        Ok(result) => (Some(result), None),
+                        Err(error) => (None, Some(error)),
+                    };
+                    let _ = event_sender.send(crate::ipc::UiEvent::NativeOpResult {
+                        request_id,
+                        result,
+                        error,
+                    });
+                });
+            if let Err(e) = spawned {
+                report_runtime_error(
+                    _event_sender,
+                    command,
+                    ErrorCode::Internal,
+                    None,
+                    format!("Failed to spawn native op: {e}"),
+                );
+            }
+        }
+
+        ClientCommand::ResetTree => {
+            println!("[UI] Client runtime restarted; clearing widget tree");
+            clear_root(render_root, widget_manager);
+            // The old client's frame subscription died with it.
+            edit_frame_clock(render_root, |clock| FrameClock::set_running(clock, false));
+        }
+
+        ClientCommand::Handshake(status) => match status {
+            HandshakeStatus::Waiting { remaining_secs } => splash::set_status(
+                render_root,
+                format!("Waiting for the app to connect… {remaining_secs}s"),
+                false,
+            ),
+            HandshakeStatus::TimedOut { timeout_secs } => splash::set_status(
+                render_root,
+                format!("The app did not connect within {timeout_secs}s"),
+                true,
+            ),
+            HandshakeStatus::Connected => splash::set_status(render_root, String::new(), false),
+        },
+
         ClientCommand::SetImageData { id, data } => {
             if let Some(info) = widget_manager.widgets.get(&id) {
                 if matches!(info.kind, WidgetKind::Image) {
                     let widget_id = info.widget_id;
                     super::widgets::image::update_data(render_root, widget_id, &data);
+                } else if matches!(info.kind, WidgetKind::Avatar) {
+                    // Empty data clears the image and shows the initials again.
+                    let image = if data.is_empty() {
+                        None
+                    } else {
+                        super::widgets::image::decode_image_bytes(&data)
+                    };
+                    render_root.edit_widget(info.widget_id, |mut widget| {
+                        let mut avatar = widget.downcast::<AvatarWidget>();
+                        AvatarWidget::set_image(&mut avatar, image);
+                    });
                 } else {
                     report_runtime_error(
                         _event_sender,
-                        "ui-handler",
+                        command,
+                        ErrorCode::UnsupportedOperation,
+                        Some(&id),
                         format!(
                             "SetImageData on {:?} is not supported for widget '{id}'",
                             info.kind
                         ),
-                        false,
                     );
                 }
             } else {
                 eprintln!("[UI] Widget '{}' not found for SetImageData", id);
                 report_runtime_error(
                     _event_sender,
-                    "ui-handler",
+                    command,
+                    ErrorCode::WidgetNotFound,
+                    Some(&id),
                     format!("Widget '{id}' not found for SetImageData"),
-                    false,
                 );
             }
         }
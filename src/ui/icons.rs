@@ -0,0 +1,179 @@
+use std::collections::HashMap;
+
+/// Pack used when an icon name has no `pack:` prefix.
+pub const DEFAULT_PACK: &str = "lucide";
+
+/// Outer `<svg>` element shared by every Lucide icon (24x24, 2px round strokes).
+const LUCIDE_HEADER: &str = r#"<svg xmlns="http://www.w3.org/2000/svg" width="24" height="24" viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2" stroke-linecap="round" stroke-linejoin="round">"#;
+
+/// Wrapper for bodies registered from JS without their own `<svg>` element.
+const PLAIN_HEADER: &str = r#"<svg xmlns="http://www.w3.org/2000/svg" width="24" height="24" viewBox="0 0 24 24" fill="currentColor">"#;
+
+/// Bundled subset of Lucide (ISC license), stored as the inner SVG elements.
+const LUCIDE: &[(&str, &str)] = &[
+    (
+        "alert-circle",
+        r#"<circle cx="12" cy="12" r="10"/><line x1="12" x2="12" y1="8" y2="12"/><line x1="12" x2="12.01" y1="16" y2="16"/>"#,
+    ),
+    (
+        "arrow-left",
+        r#"<path d="m12 19-7-7 7-7"/><path d="M19 12H5"/>"#,
+    ),
+    (
+        "arrow-right",
+        r#"<path d="M5 12h14"/><path d="m12 5 7 7-7 7"/>"#,
+    ),
+    ("check", r#"<path d="M20 6 9 17l-5-5"/>"#),
+    ("chevron-down", r#"<path d="m6 9 6 6 6-6"/>"#),
+    ("chevron-left", r#"<path d="m15 18-6-6 6-6"/>"#),
+    ("chevron-right", r#"<path d="m9 18 6-6-6-6"/>"#),
+    ("chevron-up", r#"<path d="m18 15-6-6-6 6"/>"#),
+    ("circle", r#"<circle cx="12" cy="12" r="10"/>"#),
+    (
+        "copy",
+        r#"<rect width="14" height="14" x="8" y="8" rx="2" ry="2"/><path d="M4 16c-1.1 0-2-.9-2-2V4c0-1.1.9-2 2-2h10c1.1 0 2 .9 2 2"/>"#,
+    ),
+    (
+        "download",
+        r#"<path d="M21 15v4a2 2 0 0 1-2 2H5a2 2 0 0 1-2-2v-4"/><polyline points="7 10 12 15 17 10"/><line x1="12" x2="12" y1="15" y2="3"/>"#,
+    ),
+    (
+        "external-link",
+        r#"<path d="M15 3h6v6"/><path d="M10 14 21 3"/><path d="M18 13v6a2 2 0 0 1-2 2H5a2 2 0 0 1-2-2V8a2 2 0 0 1 2-2h6"/>"#,
+    ),
+    (
+        "eye",
+        r#"<path d="M2 12s3-7 10-7 10 7 10 7-3 7-10 7-10-7-10-7Z"/><circle cx="12" cy="12" r="3"/>"#,
+    ),
+    (
+        "heart",
+        r#"<path d="M19 14c1.49-1.46 3-3.21 3-5.5A5.5 5.5 0 0 0 16.5 3c-1.76 0-3 .5-4.5 2-1.5-1.5-2.74-2-4.5-2A5.5 5.5 0 0 0 2 8.5c0 2.3 1.5 4.05 3 5.5l7 7Z"/>"#,
+    ),
+    (
+        "home",
+        r#"<path d="m3 9 9-7 9 7v11a2 2 0 0 1-2 2H5a2 2 0 0 1-2-2z"/><polyline points="9 22 9 12 15 12 15 22"/>"#,
+    ),
+    (
+        "info",
+        r#"<circle cx="12" cy="12" r="10"/><path d="M12 16v-4"/><path d="M12 8h.01"/>"#,
+    ),
+    (
+        "menu",
+        r#"<line x1="4" x2="20" y1="12" y2="12"/><line x1="4" x2="20" y1="6" y2="6"/><line x1="4" x2="20" y1="18" y2="18"/>"#,
+    ),
+    ("minus", r#"<path d="M5 12h14"/>"#),
+    ("plus", r#"<path d="M5 12h14"/><path d="M12 5v14"/>"#),
+    (
+        "refresh-cw",
+        r#"<path d="M3 12a9 9 0 0 1 9-9 9.75 9.75 0 0 1 6.74 2.74L21 8"/><path d="M21 3v5h-5"/><path d="M21 12a9 9 0 0 1-9 9 9.75 9.75 0 0 1-6.74-2.74L3 16"/><path d="M8 16H3v5"/>"#,
+    ),
+    (
+        "search",
+        r#"<circle cx="11" cy="11" r="8"/><path d="m21 21-4.3-4.3"/>"#,
+    ),
+    (
+        "star",
+        r#"<polygon points="12 2 15.09 8.26 22 9.27 17 14.14 18.18 21.02 12 17.77 5.82 21.02 7 14.14 2 9.27 8.91 8.26 12 2"/>"#,
+    ),
+    (
+        "trash",
+        r#"<path d="M3 6h18"/><path d="M19 6v14c0 1-1 2-2 2H7c-1 0-2-1-2-2V6"/><path d="M8 6V4c0-1 1-2 2-2h4c1 0 2 1 2 2v2"/>"#,
+    ),
+    (
+        "upload",
+        r#"<path d="M21 15v4a2 2 0 0 1-2 2H5a2 2 0 0 1-2-2v-4"/><polyline points="17 8 12 3 7 8"/><line x1="12" x2="12" y1="3" y2="15"/>"#,
+    ),
+    (
+        "user",
+        r#"<path d="M19 21v-2a4 4 0 0 0-4-4H9a4 4 0 0 0-4 4v2"/><circle cx="12" cy="7" r="4"/>"#,
+    ),
+    ("x", r#"<path d="M18 6 6 18"/><path d="m6 6 12 12"/>"#),
+];
+
+/// Resolves `pack:name` icon references to SVG markup.
+///
+/// The bundled Lucide subset is always available; apps can register further
+/// packs (or override bundled icons) once via `RegisterIconPack`, after which
+/// each Icon widget only sends its name over IPC.
+#[derive(Debug, Default)]
+pub struct IconRegistry {
+    /// pack prefix → icon name → full SVG markup
+    packs: HashMap<String, HashMap<String, String>>,
+}
+
+impl IconRegistry {
+    /// Add icons to `prefix`, merging with any icons already registered there.
+    /// Entries without an `<svg>` root are wrapped in a 24x24 `currentColor` element.
+    pub fn register_pack(&mut self, prefix: &str, icons: HashMap<String, String>) {
+        let pack = self.packs.entry(prefix.to_string()).or_default();
+        for (name, markup) in icons {
+            let markup = if markup.trim_start().starts_with("<svg") {
+                markup
+            } else {
+                format!("{PLAIN_HEADER}{markup}</svg>")
+            };
+            pack.insert(name, markup);
+        }
+    }
+
+    /// Look up `pack:name` (or a bare `name` in the default pack).
+    pub fn resolve(&self, reference: &str) -> Option<String> {
+        let (prefix, name) = reference
+            .trim()
+            .split_once(':')
+            .unwrap_or((DEFAULT_PACK, reference.trim()));
+
+        if let Some(markup) = self.packs.get(prefix).and_then(|pack| pack.get(name)) {
+            return Some(markup.clone());
+        }
+        if prefix == DEFAULT_PACK {
+            return LUCIDE
+                .iter()
+                .find(|(icon, _)| *icon == name)
+                .map(|(_, body)| format!("{LUCIDE_HEADER}{body}</svg>"));
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_bundled_icon() {
+        let registry = IconRegistry::default();
+        let check = registry.resolve("lucide:check").unwrap();
+        assert!(check.starts_with("<svg"));
+        assert!(check.contains("M20 6 9 17l-5-5"));
+        assert_eq!(registry.resolve("check"), Some(check));
+        assert!(registry.resolve("lucide:nope").is_none());
+        assert!(registry.resolve("mdi:check").is_none());
+    }
+
+    #[test]
+    fn test_registered_pack_wraps_bodies_and_overrides() {
+        let mut registry = IconRegistry::default();
+        registry.register_pack(
+            "app",
+            HashMap::from([(
+                "dot".to_string(),
+                "<circle cx=\"12\" cy=\"12\" r=\"4\"/>".to_string(),
+            )]),
+        );
+        registry.register_pack(
+            "lucide",
+            HashMap::from([(
+                "check".to_string(),
+                "<svg viewBox=\"0 0 8 8\"/>".to_string(),
+            )]),
+        );
+
+        let dot = registry.resolve("app:dot").unwrap();
+        assert!(dot.starts_with("<svg") && dot.ends_with("</svg>"));
+        assert_eq!(
+            registry.resolve("check").unwrap(),
+            "<svg viewBox=\"0 0 8 8\"/>"
+        );
+    }
+}
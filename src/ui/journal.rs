@@ -0,0 +1,314 @@
+// Undo Journal
+// Opt-in (`SetJournal`) record of the tree mutations the client sends:
+// creating and removing widgets, text and style changes. Each is stored with
+// the commands that reverse it, worked out from the widget records before it
+// is applied, so `Undo`/`Redo` step through them on the UI thread without the
+// client rebuilding the tree from its own state.
+//
+// Re-created widgets are appended to their parent, so undoing a removal also
+// removes and re-creates the siblings after it to restore their order; those
+// come back as declared, without runtime state such as scroll offsets. A
+// style key the widget did not have before cannot be unset, so undoing it
+// keeps the newer value.
+
+use std::collections::VecDeque;
+
+use crate::ipc::{BoxStyle, ClientCommand};
+use crate::ui::snapshot;
+use crate::ui::widget_manager::{WidgetManager, is_root_key};
+
+/// Undo steps kept when `SetJournal` gives no limit.
+const DEFAULT_LIMIT: usize = 100;
+
+/// One journaled command and the commands that reverse it.
+#[derive(Debug, Clone)]
+struct Step {
+    forward: ClientCommand,
+    inverse: Vec<ClientCommand>,
+}
+
+/// Undo and redo stacks of steps; a group is undone and redone as one entry.
+#[derive(Debug)]
+pub struct Journal {
+    enabled: bool,
+    limit: usize,
+    undo: VecDeque<Vec<Step>>,
+    redo: Vec<Vec<Step>>,
+    /// Steps recorded since the outermost `BeginUndoGroup`
+    group: Option<Vec<Step>>,
+    group_depth: usize,
+    /// Nonzero while commands derived from others (bindings, animations,
+    /// replays) run; they are not journaled
+    paused: usize,
+    /// (can undo, can redo) last reported to JS
+    reported: (bool, bool),
+}
+
+impl Default for Journal {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            limit: DEFAULT_LIMIT,
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+            group: None,
+            group_depth: 0,
+            paused: 0,
+            reported: (false, false),
+        }
+    }
+}
+
+impl Journal {
+    /// Turn journaling on or off; turning it off drops both stacks.
+    pub fn set_enabled(&mut self, enabled: bool, limit: Option<usize>) {
+        self.enabled = enabled;
+        self.limit = limit.unwrap_or(DEFAULT_LIMIT).max(1);
+        if enabled {
+            while self.undo.len() > self.limit {
+                self.undo.pop_front();
+            }
+        } else {
+            self.clear();
+        }
+    }
+
+    /// Whether a command handled now would be journaled.
+    pub fn is_recording(&self) -> bool {
+        self.enabled && self.paused == 0
+    }
+
+    pub fn pause(&mut self) {
+        self.paused += 1;
+    }
+
+    pub fn resume(&mut self) {
+        self.paused = self.paused.saturating_sub(1);
+    }
+
+    /// Drop every step, e.g. when the tree is cleared. The settings are kept.
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.group = None;
+        self.group_depth = 0;
+    }
+
+    /// Record `forward`, undone by running `inverse` in order. A new step
+    /// makes the redo stack unreachable.
+    pub fn push(&mut self, forward: ClientCommand, inverse: Vec<ClientCommand>) {
+        self.redo.clear();
+        let step = Step { forward, inverse };
+        match &mut self.group {
+            Some(group) => group.push(step),
+            None => self.commit(vec![step]),
+        }
+    }
+
+    pub fn begin_group(&mut self) {
+        self.group_depth += 1;
+        self.group.get_or_insert_with(Vec::new);
+    }
+
+    /// Close the innermost group; closing the outermost makes its steps one
+    /// undo entry.
+    pub fn end_group(&mut self) {
+        self.group_depth = self.group_depth.saturating_sub(1);
+        if self.group_depth == 0 {
+            self.close_group();
+        }
+    }
+
+    fn close_group(&mut self) {
+        self.group_depth = 0;
+        if let Some(group) = self.group.take()
+            && !group.is_empty()
+        {
+            self.commit(group);
+        }
+    }
+
+    fn commit(&mut self, entry: Vec<Step>) {
+        self.undo.push_back(entry);
+        while self.undo.len() > self.limit {
+            self.undo.pop_front();
+        }
+    }
+
+    /// The commands that undo the latest entry, moving it to the redo stack.
+    /// An open group is closed first.
+    pub fn undo(&mut self) -> Option<Vec<ClientCommand>> {
+        self.close_group();
+        let entry = self.undo.pop_back()?;
+        let commands = entry
+            .iter()
+            .rev()
+            .flat_map(|step| step.inverse.iter().cloned())
+            .collect();
+        self.redo.push(entry);
+        Some(commands)
+    }
+
+    /// The commands that redo the last undone entry, moving it back.
+    pub fn redo(&mut self) -> Option<Vec<ClientCommand>> {
+        self.close_group();
+        let entry = self.redo.pop()?;
+        let commands = entry.iter().map(|step| step.forward.clone()).collect();
+        self.commit(entry);
+        Some(commands)
+    }
+
+    /// (can undo, can redo) if it changed since the last call.
+    pub fn take_change(&mut self) -> Option<(bool, bool)> {
+        let state = (!self.undo.is_empty(), !self.redo.is_empty());
+        (state != self.reported).then(|| {
+            self.reported = state;
+            state
+        })
+    }
+}
+
+/// The commands that reverse `cmd` on the tree as it is now, or None if `cmd`
+/// is not journaled or would not apply.
+pub fn inverse_of(
+    cmd: &ClientCommand,
+    widget_manager: &WidgetManager,
+) -> Option<Vec<ClientCommand>> {
+    match cmd {
+        ClientCommand::CreateWidget { id, parent_id, .. } => {
+            let parent_known = parent_id.as_deref().is_none_or(|parent| {
+                is_root_key(parent) || widget_manager.widgets.contains_key(parent)
+            });
+            (parent_known && !widget_manager.widgets.contains_key(id))
+                .then(|| vec![ClientCommand::RemoveWidget { id: id.clone() }])
+        }
+        ClientCommand::RemoveWidget { id } => {
+            let info = widget_manager.widgets.get(id)?;
+            widget_manager.records.get(id)?;
+            let siblings = widget_manager
+                .parent_to_children
+                .get(info.parent_id.as_deref().unwrap_or("__root__"))?;
+            let later = &siblings[siblings.iter().position(|child| child == id)? + 1..];
+            let mut inverse: Vec<ClientCommand> = later
+                .iter()
+                .map(|sibling| ClientCommand::RemoveWidget {
+                    id: sibling.clone(),
+                })
+                .collect();
+            for widget in std::iter::once(id).chain(later) {
+                inverse.extend(snapshot::subtree_commands(widget_manager, widget));
+            }
+            Some(inverse)
+        }
+        ClientCommand::SetWidgetText { id, .. } => {
+            let record = widget_manager.records.get(id)?;
+            Some(vec![ClientCommand::SetWidgetText {
+                id: id.clone(),
+                text: record.text.clone().unwrap_or_default(),
+            }])
+        }
+        ClientCommand::SetWidgetStyle { id, style } => {
+            let before = if is_root_key(id) {
+                widget_manager.root_styles.get(id)
+            } else {
+                widget_manager.records.get(id)?.style.as_ref()
+            };
+            Some(vec![ClientCommand::SetWidgetStyle {
+                id: id.clone(),
+                style: inverse_style(before, style)?,
+            }])
+        }
+        _ => None,
+    }
+}
+
+/// The values `before` had for the keys `update` sets; keys it did not have
+/// are left out.
+fn inverse_style(before: Option<&BoxStyle>, update: &BoxStyle) -> Option<BoxStyle> {
+    let before = match before {
+        Some(style) => serde_json::to_value(style).ok()?,
+        None => serde_json::Value::Null,
+    };
+    let serde_json::Value::Object(changes) = serde_json::to_value(update).ok()? else {
+        return None;
+    };
+    let mut inverse = serde_json::Map::new();
+    for (key, value) in changes {
+        if value.is_null() {
+            continue;
+        }
+        if let Some(old) = before.get(&key).filter(|old| !old.is_null()) {
+            inverse.insert(key, old.clone());
+        }
+    }
+    serde_json::from_value(serde_json::Value::Object(inverse)).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(id: &str, text: &str) -> ClientCommand {
+        ClientCommand::SetWidgetText {
+            id: id.to_string(),
+            text: text.to_string(),
+        }
+    }
+
+    fn texts(commands: &[ClientCommand]) -> Vec<&str> {
+        commands
+            .iter()
+            .map(|command| match command {
+                ClientCommand::SetWidgetText { text, .. } => text.as_str(),
+                _ => "?",
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_groups_undo_as_one_entry_in_reverse() {
+        let mut journal = Journal::default();
+        journal.set_enabled(true, None);
+        journal.begin_group();
+        journal.push(text("a", "1"), vec![text("a", "0")]);
+        journal.begin_group();
+        journal.push(text("a", "2"), vec![text("a", "1")]);
+        journal.end_group();
+        assert_eq!(journal.take_change(), None);
+        journal.end_group();
+        assert_eq!(journal.take_change(), Some((true, false)));
+
+        assert_eq!(texts(&journal.undo().unwrap()), ["1", "0"]);
+        assert_eq!(journal.take_change(), Some((false, true)));
+        assert!(journal.undo().is_none());
+        assert_eq!(texts(&journal.redo().unwrap()), ["1", "2"]);
+
+        // A new step after an undo drops what could have been redone.
+        journal.undo();
+        journal.push(text("a", "3"), vec![text("a", "0")]);
+        assert!(journal.redo().is_none());
+    }
+
+    #[test]
+    fn test_limit_drops_the_oldest_steps() {
+        let mut journal = Journal::default();
+        journal.set_enabled(true, Some(2));
+        for value in ["1", "2", "3"] {
+            journal.push(text("a", value), vec![text("a", value)]);
+        }
+        assert_eq!(texts(&journal.undo().unwrap()), ["3"]);
+        assert_eq!(texts(&journal.undo().unwrap()), ["2"]);
+        assert!(journal.undo().is_none());
+    }
+
+    #[test]
+    fn test_inverse_style_restores_changed_keys() {
+        let before: BoxStyle =
+            serde_json::from_str(r##"{"color": "#ff0000", "fontSize": 14}"##).unwrap();
+        let update: BoxStyle = serde_json::from_str(r#"{"fontSize": 20, "padding": 4}"#).unwrap();
+        let inverse = inverse_style(Some(&before), &update).unwrap();
+        assert_eq!(inverse.font_size, Some(14.0));
+        assert!(inverse.color.is_none());
+        assert!(inverse.padding.is_none());
+    }
+}
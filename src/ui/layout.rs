@@ -1,9 +1,48 @@
-use masonry::properties::types::CrossAxisAlignment;
-use masonry::widgets::Flex;
+use masonry::core::{NewWidget, PropertySet, WidgetOptions};
+use masonry::properties::Dimensions;
+use masonry::properties::types::{CrossAxisAlignment, MainAxisAlignment};
+use masonry::widgets::{ChildAlignment, Flex, ZStack};
+
+use super::widget_manager::{LAYERS, ROOT_FLEX_TAG, ROOT_STACK_TAG};
+use super::widgets::layer_impl::Layer;
+use super::widgets::splash::{self, Splash};
 
 /// Create the initial widget tree for the application.
-/// This is an empty root Flex column tagged with ROOT_FLEX_TAG.
-/// It fills the entire window, letting JS-created children control their own layout.
-pub fn create_initial_ui() -> Flex {
-    Flex::column().cross_axis_alignment(CrossAxisAlignment::Stretch)
+///
+/// A ZStack of named layers, bottom to top: the content layer (`__root__`, an
+/// empty Flex column tagged with ROOT_FLEX_TAG) and then the overlay, toast
+/// and debug layers from `LAYERS`. Everything fills the window, so popups
+/// added to `__overlay__` always draw above the content. The splash layer
+/// sits on top of them all, showing `splash` (or whatever `SetSplash` asks
+/// for) until the first widget is mounted.
+pub fn create_initial_ui(splash: Option<Splash>) -> NewWidget<ZStack> {
+    let content = Flex::column().cross_axis_alignment(CrossAxisAlignment::Stretch);
+    let mut stack = ZStack::new().with(
+        NewWidget::new_with(
+            content,
+            Some(ROOT_FLEX_TAG),
+            WidgetOptions::default(),
+            PropertySet::new().with(Dimensions::STRETCH),
+        ),
+        ChildAlignment::ParentAligned,
+    );
+    for layer in &LAYERS {
+        let (main, cross) = default_alignment(layer.id);
+        stack = stack.with(
+            NewWidget::new_with_tag(Layer::new(main, cross), layer.tag),
+            ChildAlignment::ParentAligned,
+        );
+    }
+    stack = stack.with(splash::new_layer(splash), ChildAlignment::ParentAligned);
+    NewWidget::new_with_tag(stack, ROOT_STACK_TAG)
+}
+
+/// Where a layer places its children until `SetWidgetStyle` says otherwise:
+/// overlays centered like dialogs, toasts along the bottom edge.
+fn default_alignment(layer_id: &str) -> (MainAxisAlignment, CrossAxisAlignment) {
+    match layer_id {
+        "__overlay__" => (MainAxisAlignment::Center, CrossAxisAlignment::Center),
+        "__toast__" => (MainAxisAlignment::End, CrossAxisAlignment::Center),
+        _ => (MainAxisAlignment::Start, CrossAxisAlignment::Start),
+    }
 }
@@ -1,24 +1,40 @@
 // UI Thread Module
 // Handles the main window, widget tree, and rendering using masonry_winit
 
+pub mod accelerators;
+pub mod animations;
+pub mod bindings;
 pub mod creation;
 pub mod driver;
+pub mod fonts;
 pub mod global_state;
+pub mod gpu_renderer;
 pub mod handler;
+pub mod icons;
+pub mod journal;
 pub mod layout;
+pub mod print;
+pub mod raw_properties;
+pub mod snapshot;
 pub mod styles;
+pub mod theme;
+pub mod widget_factory;
 pub mod widget_manager;
 pub mod widgets;
 
+use std::path::Path;
+
 use masonry::core::NewWidget;
 use masonry::dpi::LogicalSize;
-use masonry::theme::default_property_set;
+use masonry::peniko::Color;
 use masonry_winit::app::{EventLoopProxy, NewWindow, WindowId};
-use masonry_winit::winit::window::Window;
+use masonry_winit::winit::window::{Fullscreen, Icon, Window, WindowButtons};
 
 use self::driver::VellumDriver;
 use self::layout::create_initial_ui;
-use self::widget_manager::ROOT_FLEX_TAG;
+use self::widgets::frame_clock_impl::{FRAME_CLOCK_TAG, FrameClock};
+use self::widgets::splash::Splash;
+use crate::cli::WindowOptions;
 use crate::ipc::UiEventSender;
 
 /// Holds the information needed to set up IPC before the event loop blocks.
@@ -47,42 +63,118 @@ pub fn prepare_ui() -> (UiSetup, masonry_winit::app::EventLoop) {
     (setup, event_loop)
 }
 
+/// Decode a PNG/ICO/etc. file into a window icon.
+fn load_window_icon(path: &Path) -> Option<Icon> {
+    let decoded = image::open(path)
+        .map_err(|e| eprintln!("[UI] Failed to load window icon {}: {e}", path.display()))
+        .ok()?
+        .to_rgba8();
+    let (width, height) = decoded.dimensions();
+    Icon::from_rgba(decoded.into_raw(), width, height)
+        .map_err(|e| eprintln!("[UI] Invalid window icon {}: {e}", path.display()))
+        .ok()
+}
+
 /// Run the UI application on the main thread (blocks forever).
 /// Must be called after the JS thread has been spawned with the EventLoopProxy.
 pub fn run_ui_blocking(
     event_loop: masonry_winit::app::EventLoop,
     window_id: WindowId,
     event_sender: UiEventSender,
+    options: &WindowOptions,
 ) {
-    let window_size = LogicalSize::new(800.0, 600.0);
+    let window_size = LogicalSize::new(
+        options.width.unwrap_or(800.0),
+        options.height.unwrap_or(600.0),
+    );
+    let (min_width, min_height) = options.min_size.unwrap_or((400.0, 300.0));
 
-    let window_attributes = Window::default_attributes()
-        .with_title("Vellum - JavaScript Desktop Runtime")
-        .with_resizable(true)
-        .with_min_inner_size(LogicalSize::new(400.0, 300.0))
+    let title = options
+        .title
+        .as_deref()
+        .unwrap_or("Vellum - JavaScript Desktop Runtime");
+    let mut window_attributes = Window::default_attributes()
+        .with_title(title)
+        .with_resizable(options.resizable.unwrap_or(true))
+        .with_min_inner_size(LogicalSize::new(min_width, min_height))
         .with_inner_size(window_size);
+    let kiosk = options.kiosk.unwrap_or(false);
+    if kiosk || options.fullscreen.unwrap_or(false) {
+        window_attributes = window_attributes.with_fullscreen(Some(Fullscreen::Borderless(None)));
+    }
+    global_state::set_kiosk(kiosk);
+    if kiosk {
+        window_attributes = window_attributes
+            .with_decorations(false)
+            .with_resizable(false)
+            .with_enabled_buttons(WindowButtons::empty());
+        // Runs on the UI thread, which Windows' execution state is tied to.
+        crate::power::keep_display_on("Kiosk mode");
+    }
+    if let Some(icon) = options.icon.as_deref().and_then(load_window_icon) {
+        window_attributes = window_attributes.with_window_icon(Some(icon));
+    }
+    let transparent = options.transparent.unwrap_or(false);
+    global_state::set_window_transparent(transparent);
+    if transparent {
+        window_attributes = window_attributes.with_transparent(true);
+    }
 
     let error_sender = event_sender.clone();
+    let clock_sender = event_sender.clone();
     let driver = VellumDriver::new(event_sender);
-    let main_widget = create_initial_ui();
+    // Paint the configured background from the first frame, before the
+    // client has connected and styled `__root__`.
+    let background = options
+        .background
+        .as_ref()
+        .map(|color| styles::color_value_to_peniko(color).with_alpha(1.0))
+        .unwrap_or(Color::BLACK);
+    global_state::set_window_background(background);
+    let splash = options.splash.unwrap_or(false).then(|| Splash {
+        message: options
+            .title
+            .clone()
+            .unwrap_or_else(|| "Loading…".to_string()),
+        ..Splash::default()
+    });
+    let main_widget = create_initial_ui(splash);
 
     masonry_winit::app::run_with(
         event_loop,
-        vec![NewWindow::new_with_id(
-            window_id,
-            window_attributes,
-            NewWidget::new_with_tag(main_widget, ROOT_FLEX_TAG).erased(),
-        )],
+        vec![
+            NewWindow::new_with_id(
+                window_id,
+                window_attributes,
+                NewWidget::new_with_tag(
+                    FrameClock::new(main_widget, clock_sender),
+                    FRAME_CLOCK_TAG,
+                )
+                .erased(),
+            )
+            // A transparent window clears to nothing; the root paints its own
+            // background per `backgroundMode`.
+            .with_base_color(if transparent {
+                Color::TRANSPARENT
+            } else {
+                background
+            }),
+        ],
         driver,
-        default_property_set(),
+        theme::widget_defaults().property_set(),
     )
     .unwrap_or_else(|e| {
         let message = format!("Fatal UI runtime failure: {e}");
         let _ = error_sender.send(crate::ipc::UiEvent::RuntimeError {
             source: "ui-runtime".to_string(),
+            code: crate::ipc::ErrorCode::Internal,
             message: message.clone(),
             fatal: true,
+            widget_id: None,
+            command: None,
         });
-        panic!("{message}");
+        eprintln!("[UI] {message}");
+        crate::crash::report_fatal(&message);
+        global_state::set_exit_code(1);
     });
 }
@@ -0,0 +1,342 @@
+// Printing and PDF Export
+// `ExportPdf` and `PrintWindow` render the window (or one widget) off screen
+// with vello, read the pixels back and write them out as PDF pages. A Portal
+// is paged through its whole content, one viewport per page, instead of being
+// cut off where it stops scrolling.
+//
+// Frames from videos and GpuSurfaces live in textures owned by the window's
+// renderer, so they are left out.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc;
+
+use image::codecs::jpeg::JpegEncoder;
+use image::{DynamicImage, RgbaImage};
+use masonry::app::RenderRoot;
+use masonry::core::{Widget, WidgetId, WidgetRef};
+use masonry::kurbo::{Affine, Rect, Vec2};
+use masonry::peniko::{Color, Fill};
+use masonry::vello::{self, Scene, wgpu};
+
+use crate::ipc::WidgetKind;
+use crate::pdf::{self, PdfPage};
+use crate::ui::global_state::{ClonedWgpu, get_wgpu_context, window_background};
+use crate::ui::widget_manager::{ROOT_STACK_TAG, WidgetManager};
+use crate::ui::widgets::scroll_area_impl::ScrollArea;
+
+/// Pages render at twice their logical size so text stays sharp on paper.
+const PRINT_SCALE: f64 = 2.0;
+/// PDF points per logical pixel (96 pixels to the inch, 72 points).
+const POINTS_PER_PX: f64 = 0.75;
+const JPEG_QUALITY: u8 = 90;
+
+/// One rendered page, at `scale` pixels per logical pixel.
+pub struct PageImage {
+    image: RgbaImage,
+    scale: f64,
+}
+
+fn window_rect(widget: &WidgetRef<'_, dyn Widget>) -> Rect {
+    let ctx = widget.ctx();
+    ctx.window_transform()
+        .transform_rect_bbox(ctx.size().to_rect())
+}
+
+/// Render what widget `id` shows (the whole window when None). A Portal
+/// gives one page per viewport of its content; anything else one page.
+pub fn render_pages(
+    render_root: &mut RenderRoot,
+    widget_manager: &WidgetManager,
+    id: Option<&str>,
+) -> Result<Vec<PageImage>, String> {
+    let wgpu_cx = get_wgpu_context().ok_or("the renderer has not started yet")?;
+    let (widget_id, portal) = match id {
+        Some(id) => {
+            let info = widget_manager
+                .widgets
+                .get(id)
+                .ok_or_else(|| format!("widget '{id}' not found"))?;
+            (info.widget_id, matches!(info.kind, WidgetKind::Portal))
+        }
+        None => {
+            let root = render_root
+                .get_widget_with_tag(ROOT_STACK_TAG)
+                .ok_or("the window has no root widget")?;
+            (root.id(), false)
+        }
+    };
+    let mut renderer = vello::Renderer::new(
+        &wgpu_cx.device,
+        vello::RendererOptions {
+            antialiasing_support: vello::AaSupport::area_only(),
+            ..Default::default()
+        },
+    )
+    .map_err(|e| format!("could not create a renderer: {e}"))?;
+
+    if !portal {
+        let (scene, _) = render_root.redraw();
+        let rect = widget_rect(render_root, widget_id)?;
+        return Ok(vec![rasterize(&wgpu_cx, &mut renderer, &scene, rect)?]);
+    }
+
+    let (start, viewport, content) = {
+        let portal = render_root
+            .get_widget(widget_id)
+            .and_then(|widget| widget.downcast::<ScrollArea>())
+            .ok_or("the portal is not in the tree")?;
+        let area = portal.inner();
+        (area.offset(), area.viewport_height(), area.content_height())
+    };
+    let mut pages = Vec::new();
+    for top in page_tops(viewport, content) {
+        scroll_portal(render_root, widget_id, Vec2::new(start.x, top));
+        let (scene, _) = render_root.redraw();
+        let rect = widget_rect(render_root, widget_id)?;
+        // The last page can't scroll a full viewport; keep only what the
+        // previous page didn't show.
+        let scrolled = top.min((content - viewport).max(0.0));
+        let rect = Rect::new(rect.x0, rect.y0 + top - scrolled, rect.x1, rect.y1);
+        pages.push(rasterize(&wgpu_cx, &mut renderer, &scene, rect)?);
+    }
+    scroll_portal(render_root, widget_id, start);
+    Ok(pages)
+}
+
+/// Scroll offsets of the pages of a `content` tall Portal with a `viewport`
+/// tall window onto it.
+fn page_tops(viewport: f64, content: f64) -> Vec<f64> {
+    if viewport <= 0.0 || content <= viewport {
+        return vec![0.0];
+    }
+    let count = (content / viewport).ceil() as usize;
+    (0..count).map(|page| page as f64 * viewport).collect()
+}
+
+fn scroll_portal(render_root: &mut RenderRoot, widget_id: WidgetId, offset: Vec2) {
+    render_root.edit_widget(widget_id, |mut widget| {
+        ScrollArea::set_offset(&mut widget.downcast::<ScrollArea>(), offset);
+    });
+}
+
+fn widget_rect(render_root: &RenderRoot, widget_id: WidgetId) -> Result<Rect, String> {
+    let widget = render_root
+        .get_widget(widget_id)
+        .ok_or("the widget is not in the tree")?;
+    let rect = window_rect(&widget);
+    if rect.width() < 1.0 || rect.height() < 1.0 {
+        return Err("the widget has no size to print".to_string());
+    }
+    Ok(rect)
+}
+
+/// Draw the `rect` part of `scene` on the window background and read it back.
+fn rasterize(
+    wgpu_cx: &ClonedWgpu,
+    renderer: &mut vello::Renderer,
+    scene: &Scene,
+    rect: Rect,
+) -> Result<PageImage, String> {
+    let device = &wgpu_cx.device;
+    let max = device.limits().max_texture_dimension_2d as f64;
+    let scale = PRINT_SCALE.min(max / rect.width().max(rect.height()));
+    let width = (rect.width() * scale).ceil() as u32;
+    let height = (rect.height() * scale).ceil() as u32;
+
+    let mut page = Scene::new();
+    let transform = Affine::scale(scale) * Affine::translate(-rect.origin().to_vec2());
+    page.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        window_background(),
+        None,
+        &Rect::new(0.0, 0.0, width as f64, height as f64),
+    );
+    page.append(scene, Some(transform));
+
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("Print_Texture"),
+        size: wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::Rgba8Unorm,
+        usage: wgpu::TextureUsages::STORAGE_BINDING | wgpu::TextureUsages::COPY_SRC,
+        view_formats: &[],
+    });
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+    renderer
+        .render_to_texture(
+            device,
+            &wgpu_cx.queue,
+            &page,
+            &view,
+            &vello::RenderParams {
+                base_color: Color::WHITE,
+                width,
+                height,
+                antialiasing_method: vello::AaConfig::Area,
+            },
+        )
+        .map_err(|e| format!("rendering failed: {e}"))?;
+
+    Ok(PageImage {
+        image: read_back(wgpu_cx, &texture)?,
+        scale,
+    })
+}
+
+/// Copy an Rgba8 texture into an image, blocking until the GPU is done.
+fn read_back(wgpu_cx: &ClonedWgpu, texture: &wgpu::Texture) -> Result<RgbaImage, String> {
+    let (width, height) = (texture.width(), texture.height());
+    // Rows are copied at a 256 byte stride.
+    let padded_row = (width * 4).div_ceil(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT)
+        * wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+    let buffer = wgpu_cx.device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Print_Readback"),
+        size: padded_row as u64 * height as u64,
+        usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+    let mut encoder = wgpu_cx
+        .device
+        .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+    encoder.copy_texture_to_buffer(
+        texture.as_image_copy(),
+        wgpu::TexelCopyBufferInfo {
+            buffer: &buffer,
+            layout: wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(padded_row),
+                rows_per_image: None,
+            },
+        },
+        texture.size(),
+    );
+    wgpu_cx.queue.submit([encoder.finish()]);
+
+    let slice = buffer.slice(..);
+    let (sender, receiver) = mpsc::channel();
+    slice.map_async(wgpu::MapMode::Read, move |result| {
+        let _ = sender.send(result);
+    });
+    wgpu_cx
+        .device
+        .poll(wgpu::PollType::Wait)
+        .map_err(|e| format!("waiting for the GPU failed: {e}"))?;
+    receiver
+        .recv()
+        .map_err(|_| "the GPU dropped the readback".to_string())?
+        .map_err(|e| format!("reading the page back failed: {e}"))?;
+
+    let mapped = slice.get_mapped_range();
+    let mut pixels = Vec::with_capacity((width * height * 4) as usize);
+    for row in mapped.chunks(padded_row as usize) {
+        pixels.extend_from_slice(&row[..(width * 4) as usize]);
+    }
+    drop(mapped);
+    buffer.unmap();
+    RgbaImage::from_raw(width, height, pixels).ok_or_else(|| "short readback".to_string())
+}
+
+/// Encode `pages` and write them to `path` as a PDF.
+pub fn write_pdf(pages: Vec<PageImage>, path: &Path) -> Result<(), String> {
+    let pages = pages
+        .into_iter()
+        .map(|page| {
+            let (pixel_width, pixel_height) = page.image.dimensions();
+            let rgb = DynamicImage::ImageRgba8(page.image).to_rgb8();
+            let mut jpeg = Vec::new();
+            JpegEncoder::new_with_quality(&mut jpeg, JPEG_QUALITY)
+                .encode_image(&rgb)
+                .map_err(|e| format!("encoding a page failed: {e}"))?;
+            let points = POINTS_PER_PX / page.scale;
+            Ok(PdfPage {
+                jpeg,
+                pixel_width,
+                pixel_height,
+                width_pt: pixel_width as f64 * points,
+                height_pt: pixel_height as f64 * points,
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    std::fs::write(path, pdf::write(&pages))
+        .map_err(|e| format!("could not write {}: {e}", path.display()))
+}
+
+/// Write `pages` to a temporary PDF and open the platform's print dialog on
+/// it, so the user picks the printer and settings: Preview's print sheet on
+/// macOS, Okular's (or the GNOME print previewer) on Linux, and the default
+/// PDF viewer on Windows, which has no dialog that prints a PDF by itself.
+/// The file is deleted once the dialog is closed.
+pub fn print(pages: Vec<PageImage>) -> Result<(), String> {
+    static NEXT_JOB: AtomicU64 = AtomicU64::new(0);
+    let job = NEXT_JOB.fetch_add(1, Ordering::Relaxed);
+    let path: PathBuf =
+        std::env::temp_dir().join(format!("vellum-print-{}-{job}.pdf", std::process::id()));
+    write_pdf(pages, &path)?;
+    let result = open_print_dialog(&path);
+    // On Windows a viewer that is still open keeps the file locked; the temp
+    // directory gets it then.
+    let _ = std::fs::remove_file(&path);
+    result
+}
+
+/// Run the first dialog tool that exists, waiting until the user is done.
+fn open_print_dialog(path: &Path) -> Result<(), String> {
+    let file = path.display().to_string();
+    let attempts: Vec<(&str, Vec<String>)> = if cfg!(target_os = "macos") {
+        let script = format!(
+            "with timeout of 3600 seconds\n\
+             tell application \"Preview\" to print POSIX file \"{}\" with print dialog\n\
+             end timeout",
+            file.replace('\\', "\\\\").replace('"', "\\\"")
+        );
+        vec![("osascript", vec!["-e".to_string(), script])]
+    } else if cfg!(windows) {
+        let script = format!(
+            "Start-Process -Wait -FilePath '{}'",
+            file.replace('\'', "''")
+        );
+        vec![(
+            "powershell",
+            vec!["-NoProfile".to_string(), "-Command".to_string(), script],
+        )]
+    } else {
+        vec![
+            ("okular", vec!["--print".to_string(), file.clone()]),
+            ("evince-previewer", vec![file.clone()]),
+        ]
+    };
+    let tried: Vec<&str> = attempts.iter().map(|(program, _)| *program).collect();
+    for (program, args) in &attempts {
+        match std::process::Command::new(program).args(args).status() {
+            Ok(status) if status.success() => return Ok(()),
+            Ok(status) => return Err(format!("the print dialog failed ({status})")),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(format!("could not open the print dialog: {e}")),
+        }
+    }
+    Err(format!(
+        "no print dialog is available (tried {})",
+        tried.join(", ")
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_tops_cover_the_content() {
+        assert_eq!(page_tops(100.0, 80.0), vec![0.0]);
+        assert_eq!(page_tops(100.0, 250.0), vec![0.0, 100.0, 200.0]);
+        assert_eq!(page_tops(100.0, 300.0), vec![0.0, 100.0, 200.0]);
+        assert_eq!(page_tops(0.0, 300.0), vec![0.0]);
+    }
+}
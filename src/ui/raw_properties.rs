@@ -0,0 +1,95 @@
+use masonry::core::{Widget, WidgetMut};
+use masonry::peniko::Color;
+use masonry::properties::{
+    ActiveBackground, Background, BorderColor, ContentColor, DisabledBackground,
+    DisabledContentColor, FocusedBorderColor, HoveredBorderColor,
+};
+
+use crate::ipc::ColorValue;
+use crate::ui::styles::color_value_to_peniko;
+
+/// Masonry properties reachable through `SetRawProperty` that `BoxStyle`
+/// doesn't model yet. Names match the masonry property types.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RawProperty {
+    ActiveBackground(Color),
+    DisabledBackground(Color),
+    DisabledContentColor(Color),
+    FocusedBorderColor(Color),
+    HoveredBorderColor(Color),
+}
+
+pub const NAMES: &[&str] = &[
+    "ActiveBackground",
+    "DisabledBackground",
+    "DisabledContentColor",
+    "FocusedBorderColor",
+    "HoveredBorderColor",
+];
+
+/// Parse `json` as the value of the property called `name`.
+pub fn parse(name: &str, json: &str) -> Result<RawProperty, String> {
+    let color = || {
+        serde_json::from_str::<ColorValue>(json)
+            .map(|color| color_value_to_peniko(&color))
+            .map_err(|e| format!("{name} expects a color: {e}"))
+    };
+    match name {
+        "ActiveBackground" => color().map(RawProperty::ActiveBackground),
+        "DisabledBackground" => color().map(RawProperty::DisabledBackground),
+        "DisabledContentColor" => color().map(RawProperty::DisabledContentColor),
+        "FocusedBorderColor" => color().map(RawProperty::FocusedBorderColor),
+        "HoveredBorderColor" => color().map(RawProperty::HoveredBorderColor),
+        _ => Err(format!(
+            "Unknown raw property '{name}'; supported: {}",
+            NAMES.join(", ")
+        )),
+    }
+}
+
+pub fn apply<W: Widget + ?Sized>(widget: &mut WidgetMut<'_, W>, property: RawProperty) {
+    match property {
+        RawProperty::ActiveBackground(color) => {
+            widget.insert_prop(ActiveBackground(Background::Color(color)));
+        }
+        RawProperty::DisabledBackground(color) => {
+            widget.insert_prop(DisabledBackground(Background::Color(color)));
+        }
+        RawProperty::DisabledContentColor(color) => {
+            widget.insert_prop(DisabledContentColor(ContentColor::new(color)));
+        }
+        RawProperty::FocusedBorderColor(color) => {
+            widget.insert_prop(FocusedBorderColor(BorderColor::new(color)));
+        }
+        RawProperty::HoveredBorderColor(color) => {
+            widget.insert_prop(HoveredBorderColor(BorderColor::new(color)));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_color_properties() {
+        assert_eq!(
+            parse("ActiveBackground", r##""#ff0000""##),
+            Ok(RawProperty::ActiveBackground(Color::from_rgba8(
+                255, 0, 0, 255
+            )))
+        );
+        assert!(matches!(
+            parse("DisabledContentColor", r#""gray""#),
+            Ok(RawProperty::DisabledContentColor(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse("ActiveBackground", "12").is_err());
+        assert!(parse("ActiveBackground", "not json").is_err());
+        let err = parse("BoxShadow", "{}").unwrap_err();
+        assert!(err.contains("HoveredBorderColor"));
+    }
+}
@@ -0,0 +1,232 @@
+// Tree Snapshots
+// `SerializeTree` captures the declarative tree as MsgPack: how each JS widget
+// was created (kind, parent, text, style, params, badge) with the text and
+// style updates made since folded in, plus the body and layer styles.
+// `RestoreTree` rebuilds the UI from such a snapshot, so a restarted client or
+// a relaunched app can show its last screen at once and then take it over.
+//
+// Runtime state that is not part of the declaration (scroll offsets, bindings,
+// animations, paint ops) is not captured.
+
+use serde::{Deserialize, Serialize};
+
+use crate::ipc::{BadgeSpec, BoxStyle, ClientCommand, WidgetData, WidgetKind};
+use crate::ui::widget_manager::{LAYERS, WidgetManager};
+
+/// Bumped when the snapshot layout changes; older snapshots are rejected.
+const SNAPSHOT_VERSION: u32 = 1;
+
+/// How a JS widget was declared, kept up to date for `SerializeTree`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WidgetRecord {
+    pub kind: WidgetKind,
+    pub text: Option<String>,
+    pub style: Option<BoxStyle>,
+    pub data: Option<WidgetData>,
+    pub badge: Option<BadgeSpec>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SnapshotWidget {
+    id: String,
+    parent_id: Option<String>,
+    record: WidgetRecord,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TreeSnapshot {
+    version: u32,
+    /// Body and layer styles, by reserved id
+    root_styles: Vec<(String, BoxStyle)>,
+    /// Parents before their children, siblings in order
+    widgets: Vec<SnapshotWidget>,
+}
+
+/// Fold a `SetWidgetStyle` into the style a widget was recorded with: keys set
+/// in `update` win, the rest are kept.
+pub fn merge_style(base: &mut Option<BoxStyle>, update: &BoxStyle) {
+    let Some(current) = base.as_ref() else {
+        *base = Some(update.clone());
+        return;
+    };
+    let (Ok(serde_json::Value::Object(mut merged)), Ok(serde_json::Value::Object(changes))) =
+        (serde_json::to_value(current), serde_json::to_value(update))
+    else {
+        return;
+    };
+    for (key, value) in changes {
+        if !value.is_null() {
+            merged.insert(key, value);
+        }
+    }
+    match serde_json::from_value(serde_json::Value::Object(merged)) {
+        Ok(style) => *base = Some(style),
+        Err(e) => eprintln!("[UI] Could not record a style update: {e}"),
+    }
+}
+
+/// Append `parent`'s children, then theirs, depth first.
+fn collect(widget_manager: &WidgetManager, parent: &str, out: &mut Vec<SnapshotWidget>) {
+    let Some(children) = widget_manager.parent_to_children.get(parent) else {
+        return;
+    };
+    for id in children {
+        let Some(record) = widget_manager.records.get(id) else {
+            continue;
+        };
+        out.push(SnapshotWidget {
+            id: id.clone(),
+            parent_id: (parent != "__root__").then(|| parent.to_string()),
+            record: record.clone(),
+        });
+        collect(widget_manager, id, out);
+    }
+}
+
+/// The `CreateWidget` for one snapshot entry.
+fn create_command(widget: SnapshotWidget) -> ClientCommand {
+    ClientCommand::CreateWidget {
+        id: widget.id,
+        kind: widget.record.kind,
+        parent_id: widget.parent_id,
+        text: widget.record.text,
+        style: widget.record.style,
+        data: widget.record.data,
+        badge: widget.record.badge,
+    }
+}
+
+/// The commands that re-create widget `id` and its descendants as they are
+/// now, appended to its parent. Empty if `id` was not declared by JS.
+pub fn subtree_commands(widget_manager: &WidgetManager, id: &str) -> Vec<ClientCommand> {
+    let (Some(info), Some(record)) = (
+        widget_manager.widgets.get(id),
+        widget_manager.records.get(id),
+    ) else {
+        return Vec::new();
+    };
+    let mut widgets = vec![SnapshotWidget {
+        id: id.to_string(),
+        parent_id: info.parent_id.clone(),
+        record: record.clone(),
+    }];
+    collect(widget_manager, id, &mut widgets);
+    widgets.into_iter().map(create_command).collect()
+}
+
+/// Serialize the current tree.
+pub fn serialize(widget_manager: &WidgetManager) -> Result<Vec<u8>, String> {
+    let mut widgets = Vec::new();
+    for root in std::iter::once("__root__").chain(LAYERS.iter().map(|layer| layer.id)) {
+        collect(widget_manager, root, &mut widgets);
+    }
+    let mut root_styles: Vec<(String, BoxStyle)> = widget_manager
+        .root_styles
+        .iter()
+        .map(|(id, style)| (id.clone(), style.clone()))
+        .collect();
+    root_styles.sort_by(|a, b| a.0.cmp(&b.0));
+    let snapshot = TreeSnapshot {
+        version: SNAPSHOT_VERSION,
+        root_styles,
+        widgets,
+    };
+    rmp_serde::to_vec_named(&snapshot).map_err(|e| format!("Could not serialize the tree: {e}"))
+}
+
+/// The commands that rebuild the tree in `bytes` on an empty one.
+pub fn restore_commands(bytes: &[u8]) -> Result<Vec<ClientCommand>, String> {
+    let snapshot: TreeSnapshot =
+        rmp_serde::from_slice(bytes).map_err(|e| format!("Invalid tree snapshot: {e}"))?;
+    if snapshot.version != SNAPSHOT_VERSION {
+        return Err(format!(
+            "Tree snapshot version {} is not supported (expected {SNAPSHOT_VERSION})",
+            snapshot.version
+        ));
+    }
+    let styles = snapshot
+        .root_styles
+        .into_iter()
+        .map(|(id, style)| ClientCommand::SetWidgetStyle { id, style });
+    let widgets = snapshot.widgets.into_iter().map(create_command);
+    Ok(styles.chain(widgets).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::widget_manager::WidgetInfo;
+    use masonry::core::WidgetId;
+
+    fn style(json: &str) -> BoxStyle {
+        serde_json::from_str(json).unwrap()
+    }
+
+    fn add(manager: &mut WidgetManager, id: &str, parent: Option<&str>, text: Option<&str>) {
+        manager.register_widget(
+            id.to_string(),
+            WidgetInfo {
+                widget_id: WidgetId::next(),
+                kind: WidgetKind::Label,
+                parent_id: parent.map(String::from),
+                child_index: 0,
+            },
+        );
+        manager.records.insert(
+            id.to_string(),
+            WidgetRecord {
+                kind: WidgetKind::Label,
+                text: text.map(String::from),
+                style: Some(style(r##"{"color": "#ff0000", "fontSize": 14}"##)),
+                data: None,
+                badge: None,
+            },
+        );
+    }
+
+    #[test]
+    fn test_merge_style_keeps_unset_keys() {
+        let mut base = Some(style(r##"{"color": "#ff0000", "fontSize": 14}"##));
+        merge_style(&mut base, &style(r#"{"fontSize": 20}"#));
+        let merged = base.unwrap();
+        assert_eq!(merged.font_size, Some(20.0));
+        assert!(merged.color.is_some());
+    }
+
+    #[test]
+    fn test_snapshot_round_trip_puts_parents_first() {
+        let mut manager = WidgetManager::new();
+        add(&mut manager, "list", None, None);
+        add(&mut manager, "item", Some("list"), Some("Hello"));
+        manager.root_styles.insert(
+            "__root__".to_string(),
+            style(r##"{"background": "#1e1e2e"}"##),
+        );
+
+        let bytes = serialize(&manager).unwrap();
+        let commands = restore_commands(&bytes).unwrap();
+        let ids: Vec<&str> = commands
+            .iter()
+            .map(|command| match command {
+                ClientCommand::SetWidgetStyle { id, .. } => id.as_str(),
+                ClientCommand::CreateWidget { id, .. } => id.as_str(),
+                _ => "?",
+            })
+            .collect();
+        assert_eq!(ids, ["__root__", "list", "item"]);
+        let ClientCommand::CreateWidget {
+            parent_id,
+            text,
+            style,
+            ..
+        } = &commands[2]
+        else {
+            panic!("expected a CreateWidget");
+        };
+        assert_eq!(parent_id.as_deref(), Some("list"));
+        assert_eq!(text.as_deref(), Some("Hello"));
+        assert_eq!(style.as_ref().unwrap().font_size, Some(14.0));
+
+        assert!(restore_commands(b"not msgpack").is_err());
+    }
+}
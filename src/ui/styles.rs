@@ -1,17 +1,22 @@
 use masonry::core::{PropertySet, StyleProperty};
 use masonry::layout::{Dim, Length};
+use masonry::parley::Alignment;
 use masonry::parley::style::{
     FontFamily, FontStack, FontStyle, FontWeight, GenericFamily, LineHeight,
 };
 use masonry::peniko::Color;
 use masonry::properties::types::{CrossAxisAlignment, MainAxisAlignment};
 use masonry::properties::{
-    Background, BorderColor, BorderWidth, ContentColor, CornerRadius, Dimensions, Gap,
-    HoveredBorderColor, Padding,
+    Background, BorderColor, BorderWidth, ContentColor, CornerRadius, Dimensions,
+    DisabledBackground, DisabledContentColor, Gap, HoveredBorderColor, Padding,
 };
 use masonry::widgets::Flex;
 
-use crate::ipc::{BoxStyle, ColorValue, CrossAlign, FontStyleValue, MainAlign, PaddingValue};
+use crate::ipc::{
+    BackgroundMode, BoxStyle, ColorValue, CrossAlign, FontStyleValue, MainAlign, PaddingValue,
+    TextAlignValue,
+};
+use crate::ui::global_state::{is_right_to_left, ui_scale, window_background};
 
 // ── Color conversion helper ──
 
@@ -26,6 +31,40 @@ pub fn color_value_to_peniko(cv: &ColorValue) -> Color {
     }
 }
 
+// ── UI scale ──
+
+/// Scale a style length by the interface zoom (`SetUiScale`).
+pub fn scaled(value: f64) -> f64 {
+    value * ui_scale()
+}
+
+/// `Length::px` for a style length, scaled by the interface zoom.
+pub fn px(value: f64) -> Length {
+    Length::px(scaled(value))
+}
+
+/// Font size scaled by the interface zoom.
+pub fn font_size(size: f32) -> StyleProperty {
+    StyleProperty::FontSize(scaled(size as f64) as f32)
+}
+
+/// Copy the size-bearing fields set in `style` into `sizes`, the record that
+/// `SetUiScale` re-applies. The font family is kept too, since re-applying
+/// text styles without it would fall back to sans-serif.
+pub fn remember_sizes(sizes: &mut BoxStyle, style: &BoxStyle) {
+    sizes.font_size = style.font_size.or(sizes.font_size);
+    sizes.font_family = style.font_family.clone().or(sizes.font_family.take());
+    sizes.letter_spacing = style.letter_spacing.or(sizes.letter_spacing);
+    sizes.word_spacing = style.word_spacing.or(sizes.word_spacing);
+    sizes.border_width = style.border_width.or(sizes.border_width);
+    sizes.corner_radius = style.corner_radius.or(sizes.corner_radius);
+    sizes.padding = style.padding.clone().or(sizes.padding.take());
+    sizes.width = style.width.or(sizes.width);
+    sizes.height = style.height.or(sizes.height);
+    sizes.icon_size = style.icon_size.or(sizes.icon_size);
+    sizes.gap = style.gap.or(sizes.gap);
+}
+
 // ── Style application helpers ──
 
 /// Apply text-related StyleProperty items to a builder that supports `with_style`
@@ -33,7 +72,7 @@ pub fn build_text_styles(style: &BoxStyle) -> Vec<StyleProperty> {
     let mut props = Vec::new();
 
     if let Some(size) = style.font_size {
-        props.push(StyleProperty::FontSize(size));
+        props.push(font_size(size));
     }
     if let Some(weight) = style.font_weight {
         props.push(StyleProperty::FontWeight(FontWeight::new(weight)));
@@ -55,13 +94,13 @@ pub fn build_text_styles(style: &BoxStyle) -> Vec<StyleProperty> {
         )));
     }
     if let Some(ls) = style.letter_spacing {
-        props.push(StyleProperty::LetterSpacing(ls));
+        props.push(StyleProperty::LetterSpacing(scaled(ls as f64) as f32));
     }
     if let Some(lh) = style.line_height {
         props.push(StyleProperty::LineHeight(LineHeight::FontSizeRelative(lh)));
     }
     if let Some(ws) = style.word_spacing {
-        props.push(StyleProperty::WordSpacing(ws));
+        props.push(StyleProperty::WordSpacing(scaled(ws as f64) as f32));
     }
     if let Some(true) = style.underline {
         props.push(StyleProperty::Underline(true));
@@ -84,6 +123,16 @@ pub fn build_box_properties(style: &BoxStyle) -> PropertySet {
     if let Some(ref bg) = style.background {
         props = props.with(Background::Color(color_value_to_peniko(bg)));
     }
+    if let Some(ref color) = style.disabled_color {
+        props = props.with(DisabledContentColor(ContentColor::new(
+            color_value_to_peniko(color),
+        )));
+    }
+    if let Some(ref bg) = style.disabled_background {
+        props = props.with(DisabledBackground(Background::Color(
+            color_value_to_peniko(bg),
+        )));
+    }
     if let Some(ref bc) = style.border_color {
         props = props.with(BorderColor::new(color_value_to_peniko(bc)));
     }
@@ -93,45 +142,28 @@ pub fn build_box_properties(style: &BoxStyle) -> PropertySet {
         ))));
     }
     if let Some(bw) = style.border_width {
-        props = props.with(BorderWidth::all(bw));
+        props = props.with(BorderWidth::all(scaled(bw)));
     }
     if let Some(cr) = style.corner_radius {
-        props = props.with(CornerRadius::all(cr));
+        props = props.with(CornerRadius::all(scaled(cr)));
     }
     if let Some(ref pad) = style.padding {
-        match pad {
-            PaddingValue::Uniform(v) => {
-                props = props.with(Padding::all(*v));
-            }
-            PaddingValue::Sides {
-                top,
-                right,
-                bottom,
-                left,
-            } => {
-                props = props.with(Padding {
-                    left: *left,
-                    top: *top,
-                    right: *right,
-                    bottom: *bottom,
-                });
-            }
-        }
+        props = props.with(padding(pad, is_right_to_left()));
     }
 
     if let Some(gap) = style.gap {
-        props = props.with(Gap::new(Length::px(gap)));
+        props = props.with(Gap::new(px(gap)));
     }
 
     match (style.width, style.height) {
         (Some(w), Some(h)) => {
-            props = props.with(Dimensions::fixed(Length::px(w), Length::px(h)));
+            props = props.with(Dimensions::fixed(px(w), px(h)));
         }
         (Some(w), None) => {
-            props = props.with(Dimensions::width(Length::px(w)));
+            props = props.with(Dimensions::width(px(w)));
         }
         (None, Some(h)) => {
-            props = props.with(Dimensions::height(Length::px(h)));
+            props = props.with(Dimensions::height(px(h)));
         }
         (None, None) => {}
     }
@@ -139,6 +171,46 @@ pub fn build_box_properties(style: &BoxStyle) -> PropertySet {
     props
 }
 
+/// Convert a padding value; in right-to-left layouts `left`/`right` mean the
+/// start and end sides, so they are swapped.
+pub fn padding(pad: &PaddingValue, rtl: bool) -> Padding {
+    match pad {
+        PaddingValue::Uniform(v) => Padding::all(scaled(*v)),
+        PaddingValue::Sides {
+            top,
+            right,
+            bottom,
+            left,
+        } => {
+            let (left, right) = if rtl { (right, left) } else { (left, right) };
+            Padding {
+                left: scaled(*left),
+                top: scaled(*top),
+                right: scaled(*right),
+                bottom: scaled(*bottom),
+            }
+        }
+    }
+}
+
+/// Resolve `textAlign` to a physical alignment. Start/end follow the layout
+/// direction, and right-to-left text defaults to right-aligned.
+pub fn text_alignment(align: Option<&TextAlignValue>, rtl: bool) -> Option<Alignment> {
+    let (start, end) = if rtl {
+        (Alignment::Right, Alignment::Left)
+    } else {
+        (Alignment::Left, Alignment::Right)
+    };
+    match align {
+        Some(TextAlignValue::Start) => Some(start),
+        Some(TextAlignValue::End) => Some(end),
+        Some(TextAlignValue::Center) => Some(Alignment::Center),
+        Some(TextAlignValue::Justify) => Some(Alignment::Justify),
+        None if rtl => Some(start),
+        None => None,
+    }
+}
+
 /// Apply box-model style properties to an existing widget via insert_prop.
 /// Works on any WidgetMut that implements HasProperty for the relevant properties.
 pub fn apply_box_props_to_widget(
@@ -151,6 +223,16 @@ pub fn apply_box_props_to_widget(
     if let Some(ref bg) = style.background {
         widget.insert_prop(Background::Color(color_value_to_peniko(bg)));
     }
+    if let Some(ref color) = style.disabled_color {
+        widget.insert_prop(DisabledContentColor(ContentColor::new(
+            color_value_to_peniko(color),
+        )));
+    }
+    if let Some(ref bg) = style.disabled_background {
+        widget.insert_prop(DisabledBackground(Background::Color(
+            color_value_to_peniko(bg),
+        )));
+    }
     if let Some(ref bc) = style.border_color {
         widget.insert_prop(BorderColor::new(color_value_to_peniko(bc)));
     }
@@ -160,78 +242,77 @@ pub fn apply_box_props_to_widget(
         ))));
     }
     if let Some(bw) = style.border_width {
-        widget.insert_prop(BorderWidth::all(bw));
+        widget.insert_prop(BorderWidth::all(scaled(bw)));
     }
     if let Some(cr) = style.corner_radius {
-        widget.insert_prop(CornerRadius::all(cr));
+        widget.insert_prop(CornerRadius::all(scaled(cr)));
     }
     if let Some(ref pad) = style.padding {
-        match pad {
-            PaddingValue::Uniform(v) => {
-                widget.insert_prop(Padding::all(*v));
-            }
-            PaddingValue::Sides {
-                top,
-                right,
-                bottom,
-                left,
-            } => {
-                widget.insert_prop(Padding {
-                    left: *left,
-                    top: *top,
-                    right: *right,
-                    bottom: *bottom,
-                });
-            }
-        }
+        widget.insert_prop(padding(pad, is_right_to_left()));
     }
 
     if let Some(gap) = style.gap {
-        widget.insert_prop(Gap::new(Length::px(gap)));
+        widget.insert_prop(Gap::new(px(gap)));
     }
 
     match (style.width, style.height) {
         (Some(w), Some(h)) => {
-            widget.insert_prop(Dimensions::fixed(Length::px(w), Length::px(h)));
+            widget.insert_prop(Dimensions::fixed(px(w), px(h)));
         }
         (Some(w), None) => {
-            widget.insert_prop(Dimensions::width(Length::px(w)));
+            widget.insert_prop(Dimensions::width(px(w)));
         }
         (None, Some(h)) => {
-            widget.insert_prop(Dimensions::height(Length::px(h)));
+            widget.insert_prop(Dimensions::height(px(h)));
         }
         (None, None) => {}
     }
 }
 
+/// Map a main-axis alignment; `mirrored` rows (right-to-left, children stored
+/// in reverse) swap Start and End so content still packs toward the reading start.
+pub fn main_axis_alignment(align: &MainAlign, mirrored: bool) -> MainAxisAlignment {
+    match align {
+        MainAlign::Start if mirrored => MainAxisAlignment::End,
+        MainAlign::End if mirrored => MainAxisAlignment::Start,
+        MainAlign::Start => MainAxisAlignment::Start,
+        MainAlign::Center => MainAxisAlignment::Center,
+        MainAlign::End => MainAxisAlignment::End,
+        MainAlign::SpaceBetween => MainAxisAlignment::SpaceBetween,
+        MainAlign::SpaceAround => MainAxisAlignment::SpaceAround,
+        MainAlign::SpaceEvenly => MainAxisAlignment::SpaceEvenly,
+    }
+}
+
+/// Map a cross-axis alignment; right-to-left columns swap Start and End.
+pub fn cross_axis_alignment(align: &CrossAlign, mirrored: bool) -> CrossAxisAlignment {
+    match align {
+        CrossAlign::Start if mirrored => CrossAxisAlignment::End,
+        CrossAlign::End if mirrored => CrossAxisAlignment::Start,
+        CrossAlign::Start => CrossAxisAlignment::Start,
+        CrossAlign::Center => CrossAxisAlignment::Center,
+        CrossAlign::End => CrossAxisAlignment::End,
+        CrossAlign::Fill => CrossAxisAlignment::Stretch,
+        CrossAlign::Baseline => CrossAxisAlignment::Start,
+    }
+}
+
 /// Apply style to a Flex widget (root or otherwise). Handles box props + flex-specific props.
-pub fn apply_flex_style(flex: &mut masonry::core::WidgetMut<'_, Flex>, style: &BoxStyle) {
+/// `mirror_main` / `mirror_cross` flip start and end on the horizontal axis
+/// of right-to-left rows / columns.
+pub fn apply_flex_style(
+    flex: &mut masonry::core::WidgetMut<'_, Flex>,
+    style: &BoxStyle,
+    mirror_main: bool,
+    mirror_cross: bool,
+) {
     apply_box_props_to_widget(flex, style);
 
     if let Some(ref ca) = style.cross_axis_alignment {
-        Flex::set_cross_axis_alignment(
-            flex,
-            match ca {
-                CrossAlign::Start => CrossAxisAlignment::Start,
-                CrossAlign::Center => CrossAxisAlignment::Center,
-                CrossAlign::End => CrossAxisAlignment::End,
-                CrossAlign::Fill => CrossAxisAlignment::Stretch,
-                CrossAlign::Baseline => CrossAxisAlignment::Start,
-            },
-        );
+        Flex::set_cross_axis_alignment(flex, cross_axis_alignment(ca, mirror_cross));
     }
     if let Some(ref ma) = style.main_axis_alignment {
-        Flex::set_main_axis_alignment(
-            flex,
-            match ma {
-                MainAlign::Start => MainAxisAlignment::Start,
-                MainAlign::Center => MainAxisAlignment::Center,
-                MainAlign::End => MainAxisAlignment::End,
-                MainAlign::SpaceBetween => MainAxisAlignment::SpaceBetween,
-                MainAlign::SpaceAround => MainAxisAlignment::SpaceAround,
-                MainAlign::SpaceEvenly => MainAxisAlignment::SpaceEvenly,
-            },
-        );
+        Flex::set_main_axis_alignment(flex, main_axis_alignment(ma, mirror_main));
     }
 
     if let Some(true) = style.must_fill_main_axis {
@@ -244,6 +325,25 @@ pub fn apply_flex_style(flex: &mut masonry::core::WidgetMut<'_, Flex>, style: &B
     }
 }
 
+/// Tint laid over the desktop by `systemMaterial` when no `background` is
+/// given: dark enough to keep default white text legible.
+const MATERIAL_TINT: Color = Color::from_rgba8(0x20, 0x20, 0x24, 0xB0);
+
+/// The root's fill for a background mode. A `background` color overrides the
+/// default fill or tint; a solid root defaults to the window's clear color so
+/// switching modes doesn't flash, and `transparent` paints nothing so a
+/// transparent window shows what is behind it.
+pub fn root_background(mode: BackgroundMode, background: Option<&ColorValue>) -> Color {
+    let color = background.map(color_value_to_peniko);
+    match mode {
+        BackgroundMode::Solid => color
+            .map(|c| c.with_alpha(1.0))
+            .unwrap_or_else(window_background),
+        BackgroundMode::Transparent => Color::TRANSPARENT,
+        BackgroundMode::SystemMaterial => color.unwrap_or(MATERIAL_TINT),
+    }
+}
+
 #[cfg(test)]
 pub fn default_text_style_props() -> Vec<StyleProperty> {
     vec![
@@ -257,7 +357,7 @@ pub fn default_text_style_props() -> Vec<StyleProperty> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::ipc::{BoxStyle, FontStyleValue, PaddingValue};
+    use crate::ipc::{BoxStyle, FontStyleValue, PaddingValue, TextAlignValue};
     use masonry::core::StyleProperty;
 
     #[test]
@@ -344,6 +444,118 @@ mod tests {
         let _ = props;
     }
 
+    #[test]
+    fn test_main_axis_alignment_mirroring() {
+        assert_eq!(
+            main_axis_alignment(&MainAlign::Start, false),
+            MainAxisAlignment::Start
+        );
+        assert_eq!(
+            main_axis_alignment(&MainAlign::Start, true),
+            MainAxisAlignment::End
+        );
+        assert_eq!(
+            main_axis_alignment(&MainAlign::End, true),
+            MainAxisAlignment::Start
+        );
+        assert_eq!(
+            main_axis_alignment(&MainAlign::SpaceBetween, true),
+            MainAxisAlignment::SpaceBetween
+        );
+    }
+
+    #[test]
+    fn test_remember_sizes_merges_size_fields() {
+        let mut sizes = BoxStyle::default();
+        remember_sizes(
+            &mut sizes,
+            &BoxStyle {
+                font_size: Some(14.0),
+                width: Some(100.0),
+                background: Some(ColorValue::Named("white".to_string())),
+                ..Default::default()
+            },
+        );
+        remember_sizes(
+            &mut sizes,
+            &BoxStyle {
+                width: Some(120.0),
+                gap: Some(8.0),
+                ..Default::default()
+            },
+        );
+        assert_eq!(sizes.font_size, Some(14.0));
+        assert_eq!(sizes.width, Some(120.0));
+        assert_eq!(sizes.gap, Some(8.0));
+        assert!(sizes.background.is_none());
+    }
+
+    #[test]
+    fn test_padding_mirroring() {
+        let sides = PaddingValue::Sides {
+            top: 1.0,
+            right: 2.0,
+            bottom: 3.0,
+            left: 4.0,
+        };
+        let ltr = padding(&sides, false);
+        assert_eq!((ltr.left, ltr.right), (4.0, 2.0));
+        let rtl = padding(&sides, true);
+        assert_eq!(
+            (rtl.left, rtl.right, rtl.top, rtl.bottom),
+            (2.0, 4.0, 1.0, 3.0)
+        );
+    }
+
+    #[test]
+    fn test_text_alignment_follows_direction() {
+        assert_eq!(text_alignment(None, false), None);
+        assert_eq!(text_alignment(None, true), Some(Alignment::Right));
+        assert_eq!(
+            text_alignment(Some(&TextAlignValue::Start), false),
+            Some(Alignment::Left)
+        );
+        assert_eq!(
+            text_alignment(Some(&TextAlignValue::End), true),
+            Some(Alignment::Left)
+        );
+        assert_eq!(
+            text_alignment(Some(&TextAlignValue::Center), true),
+            Some(Alignment::Center)
+        );
+    }
+
+    #[test]
+    fn test_root_background_modes() {
+        let red = ColorValue::Rgba {
+            r: 255,
+            g: 0,
+            b: 0,
+            a: 128,
+        };
+        assert_eq!(
+            root_background(BackgroundMode::Solid, None),
+            window_background()
+        );
+        // A solid root never lets the window show through.
+        assert_eq!(
+            root_background(BackgroundMode::Solid, Some(&red)),
+            Color::from_rgba8(255, 0, 0, 255)
+        );
+        assert_eq!(
+            root_background(BackgroundMode::Transparent, Some(&red)),
+            Color::TRANSPARENT
+        );
+        assert_eq!(
+            root_background(BackgroundMode::SystemMaterial, None),
+            MATERIAL_TINT
+        );
+        assert_eq!(
+            root_background(BackgroundMode::SystemMaterial, Some(&red)),
+            Color::from_rgba8(255, 0, 0, 128)
+        );
+    }
+
     #[test]
     fn test_default_text_style_props() {
         let defaults = default_text_style_props();
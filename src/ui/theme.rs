@@ -0,0 +1,110 @@
+// Widget Defaults
+// What widgets look like before JS styles them. Masonry's default property set
+// supplies the padding, borders and colors of its widgets, and labels created
+// without a style are 30px and white. The manifest's `[theme]` overrides
+// these, so apps don't fight baked-in values with per-widget styles:
+//
+//   [theme]
+//   masonry-defaults = true   # false starts from no default properties at all
+//   font-size = 16            # labels without a fontSize
+//   text-color = "#1e1e2e"    # labels without a color
+//   gap = 4                   # between the children of rows and columns
+
+use std::sync::Mutex;
+
+use masonry::core::DefaultProperties;
+use masonry::peniko::Color;
+use masonry::properties::{ContentColor, Gap};
+use masonry::theme::default_property_set;
+use masonry::widgets::{Flex, Label};
+
+use crate::ui::styles;
+
+/// Font size of a label created without a style, unless the theme sets one.
+pub const DEFAULT_FONT_SIZE: f32 = 30.0;
+/// Text color of a label created without a style, unless the theme sets one.
+pub const DEFAULT_TEXT_COLOR: Color = Color::WHITE;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WidgetDefaults {
+    /// Start from masonry's default property set rather than an empty one
+    pub masonry_defaults: bool,
+    /// Font size of every label whose style sets none
+    pub font_size: Option<f32>,
+    /// Text color of every label whose style sets none
+    pub text_color: Option<Color>,
+    /// Gap between the children of a Flex, in logical px
+    pub gap: Option<f64>,
+}
+
+impl WidgetDefaults {
+    pub const BUILT_IN: Self = Self {
+        masonry_defaults: true,
+        font_size: None,
+        text_color: None,
+        gap: None,
+    };
+
+    /// The default properties the window's widgets start from.
+    pub fn property_set(&self) -> DefaultProperties {
+        let mut properties = if self.masonry_defaults {
+            default_property_set()
+        } else {
+            DefaultProperties::new()
+        };
+        if let Some(gap) = self.gap {
+            properties.insert::<Flex, _>(Gap::new(styles::px(gap)));
+        }
+        if let Some(color) = self.text_color {
+            properties.insert::<Label, _>(ContentColor::new(color));
+        }
+        properties
+    }
+
+    /// Font size of a label created without a style.
+    pub fn unstyled_font_size(&self) -> f32 {
+        self.font_size.unwrap_or(DEFAULT_FONT_SIZE)
+    }
+
+    /// Text color of a label created without a style.
+    pub fn unstyled_text_color(&self) -> Color {
+        self.text_color.unwrap_or(DEFAULT_TEXT_COLOR)
+    }
+}
+
+impl Default for WidgetDefaults {
+    fn default() -> Self {
+        Self::BUILT_IN
+    }
+}
+
+static WIDGET_DEFAULTS: Mutex<WidgetDefaults> = Mutex::new(WidgetDefaults::BUILT_IN);
+
+/// Set before the window opens; later changes only reach widgets created after.
+pub fn set_widget_defaults(defaults: WidgetDefaults) {
+    *WIDGET_DEFAULTS.lock().unwrap_or_else(|e| e.into_inner()) = defaults;
+}
+
+pub fn widget_defaults() -> WidgetDefaults {
+    *WIDGET_DEFAULTS.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unstyled_labels_fall_back_to_built_in_values() {
+        let defaults = WidgetDefaults::default();
+        assert_eq!(defaults.unstyled_font_size(), 30.0);
+        assert_eq!(defaults.unstyled_text_color(), Color::WHITE);
+
+        let themed = WidgetDefaults {
+            font_size: Some(16.0),
+            text_color: Some(Color::BLACK),
+            ..WidgetDefaults::default()
+        };
+        assert_eq!(themed.unstyled_font_size(), 16.0);
+        assert_eq!(themed.unstyled_text_color(), Color::BLACK);
+    }
+}
@@ -0,0 +1,112 @@
+// Custom Widget Factories
+// `WidgetKind::Custom("name")` widgets are built by a factory registered under
+// that name. Unregistered kinds fall back to a placeholder Label (see
+// widgets/custom.rs). Applications embedding the runtime register theirs with
+// `Extensions::widget_factory` before calling `vellum::run` (see app.rs).
+
+use std::sync::{Arc, Mutex};
+
+use masonry::core::{ErasedAction, NewWidget, Widget, WidgetMut};
+
+use crate::ipc::{BoxStyle, WidgetActionKind};
+
+/// What a factory gets to build a widget from.
+pub struct CreateContext<'a> {
+    /// JS widget id
+    pub id: &'a str,
+    /// Initial text, if the widget was created with one
+    pub text: Option<&'a str>,
+    pub style: Option<&'a BoxStyle>,
+}
+
+/// Builds and updates the widgets of one custom kind.
+///
+/// `set_text` and `set_value` return false when the kind doesn't support the
+/// update, which is reported to JS as an unsupported operation.
+pub trait WidgetFactory: Send + Sync {
+    fn create(&self, ctx: &CreateContext<'_>) -> NewWidget<dyn Widget>;
+
+    fn set_text(&self, _widget: &mut WidgetMut<'_, dyn Widget>, _text: &str) -> bool {
+        false
+    }
+
+    fn set_value(&self, _widget: &mut WidgetMut<'_, dyn Widget>, _value: f64) -> bool {
+        false
+    }
+
+    /// Translate an action submitted by the widget into an event for JS.
+    /// Returning None leaves it to the built-in action handling.
+    fn map_action(&self, _action: &ErasedAction) -> Option<WidgetActionKind> {
+        None
+    }
+}
+
+static FACTORIES: Mutex<Vec<(String, Arc<dyn WidgetFactory>)>> = Mutex::new(Vec::new());
+
+/// Register `factory` for `WidgetKind::Custom(kind)`, replacing any factory
+/// already registered under that name. Register before the first widget of
+/// the kind is created; existing widgets keep the factory that built them.
+pub fn register_widget_factory(kind: impl Into<String>, factory: impl WidgetFactory + 'static) {
+    let kind = kind.into();
+    let factory: Arc<dyn WidgetFactory> = Arc::new(factory);
+    let mut factories = FACTORIES.lock().unwrap_or_else(|e| e.into_inner());
+    match factories.iter_mut().find(|(name, _)| *name == kind) {
+        Some(entry) => entry.1 = factory,
+        None => factories.push((kind, factory)),
+    }
+}
+
+/// The factory registered for a custom kind, if any.
+pub fn factory_for(kind: &str) -> Option<Arc<dyn WidgetFactory>> {
+    FACTORIES
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .find(|(name, _)| name == kind)
+        .map(|(_, factory)| factory.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use masonry::widgets::Label;
+
+    use super::*;
+    use crate::ipc::ClickInput;
+
+    struct Tagged(&'static str);
+
+    impl WidgetFactory for Tagged {
+        fn create(&self, _ctx: &CreateContext<'_>) -> NewWidget<dyn Widget> {
+            NewWidget::new(Label::new(self.0)).erased()
+        }
+
+        fn map_action(&self, _action: &ErasedAction) -> Option<WidgetActionKind> {
+            Some(WidgetActionKind::click(ClickInput::PRIMARY))
+        }
+    }
+
+    #[test]
+    fn test_register_and_replace_factory() {
+        assert!(factory_for("test-gauge").is_none());
+
+        register_widget_factory("test-gauge", Tagged("first"));
+        register_widget_factory("test-gauge", Tagged("second"));
+        let factories = FACTORIES.lock().unwrap();
+        assert_eq!(
+            factories
+                .iter()
+                .filter(|(name, _)| name == "test-gauge")
+                .count(),
+            1
+        );
+        drop(factories);
+
+        let factory = factory_for("test-gauge").expect("registered");
+        let action: ErasedAction = Box::new(());
+        assert!(matches!(
+            factory.map_action(&action),
+            Some(WidgetActionKind::Click { count: 1, .. })
+        ));
+        assert!(factory_for("Test-Gauge").is_none());
+    }
+}
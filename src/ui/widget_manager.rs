@@ -1,12 +1,61 @@
-use crate::ipc::WidgetKind;
+use crate::ipc::{BackgroundMode, BadgeSpec, BoxStyle, ColorValue, WidgetKind};
+use crate::ui::accelerators::Accelerator;
+use crate::ui::animations::Animation;
+use crate::ui::bindings::Binding;
+use crate::ui::icons::IconRegistry;
+use crate::ui::journal::Journal;
+use crate::ui::snapshot::WidgetRecord;
 use masonry::core::WidgetId;
 use masonry::core::WidgetTag;
-use masonry::widgets::Flex;
-use std::collections::HashMap;
+use masonry::kurbo::Size;
+use masonry::widgets::{Flex, ZStack};
+use std::collections::{HashMap, HashSet};
+
+use crate::ui::widgets::layer_impl::Layer;
+use crate::ui::widgets::viewport::VisibilityObserver;
 
 /// Tag for the root Flex container that holds all dynamically created widgets.
 pub const ROOT_FLEX_TAG: WidgetTag<Flex> = WidgetTag::named("root_flex");
 
+/// Tag for the ZStack stacking the content layer under the named layers.
+pub const ROOT_STACK_TAG: WidgetTag<ZStack> = WidgetTag::named("root_stack");
+
+/// A reserved parent id that addresses one of the root layers.
+pub struct RootLayer {
+    pub id: &'static str,
+    pub tag: WidgetTag<Layer>,
+}
+
+/// Layers above the content layer (`__root__`), bottom to top.
+pub const LAYERS: [RootLayer; 3] = [
+    RootLayer {
+        id: "__overlay__",
+        tag: WidgetTag::named("overlay_layer"),
+    },
+    RootLayer {
+        id: "__toast__",
+        tag: WidgetTag::named("toast_layer"),
+    },
+    RootLayer {
+        id: "__debug__",
+        tag: WidgetTag::named("debug_layer"),
+    },
+];
+
+/// The tag of the layer reserved under `id`, if it names one.
+pub fn layer_tag(id: &str) -> Option<WidgetTag<Layer>> {
+    LAYERS
+        .iter()
+        .find(|layer| layer.id == id)
+        .map(|layer| layer.tag)
+}
+
+/// Whether `key` is `__root__` or a layer id: a parent that exists without
+/// being a tracked widget.
+pub fn is_root_key(key: &str) -> bool {
+    key == "__root__" || layer_tag(key).is_some()
+}
+
 /// Information tracked for each JS-created widget.
 #[derive(Debug, Clone)]
 pub struct WidgetInfo {
@@ -20,21 +69,96 @@ pub struct WidgetInfo {
     pub child_index: usize,
 }
 
+/// A badge overlay attached to a widget through an internal ZStack wrapper.
+#[derive(Debug, Clone)]
+pub struct BadgeOverlay {
+    /// The masonry WidgetId of the badge box inside the wrapper.
+    pub badge_id: WidgetId,
+    /// The current badge configuration (colors and position are kept across updates).
+    pub spec: BadgeSpec,
+}
+
 /// Manages the mapping from JS widget IDs to masonry widget state.
 pub struct WidgetManager {
     /// Maps JS string IDs → tracked widget info.
     pub widgets: HashMap<String, WidgetInfo>,
     /// Maps a parent ID (or "__root__") to an ordered list of child IDs.
     pub parent_to_children: HashMap<String, Vec<String>>,
+    /// Maps a badged widget's masonry WidgetId → its badge overlay.
+    pub badges: HashMap<WidgetId, BadgeOverlay>,
+    /// Maps a clipped widget's masonry WidgetId → the WidgetId of its ClipBox wrapper.
+    pub clips: HashMap<WidgetId, WidgetId>,
+    /// Maps a container's masonry WidgetId → the WidgetId of its BackgroundBox wrapper.
+    pub backgrounds: HashMap<WidgetId, WidgetId>,
+    /// Maps a Button's masonry WidgetId → the WidgetId of its PressFeedback wrapper.
+    pub feedbacks: HashMap<WidgetId, WidgetId>,
+    /// Icon packs resolvable by Icon widgets (bundled Lucide plus JS-registered packs).
+    pub icons: IconRegistry,
+    /// Row Flexes created in right-to-left mode. Their children are stored in
+    /// reverse, so `child_index` counts from the right edge.
+    pub mirrored_rows: HashSet<WidgetId>,
+    /// Column Flexes created in right-to-left mode; cross-axis start is the right edge.
+    pub mirrored_columns: HashSet<WidgetId>,
+    /// Size-bearing style fields last applied to each widget (and `__root__`),
+    /// re-applied by `SetUiScale`.
+    pub sized_styles: HashMap<String, BoxStyle>,
+    /// Data channel name → the widget properties it feeds (`BindValue`).
+    pub bindings: HashMap<String, Vec<Binding>>,
+    /// Running `AnimateValue` tweens, stepped by the frame clock.
+    pub animations: Vec<Animation>,
+    /// The root's `backgroundMode`, kept so later `background` changes on
+    /// `__root__` are painted within the same mode.
+    pub root_background_mode: Option<BackgroundMode>,
+    /// Shortcuts of Buttons created with an `accelerator`, by masonry
+    /// WidgetId. Their inner Flex ends with the hint label.
+    pub accelerators: HashMap<WidgetId, Accelerator>,
+    /// Text color of Buttons created with a `variant`, by masonry WidgetId.
+    /// Children created without a `color` take it.
+    pub button_text_colors: HashMap<WidgetId, ColorValue>,
+    /// Shortcuts of dock menu items, by item id (kept across `clear`).
+    pub menu_accelerators: Vec<(String, Accelerator)>,
+    /// How each JS widget was declared, by id, for `SerializeTree`.
+    pub records: HashMap<String, WidgetRecord>,
+    /// Styles set on `__root__` and the layers (kept across `clear`).
+    pub root_styles: HashMap<String, BoxStyle>,
+    /// Undo/redo steps of tree mutations, when enabled with `SetJournal`.
+    pub journal: Journal,
+    /// Widgets registered with `ObserveVisibility`, by id.
+    pub visibility_observers: HashMap<String, VisibilityObserver>,
+    /// Widgets registered with `ObserveResize`, by id, with the size last
+    /// reported (None until the first report).
+    pub resize_observers: HashMap<String, Option<Size>>,
 }
 
 impl WidgetManager {
     pub fn new() -> Self {
         let mut parent_to_children = HashMap::new();
         parent_to_children.insert("__root__".to_string(), Vec::new());
+        for layer in &LAYERS {
+            parent_to_children.insert(layer.id.to_string(), Vec::new());
+        }
         Self {
             widgets: HashMap::new(),
             parent_to_children,
+            badges: HashMap::new(),
+            clips: HashMap::new(),
+            backgrounds: HashMap::new(),
+            feedbacks: HashMap::new(),
+            icons: IconRegistry::default(),
+            mirrored_rows: HashSet::new(),
+            mirrored_columns: HashSet::new(),
+            sized_styles: HashMap::new(),
+            bindings: HashMap::new(),
+            animations: Vec::new(),
+            root_background_mode: None,
+            accelerators: HashMap::new(),
+            button_text_colors: HashMap::new(),
+            menu_accelerators: Vec::new(),
+            records: HashMap::new(),
+            root_styles: HashMap::new(),
+            journal: Journal::default(),
+            visibility_observers: HashMap::new(),
+            resize_observers: HashMap::new(),
         }
     }
 
@@ -81,8 +205,85 @@ impl WidgetManager {
         }
     }
 
+    /// Move a child of `parent_key` from index `from` to `to`, after the
+    /// masonry parent reordered it itself (ReorderableList drops).
+    pub fn move_child(&mut self, parent_key: &str, from: usize, to: usize) {
+        if let Some(children) = self.parent_to_children.get_mut(parent_key)
+            && from < children.len()
+            && to < children.len()
+        {
+            let moved = children.remove(from);
+            children.insert(to, moved);
+            self.recompute_parent_state(parent_key);
+        }
+    }
+
+    /// Drop badge / clip / background wrapper bookkeeping for a removed widget.
+    fn forget_wrappers(&mut self, widget_id: WidgetId) {
+        self.badges.remove(&widget_id);
+        self.clips.remove(&widget_id);
+        self.backgrounds.remove(&widget_id);
+        self.feedbacks.remove(&widget_id);
+        self.button_text_colors.remove(&widget_id);
+        self.mirrored_rows.remove(&widget_id);
+        self.mirrored_columns.remove(&widget_id);
+        self.accelerators.remove(&widget_id);
+    }
+
+    /// Feed `property` of widget `id` from `channel`. A property has at most
+    /// one source, so an earlier binding of it (on any channel) is replaced.
+    pub fn bind(&mut self, id: &str, property: &str, channel: &str) {
+        self.unbind(id, property);
+        self.bindings
+            .entry(channel.to_string())
+            .or_default()
+            .push(Binding {
+                id: id.to_string(),
+                property: property.to_string(),
+            });
+    }
+
+    pub fn unbind(&mut self, id: &str, property: &str) {
+        self.bindings.retain(|_, bound| {
+            bound.retain(|b| b.id != id || b.property != property);
+            !bound.is_empty()
+        });
+    }
+
+    /// Start `animation`, replacing one already running on the same property.
+    pub fn animate(&mut self, animation: Animation) {
+        self.stop_animation(&animation.id, Some(&animation.property));
+        self.animations.push(animation);
+    }
+
+    /// Stop the animation of `property` on widget `id`, or all of its
+    /// animations when `property` is None.
+    pub fn stop_animation(&mut self, id: &str, property: Option<&str>) {
+        self.animations
+            .retain(|a| a.id != id || property.is_some_and(|p| p != a.property));
+    }
+
+    /// Whether any widget's visibility or size is observed, so layouts need
+    /// a viewport sweep.
+    pub fn is_observing(&self) -> bool {
+        !self.visibility_observers.is_empty() || !self.resize_observers.is_empty()
+    }
+
+    fn forget_bindings(&mut self, ids: &HashSet<&str>) {
+        self.bindings.retain(|_, bound| {
+            bound.retain(|b| !ids.contains(b.id.as_str()));
+            !bound.is_empty()
+        });
+        self.animations.retain(|a| !ids.contains(a.id.as_str()));
+    }
+
     pub fn remove_widget_subtree(&mut self, id: &str) -> Option<WidgetInfo> {
         let removed = self.widgets.remove(id)?;
+        self.forget_wrappers(removed.widget_id);
+        self.sized_styles.remove(id);
+        self.records.remove(id);
+        self.visibility_observers.remove(id);
+        self.resize_observers.remove(id);
         let parent_key = removed
             .parent_id
             .clone()
@@ -96,18 +297,174 @@ impl WidgetManager {
         // Collect and remove all descendants recursively
         let mut descendants = Vec::new();
         self.collect_descendants(id, &mut descendants);
-        for child_id in descendants {
-            self.widgets.remove(&child_id);
-            self.parent_to_children.remove(&child_id);
+        for child_id in &descendants {
+            if let Some(child) = self.widgets.remove(child_id) {
+                self.forget_wrappers(child.widget_id);
+            }
+            self.sized_styles.remove(child_id);
+            self.records.remove(child_id);
+            self.visibility_observers.remove(child_id);
+            self.resize_observers.remove(child_id);
+            self.parent_to_children.remove(child_id);
         }
 
         // Remove the sublist for the widget
         self.parent_to_children.remove(id);
 
+        let mut unbound: HashSet<&str> = descendants.iter().map(String::as_str).collect();
+        unbound.insert(id);
+        self.forget_bindings(&unbound);
+
         self.recompute_parent_state(&parent_key);
 
         Some(removed)
     }
+
+    /// Forget every tracked widget, e.g. after the root Flex has been emptied.
+    /// Registered icon packs are kept.
+    pub fn clear(&mut self) {
+        self.widgets.clear();
+        self.parent_to_children.clear();
+        self.parent_to_children
+            .insert("__root__".to_string(), Vec::new());
+        for layer in &LAYERS {
+            self.parent_to_children
+                .insert(layer.id.to_string(), Vec::new());
+        }
+        self.badges.clear();
+        self.clips.clear();
+        self.backgrounds.clear();
+        self.feedbacks.clear();
+        self.button_text_colors.clear();
+        self.mirrored_rows.clear();
+        self.mirrored_columns.clear();
+        self.accelerators.clear();
+        self.sized_styles.retain(|id, _| is_root_key(id));
+        self.records.clear();
+        self.journal.clear();
+        self.visibility_observers.clear();
+        self.resize_observers.clear();
+        self.bindings.clear();
+        self.animations.clear();
+    }
+
+    /// Cross-check `widgets` against `parent_to_children`.
+    /// Returns one description per inconsistency; empty means the maps agree.
+    pub fn audit(&self) -> Vec<String> {
+        let mut issues = Vec::new();
+
+        let mut ids: Vec<&String> = self.widgets.keys().collect();
+        ids.sort();
+        for id in ids {
+            let info = &self.widgets[id];
+            let parent_key = info.parent_id.as_deref().unwrap_or("__root__");
+            if !is_root_key(parent_key) && !self.widgets.contains_key(parent_key) {
+                issues.push(format!("'{id}' references unknown parent '{parent_key}'"));
+            }
+            match self
+                .parent_to_children
+                .get(parent_key)
+                .and_then(|children| children.iter().position(|child| child == id))
+            {
+                Some(position) if position != info.child_index => issues.push(format!(
+                    "'{id}' has child_index {} but is at position {position} under '{parent_key}'",
+                    info.child_index
+                )),
+                Some(_) => {}
+                None => issues.push(format!(
+                    "'{id}' is missing from the child list of '{parent_key}'"
+                )),
+            }
+        }
+
+        let mut parents: Vec<&String> = self.parent_to_children.keys().collect();
+        parents.sort();
+        for parent_key in parents {
+            if !is_root_key(parent_key) && !self.widgets.contains_key(parent_key) {
+                issues.push(format!("child list kept for unknown widget '{parent_key}'"));
+            }
+            let children = &self.parent_to_children[parent_key];
+            for (position, child) in children.iter().enumerate() {
+                if !self.widgets.contains_key(child) {
+                    issues.push(format!("'{parent_key}' lists unknown child '{child}'"));
+                } else if children[..position].contains(child) {
+                    issues.push(format!(
+                        "'{parent_key}' lists child '{child}' more than once"
+                    ));
+                } else if self.widgets[child]
+                    .parent_id
+                    .as_deref()
+                    .unwrap_or("__root__")
+                    != parent_key.as_str()
+                {
+                    issues.push(format!(
+                        "'{parent_key}' lists '{child}', which belongs to another parent"
+                    ));
+                }
+            }
+        }
+
+        issues
+    }
+
+    /// Rebuild child lists and indices from `widgets`, which is authoritative.
+    ///
+    /// Entries for unknown or misplaced children are dropped, tracked widgets
+    /// missing from their parent's list are re-inserted, and every `child_index`
+    /// is renumbered. Widgets whose parent is unknown cannot be fixed here.
+    /// Re-inserted widgets go back at their recorded `child_index`; the order
+    /// is not read back from the masonry tree, so it may not match what is on
+    /// screen.
+    pub fn repair(&mut self) {
+        let widgets = &self.widgets;
+        self.parent_to_children
+            .retain(|parent_key, _| is_root_key(parent_key) || widgets.contains_key(parent_key));
+        for (parent_key, children) in self.parent_to_children.iter_mut() {
+            let mut seen = std::collections::HashSet::new();
+            children.retain(|child| {
+                widgets.get(child).is_some_and(|info| {
+                    info.parent_id.as_deref().unwrap_or("__root__") == parent_key.as_str()
+                }) && seen.insert(child.clone())
+            });
+        }
+
+        // Re-insert missing widgets at their recorded index, which is the best
+        // guess at their position in the masonry container.
+        let mut missing: Vec<(&String, &WidgetInfo)> = self
+            .widgets
+            .iter()
+            .filter(|(id, info)| {
+                let parent_key = info.parent_id.as_deref().unwrap_or("__root__");
+                !self
+                    .parent_to_children
+                    .get(parent_key)
+                    .is_some_and(|children| children.contains(id))
+            })
+            .collect();
+        missing.sort_by_key(|(_, info)| info.child_index);
+        let missing: Vec<(String, String, usize)> = missing
+            .into_iter()
+            .map(|(id, info)| {
+                let parent_key = info
+                    .parent_id
+                    .clone()
+                    .unwrap_or_else(|| "__root__".to_string());
+                (id.clone(), parent_key, info.child_index)
+            })
+            .collect();
+        for (id, parent_key, index) in missing {
+            let children = self.parent_to_children.entry(parent_key).or_default();
+            children.insert(index.min(children.len()), id);
+        }
+        for id in self.widgets.keys() {
+            self.parent_to_children.entry(id.clone()).or_default();
+        }
+
+        let parents: Vec<String> = self.parent_to_children.keys().cloned().collect();
+        for parent_key in parents {
+            self.recompute_parent_state(&parent_key);
+        }
+    }
 }
 
 #[cfg(test)]
@@ -119,8 +476,97 @@ mod tests {
     fn test_widget_manager_new() {
         let manager = WidgetManager::new();
         assert!(manager.widgets.is_empty());
-        assert_eq!(manager.parent_to_children.len(), 1);
+        assert_eq!(manager.parent_to_children.len(), 1 + LAYERS.len());
         assert!(manager.parent_to_children.contains_key("__root__"));
+        assert!(manager.parent_to_children.contains_key("__overlay__"));
+    }
+
+    #[test]
+    fn test_layer_children_are_not_orphans() {
+        let mut manager = WidgetManager::new();
+        manager.register_widget(
+            "tooltip".to_string(),
+            WidgetInfo {
+                widget_id: WidgetId::next(),
+                kind: WidgetKind::Label,
+                parent_id: Some("__overlay__".to_string()),
+                child_index: 0,
+            },
+        );
+        assert!(manager.audit().is_empty());
+        assert_eq!(manager.next_child_index("__overlay__"), 1);
+        assert!(is_root_key("__toast__"));
+        assert!(!is_root_key("__missing__"));
+
+        manager.clear();
+        assert_eq!(manager.next_child_index("__overlay__"), 0);
+        assert!(manager.audit().is_empty());
+    }
+
+    #[test]
+    fn test_clear_keeps_root_entry() {
+        let mut manager = WidgetManager::new();
+        manager.register_widget(
+            "row".to_string(),
+            WidgetInfo {
+                widget_id: WidgetId::next(),
+                kind: WidgetKind::Flex,
+                parent_id: None,
+                child_index: 0,
+            },
+        );
+        manager.clear();
+        assert!(manager.widgets.is_empty());
+        assert_eq!(manager.next_child_index("__root__"), 0);
+        assert!(manager.audit().is_empty());
+    }
+
+    #[test]
+    fn test_bindings_follow_widget_removal() {
+        let mut manager = WidgetManager::new();
+        for (id, parent_id) in [("panel", None), ("meter", Some("panel"))] {
+            manager.register_widget(
+                id.to_string(),
+                WidgetInfo {
+                    widget_id: WidgetId::next(),
+                    kind: WidgetKind::Flex,
+                    parent_id: parent_id.map(str::to_string),
+                    child_index: 0,
+                },
+            );
+        }
+        manager.bind("meter", "value", "level");
+        manager.bind("meter", "value", "peak");
+        manager.bind("panel", "width", "peak");
+        assert!(!manager.bindings.contains_key("level"));
+        assert_eq!(manager.bindings["peak"].len(), 2);
+
+        manager.unbind("panel", "width");
+        assert_eq!(manager.bindings["peak"].len(), 1);
+
+        manager.remove_widget_subtree("panel");
+        assert!(manager.bindings.is_empty());
+    }
+
+    #[test]
+    fn test_move_child_reindexes_siblings() {
+        let mut manager = WidgetManager::new();
+        for (index, id) in ["list", "a", "b", "c"].into_iter().enumerate() {
+            manager.register_widget(
+                id.to_string(),
+                WidgetInfo {
+                    widget_id: WidgetId::next(),
+                    kind: WidgetKind::ReorderableList,
+                    parent_id: (index > 0).then(|| "list".to_string()),
+                    child_index: index.saturating_sub(1),
+                },
+            );
+        }
+        manager.move_child("list", 0, 2);
+        assert_eq!(manager.parent_to_children["list"], ["b", "c", "a"]);
+        assert_eq!(manager.widgets["a"].child_index, 2);
+        assert_eq!(manager.widgets["b"].child_index, 0);
+        assert!(manager.audit().is_empty());
     }
 
     #[test]
@@ -238,4 +684,88 @@ mod tests {
         assert_eq!(manager.next_child_index("__root__"), 1);
         assert_eq!(manager.current_child_count("__root__"), 1);
     }
+
+    #[test]
+    fn test_remove_widget_subtree_drops_wrappers() {
+        use crate::ipc::{BadgePosition, BadgeValue};
+
+        let mut manager = WidgetManager::new();
+        let flex_id = WidgetId::next();
+        let icon_id = WidgetId::next();
+        manager.register_widget(
+            "tabs".to_string(),
+            WidgetInfo {
+                widget_id: flex_id,
+                kind: WidgetKind::Flex,
+                parent_id: None,
+                child_index: 0,
+            },
+        );
+        manager.register_widget(
+            "inbox".to_string(),
+            WidgetInfo {
+                widget_id: icon_id,
+                kind: WidgetKind::Svg,
+                parent_id: Some("tabs".to_string()),
+                child_index: 0,
+            },
+        );
+        manager.badges.insert(
+            icon_id,
+            BadgeOverlay {
+                badge_id: WidgetId::next(),
+                spec: BadgeSpec {
+                    value: BadgeValue::Count(3),
+                    color: None,
+                    text_color: None,
+                    position: BadgePosition::TopRight,
+                },
+            },
+        );
+
+        manager.clips.insert(flex_id, WidgetId::next());
+
+        manager.remove_widget_subtree("tabs");
+        assert!(manager.badges.is_empty());
+        assert!(manager.clips.is_empty());
+    }
+
+    #[test]
+    fn test_audit_and_repair() {
+        let mut manager = WidgetManager::new();
+        for (index, id) in ["a", "b", "c"].into_iter().enumerate() {
+            manager.register_widget(
+                id.to_string(),
+                WidgetInfo {
+                    widget_id: WidgetId::next(),
+                    kind: WidgetKind::Label,
+                    parent_id: None,
+                    child_index: index,
+                },
+            );
+        }
+        assert!(manager.audit().is_empty());
+
+        // Simulate the handler forgetting to sync metadata.
+        manager.widgets.get_mut("c").unwrap().child_index = 7;
+        manager
+            .parent_to_children
+            .get_mut("__root__")
+            .unwrap()
+            .retain(|id| id != "b");
+        manager
+            .parent_to_children
+            .get_mut("__root__")
+            .unwrap()
+            .push("ghost".to_string());
+
+        let issues = manager.audit();
+        assert_eq!(issues.len(), 3, "{issues:?}");
+
+        manager.repair();
+        assert!(manager.audit().is_empty());
+        assert_eq!(manager.widgets["a"].child_index, 0);
+        assert_eq!(manager.widgets["b"].child_index, 1);
+        assert_eq!(manager.widgets["c"].child_index, 2);
+    }
 }
@@ -0,0 +1,96 @@
+use masonry::app::RenderRoot;
+use masonry::core::{NewWidget, PropertySet, StyleProperty, WidgetOptions};
+use masonry::parley::style::{FontFamily, FontStack, FontWeight, GenericFamily};
+use masonry::peniko::Color;
+use masonry::properties::ContentColor;
+use masonry::widgets::Label;
+
+use crate::ipc::{BoxStyle, WidgetData, WidgetKind};
+use crate::ui::styles::{
+    build_box_properties, build_text_styles, color_value_to_peniko, font_size,
+};
+use crate::ui::widget_manager::{WidgetInfo, WidgetManager};
+use crate::ui::widgets::avatar_impl::AvatarWidget;
+use crate::ui::widgets::image::decode_image_bytes;
+use crate::ui::widgets::utils::{WrapperOptions, add_to_parent};
+
+#[allow(clippy::too_many_arguments)]
+pub fn create(
+    render_root: &mut RenderRoot,
+    widget_manager: &mut WidgetManager,
+    id: String,
+    parent_id: Option<String>,
+    text: Option<String>,
+    style: Option<BoxStyle>,
+    data: Option<WidgetData>,
+    child_index: usize,
+    wrappers: WrapperOptions,
+) {
+    let style_ref = style.as_ref();
+    let name = text.unwrap_or_default();
+
+    let (image_bytes, color, status) = match data {
+        Some(WidgetData::Avatar {
+            image,
+            color,
+            status,
+        }) => (image, color, status),
+        _ => (None, None, None),
+    };
+    // An undecodable image falls back to initials rather than failing creation.
+    let image = image_bytes
+        .filter(|bytes| !bytes.is_empty())
+        .and_then(|bytes| decode_image_bytes(&bytes));
+
+    let mut label = Label::new(AvatarWidget::label_text(&name, image.is_some()));
+    let text_styles = style_ref.map(build_text_styles).unwrap_or_else(|| {
+        vec![
+            font_size(16.0),
+            StyleProperty::FontStack(FontStack::Single(FontFamily::Generic(
+                GenericFamily::SansSerif,
+            ))),
+        ]
+    });
+    for s in text_styles {
+        label = label.with_style(s);
+    }
+    label = label.with_style(StyleProperty::FontWeight(FontWeight::SEMI_BOLD));
+    let label_color = style_ref
+        .and_then(|s| s.color.as_ref())
+        .map(color_value_to_peniko)
+        .unwrap_or(Color::WHITE);
+    let label_widget = NewWidget::new_with(
+        label,
+        None,
+        WidgetOptions::default(),
+        PropertySet::new().with(ContentColor::new(label_color)),
+    );
+
+    let avatar = AvatarWidget::new(&name, label_widget)
+        .with_image(image)
+        .with_color(color.as_ref().map(color_value_to_peniko))
+        .with_status(status.as_ref().map(color_value_to_peniko));
+
+    let props = style_ref.map(build_box_properties).unwrap_or_default();
+    let new_widget = NewWidget::new_with(avatar, None, WidgetOptions::default(), props);
+    let widget_id = new_widget.id();
+
+    if add_to_parent(
+        render_root,
+        widget_manager,
+        &parent_id,
+        new_widget,
+        style_ref.and_then(|s| s.flex),
+        wrappers,
+    ) {
+        widget_manager.register_widget(
+            id,
+            WidgetInfo {
+                widget_id,
+                kind: WidgetKind::Avatar,
+                parent_id: parent_id.clone(),
+                child_index,
+            },
+        );
+    }
+}
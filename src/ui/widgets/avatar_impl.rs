@@ -0,0 +1,283 @@
+use masonry::accesskit::{Node, Role};
+use masonry::core::{
+    AccessCtx, ChildrenIds, LayoutCtx, MeasureCtx, NewWidget, PaintCtx, PropertiesMut,
+    PropertiesRef, RegisterCtx, Update, UpdateCtx, Widget, WidgetMut, WidgetPod,
+};
+use masonry::kurbo::{Affine, Circle, Point, Size};
+use masonry::peniko::{Color, Fill, ImageBrush, ImageData};
+use masonry::vello::Scene;
+use masonry::widgets::Label;
+
+const DEFAULT_SIZE: f64 = 40.0;
+/// Status dot diameter relative to the avatar's diameter.
+const STATUS_RATIO: f64 = 0.28;
+const STATUS_RING_WIDTH: f64 = 2.0;
+const STATUS_RING_COLOR: Color = Color::from_rgba8(24, 24, 27, 255);
+/// Fallback backgrounds, picked deterministically from the name.
+const PALETTE: [Color; 8] = [
+    Color::from_rgba8(239, 68, 68, 255),
+    Color::from_rgba8(249, 115, 22, 255),
+    Color::from_rgba8(234, 179, 8, 255),
+    Color::from_rgba8(34, 197, 94, 255),
+    Color::from_rgba8(20, 184, 166, 255),
+    Color::from_rgba8(59, 130, 246, 255),
+    Color::from_rgba8(139, 92, 246, 255),
+    Color::from_rgba8(236, 72, 153, 255),
+];
+
+/// A circular avatar showing an image, or the initials of `name` when no image is set,
+/// with an optional status dot in the bottom-right corner.
+///
+/// Masonry's Image widget can't be clipped to a circle, so the image is painted here
+/// inside a circular clip layer.
+pub struct AvatarWidget {
+    name: String,
+    image: Option<ImageBrush>,
+    image_size: Size,
+    /// Overrides the name-derived fallback background.
+    color: Option<Color>,
+    status: Option<Color>,
+    initials: WidgetPod<Label>,
+    last_size: Size,
+}
+
+impl AvatarWidget {
+    pub fn new(name: &str, initials_label: NewWidget<Label>) -> Self {
+        Self {
+            name: name.to_string(),
+            image: None,
+            image_size: Size::ZERO,
+            color: None,
+            status: None,
+            initials: initials_label.to_pod(),
+            last_size: Size::ZERO,
+        }
+    }
+
+    pub fn with_image(mut self, image: Option<ImageData>) -> Self {
+        self.set_image_impl(image);
+        self
+    }
+
+    pub fn with_color(mut self, color: Option<Color>) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn with_status(mut self, status: Option<Color>) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// Text shown by the fallback label: empty while an image is displayed.
+    pub fn label_text(name: &str, has_image: bool) -> String {
+        if has_image {
+            String::new()
+        } else {
+            initials(name)
+        }
+    }
+
+    fn set_image_impl(&mut self, image: Option<ImageData>) {
+        self.image_size = image
+            .as_ref()
+            .map(|img| Size::new(img.width as f64, img.height as f64))
+            .unwrap_or(Size::ZERO);
+        self.image = image.map(ImageBrush::from);
+    }
+
+    fn fallback_color(&self) -> Color {
+        self.color.unwrap_or_else(|| {
+            let hash = self
+                .name
+                .bytes()
+                .fold(0u32, |acc, b| acc.wrapping_mul(31).wrapping_add(b as u32));
+            PALETTE[hash as usize % PALETTE.len()]
+        })
+    }
+
+    fn sync_label(this: &mut WidgetMut<'_, Self>) {
+        let text = Self::label_text(&this.widget.name, this.widget.image.is_some());
+        let mut label = this.ctx.get_mut(&mut this.widget.initials);
+        Label::set_text(&mut label, text);
+    }
+
+    pub fn set_name(this: &mut WidgetMut<'_, Self>, name: String) {
+        this.widget.name = name;
+        Self::sync_label(this);
+        this.ctx.request_render();
+    }
+
+    /// Set or clear the image. Clearing falls back to initials.
+    pub fn set_image(this: &mut WidgetMut<'_, Self>, image: Option<ImageData>) {
+        this.widget.set_image_impl(image);
+        Self::sync_label(this);
+        this.ctx.request_render();
+    }
+
+    pub fn set_status(this: &mut WidgetMut<'_, Self>, status: Option<Color>) {
+        this.widget.status = status;
+        this.ctx.request_render();
+    }
+}
+
+/// Up to two uppercase initials: first letters of the first and last words.
+pub fn initials(name: &str) -> String {
+    let words: Vec<&str> = name.split_whitespace().collect();
+    let first_char = |word: &str| word.chars().next();
+    let letters: Vec<char> = match words.as_slice() {
+        [] => Vec::new(),
+        [only] => only.chars().take(2).collect(),
+        [first, .., last] => first_char(first)
+            .into_iter()
+            .chain(first_char(last))
+            .collect(),
+    };
+    letters.into_iter().flat_map(char::to_uppercase).collect()
+}
+
+impl Widget for AvatarWidget {
+    type Action = masonry::core::NoAction;
+
+    fn register_children(&mut self, ctx: &mut RegisterCtx<'_>) {
+        ctx.register_child(&mut self.initials);
+    }
+
+    fn update(
+        &mut self,
+        _ctx: &mut UpdateCtx<'_>,
+        _props: &mut PropertiesMut<'_>,
+        _event: &Update,
+    ) {
+    }
+
+    fn measure(
+        &mut self,
+        _ctx: &mut MeasureCtx<'_>,
+        _props: &PropertiesRef<'_>,
+        _axis: masonry::kurbo::Axis,
+        len_req: masonry::layout::LenReq,
+        _cross_length: Option<f64>,
+    ) -> f64 {
+        match len_req {
+            masonry::layout::LenReq::FitContent(space) => DEFAULT_SIZE.min(space),
+            _ => DEFAULT_SIZE,
+        }
+    }
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx<'_>,
+        _props: &PropertiesRef<'_>,
+        size: masonry::kurbo::Size,
+    ) {
+        self.last_size = size;
+        let label_size = ctx.compute_size(
+            &mut self.initials,
+            masonry::layout::SizeDef::fit(size),
+            size.into(),
+        );
+        ctx.run_layout(&mut self.initials, label_size);
+        ctx.place_child(
+            &mut self.initials,
+            Point::new(
+                (size.width - label_size.width) * 0.5,
+                (size.height - label_size.height) * 0.5,
+            ),
+        );
+    }
+
+    fn paint(&mut self, _ctx: &mut PaintCtx<'_>, _props: &PropertiesRef<'_>, scene: &mut Scene) {
+        let size = self.last_size;
+        let radius = size.width.min(size.height) * 0.5;
+        let center = Point::new(size.width * 0.5, size.height * 0.5);
+        let circle = Circle::new(center, radius);
+
+        match &self.image {
+            Some(image) if self.image_size.width > 0.0 && self.image_size.height > 0.0 => {
+                // Cover-fit the image into the circle's bounding square.
+                let scale = (2.0 * radius / self.image_size.width)
+                    .max(2.0 * radius / self.image_size.height);
+                let offset = Point::new(
+                    center.x - self.image_size.width * scale * 0.5,
+                    center.y - self.image_size.height * scale * 0.5,
+                );
+                scene.push_clip_layer(Affine::IDENTITY, &circle);
+                scene.draw_image(
+                    image,
+                    Affine::translate(offset.to_vec2()) * Affine::scale(scale),
+                );
+                scene.pop_layer();
+            }
+            _ => {
+                scene.fill(
+                    Fill::NonZero,
+                    Affine::IDENTITY,
+                    self.fallback_color(),
+                    None,
+                    &circle,
+                );
+            }
+        }
+
+        if let Some(status) = self.status {
+            let dot_radius = radius * STATUS_RATIO;
+            // Tuck the ringed dot into the bottom-right corner of the bounding square.
+            let outer = dot_radius + STATUS_RING_WIDTH;
+            let dot_center = Point::new(center.x + radius - outer, center.y + radius - outer);
+            scene.fill(
+                Fill::NonZero,
+                Affine::IDENTITY,
+                STATUS_RING_COLOR,
+                None,
+                &Circle::new(dot_center, outer),
+            );
+            scene.fill(
+                Fill::NonZero,
+                Affine::IDENTITY,
+                status,
+                None,
+                &Circle::new(dot_center, dot_radius),
+            );
+        }
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::Image
+    }
+
+    fn accessibility(
+        &mut self,
+        _ctx: &mut AccessCtx<'_>,
+        _props: &PropertiesRef<'_>,
+        node: &mut Node,
+    ) {
+        if !self.name.is_empty() {
+            node.set_label(self.name.clone());
+        }
+    }
+
+    fn children_ids(&self) -> ChildrenIds {
+        ChildrenIds::from_slice(&[self.initials.id()])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_initials() {
+        assert_eq!(initials("Ada Lovelace"), "AL");
+        assert_eq!(initials("grace brewster murray hopper"), "GH");
+        assert_eq!(initials("linus"), "LI");
+        assert_eq!(initials("  "), "");
+        assert_eq!(initials("élodie durand"), "ÉD");
+    }
+
+    #[test]
+    fn test_label_text_hidden_when_image_present() {
+        assert_eq!(AvatarWidget::label_text("Ada Lovelace", true), "");
+        assert_eq!(AvatarWidget::label_text("Ada Lovelace", false), "AL");
+    }
+}
@@ -0,0 +1,128 @@
+// Container Background Images
+// `backgroundImage` on Flex/Container/SizedBox. The container is wrapped in an
+// internal BackgroundBox that paints its `background` color and then the image
+// beneath the container's children.
+
+use masonry::app::RenderRoot;
+use masonry::core::{NewWidget, Widget, WidgetId};
+use masonry::peniko::{Color, ImageData};
+
+use crate::assets;
+use crate::ipc::{BoxStyle, ImageSource, ObjectFitValue, WidgetKind};
+use crate::ui::styles::{color_value_to_peniko, scaled};
+use crate::ui::widgets::background_box_impl::BackgroundBox;
+use crate::ui::widgets::image::decode_image_bytes;
+use crate::ui::widgets::image_placement::ImagePlacement;
+
+/// What the BackgroundBox of a container being created paints.
+pub struct BackgroundSpec {
+    image: Option<ImageData>,
+    placement: ImagePlacement,
+    color: Option<Color>,
+    corner_radius: f64,
+}
+
+/// Containers that take a background image.
+pub fn supports(kind: &WidgetKind) -> bool {
+    matches!(
+        kind,
+        WidgetKind::Flex | WidgetKind::Container | WidgetKind::SizedBox
+    )
+}
+
+/// Decode the image `source` refers to, logging why it could not be.
+pub fn load(source: &ImageSource) -> Option<ImageData> {
+    match source {
+        ImageSource::Url(url) if url.starts_with(assets::SCHEME) => {
+            decode_image_bytes(&assets::load(url)?)
+        }
+        ImageSource::Url(url) => {
+            eprintln!("[UI] Background images must be asset:// URLs or bytes, not '{url}'");
+            None
+        }
+        ImageSource::Bytes(bytes) => decode_image_bytes(bytes),
+    }
+}
+
+/// Apply the background image fields `style` sets to `placement`.
+fn style_placement(placement: &mut ImagePlacement, style: &BoxStyle) {
+    placement.update(
+        style.background_fit,
+        style.background_position.as_deref(),
+        style.background_repeat,
+    );
+}
+
+/// Take the background of a container being created out of `style`, so the
+/// container leaves its color for the wrapper to paint. None when the style
+/// has no `backgroundImage`.
+pub fn take_spec(style: &mut BoxStyle) -> Option<BackgroundSpec> {
+    let source = style.background_image.as_ref()?;
+    let mut placement = ImagePlacement {
+        fit: ObjectFitValue::Cover,
+        ..ImagePlacement::default()
+    };
+    style_placement(&mut placement, style);
+    Some(BackgroundSpec {
+        image: load(source),
+        placement,
+        color: style.background.take().as_ref().map(color_value_to_peniko),
+        corner_radius: scaled(style.corner_radius.unwrap_or(0.0)),
+    })
+}
+
+/// Wrap `content` in a BackgroundBox painting `spec`.
+pub fn wrap(content: NewWidget<dyn Widget>, spec: BackgroundSpec) -> NewWidget<BackgroundBox> {
+    NewWidget::new(
+        BackgroundBox::new(content, spec.image, spec.placement)
+            .with_color(spec.color)
+            .with_corner_radius(spec.corner_radius),
+    )
+}
+
+/// Restyle the BackgroundBox `wrapper_id` created by [`wrap`]. Its
+/// `background` color moves out of `style` into the wrapper, as at creation.
+pub fn update(render_root: &mut RenderRoot, wrapper_id: WidgetId, style: &mut BoxStyle) {
+    let image = style.background_image.as_ref().map(load);
+    let color = style.background.take();
+    render_root.edit_widget(wrapper_id, |mut widget| {
+        let mut wrapper = widget.downcast::<BackgroundBox>();
+        if let Some(image) = image {
+            BackgroundBox::set_image_data(&mut wrapper, image);
+        }
+        let mut placement = wrapper.widget.placement();
+        style_placement(&mut placement, style);
+        BackgroundBox::set_placement(&mut wrapper, placement);
+        if let Some(color) = &color {
+            BackgroundBox::set_color(&mut wrapper, color_value_to_peniko(color));
+        }
+        if let Some(radius) = style.corner_radius {
+            BackgroundBox::set_corner_radius(&mut wrapper, scaled(radius));
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_take_spec_moves_the_color_to_the_wrapper() {
+        let mut style: BoxStyle = serde_json::from_str(
+            r##"{"background": "#ff0000", "backgroundImage": [1, 2, 3],
+                "backgroundPosition": "top", "cornerRadius": 8}"##,
+        )
+        .unwrap();
+        let spec = take_spec(&mut style).unwrap();
+        assert!(style.background.is_none());
+        assert_eq!(spec.color, Some(Color::from_rgba8(255, 0, 0, 255)));
+        // Undecodable bytes leave only the color.
+        assert!(spec.image.is_none());
+        assert_eq!(spec.placement.fit, ObjectFitValue::Cover);
+        assert_eq!(spec.placement.position, (0.5, 0.0));
+
+        let mut plain: BoxStyle = serde_json::from_str(r##"{"background": "#ff0000"}"##).unwrap();
+        assert!(take_spec(&mut plain).is_none());
+        assert!(plain.background.is_some());
+    }
+}
@@ -0,0 +1,171 @@
+use masonry::accesskit::{Node, Role};
+use masonry::core::{
+    AccessCtx, ChildrenIds, LayoutCtx, MeasureCtx, NewWidget, PaintCtx, PropertiesMut,
+    PropertiesRef, RegisterCtx, Update, UpdateCtx, Widget, WidgetMut, WidgetPod,
+};
+use masonry::kurbo::{Affine, Point, RoundedRect, Size};
+use masonry::peniko::{Color, Fill, ImageBrush, ImageData};
+use masonry::vello::Scene;
+
+use crate::ui::widgets::image_placement::{ImagePlacement, tiles};
+
+/// Internal single-child wrapper implementing `backgroundImage` on containers.
+///
+/// The container's own `background` would cover anything painted before it,
+/// so the wrapper takes the color over and paints it, then the image, inside
+/// the (rounded) bounds before the container and its children draw on top.
+pub struct BackgroundBox {
+    child: WidgetPod<dyn Widget>,
+    image: Option<ImageBrush>,
+    image_size: Size,
+    placement: ImagePlacement,
+    color: Option<Color>,
+    corner_radius: f64,
+    last_size: Size,
+}
+
+fn image_size(image: Option<&ImageData>) -> Size {
+    image.map_or(Size::ZERO, |image| {
+        Size::new(image.width as f64, image.height as f64)
+    })
+}
+
+impl BackgroundBox {
+    pub fn new(
+        child: NewWidget<impl Widget + ?Sized>,
+        image: Option<ImageData>,
+        placement: ImagePlacement,
+    ) -> Self {
+        Self {
+            child: child.erased().to_pod(),
+            image_size: image_size(image.as_ref()),
+            image: image.map(ImageBrush::from),
+            placement,
+            color: None,
+            corner_radius: 0.0,
+            last_size: Size::ZERO,
+        }
+    }
+
+    pub fn with_color(mut self, color: Option<Color>) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn with_corner_radius(mut self, corner_radius: f64) -> Self {
+        self.corner_radius = corner_radius.max(0.0);
+        self
+    }
+
+    pub fn placement(&self) -> ImagePlacement {
+        self.placement
+    }
+
+    /// Replace the image; `None` leaves only the color.
+    pub fn set_image_data(this: &mut WidgetMut<'_, Self>, image: Option<ImageData>) {
+        this.widget.image_size = image_size(image.as_ref());
+        this.widget.image = image.map(ImageBrush::from);
+        this.ctx.request_render();
+    }
+
+    pub fn set_placement(this: &mut WidgetMut<'_, Self>, placement: ImagePlacement) {
+        if this.widget.placement != placement {
+            this.widget.placement = placement;
+            this.ctx.request_render();
+        }
+    }
+
+    pub fn set_color(this: &mut WidgetMut<'_, Self>, color: Color) {
+        this.widget.color = Some(color);
+        this.ctx.request_render();
+    }
+
+    pub fn set_corner_radius(this: &mut WidgetMut<'_, Self>, corner_radius: f64) {
+        this.widget.corner_radius = corner_radius.max(0.0);
+        this.ctx.request_render();
+    }
+}
+
+impl Widget for BackgroundBox {
+    type Action = masonry::core::NoAction;
+
+    fn accepts_pointer_interaction(&self) -> bool {
+        false
+    }
+
+    fn register_children(&mut self, ctx: &mut RegisterCtx<'_>) {
+        ctx.register_child(&mut self.child);
+    }
+
+    fn update(
+        &mut self,
+        _ctx: &mut UpdateCtx<'_>,
+        _props: &mut PropertiesMut<'_>,
+        _event: &Update,
+    ) {
+    }
+
+    fn measure(
+        &mut self,
+        ctx: &mut MeasureCtx<'_>,
+        _props: &PropertiesRef<'_>,
+        axis: masonry::kurbo::Axis,
+        len_req: masonry::layout::LenReq,
+        cross_length: Option<f64>,
+    ) -> f64 {
+        ctx.compute_length(
+            &mut self.child,
+            len_req.into(),
+            masonry::layout::LayoutSize::maybe(axis.cross(), cross_length),
+            axis,
+            cross_length,
+        )
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx<'_>, _props: &PropertiesRef<'_>, size: Size) {
+        self.last_size = size;
+        let child_size = ctx.compute_size(
+            &mut self.child,
+            masonry::layout::SizeDef::fit(size),
+            size.into(),
+        );
+        ctx.run_layout(&mut self.child, child_size);
+        ctx.place_child(&mut self.child, Point::ORIGIN);
+        ctx.derive_baselines(&self.child);
+    }
+
+    fn paint(&mut self, _ctx: &mut PaintCtx<'_>, _props: &PropertiesRef<'_>, scene: &mut Scene) {
+        let shape = RoundedRect::from_rect(self.last_size.to_rect(), self.corner_radius);
+        if let Some(color) = self.color {
+            scene.fill(Fill::NonZero, Affine::IDENTITY, color, None, &shape);
+        }
+        let Some(image) = &self.image else {
+            return;
+        };
+        let copies = tiles(&self.placement, self.last_size, self.image_size);
+        if copies.is_empty() {
+            return;
+        }
+        scene.push_clip_layer(Affine::IDENTITY, &shape);
+        for transform in copies {
+            scene.draw_image(image, transform);
+        }
+        scene.pop_layer();
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::GenericContainer
+    }
+
+    fn accessibility(
+        &mut self,
+        _ctx: &mut AccessCtx<'_>,
+        _props: &PropertiesRef<'_>,
+        _node: &mut Node,
+    ) {
+    }
+
+    fn children_ids(&self) -> ChildrenIds {
+        ChildrenIds::from_slice(&[self.child.id()])
+    }
+}
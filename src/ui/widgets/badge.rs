@@ -0,0 +1,124 @@
+use masonry::app::RenderRoot;
+use masonry::core::{NewWidget, PropertySet, StyleProperty, Widget, WidgetId, WidgetOptions};
+use masonry::layout::Length;
+use masonry::parley::style::{FontFamily, FontStack, FontWeight, GenericFamily};
+use masonry::peniko::Color;
+use masonry::properties::types::UnitPoint;
+use masonry::properties::{Background, ContentColor, CornerRadius, Dimensions, Padding};
+use masonry::widgets::{ChildAlignment, Label, SizedBox, ZStack};
+
+use crate::ipc::{BadgePosition, BadgeSpec, BadgeValue};
+use crate::ui::styles::color_value_to_peniko;
+
+const DOT_SIZE: f64 = 8.0;
+const PILL_HEIGHT: f64 = 16.0;
+const FONT_SIZE: f32 = 11.0;
+const DEFAULT_COLOR: Color = Color::from_rgba8(239, 68, 68, 255);
+const DEFAULT_TEXT_COLOR: Color = Color::WHITE;
+/// Counts above this are shown as "99+".
+const MAX_COUNT: u32 = 99;
+
+/// Wrap `content` in an internal ZStack with a badge pinned to one of its corners.
+///
+/// Returns the wrapper together with the id of the badge box so that later
+/// `SetBadge` commands can update it in place.
+pub fn wrap(content: NewWidget<dyn Widget>, spec: &BadgeSpec) -> (NewWidget<ZStack>, WidgetId) {
+    let badge = build_badge(spec);
+    let badge_id = badge.id();
+    let zstack = ZStack::new()
+        .with(content, ChildAlignment::ParentAligned)
+        .with(
+            badge,
+            ChildAlignment::SelfAligned(corner_point(spec.position)),
+        );
+    (NewWidget::new(zstack), badge_id)
+}
+
+/// Update an existing badge box created by [`wrap`].
+pub fn update(render_root: &mut RenderRoot, badge_id: WidgetId, spec: &BadgeSpec) {
+    render_root.edit_widget(badge_id, |mut widget| {
+        let mut sbox = widget.downcast::<SizedBox>();
+        sbox.insert_prop(dimensions_for(&spec.value));
+        sbox.insert_prop(padding_for(&spec.value));
+        match count_text(&spec.value) {
+            Some(text) => SizedBox::set_child(&mut sbox, build_label(text, spec)),
+            None => SizedBox::remove_child(&mut sbox),
+        }
+    });
+}
+
+fn build_badge(spec: &BadgeSpec) -> NewWidget<SizedBox> {
+    let color = spec
+        .color
+        .as_ref()
+        .map(color_value_to_peniko)
+        .unwrap_or(DEFAULT_COLOR);
+    let props = PropertySet::new()
+        .with(Background::Color(color))
+        .with(CornerRadius::all(PILL_HEIGHT * 0.5))
+        .with(dimensions_for(&spec.value))
+        .with(padding_for(&spec.value));
+
+    let sbox = match count_text(&spec.value) {
+        Some(text) => SizedBox::new(build_label(text, spec)),
+        None => SizedBox::empty(),
+    };
+    NewWidget::new_with(sbox, None, WidgetOptions::default(), props)
+}
+
+fn build_label(text: String, spec: &BadgeSpec) -> NewWidget<Label> {
+    let text_color = spec
+        .text_color
+        .as_ref()
+        .map(color_value_to_peniko)
+        .unwrap_or(DEFAULT_TEXT_COLOR);
+    let label = Label::new(text)
+        .with_style(StyleProperty::FontSize(FONT_SIZE))
+        .with_style(StyleProperty::FontWeight(FontWeight::BOLD))
+        .with_style(StyleProperty::FontStack(FontStack::Single(
+            FontFamily::Generic(GenericFamily::SansSerif),
+        )));
+    NewWidget::new_with(
+        label,
+        None,
+        WidgetOptions::default(),
+        PropertySet::new().with(ContentColor::new(text_color)),
+    )
+}
+
+fn dimensions_for(value: &BadgeValue) -> Dimensions {
+    match value {
+        BadgeValue::Hidden => Dimensions::fixed(Length::px(0.0), Length::px(0.0)),
+        BadgeValue::Dot => Dimensions::fixed(Length::px(DOT_SIZE), Length::px(DOT_SIZE)),
+        BadgeValue::Count(_) => Dimensions::height(Length::px(PILL_HEIGHT)),
+    }
+}
+
+fn padding_for(value: &BadgeValue) -> Padding {
+    match value {
+        BadgeValue::Count(_) => Padding {
+            left: 5.0,
+            top: 1.0,
+            right: 5.0,
+            bottom: 1.0,
+        },
+        BadgeValue::Hidden | BadgeValue::Dot => Padding::all(0.0),
+    }
+}
+
+fn corner_point(position: BadgePosition) -> UnitPoint {
+    match position {
+        BadgePosition::TopRight => UnitPoint::TOP_RIGHT,
+        BadgePosition::TopLeft => UnitPoint::TOP_LEFT,
+        BadgePosition::BottomRight => UnitPoint::BOTTOM_RIGHT,
+        BadgePosition::BottomLeft => UnitPoint::BOTTOM_LEFT,
+    }
+}
+
+fn count_text(value: &BadgeValue) -> Option<String> {
+    match value {
+        BadgeValue::Count(count) if *count > MAX_COUNT => Some(format!("{MAX_COUNT}+")),
+        BadgeValue::Count(count) => Some(count.to_string()),
+        BadgeValue::Hidden | BadgeValue::Dot => None,
+    }
+}
@@ -1,24 +1,79 @@
 use masonry::app::RenderRoot;
-use masonry::core::{NewWidget, WidgetOptions};
-use masonry::widgets::{Button, Flex};
+use masonry::core::{NewWidget, PropertySet, StyleProperty, WidgetOptions};
+use masonry::parley::Alignment;
+use masonry::parley::style::{FontFamily, FontStack, GenericFamily};
+use masonry::peniko::Color;
+use masonry::properties::{ActiveBackground, Background, ContentColor};
+use masonry::widgets::{Button, Flex, Label};
 
-use crate::ipc::{BoxStyle, CrossAlign, FlexDirection, MainAlign, WidgetKind};
-use crate::ui::styles::build_box_properties;
+use crate::ipc::{
+    BoxStyle, ButtonFeedbackParams, ButtonVariant, ColorValue, CrossAlign, FlexDirection,
+    MainAlign, WidgetData, WidgetKind,
+};
+use crate::ui::accelerators::Accelerator;
+use crate::ui::styles::{build_box_properties, color_value_to_peniko, font_size, scaled};
 use crate::ui::widget_manager::{WidgetInfo, WidgetManager};
-use crate::ui::widgets::utils::add_to_parent;
+use crate::ui::widgets::press_feedback_impl::Feedback;
+use crate::ui::widgets::utils::{WrapperOptions, add_to_parent};
 
 use masonry::properties::types::{CrossAxisAlignment, MainAxisAlignment};
 
+const HINT_FONT_SIZE: f32 = 12.0;
+const HINT_COLOR: Color = Color::from_rgba8(255, 255, 255, 140);
+
+const ACCENT: ColorValue = ColorValue::Rgba {
+    r: 59,
+    g: 130,
+    b: 246,
+    a: 255,
+};
+const TRANSPARENT: ColorValue = ColorValue::Rgba {
+    r: 0,
+    g: 0,
+    b: 0,
+    a: 0,
+};
+const VARIANT_CORNER_RADIUS: f64 = 6.0;
+const DEFAULT_HOVER_LIGHTEN: f32 = 0.08;
+const DEFAULT_PRESS_DARKEN: f32 = 0.12;
+
+#[allow(clippy::too_many_arguments)]
 pub fn create(
     render_root: &mut RenderRoot,
     widget_manager: &mut WidgetManager,
     id: String,
     parent_id: Option<String>,
     style: Option<BoxStyle>,
+    data: Option<WidgetData>,
     child_index: usize,
+    mut wrappers: WrapperOptions,
 ) {
+    let (variant, feedback_params) = match &data {
+        Some(WidgetData::Button {
+            variant, feedback, ..
+        }) => (*variant, *feedback),
+        _ => (None, ButtonFeedbackParams::default()),
+    };
+    let style = match variant {
+        Some(variant) => Some(apply_variant(style.unwrap_or_default(), variant)),
+        None => style,
+    };
     let style_ref = style.as_ref();
 
+    let accelerator = match &data {
+        Some(WidgetData::Button {
+            accelerator: Some(text),
+            ..
+        }) => match Accelerator::parse(text) {
+            Ok(accelerator) => Some(accelerator),
+            Err(err) => {
+                eprintln!("[UI] Ignoring accelerator on button '{id}': {err}");
+                None
+            }
+        },
+        _ => None,
+    };
+
     // Button inner layout comes from BoxStyle (direction/alignment/gap/fill).
     // Direction defaults to Row.
     let dir = style_ref.and_then(|s| s.direction.clone());
@@ -56,17 +111,42 @@ pub fn create(
         new_flex = new_flex.main_axis_alignment(MainAxisAlignment::Center);
     }
 
+    // The shortcut hint stays the last child of the inner Flex, after
+    // everything JS adds, and takes the free space so it sits at the end.
+    if let Some(accelerator) = &accelerator {
+        new_flex = new_flex.with(hint_label(accelerator), 1.0);
+    }
+
     let button = Button::new(NewWidget::new(new_flex));
-    let props = style_ref.map(build_box_properties).unwrap_or_default();
+    let mut props = style_ref.map(build_box_properties).unwrap_or_default();
+    if variant.is_some()
+        && let Some(background) = style_ref.and_then(|s| s.background.as_ref())
+    {
+        // Keep the variant's background while held; the overlay darkens it.
+        props = props.with(ActiveBackground(Background::Color(color_value_to_peniko(
+            background,
+        ))));
+    }
     let new_widget = NewWidget::new_with(button, None, WidgetOptions::default(), props);
     let widget_id = new_widget.id();
 
+    if variant.is_some() || !feedback_params.is_empty() {
+        let feedback = resolve_feedback(
+            variant,
+            &feedback_params,
+            style_ref.and_then(|s| s.color.as_ref()),
+        );
+        let corner_radius = scaled(style_ref.and_then(|s| s.corner_radius).unwrap_or(0.0));
+        wrappers.feedback = Some((feedback, corner_radius));
+    }
+
     if add_to_parent(
         render_root,
         widget_manager,
         &parent_id,
         new_widget,
         style_ref.and_then(|s| s.flex),
+        wrappers,
     ) {
         widget_manager.register_widget(
             id,
@@ -77,5 +157,122 @@ pub fn create(
                 child_index,
             },
         );
+        if let Some(accelerator) = accelerator {
+            widget_manager.accelerators.insert(widget_id, accelerator);
+        }
+        if let Some(color) = style_ref
+            .and_then(|s| s.color.clone())
+            .filter(|_| variant.is_some())
+        {
+            widget_manager.button_text_colors.insert(widget_id, color);
+        }
+    }
+}
+
+/// Fill the style fields `variant` implies that the caller left unset.
+fn apply_variant(mut style: BoxStyle, variant: ButtonVariant) -> BoxStyle {
+    let (background, border, color) = match variant {
+        ButtonVariant::Filled => (
+            ACCENT,
+            None,
+            ColorValue::Rgba {
+                r: 255,
+                g: 255,
+                b: 255,
+                a: 255,
+            },
+        ),
+        ButtonVariant::Outline => (TRANSPARENT, Some(ACCENT), ACCENT),
+        ButtonVariant::Ghost => (TRANSPARENT, None, ACCENT),
+    };
+    style.background.get_or_insert(background);
+    style.color.get_or_insert(color);
+    match border {
+        Some(border) => {
+            let border = style.border_color.get_or_insert(border).clone();
+            style.hover_border_color.get_or_insert(border);
+            style.border_width.get_or_insert(1.0);
+        }
+        None => {
+            style.border_width.get_or_insert(0.0);
+        }
+    }
+    style.corner_radius.get_or_insert(VARIANT_CORNER_RADIUS);
+    style
+}
+
+/// Work out the overlays for a button. Filled buttons (and buttons without a
+/// variant) lighten with white; outline and ghost buttons have no fill of their
+/// own, so they are tinted with their text color instead.
+fn resolve_feedback(
+    variant: Option<ButtonVariant>,
+    params: &ButtonFeedbackParams,
+    text_color: Option<&ColorValue>,
+) -> Feedback {
+    let lighten = params
+        .hover_lighten
+        .map_or(DEFAULT_HOVER_LIGHTEN, |v| v as f32);
+    let darken = params
+        .press_darken
+        .map_or(DEFAULT_PRESS_DARKEN, |v| v as f32);
+    let hover_base = match variant {
+        Some(ButtonVariant::Outline | ButtonVariant::Ghost) => {
+            text_color.map_or(color_value_to_peniko(&ACCENT), color_value_to_peniko)
+        }
+        _ => Color::WHITE,
+    };
+    Feedback {
+        hover: (lighten > 0.0).then(|| hover_base.with_alpha(lighten)),
+        press: (darken > 0.0).then(|| Color::BLACK.with_alpha(darken)),
+        ripple: params.ripple.unwrap_or(false),
+    }
+}
+
+fn hint_label(accelerator: &Accelerator) -> NewWidget<Label> {
+    let label = Label::new(accelerator.label())
+        .with_style(font_size(HINT_FONT_SIZE))
+        .with_style(StyleProperty::FontStack(FontStack::Single(
+            FontFamily::Generic(GenericFamily::SansSerif),
+        )))
+        .with_text_alignment(Alignment::End);
+    NewWidget::new_with(
+        label,
+        None,
+        WidgetOptions::default(),
+        PropertySet::new().with(ContentColor::new(HINT_COLOR)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_variant_fills_only_unset_fields() {
+        let style = BoxStyle {
+            corner_radius: Some(12.0),
+            ..BoxStyle::default()
+        };
+        let style = apply_variant(style, ButtonVariant::Outline);
+        assert_eq!(style.background, Some(TRANSPARENT));
+        assert_eq!(style.border_color, Some(ACCENT));
+        assert_eq!(style.hover_border_color, Some(ACCENT));
+        assert_eq!(style.border_width, Some(1.0));
+        assert_eq!(style.corner_radius, Some(12.0));
+
+        let feedback = resolve_feedback(
+            Some(ButtonVariant::Ghost),
+            &ButtonFeedbackParams {
+                press_darken: Some(0.0),
+                ..ButtonFeedbackParams::default()
+            },
+            style.color.as_ref(),
+        );
+        assert_eq!(
+            feedback.hover,
+            Some(color_value_to_peniko(&ACCENT).with_alpha(DEFAULT_HOVER_LIGHTEN))
+        );
+        assert_eq!(feedback.press, None);
+        assert!(!feedback.ripple);
     }
 }
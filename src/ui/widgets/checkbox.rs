@@ -5,7 +5,7 @@ use masonry::widgets::Checkbox;
 use crate::ipc::{BoxStyle, WidgetData, WidgetKind};
 use crate::ui::styles::build_box_properties;
 use crate::ui::widget_manager::{WidgetInfo, WidgetManager};
-use crate::ui::widgets::utils::add_to_parent;
+use crate::ui::widgets::utils::{WrapperOptions, add_to_parent};
 
 #[allow(clippy::too_many_arguments)]
 pub fn create(
@@ -17,6 +17,7 @@ pub fn create(
     style: Option<BoxStyle>,
     data: Option<WidgetData>,
     child_index: usize,
+    wrappers: WrapperOptions,
 ) {
     let style_ref = style.as_ref();
     let label_text = text.unwrap_or_default();
@@ -39,6 +40,7 @@ pub fn create(
         &parent_id,
         new_widget,
         style_ref.and_then(|s| s.flex),
+        wrappers,
     ) {
         widget_manager.register_widget(
             id,
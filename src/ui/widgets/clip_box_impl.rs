@@ -0,0 +1,141 @@
+use masonry::accesskit::{Node, Role};
+use masonry::core::{
+    AccessCtx, ChildrenIds, LayoutCtx, MeasureCtx, NewWidget, PaintCtx, PropertiesMut,
+    PropertiesRef, RegisterCtx, Update, UpdateCtx, Widget, WidgetMut, WidgetPod,
+};
+use masonry::kurbo::{Affine, Point, RoundedRect, Size};
+use masonry::vello::Scene;
+
+/// Internal single-child wrapper implementing `overflow: "hidden"`.
+///
+/// The rectangular bounds are clipped through masonry's clip path (which also
+/// limits hit-testing), and a rounded clip layer is pushed in `paint` and popped
+/// in `post_paint` so that descendants follow the corner radius too.
+pub struct ClipBox {
+    child: WidgetPod<dyn Widget>,
+    corner_radius: f64,
+    enabled: bool,
+    /// Whether `paint` pushed a layer that `post_paint` must pop.
+    layer_pushed: bool,
+    last_size: Size,
+}
+
+impl ClipBox {
+    pub fn new(child: NewWidget<impl Widget + ?Sized>, corner_radius: f64) -> Self {
+        Self {
+            child: child.erased().to_pod(),
+            corner_radius: corner_radius.max(0.0),
+            enabled: true,
+            layer_pushed: false,
+            last_size: Size::ZERO,
+        }
+    }
+
+    pub fn set_corner_radius(this: &mut WidgetMut<'_, Self>, corner_radius: f64) {
+        this.widget.corner_radius = corner_radius.max(0.0);
+        this.ctx.request_render();
+    }
+
+    /// Toggle clipping without removing the wrapper (`overflow: "visible"` at runtime).
+    pub fn set_enabled(this: &mut WidgetMut<'_, Self>, enabled: bool) {
+        if this.widget.enabled != enabled {
+            this.widget.enabled = enabled;
+            this.ctx.request_layout();
+        }
+    }
+}
+
+impl Widget for ClipBox {
+    type Action = masonry::core::NoAction;
+
+    fn accepts_pointer_interaction(&self) -> bool {
+        false
+    }
+
+    fn register_children(&mut self, ctx: &mut RegisterCtx<'_>) {
+        ctx.register_child(&mut self.child);
+    }
+
+    fn update(
+        &mut self,
+        _ctx: &mut UpdateCtx<'_>,
+        _props: &mut PropertiesMut<'_>,
+        _event: &Update,
+    ) {
+    }
+
+    fn measure(
+        &mut self,
+        ctx: &mut MeasureCtx<'_>,
+        _props: &PropertiesRef<'_>,
+        axis: masonry::kurbo::Axis,
+        len_req: masonry::layout::LenReq,
+        cross_length: Option<f64>,
+    ) -> f64 {
+        ctx.compute_length(
+            &mut self.child,
+            len_req.into(),
+            masonry::layout::LayoutSize::maybe(axis.cross(), cross_length),
+            axis,
+            cross_length,
+        )
+    }
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx<'_>,
+        _props: &PropertiesRef<'_>,
+        size: masonry::kurbo::Size,
+    ) {
+        self.last_size = size;
+        let child_size = ctx.compute_size(
+            &mut self.child,
+            masonry::layout::SizeDef::fit(size),
+            size.into(),
+        );
+        ctx.run_layout(&mut self.child, child_size);
+        ctx.place_child(&mut self.child, Point::ORIGIN);
+        ctx.derive_baselines(&self.child);
+
+        if self.enabled {
+            ctx.set_clip_path(size.to_rect());
+        } else {
+            ctx.clear_clip_path();
+        }
+    }
+
+    fn paint(&mut self, _ctx: &mut PaintCtx<'_>, _props: &PropertiesRef<'_>, scene: &mut Scene) {
+        self.layer_pushed = self.enabled && self.corner_radius > 0.0;
+        if self.layer_pushed {
+            let shape = RoundedRect::from_rect(self.last_size.to_rect(), self.corner_radius);
+            scene.push_clip_layer(Affine::IDENTITY, &shape);
+        }
+    }
+
+    fn post_paint(
+        &mut self,
+        _ctx: &mut PaintCtx<'_>,
+        _props: &PropertiesRef<'_>,
+        scene: &mut Scene,
+    ) {
+        if self.layer_pushed {
+            scene.pop_layer();
+        }
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::GenericContainer
+    }
+
+    fn accessibility(
+        &mut self,
+        _ctx: &mut AccessCtx<'_>,
+        _props: &PropertiesRef<'_>,
+        _node: &mut Node,
+    ) {
+    }
+
+    fn children_ids(&self) -> ChildrenIds {
+        ChildrenIds::from_slice(&[self.child.id()])
+    }
+}
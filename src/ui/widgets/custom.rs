@@ -6,9 +6,10 @@ use masonry::properties::ContentColor;
 use masonry::widgets::Label;
 
 use crate::ipc::{BoxStyle, WidgetKind};
-use crate::ui::styles::{build_box_properties, build_text_styles};
+use crate::ui::styles::{build_box_properties, build_text_styles, font_size};
+use crate::ui::widget_factory::{CreateContext, factory_for};
 use crate::ui::widget_manager::{WidgetInfo, WidgetManager};
-use crate::ui::widgets::utils::add_to_parent;
+use crate::ui::widgets::utils::{WrapperOptions, add_to_parent};
 
 #[allow(clippy::too_many_arguments)]
 pub fn create(
@@ -20,14 +21,45 @@ pub fn create(
     text: Option<String>,
     style: Option<BoxStyle>,
     child_index: usize,
+    wrappers: WrapperOptions,
 ) {
     let style_ref = style.as_ref();
-    // Custom widgets default to a Label for now
+    if let WidgetKind::Custom(name) = &kind
+        && let Some(factory) = factory_for(name)
+    {
+        let new_widget = factory.create(&CreateContext {
+            id: &id,
+            text: text.as_deref(),
+            style: style_ref,
+        });
+        let widget_id = new_widget.id();
+        if add_to_parent(
+            render_root,
+            widget_manager,
+            &parent_id,
+            new_widget,
+            style_ref.and_then(|s| s.flex),
+            wrappers,
+        ) {
+            widget_manager.register_widget(
+                id,
+                WidgetInfo {
+                    widget_id,
+                    kind,
+                    parent_id,
+                    child_index,
+                },
+            );
+        }
+        return;
+    }
+
+    // Kinds without a registered factory render as a placeholder Label
     let label_text = text.unwrap_or_else(|| format!("[{:?}]", kind));
 
     let text_styles: Vec<StyleProperty> = style_ref.map(build_text_styles).unwrap_or_else(|| {
         vec![
-            StyleProperty::FontSize(16.0),
+            font_size(16.0),
             StyleProperty::FontStack(FontStack::Single(FontFamily::Generic(
                 GenericFamily::SansSerif,
             ))),
@@ -51,6 +83,7 @@ pub fn create(
         &parent_id,
         new_widget,
         style_ref.and_then(|s| s.flex),
+        wrappers,
     ) {
         widget_manager.register_widget(
             id,
@@ -0,0 +1,319 @@
+use masonry::accesskit::{Node, Role};
+use masonry::core::{
+    AccessCtx, ChildrenIds, EventCtx, LayoutCtx, MeasureCtx, PaintCtx, PointerEvent, PropertiesMut,
+    PropertiesRef, RegisterCtx, Update, UpdateCtx, Widget, WidgetMut,
+};
+use masonry::kurbo::{Affine, BezPath, Circle, Line, Rect, Shape, Size, Stroke};
+use masonry::peniko::{Color, Fill};
+use masonry::vello::Scene;
+
+use crate::ipc::{PaintOp, PointerPhase};
+use crate::ui::global_state::ui_scale;
+use crate::ui::styles::{color_value_to_peniko, scaled};
+
+/// Size used when the parent doesn't constrain the widget (an HTML canvas's default).
+const DEFAULT_WIDTH: f64 = 300.0;
+const DEFAULT_HEIGHT: f64 = 150.0;
+/// Curve flattening tolerance when converting shapes to paths.
+const TOLERANCE: f64 = 0.1;
+
+/// Pointer input in local, unzoomed coordinates, forwarded to JS.
+#[derive(Debug, Clone, Copy)]
+pub struct PaintPointer {
+    pub phase: PointerPhase,
+    pub x: f64,
+    pub y: f64,
+}
+
+/// A display list entry with colors resolved and shapes converted to paths.
+#[derive(Debug, Clone)]
+enum Command {
+    Fill(BezPath, Color),
+    Stroke(BezPath, Color, f64),
+    Save,
+    Restore,
+    Transform(Affine),
+}
+
+/// Resolve paint ops into drawable commands. Fails on malformed path data,
+/// naming the offending op.
+fn compile(ops: &[PaintOp]) -> Result<Vec<Command>, String> {
+    let mut commands = Vec::with_capacity(ops.len());
+    for (index, op) in ops.iter().enumerate() {
+        match op {
+            PaintOp::FillRect {
+                x,
+                y,
+                width,
+                height,
+                color,
+                radius,
+            } => {
+                let rect = Rect::new(*x, *y, x + width, y + height).to_rounded_rect(*radius);
+                commands.push(Command::Fill(
+                    rect.to_path(TOLERANCE),
+                    color_value_to_peniko(color),
+                ));
+            }
+            PaintOp::StrokeRect {
+                x,
+                y,
+                width,
+                height,
+                color,
+                line_width,
+                radius,
+            } => {
+                let rect = Rect::new(*x, *y, x + width, y + height).to_rounded_rect(*radius);
+                commands.push(Command::Stroke(
+                    rect.to_path(TOLERANCE),
+                    color_value_to_peniko(color),
+                    *line_width,
+                ));
+            }
+            PaintOp::FillCircle { cx, cy, r, color } => {
+                commands.push(Command::Fill(
+                    Circle::new((*cx, *cy), *r).to_path(TOLERANCE),
+                    color_value_to_peniko(color),
+                ));
+            }
+            PaintOp::StrokeCircle {
+                cx,
+                cy,
+                r,
+                color,
+                line_width,
+            } => {
+                commands.push(Command::Stroke(
+                    Circle::new((*cx, *cy), *r).to_path(TOLERANCE),
+                    color_value_to_peniko(color),
+                    *line_width,
+                ));
+            }
+            PaintOp::Line {
+                x1,
+                y1,
+                x2,
+                y2,
+                color,
+                line_width,
+            } => {
+                commands.push(Command::Stroke(
+                    Line::new((*x1, *y1), (*x2, *y2)).to_path(TOLERANCE),
+                    color_value_to_peniko(color),
+                    *line_width,
+                ));
+            }
+            PaintOp::Path {
+                d,
+                fill,
+                stroke,
+                line_width,
+            } => {
+                let path =
+                    BezPath::from_svg(d).map_err(|e| format!("op {index}: invalid path: {e}"))?;
+                if let Some(fill) = fill {
+                    commands.push(Command::Fill(path.clone(), color_value_to_peniko(fill)));
+                }
+                if let Some(stroke) = stroke {
+                    commands.push(Command::Stroke(
+                        path,
+                        color_value_to_peniko(stroke),
+                        *line_width,
+                    ));
+                }
+            }
+            PaintOp::Save => commands.push(Command::Save),
+            PaintOp::Restore => commands.push(Command::Restore),
+            PaintOp::Translate { x, y } => {
+                commands.push(Command::Transform(Affine::translate((*x, *y))));
+            }
+            PaintOp::Scale { x, y } => {
+                commands.push(Command::Transform(Affine::scale_non_uniform(*x, *y)));
+            }
+            PaintOp::Rotate { radians } => {
+                commands.push(Command::Transform(Affine::rotate(*radians)));
+            }
+        }
+    }
+    Ok(commands)
+}
+
+/// Paints a display list sent from JS and reports pointer input back, for
+/// drawings no built-in widget covers (charts, signatures, diagrams).
+pub struct CustomPaintWidget {
+    commands: Vec<Command>,
+    last_size: Size,
+}
+
+impl CustomPaintWidget {
+    pub fn new(ops: &[PaintOp]) -> Result<Self, String> {
+        Ok(Self {
+            commands: compile(ops)?,
+            last_size: Size::ZERO,
+        })
+    }
+
+    /// Replace the display list. On error the previous drawing is kept.
+    pub fn set_ops(this: &mut WidgetMut<'_, Self>, ops: &[PaintOp]) -> Result<(), String> {
+        this.widget.commands = compile(ops)?;
+        this.ctx.request_render();
+        Ok(())
+    }
+}
+
+impl Widget for CustomPaintWidget {
+    type Action = PaintPointer;
+
+    fn on_pointer_event(
+        &mut self,
+        ctx: &mut EventCtx<'_>,
+        _props: &mut PropertiesMut<'_>,
+        event: &PointerEvent,
+    ) {
+        let (phase, position) = match event {
+            PointerEvent::Down(button_event) => {
+                ctx.capture_pointer();
+                (PointerPhase::Down, button_event.state.position)
+            }
+            PointerEvent::Move(update) => (PointerPhase::Move, update.current.position),
+            PointerEvent::Up(button_event) => (PointerPhase::Up, button_event.state.position),
+            _ => return,
+        };
+        // Ops are drawn zoomed by the UI scale; report positions in op space.
+        let local = ctx.local_position(position);
+        let zoom = ui_scale();
+        ctx.submit_action::<Self::Action>(PaintPointer {
+            phase,
+            x: local.x / zoom,
+            y: local.y / zoom,
+        });
+    }
+
+    fn register_children(&mut self, _ctx: &mut RegisterCtx<'_>) {}
+
+    fn update(
+        &mut self,
+        _ctx: &mut UpdateCtx<'_>,
+        _props: &mut PropertiesMut<'_>,
+        _event: &Update,
+    ) {
+    }
+
+    fn measure(
+        &mut self,
+        _ctx: &mut MeasureCtx<'_>,
+        _props: &PropertiesRef<'_>,
+        axis: masonry::kurbo::Axis,
+        len_req: masonry::layout::LenReq,
+        _cross_length: Option<f64>,
+    ) -> f64 {
+        let preferred = scaled(match axis {
+            masonry::kurbo::Axis::Horizontal => DEFAULT_WIDTH,
+            masonry::kurbo::Axis::Vertical => DEFAULT_HEIGHT,
+        });
+        match len_req {
+            masonry::layout::LenReq::FitContent(space) => preferred.min(space),
+            _ => preferred,
+        }
+    }
+
+    fn layout(
+        &mut self,
+        _ctx: &mut LayoutCtx<'_>,
+        _props: &PropertiesRef<'_>,
+        size: masonry::kurbo::Size,
+    ) {
+        self.last_size = size;
+    }
+
+    fn paint(&mut self, _ctx: &mut PaintCtx<'_>, _props: &PropertiesRef<'_>, scene: &mut Scene) {
+        let bounds = self.last_size.to_rect();
+        scene.push_clip_layer(Affine::IDENTITY, &bounds);
+        let mut transform = Affine::scale(ui_scale());
+        let mut saved = Vec::new();
+        for command in &self.commands {
+            match command {
+                Command::Fill(path, color) => {
+                    scene.fill(Fill::NonZero, transform, *color, None, path);
+                }
+                Command::Stroke(path, color, width) => {
+                    scene.stroke(&Stroke::new(*width), transform, *color, None, path);
+                }
+                Command::Save => saved.push(transform),
+                Command::Restore => {
+                    if let Some(previous) = saved.pop() {
+                        transform = previous;
+                    }
+                }
+                Command::Transform(affine) => transform *= *affine,
+            }
+        }
+        scene.pop_layer();
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::Canvas
+    }
+
+    fn accessibility(
+        &mut self,
+        _ctx: &mut AccessCtx<'_>,
+        _props: &PropertiesRef<'_>,
+        _node: &mut Node,
+    ) {
+    }
+
+    fn children_ids(&self) -> ChildrenIds {
+        ChildrenIds::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ipc::ColorValue;
+
+    fn red() -> ColorValue {
+        ColorValue::Named("red".to_string())
+    }
+
+    #[test]
+    fn test_compile_paths_and_transforms() {
+        let commands = compile(&[
+            PaintOp::Save,
+            PaintOp::Translate { x: 10.0, y: 0.0 },
+            PaintOp::Path {
+                d: "M0 0 L10 10 Z".to_string(),
+                fill: Some(red()),
+                stroke: Some(red()),
+                line_width: 2.0,
+            },
+            PaintOp::Restore,
+        ])
+        .unwrap();
+        assert_eq!(commands.len(), 5);
+        assert!(matches!(commands[2], Command::Fill(..)));
+        assert!(matches!(commands[3], Command::Stroke(_, _, width) if width == 2.0));
+    }
+
+    #[test]
+    fn test_compile_rejects_bad_path() {
+        let err = compile(&[
+            PaintOp::FillCircle {
+                cx: 0.0,
+                cy: 0.0,
+                r: 4.0,
+                color: red(),
+            },
+            PaintOp::Path {
+                d: "M0 0 Q".to_string(),
+                fill: Some(red()),
+                stroke: None,
+                line_width: 1.0,
+            },
+        ])
+        .unwrap_err();
+        assert!(err.starts_with("op 1:"));
+    }
+}
@@ -0,0 +1,79 @@
+use masonry::app::RenderRoot;
+use masonry::core::{NewWidget, PropertySet, StyleProperty, WidgetOptions};
+use masonry::parley::style::{FontFamily, FontStack, FontWeight, GenericFamily};
+use masonry::peniko::Color;
+use masonry::properties::types::CrossAxisAlignment;
+use masonry::properties::{Background, ContentColor, Gap, Padding};
+use masonry::widgets::{Flex, Label, Portal, Prose, TextArea};
+
+use crate::ipc::WidgetKind;
+use crate::ui::widget_manager::{ROOT_FLEX_TAG, WidgetInfo, WidgetManager};
+use crate::ui::widgets::utils::clear_root;
+
+/// JS id under which the overlay is tracked, so a second crash replaces it.
+const OVERLAY_ID: &str = "__error_overlay__";
+
+/// Replace the whole window content with a crash report.
+///
+/// Used when the client runtime hit an uncaught exception: its widget tree can
+/// no longer be trusted, so every root child is dropped instead of overlaying.
+pub fn show(
+    render_root: &mut RenderRoot,
+    widget_manager: &mut WidgetManager,
+    title: String,
+    details: String,
+) {
+    clear_root(render_root, widget_manager);
+
+    let title_label = Label::new(title)
+        .with_style(StyleProperty::FontSize(20.0))
+        .with_style(StyleProperty::FontWeight(FontWeight::BOLD));
+    let title_props =
+        PropertySet::new().with(ContentColor::new(Color::from_rgb8(0xff, 0x6b, 0x6b)));
+
+    // Selectable so the stack can be copied into a bug report; scrolls when long.
+    let details_area = TextArea::new_immutable(&details)
+        .with_style(StyleProperty::FontSize(13.0))
+        .with_style(StyleProperty::FontStack(FontStack::Single(
+            FontFamily::Generic(GenericFamily::Monospace),
+        )));
+    let details_props =
+        PropertySet::new().with(ContentColor::new(Color::from_rgb8(0xe6, 0xe6, 0xe6)));
+
+    let overlay = Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Stretch)
+        .with_fixed(NewWidget::new_with(
+            title_label,
+            None,
+            WidgetOptions::default(),
+            title_props,
+        ))
+        .with(
+            NewWidget::new(Portal::new(NewWidget::new_with(
+                Prose::from_text_area(NewWidget::new(details_area)),
+                None,
+                WidgetOptions::default(),
+                details_props,
+            ))),
+            1.0,
+        );
+    let overlay_props = PropertySet::new()
+        .with(Background::Color(Color::from_rgb8(0x1e, 0x1e, 0x24)))
+        .with(Padding::all(24.0))
+        .with(Gap::new(masonry::layout::Length::px(12.0)));
+    let new_widget = NewWidget::new_with(overlay, None, WidgetOptions::default(), overlay_props);
+    let widget_id = new_widget.id();
+
+    render_root.edit_widget_with_tag(ROOT_FLEX_TAG, |mut flex| {
+        masonry::core::CollectionWidget::add(&mut flex, new_widget.erased(), 1.0);
+    });
+    widget_manager.register_widget(
+        OVERLAY_ID.to_string(),
+        WidgetInfo {
+            widget_id,
+            kind: WidgetKind::Flex,
+            parent_id: None,
+            child_index: 0,
+        },
+    );
+}
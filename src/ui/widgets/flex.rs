@@ -2,13 +2,13 @@ use masonry::app::RenderRoot;
 use masonry::core::{NewWidget, WidgetOptions};
 use masonry::layout::Dim;
 use masonry::properties::Dimensions;
-use masonry::properties::types::{CrossAxisAlignment, MainAxisAlignment};
 use masonry::widgets::Flex;
 
-use crate::ipc::{BoxStyle, CrossAlign, FlexDirection, MainAlign, WidgetData, WidgetKind};
-use crate::ui::styles::build_box_properties;
+use crate::ipc::{BoxStyle, FlexDirection, MainAlign, WidgetData, WidgetKind};
+use crate::ui::global_state::is_right_to_left;
+use crate::ui::styles::{build_box_properties, cross_axis_alignment, main_axis_alignment};
 use crate::ui::widget_manager::{WidgetInfo, WidgetManager};
-use crate::ui::widgets::utils::add_to_parent;
+use crate::ui::widgets::utils::{WrapperOptions, add_to_parent};
 
 #[allow(clippy::too_many_arguments)]
 pub fn create(
@@ -20,10 +20,14 @@ pub fn create(
     style: Option<BoxStyle>,
     _data: Option<WidgetData>,
     child_index: usize,
+    wrappers: WrapperOptions,
 ) {
     let style_ref = style.as_ref();
 
     let dir = style_ref.and_then(|s| s.direction.clone());
+    let is_row = matches!(dir, Some(FlexDirection::Row));
+    let mirrored = is_row && is_right_to_left();
+    let mirrored_column = !is_row && is_right_to_left();
     let mut new_flex = match dir.as_ref() {
         Some(FlexDirection::Row) => Flex::row(),
         _ => Flex::column(),
@@ -31,25 +35,15 @@ pub fn create(
 
     let cross = style_ref.and_then(|s| s.cross_axis_alignment.clone());
     if let Some(ref ca) = cross {
-        new_flex = new_flex.cross_axis_alignment(match ca {
-            CrossAlign::Start => CrossAxisAlignment::Start,
-            CrossAlign::Center => CrossAxisAlignment::Center,
-            CrossAlign::End => CrossAxisAlignment::End,
-            CrossAlign::Fill => CrossAxisAlignment::Stretch,
-            CrossAlign::Baseline => CrossAxisAlignment::Start,
-        });
+        new_flex = new_flex.cross_axis_alignment(cross_axis_alignment(ca, mirrored_column));
     }
 
     let main = style_ref.and_then(|s| s.main_axis_alignment.clone());
     if let Some(ref ma) = main {
-        new_flex = new_flex.main_axis_alignment(match ma {
-            MainAlign::Start => MainAxisAlignment::Start,
-            MainAlign::Center => MainAxisAlignment::Center,
-            MainAlign::End => MainAxisAlignment::End,
-            MainAlign::SpaceBetween => MainAxisAlignment::SpaceBetween,
-            MainAlign::SpaceAround => MainAxisAlignment::SpaceAround,
-            MainAlign::SpaceEvenly => MainAxisAlignment::SpaceEvenly,
-        });
+        new_flex = new_flex.main_axis_alignment(main_axis_alignment(ma, mirrored));
+    } else if mirrored {
+        // Flex packs toward the start by default; a mirrored row packs right.
+        new_flex = new_flex.main_axis_alignment(main_axis_alignment(&MainAlign::Start, true));
     }
 
     let mut props = style_ref.map(build_box_properties).unwrap_or_default();
@@ -74,7 +68,14 @@ pub fn create(
         &parent_id,
         new_widget,
         style_ref.and_then(|s| s.flex),
+        wrappers,
     ) {
+        if mirrored {
+            widget_manager.mirrored_rows.insert(widget_id);
+        }
+        if mirrored_column {
+            widget_manager.mirrored_columns.insert(widget_id);
+        }
         // Flex/Container can have children, so init child count
         widget_manager.register_widget(
             id,
@@ -0,0 +1,529 @@
+use std::time::Duration;
+
+use masonry::accesskit::{Node, Role};
+use masonry::app::RenderRoot;
+use masonry::core::keyboard::{Key, KeyState, Modifiers};
+use masonry::core::{
+    AccessCtx, ChildrenIds, EventCtx, LayoutCtx, MeasureCtx, NewWidget, NoAction, PaintCtx,
+    PointerButton, PointerEvent, PropertiesMut, PropertiesRef, RegisterCtx, TextEvent, Update,
+    UpdateCtx, Widget, WidgetMut, WidgetPod, WidgetTag,
+};
+use masonry::kurbo::Point;
+use masonry::vello::Scene;
+use masonry_winit::app::MasonryUserEvent;
+
+use crate::hooks::{self, FrameStarted};
+use crate::ipc::{ClickInput, KeyModifiers, MouseButton, UiEvent, UiEventSender};
+use crate::ui::accelerators::KeyChord;
+use crate::ui::animations::AnimationFrame;
+use crate::ui::global_state::{get_event_loop_proxy, set_last_click_input};
+use crate::ui::widgets::viewport;
+
+/// Tag for the frame clock wrapping the root Flex.
+pub const FRAME_CLOCK_TAG: WidgetTag<FrameClock> = WidgetTag::named("frame_clock");
+
+/// Default longest gap between two clicks of a double click.
+pub const DEFAULT_DOUBLE_CLICK_MS: f64 = 500.0;
+/// Default hold before a press becomes a long press.
+pub const DEFAULT_LONG_PRESS_MS: f64 = 500.0;
+/// How far (logical px) the pointer may drift and still be the same press.
+const PRESS_SLOP: f64 = 8.0;
+
+/// Internal single-child wrapper at the window root that sends
+/// `UiEvent::FrameTick` on every animation frame while JS is subscribed, so JS
+/// animations step with the display instead of a timer. It also paces the
+/// UI-thread animations started by `AnimateValue`, records the button,
+/// click count and modifiers of input bubbling up for `Click` actions,
+/// recognizes double clicks and long presses (see `PressGesture`), and
+/// reports key presses to the driver to match against accelerators. Runtime
+/// hooks that want frames get them from here too.
+pub struct FrameClock {
+    child: WidgetPod<dyn Widget>,
+    event_sender: UiEventSender,
+    running: bool,
+    /// Ask the driver to step `AnimateValue` animations every frame.
+    animating: bool,
+    presses: PressTracker,
+}
+
+/// Which gesture a `PressGesture` is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PressGestureKind {
+    DoubleClick,
+    LongPress,
+}
+
+/// A double click or long press recognized by the frame clock, at a window
+/// point. Sent to the driver through the event loop proxy, which hands it to
+/// the JS widget under `position`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PressGesture {
+    pub kind: PressGestureKind,
+    pub position: Point,
+    pub button: MouseButton,
+    pub modifiers: KeyModifiers,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Press {
+    /// Milliseconds on the event stamp clock.
+    at: f64,
+    position: Point,
+    button: MouseButton,
+    modifiers: KeyModifiers,
+    /// This press completed a double click, so it can't start another.
+    repeat: bool,
+    long_pressed: bool,
+}
+
+/// Timing state behind `PressGesture`. A threshold of 0 turns its gesture off.
+#[derive(Debug)]
+struct PressTracker {
+    double_click_ms: f64,
+    long_press_ms: f64,
+    held: Option<Press>,
+    last_click: Option<Press>,
+}
+
+fn near(a: Point, b: Point) -> bool {
+    (a - b).hypot() <= PRESS_SLOP
+}
+
+impl PressTracker {
+    fn new() -> Self {
+        Self {
+            double_click_ms: DEFAULT_DOUBLE_CLICK_MS,
+            long_press_ms: DEFAULT_LONG_PRESS_MS,
+            held: None,
+            last_click: None,
+        }
+    }
+
+    fn gesture(kind: PressGestureKind, press: &Press) -> PressGesture {
+        PressGesture {
+            kind,
+            position: press.position,
+            button: press.button,
+            modifiers: press.modifiers,
+        }
+    }
+
+    fn down(&mut self, mut press: Press) -> Option<PressGesture> {
+        let double = self.last_click.take().filter(|last| {
+            self.double_click_ms > 0.0
+                && last.button == press.button
+                && press.at - last.at <= self.double_click_ms
+                && near(last.position, press.position)
+        });
+        press.repeat = double.is_some();
+        self.held = Some(press);
+        double.map(|_| Self::gesture(PressGestureKind::DoubleClick, &press))
+    }
+
+    /// Dragging away cancels both a pending long press and a double click.
+    fn moved(&mut self, position: Point) {
+        if self
+            .held
+            .is_some_and(|press| !near(press.position, position))
+        {
+            self.held = None;
+            self.last_click = None;
+        }
+    }
+
+    fn up(&mut self) {
+        self.last_click = self
+            .held
+            .take()
+            .filter(|press| !press.repeat && !press.long_pressed);
+    }
+
+    fn cancel(&mut self) {
+        self.held = None;
+        self.last_click = None;
+    }
+
+    /// Whether a held press still needs frames to time its long press.
+    fn waiting(&self) -> bool {
+        self.long_press_ms > 0.0
+            && self
+                .held
+                .is_some_and(|press| press.button == MouseButton::Primary && !press.long_pressed)
+    }
+
+    fn tick(&mut self, now: f64) -> Option<PressGesture> {
+        if !self.waiting() {
+            return None;
+        }
+        let press = self.held.as_mut()?;
+        if now - press.at < self.long_press_ms {
+            return None;
+        }
+        press.long_pressed = true;
+        Some(Self::gesture(PressGestureKind::LongPress, press))
+    }
+}
+
+impl FrameClock {
+    pub fn new(child: NewWidget<impl Widget + ?Sized>, event_sender: UiEventSender) -> Self {
+        Self {
+            child: child.erased().to_pod(),
+            event_sender,
+            running: false,
+            animating: false,
+            presses: PressTracker::new(),
+        }
+    }
+
+    /// Change the double click and long press thresholds in milliseconds;
+    /// `None` keeps the current value and 0 turns the gesture off.
+    pub fn set_gesture_timing(
+        this: &mut WidgetMut<'_, Self>,
+        double_click_ms: Option<f64>,
+        long_press_ms: Option<f64>,
+    ) {
+        let presses = &mut this.widget.presses;
+        presses.double_click_ms = double_click_ms.unwrap_or(presses.double_click_ms);
+        presses.long_press_ms = long_press_ms.unwrap_or(presses.long_press_ms);
+    }
+
+    pub fn set_running(this: &mut WidgetMut<'_, Self>, running: bool) {
+        if this.widget.running != running {
+            this.widget.running = running;
+            if running {
+                this.ctx.request_anim_frame();
+            }
+        }
+    }
+
+    pub fn set_animating(this: &mut WidgetMut<'_, Self>, animating: bool) {
+        if this.widget.animating != animating {
+            this.widget.animating = animating;
+            if animating {
+                this.ctx.request_anim_frame();
+            }
+        }
+    }
+}
+
+fn key_modifiers(modifiers: Modifiers) -> KeyModifiers {
+    KeyModifiers {
+        shift: modifiers.contains(Modifiers::SHIFT),
+        ctrl: modifiers.contains(Modifiers::CONTROL),
+        alt: modifiers.contains(Modifiers::ALT),
+        meta: modifiers.contains(Modifiers::META),
+    }
+}
+
+fn send_to_driver(action: impl std::fmt::Debug + Send + 'static) {
+    if let Some((proxy, win_id)) = get_event_loop_proxy() {
+        let _ = proxy.send_event(MasonryUserEvent::AsyncAction(win_id, Box::new(action)));
+    }
+}
+
+/// The accelerator spelling of a key (see `Accelerator::key`); None for
+/// modifier keys and other keys that can't complete a shortcut.
+fn chord_key(key: &Key) -> Option<String> {
+    match key {
+        Key::Character(text) => Some(text.to_lowercase()),
+        Key::Named(named) => {
+            let name = named.to_string();
+            let modifier = matches!(
+                name.as_str(),
+                "Shift" | "Control" | "Alt" | "AltGraph" | "Meta" | "Super"
+            );
+            (!modifier).then_some(name)
+        }
+    }
+}
+
+fn mouse_button(button: Option<PointerButton>) -> MouseButton {
+    match button {
+        Some(PointerButton::Primary) | None => MouseButton::Primary,
+        Some(PointerButton::Secondary) => MouseButton::Secondary,
+        Some(PointerButton::Auxiliary) => MouseButton::Auxiliary,
+        Some(PointerButton::X1) => MouseButton::Back,
+        Some(PointerButton::X2) => MouseButton::Forward,
+        Some(_) => MouseButton::Other,
+    }
+}
+
+/// Edit the window's frame clock. False when the tree has none (the headless
+/// test harness mounts the root Flex directly).
+pub fn edit_frame_clock(
+    render_root: &mut RenderRoot,
+    f: impl FnOnce(&mut WidgetMut<'_, FrameClock>),
+) -> bool {
+    if render_root.get_widget_with_tag(FRAME_CLOCK_TAG).is_none() {
+        return false;
+    }
+    render_root.edit_widget_with_tag(FRAME_CLOCK_TAG, |mut clock| f(&mut clock));
+    true
+}
+
+impl Widget for FrameClock {
+    type Action = NoAction;
+
+    fn accepts_pointer_interaction(&self) -> bool {
+        false
+    }
+
+    fn register_children(&mut self, ctx: &mut RegisterCtx<'_>) {
+        ctx.register_child(&mut self.child);
+    }
+
+    fn on_pointer_event(
+        &mut self,
+        ctx: &mut EventCtx<'_>,
+        _props: &mut PropertiesMut<'_>,
+        event: &PointerEvent,
+    ) {
+        if let PointerEvent::Down(button_event) | PointerEvent::Up(button_event) = event {
+            set_last_click_input(ClickInput {
+                button: Some(mouse_button(button_event.button)),
+                count: button_event.state.count.max(1),
+                modifiers: key_modifiers(button_event.state.modifiers),
+            });
+        }
+        // The clock sits at the window origin, so local points are window points.
+        match event {
+            PointerEvent::Down(button_event) => {
+                let press = Press {
+                    at: self.event_sender.elapsed_ms(),
+                    position: ctx.local_position(button_event.state.position),
+                    button: mouse_button(button_event.button),
+                    modifiers: key_modifiers(button_event.state.modifiers),
+                    repeat: false,
+                    long_pressed: false,
+                };
+                if let Some(gesture) = self.presses.down(press) {
+                    send_to_driver(gesture);
+                }
+                if self.presses.waiting() {
+                    ctx.request_anim_frame();
+                }
+            }
+            PointerEvent::Move(update) => {
+                self.presses
+                    .moved(ctx.local_position(update.current.position));
+            }
+            PointerEvent::Up(_) => self.presses.up(),
+            PointerEvent::Cancel(_) => self.presses.cancel(),
+            _ => {}
+        }
+    }
+
+    fn on_text_event(
+        &mut self,
+        _ctx: &mut EventCtx<'_>,
+        _props: &mut PropertiesMut<'_>,
+        event: &TextEvent,
+    ) {
+        if let TextEvent::Keyboard(key_event) = event
+            && key_event.state == KeyState::Down
+        {
+            let modifiers = key_modifiers(key_event.modifiers);
+            set_last_click_input(ClickInput {
+                button: None,
+                count: 1,
+                modifiers,
+            });
+            if !key_event.repeat
+                && let Some(key) = chord_key(&key_event.key)
+            {
+                send_to_driver(KeyChord { key, modifiers });
+            }
+        }
+    }
+
+    fn on_anim_frame(
+        &mut self,
+        ctx: &mut UpdateCtx<'_>,
+        _props: &mut PropertiesMut<'_>,
+        interval: u64,
+    ) {
+        if hooks::wants_frames() {
+            hooks::frame_started(&FrameStarted {
+                interval: Duration::from_nanos(interval),
+                timestamp: self.event_sender.elapsed_ms(),
+            });
+        }
+        if self.running {
+            // Same clock as the stamp on every event.
+            let timestamp = self.event_sender.elapsed_ms();
+            if self
+                .event_sender
+                .send(UiEvent::FrameTick { timestamp })
+                .is_err()
+            {
+                // Nobody left to tick for.
+                self.running = false;
+            }
+        }
+        if self.animating
+            && let Some((proxy, win_id)) = get_event_loop_proxy()
+        {
+            let _ = proxy.send_event(MasonryUserEvent::AsyncAction(
+                win_id,
+                Box::new(AnimationFrame),
+            ));
+        }
+        if let Some(gesture) = self.presses.tick(self.event_sender.elapsed_ms()) {
+            send_to_driver(gesture);
+        }
+        if self.running || self.animating || self.presses.waiting() || hooks::wants_frames() {
+            ctx.request_anim_frame();
+        }
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx<'_>, _props: &mut PropertiesMut<'_>, event: &Update) {
+        // Hooks registered before the window opened start the frames.
+        if matches!(event, Update::WidgetAdded) && hooks::wants_frames() {
+            ctx.request_anim_frame();
+        }
+    }
+
+    fn measure(
+        &mut self,
+        ctx: &mut MeasureCtx<'_>,
+        _props: &PropertiesRef<'_>,
+        axis: masonry::kurbo::Axis,
+        len_req: masonry::layout::LenReq,
+        cross_length: Option<f64>,
+    ) -> f64 {
+        ctx.compute_length(
+            &mut self.child,
+            len_req.into(),
+            masonry::layout::LayoutSize::maybe(axis.cross(), cross_length),
+            axis,
+            cross_length,
+        )
+    }
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx<'_>,
+        _props: &PropertiesRef<'_>,
+        size: masonry::kurbo::Size,
+    ) {
+        let child_size = ctx.compute_size(
+            &mut self.child,
+            masonry::layout::SizeDef::fit(size),
+            size.into(),
+        );
+        ctx.run_layout(&mut self.child, child_size);
+        ctx.place_child(&mut self.child, Point::ORIGIN);
+        ctx.derive_baselines(&self.child);
+        // Anything may have moved; re-measure observed widgets.
+        viewport::layout_changed();
+    }
+
+    fn paint(&mut self, _ctx: &mut PaintCtx<'_>, _props: &PropertiesRef<'_>, _scene: &mut Scene) {}
+
+    fn accessibility_role(&self) -> Role {
+        Role::GenericContainer
+    }
+
+    fn accessibility(
+        &mut self,
+        _ctx: &mut AccessCtx<'_>,
+        _props: &PropertiesRef<'_>,
+        _node: &mut Node,
+    ) {
+    }
+
+    fn children_ids(&self) -> ChildrenIds {
+        ChildrenIds::from_slice(&[self.child.id()])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use masonry::kurbo::Vec2;
+
+    #[test]
+    fn test_click_input_mapping() {
+        assert_eq!(mouse_button(None), MouseButton::Primary);
+        assert_eq!(
+            mouse_button(Some(PointerButton::Secondary)),
+            MouseButton::Secondary
+        );
+        assert_eq!(mouse_button(Some(PointerButton::X1)), MouseButton::Back);
+        assert_eq!(
+            key_modifiers(Modifiers::SHIFT | Modifiers::META),
+            KeyModifiers {
+                shift: true,
+                meta: true,
+                ..KeyModifiers::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_chord_key_spelling() {
+        use masonry::core::keyboard::NamedKey;
+
+        assert_eq!(
+            chord_key(&Key::Character("S".into())),
+            Some("s".to_string())
+        );
+        assert_eq!(
+            chord_key(&Key::Named(NamedKey::ArrowUp)),
+            Some("ArrowUp".to_string())
+        );
+        assert_eq!(chord_key(&Key::Named(NamedKey::Shift)), None);
+    }
+
+    fn press(at: f64, x: f64) -> Press {
+        Press {
+            at,
+            position: Point::new(x, 10.0),
+            button: MouseButton::Primary,
+            modifiers: KeyModifiers::default(),
+            repeat: false,
+            long_pressed: false,
+        }
+    }
+
+    #[test]
+    fn test_double_click_needs_a_quick_nearby_second_press() {
+        let mut presses = PressTracker::new();
+        assert_eq!(presses.down(press(0.0, 10.0)), None);
+        presses.up();
+        let double = presses.down(press(200.0, 14.0)).unwrap();
+        assert_eq!(double.kind, PressGestureKind::DoubleClick);
+        presses.up();
+        // A third click starts over rather than doubling again.
+        assert_eq!(presses.down(press(300.0, 14.0)), None);
+        presses.up();
+
+        // Too slow, then too far.
+        assert_eq!(presses.down(press(1000.0, 14.0)), None);
+        presses.up();
+        assert_eq!(presses.down(press(1200.0, 40.0)), None);
+        presses.up();
+    }
+
+    #[test]
+    fn test_long_press_fires_once_unless_moved() {
+        let mut presses = PressTracker::new();
+        presses.down(press(0.0, 10.0));
+        assert!(presses.waiting());
+        assert_eq!(presses.tick(499.0), None);
+        let long = presses.tick(500.0).unwrap();
+        assert_eq!(long.kind, PressGestureKind::LongPress);
+        assert_eq!(presses.tick(600.0), None);
+        presses.up();
+        // A long press doesn't count as the first click of a double click.
+        assert_eq!(presses.down(press(700.0, 10.0)), None);
+
+        presses.moved(Point::new(10.0, 10.0) + Vec2::new(20.0, 0.0));
+        assert!(!presses.waiting());
+        assert_eq!(presses.tick(2000.0), None);
+
+        presses.long_press_ms = 0.0;
+        presses.down(press(3000.0, 10.0));
+        assert!(!presses.waiting());
+    }
+}
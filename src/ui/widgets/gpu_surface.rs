@@ -0,0 +1,77 @@
+use masonry::app::RenderRoot;
+use masonry::core::{NewWidget, WidgetOptions};
+
+use crate::ipc::{BoxStyle, WidgetData, WidgetKind};
+use crate::ui::gpu_renderer::{GpuRenderer, ShaderRenderer, renderer_for};
+use crate::ui::styles::build_box_properties;
+use crate::ui::widget_manager::{WidgetInfo, WidgetManager};
+use crate::ui::widgets::gpu_surface_impl::GpuSurfaceWidget;
+use crate::ui::widgets::utils::{WrapperOptions, add_to_parent};
+
+/// Drawn when neither a shader nor a renderer is given: a transparent surface.
+const BLANK_SHADER: &str = r#"
+@fragment
+fn fs_main(@builtin(position) pos: vec4<f32>) -> @location(0) vec4<f32> {
+    return vec4<f32>(0.0);
+}
+"#;
+
+#[allow(clippy::too_many_arguments)]
+pub fn create(
+    render_root: &mut RenderRoot,
+    widget_manager: &mut WidgetManager,
+    id: String,
+    parent_id: Option<String>,
+    style: Option<BoxStyle>,
+    data: Option<WidgetData>,
+    child_index: usize,
+    wrappers: WrapperOptions,
+) {
+    let style_ref = style.as_ref();
+
+    let (shader, renderer_name, uniforms, animate) = match data {
+        Some(WidgetData::GpuSurface {
+            shader,
+            renderer,
+            uniforms,
+            animate,
+        }) => (shader, renderer, uniforms, animate),
+        _ => (None, None, Vec::new(), false),
+    };
+    let renderer: Box<dyn GpuRenderer> = match renderer_name {
+        Some(name) => renderer_for(&name).unwrap_or_else(|| {
+            eprintln!(
+                "[UI] GpuSurface widget '{}' uses unregistered renderer '{}'",
+                id, name
+            );
+            Box::new(ShaderRenderer::new(BLANK_SHADER))
+        }),
+        None => Box::new(ShaderRenderer::new(
+            shader.as_deref().unwrap_or(BLANK_SHADER),
+        )),
+    };
+
+    let surface = GpuSurfaceWidget::new(renderer, uniforms, animate);
+    let props = style_ref.map(build_box_properties).unwrap_or_default();
+    let new_widget = NewWidget::new_with(surface, None, WidgetOptions::default(), props);
+    let widget_id = new_widget.id();
+
+    if add_to_parent(
+        render_root,
+        widget_manager,
+        &parent_id,
+        new_widget,
+        style_ref.and_then(|s| s.flex),
+        wrappers,
+    ) {
+        widget_manager.register_widget(
+            id,
+            WidgetInfo {
+                widget_id,
+                kind: WidgetKind::GpuSurface,
+                parent_id: parent_id.clone(),
+                child_index,
+            },
+        );
+    }
+}
@@ -0,0 +1,284 @@
+use std::sync::Arc;
+
+use masonry::accesskit::{Node, Role};
+use masonry::core::{
+    AccessCtx, ChildrenIds, ErasedAction, EventCtx, LayoutCtx, MeasureCtx, PaintCtx, PointerEvent,
+    PropertiesMut, PropertiesRef, RegisterCtx, Update, UpdateCtx, Widget, WidgetId, WidgetMut,
+};
+use masonry::kurbo::{Affine, Size};
+use masonry::peniko::{ImageBrush, ImageData};
+use masonry::vello::Scene;
+use masonry::vello::wgpu;
+use masonry_winit::app::MasonryUserEvent;
+
+use crate::ipc::PointerPhase;
+use crate::ui::global_state::{ClonedWgpu, get_event_loop_proxy, get_wgpu_context};
+use crate::ui::gpu_renderer::{GpuRenderer, RenderTarget, ShaderRenderer};
+use crate::ui::styles::scaled;
+use crate::ui::widgets::custom_paint_impl::PaintPointer;
+use crate::ui::widgets::video_widget_impl::{VideoAction, create_unique_overlay_key};
+
+const DEFAULT_WIDTH: f64 = 300.0;
+const DEFAULT_HEIGHT: f64 = 150.0;
+
+/// Sent after `VideoAction::SetOverride` so the surface repaints once the
+/// driver has swapped its texture in.
+#[derive(Clone, Debug)]
+pub enum GpuSurfaceAction {
+    OverrideReady(WidgetId),
+}
+
+/// The texture a surface renders into, drawn in place of its overlay key.
+struct Surface {
+    texture: Arc<wgpu::Texture>,
+    view: wgpu::TextureView,
+    overlay_key: ImageData,
+    image: ImageBrush,
+}
+
+impl Surface {
+    fn create(wgpu_cx: &ClonedWgpu, width: u32, height: u32, widget_id: WidgetId) -> Self {
+        let texture = Arc::new(wgpu_cx.device.create_texture(&wgpu::TextureDescriptor {
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                | wgpu::TextureUsages::TEXTURE_BINDING
+                | wgpu::TextureUsages::COPY_SRC
+                | wgpu::TextureUsages::COPY_DST,
+            label: Some("GpuSurface_Texture"),
+            view_formats: &[],
+        }));
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let overlay_key = create_unique_overlay_key(width, height);
+
+        if let Some((proxy, win_id)) = get_event_loop_proxy() {
+            let action = VideoAction::SetOverride(overlay_key.clone(), texture.clone());
+            let _ = proxy.send_event(MasonryUserEvent::AsyncAction(win_id, Box::new(action)));
+            let ready: ErasedAction = Box::new(GpuSurfaceAction::OverrideReady(widget_id));
+            let _ = proxy.send_event(MasonryUserEvent::AsyncAction(win_id, ready));
+        }
+
+        Self {
+            texture,
+            view,
+            image: ImageBrush::from(overlay_key.clone()),
+            overlay_key,
+        }
+    }
+
+    fn release(self) {
+        if let Some((proxy, win_id)) = get_event_loop_proxy() {
+            let action = VideoAction::ClearOverride(self.overlay_key);
+            let _ = proxy.send_event(MasonryUserEvent::AsyncAction(win_id, Box::new(action)));
+        }
+    }
+}
+
+/// A widget backed by a wgpu texture that a `GpuRenderer` draws into on every
+/// paint: a WGSL shader with uniforms from JS, or a renderer registered by a
+/// Rust plugin. For visualizations and games that outgrow `CustomPaint`.
+pub struct GpuSurfaceWidget {
+    renderer: Box<dyn GpuRenderer>,
+    uniforms: Vec<f32>,
+    /// Repaint every frame and advance `time`.
+    animate: bool,
+    time: f64,
+    wgpu: Option<ClonedWgpu>,
+    surface: Option<Surface>,
+    /// Last render error, so a failing renderer is reported once, not per frame.
+    last_error: Option<String>,
+    last_size: Size,
+}
+
+impl GpuSurfaceWidget {
+    pub fn new(renderer: Box<dyn GpuRenderer>, uniforms: Vec<f32>, animate: bool) -> Self {
+        Self {
+            renderer,
+            uniforms,
+            animate,
+            time: 0.0,
+            wgpu: None,
+            surface: None,
+            last_error: None,
+            last_size: Size::ZERO,
+        }
+    }
+
+    pub fn set_uniforms(this: &mut WidgetMut<'_, Self>, values: Vec<f32>) {
+        this.widget.uniforms = values;
+        this.ctx.request_render();
+    }
+
+    /// Replace the shader. Before wgpu is up there is nothing to compile
+    /// against, so the source is kept and compiled on first paint.
+    pub fn set_shader(this: &mut WidgetMut<'_, Self>, wgsl: &str) -> Result<(), String> {
+        match get_wgpu_context() {
+            Some(wgpu_cx) => this.widget.renderer.set_shader(&wgpu_cx.device, wgsl)?,
+            None => this.widget.renderer = Box::new(ShaderRenderer::new(wgsl)),
+        }
+        this.widget.last_error = None;
+        this.ctx.request_render();
+        Ok(())
+    }
+
+    pub fn on_override_ready(this: &mut WidgetMut<'_, Self>) {
+        this.ctx.request_render();
+    }
+}
+
+impl Drop for GpuSurfaceWidget {
+    fn drop(&mut self) {
+        if let Some(surface) = self.surface.take() {
+            surface.release();
+        }
+    }
+}
+
+impl Widget for GpuSurfaceWidget {
+    type Action = PaintPointer;
+
+    fn on_pointer_event(
+        &mut self,
+        ctx: &mut EventCtx<'_>,
+        _props: &mut PropertiesMut<'_>,
+        event: &PointerEvent,
+    ) {
+        let (phase, position) = match event {
+            PointerEvent::Down(button_event) => {
+                ctx.capture_pointer();
+                (PointerPhase::Down, button_event.state.position)
+            }
+            PointerEvent::Move(update) => (PointerPhase::Move, update.current.position),
+            PointerEvent::Up(button_event) => (PointerPhase::Up, button_event.state.position),
+            _ => return,
+        };
+        // Surface pixels match layout units, so positions map straight onto
+        // `surface.resolution`.
+        let local = ctx.local_position(position);
+        ctx.submit_action::<Self::Action>(PaintPointer {
+            phase,
+            x: local.x,
+            y: local.y,
+        });
+    }
+
+    fn register_children(&mut self, _ctx: &mut RegisterCtx<'_>) {}
+
+    fn on_anim_frame(
+        &mut self,
+        ctx: &mut UpdateCtx<'_>,
+        _props: &mut PropertiesMut<'_>,
+        interval: u64,
+    ) {
+        if !self.animate {
+            return;
+        }
+        self.time += interval as f64 / 1_000_000_000.0;
+        ctx.request_paint_only();
+        ctx.request_anim_frame();
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx<'_>, _props: &mut PropertiesMut<'_>, event: &Update) {
+        if event == &Update::WidgetAdded && self.animate {
+            ctx.request_anim_frame();
+        }
+    }
+
+    fn measure(
+        &mut self,
+        _ctx: &mut MeasureCtx<'_>,
+        _props: &PropertiesRef<'_>,
+        axis: masonry::kurbo::Axis,
+        len_req: masonry::layout::LenReq,
+        _cross_length: Option<f64>,
+    ) -> f64 {
+        let preferred = scaled(match axis {
+            masonry::kurbo::Axis::Horizontal => DEFAULT_WIDTH,
+            masonry::kurbo::Axis::Vertical => DEFAULT_HEIGHT,
+        });
+        match len_req {
+            masonry::layout::LenReq::FitContent(space) => preferred.min(space),
+            _ => preferred,
+        }
+    }
+
+    fn layout(
+        &mut self,
+        _ctx: &mut LayoutCtx<'_>,
+        _props: &PropertiesRef<'_>,
+        size: masonry::kurbo::Size,
+    ) {
+        self.last_size = size;
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx<'_>, _props: &PropertiesRef<'_>, scene: &mut Scene) {
+        let width = self.last_size.width.ceil() as u32;
+        let height = self.last_size.height.ceil() as u32;
+        if width == 0 || height == 0 {
+            return;
+        }
+        if self.wgpu.is_none() {
+            self.wgpu = get_wgpu_context();
+        }
+        let Some(wgpu_cx) = self.wgpu.as_ref() else {
+            return;
+        };
+
+        // A new size needs a new texture and a new overlay key.
+        if self
+            .surface
+            .as_ref()
+            .is_none_or(|s| s.texture.width() != width || s.texture.height() != height)
+        {
+            if let Some(old) = self.surface.take() {
+                old.release();
+            }
+            self.surface = Some(Surface::create(wgpu_cx, width, height, ctx.widget_id()));
+        }
+        let Some(surface) = &self.surface else {
+            return;
+        };
+
+        let target = RenderTarget {
+            device: &wgpu_cx.device,
+            queue: &wgpu_cx.queue,
+            texture: &surface.texture,
+            view: &surface.view,
+            uniforms: &self.uniforms,
+            time: self.time,
+        };
+        match self.renderer.render(&target) {
+            Ok(()) => self.last_error = None,
+            Err(err) => {
+                if self.last_error.as_ref() != Some(&err) {
+                    eprintln!("[UI] GpuSurface render failed: {err}");
+                    self.last_error = Some(err);
+                }
+            }
+        }
+        scene.draw_image(&surface.image, Affine::IDENTITY);
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::Canvas
+    }
+
+    fn accessibility(
+        &mut self,
+        _ctx: &mut AccessCtx<'_>,
+        _props: &PropertiesRef<'_>,
+        _node: &mut Node,
+    ) {
+    }
+
+    fn children_ids(&self) -> ChildrenIds {
+        ChildrenIds::new()
+    }
+}
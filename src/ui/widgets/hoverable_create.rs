@@ -4,7 +4,7 @@ use masonry::core::{NewWidget, WidgetOptions};
 use crate::ipc::{BoxStyle, WidgetKind};
 use crate::ui::styles::build_box_properties;
 use crate::ui::widget_manager::{WidgetInfo, WidgetManager};
-use crate::ui::widgets::utils::add_to_parent;
+use crate::ui::widgets::utils::{WrapperOptions, add_to_parent};
 
 use super::hoverable::Hoverable;
 
@@ -15,6 +15,7 @@ pub fn create(
     parent_id: Option<String>,
     style: Option<BoxStyle>,
     child_index: usize,
+    wrappers: WrapperOptions,
 ) {
     let style_ref = style.as_ref();
 
@@ -30,6 +31,7 @@ pub fn create(
         &parent_id,
         new_widget,
         style_ref.and_then(|s| s.flex),
+        wrappers,
     ) {
         widget_manager.register_widget(
             id,
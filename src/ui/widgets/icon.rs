@@ -0,0 +1,73 @@
+use masonry::app::RenderRoot;
+use masonry::core::{NewWidget, WidgetOptions};
+
+use crate::ipc::{BoxStyle, WidgetData, WidgetKind};
+use crate::ui::styles::build_box_properties;
+use crate::ui::widget_manager::{WidgetInfo, WidgetManager};
+use crate::ui::widgets::svg_widget_impl::SvgWidget;
+use crate::ui::widgets::utils::{WrapperOptions, add_to_parent};
+
+/// Resolve an Icon's `text` to SVG markup: a `pack:name` reference, or raw
+/// markup for callers still using `icon` as an alias of `svg`.
+pub fn resolve_source(widget_manager: &WidgetManager, reference: &str) -> Option<String> {
+    if reference.trim_start().starts_with('<') {
+        return Some(reference.to_string());
+    }
+    widget_manager.icons.resolve(reference)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn create(
+    render_root: &mut RenderRoot,
+    widget_manager: &mut WidgetManager,
+    id: String,
+    parent_id: Option<String>,
+    text: Option<String>,
+    style: Option<BoxStyle>,
+    data: Option<WidgetData>,
+    child_index: usize,
+    wrappers: WrapperOptions,
+) {
+    let style_ref = style.as_ref();
+
+    let raw_svg = match data {
+        Some(WidgetData::Icon { svg_data }) => svg_data,
+        _ => None,
+    };
+    let reference = text.unwrap_or_default();
+    let svg_source = raw_svg
+        .or_else(|| resolve_source(widget_manager, &reference))
+        .unwrap_or_else(|| {
+            eprintln!("[UI] Icon widget '{}': unknown icon '{}'", id, reference);
+            // Keep an empty 24x24 slot so layout is stable and SetWidgetText can fix it later.
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="24" height="24"/>"#.to_string()
+        });
+
+    let props = style_ref.map(build_box_properties).unwrap_or_default();
+    let new_widget = NewWidget::new_with(
+        SvgWidget::new(svg_source),
+        None,
+        WidgetOptions::default(),
+        props,
+    );
+    let widget_id = new_widget.id();
+
+    if add_to_parent(
+        render_root,
+        widget_manager,
+        &parent_id,
+        new_widget,
+        style_ref.and_then(|s| s.flex),
+        wrappers,
+    ) {
+        widget_manager.register_widget(
+            id,
+            WidgetInfo {
+                widget_id,
+                kind: WidgetKind::Icon,
+                parent_id: parent_id.clone(),
+                child_index,
+            },
+        );
+    }
+}
@@ -1,16 +1,16 @@
 use masonry::app::RenderRoot;
 use masonry::core::{NewWidget, WidgetId, WidgetOptions};
 use masonry::peniko::{ImageAlphaType, ImageData, ImageFormat};
-use masonry::properties::ObjectFit;
-use masonry::widgets::Image;
 
 use crate::ipc::{BoxStyle, WidgetData, WidgetKind};
 use crate::ui::styles::build_box_properties;
 use crate::ui::widget_manager::{WidgetInfo, WidgetManager};
-use crate::ui::widgets::utils::add_to_parent;
+use crate::ui::widgets::image_placement::{ImagePlacement, parse_fit};
+use crate::ui::widgets::image_widget_impl::ImageWidget;
+use crate::ui::widgets::utils::{WrapperOptions, add_to_parent};
 
 /// Decode raw file bytes (PNG/JPEG/WebP/etc.) into masonry ImageData
-fn decode_image_bytes(data: &[u8]) -> Option<ImageData> {
+pub fn decode_image_bytes(data: &[u8]) -> Option<ImageData> {
     match image::load_from_memory(data) {
         Ok(img) => {
             let rgba = img.to_rgba8();
@@ -30,18 +30,7 @@ fn decode_image_bytes(data: &[u8]) -> Option<ImageData> {
     }
 }
 
-/// Parse object-fit string into masonry ObjectFit
-fn parse_object_fit(s: &str) -> ObjectFit {
-    match s.to_lowercase().as_str() {
-        "contain" => ObjectFit::Contain,
-        "cover" => ObjectFit::Cover,
-        "fill" => ObjectFit::Stretch,
-        "none" => ObjectFit::None,
-        "scale-down" | "scaledown" | "scale_down" => ObjectFit::ScaleDown,
-        _ => ObjectFit::Contain,
-    }
-}
-
+#[allow(clippy::too_many_arguments)]
 pub fn create(
     render_root: &mut RenderRoot,
     widget_manager: &mut WidgetManager,
@@ -50,10 +39,15 @@ pub fn create(
     style: Option<BoxStyle>,
     data: Option<WidgetData>,
     child_index: usize,
+    wrappers: WrapperOptions,
 ) {
     // Extract image-specific data from WidgetData
-    let (image_data_bytes, object_fit_str) = match &data {
-        Some(WidgetData::Image { data, object_fit }) => (data.as_slice(), object_fit.clone()),
+    let (image_data_bytes, object_fit_str, alt) = match &data {
+        Some(WidgetData::Image {
+            data,
+            object_fit,
+            alt,
+        }) => (data.as_slice(), object_fit.clone(), alt.clone()),
         _ => {
             eprintln!(
                 "[UI] Image widget '{}' missing image data in WidgetData",
@@ -71,21 +65,21 @@ pub fn create(
         }
     };
 
-    let object_fit = object_fit_str
-        .as_deref()
-        .map(parse_object_fit)
-        .unwrap_or(ObjectFit::Contain);
-
     let style_ref = style.as_ref();
-    let mut props = style_ref.map(build_box_properties).unwrap_or_default();
-    props = props.with(object_fit);
+    let mut placement = ImagePlacement {
+        fit: object_fit_str.as_deref().map(parse_fit).unwrap_or_default(),
+        ..ImagePlacement::default()
+    };
+    if let Some(style) = style_ref {
+        style_placement(&mut placement, style);
+    }
+    let props = style_ref.map(build_box_properties).unwrap_or_default();
 
-    let new_widget = NewWidget::new_with(
-        Image::new(image_data),
-        None,
-        WidgetOptions::default(),
-        props,
-    );
+    let mut image = ImageWidget::new(image_data, placement);
+    if let Some(alt) = alt.filter(|alt| !alt.is_empty()) {
+        image = image.with_alt_text(alt);
+    }
+    let new_widget = NewWidget::new_with(image, None, WidgetOptions::default(), props);
     let widget_id = new_widget.id();
 
     if add_to_parent(
@@ -94,6 +88,7 @@ pub fn create(
         &parent_id,
         new_widget,
         style_ref.and_then(|s| s.flex),
+        wrappers,
     ) {
         widget_manager.register_widget(
             id,
@@ -118,7 +113,27 @@ pub fn update_data(render_root: &mut RenderRoot, widget_id: WidgetId, data: &[u8
     };
 
     render_root.edit_widget(widget_id, |mut widget| {
-        let mut img = widget.downcast::<Image>();
-        Image::set_image_data(&mut img, image_data);
+        let mut img = widget.downcast::<ImageWidget>();
+        ImageWidget::set_image_data(&mut img, image_data);
+    });
+}
+
+/// Apply the image fields `style` sets to `placement`.
+pub fn style_placement(placement: &mut ImagePlacement, style: &BoxStyle) {
+    placement.update(
+        style.object_fit,
+        style.object_position.as_deref(),
+        style.repeat,
+    );
+}
+
+/// Restyle an existing Image widget: `objectFit`, `objectPosition` and
+/// `repeat` take effect in place.
+pub fn update_style(render_root: &mut RenderRoot, widget_id: WidgetId, style: &BoxStyle) {
+    render_root.edit_widget(widget_id, |mut widget| {
+        let mut img = widget.downcast::<ImageWidget>();
+        let mut placement = img.widget.placement();
+        style_placement(&mut placement, style);
+        ImageWidget::set_placement(&mut img, placement);
     });
 }
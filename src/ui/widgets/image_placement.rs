@@ -0,0 +1,231 @@
+// Image Placement
+// Where the copies of an image go inside a box, following CSS object-fit,
+// object-position and background-repeat. The caller clips to the box.
+
+use masonry::kurbo::{Affine, Size};
+
+use crate::ipc::{ImageRepeat, ObjectFitValue};
+
+/// Past this many copies (a tiny tile over a large box) only the positioned
+/// one is drawn.
+const MAX_TILES: usize = 4096;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImagePlacement {
+    pub fit: ObjectFitValue,
+    /// Share of the free space to the left of and above the image; 0.5
+    /// centers it
+    pub position: (f64, f64),
+    pub repeat: ImageRepeat,
+}
+
+impl Default for ImagePlacement {
+    fn default() -> Self {
+        Self {
+            fit: ObjectFitValue::Contain,
+            position: (0.5, 0.5),
+            repeat: ImageRepeat::NoRepeat,
+        }
+    }
+}
+
+impl ImagePlacement {
+    /// Take the parts that are set. An invalid position is logged and kept
+    /// as it was.
+    pub fn update(
+        &mut self,
+        fit: Option<ObjectFitValue>,
+        position: Option<&str>,
+        repeat: Option<ImageRepeat>,
+    ) {
+        if let Some(fit) = fit {
+            self.fit = fit;
+        }
+        if let Some(position) = position {
+            match parse_position(position) {
+                Some(parsed) => self.position = parsed,
+                None => eprintln!("[UI] Ignoring invalid image position '{position}'"),
+            }
+        }
+        if let Some(repeat) = repeat {
+            self.repeat = repeat;
+        }
+    }
+}
+
+/// Parse an `objectFit` string; anything unknown contains.
+pub fn parse_fit(fit: &str) -> ObjectFitValue {
+    match fit.to_lowercase().as_str() {
+        "cover" => ObjectFitValue::Cover,
+        "fill" | "stretch" => ObjectFitValue::Fill,
+        "none" => ObjectFitValue::None,
+        "scale-down" | "scaledown" | "scale_down" => ObjectFitValue::ScaleDown,
+        _ => ObjectFitValue::Contain,
+    }
+}
+
+enum Offset {
+    X(f64),
+    Y(f64),
+    /// `center` or a percentage, which either axis can take
+    Either(f64),
+}
+
+fn parse_offset(token: &str) -> Option<Offset> {
+    Some(match token {
+        "left" => Offset::X(0.0),
+        "right" => Offset::X(1.0),
+        "top" => Offset::Y(0.0),
+        "bottom" => Offset::Y(1.0),
+        "center" => Offset::Either(0.5),
+        _ => {
+            let percent: f64 = token.strip_suffix('%')?.trim().parse().ok()?;
+            Offset::Either(percent / 100.0)
+        }
+    })
+}
+
+/// Parse a CSS position such as "center", "top left", "right 25%" or
+/// "25% 75%" into shares of the free space (x, y). An axis left out is
+/// centered.
+pub fn parse_position(position: &str) -> Option<(f64, f64)> {
+    let lowered = position.to_lowercase();
+    let offsets: Vec<Offset> = lowered
+        .split_whitespace()
+        .map(parse_offset)
+        .collect::<Option<_>>()?;
+    match offsets.as_slice() {
+        [Offset::X(x)] | [Offset::Either(x)] => Some((*x, 0.5)),
+        [Offset::Y(y)] => Some((0.5, *y)),
+        [Offset::X(x), Offset::Y(y)]
+        | [Offset::Y(y), Offset::X(x)]
+        | [Offset::X(x), Offset::Either(y)]
+        | [Offset::Either(y), Offset::X(x)]
+        | [Offset::Either(x), Offset::Y(y)]
+        | [Offset::Y(y), Offset::Either(x)]
+        | [Offset::Either(x), Offset::Either(y)] => Some((*x, *y)),
+        _ => None,
+    }
+}
+
+/// Size of one copy of an `image`-sized image fitted into `size`.
+fn fitted(fit: ObjectFitValue, size: Size, image: Size) -> Size {
+    let contain = (size.width / image.width).min(size.height / image.height);
+    let cover = (size.width / image.width).max(size.height / image.height);
+    match fit {
+        ObjectFitValue::Contain => image * contain,
+        ObjectFitValue::Cover => image * cover,
+        ObjectFitValue::Fill => size,
+        ObjectFitValue::None => image,
+        ObjectFitValue::ScaleDown => image * contain.min(1.0),
+    }
+}
+
+/// Where copies start along one axis: at `origin`, or every `step` across
+/// `0..extent` when repeating.
+fn offsets(origin: f64, step: f64, extent: f64, repeat: bool) -> Vec<f64> {
+    if !repeat {
+        return vec![origin];
+    }
+    let first = origin - (origin / step).ceil() * step;
+    let count = ((extent - first) / step).ceil().max(1.0);
+    if count > MAX_TILES as f64 {
+        return vec![origin];
+    }
+    (0..count as usize)
+        .map(|index| first + index as f64 * step)
+        .collect()
+}
+
+/// The transform of each copy of an `image`-sized image drawn into a box of
+/// `size`; empty when either is empty.
+pub fn tiles(placement: &ImagePlacement, size: Size, image: Size) -> Vec<Affine> {
+    if image.width <= 0.0 || image.height <= 0.0 || size.width <= 0.0 || size.height <= 0.0 {
+        return Vec::new();
+    }
+    let tile = fitted(placement.fit, size, image);
+    if tile.width <= 0.0 || tile.height <= 0.0 {
+        return Vec::new();
+    }
+    let origin_x = (size.width - tile.width) * placement.position.0;
+    let origin_y = (size.height - tile.height) * placement.position.1;
+    let (repeat_x, repeat_y) = match placement.repeat {
+        ImageRepeat::NoRepeat => (false, false),
+        ImageRepeat::Repeat => (true, true),
+        ImageRepeat::RepeatX => (true, false),
+        ImageRepeat::RepeatY => (false, true),
+    };
+    let mut xs = offsets(origin_x, tile.width, size.width, repeat_x);
+    let mut ys = offsets(origin_y, tile.height, size.height, repeat_y);
+    if xs.len() * ys.len() > MAX_TILES {
+        xs = vec![origin_x];
+        ys = vec![origin_y];
+    }
+    let scale = Affine::scale_non_uniform(tile.width / image.width, tile.height / image.height);
+    ys.iter()
+        .flat_map(|&y| xs.iter().map(move |&x| Affine::translate((x, y)) * scale))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_position() {
+        assert_eq!(parse_position("center"), Some((0.5, 0.5)));
+        assert_eq!(parse_position("top left"), Some((0.0, 0.0)));
+        assert_eq!(parse_position("left top"), Some((0.0, 0.0)));
+        assert_eq!(parse_position("Bottom"), Some((0.5, 1.0)));
+        assert_eq!(parse_position("right 25%"), Some((1.0, 0.25)));
+        assert_eq!(parse_position("25% 75%"), Some((0.25, 0.75)));
+        assert_eq!(parse_position("left right"), None);
+        assert_eq!(parse_position("middle"), None);
+        assert_eq!(parse_position(""), None);
+    }
+
+    #[test]
+    fn test_single_copy_fits_and_positions() {
+        let image = Size::new(100.0, 50.0);
+        let size = Size::new(200.0, 200.0);
+        let contain = ImagePlacement::default();
+        assert_eq!(
+            tiles(&contain, size, image),
+            vec![Affine::translate((0.0, 50.0)) * Affine::scale_non_uniform(2.0, 2.0)]
+        );
+        let top_left_cover = ImagePlacement {
+            fit: ObjectFitValue::Cover,
+            position: (0.0, 0.0),
+            ..ImagePlacement::default()
+        };
+        assert_eq!(
+            tiles(&top_left_cover, size, image),
+            vec![Affine::translate((0.0, 0.0)) * Affine::scale_non_uniform(4.0, 4.0)]
+        );
+        assert!(tiles(&contain, Size::ZERO, image).is_empty());
+    }
+
+    #[test]
+    fn test_repeat_covers_the_box() {
+        let placement = ImagePlacement {
+            fit: ObjectFitValue::None,
+            position: (0.5, 0.0),
+            repeat: ImageRepeat::RepeatX,
+        };
+        // A 30px tile centered in 100px sits at 35, with copies either side.
+        let origins: Vec<f64> = tiles(&placement, Size::new(100.0, 40.0), Size::new(30.0, 30.0))
+            .iter()
+            .map(|transform| transform.translation().x)
+            .collect();
+        assert_eq!(origins, vec![-25.0, 5.0, 35.0, 65.0, 95.0]);
+
+        let tiny = ImagePlacement {
+            repeat: ImageRepeat::Repeat,
+            ..placement
+        };
+        assert_eq!(
+            tiles(&tiny, Size::new(1000.0, 1000.0), Size::new(1.0, 1.0)).len(),
+            1
+        );
+    }
+}
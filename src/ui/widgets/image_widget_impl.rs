@@ -0,0 +1,130 @@
+use masonry::accesskit::{Node, Role};
+use masonry::core::{
+    AccessCtx, ChildrenIds, LayoutCtx, MeasureCtx, PaintCtx, PropertiesMut, PropertiesRef,
+    RegisterCtx, Update, UpdateCtx, Widget, WidgetMut,
+};
+use masonry::kurbo::{Affine, Axis, Size};
+use masonry::layout::LenReq;
+use masonry::peniko::{ImageBrush, ImageData};
+use masonry::vello::Scene;
+
+use crate::ui::widgets::image_placement::{ImagePlacement, tiles};
+
+/// An image fitted, positioned and optionally tiled inside its bounds.
+///
+/// Masonry's Image widget only draws one fitted copy, centered, so `<image>`
+/// paints here to support `objectPosition` and `repeat`.
+pub struct ImageWidget {
+    image: ImageBrush,
+    image_size: Size,
+    placement: ImagePlacement,
+    alt: Option<String>,
+    last_size: Size,
+}
+
+impl ImageWidget {
+    pub fn new(image: ImageData, placement: ImagePlacement) -> Self {
+        Self {
+            image_size: Size::new(image.width as f64, image.height as f64),
+            image: ImageBrush::from(image),
+            placement,
+            alt: None,
+            last_size: Size::ZERO,
+        }
+    }
+
+    pub fn with_alt_text(mut self, alt: String) -> Self {
+        self.alt = Some(alt);
+        self
+    }
+
+    pub fn placement(&self) -> ImagePlacement {
+        self.placement
+    }
+
+    pub fn set_image_data(this: &mut WidgetMut<'_, Self>, image: ImageData) {
+        this.widget.image_size = Size::new(image.width as f64, image.height as f64);
+        this.widget.image = ImageBrush::from(image);
+        // The natural size may have changed.
+        this.ctx.request_layout();
+    }
+
+    pub fn set_placement(this: &mut WidgetMut<'_, Self>, placement: ImagePlacement) {
+        if this.widget.placement != placement {
+            this.widget.placement = placement;
+            this.ctx.request_render();
+        }
+    }
+}
+
+impl Widget for ImageWidget {
+    type Action = masonry::core::NoAction;
+
+    fn register_children(&mut self, _ctx: &mut RegisterCtx<'_>) {}
+
+    fn update(
+        &mut self,
+        _ctx: &mut UpdateCtx<'_>,
+        _props: &mut PropertiesMut<'_>,
+        _event: &Update,
+    ) {
+    }
+
+    fn measure(
+        &mut self,
+        _ctx: &mut MeasureCtx<'_>,
+        _props: &PropertiesRef<'_>,
+        axis: Axis,
+        len_req: LenReq,
+        cross_length: Option<f64>,
+    ) -> f64 {
+        let (natural, natural_cross) = match axis {
+            Axis::Horizontal => (self.image_size.width, self.image_size.height),
+            Axis::Vertical => (self.image_size.height, self.image_size.width),
+        };
+        // Keep the aspect ratio when the other side is already decided.
+        let preferred = match cross_length {
+            Some(cross) if natural_cross > 0.0 => natural * cross / natural_cross,
+            _ => natural,
+        };
+        match len_req {
+            LenReq::FitContent(space) => preferred.min(space),
+            _ => preferred,
+        }
+    }
+
+    fn layout(&mut self, _ctx: &mut LayoutCtx<'_>, _props: &PropertiesRef<'_>, size: Size) {
+        self.last_size = size;
+    }
+
+    fn paint(&mut self, _ctx: &mut PaintCtx<'_>, _props: &PropertiesRef<'_>, scene: &mut Scene) {
+        let copies = tiles(&self.placement, self.last_size, self.image_size);
+        if copies.is_empty() {
+            return;
+        }
+        scene.push_clip_layer(Affine::IDENTITY, &self.last_size.to_rect());
+        for transform in copies {
+            scene.draw_image(&self.image, transform);
+        }
+        scene.pop_layer();
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::Image
+    }
+
+    fn accessibility(
+        &mut self,
+        _ctx: &mut AccessCtx<'_>,
+        _props: &PropertiesRef<'_>,
+        node: &mut Node,
+    ) {
+        if let Some(alt) = &self.alt {
+            node.set_label(alt.clone());
+        }
+    }
+
+    fn children_ids(&self) -> ChildrenIds {
+        ChildrenIds::new()
+    }
+}
@@ -1,16 +1,20 @@
 use masonry::app::RenderRoot;
 use masonry::core::{NewWidget, PropertySet, StyleProperty, WidgetOptions};
 use masonry::parley::style::{FontFamily, FontStack, GenericFamily};
-use masonry::peniko::Color;
 use masonry::properties::ContentColor;
-use masonry::widgets::Label;
+use masonry::widgets::{Label, Prose, TextArea};
 
 use crate::ipc::BoxStyle;
 use crate::ipc::WidgetKind;
-use crate::ui::styles::{build_box_properties, build_text_styles};
+use crate::ui::global_state::is_right_to_left;
+use crate::ui::styles::{
+    build_box_properties, build_text_styles, color_value_to_peniko, font_size, text_alignment,
+};
+use crate::ui::theme::widget_defaults;
 use crate::ui::widget_manager::{WidgetInfo, WidgetManager};
-use crate::ui::widgets::utils::add_to_parent;
+use crate::ui::widgets::utils::{WrapperOptions, add_to_parent};
 
+#[allow(clippy::too_many_arguments)]
 pub fn create(
     render_root: &mut RenderRoot,
     widget_manager: &mut WidgetManager,
@@ -18,28 +22,108 @@ pub fn create(
     parent_id: Option<String>,
     text: Option<String>,
     style: Option<BoxStyle>,
+    selectable: bool,
     child_index: usize,
+    wrappers: WrapperOptions,
 ) {
     let label_text = text.as_deref().unwrap_or("[Label]");
-    let mut label = Label::new(label_text);
     let style_ref = style.as_ref();
 
-    let text_styles = style_ref.map(build_text_styles).unwrap_or_else(|| {
-        vec![
-            StyleProperty::FontSize(30.0),
+    let defaults = widget_defaults();
+    let text_styles = match style_ref {
+        Some(style) => {
+            let mut styles = build_text_styles(style);
+            if style.font_size.is_none()
+                && let Some(size) = defaults.font_size
+            {
+                styles.push(font_size(size));
+            }
+            styles
+        }
+        None => vec![
+            font_size(defaults.unstyled_font_size()),
             StyleProperty::FontStack(FontStack::Single(FontFamily::Generic(
                 GenericFamily::SansSerif,
             ))),
-        ]
+        ],
+    };
+    let alignment = text_alignment(
+        style_ref.and_then(|s| s.text_align.as_ref()),
+        is_right_to_left(),
+    );
+    let props = style_ref.map(build_box_properties).unwrap_or_else(|| {
+        PropertySet::new().with(ContentColor::new(defaults.unstyled_text_color()))
     });
+
+    if selectable {
+        // Prose is masonry's read-only text area: it takes mouse selection and
+        // copies it with Ctrl/Cmd+C. It is registered as a Prose so text and
+        // style updates reach the right widget.
+        let mut area = TextArea::new_immutable(label_text);
+        for s in &text_styles {
+            area = area.with_style(s.clone());
+        }
+        if let Some(alignment) = alignment {
+            area = area.with_text_alignment(alignment);
+        }
+        let color = style_ref
+            .and_then(|s| s.color.as_ref())
+            .map_or(defaults.unstyled_text_color(), color_value_to_peniko);
+        let area = NewWidget::new_with(
+            area,
+            None,
+            WidgetOptions::default(),
+            PropertySet::new().with(ContentColor::new(color)),
+        );
+        let prose = Prose::from_text_area(area);
+        let new_widget = NewWidget::new_with(prose, None, WidgetOptions::default(), props);
+        register(
+            render_root,
+            widget_manager,
+            id,
+            parent_id,
+            new_widget,
+            style_ref.and_then(|s| s.flex),
+            WidgetKind::Prose,
+            child_index,
+            wrappers,
+        );
+        return;
+    }
+
+    let mut label = Label::new(label_text);
     for s in &text_styles {
         label = label.with_style(s.clone());
     }
-
-    let props = style_ref
-        .map(build_box_properties)
-        .unwrap_or_else(|| PropertySet::new().with(ContentColor::new(Color::WHITE)));
+    if let Some(alignment) = alignment {
+        label = label.with_text_alignment(alignment);
+    }
     let new_widget = NewWidget::new_with(label, None, WidgetOptions::default(), props);
+    register(
+        render_root,
+        widget_manager,
+        id,
+        parent_id,
+        new_widget,
+        style_ref.and_then(|s| s.flex),
+        WidgetKind::Label,
+        child_index,
+        wrappers,
+    );
+}
+
+#[allow(clippy::too_many_arguments)]
+fn register(
+    render_root: &mut RenderRoot,
+    widget_manager: &mut WidgetManager,
+    id: String,
+    parent_id: Option<String>,
+    new_widget: NewWidget<impl masonry::core::Widget>,
+    flex: Option<f64>,
+    kind: WidgetKind,
+    child_index: usize,
+    wrappers: WrapperOptions,
+) {
     let widget_id = new_widget.id();
 
     if add_to_parent(
@@ -47,13 +131,14 @@ pub fn create(
         widget_manager,
         &parent_id,
         new_widget,
-        style_ref.and_then(|s| s.flex),
+        flex,
+        wrappers,
     ) {
         widget_manager.register_widget(
             id,
             WidgetInfo {
                 widget_id,
-                kind: WidgetKind::Label,
+                kind,
                 parent_id: parent_id.clone(),
                 child_index,
             },
@@ -0,0 +1,218 @@
+use masonry::accesskit::{Node, Role};
+use masonry::core::{
+    AccessCtx, ChildrenIds, LayoutCtx, MeasureCtx, NewWidget, PaintCtx, PropertiesMut,
+    PropertiesRef, RegisterCtx, Update, UpdateCtx, Widget, WidgetMut, WidgetPod,
+};
+use masonry::kurbo::{Axis, Point, Size};
+use masonry::layout::{LayoutSize, LenReq, SizeDef};
+use masonry::properties::types::{CrossAxisAlignment, MainAxisAlignment};
+use masonry::vello::Scene;
+
+/// One of the window's root layers (`__overlay__`, `__toast__`, `__debug__`),
+/// and the `<zstack>` widget (centered both ways).
+///
+/// Fills the window above the content layer. Each child is sized to its own
+/// content and placed independently, aligned within the layer vertically by
+/// `main` and horizontally by `cross`. Children with a higher `zIndex` are
+/// painted and hit first; equal ones keep their order (later on top). The
+/// layer itself never takes pointer input, so clicks outside its children
+/// reach the layers below.
+pub struct Layer {
+    children: Vec<WidgetPod<dyn Widget>>,
+    /// `zIndex` of each child, by position in `children`
+    z_indices: Vec<i32>,
+    main: MainAxisAlignment,
+    cross: CrossAxisAlignment,
+}
+
+impl Layer {
+    pub fn new(main: MainAxisAlignment, cross: CrossAxisAlignment) -> Self {
+        Self {
+            children: Vec::new(),
+            z_indices: Vec::new(),
+            main,
+            cross,
+        }
+    }
+
+    /// Builder-style variant of `add_child`, for a layer not yet in the tree.
+    pub fn with_child(mut self, child: NewWidget<impl Widget + ?Sized>) -> Self {
+        self.children.push(child.erased().to_pod());
+        self.z_indices.push(0);
+        self
+    }
+
+    pub fn set_alignment(
+        this: &mut WidgetMut<'_, Self>,
+        main: Option<MainAxisAlignment>,
+        cross: Option<CrossAxisAlignment>,
+    ) {
+        this.widget.main = main.unwrap_or(this.widget.main);
+        this.widget.cross = cross.unwrap_or(this.widget.cross);
+        this.ctx.request_layout();
+    }
+
+    pub fn add_child(this: &mut WidgetMut<'_, Self>, child: NewWidget<impl Widget + ?Sized>) {
+        this.widget.children.push(child.erased().to_pod());
+        this.widget.z_indices.push(0);
+        this.ctx.children_changed();
+    }
+
+    pub fn remove_child(this: &mut WidgetMut<'_, Self>, index: usize) {
+        let child = this.widget.children.remove(index);
+        this.widget.z_indices.remove(index);
+        this.ctx.remove_child(child);
+    }
+
+    /// Restack the child at `index`; out-of-range indices are ignored.
+    pub fn set_z_index(this: &mut WidgetMut<'_, Self>, index: usize, z_index: i32) {
+        if let Some(current) = this.widget.z_indices.get_mut(index)
+            && *current != z_index
+        {
+            *current = z_index;
+            this.ctx.children_changed();
+            this.ctx.request_render();
+        }
+    }
+}
+
+/// Positions in `children` from bottom to top of the stack.
+fn paint_order(z_indices: &[i32]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..z_indices.len()).collect();
+    order.sort_by_key(|&index| z_indices[index]);
+    order
+}
+
+/// Offset of free space `free` taken before a child: a stack has no space
+/// between children, so the spaced alignments center like `Center`.
+fn leading_space(main: MainAxisAlignment, free: f64) -> f64 {
+    let free = free.max(0.0);
+    match main {
+        MainAxisAlignment::Start => 0.0,
+        MainAxisAlignment::End => free,
+        _ => free / 2.0,
+    }
+}
+
+fn cross_space(cross: CrossAxisAlignment, free: f64) -> f64 {
+    let free = free.max(0.0);
+    match cross {
+        CrossAxisAlignment::Center => free / 2.0,
+        CrossAxisAlignment::End => free,
+        _ => 0.0,
+    }
+}
+
+impl Widget for Layer {
+    type Action = masonry::core::NoAction;
+
+    fn accepts_pointer_interaction(&self) -> bool {
+        false
+    }
+
+    fn register_children(&mut self, ctx: &mut RegisterCtx<'_>) {
+        for child in &mut self.children {
+            ctx.register_child(child);
+        }
+    }
+
+    fn update(
+        &mut self,
+        _ctx: &mut UpdateCtx<'_>,
+        _props: &mut PropertiesMut<'_>,
+        _event: &Update,
+    ) {
+    }
+
+    fn measure(
+        &mut self,
+        ctx: &mut MeasureCtx<'_>,
+        _props: &PropertiesRef<'_>,
+        axis: Axis,
+        len_req: LenReq,
+        cross_length: Option<f64>,
+    ) -> f64 {
+        self.children
+            .iter_mut()
+            .map(|child| {
+                ctx.compute_length(
+                    child,
+                    len_req.into(),
+                    LayoutSize::maybe(axis.cross(), cross_length),
+                    axis,
+                    cross_length,
+                )
+            })
+            .fold(0.0, f64::max)
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx<'_>, _props: &PropertiesRef<'_>, size: Size) {
+        for child in &mut self.children {
+            let child_size = if matches!(self.cross, CrossAxisAlignment::Stretch) {
+                let height = ctx.compute_length(
+                    child,
+                    LenReq::MaxContent.into(),
+                    size.into(),
+                    Axis::Vertical,
+                    Some(size.width),
+                );
+                Size::new(size.width, height.min(size.height))
+            } else {
+                ctx.compute_size(child, SizeDef::fit(size), size.into())
+            };
+            ctx.run_layout(child, child_size);
+            let origin = Point::new(
+                cross_space(self.cross, size.width - child_size.width),
+                leading_space(self.main, size.height - child_size.height),
+            );
+            ctx.place_child(child, origin);
+        }
+    }
+
+    fn paint(&mut self, _ctx: &mut PaintCtx<'_>, _props: &PropertiesRef<'_>, _scene: &mut Scene) {}
+
+    fn accessibility_role(&self) -> Role {
+        Role::GenericContainer
+    }
+
+    fn accessibility(
+        &mut self,
+        _ctx: &mut AccessCtx<'_>,
+        _props: &PropertiesRef<'_>,
+        _node: &mut Node,
+    ) {
+    }
+
+    /// Masonry paints children in this order and hit-tests them in reverse,
+    /// so `zIndex` only has to sort it.
+    fn children_ids(&self) -> ChildrenIds {
+        paint_order(&self.z_indices)
+            .into_iter()
+            .map(|index| self.children[index].id())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_children_align_within_free_space() {
+        assert_eq!(leading_space(MainAxisAlignment::Start, 100.0), 0.0);
+        assert_eq!(leading_space(MainAxisAlignment::End, 100.0), 100.0);
+        assert_eq!(leading_space(MainAxisAlignment::SpaceBetween, 100.0), 50.0);
+        assert_eq!(cross_space(CrossAxisAlignment::Center, 40.0), 20.0);
+        assert_eq!(cross_space(CrossAxisAlignment::Stretch, 40.0), 0.0);
+        // Children larger than the layer stay pinned to its origin.
+        assert_eq!(leading_space(MainAxisAlignment::End, -10.0), 0.0);
+    }
+
+    #[test]
+    fn test_paint_order_follows_z_index() {
+        assert_eq!(paint_order(&[0, 0, 0]), vec![0, 1, 2]);
+        // Raised children go last; ties keep their insertion order.
+        assert_eq!(paint_order(&[2, 0, -1, 0]), vec![2, 1, 3, 0]);
+        assert!(paint_order(&[]).is_empty());
+    }
+}
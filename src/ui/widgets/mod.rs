@@ -1,23 +1,60 @@
+pub mod avatar;
+pub mod avatar_impl;
+pub mod background;
+pub mod background_box_impl;
+pub mod badge;
 pub mod button;
 pub mod checkbox;
+pub mod clip_box_impl;
 pub mod custom;
+pub mod custom_paint;
+pub mod custom_paint_impl;
+pub mod error_overlay;
 pub mod flex;
+pub mod frame_clock_impl;
+pub mod gpu_surface;
+pub mod gpu_surface_impl;
 pub mod grid;
 pub mod hoverable;
 pub mod hoverable_create;
+pub mod icon;
 pub mod image;
+pub mod image_placement;
+pub mod image_widget_impl;
 pub mod label;
+pub mod layer_impl;
+pub mod native_widget_impl;
 pub mod portal;
+pub mod press_feedback_impl;
 pub mod progress_bar;
+pub mod progress_bar_impl;
 pub mod prose;
+pub mod qr_code;
+pub mod qr_code_impl;
+pub mod range_slider;
+pub mod range_slider_impl;
+pub mod reorderable_list;
+pub mod reorderable_list_impl;
+pub mod scroll_area_impl;
 pub mod sized_box;
+pub mod skeleton;
+pub mod skeleton_impl;
 pub mod slider;
 pub mod spinner;
+pub mod splash;
 pub mod svg;
+pub mod svg_cache;
 pub mod svg_widget_impl;
 pub mod text_area;
 pub mod text_input;
 pub mod utils;
 pub mod video;
+#[cfg(all(feature = "dmabuf", target_os = "linux"))]
+pub mod video_dmabuf;
+pub mod video_ffmpeg;
+#[cfg(feature = "gstreamer")]
+pub mod video_gstreamer;
+pub mod video_registry;
 pub mod video_widget_impl;
+pub mod viewport;
 pub mod zstack;
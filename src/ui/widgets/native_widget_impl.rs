@@ -0,0 +1,281 @@
+use std::ffi::{CString, c_void};
+use std::sync::Arc;
+
+use masonry::accesskit::{Node, Role};
+use masonry::core::{
+    AccessCtx, ChildrenIds, ErasedAction, EventCtx, LayoutCtx, MeasureCtx, NewWidget, PaintCtx,
+    PointerEvent, PropertiesMut, PropertiesRef, RegisterCtx, Update, UpdateCtx, Widget, WidgetMut,
+    WidgetOptions,
+};
+use masonry::kurbo::{Affine, Size};
+use masonry::peniko::{Blob, ImageAlphaType, ImageBrush, ImageData, ImageFormat};
+use masonry::vello::Scene;
+
+use crate::ipc::WidgetActionKind;
+use crate::plugins::{
+    POINTER_CLICK, POINTER_DOWN, POINTER_MOVE, POINTER_REPAINT, POINTER_UP, POINTER_VALUE_CHANGED,
+    WidgetVTable,
+};
+use crate::ui::global_state::last_click_input;
+use crate::ui::styles::build_box_properties;
+use crate::ui::widget_factory::{CreateContext, WidgetFactory};
+
+#[derive(Debug, Clone, Copy)]
+pub enum NativeWidgetAction {
+    Click,
+    ValueChanged(f64),
+}
+
+/// Builds `NativeWidget`s for a kind registered by a plugin.
+pub struct NativeWidgetFactory {
+    kind: String,
+    vtable: WidgetVTable,
+}
+
+impl NativeWidgetFactory {
+    pub fn new(kind: String, vtable: WidgetVTable) -> Self {
+        Self { kind, vtable }
+    }
+}
+
+impl WidgetFactory for NativeWidgetFactory {
+    fn create(&self, ctx: &CreateContext<'_>) -> NewWidget<dyn Widget> {
+        let widget = NativeWidget::new(&self.kind, self.vtable, ctx.id, ctx.text);
+        match ctx.style {
+            Some(style) => NewWidget::new_with(
+                widget,
+                None,
+                WidgetOptions::default(),
+                build_box_properties(style),
+            )
+            .erased(),
+            None => NewWidget::new(widget).erased(),
+        }
+    }
+
+    fn set_text(&self, widget: &mut WidgetMut<'_, dyn Widget>, text: &str) -> bool {
+        let mut native = widget.downcast::<NativeWidget>();
+        NativeWidget::set_text(&mut native, text)
+    }
+
+    fn set_value(&self, widget: &mut WidgetMut<'_, dyn Widget>, value: f64) -> bool {
+        let mut native = widget.downcast::<NativeWidget>();
+        NativeWidget::set_value(&mut native, value)
+    }
+
+    fn map_action(&self, action: &ErasedAction) -> Option<WidgetActionKind> {
+        match action.downcast_ref::<NativeWidgetAction>()? {
+            NativeWidgetAction::Click => Some(WidgetActionKind::click(last_click_input())),
+            NativeWidgetAction::ValueChanged(value) => Some(WidgetActionKind::ValueChanged(*value)),
+        }
+    }
+}
+
+/// A widget implemented by a native plugin: the plugin paints into an RGBA
+/// buffer that is drawn as an image, and receives local pointer input.
+pub struct NativeWidget {
+    kind: String,
+    vtable: WidgetVTable,
+    instance: *mut c_void,
+    image: Option<ImageBrush>,
+    dirty: bool,
+    last_size: Size,
+}
+
+// Plugin instances are created, used and destroyed on the UI thread only.
+unsafe impl Send for NativeWidget {}
+
+impl NativeWidget {
+    pub fn new(kind: &str, vtable: WidgetVTable, id: &str, text: Option<&str>) -> Self {
+        let id = CString::new(id).unwrap_or_default();
+        let text = text.and_then(|text| CString::new(text).ok());
+        let instance = match vtable.create {
+            Some(create) => unsafe {
+                create(
+                    vtable.user_data,
+                    id.as_ptr(),
+                    text.as_ref().map_or(std::ptr::null(), |text| text.as_ptr()),
+                )
+            },
+            None => std::ptr::null_mut(),
+        };
+        if instance.is_null() {
+            eprintln!("[Plugins] '{kind}' failed to create widget {id:?}");
+        }
+        Self {
+            kind: kind.to_string(),
+            vtable,
+            instance,
+            image: None,
+            dirty: true,
+            last_size: Size::ZERO,
+        }
+    }
+
+    pub fn set_text(this: &mut WidgetMut<'_, Self>, text: &str) -> bool {
+        let (Some(set_text), false) = (this.widget.vtable.set_text, this.widget.instance.is_null())
+        else {
+            return false;
+        };
+        let Ok(text) = CString::new(text) else {
+            return false;
+        };
+        let applied = unsafe { set_text(this.widget.instance, text.as_ptr()) } != 0;
+        if applied {
+            this.widget.dirty = true;
+            this.ctx.request_render();
+        }
+        applied
+    }
+
+    pub fn set_value(this: &mut WidgetMut<'_, Self>, value: f64) -> bool {
+        let (Some(set_value), false) =
+            (this.widget.vtable.set_value, this.widget.instance.is_null())
+        else {
+            return false;
+        };
+        let applied = unsafe { set_value(this.widget.instance, value) } != 0;
+        if applied {
+            this.widget.dirty = true;
+            this.ctx.request_render();
+        }
+        applied
+    }
+
+    /// Let the plugin redraw at the current size.
+    fn repaint(&mut self) {
+        let (Some(paint), false) = (self.vtable.paint, self.instance.is_null()) else {
+            return;
+        };
+        let width = self.last_size.width.ceil() as u32;
+        let height = self.last_size.height.ceil() as u32;
+        if width == 0 || height == 0 {
+            self.image = None;
+            return;
+        }
+        let mut pixels = vec![0_u8; width as usize * height as usize * 4];
+        unsafe { paint(self.instance, width, height, pixels.as_mut_ptr()) };
+        self.image = Some(ImageBrush::from(ImageData {
+            data: Blob::new(Arc::new(pixels)),
+            format: ImageFormat::Rgba8,
+            alpha_type: ImageAlphaType::Alpha,
+            width,
+            height,
+        }));
+    }
+}
+
+impl Drop for NativeWidget {
+    fn drop(&mut self) {
+        if let Some(destroy) = self.vtable.destroy
+            && !self.instance.is_null()
+        {
+            unsafe { destroy(self.instance) };
+        }
+    }
+}
+
+impl Widget for NativeWidget {
+    type Action = NativeWidgetAction;
+
+    fn on_pointer_event(
+        &mut self,
+        ctx: &mut EventCtx<'_>,
+        _props: &mut PropertiesMut<'_>,
+        event: &PointerEvent,
+    ) {
+        let Some(pointer) = self.vtable.pointer.filter(|_| !self.instance.is_null()) else {
+            return;
+        };
+        let (kind, position) = match event {
+            PointerEvent::Down(button_event) => {
+                ctx.capture_pointer();
+                (POINTER_DOWN, button_event.state.position)
+            }
+            PointerEvent::Move(update) => (POINTER_MOVE, update.current.position),
+            PointerEvent::Up(button_event) => (POINTER_UP, button_event.state.position),
+            _ => return,
+        };
+        let local = ctx.local_position(position);
+        let mut value = 0.0;
+        let flags = unsafe { pointer(self.instance, kind, local.x, local.y, &mut value) };
+        if flags & POINTER_CLICK != 0 {
+            ctx.submit_action::<Self::Action>(NativeWidgetAction::Click);
+        }
+        if flags & POINTER_VALUE_CHANGED != 0 {
+            ctx.submit_action::<Self::Action>(NativeWidgetAction::ValueChanged(value));
+        }
+        if flags & POINTER_REPAINT != 0 {
+            self.dirty = true;
+            ctx.request_render();
+        }
+    }
+
+    fn register_children(&mut self, _ctx: &mut RegisterCtx<'_>) {}
+
+    fn update(
+        &mut self,
+        _ctx: &mut UpdateCtx<'_>,
+        _props: &mut PropertiesMut<'_>,
+        _event: &Update,
+    ) {
+    }
+
+    fn measure(
+        &mut self,
+        _ctx: &mut MeasureCtx<'_>,
+        _props: &PropertiesRef<'_>,
+        axis: masonry::kurbo::Axis,
+        len_req: masonry::layout::LenReq,
+        _cross_length: Option<f64>,
+    ) -> f64 {
+        let preferred = match axis {
+            masonry::kurbo::Axis::Horizontal => self.vtable.preferred_width,
+            masonry::kurbo::Axis::Vertical => self.vtable.preferred_height,
+        }
+        .max(0.0);
+        match len_req {
+            masonry::layout::LenReq::FitContent(space) => preferred.min(space),
+            _ => preferred,
+        }
+    }
+
+    fn layout(
+        &mut self,
+        _ctx: &mut LayoutCtx<'_>,
+        _props: &PropertiesRef<'_>,
+        size: masonry::kurbo::Size,
+    ) {
+        if size != self.last_size {
+            self.last_size = size;
+            self.dirty = true;
+        }
+    }
+
+    fn paint(&mut self, _ctx: &mut PaintCtx<'_>, _props: &PropertiesRef<'_>, scene: &mut Scene) {
+        if self.dirty {
+            self.dirty = false;
+            self.repaint();
+        }
+        if let Some(image) = &self.image {
+            scene.draw_image(image, Affine::IDENTITY);
+        }
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::Unknown
+    }
+
+    fn accessibility(
+        &mut self,
+        _ctx: &mut AccessCtx<'_>,
+        _props: &PropertiesRef<'_>,
+        node: &mut Node,
+    ) {
+        node.set_label(self.kind.clone());
+    }
+
+    fn children_ids(&self) -> ChildrenIds {
+        ChildrenIds::new()
+    }
+}
@@ -1,23 +1,36 @@
 use masonry::app::RenderRoot;
 use masonry::core::{NewWidget, WidgetOptions};
-use masonry::widgets::{Flex, Portal};
 
-use crate::ipc::{BoxStyle, WidgetKind};
+use crate::ipc::{BoxStyle, WidgetData, WidgetKind};
 use crate::ui::styles::build_box_properties;
 use crate::ui::widget_manager::{WidgetInfo, WidgetManager};
-use crate::ui::widgets::utils::add_to_parent;
+use crate::ui::widgets::scroll_area_impl::{ScrollArea, ScrollbarStyle};
+use crate::ui::widgets::utils::{WrapperOptions, add_to_parent};
 
+#[allow(clippy::too_many_arguments)]
 pub fn create(
     render_root: &mut RenderRoot,
     widget_manager: &mut WidgetManager,
     id: String,
     parent_id: Option<String>,
     style: Option<BoxStyle>,
+    data: Option<WidgetData>,
     child_index: usize,
+    wrappers: WrapperOptions,
 ) {
     let style_ref = style.as_ref();
-    let inner_flex = Flex::column();
-    let portal = Portal::new(NewWidget::new(inner_flex));
+    let scrollbar = style_ref
+        .map(ScrollbarStyle::from_style)
+        .unwrap_or_default();
+    let pull_to_refresh = matches!(
+        data,
+        Some(WidgetData::Portal {
+            pull_to_refresh: true
+        })
+    );
+    let portal = ScrollArea::new()
+        .with_style(&scrollbar)
+        .with_pull_to_refresh(pull_to_refresh);
 
     let props = style_ref.map(build_box_properties).unwrap_or_default();
     let new_widget = NewWidget::new_with(portal, None, WidgetOptions::default(), props);
@@ -29,6 +42,7 @@ pub fn create(
         &parent_id,
         new_widget,
         style_ref.and_then(|s| s.flex),
+        wrappers,
     ) {
         widget_manager.register_widget(
             id,
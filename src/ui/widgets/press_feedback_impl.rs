@@ -0,0 +1,248 @@
+use masonry::accesskit::{Node, Role};
+use masonry::core::{
+    AccessCtx, ChildrenIds, EventCtx, LayoutCtx, MeasureCtx, NewWidget, PaintCtx, PointerEvent,
+    PropertiesMut, PropertiesRef, RegisterCtx, Update, UpdateCtx, Widget, WidgetMut, WidgetPod,
+};
+use masonry::kurbo::{Affine, Circle, Point, RoundedRect, Size};
+use masonry::peniko::{Color, Fill};
+use masonry::vello::Scene;
+
+/// How long a ripple takes to spread over the button and fade out.
+const RIPPLE_NS: u64 = 450_000_000;
+
+/// Overlays painted over a button's content.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Feedback {
+    /// Tint while the pointer is over the button
+    pub hover: Option<Color>,
+    /// Tint while the button is held down
+    pub press: Option<Color>,
+    /// Spread a circle of the press tint from where the button was pressed
+    pub ripple: bool,
+}
+
+impl Feedback {
+    fn ripple_color(&self) -> Color {
+        self.press.unwrap_or(Color::from_rgba8(0, 0, 0, 31))
+    }
+}
+
+struct Ripple {
+    center: Point,
+    elapsed_ns: u64,
+}
+
+/// Internal single-child wrapper drawing a Button's hover and press feedback.
+///
+/// The overlays are painted in `post_paint`, over the button and its label,
+/// and clipped to the button's rounded bounds. Pointer events reach the
+/// wrapper as they bubble up from the button.
+pub struct PressFeedback {
+    child: WidgetPod<dyn Widget>,
+    feedback: Feedback,
+    corner_radius: f64,
+    hovered: bool,
+    pressed: bool,
+    ripple: Option<Ripple>,
+    last_size: Size,
+}
+
+impl PressFeedback {
+    pub fn new(child: NewWidget<impl Widget + ?Sized>, feedback: Feedback) -> Self {
+        Self {
+            child: child.erased().to_pod(),
+            feedback,
+            corner_radius: 0.0,
+            hovered: false,
+            pressed: false,
+            ripple: None,
+            last_size: Size::ZERO,
+        }
+    }
+
+    pub fn with_corner_radius(mut self, corner_radius: f64) -> Self {
+        self.corner_radius = corner_radius.max(0.0);
+        self
+    }
+
+    pub fn set_corner_radius(this: &mut WidgetMut<'_, Self>, corner_radius: f64) {
+        this.widget.corner_radius = corner_radius.max(0.0);
+        this.ctx.request_render();
+    }
+}
+
+/// Radius a ripple from `center` needs to cover the whole of `size`.
+fn ripple_reach(center: Point, size: Size) -> f64 {
+    [
+        Point::ORIGIN,
+        Point::new(size.width, 0.0),
+        Point::new(0.0, size.height),
+        Point::new(size.width, size.height),
+    ]
+    .iter()
+    .map(|corner| corner.distance(center))
+    .fold(0.0, f64::max)
+}
+
+impl Widget for PressFeedback {
+    type Action = masonry::core::NoAction;
+
+    fn accepts_pointer_interaction(&self) -> bool {
+        false
+    }
+
+    fn on_pointer_event(
+        &mut self,
+        ctx: &mut EventCtx<'_>,
+        _props: &mut PropertiesMut<'_>,
+        event: &PointerEvent,
+    ) {
+        match event {
+            PointerEvent::Down(button_event) if !ctx.is_disabled() => {
+                self.pressed = true;
+                if self.feedback.ripple {
+                    self.ripple = Some(Ripple {
+                        center: ctx.local_position(button_event.state.position),
+                        elapsed_ns: 0,
+                    });
+                    ctx.request_anim_frame();
+                }
+                ctx.request_render();
+            }
+            PointerEvent::Up(_) | PointerEvent::Cancel(_) if self.pressed => {
+                self.pressed = false;
+                ctx.request_render();
+            }
+            _ => {}
+        }
+    }
+
+    fn on_anim_frame(
+        &mut self,
+        ctx: &mut UpdateCtx<'_>,
+        _props: &mut PropertiesMut<'_>,
+        interval: u64,
+    ) {
+        let Some(ripple) = &mut self.ripple else {
+            return;
+        };
+        ripple.elapsed_ns = ripple.elapsed_ns.saturating_add(interval);
+        if ripple.elapsed_ns < RIPPLE_NS {
+            ctx.request_anim_frame();
+        } else {
+            self.ripple = None;
+        }
+        ctx.request_render();
+    }
+
+    fn register_children(&mut self, ctx: &mut RegisterCtx<'_>) {
+        ctx.register_child(&mut self.child);
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx<'_>, _props: &mut PropertiesMut<'_>, event: &Update) {
+        if let Update::HoveredChanged(hovered) | Update::ChildHoveredChanged(hovered) = event
+            && self.hovered != *hovered
+        {
+            self.hovered = *hovered;
+            ctx.request_render();
+        }
+    }
+
+    fn measure(
+        &mut self,
+        ctx: &mut MeasureCtx<'_>,
+        _props: &PropertiesRef<'_>,
+        axis: masonry::kurbo::Axis,
+        len_req: masonry::layout::LenReq,
+        cross_length: Option<f64>,
+    ) -> f64 {
+        ctx.compute_length(
+            &mut self.child,
+            len_req.into(),
+            masonry::layout::LayoutSize::maybe(axis.cross(), cross_length),
+            axis,
+            cross_length,
+        )
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx<'_>, _props: &PropertiesRef<'_>, size: Size) {
+        self.last_size = size;
+        let child_size = ctx.compute_size(
+            &mut self.child,
+            masonry::layout::SizeDef::fit(size),
+            size.into(),
+        );
+        ctx.run_layout(&mut self.child, child_size);
+        ctx.place_child(&mut self.child, Point::ORIGIN);
+        ctx.derive_baselines(&self.child);
+    }
+
+    fn paint(&mut self, _ctx: &mut PaintCtx<'_>, _props: &PropertiesRef<'_>, _scene: &mut Scene) {}
+
+    fn post_paint(
+        &mut self,
+        ctx: &mut PaintCtx<'_>,
+        _props: &PropertiesRef<'_>,
+        scene: &mut Scene,
+    ) {
+        if ctx.is_disabled() {
+            return;
+        }
+        let tints = [
+            self.feedback.hover.filter(|_| self.hovered),
+            self.feedback.press.filter(|_| self.pressed),
+        ];
+        if tints.iter().all(Option::is_none) && self.ripple.is_none() {
+            return;
+        }
+        let shape = RoundedRect::from_rect(self.last_size.to_rect(), self.corner_radius);
+        scene.push_clip_layer(Affine::IDENTITY, &shape);
+        for tint in tints.into_iter().flatten() {
+            scene.fill(Fill::NonZero, Affine::IDENTITY, tint, None, &shape);
+        }
+        if let Some(ripple) = &self.ripple {
+            let progress = ripple.elapsed_ns as f64 / RIPPLE_NS as f64;
+            let radius = ripple_reach(ripple.center, self.last_size) * progress.sqrt();
+            let color = self.feedback.ripple_color();
+            let fade = color.components[3] * (1.0 - progress as f32);
+            scene.fill(
+                Fill::NonZero,
+                Affine::IDENTITY,
+                color.with_alpha(fade),
+                None,
+                &Circle::new(ripple.center, radius),
+            );
+        }
+        scene.pop_layer();
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::GenericContainer
+    }
+
+    fn accessibility(
+        &mut self,
+        _ctx: &mut AccessCtx<'_>,
+        _props: &PropertiesRef<'_>,
+        _node: &mut Node,
+    ) {
+    }
+
+    fn children_ids(&self) -> ChildrenIds {
+        ChildrenIds::from_slice(&[self.child.id()])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ripple_reaches_the_farthest_corner() {
+        let size = Size::new(30.0, 40.0);
+        assert_eq!(ripple_reach(Point::ORIGIN, size), 50.0);
+        assert_eq!(ripple_reach(Point::new(15.0, 20.0), size), 25.0);
+        // A press near the right edge still has to cover the left corners.
+        assert_eq!(ripple_reach(Point::new(30.0, 0.0), size), 50.0);
+    }
+}
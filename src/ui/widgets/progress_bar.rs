@@ -1,12 +1,13 @@
 use masonry::app::RenderRoot;
 use masonry::core::{NewWidget, WidgetOptions};
-use masonry::widgets::ProgressBar;
 
 use crate::ipc::{BoxStyle, WidgetData, WidgetKind};
-use crate::ui::styles::build_box_properties;
+use crate::ui::styles::{build_box_properties, color_value_to_peniko};
 use crate::ui::widget_manager::{WidgetInfo, WidgetManager};
-use crate::ui::widgets::utils::add_to_parent;
+use crate::ui::widgets::progress_bar_impl::ProgressBarWidget;
+use crate::ui::widgets::utils::{WrapperOptions, add_to_parent};
 
+#[allow(clippy::too_many_arguments)]
 pub fn create(
     render_root: &mut RenderRoot,
     widget_manager: &mut WidgetManager,
@@ -15,16 +16,30 @@ pub fn create(
     style: Option<BoxStyle>,
     data: Option<WidgetData>,
     child_index: usize,
+    wrappers: WrapperOptions,
 ) {
     let style_ref = style.as_ref();
 
     // Extract progress from WidgetData
-    let progress = match &data {
-        Some(WidgetData::ProgressBar { progress }) => *progress,
-        _ => None,
+    let (progress, buffer, indeterminate) = match &data {
+        Some(WidgetData::ProgressBar {
+            progress,
+            buffer,
+            indeterminate,
+        }) => (*progress, *buffer, *indeterminate),
+        _ => (None, None, false),
     };
 
-    let pbar = ProgressBar::new(progress);
+    let pbar = ProgressBarWidget::new(if indeterminate { None } else { progress })
+        .with_buffer(buffer)
+        .with_colors(
+            style_ref
+                .and_then(|s| s.track_color.as_ref())
+                .map(color_value_to_peniko),
+            style_ref
+                .and_then(|s| s.fill_color.as_ref())
+                .map(color_value_to_peniko),
+        );
 
     let props = style_ref.map(build_box_properties).unwrap_or_default();
     let new_widget = NewWidget::new_with(pbar, None, WidgetOptions::default(), props);
@@ -36,6 +51,7 @@ pub fn create(
         &parent_id,
         new_widget,
         style_ref.and_then(|s| s.flex),
+        wrappers,
     ) {
         widget_manager.register_widget(
             id,
@@ -0,0 +1,276 @@
+use masonry::accesskit::{Node, Role};
+use masonry::core::{
+    AccessCtx, ChildrenIds, LayoutCtx, MeasureCtx, NoAction, PaintCtx, PropertiesMut,
+    PropertiesRef, RegisterCtx, Update, UpdateCtx, Widget, WidgetMut,
+};
+use masonry::kurbo::{Affine, Rect, RoundedRect, Size};
+use masonry::peniko::{Color, Fill};
+use masonry::vello::Scene;
+
+const DEFAULT_WIDTH: f64 = 200.0;
+const DEFAULT_HEIGHT: f64 = 8.0;
+const DEFAULT_TRACK_COLOR: Color = Color::from_rgba8(255, 255, 255, 40);
+const DEFAULT_FILL_COLOR: Color = Color::from_rgba8(59, 130, 246, 255);
+/// Fraction of the track covered by the moving segment in indeterminate mode.
+const INDETERMINATE_SEGMENT: f64 = 0.3;
+/// Duration of one indeterminate sweep, in nanoseconds.
+const INDETERMINATE_PERIOD_NS: f64 = 1_400_000_000.0;
+
+/// A progress bar with determinate, indeterminate and buffered modes.
+///
+/// Masonry's built-in ProgressBar has no secondary "buffered" value and no
+/// styling hooks for track/fill colors, so this widget paints everything itself.
+pub struct ProgressBarWidget {
+    /// `None` means indeterminate.
+    progress: Option<f64>,
+    buffer: Option<f64>,
+    track_color: Color,
+    fill_color: Color,
+    /// Position of the indeterminate sweep, in `[0, 1)`.
+    phase: f64,
+    last_size: Size,
+}
+
+impl ProgressBarWidget {
+    pub fn new(progress: Option<f64>) -> Self {
+        Self {
+            progress: progress.map(clamp_unit),
+            buffer: None,
+            track_color: DEFAULT_TRACK_COLOR,
+            fill_color: DEFAULT_FILL_COLOR,
+            phase: 0.0,
+            last_size: Size::ZERO,
+        }
+    }
+
+    pub fn with_buffer(mut self, buffer: Option<f64>) -> Self {
+        self.buffer = buffer.map(clamp_unit);
+        self
+    }
+
+    pub fn with_colors(mut self, track: Option<Color>, fill: Option<Color>) -> Self {
+        if let Some(track) = track {
+            self.track_color = track;
+        }
+        if let Some(fill) = fill {
+            self.fill_color = fill;
+        }
+        self
+    }
+
+    fn is_indeterminate(&self) -> bool {
+        self.progress.is_none()
+    }
+
+    /// Horizontal extent `(start, end)` of the indeterminate segment as fractions of the track.
+    fn indeterminate_segment(phase: f64) -> (f64, f64) {
+        // The segment enters from the left and leaves on the right,
+        // so it travels `1 + segment` track widths per period.
+        let head = phase * (1.0 + INDETERMINATE_SEGMENT);
+        let start = (head - INDETERMINATE_SEGMENT).max(0.0);
+        let end = head.min(1.0);
+        (start, end)
+    }
+
+    /// Set a determinate progress value, leaving indeterminate mode.
+    pub fn set_progress(this: &mut WidgetMut<'_, Self>, progress: Option<f64>) {
+        let was_indeterminate = this.widget.is_indeterminate();
+        this.widget.progress = progress.map(clamp_unit);
+        if this.widget.is_indeterminate() && !was_indeterminate {
+            this.widget.phase = 0.0;
+            this.ctx.request_anim_frame();
+        }
+        this.ctx.request_render();
+    }
+
+    pub fn set_indeterminate(this: &mut WidgetMut<'_, Self>, indeterminate: bool) {
+        if indeterminate {
+            Self::set_progress(this, None);
+        } else if this.widget.is_indeterminate() {
+            Self::set_progress(this, Some(0.0));
+        }
+    }
+
+    pub fn set_buffer(this: &mut WidgetMut<'_, Self>, buffer: Option<f64>) {
+        this.widget.buffer = buffer.map(clamp_unit);
+        this.ctx.request_render();
+    }
+
+    pub fn set_colors(this: &mut WidgetMut<'_, Self>, track: Option<Color>, fill: Option<Color>) {
+        if track.is_none() && fill.is_none() {
+            return;
+        }
+        if let Some(track) = track {
+            this.widget.track_color = track;
+        }
+        if let Some(fill) = fill {
+            this.widget.fill_color = fill;
+        }
+        this.ctx.request_render();
+    }
+}
+
+fn clamp_unit(value: f64) -> f64 {
+    if value.is_nan() {
+        0.0
+    } else {
+        value.clamp(0.0, 1.0)
+    }
+}
+
+impl Widget for ProgressBarWidget {
+    type Action = NoAction;
+
+    fn accepts_pointer_interaction(&self) -> bool {
+        false
+    }
+
+    fn register_children(&mut self, _ctx: &mut RegisterCtx<'_>) {}
+
+    fn on_anim_frame(
+        &mut self,
+        ctx: &mut UpdateCtx<'_>,
+        _props: &mut PropertiesMut<'_>,
+        interval: u64,
+    ) {
+        if !self.is_indeterminate() {
+            return;
+        }
+        self.phase = (self.phase + interval as f64 / INDETERMINATE_PERIOD_NS).fract();
+        ctx.request_paint_only();
+        ctx.request_anim_frame();
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx<'_>, _props: &mut PropertiesMut<'_>, event: &Update) {
+        if event == &Update::WidgetAdded && self.is_indeterminate() {
+            ctx.request_anim_frame();
+        }
+    }
+
+    fn measure(
+        &mut self,
+        _ctx: &mut MeasureCtx<'_>,
+        _props: &PropertiesRef<'_>,
+        axis: masonry::kurbo::Axis,
+        len_req: masonry::layout::LenReq,
+        _cross_length: Option<f64>,
+    ) -> f64 {
+        match axis {
+            masonry::kurbo::Axis::Horizontal => match len_req {
+                masonry::layout::LenReq::MinContent => DEFAULT_HEIGHT * 2.0,
+                masonry::layout::LenReq::MaxContent => DEFAULT_WIDTH,
+                masonry::layout::LenReq::FitContent(space) => space,
+            },
+            masonry::kurbo::Axis::Vertical => match len_req {
+                masonry::layout::LenReq::FitContent(space) => DEFAULT_HEIGHT.min(space),
+                _ => DEFAULT_HEIGHT,
+            },
+        }
+    }
+
+    fn layout(
+        &mut self,
+        _ctx: &mut LayoutCtx<'_>,
+        _props: &PropertiesRef<'_>,
+        size: masonry::kurbo::Size,
+    ) {
+        self.last_size = size;
+    }
+
+    fn paint(&mut self, _ctx: &mut PaintCtx<'_>, _props: &PropertiesRef<'_>, scene: &mut Scene) {
+        let width = self.last_size.width;
+        let height = self.last_size.height;
+        let radius = height * 0.5;
+
+        let segment = |start: f64, end: f64| {
+            RoundedRect::from_rect(Rect::new(start * width, 0.0, end * width, height), radius)
+        };
+
+        scene.fill(
+            Fill::NonZero,
+            Affine::IDENTITY,
+            self.track_color,
+            None,
+            &segment(0.0, 1.0),
+        );
+
+        if let Some(buffer) = self.buffer
+            && buffer > 0.0
+        {
+            scene.fill(
+                Fill::NonZero,
+                Affine::IDENTITY,
+                self.fill_color.multiply_alpha(0.35),
+                None,
+                &segment(0.0, buffer),
+            );
+        }
+
+        let (start, end) = match self.progress {
+            Some(progress) => (0.0, progress),
+            None => Self::indeterminate_segment(self.phase),
+        };
+        if end > start {
+            scene.fill(
+                Fill::NonZero,
+                Affine::IDENTITY,
+                self.fill_color,
+                None,
+                &segment(start, end),
+            );
+        }
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::ProgressIndicator
+    }
+
+    fn accessibility(
+        &mut self,
+        _ctx: &mut AccessCtx<'_>,
+        _props: &PropertiesRef<'_>,
+        node: &mut Node,
+    ) {
+        if let Some(progress) = self.progress {
+            node.set_min_numeric_value(0.0);
+            node.set_max_numeric_value(1.0);
+            node.set_numeric_value(progress);
+        }
+    }
+
+    fn children_ids(&self) -> ChildrenIds {
+        ChildrenIds::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_is_clamped() {
+        assert_eq!(ProgressBarWidget::new(Some(1.5)).progress, Some(1.0));
+        assert_eq!(ProgressBarWidget::new(Some(-0.2)).progress, Some(0.0));
+        assert_eq!(ProgressBarWidget::new(Some(f64::NAN)).progress, Some(0.0));
+        assert!(ProgressBarWidget::new(None).is_indeterminate());
+    }
+
+    #[test]
+    fn test_buffer_is_clamped() {
+        let bar = ProgressBarWidget::new(Some(0.2)).with_buffer(Some(2.0));
+        assert_eq!(bar.buffer, Some(1.0));
+    }
+
+    #[test]
+    fn test_indeterminate_segment_sweeps_across_track() {
+        assert_eq!(ProgressBarWidget::indeterminate_segment(0.0), (0.0, 0.0));
+
+        let (start, end) = ProgressBarWidget::indeterminate_segment(0.5);
+        assert!(start > 0.0 && end < 1.0);
+        assert!((end - start - INDETERMINATE_SEGMENT).abs() < 1e-9);
+
+        let (start, end) = ProgressBarWidget::indeterminate_segment(0.999);
+        assert!(start > 0.9);
+        assert_eq!(end, 1.0);
+    }
+}
@@ -0,0 +1,68 @@
+use masonry::app::RenderRoot;
+use masonry::core::{NewWidget, WidgetOptions};
+
+use crate::ipc::{BoxStyle, Symbology, WidgetData, WidgetKind};
+use crate::ui::styles::{build_box_properties, color_value_to_peniko};
+use crate::ui::widget_manager::{WidgetInfo, WidgetManager};
+use crate::ui::widgets::qr_code_impl::QrCodeWidget;
+use crate::ui::widgets::utils::{WrapperOptions, add_to_parent};
+
+#[allow(clippy::too_many_arguments)]
+pub fn create(
+    render_root: &mut RenderRoot,
+    widget_manager: &mut WidgetManager,
+    id: String,
+    parent_id: Option<String>,
+    text: Option<String>,
+    style: Option<BoxStyle>,
+    data: Option<WidgetData>,
+    child_index: usize,
+    wrappers: WrapperOptions,
+) {
+    let style_ref = style.as_ref();
+
+    let (symbology, quiet_zone, color, background) = match data {
+        Some(WidgetData::QrCode {
+            symbology,
+            quiet_zone,
+            color,
+            background,
+        }) => (symbology, quiet_zone, color, background),
+        _ => (Symbology::default(), None, None, None),
+    };
+
+    let (qr_code, error) = QrCodeWidget::new(symbology, text.unwrap_or_default());
+    if let Some(err) = error {
+        eprintln!(
+            "[UI] QrCode widget '{}' could not encode its data: {}",
+            id, err
+        );
+    }
+    let qr_code = qr_code.with_quiet_zone(quiet_zone).with_colors(
+        color.as_ref().map(color_value_to_peniko),
+        background.as_ref().map(color_value_to_peniko),
+    );
+
+    let props = style_ref.map(build_box_properties).unwrap_or_default();
+    let new_widget = NewWidget::new_with(qr_code, None, WidgetOptions::default(), props);
+    let widget_id = new_widget.id();
+
+    if add_to_parent(
+        render_root,
+        widget_manager,
+        &parent_id,
+        new_widget,
+        style_ref.and_then(|s| s.flex),
+        wrappers,
+    ) {
+        widget_manager.register_widget(
+            id,
+            WidgetInfo {
+                widget_id,
+                kind: WidgetKind::QrCode,
+                parent_id: parent_id.clone(),
+                child_index,
+            },
+        );
+    }
+}
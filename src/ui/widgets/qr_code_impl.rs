@@ -0,0 +1,316 @@
+use masonry::accesskit::{Node, Role};
+use masonry::core::{
+    AccessCtx, ChildrenIds, LayoutCtx, MeasureCtx, NoAction, PaintCtx, PropertiesMut,
+    PropertiesRef, RegisterCtx, Update, UpdateCtx, Widget, WidgetMut,
+};
+use masonry::kurbo::{Affine, Point, Rect, Size};
+use masonry::peniko::{Color, Fill};
+use masonry::vello::Scene;
+
+use crate::ipc::Symbology;
+
+/// Preferred size of one module (pixels) when the parent doesn't constrain us.
+const DEFAULT_MODULE_SIZE: f64 = 4.0;
+/// Preferred bar height for linear codes.
+const DEFAULT_BAR_HEIGHT: f64 = 80.0;
+
+/// An encoded symbol: a `columns` x `rows` grid of dark/light modules.
+/// Linear codes (Code128) have a single row that is stretched vertically.
+#[derive(Debug, Clone)]
+pub struct Modules {
+    columns: usize,
+    rows: usize,
+    dark: Vec<bool>,
+}
+
+impl Modules {
+    fn is_dark(&self, x: usize, y: usize) -> bool {
+        self.dark[y * self.columns + x]
+    }
+
+    /// Horizontal runs `(start, len)` of dark modules in `row`, so adjacent
+    /// modules are painted as one rect without hairline seams.
+    fn dark_runs(&self, row: usize) -> Vec<(usize, usize)> {
+        let mut runs = Vec::new();
+        let mut x = 0;
+        while x < self.columns {
+            if self.is_dark(x, row) {
+                let start = x;
+                while x < self.columns && self.is_dark(x, row) {
+                    x += 1;
+                }
+                runs.push((start, x - start));
+            } else {
+                x += 1;
+            }
+        }
+        runs
+    }
+}
+
+/// Encode `data` with the given symbology.
+pub fn encode(symbology: Symbology, data: &str) -> Result<Modules, String> {
+    match symbology {
+        Symbology::Qr => {
+            let code = qrcode::QrCode::new(data.as_bytes()).map_err(|e| e.to_string())?;
+            let width = code.width();
+            let dark = code
+                .to_colors()
+                .into_iter()
+                .map(|c| c == qrcode::Color::Dark)
+                .collect();
+            Ok(Modules {
+                columns: width,
+                rows: width,
+                dark,
+            })
+        }
+        Symbology::Code128 => {
+            // Character set B covers printable ASCII, which is what callers pass in practice.
+            let code = barcoders::sym::code128::Code128::new(format!("Ɓ{data}"))
+                .map_err(|e| e.to_string())?;
+            let dark: Vec<bool> = code.encode().into_iter().map(|bit| bit == 1).collect();
+            Ok(Modules {
+                columns: dark.len(),
+                rows: 1,
+                dark,
+            })
+        }
+    }
+}
+
+/// Renders a QR code or Code128 barcode for a string, natively with vello.
+pub struct QrCodeWidget {
+    data: String,
+    symbology: Symbology,
+    modules: Option<Modules>,
+    /// Light margin around the symbol, in modules.
+    quiet_zone: u32,
+    foreground: Color,
+    background: Color,
+    last_size: Size,
+}
+
+impl QrCodeWidget {
+    /// Returns the widget along with the encoding error, if any. A widget whose
+    /// data failed to encode paints only its background.
+    pub fn new(symbology: Symbology, data: String) -> (Self, Option<String>) {
+        let (modules, error) = match encode(symbology, &data) {
+            Ok(modules) => (Some(modules), None),
+            Err(err) => (None, Some(err)),
+        };
+        let widget = Self {
+            data,
+            symbology,
+            modules,
+            quiet_zone: symbology.default_quiet_zone(),
+            foreground: Color::BLACK,
+            background: Color::WHITE,
+            last_size: Size::ZERO,
+        };
+        (widget, error)
+    }
+
+    pub fn with_quiet_zone(mut self, quiet_zone: Option<u32>) -> Self {
+        if let Some(quiet_zone) = quiet_zone {
+            self.quiet_zone = quiet_zone;
+        }
+        self
+    }
+
+    pub fn with_colors(mut self, foreground: Option<Color>, background: Option<Color>) -> Self {
+        if let Some(foreground) = foreground {
+            self.foreground = foreground;
+        }
+        if let Some(background) = background {
+            self.background = background;
+        }
+        self
+    }
+
+    /// Total extent in modules, including the quiet zone on both sides.
+    fn extent(&self) -> (f64, f64) {
+        let quiet = 2.0 * self.quiet_zone as f64;
+        match &self.modules {
+            Some(m) => (m.columns as f64 + quiet, m.rows as f64 + quiet),
+            None => (quiet.max(1.0), quiet.max(1.0)),
+        }
+    }
+
+    /// Re-encode with new data. On failure the previous symbol is cleared and
+    /// the error is returned so the handler can report it.
+    pub fn set_data(this: &mut WidgetMut<'_, Self>, data: String) -> Result<(), String> {
+        let result = encode(this.widget.symbology, &data);
+        this.widget.data = data;
+        let outcome = match result {
+            Ok(modules) => {
+                this.widget.modules = Some(modules);
+                Ok(())
+            }
+            Err(err) => {
+                this.widget.modules = None;
+                Err(err)
+            }
+        };
+        // The module count can change with the data length.
+        this.ctx.request_layout();
+        this.ctx.request_accessibility_update();
+        outcome
+    }
+
+    pub fn set_foreground(this: &mut WidgetMut<'_, Self>, foreground: Color) {
+        this.widget.foreground = foreground;
+        this.ctx.request_render();
+    }
+}
+
+impl Widget for QrCodeWidget {
+    type Action = NoAction;
+
+    fn accepts_pointer_interaction(&self) -> bool {
+        false
+    }
+
+    fn register_children(&mut self, _ctx: &mut RegisterCtx<'_>) {}
+
+    fn update(
+        &mut self,
+        _ctx: &mut UpdateCtx<'_>,
+        _props: &mut PropertiesMut<'_>,
+        _event: &Update,
+    ) {
+    }
+
+    fn measure(
+        &mut self,
+        _ctx: &mut MeasureCtx<'_>,
+        _props: &PropertiesRef<'_>,
+        axis: masonry::kurbo::Axis,
+        len_req: masonry::layout::LenReq,
+        _cross_length: Option<f64>,
+    ) -> f64 {
+        let (columns, rows) = self.extent();
+        let preferred = match (axis, self.symbology) {
+            (masonry::kurbo::Axis::Horizontal, _) => columns * DEFAULT_MODULE_SIZE,
+            (masonry::kurbo::Axis::Vertical, Symbology::Qr) => rows * DEFAULT_MODULE_SIZE,
+            (masonry::kurbo::Axis::Vertical, Symbology::Code128) => DEFAULT_BAR_HEIGHT,
+        };
+        match len_req {
+            masonry::layout::LenReq::FitContent(space) => preferred.min(space),
+            _ => preferred,
+        }
+    }
+
+    fn layout(
+        &mut self,
+        _ctx: &mut LayoutCtx<'_>,
+        _props: &PropertiesRef<'_>,
+        size: masonry::kurbo::Size,
+    ) {
+        self.last_size = size;
+    }
+
+    fn paint(&mut self, _ctx: &mut PaintCtx<'_>, _props: &PropertiesRef<'_>, scene: &mut Scene) {
+        let size = self.last_size;
+        if size.width <= 0.0 || size.height <= 0.0 {
+            return;
+        }
+
+        let (columns, rows) = self.extent();
+        // QR modules stay square; linear bars stretch to the full height.
+        let (module_w, module_h) = match self.symbology {
+            Symbology::Qr => {
+                let module = (size.width / columns).min(size.height / rows);
+                (module, module)
+            }
+            Symbology::Code128 => (size.width / columns, size.height / rows),
+        };
+        let symbol = Size::new(columns * module_w, rows * module_h);
+        let origin = Point::new(
+            (size.width - symbol.width) * 0.5,
+            (size.height - symbol.height) * 0.5,
+        );
+
+        scene.fill(
+            Fill::NonZero,
+            Affine::IDENTITY,
+            self.background,
+            None,
+            &Rect::from_origin_size(origin, symbol),
+        );
+
+        let Some(modules) = &self.modules else {
+            return;
+        };
+        let quiet = self.quiet_zone as f64;
+        for row in 0..modules.rows {
+            let y0 = origin.y + (row as f64 + quiet) * module_h;
+            // For linear codes the single row covers everything between the quiet zones.
+            let y1 = if matches!(self.symbology, Symbology::Code128) {
+                origin.y + symbol.height - quiet * module_h
+            } else {
+                y0 + module_h
+            };
+            for (start, len) in modules.dark_runs(row) {
+                let x0 = origin.x + (start as f64 + quiet) * module_w;
+                let rect = Rect::new(x0, y0, x0 + len as f64 * module_w, y1);
+                scene.fill(
+                    Fill::NonZero,
+                    Affine::IDENTITY,
+                    self.foreground,
+                    None,
+                    &rect,
+                );
+            }
+        }
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::Image
+    }
+
+    fn accessibility(
+        &mut self,
+        _ctx: &mut AccessCtx<'_>,
+        _props: &PropertiesRef<'_>,
+        node: &mut Node,
+    ) {
+        node.set_label(self.data.clone());
+    }
+
+    fn children_ids(&self) -> ChildrenIds {
+        ChildrenIds::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_qr_is_square() {
+        let modules = encode(Symbology::Qr, "hello").unwrap();
+        assert_eq!(modules.columns, modules.rows);
+        // Version 1 symbols are 21x21 and start with a finder pattern.
+        assert_eq!(modules.columns, 21);
+        assert!(modules.is_dark(0, 0));
+    }
+
+    #[test]
+    fn test_code128_is_single_row() {
+        let modules = encode(Symbology::Code128, "VELLUM-42").unwrap();
+        assert_eq!(modules.rows, 1);
+        assert!(modules.is_dark(0, 0));
+        assert!(encode(Symbology::Code128, "ünïcode").is_err());
+    }
+
+    #[test]
+    fn test_dark_runs_merge_adjacent_modules() {
+        let modules = Modules {
+            columns: 6,
+            rows: 1,
+            dark: vec![true, true, false, true, false, true],
+        };
+        assert_eq!(modules.dark_runs(0), vec![(0, 2), (3, 1), (5, 1)]);
+    }
+}
@@ -0,0 +1,342 @@
+use std::any::TypeId;
+
+use masonry::accesskit::{Node, Role};
+use masonry::core::{
+    AccessCtx, ChildrenIds, EventCtx, HasProperty, LayoutCtx, MeasureCtx, PaintCtx, PointerEvent,
+    PropertiesMut, PropertiesRef, RegisterCtx, Update, UpdateCtx, Widget, WidgetMut,
+};
+use masonry::kurbo::{Affine, Circle, Point, Rect, RoundedRect, Size};
+use masonry::peniko::{Color, Fill};
+use masonry::properties::ContentColor;
+use masonry::vello::Scene;
+
+const THUMB_RADIUS: f64 = 9.0;
+const TRACK_THICKNESS: f64 = 4.0;
+const DEFAULT_WIDTH: f64 = 200.0;
+const TRACK_COLOR: Color = Color::from_rgba8(255, 255, 255, 60);
+const THUMB_COLOR: Color = Color::WHITE;
+
+/// Action emitted whenever either thumb of a RangeSlider moves.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RangeChanged {
+    pub low: f64,
+    pub high: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Thumb {
+    Low,
+    High,
+}
+
+/// A horizontal slider with two thumbs selecting a `[low, high]` sub-range of `[min, max]`.
+pub struct RangeSlider {
+    min: f64,
+    max: f64,
+    low: f64,
+    high: f64,
+    step: Option<f64>,
+    active_thumb: Option<Thumb>,
+    last_size: Size,
+}
+
+impl RangeSlider {
+    pub fn new(min: f64, max: f64, low: f64, high: f64) -> Self {
+        let (min, max) = if min <= max { (min, max) } else { (max, min) };
+        let mut slider = Self {
+            min,
+            max,
+            low: min,
+            high: max,
+            step: None,
+            active_thumb: None,
+            last_size: Size::ZERO,
+        };
+        slider.set_range_impl(low, high);
+        slider
+    }
+
+    pub fn with_step(mut self, step: f64) -> Self {
+        if step > 0.0 {
+            self.step = Some(step);
+            let (low, high) = (self.low, self.high);
+            self.set_range_impl(low, high);
+        }
+        self
+    }
+
+    /// Clamp a raw value into `[min, max]` and snap it to the configured step.
+    fn snap(&self, value: f64) -> f64 {
+        let clamped = value.clamp(self.min, self.max);
+        match self.step {
+            Some(step) => {
+                let snapped = self.min + ((clamped - self.min) / step).round() * step;
+                snapped.clamp(self.min, self.max)
+            }
+            None => clamped,
+        }
+    }
+
+    /// Set both thumbs, keeping `low <= high`. Returns true if anything changed.
+    fn set_range_impl(&mut self, low: f64, high: f64) -> bool {
+        let (low, high) = if low <= high {
+            (low, high)
+        } else {
+            (high, low)
+        };
+        let low = self.snap(low);
+        let high = self.snap(high);
+        let changed = low != self.low || high != self.high;
+        self.low = low;
+        self.high = high;
+        changed
+    }
+
+    /// Move a single thumb without letting it cross the other one.
+    fn move_thumb(&mut self, thumb: Thumb, value: f64) -> bool {
+        let value = self.snap(value);
+        match thumb {
+            Thumb::Low => self.set_range_impl(value.min(self.high), self.high),
+            Thumb::High => self.set_range_impl(self.low, value.max(self.low)),
+        }
+    }
+
+    fn track_span(&self) -> (f64, f64) {
+        let start = THUMB_RADIUS;
+        let end = (self.last_size.width - THUMB_RADIUS).max(start);
+        (start, end)
+    }
+
+    fn value_to_x(&self, value: f64) -> f64 {
+        let (start, end) = self.track_span();
+        let range = self.max - self.min;
+        if range <= 0.0 {
+            return start;
+        }
+        start + (value - self.min) / range * (end - start)
+    }
+
+    fn x_to_value(&self, x: f64) -> f64 {
+        let (start, end) = self.track_span();
+        if end <= start {
+            return self.min;
+        }
+        let t = ((x - start) / (end - start)).clamp(0.0, 1.0);
+        self.min + t * (self.max - self.min)
+    }
+
+    /// Pick the thumb closest to a pointer x position.
+    /// When both thumbs overlap, pick the one that can move towards the pointer.
+    fn nearest_thumb(&self, x: f64) -> Thumb {
+        let low_x = self.value_to_x(self.low);
+        let high_x = self.value_to_x(self.high);
+        if (low_x - high_x).abs() < f64::EPSILON {
+            return if x < low_x { Thumb::Low } else { Thumb::High };
+        }
+        if (x - low_x).abs() <= (x - high_x).abs() {
+            Thumb::Low
+        } else {
+            Thumb::High
+        }
+    }
+
+    pub fn set_range(this: &mut WidgetMut<'_, Self>, low: f64, high: f64) {
+        if this.widget.set_range_impl(low, high) {
+            this.ctx.request_render();
+            this.ctx.request_accessibility_update();
+        }
+    }
+}
+
+impl HasProperty<ContentColor> for RangeSlider {}
+
+impl Widget for RangeSlider {
+    type Action = RangeChanged;
+
+    fn on_pointer_event(
+        &mut self,
+        ctx: &mut EventCtx<'_>,
+        _props: &mut PropertiesMut<'_>,
+        event: &PointerEvent,
+    ) {
+        match event {
+            PointerEvent::Down(button_event) => {
+                ctx.capture_pointer();
+                let local = ctx.local_position(button_event.state.position);
+                let thumb = self.nearest_thumb(local.x);
+                self.active_thumb = Some(thumb);
+                if self.move_thumb(thumb, self.x_to_value(local.x)) {
+                    ctx.submit_action::<Self::Action>(RangeChanged {
+                        low: self.low,
+                        high: self.high,
+                    });
+                    ctx.request_render();
+                    ctx.request_accessibility_update();
+                }
+            }
+            PointerEvent::Move(update) => {
+                if let Some(thumb) = self.active_thumb {
+                    let local = ctx.local_position(update.current.position);
+                    if self.move_thumb(thumb, self.x_to_value(local.x)) {
+                        ctx.submit_action::<Self::Action>(RangeChanged {
+                            low: self.low,
+                            high: self.high,
+                        });
+                        ctx.request_render();
+                        ctx.request_accessibility_update();
+                    }
+                }
+            }
+            PointerEvent::Up(_) | PointerEvent::Cancel(_) => {
+                self.active_thumb = None;
+                ctx.request_render();
+            }
+            _ => {}
+        }
+    }
+
+    fn register_children(&mut self, _ctx: &mut RegisterCtx<'_>) {}
+
+    fn property_changed(&mut self, ctx: &mut UpdateCtx<'_>, property_type: TypeId) {
+        if property_type == TypeId::of::<ContentColor>() {
+            ctx.request_render();
+        }
+    }
+
+    fn update(
+        &mut self,
+        _ctx: &mut UpdateCtx<'_>,
+        _props: &mut PropertiesMut<'_>,
+        _event: &Update,
+    ) {
+    }
+
+    fn measure(
+        &mut self,
+        _ctx: &mut MeasureCtx<'_>,
+        _props: &PropertiesRef<'_>,
+        axis: masonry::kurbo::Axis,
+        len_req: masonry::layout::LenReq,
+        _cross_length: Option<f64>,
+    ) -> f64 {
+        let preferred = match axis {
+            masonry::kurbo::Axis::Horizontal => DEFAULT_WIDTH,
+            masonry::kurbo::Axis::Vertical => THUMB_RADIUS * 2.0,
+        };
+
+        match len_req {
+            masonry::layout::LenReq::MinContent => match axis {
+                masonry::kurbo::Axis::Horizontal => THUMB_RADIUS * 4.0,
+                masonry::kurbo::Axis::Vertical => preferred,
+            },
+            masonry::layout::LenReq::MaxContent => preferred,
+            masonry::layout::LenReq::FitContent(space) => match axis {
+                masonry::kurbo::Axis::Horizontal => space,
+                masonry::kurbo::Axis::Vertical => preferred.min(space),
+            },
+        }
+    }
+
+    fn layout(
+        &mut self,
+        _ctx: &mut LayoutCtx<'_>,
+        _props: &PropertiesRef<'_>,
+        size: masonry::kurbo::Size,
+    ) {
+        self.last_size = size;
+    }
+
+    fn paint(&mut self, _ctx: &mut PaintCtx<'_>, props: &PropertiesRef<'_>, scene: &mut Scene) {
+        let accent = props.get::<ContentColor>().color;
+        let center_y = self.last_size.height * 0.5;
+        let (start, end) = self.track_span();
+
+        let track = RoundedRect::from_rect(
+            Rect::new(
+                start,
+                center_y - TRACK_THICKNESS * 0.5,
+                end,
+                center_y + TRACK_THICKNESS * 0.5,
+            ),
+            TRACK_THICKNESS * 0.5,
+        );
+        scene.fill(Fill::NonZero, Affine::IDENTITY, TRACK_COLOR, None, &track);
+
+        let low_x = self.value_to_x(self.low);
+        let high_x = self.value_to_x(self.high);
+        let selected = RoundedRect::from_rect(
+            Rect::new(
+                low_x,
+                center_y - TRACK_THICKNESS * 0.5,
+                high_x,
+                center_y + TRACK_THICKNESS * 0.5,
+            ),
+            TRACK_THICKNESS * 0.5,
+        );
+        scene.fill(Fill::NonZero, Affine::IDENTITY, accent, None, &selected);
+
+        for (thumb, x) in [(Thumb::Low, low_x), (Thumb::High, high_x)] {
+            let circle = Circle::new(Point::new(x, center_y), THUMB_RADIUS);
+            let color = if self.active_thumb == Some(thumb) {
+                accent
+            } else {
+                THUMB_COLOR
+            };
+            scene.fill(Fill::NonZero, Affine::IDENTITY, color, None, &circle);
+        }
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::Slider
+    }
+
+    /// One slider node: its numeric value is the low thumb, so assistive
+    /// tools can read both ends only from the text value ("20 – 80") and can't
+    /// move the high thumb.
+    fn accessibility(
+        &mut self,
+        _ctx: &mut AccessCtx<'_>,
+        _props: &PropertiesRef<'_>,
+        node: &mut Node,
+    ) {
+        node.set_min_numeric_value(self.min);
+        node.set_max_numeric_value(self.max);
+        node.set_numeric_value(self.low);
+        node.set_value(format!("{} – {}", self.low, self.high));
+    }
+
+    fn children_ids(&self) -> ChildrenIds {
+        ChildrenIds::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_orders_and_clamps_values() {
+        let slider = RangeSlider::new(0.0, 100.0, 80.0, 120.0);
+        assert_eq!(slider.low, 80.0);
+        assert_eq!(slider.high, 100.0);
+
+        let swapped = RangeSlider::new(0.0, 10.0, 7.0, 3.0);
+        assert_eq!((swapped.low, swapped.high), (3.0, 7.0));
+    }
+
+    #[test]
+    fn test_step_snapping() {
+        let slider = RangeSlider::new(0.0, 100.0, 12.0, 88.0).with_step(10.0);
+        assert_eq!((slider.low, slider.high), (10.0, 90.0));
+    }
+
+    #[test]
+    fn test_thumbs_do_not_cross() {
+        let mut slider = RangeSlider::new(0.0, 100.0, 20.0, 40.0);
+        assert!(slider.move_thumb(Thumb::Low, 60.0));
+        assert_eq!((slider.low, slider.high), (40.0, 40.0));
+
+        assert!(!slider.move_thumb(Thumb::High, 10.0));
+        assert_eq!((slider.low, slider.high), (40.0, 40.0));
+    }
+}
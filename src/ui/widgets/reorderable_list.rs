@@ -0,0 +1,50 @@
+use masonry::app::RenderRoot;
+use masonry::core::{NewWidget, WidgetOptions};
+
+use crate::ipc::{BoxStyle, WidgetKind};
+use crate::ui::styles::{build_box_properties, color_value_to_peniko, scaled};
+use crate::ui::widget_manager::{WidgetInfo, WidgetManager};
+use crate::ui::widgets::reorderable_list_impl::ReorderableList;
+use crate::ui::widgets::utils::{WrapperOptions, add_to_parent};
+
+pub fn create(
+    render_root: &mut RenderRoot,
+    widget_manager: &mut WidgetManager,
+    id: String,
+    parent_id: Option<String>,
+    style: Option<BoxStyle>,
+    child_index: usize,
+    wrappers: WrapperOptions,
+) {
+    let style_ref = style.as_ref();
+    let list = ReorderableList::new()
+        .with_gap(scaled(style_ref.and_then(|s| s.gap).unwrap_or(0.0)))
+        .with_indicator_color(
+            style_ref
+                .and_then(|s| s.fill_color.as_ref())
+                .map(color_value_to_peniko),
+        );
+
+    let props = style_ref.map(build_box_properties).unwrap_or_default();
+    let new_widget = NewWidget::new_with(list, None, WidgetOptions::default(), props);
+    let widget_id = new_widget.id();
+
+    if add_to_parent(
+        render_root,
+        widget_manager,
+        &parent_id,
+        new_widget,
+        style_ref.and_then(|s| s.flex),
+        wrappers,
+    ) {
+        widget_manager.register_widget(
+            id,
+            WidgetInfo {
+                widget_id,
+                kind: WidgetKind::ReorderableList,
+                parent_id: parent_id.clone(),
+                child_index,
+            },
+        );
+    }
+}
@@ -0,0 +1,405 @@
+use masonry::accesskit::{Node, Role};
+use masonry::core::{
+    AccessCtx, ChildrenIds, EventCtx, LayoutCtx, MeasureCtx, NewWidget, PaintCtx, PointerEvent,
+    PropertiesMut, PropertiesRef, RegisterCtx, Update, UpdateCtx, Widget, WidgetMut, WidgetPod,
+};
+use masonry::kurbo::{Affine, Axis, Point, Rect, Size};
+use masonry::layout::{LayoutSize, LenReq};
+use masonry::peniko::{Color, Fill};
+use masonry::vello::Scene;
+
+use crate::ui::styles::scaled;
+
+/// Pointer travel before a press turns into a drag, so clicks still work.
+const DRAG_THRESHOLD: f64 = 4.0;
+/// Fraction of the remaining distance rows slide each frame.
+const SLIDE_FACTOR: f64 = 0.35;
+const INDICATOR_THICKNESS: f64 = 2.0;
+const DEFAULT_INDICATOR_COLOR: Color = Color::from_rgba8(59, 130, 246, 255);
+
+/// Submitted when a dragged row is dropped in a new slot. The list has
+/// already moved the row; JS only updates its model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ItemMoved {
+    pub from: usize,
+    pub to: usize,
+}
+
+struct Row {
+    pod: WidgetPod<dyn Widget>,
+    height: f64,
+    /// Where the row is drawn; slides toward its slot while dragging.
+    shown_top: f64,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Gesture {
+    /// Pressed on row `index` at `y`, not yet past the drag threshold.
+    Pressed { index: usize, y: f64 },
+    /// Row `from` follows the pointer; `grab` is the pointer offset into it.
+    Dragging {
+        from: usize,
+        to: usize,
+        y: f64,
+        grab: f64,
+    },
+}
+
+/// A column whose children can be dragged into a new order.
+///
+/// While a row is dragged it follows the pointer and the other rows slide
+/// apart to open a gap at the drop slot, marked by an indicator line. On
+/// release the row is moved and `ItemMoved` is submitted.
+pub struct ReorderableList {
+    rows: Vec<Row>,
+    gap: f64,
+    indicator_color: Color,
+    gesture: Option<Gesture>,
+    width: f64,
+}
+
+impl ReorderableList {
+    pub fn new() -> Self {
+        Self {
+            rows: Vec::new(),
+            gap: 0.0,
+            indicator_color: DEFAULT_INDICATOR_COLOR,
+            gesture: None,
+            width: 0.0,
+        }
+    }
+
+    pub fn with_gap(mut self, gap: f64) -> Self {
+        self.gap = gap.max(0.0);
+        self
+    }
+
+    pub fn with_indicator_color(mut self, color: Option<Color>) -> Self {
+        if let Some(color) = color {
+            self.indicator_color = color;
+        }
+        self
+    }
+
+    pub fn set_gap(this: &mut WidgetMut<'_, Self>, gap: f64) {
+        this.widget.gap = gap.max(0.0);
+        this.ctx.request_layout();
+    }
+
+    pub fn set_indicator_color(this: &mut WidgetMut<'_, Self>, color: Color) {
+        this.widget.indicator_color = color;
+        this.ctx.request_render();
+    }
+
+    pub fn add_child(this: &mut WidgetMut<'_, Self>, child: NewWidget<impl Widget + ?Sized>) {
+        this.widget.rows.push(Row {
+            pod: child.erased().to_pod(),
+            height: 0.0,
+            shown_top: f64::NAN,
+        });
+        this.ctx.children_changed();
+    }
+
+    pub fn remove_child(this: &mut WidgetMut<'_, Self>, index: usize) {
+        // Indices shift under an in-flight drag; drop it.
+        this.widget.gesture = None;
+        let row = this.widget.rows.remove(index);
+        this.ctx.remove_child(row.pod);
+    }
+
+    fn heights(&self) -> Vec<f64> {
+        self.rows.iter().map(|row| row.height).collect()
+    }
+
+    fn row_at(&self, y: f64) -> Option<usize> {
+        let tops = slot_tops(&self.heights(), self.gap, None);
+        tops.iter()
+            .zip(&self.rows)
+            .position(|(top, row)| y >= *top && y < top + row.height)
+    }
+
+    fn reorder(&self) -> Option<(usize, usize)> {
+        match self.gesture {
+            Some(Gesture::Dragging { from, to, .. }) => Some((from, to)),
+            _ => None,
+        }
+    }
+
+    /// Slide every row a step toward its slot. True while any is still moving.
+    fn slide(&mut self) -> bool {
+        let tops = slot_tops(&self.heights(), self.gap, self.reorder());
+        let mut moving = false;
+        for (row, top) in self.rows.iter_mut().zip(tops) {
+            let next = if row.shown_top.is_nan() {
+                top
+            } else {
+                row.shown_top + (top - row.shown_top) * SLIDE_FACTOR
+            };
+            row.shown_top = if (next - top).abs() < 0.5 { top } else { next };
+            moving |= row.shown_top != top;
+        }
+        moving
+    }
+}
+
+impl Default for ReorderableList {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Top of each row's slot, indexed by the row's current position. With
+/// `reorder = Some((from, to))` row `from` is taken out and the gap opens at
+/// `to` (its slot is where it will land).
+fn slot_tops(heights: &[f64], gap: f64, reorder: Option<(usize, usize)>) -> Vec<f64> {
+    let mut order: Vec<usize> = (0..heights.len()).collect();
+    if let Some((from, to)) = reorder {
+        let moved = order.remove(from);
+        order.insert(to, moved);
+    }
+    let mut tops = vec![0.0; heights.len()];
+    let mut y = 0.0;
+    for index in order {
+        tops[index] = y;
+        y += heights[index] + gap;
+    }
+    tops
+}
+
+/// Slot a row dragged out of `from` lands in when its middle is at `center`.
+fn drop_index(heights: &[f64], gap: f64, from: usize, center: f64) -> usize {
+    let mut y = 0.0;
+    let mut to = 0;
+    for (index, height) in heights.iter().enumerate() {
+        if index == from {
+            continue;
+        }
+        if center > y + height / 2.0 {
+            to += 1;
+        }
+        y += height + gap;
+    }
+    to
+}
+
+impl Widget for ReorderableList {
+    type Action = ItemMoved;
+
+    fn on_pointer_event(
+        &mut self,
+        ctx: &mut EventCtx<'_>,
+        _props: &mut PropertiesMut<'_>,
+        event: &PointerEvent,
+    ) {
+        match event {
+            PointerEvent::Down(button_event) => {
+                let local = ctx.local_position(button_event.state.position);
+                if let Some(index) = self.row_at(local.y) {
+                    self.gesture = Some(Gesture::Pressed { index, y: local.y });
+                }
+            }
+            PointerEvent::Move(update) => {
+                let y = ctx.local_position(update.current.position).y;
+                match self.gesture {
+                    Some(Gesture::Pressed { index, y: start })
+                        if (y - start).abs() > scaled(DRAG_THRESHOLD) =>
+                    {
+                        let tops = slot_tops(&self.heights(), self.gap, None);
+                        self.gesture = Some(Gesture::Dragging {
+                            from: index,
+                            to: index,
+                            y,
+                            grab: start - tops[index],
+                        });
+                        ctx.capture_pointer();
+                    }
+                    Some(Gesture::Dragging { from, grab, .. }) => {
+                        let center = y - grab + self.rows[from].height / 2.0;
+                        let to = drop_index(&self.heights(), self.gap, from, center);
+                        self.gesture = Some(Gesture::Dragging { from, to, y, grab });
+                    }
+                    _ => return,
+                }
+                ctx.request_anim_frame();
+                ctx.request_layout();
+                ctx.set_handled();
+            }
+            PointerEvent::Up(_) => {
+                if let Some(Gesture::Dragging { from, to, .. }) = self.gesture.take() {
+                    if from != to {
+                        let row = self.rows.remove(from);
+                        self.rows.insert(to, row);
+                        ctx.children_changed();
+                        ctx.submit_action::<Self::Action>(ItemMoved { from, to });
+                    }
+                    ctx.request_anim_frame();
+                    ctx.request_layout();
+                    ctx.set_handled();
+                }
+            }
+            PointerEvent::Cancel(_) => {
+                if self.gesture.take().is_some() {
+                    ctx.request_anim_frame();
+                    ctx.request_layout();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn register_children(&mut self, ctx: &mut RegisterCtx<'_>) {
+        for row in &mut self.rows {
+            ctx.register_child(&mut row.pod);
+        }
+    }
+
+    fn on_anim_frame(
+        &mut self,
+        ctx: &mut UpdateCtx<'_>,
+        _props: &mut PropertiesMut<'_>,
+        _interval: u64,
+    ) {
+        if self.slide() {
+            ctx.request_anim_frame();
+        }
+        ctx.request_layout();
+    }
+
+    fn update(
+        &mut self,
+        _ctx: &mut UpdateCtx<'_>,
+        _props: &mut PropertiesMut<'_>,
+        _event: &Update,
+    ) {
+    }
+
+    fn measure(
+        &mut self,
+        ctx: &mut MeasureCtx<'_>,
+        _props: &PropertiesRef<'_>,
+        axis: Axis,
+        len_req: LenReq,
+        cross_length: Option<f64>,
+    ) -> f64 {
+        let lengths = self.rows.iter_mut().map(|row| {
+            ctx.compute_length(
+                &mut row.pod,
+                len_req.into(),
+                LayoutSize::maybe(axis.cross(), cross_length),
+                axis,
+                cross_length,
+            )
+        });
+        match axis {
+            Axis::Horizontal => lengths.fold(0.0, f64::max),
+            Axis::Vertical => {
+                let gaps = self.gap * self.rows.len().saturating_sub(1) as f64;
+                lengths.sum::<f64>() + gaps
+            }
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx<'_>, _props: &PropertiesRef<'_>, size: Size) {
+        self.width = size.width;
+        for row in &mut self.rows {
+            row.height = ctx.compute_length(
+                &mut row.pod,
+                LenReq::MaxContent.into(),
+                size.into(),
+                Axis::Vertical,
+                Some(size.width),
+            );
+            ctx.run_layout(&mut row.pod, Size::new(size.width, row.height));
+        }
+        // Rows added or resized outside a drag jump straight to their slot.
+        if self.gesture.is_none() {
+            let tops = slot_tops(&self.heights(), self.gap, None);
+            for (row, top) in self.rows.iter_mut().zip(tops) {
+                if row.shown_top.is_nan() {
+                    row.shown_top = top;
+                }
+            }
+        }
+        for index in 0..self.rows.len() {
+            let top = match self.gesture {
+                Some(Gesture::Dragging { from, y, grab, .. }) if from == index => y - grab,
+                _ => self.rows[index].shown_top,
+            };
+            ctx.place_child(&mut self.rows[index].pod, Point::new(0.0, top));
+        }
+    }
+
+    fn paint(&mut self, _ctx: &mut PaintCtx<'_>, _props: &PropertiesRef<'_>, _scene: &mut Scene) {}
+
+    /// The drop indicator is drawn over the rows, centered in the open gap.
+    fn post_paint(
+        &mut self,
+        _ctx: &mut PaintCtx<'_>,
+        _props: &PropertiesRef<'_>,
+        scene: &mut Scene,
+    ) {
+        let Some((from, to)) = self.reorder() else {
+            return;
+        };
+        let tops = slot_tops(&self.heights(), self.gap, Some((from, to)));
+        let thickness = scaled(INDICATOR_THICKNESS);
+        let y = (tops[from] - self.gap / 2.0).max(0.0);
+        scene.fill(
+            Fill::NonZero,
+            Affine::IDENTITY,
+            self.indicator_color,
+            None,
+            &Rect::new(0.0, y, self.width, y + thickness),
+        );
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::List
+    }
+
+    fn accessibility(
+        &mut self,
+        _ctx: &mut AccessCtx<'_>,
+        _props: &PropertiesRef<'_>,
+        _node: &mut Node,
+    ) {
+    }
+
+    fn children_ids(&self) -> ChildrenIds {
+        // The dragged row comes last so it paints over the others.
+        let dragged = self.reorder().map(|(from, _)| from);
+        let ids = self.rows.iter().enumerate();
+        ids.clone()
+            .filter(|(index, _)| Some(*index) != dragged)
+            .chain(ids.filter(|(index, _)| Some(*index) == dragged))
+            .map(|(_, row)| row.pod.id())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEIGHTS: [f64; 3] = [10.0, 20.0, 30.0];
+
+    #[test]
+    fn test_slot_tops_open_gap_at_drop_slot() {
+        assert_eq!(slot_tops(&HEIGHTS, 0.0, None), vec![0.0, 10.0, 30.0]);
+        assert_eq!(slot_tops(&HEIGHTS, 5.0, None), vec![0.0, 15.0, 40.0]);
+        // First row dragged to the end: the others close up above it.
+        assert_eq!(
+            slot_tops(&HEIGHTS, 0.0, Some((0, 2))),
+            vec![50.0, 0.0, 20.0]
+        );
+    }
+
+    #[test]
+    fn test_drop_index_follows_center() {
+        // Other rows (20 and 30 tall) have middles at 10 and 35.
+        assert_eq!(drop_index(&HEIGHTS, 0.0, 0, 5.0), 0);
+        assert_eq!(drop_index(&HEIGHTS, 0.0, 0, 15.0), 1);
+        assert_eq!(drop_index(&HEIGHTS, 0.0, 0, 40.0), 2);
+        assert_eq!(drop_index(&HEIGHTS, 0.0, 2, -10.0), 0);
+    }
+}
@@ -0,0 +1,1076 @@
+use masonry::accesskit::{Action, ActionData, Node, Role};
+use masonry::core::keyboard::{Key, KeyState, NamedKey};
+use masonry::core::{
+    AccessCtx, AccessEvent, ChildrenIds, EventCtx, LayoutCtx, MeasureCtx, NewWidget, PaintCtx,
+    PointerEvent, PointerScrollEvent, PointerType, PropertiesMut, PropertiesRef, RegisterCtx,
+    ScrollDelta, TextEvent, Update, UpdateCtx, Widget, WidgetMut, WidgetPod,
+};
+use masonry::kurbo::{Affine, Arc, Axis, Point, Rect, RoundedRect, Size, Stroke, Vec2};
+use masonry::layout::{LayoutSize, LenReq};
+use masonry::peniko::{Color, Fill};
+use masonry::vello::Scene;
+
+use crate::ipc::BoxStyle;
+use crate::ui::styles::{color_value_to_peniko, scaled};
+use crate::ui::widgets::{video_registry, viewport};
+
+const DEFAULT_THICKNESS: f64 = 8.0;
+const DEFAULT_THUMB_COLOR: Color = Color::from_rgba8(255, 255, 255, 90);
+const DEFAULT_TRACK_COLOR: Color = Color::TRANSPARENT;
+/// Thumbs never shrink below this length, however long the content.
+const MIN_THUMB_LENGTH: f64 = 24.0;
+/// Pixels scrolled per wheel line.
+const LINE_HEIGHT: f64 = 48.0;
+/// Auto-hidden scrollbars start fading this long after the last activity.
+const HIDE_DELAY_NS: u64 = 800_000_000;
+const FADE_NS: u64 = 200_000_000;
+/// Pull distance that triggers a refresh; the spinner rests this far down.
+const REFRESH_THRESHOLD: f64 = 64.0;
+/// The pull moves this fraction of the overscroll, so it feels stretchy.
+const PULL_RESISTANCE: f64 = 0.5;
+/// A short pull springs back once no overscroll arrives for this long.
+const PULL_SETTLE_NS: u64 = 120_000_000;
+/// Fraction of the remaining pull kept each frame while springing back.
+const PULL_DECAY: f64 = 0.8;
+const SPINNER_RADIUS: f64 = 10.0;
+const SPINNER_COLOR: Color = Color::from_rgba8(255, 255, 255, 200);
+/// Nanoseconds per spinner revolution.
+const SPIN_PERIOD_NS: f64 = 1_000_000_000.0;
+/// Children further than this many viewport heights from the viewport are
+/// stashed: not laid out or painted until they come back in range.
+const CULL_DISTANCE: f64 = 1.0;
+
+/// Submitted when a pull passes the refresh threshold; the Portal keeps its
+/// spinner until `EndRefresh`.
+#[derive(Debug, Clone, Copy)]
+pub struct RefreshRequested;
+
+/// Scrollbar appearance for a Portal, taken from its style. Unset fields keep
+/// their current value.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ScrollbarStyle {
+    pub thickness: Option<f64>,
+    pub thumb_color: Option<Color>,
+    pub track_color: Option<Color>,
+    pub auto_hide: Option<bool>,
+    pub horizontal: Option<bool>,
+}
+
+impl ScrollbarStyle {
+    pub fn from_style(style: &BoxStyle) -> Self {
+        Self {
+            thickness: style.scrollbar_width.map(|w| scaled(w.max(0.0))),
+            thumb_color: style
+                .scrollbar_thumb_color
+                .as_ref()
+                .map(color_value_to_peniko),
+            track_color: style
+                .scrollbar_track_color
+                .as_ref()
+                .map(color_value_to_peniko),
+            auto_hide: style.scrollbar_auto_hide,
+            horizontal: style.horizontal_scroll,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// A Portal child, stacked in a column.
+struct ScrollChild {
+    pod: WidgetPod<dyn Widget>,
+    /// Pinned to the top while its section scrolls.
+    sticky: bool,
+    /// Offset of the child's slot in the content, from the last layout.
+    top: f64,
+    /// Kept from the last layout while the child is culled.
+    height: f64,
+    /// Stashed for being far outside the viewport.
+    culled: bool,
+    /// Overlapped the viewport at the last visibility update.
+    visible: bool,
+}
+
+/// An in-progress scrollbar thumb drag.
+#[derive(Debug, Clone, Copy)]
+struct Drag {
+    axis: Axis,
+    /// Pointer position along `axis` minus the thumb start when grabbed.
+    grab: f64,
+}
+
+/// Scroll container behind `Portal` widgets.
+///
+/// Masonry's Portal paints its scrollbars from theme constants, so this widget
+/// scrolls its content itself and paints scrollbars whose thickness, colors
+/// and auto-hide behavior come from the Portal's style. Horizontal scrolling
+/// can be turned off, in which case the content is laid out at the viewport
+/// width. Like Portal, it scrolls a focused child into view, scrolls with the
+/// arrow, Page Up/Down, Home and End keys, and takes accessibility scroll
+/// actions.
+///
+/// Children are stacked in a column at the content width. A sticky child
+/// (a section header) stays pinned to the top while the children after it,
+/// up to the next sticky child, scroll underneath; the next header pushes it
+/// out. Wheel events the area cannot use (already at the edge) bubble on to
+/// an enclosing Portal.
+///
+/// With pull-to-refresh on, scrolling (or dragging a touch) past the top pulls
+/// the content down over a spinner; past the threshold it submits
+/// `RefreshRequested` and holds the spinner until `end_refresh`.
+///
+/// Children far outside the viewport are culled (see `update_visibility`),
+/// keeping their last height so the content length and scrollbars hold still.
+pub struct ScrollArea {
+    children: Vec<ScrollChild>,
+    offset: Vec2,
+    viewport: Size,
+    content: Size,
+    thickness: f64,
+    thumb_color: Color,
+    track_color: Color,
+    auto_hide: bool,
+    horizontal: bool,
+    drag: Option<Drag>,
+    /// Scrollbar opacity while auto-hiding, in `[0, 1]`.
+    visibility: f64,
+    /// Nanoseconds since the last scroll, drag or hover.
+    idle_ns: u64,
+    pull_to_refresh: bool,
+    /// How far the content is pulled down past the top.
+    pull: f64,
+    /// Nanoseconds since the pull last grew.
+    pull_idle_ns: u64,
+    refreshing: bool,
+    /// Spinner rotation, in turns.
+    spin: f64,
+    /// Last position of an active touch pan.
+    touch_y: Option<f64>,
+}
+
+impl ScrollArea {
+    pub fn new() -> Self {
+        Self {
+            children: Vec::new(),
+            offset: Vec2::ZERO,
+            viewport: Size::ZERO,
+            content: Size::ZERO,
+            thickness: scaled(DEFAULT_THICKNESS),
+            thumb_color: DEFAULT_THUMB_COLOR,
+            track_color: DEFAULT_TRACK_COLOR,
+            auto_hide: false,
+            horizontal: true,
+            drag: None,
+            visibility: 1.0,
+            idle_ns: 0,
+            pull_to_refresh: false,
+            pull: 0.0,
+            pull_idle_ns: 0,
+            refreshing: false,
+            spin: 0.0,
+            touch_y: None,
+        }
+    }
+
+    pub fn with_pull_to_refresh(mut self, enabled: bool) -> Self {
+        self.pull_to_refresh = enabled;
+        self
+    }
+
+    /// Hide the refresh spinner once JS has reloaded (`EndRefresh`).
+    pub fn end_refresh(this: &mut WidgetMut<'_, Self>) {
+        if this.widget.refreshing {
+            this.widget.refreshing = false;
+            this.widget.pull_idle_ns = PULL_SETTLE_NS;
+            this.ctx.request_anim_frame();
+        }
+    }
+
+    pub fn with_style(mut self, style: &ScrollbarStyle) -> Self {
+        self.apply(style);
+        self
+    }
+
+    fn apply(&mut self, style: &ScrollbarStyle) {
+        if let Some(thickness) = style.thickness {
+            self.thickness = thickness;
+        }
+        if let Some(color) = style.thumb_color {
+            self.thumb_color = color;
+        }
+        if let Some(color) = style.track_color {
+            self.track_color = color;
+        }
+        if let Some(auto_hide) = style.auto_hide {
+            self.auto_hide = auto_hide;
+            self.visibility = if auto_hide { 0.0 } else { 1.0 };
+        }
+        if let Some(horizontal) = style.horizontal {
+            self.horizontal = horizontal;
+            if !horizontal {
+                self.offset.x = 0.0;
+            }
+        }
+    }
+
+    pub fn set_style(this: &mut WidgetMut<'_, Self>, style: &ScrollbarStyle) {
+        if style.is_empty() {
+            return;
+        }
+        this.widget.apply(style);
+        this.ctx.request_layout();
+    }
+
+    pub fn add_child(
+        this: &mut WidgetMut<'_, Self>,
+        child: NewWidget<impl Widget + ?Sized>,
+        sticky: bool,
+    ) {
+        this.widget.children.push(ScrollChild {
+            pod: child.erased().to_pod(),
+            sticky,
+            top: 0.0,
+            height: 0.0,
+            culled: false,
+            visible: false,
+        });
+        this.ctx.children_changed();
+    }
+
+    pub fn remove_child(this: &mut WidgetMut<'_, Self>, index: usize) {
+        let child = this.widget.children.remove(index);
+        this.ctx.remove_child(child.pod);
+    }
+
+    pub fn offset(&self) -> Vec2 {
+        self.offset
+    }
+
+    pub fn viewport_height(&self) -> f64 {
+        self.viewport.height
+    }
+
+    pub fn content_height(&self) -> f64 {
+        self.content.height
+    }
+
+    /// Scroll to `offset` (clamped), as `ExportPdf` does page by page.
+    pub fn set_offset(this: &mut WidgetMut<'_, Self>, offset: Vec2) {
+        if this.widget.scroll_to(offset) {
+            this.ctx.request_layout();
+        }
+    }
+
+    /// Cull the children far outside the viewport and bring back those in
+    /// range. Returns the indices of the children that came into view since
+    /// the last call. Run by the viewport sweep after layout.
+    pub fn update_visibility(this: &mut WidgetMut<'_, Self>) -> Vec<usize> {
+        let view_top = this.widget.offset.y - this.widget.pull;
+        let view_height = this.widget.viewport.height;
+        let mut entered = Vec::new();
+        let mut restashed = false;
+        for index in 0..this.widget.children.len() {
+            let top = this.widget.child_top(index);
+            let child = &mut this.widget.children[index];
+            let (visible, far) = row_visibility(top, child.height, view_top, view_height);
+            // Sticky headers are pinned into view; never cull them.
+            let far = far && !child.sticky;
+            if far != child.culled {
+                child.culled = far;
+                this.ctx.set_stashed(&mut child.pod, far);
+                restashed = true;
+            }
+            if visible && !child.visible {
+                entered.push(index);
+            }
+            child.visible = visible;
+        }
+        if restashed {
+            this.ctx.request_layout();
+        }
+        entered
+    }
+
+    /// Where child `index` sits in the content, after sticky pinning.
+    fn child_top(&self, index: usize) -> f64 {
+        let child = &self.children[index];
+        if !child.sticky {
+            return child.top;
+        }
+        let section_end = self.children[index + 1..]
+            .iter()
+            .find(|next| next.sticky)
+            .map_or(self.content.height, |next| next.top);
+        sticky_top(child.top, child.height, section_end, self.offset.y)
+    }
+
+    fn max_offset(&self) -> Vec2 {
+        max_offset(self.viewport, self.content)
+    }
+
+    /// Move to `offset` (clamped). True if the position changed.
+    fn scroll_to(&mut self, offset: Vec2) -> bool {
+        let max = self.max_offset();
+        let clamped = Vec2::new(offset.x.clamp(0.0, max.x), offset.y.clamp(0.0, max.y));
+        let changed = clamped != self.offset;
+        self.offset = clamped;
+        changed
+    }
+
+    fn bar(&self, axis: Axis) -> Option<(Rect, Rect)> {
+        bar_rects(
+            axis,
+            self.viewport,
+            self.content,
+            self.offset,
+            self.thickness,
+            self.horizontal,
+        )
+    }
+
+    /// Scroll by `delta` (positive moves the content down). At the top, with
+    /// pull-to-refresh on, the overscroll becomes a pull instead. True if
+    /// anything moved.
+    fn scroll_by(&mut self, ctx: &mut EventCtx<'_>, delta: Vec2) -> bool {
+        let mut delta = delta;
+        let mut moved = false;
+        if self.pull_to_refresh && !self.refreshing {
+            if self.pull > 0.0 && delta.y < 0.0 {
+                // Pushing back up releases the pull before scrolling.
+                let released = self.pull.min(-delta.y);
+                self.pull -= released;
+                delta.y += released;
+                moved = true;
+            } else if self.offset.y <= 0.0 && delta.y > 0.0 {
+                self.pull = pull_after(self.pull, delta.y);
+                self.pull_idle_ns = 0;
+                delta.y = 0.0;
+                moved = true;
+                if self.pull >= scaled(REFRESH_THRESHOLD) {
+                    self.refreshing = true;
+                    self.spin = 0.0;
+                    ctx.submit_action::<Self::Action>(RefreshRequested);
+                }
+                ctx.request_anim_frame();
+            }
+        }
+        moved |= self.scroll_to(self.offset - delta);
+        if moved {
+            ctx.request_layout();
+        }
+        moved
+    }
+
+    /// Advance the pull spring and spinner by `interval` ns. Returns whether
+    /// more frames are needed.
+    fn step_pull(&mut self, ctx: &mut UpdateCtx<'_>, interval: u64) -> bool {
+        let target = if self.refreshing {
+            self.spin = (self.spin + interval as f64 / SPIN_PERIOD_NS).fract();
+            scaled(REFRESH_THRESHOLD)
+        } else {
+            if self.touch_y.is_some() {
+                return self.pull > 0.0;
+            }
+            self.pull_idle_ns = self.pull_idle_ns.saturating_add(interval);
+            if self.pull_idle_ns < PULL_SETTLE_NS {
+                return self.pull > 0.0;
+            }
+            0.0
+        };
+        let next = target + (self.pull - target) * PULL_DECAY;
+        let next = if (next - target).abs() < 0.5 {
+            target
+        } else {
+            next
+        };
+        if next != self.pull {
+            self.pull = next;
+            ctx.request_layout();
+        }
+        self.refreshing || self.pull > 0.0
+    }
+
+    /// Scroll so the dragged thumb starts at `pos - grab` along the drag axis.
+    fn drag_to(&mut self, ctx: &mut EventCtx<'_>, pos: f64) {
+        let Some(drag) = self.drag else {
+            return;
+        };
+        let Some((track, thumb)) = self.bar(drag.axis) else {
+            return;
+        };
+        let room = rect_len(drag.axis, track) - rect_len(drag.axis, thumb);
+        if room <= 0.0 {
+            return;
+        }
+        let fraction = ((pos - drag.grab - rect_start(drag.axis, track)) / room).clamp(0.0, 1.0);
+        let max = self.max_offset();
+        let target = match drag.axis {
+            Axis::Horizontal => Vec2::new(fraction * max.x, self.offset.y),
+            Axis::Vertical => Vec2::new(self.offset.x, fraction * max.y),
+        };
+        if self.scroll_to(target) {
+            ctx.request_layout();
+        }
+    }
+
+    /// Scroll to `offset` (clamped) from a key or accessibility action,
+    /// leaving any pull alone. True if the position changed.
+    fn jump_to(&mut self, ctx: &mut EventCtx<'_>, offset: Vec2) -> bool {
+        let moved = self.scroll_to(offset);
+        if moved {
+            self.show(ctx);
+            ctx.request_layout();
+        }
+        moved
+    }
+
+    /// Reveal auto-hidden scrollbars and restart the hide timer.
+    fn show(&mut self, ctx: &mut EventCtx<'_>) {
+        self.idle_ns = 0;
+        if self.auto_hide {
+            self.visibility = 1.0;
+            ctx.request_anim_frame();
+            ctx.request_render();
+        }
+    }
+}
+
+impl Default for ScrollArea {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The pull after `overscroll` more pixels past the top.
+fn pull_after(pull: f64, overscroll: f64) -> f64 {
+    (pull + overscroll * PULL_RESISTANCE).clamp(0.0, 2.0 * scaled(REFRESH_THRESHOLD))
+}
+
+/// Whether a child slot overlaps the `view_height` tall viewport starting at
+/// `view_top`, and whether it is far enough away to cull. A slot that has
+/// not been laid out yet is never culled.
+fn row_visibility(top: f64, height: f64, view_top: f64, view_height: f64) -> (bool, bool) {
+    let bottom = top + height;
+    let view_bottom = view_top + view_height;
+    let visible = bottom > view_top && top < view_bottom;
+    let margin = view_height * CULL_DISTANCE;
+    let far = height > 0.0 && (bottom < view_top - margin || top > view_bottom + margin);
+    (visible, far)
+}
+
+/// Content offset of a sticky child at `top` whose section ends at
+/// `section_end`, with the content scrolled down by `scroll`.
+fn sticky_top(top: f64, height: f64, section_end: f64, scroll: f64) -> f64 {
+    scroll.min(section_end - height).max(top)
+}
+
+fn max_offset(viewport: Size, content: Size) -> Vec2 {
+    Vec2::new(
+        (content.width - viewport.width).max(0.0),
+        (content.height - viewport.height).max(0.0),
+    )
+}
+
+/// How far to scroll along one axis to bring `start..end`, in viewport
+/// coordinates, into a viewport `len` long. A target longer than the viewport
+/// shows its start.
+fn pan_delta(start: f64, end: f64, len: f64) -> f64 {
+    if start < 0.0 {
+        start
+    } else if end > len {
+        (end - len).min(start)
+    } else {
+        0.0
+    }
+}
+
+/// The offset a navigation key scrolls to from `offset`, unclamped, or `None`
+/// for keys that don't scroll.
+fn key_target(key: &Key, offset: Vec2, viewport: Size, max: Vec2) -> Option<Vec2> {
+    let line = scaled(LINE_HEIGHT);
+    let Key::Named(key) = key else {
+        return None;
+    };
+    let target = match key {
+        NamedKey::ArrowUp => offset - Vec2::new(0.0, line),
+        NamedKey::ArrowDown => offset + Vec2::new(0.0, line),
+        NamedKey::ArrowLeft => offset - Vec2::new(line, 0.0),
+        NamedKey::ArrowRight => offset + Vec2::new(line, 0.0),
+        NamedKey::PageUp => offset - Vec2::new(0.0, viewport.height),
+        NamedKey::PageDown => offset + Vec2::new(0.0, viewport.height),
+        NamedKey::Home => Vec2::new(offset.x, 0.0),
+        NamedKey::End => Vec2::new(offset.x, max.y),
+        _ => return None,
+    };
+    Some(target)
+}
+
+/// Track and thumb rects of the scrollbar for `axis`, or `None` when the
+/// content fits along it (or horizontal scrolling is off).
+fn bar_rects(
+    axis: Axis,
+    viewport: Size,
+    content: Size,
+    offset: Vec2,
+    thickness: f64,
+    horizontal: bool,
+) -> Option<(Rect, Rect)> {
+    let max = max_offset(viewport, content);
+    let show_vertical = max.y > 0.0;
+    let show_horizontal = horizontal && max.x > 0.0;
+    if thickness <= 0.0 {
+        return None;
+    }
+    // Where both bars show, each stops short of the shared corner.
+    let (viewport_len, content_len, scroll, max_scroll, corner) = match axis {
+        Axis::Vertical if show_vertical => (
+            viewport.height,
+            content.height,
+            offset.y,
+            max.y,
+            if show_horizontal { thickness } else { 0.0 },
+        ),
+        Axis::Horizontal if show_horizontal => (
+            viewport.width,
+            content.width,
+            offset.x,
+            max.x,
+            if show_vertical { thickness } else { 0.0 },
+        ),
+        _ => return None,
+    };
+    let track_len = (viewport_len - corner).max(0.0);
+    let thumb_len = (track_len * viewport_len / content_len)
+        .max(scaled(MIN_THUMB_LENGTH))
+        .min(track_len);
+    let thumb_start = (track_len - thumb_len) * scroll / max_scroll;
+
+    let rects = match axis {
+        Axis::Vertical => {
+            let x = viewport.width - thickness;
+            (
+                Rect::new(x, 0.0, viewport.width, track_len),
+                Rect::new(x, thumb_start, viewport.width, thumb_start + thumb_len),
+            )
+        }
+        Axis::Horizontal => {
+            let y = viewport.height - thickness;
+            (
+                Rect::new(0.0, y, track_len, viewport.height),
+                Rect::new(thumb_start, y, thumb_start + thumb_len, viewport.height),
+            )
+        }
+    };
+    Some(rects)
+}
+
+fn along(axis: Axis, point: Point) -> f64 {
+    match axis {
+        Axis::Horizontal => point.x,
+        Axis::Vertical => point.y,
+    }
+}
+
+fn rect_start(axis: Axis, rect: Rect) -> f64 {
+    match axis {
+        Axis::Horizontal => rect.x0,
+        Axis::Vertical => rect.y0,
+    }
+}
+
+fn rect_len(axis: Axis, rect: Rect) -> f64 {
+    match axis {
+        Axis::Horizontal => rect.width(),
+        Axis::Vertical => rect.height(),
+    }
+}
+
+impl Widget for ScrollArea {
+    type Action = RefreshRequested;
+
+    fn on_pointer_event(
+        &mut self,
+        ctx: &mut EventCtx<'_>,
+        _props: &mut PropertiesMut<'_>,
+        event: &PointerEvent,
+    ) {
+        match event {
+            PointerEvent::Scroll(PointerScrollEvent { delta, .. }) => {
+                let delta = match delta {
+                    ScrollDelta::PixelDelta(pos) => Vec2::new(pos.x, pos.y),
+                    ScrollDelta::LineDelta(x, y) => {
+                        Vec2::new(f64::from(*x), f64::from(*y)) * scaled(LINE_HEIGHT)
+                    }
+                    ScrollDelta::PageDelta(x, y) => Vec2::new(
+                        f64::from(*x) * self.viewport.width,
+                        f64::from(*y) * self.viewport.height,
+                    ),
+                };
+                let delta = if self.horizontal {
+                    delta
+                } else {
+                    Vec2::new(0.0, delta.y)
+                };
+                // At the edge the event is left to bubble to an outer Portal.
+                if self.scroll_by(ctx, delta) {
+                    self.show(ctx);
+                    ctx.set_handled();
+                }
+            }
+            PointerEvent::Down(button_event) => {
+                let local = ctx.local_position(button_event.state.position);
+                for axis in [Axis::Vertical, Axis::Horizontal] {
+                    let Some((track, thumb)) = self.bar(axis) else {
+                        continue;
+                    };
+                    if !track.contains(local) {
+                        continue;
+                    }
+                    let pos = along(axis, local);
+                    let grab = if thumb.contains(local) {
+                        pos - rect_start(axis, thumb)
+                    } else {
+                        // Clicking the track centers the thumb on the pointer.
+                        rect_len(axis, thumb) / 2.0
+                    };
+                    self.drag = Some(Drag { axis, grab });
+                    ctx.capture_pointer();
+                    self.drag_to(ctx, pos);
+                    self.show(ctx);
+                    ctx.set_handled();
+                    return;
+                }
+                if button_event.pointer.pointer_type == PointerType::Touch {
+                    self.touch_y = Some(local.y);
+                }
+            }
+            PointerEvent::Move(update) => {
+                let local = ctx.local_position(update.current.position);
+                if let Some(drag) = self.drag {
+                    self.drag_to(ctx, along(drag.axis, local));
+                    self.show(ctx);
+                } else if let Some(last) = self.touch_y {
+                    self.touch_y = Some(local.y);
+                    if self.scroll_by(ctx, Vec2::new(0.0, local.y - last)) {
+                        self.show(ctx);
+                        ctx.set_handled();
+                    }
+                }
+            }
+            PointerEvent::Up(_) | PointerEvent::Cancel(_) => {
+                if self.touch_y.take().is_some() {
+                    // Let a short pull spring back right away.
+                    self.pull_idle_ns = PULL_SETTLE_NS;
+                    ctx.request_anim_frame();
+                }
+                if self.drag.take().is_some() {
+                    self.show(ctx);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Navigation keys that a focused child left unhandled scroll the area.
+    fn on_text_event(
+        &mut self,
+        ctx: &mut EventCtx<'_>,
+        _props: &mut PropertiesMut<'_>,
+        event: &TextEvent,
+    ) {
+        let TextEvent::Keyboard(key_event) = event else {
+            return;
+        };
+        if key_event.state != KeyState::Down || ctx.is_handled() {
+            return;
+        }
+        let max = self.max_offset();
+        if let Some(target) = key_target(&key_event.key, self.offset, self.viewport, max)
+            && self.jump_to(ctx, target)
+        {
+            ctx.set_handled();
+        }
+    }
+
+    fn on_access_event(
+        &mut self,
+        ctx: &mut EventCtx<'_>,
+        _props: &mut PropertiesMut<'_>,
+        event: &AccessEvent,
+    ) {
+        if ctx.target() != ctx.widget_id() {
+            return;
+        }
+        let page = self.viewport;
+        let target = match event.action {
+            Action::ScrollUp => self.offset - Vec2::new(0.0, page.height),
+            Action::ScrollDown => self.offset + Vec2::new(0.0, page.height),
+            Action::ScrollLeft => self.offset - Vec2::new(page.width, 0.0),
+            Action::ScrollRight => self.offset + Vec2::new(page.width, 0.0),
+            Action::SetScrollOffset => match &event.data {
+                Some(ActionData::SetScrollOffset(point)) => Vec2::new(point.x, point.y),
+                _ => return,
+            },
+            _ => return,
+        };
+        if self.jump_to(ctx, target) {
+            ctx.set_handled();
+        }
+    }
+
+    fn accepts_focus(&self) -> bool {
+        // Lets keyboard users tab to an area with nothing focusable in it.
+        true
+    }
+
+    fn register_children(&mut self, ctx: &mut RegisterCtx<'_>) {
+        for child in &mut self.children {
+            ctx.register_child(&mut child.pod);
+        }
+    }
+
+    fn on_anim_frame(
+        &mut self,
+        ctx: &mut UpdateCtx<'_>,
+        _props: &mut PropertiesMut<'_>,
+        interval: u64,
+    ) {
+        let mut again = self.step_pull(ctx, interval);
+        if self.auto_hide && self.drag.is_none() {
+            self.idle_ns = self.idle_ns.saturating_add(interval);
+            let fading = self.idle_ns.saturating_sub(HIDE_DELAY_NS);
+            self.visibility = (1.0 - fading as f64 / FADE_NS as f64).clamp(0.0, 1.0);
+            again |= self.visibility > 0.0;
+        }
+        if again {
+            ctx.request_anim_frame();
+        }
+        ctx.request_render();
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx<'_>, _props: &mut PropertiesMut<'_>, event: &Update) {
+        match event {
+            Update::HoveredChanged(true) | Update::ChildHoveredChanged(true) if self.auto_hide => {
+                self.idle_ns = 0;
+                self.visibility = 1.0;
+                ctx.request_anim_frame();
+                ctx.request_render();
+            }
+            // A descendant asked to be shown, e.g. on gaining focus. `target`
+            // is in this widget's coordinates, where the viewport starts at 0.
+            Update::RequestPanToChild(target) => {
+                let pan = Vec2::new(
+                    pan_delta(target.x0, target.x1, self.viewport.width),
+                    pan_delta(target.y0, target.y1, self.viewport.height),
+                );
+                if self.scroll_to(self.offset + pan) {
+                    ctx.request_layout();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn measure(
+        &mut self,
+        ctx: &mut MeasureCtx<'_>,
+        _props: &PropertiesRef<'_>,
+        axis: Axis,
+        len_req: LenReq,
+        cross_length: Option<f64>,
+    ) -> f64 {
+        let lengths = self.children.iter_mut().map(|child| {
+            if child.culled {
+                return match axis {
+                    Axis::Horizontal => 0.0,
+                    Axis::Vertical => child.height,
+                };
+            }
+            ctx.compute_length(
+                &mut child.pod,
+                len_req.into(),
+                LayoutSize::maybe(axis.cross(), cross_length),
+                axis,
+                cross_length,
+            )
+        });
+        match axis {
+            Axis::Horizontal => lengths.fold(0.0, f64::max),
+            Axis::Vertical => lengths.sum(),
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx<'_>, _props: &PropertiesRef<'_>, size: Size) {
+        let width = if self.horizontal {
+            self.children
+                .iter_mut()
+                .filter(|child| !child.culled)
+                .map(|child| {
+                    ctx.compute_length(
+                        &mut child.pod,
+                        LenReq::MaxContent.into(),
+                        size.into(),
+                        Axis::Horizontal,
+                        None,
+                    )
+                })
+                .fold(size.width, f64::max)
+        } else {
+            size.width
+        };
+
+        let mut top = 0.0;
+        for child in &mut self.children {
+            if !child.culled {
+                let height = ctx.compute_length(
+                    &mut child.pod,
+                    LenReq::MaxContent.into(),
+                    size.into(),
+                    Axis::Vertical,
+                    Some(width),
+                );
+                ctx.run_layout(&mut child.pod, Size::new(width, height));
+                child.height = height;
+            }
+            child.top = top;
+            top += child.height;
+        }
+
+        self.viewport = size;
+        self.content = Size::new(width, top.max(size.height));
+        self.scroll_to(self.offset);
+
+        for index in 0..self.children.len() {
+            if self.children[index].culled {
+                continue;
+            }
+            let y = self.child_top(index) - self.offset.y + self.pull;
+            let origin = Point::new(-self.offset.x, y);
+            ctx.place_child(&mut self.children[index].pod, origin);
+        }
+        ctx.set_clip_path(size.to_rect());
+        // Scrolling moves videos and rows in and out of view.
+        video_registry::request_sweep();
+        viewport::request_sweep();
+    }
+
+    /// The refresh spinner sits in the gap the pull opens above the content.
+    fn paint(&mut self, _ctx: &mut PaintCtx<'_>, _props: &PropertiesRef<'_>, scene: &mut Scene) {
+        if self.pull <= 0.0 {
+            return;
+        }
+        let threshold = scaled(REFRESH_THRESHOLD);
+        let radius = scaled(SPINNER_RADIUS);
+        let (start, sweep) = if self.refreshing {
+            (
+                self.spin * std::f64::consts::TAU,
+                1.5 * std::f64::consts::PI,
+            )
+        } else {
+            // The arc grows as the pull nears the threshold.
+            (
+                0.0,
+                1.5 * std::f64::consts::PI * (self.pull / threshold).min(1.0),
+            )
+        };
+        let arc = Arc::new(
+            Point::new(self.viewport.width / 2.0, self.pull.min(threshold) / 2.0),
+            Vec2::new(radius, radius),
+            start - std::f64::consts::FRAC_PI_2,
+            sweep,
+            0.0,
+        );
+        scene.stroke(
+            &Stroke::new(scaled(2.5)),
+            Affine::IDENTITY,
+            SPINNER_COLOR,
+            None,
+            &arc,
+        );
+    }
+
+    /// Scrollbars are drawn over the content.
+    fn post_paint(
+        &mut self,
+        _ctx: &mut PaintCtx<'_>,
+        _props: &PropertiesRef<'_>,
+        scene: &mut Scene,
+    ) {
+        if self.visibility <= 0.0 {
+            return;
+        }
+        let alpha = self.visibility as f32;
+        let radius = self.thickness / 2.0;
+        for axis in [Axis::Vertical, Axis::Horizontal] {
+            let Some((track, thumb)) = self.bar(axis) else {
+                continue;
+            };
+            scene.fill(
+                Fill::NonZero,
+                Affine::IDENTITY,
+                self.track_color.multiply_alpha(alpha),
+                None,
+                &track,
+            );
+            scene.fill(
+                Fill::NonZero,
+                Affine::IDENTITY,
+                self.thumb_color.multiply_alpha(alpha),
+                None,
+                &RoundedRect::from_rect(thumb, radius),
+            );
+        }
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::ScrollView
+    }
+
+    fn accessibility(
+        &mut self,
+        _ctx: &mut AccessCtx<'_>,
+        _props: &PropertiesRef<'_>,
+        node: &mut Node,
+    ) {
+        let max = self.max_offset();
+        node.set_scroll_y(self.offset.y);
+        node.set_scroll_y_min(0.0);
+        node.set_scroll_y_max(max.y);
+        node.set_scroll_x(self.offset.x);
+        node.set_scroll_x_min(0.0);
+        node.set_scroll_x_max(max.x);
+        node.add_action(Action::SetScrollOffset);
+        if self.offset.y > 0.0 {
+            node.add_action(Action::ScrollUp);
+        }
+        if self.offset.y < max.y {
+            node.add_action(Action::ScrollDown);
+        }
+        if self.offset.x > 0.0 {
+            node.add_action(Action::ScrollLeft);
+        }
+        if self.offset.x < max.x {
+            node.add_action(Action::ScrollRight);
+        }
+    }
+
+    fn children_ids(&self) -> ChildrenIds {
+        // Sticky children come last so they paint over the rows under them.
+        let (sticky, rows): (Vec<_>, Vec<_>) = self.children.iter().partition(|c| c.sticky);
+        rows.into_iter()
+            .chain(sticky)
+            .map(|child| child.pod.id())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VIEWPORT: Size = Size::new(100.0, 100.0);
+
+    #[test]
+    fn test_bars_only_when_content_overflows() {
+        let fits = Size::new(100.0, 100.0);
+        assert!(bar_rects(Axis::Vertical, VIEWPORT, fits, Vec2::ZERO, 8.0, true).is_none());
+
+        let tall = Size::new(100.0, 400.0);
+        let (track, thumb) =
+            bar_rects(Axis::Vertical, VIEWPORT, tall, Vec2::ZERO, 8.0, true).unwrap();
+        assert_eq!(track, Rect::new(92.0, 0.0, 100.0, 100.0));
+        assert_eq!(thumb, Rect::new(92.0, 0.0, 100.0, 25.0));
+        assert!(bar_rects(Axis::Horizontal, VIEWPORT, tall, Vec2::ZERO, 8.0, true).is_none());
+    }
+
+    #[test]
+    fn test_thumb_follows_offset() {
+        let tall = Size::new(100.0, 400.0);
+        let bottom = Vec2::new(0.0, 300.0);
+        let (_, thumb) = bar_rects(Axis::Vertical, VIEWPORT, tall, bottom, 8.0, true).unwrap();
+        assert_eq!(thumb.y1, 100.0);
+    }
+
+    #[test]
+    fn test_horizontal_scroll_can_be_disabled() {
+        let wide = Size::new(400.0, 400.0);
+        let (track, _) = bar_rects(Axis::Vertical, VIEWPORT, wide, Vec2::ZERO, 8.0, true).unwrap();
+        // Stops short of the corner shared with the horizontal bar.
+        assert_eq!(track.y1, 92.0);
+        assert!(bar_rects(Axis::Horizontal, VIEWPORT, wide, Vec2::ZERO, 8.0, false).is_none());
+        let (track, _) = bar_rects(Axis::Vertical, VIEWPORT, wide, Vec2::ZERO, 8.0, false).unwrap();
+        assert_eq!(track.y1, 100.0);
+    }
+
+    #[test]
+    fn test_rows_far_outside_the_viewport_are_culled() {
+        // Viewport from 1000 to 1100; culling starts a viewport beyond.
+        assert_eq!(row_visibility(1050.0, 20.0, 1000.0, 100.0), (true, false));
+        assert_eq!(row_visibility(1150.0, 20.0, 1000.0, 100.0), (false, false));
+        assert_eq!(row_visibility(1250.0, 20.0, 1000.0, 100.0), (false, true));
+        assert_eq!(row_visibility(500.0, 20.0, 1000.0, 100.0), (false, true));
+        // Not laid out yet.
+        assert_eq!(row_visibility(5000.0, 0.0, 1000.0, 100.0), (false, false));
+    }
+
+    #[test]
+    fn test_sticky_header_pins_within_its_section() {
+        // A 20px header at 100 whose section ends at 300.
+        assert_eq!(sticky_top(100.0, 20.0, 300.0, 0.0), 100.0);
+        assert_eq!(sticky_top(100.0, 20.0, 300.0, 150.0), 150.0);
+        // The next header pushes it out.
+        assert_eq!(sticky_top(100.0, 20.0, 300.0, 290.0), 280.0);
+    }
+
+    #[test]
+    fn test_pan_brings_target_into_view() {
+        // Already in view.
+        assert_eq!(pan_delta(10.0, 40.0, 100.0), 0.0);
+        // Above or below the viewport.
+        assert_eq!(pan_delta(-30.0, 0.0, 100.0), -30.0);
+        assert_eq!(pan_delta(90.0, 130.0, 100.0), 30.0);
+        // Taller than the viewport: its start lands at the top.
+        assert_eq!(pan_delta(50.0, 300.0, 100.0), 50.0);
+    }
+
+    #[test]
+    fn test_navigation_keys_scroll() {
+        let max = Vec2::new(0.0, 300.0);
+        let offset = Vec2::new(0.0, 100.0);
+        let target = |key: NamedKey| key_target(&Key::Named(key), offset, VIEWPORT, max);
+        assert_eq!(target(NamedKey::ArrowDown), Some(Vec2::new(0.0, 148.0)));
+        assert_eq!(target(NamedKey::PageUp), Some(Vec2::new(0.0, 0.0)));
+        assert_eq!(target(NamedKey::End), Some(max));
+        assert_eq!(target(NamedKey::Enter), None);
+        assert_eq!(
+            key_target(&Key::Character("j".into()), offset, VIEWPORT, max),
+            None
+        );
+    }
+
+    #[test]
+    fn test_pull_is_damped_and_capped() {
+        assert_eq!(pull_after(0.0, 40.0), 20.0);
+        assert_eq!(pull_after(20.0, 1000.0), 2.0 * REFRESH_THRESHOLD);
+        assert_eq!(pull_after(20.0, -100.0), 0.0);
+    }
+
+    #[test]
+    fn test_style_from_box_style() {
+        let style: BoxStyle = serde_json::from_value(serde_json::json!({
+            "scrollbarWidth": 4,
+            "scrollbarThumbColor": "#ff0000",
+            "scrollbarAutoHide": true,
+            "horizontalScroll": false,
+        }))
+        .unwrap();
+        let scrollbar = ScrollbarStyle::from_style(&style);
+        assert!(scrollbar.thumb_color.is_some());
+        assert!(scrollbar.track_color.is_none());
+        assert_eq!(scrollbar.auto_hide, Some(true));
+        assert_eq!(scrollbar.horizontal, Some(false));
+        assert!(ScrollbarStyle::from_style(&BoxStyle::default()).is_empty());
+    }
+}
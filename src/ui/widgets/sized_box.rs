@@ -1,12 +1,11 @@
 use masonry::app::RenderRoot;
 use masonry::core::{NewWidget, WidgetOptions};
-use masonry::layout::Length;
 use masonry::widgets::SizedBox;
 
 use crate::ipc::{BoxStyle, WidgetKind};
-use crate::ui::styles::build_box_properties;
+use crate::ui::styles::{build_box_properties, px};
 use crate::ui::widget_manager::{WidgetInfo, WidgetManager};
-use crate::ui::widgets::utils::add_to_parent;
+use crate::ui::widgets::utils::{WrapperOptions, add_to_parent};
 
 pub fn create(
     render_root: &mut RenderRoot,
@@ -15,16 +14,17 @@ pub fn create(
     parent_id: Option<String>,
     style: Option<BoxStyle>,
     child_index: usize,
+    wrappers: WrapperOptions,
 ) {
     let style_ref = style.as_ref();
 
     let mut sized = SizedBox::empty();
     if let Some(s) = style_ref {
         if let Some(w) = s.width {
-            sized = sized.width(Length::px(w));
+            sized = sized.width(px(w));
         }
         if let Some(h) = s.height {
-            sized = sized.height(Length::px(h));
+            sized = sized.height(px(h));
         }
     }
 
@@ -38,6 +38,7 @@ pub fn create(
         &parent_id,
         new_widget,
         style_ref.and_then(|s| s.flex),
+        wrappers,
     ) {
         widget_manager.register_widget(
             id,
@@ -0,0 +1,61 @@
+use masonry::app::RenderRoot;
+use masonry::core::{NewWidget, WidgetOptions};
+
+use crate::ipc::{BoxStyle, WidgetData, WidgetKind};
+use crate::ui::styles::{build_box_properties, color_value_to_peniko, scaled};
+use crate::ui::widget_manager::{WidgetInfo, WidgetManager};
+use crate::ui::widgets::skeleton_impl::SkeletonWidget;
+use crate::ui::widgets::utils::{WrapperOptions, add_to_parent};
+
+#[allow(clippy::too_many_arguments)]
+pub fn create(
+    render_root: &mut RenderRoot,
+    widget_manager: &mut WidgetManager,
+    id: String,
+    parent_id: Option<String>,
+    style: Option<BoxStyle>,
+    data: Option<WidgetData>,
+    child_index: usize,
+    wrappers: WrapperOptions,
+) {
+    let style_ref = style.as_ref();
+
+    let mut skeleton = SkeletonWidget::new();
+    if let Some(WidgetData::Skeleton {
+        circle,
+        color,
+        highlight_color,
+    }) = &data
+    {
+        skeleton = skeleton.with_circle(*circle).with_colors(
+            color.as_ref().map(color_value_to_peniko),
+            highlight_color.as_ref().map(color_value_to_peniko),
+        );
+    }
+    if let Some(radius) = style_ref.and_then(|s| s.corner_radius) {
+        skeleton = skeleton.with_corner_radius(scaled(radius));
+    }
+
+    let props = style_ref.map(build_box_properties).unwrap_or_default();
+    let new_widget = NewWidget::new_with(skeleton, None, WidgetOptions::default(), props);
+    let widget_id = new_widget.id();
+
+    if add_to_parent(
+        render_root,
+        widget_manager,
+        &parent_id,
+        new_widget,
+        style_ref.and_then(|s| s.flex),
+        wrappers,
+    ) {
+        widget_manager.register_widget(
+            id,
+            WidgetInfo {
+                widget_id,
+                kind: WidgetKind::Skeleton,
+                parent_id: parent_id.clone(),
+                child_index,
+            },
+        );
+    }
+}
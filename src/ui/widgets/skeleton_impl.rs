@@ -0,0 +1,217 @@
+use masonry::accesskit::{Node, Role};
+use masonry::core::{
+    AccessCtx, ChildrenIds, LayoutCtx, MeasureCtx, NoAction, PaintCtx, PropertiesMut,
+    PropertiesRef, RegisterCtx, Update, UpdateCtx, Widget, WidgetMut,
+};
+use masonry::kurbo::{Affine, Circle, Point, Rect, RoundedRect, Size};
+use masonry::peniko::{Color, Fill, Gradient};
+use masonry::vello::Scene;
+
+const DEFAULT_WIDTH: f64 = 200.0;
+const DEFAULT_HEIGHT: f64 = 16.0;
+const DEFAULT_RADIUS: f64 = 4.0;
+const DEFAULT_BASE_COLOR: Color = Color::from_rgba8(255, 255, 255, 20);
+const DEFAULT_HIGHLIGHT_COLOR: Color = Color::from_rgba8(255, 255, 255, 48);
+/// Width of the shimmer band as a fraction of the block width.
+const BAND_FRACTION: f64 = 0.4;
+/// Duration of one shimmer sweep, in nanoseconds.
+const SHIMMER_PERIOD_NS: f64 = 1_500_000_000.0;
+
+/// A placeholder block with a shimmer band sweeping left to right, for loading states.
+///
+/// The animation runs entirely on `on_anim_frame`, so JS never has to drive it over IPC.
+pub struct SkeletonWidget {
+    base_color: Color,
+    highlight_color: Color,
+    corner_radius: f64,
+    circle: bool,
+    /// Position of the sweep, in `[0, 1)`.
+    phase: f64,
+    last_size: Size,
+}
+
+impl SkeletonWidget {
+    pub fn new() -> Self {
+        Self {
+            base_color: DEFAULT_BASE_COLOR,
+            highlight_color: DEFAULT_HIGHLIGHT_COLOR,
+            corner_radius: DEFAULT_RADIUS,
+            circle: false,
+            phase: 0.0,
+            last_size: Size::ZERO,
+        }
+    }
+
+    pub fn with_colors(mut self, base: Option<Color>, highlight: Option<Color>) -> Self {
+        if let Some(base) = base {
+            self.base_color = base;
+        }
+        if let Some(highlight) = highlight {
+            self.highlight_color = highlight;
+        }
+        self
+    }
+
+    pub fn with_corner_radius(mut self, corner_radius: f64) -> Self {
+        self.corner_radius = corner_radius.max(0.0);
+        self
+    }
+
+    /// Render as a circle inscribed in the block, e.g. for avatar placeholders.
+    pub fn with_circle(mut self, circle: bool) -> Self {
+        self.circle = circle;
+        self
+    }
+
+    /// Horizontal extent `(start, end)` of the shimmer band for a block of `width`.
+    /// The band starts fully off the left edge and ends fully off the right edge.
+    fn band_extent(phase: f64, width: f64) -> (f64, f64) {
+        let band = width * BAND_FRACTION;
+        let start = -band + phase * (width + band);
+        (start, start + band)
+    }
+
+    pub fn set_corner_radius(this: &mut WidgetMut<'_, Self>, corner_radius: f64) {
+        this.widget.corner_radius = corner_radius.max(0.0);
+        this.ctx.request_render();
+    }
+}
+
+impl Default for SkeletonWidget {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Widget for SkeletonWidget {
+    type Action = NoAction;
+
+    fn accepts_pointer_interaction(&self) -> bool {
+        false
+    }
+
+    fn register_children(&mut self, _ctx: &mut RegisterCtx<'_>) {}
+
+    fn on_anim_frame(
+        &mut self,
+        ctx: &mut UpdateCtx<'_>,
+        _props: &mut PropertiesMut<'_>,
+        interval: u64,
+    ) {
+        self.phase = (self.phase + interval as f64 / SHIMMER_PERIOD_NS).fract();
+        ctx.request_paint_only();
+        ctx.request_anim_frame();
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx<'_>, _props: &mut PropertiesMut<'_>, event: &Update) {
+        if event == &Update::WidgetAdded {
+            ctx.request_anim_frame();
+        }
+    }
+
+    fn measure(
+        &mut self,
+        _ctx: &mut MeasureCtx<'_>,
+        _props: &PropertiesRef<'_>,
+        axis: masonry::kurbo::Axis,
+        len_req: masonry::layout::LenReq,
+        _cross_length: Option<f64>,
+    ) -> f64 {
+        match axis {
+            masonry::kurbo::Axis::Horizontal => match len_req {
+                masonry::layout::LenReq::MinContent => DEFAULT_HEIGHT,
+                masonry::layout::LenReq::MaxContent => DEFAULT_WIDTH,
+                masonry::layout::LenReq::FitContent(space) => space,
+            },
+            masonry::kurbo::Axis::Vertical => match len_req {
+                masonry::layout::LenReq::FitContent(space) => DEFAULT_HEIGHT.min(space),
+                _ => DEFAULT_HEIGHT,
+            },
+        }
+    }
+
+    fn layout(
+        &mut self,
+        _ctx: &mut LayoutCtx<'_>,
+        _props: &PropertiesRef<'_>,
+        size: masonry::kurbo::Size,
+    ) {
+        self.last_size = size;
+    }
+
+    fn paint(&mut self, _ctx: &mut PaintCtx<'_>, _props: &PropertiesRef<'_>, scene: &mut Scene) {
+        let size = self.last_size;
+        if size.width <= 0.0 || size.height <= 0.0 {
+            return;
+        }
+
+        let (start, end) = Self::band_extent(self.phase, size.width);
+        let transparent = self.highlight_color.with_alpha(0.0);
+        let shimmer = Gradient::new_linear(Point::new(start, 0.0), Point::new(end, 0.0))
+            .with_stops([transparent, self.highlight_color, transparent]);
+
+        if self.circle {
+            let radius = size.width.min(size.height) * 0.5;
+            let shape = Circle::new(Point::new(size.width * 0.5, size.height * 0.5), radius);
+            scene.fill(
+                Fill::NonZero,
+                Affine::IDENTITY,
+                self.base_color,
+                None,
+                &shape,
+            );
+            scene.fill(Fill::NonZero, Affine::IDENTITY, &shimmer, None, &shape);
+        } else {
+            let radius = self.corner_radius.min(size.height * 0.5);
+            let shape = RoundedRect::from_rect(Rect::from_origin_size(Point::ORIGIN, size), radius);
+            scene.fill(
+                Fill::NonZero,
+                Affine::IDENTITY,
+                self.base_color,
+                None,
+                &shape,
+            );
+            scene.fill(Fill::NonZero, Affine::IDENTITY, &shimmer, None, &shape);
+        }
+    }
+
+    fn accessibility_role(&self) -> Role {
+        Role::ProgressIndicator
+    }
+
+    fn accessibility(
+        &mut self,
+        _ctx: &mut AccessCtx<'_>,
+        _props: &PropertiesRef<'_>,
+        node: &mut Node,
+    ) {
+        node.set_label("Loading");
+    }
+
+    fn children_ids(&self) -> ChildrenIds {
+        ChildrenIds::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_band_starts_and_ends_off_screen() {
+        let (start, end) = SkeletonWidget::band_extent(0.0, 100.0);
+        assert_eq!(end, 0.0);
+        assert!(start < 0.0);
+
+        let (start, _) = SkeletonWidget::band_extent(1.0, 100.0);
+        assert_eq!(start, 100.0);
+    }
+
+    #[test]
+    fn test_band_width_is_constant() {
+        for phase in [0.1, 0.5, 0.9] {
+            let (start, end) = SkeletonWidget::band_extent(phase, 250.0);
+            assert!((end - start - 250.0 * BAND_FRACTION).abs() < 1e-9);
+        }
+    }
+}
@@ -5,7 +5,7 @@ use masonry::widgets::Spinner;
 use crate::ipc::{BoxStyle, WidgetKind};
 use crate::ui::styles::build_box_properties;
 use crate::ui::widget_manager::{WidgetInfo, WidgetManager};
-use crate::ui::widgets::utils::add_to_parent;
+use crate::ui::widgets::utils::{WrapperOptions, add_to_parent};
 
 pub fn create(
     render_root: &mut RenderRoot,
@@ -14,6 +14,7 @@ pub fn create(
     parent_id: Option<String>,
     style: Option<BoxStyle>,
     child_index: usize,
+    wrappers: WrapperOptions,
 ) {
     let style_ref = style.as_ref();
     let spinner = Spinner::new();
@@ -28,6 +29,7 @@ pub fn create(
         &parent_id,
         new_widget,
         style_ref.and_then(|s| s.flex),
+        wrappers,
     ) {
         widget_manager.register_widget(
             id,
@@ -0,0 +1,199 @@
+use std::sync::Mutex;
+
+use masonry::app::RenderRoot;
+use masonry::core::{NewWidget, PropertySet, StyleProperty, WidgetOptions, WidgetTag};
+use masonry::parley::style::FontWeight;
+use masonry::peniko::{Color, ImageData};
+use masonry::properties::types::{CrossAxisAlignment, MainAxisAlignment};
+use masonry::properties::{ContentColor, Dimensions, Gap, ObjectFit};
+use masonry::widgets::{Button, Flex, Image, Label, Spinner};
+
+use crate::ui::global_state;
+use crate::ui::styles::{font_size, px};
+use crate::ui::widgets::layer_impl::Layer;
+use crate::ui::widgets::progress_bar_impl::ProgressBarWidget;
+
+/// Tag of the layer holding the splash, topmost in the root stack.
+pub const SPLASH_TAG: WidgetTag<Layer> = WidgetTag::named("splash_layer");
+
+/// Tag of the button that starts the wait for a client over after it timed
+/// out.
+pub const RETRY_TAG: WidgetTag<Button> = WidgetTag::named("splash_retry");
+
+/// What the splash shows: an optional image, a message, and a progress bar
+/// (0.0 to 1.0) or, without progress, a spinner. `status` (the connection
+/// countdown) goes under the message, and `retry` puts a Retry button in
+/// place of the spinner.
+#[derive(Clone, Default)]
+pub struct Splash {
+    pub image: Option<ImageData>,
+    pub message: String,
+    pub progress: Option<f64>,
+    pub status: String,
+    pub retry: bool,
+}
+
+/// The splash on screen, kept so `SetSplash` can change one part of it
+/// without resending the rest. None once dismissed.
+static CURRENT: Mutex<Option<Splash>> = Mutex::new(None);
+
+/// Light text on dark backgrounds, dark text on light ones.
+fn text_color(background: Color) -> Color {
+    let [r, g, b, _] = background.components;
+    if 0.2126 * r + 0.7152 * g + 0.0722 * b > 0.5 {
+        Color::from_rgb8(0x20, 0x20, 0x24)
+    } else {
+        Color::from_rgb8(0xe6, 0xe6, 0xe6)
+    }
+}
+
+/// The image (at most 128px square) over the message and status over the
+/// progress bar, spinner or Retry button, centered on the window background.
+fn build(splash: &Splash) -> NewWidget<Flex> {
+    let color = text_color(global_state::window_background());
+    let mut content = Flex::column().cross_axis_alignment(CrossAxisAlignment::Center);
+    if let Some(image) = &splash.image {
+        content = content.with_fixed(NewWidget::new_with(
+            Image::new(image.clone()),
+            None,
+            WidgetOptions::default(),
+            PropertySet::new()
+                .with(ObjectFit::Contain)
+                .with(Dimensions::fixed(px(128.0), px(128.0))),
+        ));
+    }
+    if !splash.message.is_empty() {
+        let message = Label::new(splash.message.as_str())
+            .with_style(font_size(22.0))
+            .with_style(StyleProperty::FontWeight(FontWeight::SEMI_BOLD));
+        content = content.with_fixed(NewWidget::new_with(
+            message,
+            None,
+            WidgetOptions::default(),
+            PropertySet::new().with(ContentColor::new(color)),
+        ));
+    }
+    if !splash.status.is_empty() {
+        let status = Label::new(splash.status.as_str()).with_style(font_size(14.0));
+        content = content.with_fixed(NewWidget::new_with(
+            status,
+            None,
+            WidgetOptions::default(),
+            PropertySet::new().with(ContentColor::new(color)),
+        ));
+    }
+    content = match (splash.retry, splash.progress) {
+        (true, _) => content.with_fixed(NewWidget::new_with_tag(
+            Button::new(NewWidget::new(Label::new("Retry"))),
+            RETRY_TAG,
+        )),
+        (false, Some(progress)) => content.with_fixed(NewWidget::new_with(
+            ProgressBarWidget::new(Some(progress)),
+            None,
+            WidgetOptions::default(),
+            PropertySet::new().with(Dimensions::width(px(200.0))),
+        )),
+        (false, None) => content.with_fixed(NewWidget::new_with(
+            Spinner::new(),
+            None,
+            WidgetOptions::default(),
+            PropertySet::new()
+                .with(ContentColor::new(color))
+                .with(Dimensions::fixed(px(28.0), px(28.0))),
+        )),
+    };
+    NewWidget::new_with(
+        content,
+        None,
+        WidgetOptions::default(),
+        PropertySet::new().with(Gap::new(px(16.0))),
+    )
+}
+
+/// The splash layer for the root stack, showing `initial` until the app
+/// mounts its first widget (or empty, ready for `SetSplash`).
+pub fn new_layer(initial: Option<Splash>) -> NewWidget<Layer> {
+    let mut layer = Layer::new(MainAxisAlignment::Center, CrossAxisAlignment::Center);
+    if let Some(splash) = &initial {
+        layer = layer.with_child(build(splash));
+    }
+    *CURRENT.lock().unwrap_or_else(|e| e.into_inner()) = initial;
+    NewWidget::new_with_tag(layer, SPLASH_TAG)
+}
+
+/// Put `splash` in the layer, replacing what it shows if `showing`.
+fn redraw(render_root: &mut RenderRoot, showing: bool, splash: &Splash) {
+    let content = build(splash);
+    render_root.edit_widget_with_tag(SPLASH_TAG, |mut layer| {
+        if showing {
+            Layer::remove_child(&mut layer, 0);
+        }
+        Layer::add_child(&mut layer, content);
+    });
+}
+
+/// Show the splash, or update the one showing. Fields left as None keep
+/// their current value.
+pub fn show(
+    render_root: &mut RenderRoot,
+    image: Option<ImageData>,
+    message: Option<String>,
+    progress: Option<f64>,
+) {
+    let mut current = CURRENT.lock().unwrap_or_else(|e| e.into_inner());
+    let showing = current.is_some();
+    let splash = current.get_or_insert_with(Splash::default);
+    if image.is_some() {
+        splash.image = image;
+    }
+    if let Some(message) = message {
+        splash.message = message;
+    }
+    if progress.is_some() {
+        splash.progress = progress.map(|p| p.clamp(0.0, 1.0));
+    }
+    redraw(render_root, showing, splash);
+}
+
+/// Set the status line and Retry button, bringing up the splash if none is
+/// showing (the app may have started without one).
+pub fn set_status(render_root: &mut RenderRoot, status: String, retry: bool) {
+    let mut current = CURRENT.lock().unwrap_or_else(|e| e.into_inner());
+    if current.is_none() && status.is_empty() {
+        return;
+    }
+    let showing = current.is_some();
+    let splash = current.get_or_insert_with(Splash::default);
+    splash.status = status;
+    splash.retry = retry;
+    redraw(render_root, showing, splash);
+}
+
+/// Remove the splash, if it is still up.
+pub fn dismiss(render_root: &mut RenderRoot) {
+    let showing = CURRENT
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .take()
+        .is_some();
+    if showing {
+        render_root.edit_widget_with_tag(SPLASH_TAG, |mut layer| {
+            Layer::remove_child(&mut layer, 0);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_contrasts_with_background() {
+        assert_eq!(text_color(Color::BLACK), Color::from_rgb8(0xe6, 0xe6, 0xe6));
+        assert_eq!(text_color(Color::WHITE), Color::from_rgb8(0x20, 0x20, 0x24));
+        assert_eq!(
+            text_color(Color::from_rgb8(0x1e, 0x1e, 0x2e)),
+            Color::from_rgb8(0xe6, 0xe6, 0xe6)
+        );
+    }
+}
@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use masonry::app::RenderRoot;
 use masonry::core::{NewWidget, WidgetOptions};
 
@@ -5,7 +7,7 @@ use crate::ipc::{BoxStyle, WidgetData, WidgetKind};
 use crate::ui::styles::build_box_properties;
 use crate::ui::widget_manager::{WidgetInfo, WidgetManager};
 use crate::ui::widgets::svg_widget_impl::SvgWidget;
-use crate::ui::widgets::utils::add_to_parent;
+use crate::ui::widgets::utils::{WrapperOptions, add_to_parent};
 
 #[allow(clippy::too_many_arguments)]
 pub fn create(
@@ -17,21 +19,29 @@ pub fn create(
     style: Option<BoxStyle>,
     data: Option<WidgetData>,
     child_index: usize,
+    wrappers: WrapperOptions,
 ) {
     let style_ref = style.as_ref();
 
     // Extract SVG data from WidgetData, falling back to text
-    let svg_data = match &data {
-        Some(WidgetData::Svg { svg_data }) => svg_data.clone(),
-        _ => None,
-    }
-    .or_else(|| text.clone());
+    let (svg_data, resources_dir, style_sheet, alt) = match data {
+        Some(WidgetData::Svg {
+            svg_data,
+            resources_dir,
+            style_sheet,
+            alt,
+        }) => (svg_data, resources_dir, style_sheet, alt),
+        _ => (None, None, None, None),
+    };
+    let svg_data = svg_data.or_else(|| text.clone());
 
     if let Some(svg) = svg_data {
         let props = style_ref.map(build_box_properties).unwrap_or_default();
 
-        let new_widget =
-            NewWidget::new_with(SvgWidget::new(svg), None, WidgetOptions::default(), props);
+        let svg_widget = SvgWidget::new(svg)
+            .with_resources(resources_dir.map(PathBuf::from), style_sheet)
+            .with_alt_text(alt);
+        let new_widget = NewWidget::new_with(svg_widget, None, WidgetOptions::default(), props);
         let widget_id = new_widget.id();
 
         if add_to_parent(
@@ -40,6 +50,7 @@ pub fn create(
             &parent_id,
             new_widget,
             style_ref.and_then(|s| s.flex),
+            wrappers,
         ) {
             widget_manager.register_widget(
                 id,
@@ -0,0 +1,156 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+
+use masonry::kurbo::Size;
+use masonry::vello::Scene;
+use vello_svg::{append_tree, usvg};
+
+/// Maximum number of distinct (source, color, options) scenes kept alive.
+const CAPACITY: usize = 256;
+
+/// An SVG parsed and encoded at its intrinsic size.
+///
+/// Vello scenes are resolution independent, so widgets scale this with a
+/// transform at paint time instead of keying the cache on their layout size.
+pub struct CachedSvg {
+    pub scene: Scene,
+    pub size: Size,
+}
+
+/// Identifies everything that affects how an SVG string renders.
+fn cache_key(source: &str, color_hex: &str, options: &usvg::Options<'_>) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    source.hash(&mut hasher);
+    color_hex.hash(&mut hasher);
+    options.style_sheet.hash(&mut hasher);
+    options.resources_dir.hash(&mut hasher);
+    hasher.finish()
+}
+
+struct Entry {
+    svg: Rc<CachedSvg>,
+    last_used: u64,
+}
+
+/// Least-recently-used map of encoded SVG scenes.
+struct SvgCache {
+    entries: HashMap<u64, Entry>,
+    tick: u64,
+    capacity: usize,
+}
+
+impl SvgCache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            tick: 0,
+            capacity,
+        }
+    }
+
+    fn get(&mut self, key: u64) -> Option<Rc<CachedSvg>> {
+        self.tick += 1;
+        let entry = self.entries.get_mut(&key)?;
+        entry.last_used = self.tick;
+        Some(entry.svg.clone())
+    }
+
+    fn insert(&mut self, key: u64, svg: Rc<CachedSvg>) {
+        if self.entries.len() >= self.capacity
+            && !self.entries.contains_key(&key)
+            && let Some(oldest) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| *key)
+        {
+            self.entries.remove(&oldest);
+        }
+        self.tick += 1;
+        self.entries.insert(
+            key,
+            Entry {
+                svg,
+                last_used: self.tick,
+            },
+        );
+    }
+}
+
+thread_local! {
+    // Widgets only live on the UI thread, so the cache does too.
+    static SVG_CACHE: RefCell<SvgCache> = RefCell::new(SvgCache::new(CAPACITY));
+}
+
+/// Return the encoded scene for `source` with `currentColor` resolved to
+/// `color_hex`, parsing it only if no identical SVG has been seen recently.
+pub fn get_or_build(
+    source: &str,
+    color_hex: &str,
+    options: &usvg::Options<'_>,
+) -> Result<Rc<CachedSvg>, usvg::Error> {
+    let key = cache_key(source, color_hex, options);
+    if let Some(svg) = SVG_CACHE.with_borrow_mut(|cache| cache.get(key)) {
+        return Ok(svg);
+    }
+
+    // Resolve `currentColor` to the actual color (web standard behavior)
+    let resolved_source = source.replace("currentColor", color_hex);
+    let tree = usvg::Tree::from_str(&resolved_source, options)?;
+
+    let mut scene = Scene::new();
+    append_tree(&mut scene, &tree);
+    let tree_size = tree.size();
+    let svg = Rc::new(CachedSvg {
+        scene,
+        size: Size::new(
+            (tree_size.width() as f64).max(1.0),
+            (tree_size.height() as f64).max(1.0),
+        ),
+    });
+
+    SVG_CACHE.with_borrow_mut(|cache| cache.insert(key, svg.clone()));
+    Ok(svg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dummy(width: f64) -> Rc<CachedSvg> {
+        Rc::new(CachedSvg {
+            scene: Scene::new(),
+            size: Size::new(width, width),
+        })
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let mut cache = SvgCache::new(2);
+        cache.insert(1, dummy(1.0));
+        cache.insert(2, dummy(2.0));
+        // Touch 1 so that 2 becomes the eviction candidate.
+        assert!(cache.get(1).is_some());
+        cache.insert(3, dummy(3.0));
+
+        assert!(cache.get(1).is_some());
+        assert!(cache.get(2).is_none());
+        assert!(cache.get(3).is_some());
+    }
+
+    #[test]
+    fn test_key_depends_on_color_and_css() {
+        let options = usvg::Options::default();
+        let styled = usvg::Options {
+            style_sheet: Some("path{fill:red}".into()),
+            ..usvg::Options::default()
+        };
+        let base = cache_key("<svg/>", "#000000", &options);
+        assert_eq!(base, cache_key("<svg/>", "#000000", &options));
+        assert_ne!(base, cache_key("<svg/>", "#ffffff", &options));
+        assert_ne!(base, cache_key("<svg/>", "#000000", &styled));
+    }
+}
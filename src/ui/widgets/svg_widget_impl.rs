@@ -1,4 +1,6 @@
 use std::any::TypeId;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
 
 use masonry::accesskit::{Node, Role};
 use masonry::core::{
@@ -10,24 +12,134 @@ use masonry::peniko::color::{AlphaColor, Srgb};
 use masonry::properties::ContentColor;
 use masonry::vello::Scene;
 
-use vello_svg::{append_tree, usvg};
+use vello_svg::usvg;
+
+use crate::ui::widgets::svg_cache::{self, CachedSvg};
 
 pub struct SvgWidget {
     svg_source: String,
-    scene: Scene,
-    last_size: Size,
+    /// Base directory for relative `<image href>` and stylesheet references.
+    resources_dir: Option<PathBuf>,
+    /// CSS supplied by the caller, applied after any `<?xml-stylesheet?>` files.
+    extra_css: Option<String>,
+    /// Combined stylesheet handed to usvg; rebuilt whenever the source changes.
+    style_sheet: Option<String>,
+    /// Encoded scene shared with every other widget showing the same SVG.
+    svg: Option<Rc<CachedSvg>>,
+    /// Fits `svg` into the current layout size.
+    transform: Affine,
     last_color_hex: String,
     dirty: bool,
+    /// Screen reader label; without one the SVG is treated as decorative.
+    alt_text: Option<String>,
 }
 
 impl SvgWidget {
     pub fn new(svg_source: impl Into<String>) -> Self {
         Self {
             svg_source: svg_source.into(),
-            scene: Scene::new(),
-            last_size: Size::ZERO,
+            resources_dir: None,
+            extra_css: None,
+            style_sheet: None,
+            svg: None,
+            transform: Affine::IDENTITY,
             last_color_hex: String::new(),
             dirty: true,
+            alt_text: None,
+        }
+    }
+
+    pub fn with_alt_text(mut self, alt_text: Option<String>) -> Self {
+        self.alt_text = alt_text.filter(|alt| !alt.is_empty());
+        self
+    }
+
+    /// Resolve relative resources against `resources_dir` and apply `style_sheet`
+    /// on top of any external stylesheets the SVG links to.
+    pub fn with_resources(
+        mut self,
+        resources_dir: Option<PathBuf>,
+        style_sheet: Option<String>,
+    ) -> Self {
+        self.resources_dir = resources_dir;
+        self.extra_css = style_sheet;
+        self.style_sheet = self.load_style_sheet();
+        self
+    }
+
+    /// `href`s of `<?xml-stylesheet?>` processing instructions, in document order.
+    /// Stylesheets with a non-CSS `type` are skipped.
+    fn stylesheet_hrefs(source: &str) -> Vec<String> {
+        let attr = |pi: &str, name: &str| -> Option<String> {
+            let start = pi.find(&format!("{name}="))? + name.len() + 1;
+            let quote = pi[start..].chars().next()?;
+            if quote != '"' && quote != '\'' {
+                return None;
+            }
+            let rest = &pi[start + 1..];
+            rest.find(quote).map(|end| rest[..end].to_string())
+        };
+
+        let mut hrefs = Vec::new();
+        let mut rest = source;
+        while let Some(start) = rest.find("<?xml-stylesheet") {
+            let Some(len) = rest[start..].find("?>") else {
+                break;
+            };
+            let pi = &rest[start..start + len];
+            let is_css = attr(pi, "type").is_none_or(|ty| ty == "text/css");
+            if let (true, Some(href)) = (is_css, attr(pi, "href")) {
+                hrefs.push(href);
+            }
+            rest = &rest[start + len..];
+        }
+        hrefs
+    }
+
+    /// Read the SVG's linked stylesheets from disk and append the caller's CSS.
+    /// usvg only applies the stylesheet from its options, so external files are
+    /// inlined here.
+    fn load_style_sheet(&self) -> Option<String> {
+        let mut css = String::new();
+        for href in Self::stylesheet_hrefs(&self.svg_source) {
+            if href.starts_with("data:") || href.contains("://") {
+                eprintln!(
+                    "[UI] SVG stylesheet '{}' is not a local file; skipping",
+                    href
+                );
+                continue;
+            }
+            let path = match &self.resources_dir {
+                Some(dir) if Path::new(&href).is_relative() => dir.join(&href),
+                _ => PathBuf::from(&href),
+            };
+            match std::fs::read_to_string(&path) {
+                Ok(sheet) => {
+                    css.push_str(&sheet);
+                    css.push('\n');
+                }
+                Err(err) => {
+                    eprintln!(
+                        "[UI] Failed to load SVG stylesheet '{}': {}",
+                        path.display(),
+                        err
+                    );
+                }
+            }
+        }
+        if let Some(extra) = &self.extra_css {
+            css.push_str(extra);
+        }
+        (!css.is_empty()).then_some(css)
+    }
+
+    fn options(&self) -> usvg::Options<'static> {
+        // The default image href resolver already handles data URIs and files;
+        // it needs `resources_dir` for relative paths.
+        usvg::Options {
+            resources_dir: self.resources_dir.clone(),
+            style_sheet: self.style_sheet.clone(),
+            ..usvg::Options::default()
         }
     }
 
@@ -45,52 +157,33 @@ impl SvgWidget {
         }
     }
 
-    fn rebuild_scene(&mut self, size: Size, color_hex: &str) {
-        self.scene.reset();
-
-        // Resolve `currentColor` to the actual color (web standard behavior)
-        let resolved_source = self.svg_source.replace("currentColor", color_hex);
-
-        let options = usvg::Options::default();
-        let tree = match usvg::Tree::from_str(&resolved_source, &options) {
-            Ok(tree) => tree,
+    /// Fetch the scene for the current source and color from the shared cache,
+    /// parsing only if it isn't there yet.
+    fn refresh_svg(&mut self, color_hex: String) {
+        if !self.dirty && self.last_color_hex == color_hex {
+            return;
+        }
+        self.svg = match svg_cache::get_or_build(&self.svg_source, &color_hex, &self.options()) {
+            Ok(svg) => Some(svg),
             Err(err) => {
                 eprintln!("[UI] Failed to parse SVG: {}", err);
-                return;
+                None
             }
         };
-
-        let mut svg_scene = Scene::new();
-        append_tree(&mut svg_scene, &tree);
-
-        let tree_size = tree.size();
-        let source_width = tree_size.width().max(1.0) as f64;
-        let source_height = tree_size.height().max(1.0) as f64;
-
-        let target_width = size.width.max(1.0);
-        let target_height = size.height.max(1.0);
-        let scale = (target_width / source_width).min(target_height / source_height);
-
-        let offset_x = (target_width - source_width * scale) * 0.5;
-        let offset_y = (target_height - source_height * scale) * 0.5;
-        let transform = Affine::translate((offset_x, offset_y)) * Affine::scale(scale);
-
-        self.scene.append(&svg_scene, Some(transform));
+        self.last_color_hex = color_hex;
+        self.dirty = false;
     }
 
     fn intrinsic_size(&self) -> Size {
-        let options = usvg::Options::default();
-        match usvg::Tree::from_str(&self.svg_source, &options) {
-            Ok(tree) => {
-                let ts = tree.size();
-                Size::new((ts.width() as f64).max(1.0), (ts.height() as f64).max(1.0))
-            }
-            Err(_) => Size::new(24.0, 24.0),
-        }
+        self.svg
+            .as_ref()
+            .map(|svg| svg.size)
+            .unwrap_or(Size::new(24.0, 24.0))
     }
 
     pub fn set_svg_source(this: &mut WidgetMut<'_, Self>, svg_source: impl Into<String>) {
         this.widget.svg_source = svg_source.into();
+        this.widget.style_sheet = this.widget.load_style_sheet();
         this.widget.dirty = true;
         this.ctx.request_layout();
         this.ctx.request_render();
@@ -132,6 +225,7 @@ impl Widget for SvgWidget {
         len_req: masonry::layout::LenReq,
         _cross_length: Option<f64>,
     ) -> f64 {
+        self.refresh_svg(Self::color_to_hex(&_props.get::<ContentColor>().color));
         let intrinsic = self.intrinsic_size();
         let preferred = match axis {
             masonry::kurbo::Axis::Horizontal => intrinsic.width.max(1.0),
@@ -150,18 +244,22 @@ impl Widget for SvgWidget {
         _props: &PropertiesRef<'_>,
         size: masonry::kurbo::Size,
     ) {
-        let color_hex = Self::color_to_hex(&_props.get::<ContentColor>().color);
+        self.refresh_svg(Self::color_to_hex(&_props.get::<ContentColor>().color));
 
-        if self.dirty || self.last_size != size || self.last_color_hex != color_hex {
-            self.last_size = size;
-            self.last_color_hex = color_hex.clone();
-            self.rebuild_scene(size, &color_hex);
-            self.dirty = false;
-        }
+        let source = self.intrinsic_size();
+        let target_width = size.width.max(1.0);
+        let target_height = size.height.max(1.0);
+        let scale = (target_width / source.width).min(target_height / source.height);
+
+        let offset_x = (target_width - source.width * scale) * 0.5;
+        let offset_y = (target_height - source.height * scale) * 0.5;
+        self.transform = Affine::translate((offset_x, offset_y)) * Affine::scale(scale);
     }
 
     fn paint(&mut self, _ctx: &mut PaintCtx<'_>, _props: &PropertiesRef<'_>, scene: &mut Scene) {
-        scene.append(&self.scene, None);
+        if let Some(svg) = &self.svg {
+            scene.append(&svg.scene, Some(self.transform));
+        }
     }
 
     fn accessibility_role(&self) -> Role {
@@ -172,11 +270,39 @@ impl Widget for SvgWidget {
         &mut self,
         _ctx: &mut AccessCtx<'_>,
         _props: &PropertiesRef<'_>,
-        _node: &mut Node,
+        node: &mut Node,
     ) {
+        if let Some(alt_text) = &self.alt_text {
+            node.set_label(alt_text.clone());
+        }
     }
 
     fn children_ids(&self) -> ChildrenIds {
         ChildrenIds::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stylesheet_hrefs() {
+        let source = r#"<?xml version="1.0"?>
+<?xml-stylesheet type="text/css" href="theme.css"?>
+<?xml-stylesheet href='extra.css' ?>
+<?xml-stylesheet type="text/xsl" href="transform.xsl"?>
+<svg xmlns="http://www.w3.org/2000/svg"/>"#;
+        assert_eq!(
+            SvgWidget::stylesheet_hrefs(source),
+            vec!["theme.css".to_string(), "extra.css".to_string()]
+        );
+        assert!(SvgWidget::stylesheet_hrefs("<svg/>").is_empty());
+    }
+
+    #[test]
+    fn test_caller_css_applies_after_linked_sheets() {
+        let widget = SvgWidget::new("<svg/>").with_resources(None, Some("path{fill:red}".into()));
+        assert_eq!(widget.style_sheet.as_deref(), Some("path{fill:red}"));
+    }
+}
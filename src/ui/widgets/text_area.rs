@@ -5,8 +5,9 @@ use masonry::widgets::TextArea;
 use crate::ipc::{BoxStyle, WidgetKind};
 use crate::ui::styles::{build_box_properties, build_text_styles};
 use crate::ui::widget_manager::{WidgetInfo, WidgetManager};
-use crate::ui::widgets::utils::add_to_parent;
+use crate::ui::widgets::utils::{WrapperOptions, add_to_parent};
 
+#[allow(clippy::too_many_arguments)]
 pub fn create(
     render_root: &mut RenderRoot,
     widget_manager: &mut WidgetManager,
@@ -15,6 +16,7 @@ pub fn create(
     text: Option<String>,
     style: Option<BoxStyle>,
     child_index: usize,
+    wrappers: WrapperOptions,
 ) {
     let style_ref = style.as_ref();
     let initial_text = text.unwrap_or_default();
@@ -37,6 +39,7 @@ pub fn create(
         &parent_id,
         new_widget,
         style_ref.and_then(|s| s.flex),
+        wrappers,
     ) {
         widget_manager.register_widget(
             id,
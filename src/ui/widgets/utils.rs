@@ -1,21 +1,161 @@
 use masonry::app::RenderRoot;
 use masonry::core::NewWidget;
-use masonry::widgets::{ChildAlignment, Flex, SizedBox, ZStack};
+use masonry::widgets::{Flex, SizedBox};
 
-use crate::ipc::WidgetKind;
-use crate::ui::widget_manager::{ROOT_FLEX_TAG, WidgetManager};
+use crate::ipc::{BadgeSpec, WidgetKind};
+use crate::ui::widget_manager::{BadgeOverlay, LAYERS, ROOT_FLEX_TAG, WidgetManager, layer_tag};
 
+use super::background::{self, BackgroundSpec};
+use super::badge;
+use super::clip_box_impl::ClipBox;
 use super::hoverable::Hoverable;
+use super::layer_impl::Layer;
+use super::press_feedback_impl::{Feedback, PressFeedback};
+use super::reorderable_list_impl::ReorderableList;
+use super::scroll_area_impl::ScrollArea;
+
+/// Remove every top-level widget from the root flex and the layers above it,
+/// and forget all bookkeeping.
+pub fn clear_root(render_root: &mut RenderRoot, widget_manager: &mut WidgetManager) {
+    let root_children = widget_manager.current_child_count("__root__");
+    render_root.edit_widget_with_tag(ROOT_FLEX_TAG, |mut flex| {
+        for index in (0..root_children).rev() {
+            masonry::core::CollectionWidget::remove(&mut flex, index);
+        }
+    });
+    for layer in &LAYERS {
+        let children = widget_manager.current_child_count(layer.id);
+        render_root.edit_widget_with_tag(layer.tag, |mut layer| {
+            for index in (0..children).rev() {
+                Layer::remove_child(&mut layer, index);
+            }
+        });
+    }
+    widget_manager.clear();
+}
+
+/// Internal wrappers `add_to_parent` puts around a new widget. Built by
+/// `create_and_add_widget` from the create command's style and badge, and
+/// handed down through the widget's `create`.
+#[derive(Default)]
+pub struct WrapperOptions {
+    /// Hover / press overlays and corner radius, set by `button::create`.
+    pub feedback: Option<(Feedback, f64)>,
+    /// Background image of a container.
+    pub background: Option<BackgroundSpec>,
+    /// Corner radius of an `overflow: "hidden"` clip.
+    pub clip: Option<f64>,
+    pub badge: Option<BadgeSpec>,
+    /// Pin the widget as a sticky header if its parent is a Portal.
+    pub sticky: bool,
+}
 
 /// Helper: add a widget to the root flex or a named parent flex.
 /// If `flex_factor` is Some, the child is added with that flex grow factor.
+/// `wrappers` wrap the widget first: button feedback wraps it in an internal
+/// PressFeedback, a background image wraps it in an internal BackgroundBox,
+/// a clip request wraps the result in an internal ClipBox, and a badge wraps
+/// that in an internal ZStack carrying the badge overlay (so the badge itself
+/// is never clipped).
 /// Returns false if the parent was not found or is not a container.
 pub fn add_to_parent(
     render_root: &mut RenderRoot,
+    widget_manager: &mut WidgetManager,
+    parent_id: &Option<String>,
+    new_widget: NewWidget<impl masonry::core::Widget + ?Sized>,
+    flex_factor: Option<f64>,
+    wrappers: WrapperOptions,
+) -> bool {
+    let content_id = new_widget.id();
+    let mut widget = new_widget.erased();
+
+    let mut feedback_id = None;
+    if let Some((feedback, corner_radius)) = wrappers.feedback {
+        let wrapper =
+            NewWidget::new(PressFeedback::new(widget, feedback).with_corner_radius(corner_radius));
+        feedback_id = Some(wrapper.id());
+        widget = wrapper.erased();
+    }
+
+    let mut background_id = None;
+    if let Some(spec) = wrappers.background {
+        let wrapper = background::wrap(widget, spec);
+        background_id = Some(wrapper.id());
+        widget = wrapper.erased();
+    }
+
+    let mut clip_id = None;
+    if let Some(corner_radius) = wrappers.clip {
+        let wrapper = NewWidget::new(ClipBox::new(widget, corner_radius));
+        clip_id = Some(wrapper.id());
+        widget = wrapper.erased();
+    }
+
+    let mut overlay = None;
+    if let Some(spec) = wrappers.badge {
+        let (wrapper, badge_id) = badge::wrap(widget, &spec);
+        overlay = Some(BadgeOverlay { badge_id, spec });
+        widget = wrapper.erased();
+    }
+
+    let added = insert_into_parent(
+        render_root,
+        widget_manager,
+        parent_id,
+        widget,
+        flex_factor,
+        wrappers.sticky,
+    );
+    if added {
+        if let Some(feedback_id) = feedback_id {
+            widget_manager.feedbacks.insert(content_id, feedback_id);
+        }
+        if let Some(background_id) = background_id {
+            widget_manager.backgrounds.insert(content_id, background_id);
+        }
+        if let Some(clip_id) = clip_id {
+            widget_manager.clips.insert(content_id, clip_id);
+        }
+        if let Some(overlay) = overlay {
+            widget_manager.badges.insert(content_id, overlay);
+        }
+    }
+    added
+}
+
+/// Single-child containers (SizedBox, Hoverable) reject a second child rather than
+/// silently replacing the first, which would leave the replaced widget registered
+/// and desync `child_index` bookkeeping.
+pub fn check_single_child_slot(
     widget_manager: &WidgetManager,
     parent_id: &Option<String>,
-    new_widget: NewWidget<impl masonry::core::Widget>,
+) -> Result<(), String> {
+    let Some(parent_key) = parent_id.as_deref() else {
+        return Ok(());
+    };
+    let Some(parent_info) = widget_manager.widgets.get(parent_key) else {
+        return Ok(());
+    };
+    if matches!(
+        parent_info.kind,
+        WidgetKind::SizedBox | WidgetKind::Hoverable
+    ) && widget_manager.current_child_count(parent_key) > 0
+    {
+        return Err(format!(
+            "{:?} '{}' already has a child. It can only have one child — wrap multiple children in a <flex> or <row>.",
+            parent_info.kind, parent_key
+        ));
+    }
+    Ok(())
+}
+
+fn insert_into_parent(
+    render_root: &mut RenderRoot,
+    widget_manager: &WidgetManager,
+    parent_id: &Option<String>,
+    new_widget: NewWidget<dyn masonry::core::Widget>,
     flex_factor: Option<f64>,
+    sticky: bool,
 ) -> bool {
     let parent_key = parent_id.as_deref().unwrap_or("__root__");
 
@@ -28,34 +168,73 @@ pub fn add_to_parent(
             }
         });
         true
+    } else if let Some(tag) = layer_tag(parent_key) {
+        render_root.edit_widget_with_tag(tag, |mut layer| {
+            Layer::add_child(&mut layer, new_widget);
+        });
+        true
     } else if let Some(parent_info) = widget_manager.widgets.get(parent_key) {
+        if let Err(message) = check_single_child_slot(widget_manager, parent_id) {
+            eprintln!("[UI] {}", message);
+            return false;
+        }
         match &parent_info.kind {
             WidgetKind::Flex | WidgetKind::Container => {
                 let parent_wid = parent_info.widget_id;
+                // Mirrored (right-to-left) rows grow from the left, so the
+                // first child ends up rightmost.
+                let mirrored = widget_manager.mirrored_rows.contains(&parent_wid);
                 render_root.edit_widget(parent_wid, |mut parent_widget| {
                     let mut flex = parent_widget.downcast::<Flex>();
-                    if let Some(factor) = flex_factor {
-                        masonry::core::CollectionWidget::add(&mut flex, new_widget, factor);
-                    } else {
-                        Flex::add_fixed(&mut flex, new_widget);
+                    match (flex_factor, mirrored) {
+                        (Some(factor), false) => {
+                            masonry::core::CollectionWidget::add(&mut flex, new_widget, factor);
+                        }
+                        (Some(factor), true) => {
+                            masonry::core::CollectionWidget::insert(
+                                &mut flex, 0, new_widget, factor,
+                            );
+                        }
+                        (None, false) => Flex::add_fixed(&mut flex, new_widget),
+                        (None, true) => Flex::insert_fixed(&mut flex, 0, new_widget),
                     }
                 });
                 true
             }
             WidgetKind::Button => {
                 let parent_wid = parent_info.widget_id;
+                // Children go before an accelerator hint, which stays last.
+                let index = widget_manager.current_child_count(parent_key);
                 render_root.edit_widget(parent_wid, |mut parent_widget| {
                     let mut btn = parent_widget.downcast::<masonry::widgets::Button>();
                     let mut child = masonry::widgets::Button::child_mut(&mut btn);
                     let mut flex = child.downcast::<Flex>();
                     if let Some(factor) = flex_factor {
-                        masonry::core::CollectionWidget::add(&mut flex, new_widget, factor);
+                        masonry::core::CollectionWidget::insert(
+                            &mut flex, index, new_widget, factor,
+                        );
                     } else {
-                        Flex::add_fixed(&mut flex, new_widget);
+                        Flex::insert_fixed(&mut flex, index, new_widget);
                     }
                 });
                 true
             }
+            WidgetKind::Portal => {
+                let parent_wid = parent_info.widget_id;
+                render_root.edit_widget(parent_wid, |mut parent_widget| {
+                    let mut portal = parent_widget.downcast::<ScrollArea>();
+                    ScrollArea::add_child(&mut portal, new_widget, sticky);
+                });
+                true
+            }
+            WidgetKind::ReorderableList => {
+                let parent_wid = parent_info.widget_id;
+                render_root.edit_widget(parent_wid, |mut parent_widget| {
+                    let mut list = parent_widget.downcast::<ReorderableList>();
+                    ReorderableList::add_child(&mut list, new_widget);
+                });
+                true
+            }
             WidgetKind::SizedBox => {
                 let parent_wid = parent_info.widget_id;
                 render_root.edit_widget(parent_wid, |mut parent_widget| {
@@ -67,30 +246,13 @@ pub fn add_to_parent(
             WidgetKind::ZStack => {
                 let parent_wid = parent_info.widget_id;
                 render_root.edit_widget(parent_wid, |mut parent_widget| {
-                    let mut zs = parent_widget.downcast::<ZStack>();
-                    masonry::core::CollectionWidget::add(
-                        &mut zs,
-                        new_widget,
-                        ChildAlignment::ParentAligned,
-                    );
+                    let mut stack = parent_widget.downcast::<Layer>();
+                    Layer::add_child(&mut stack, new_widget);
                 });
                 true
             }
             WidgetKind::Hoverable => {
                 let parent_wid = parent_info.widget_id;
-                // Check if hoverable already has a child — count existing children
-                let child_count = widget_manager
-                    .widgets
-                    .values()
-                    .filter(|info| info.parent_id.as_deref() == Some(parent_key))
-                    .count();
-                if child_count > 0 {
-                    eprintln!(
-                        "[UI] Hoverable '{}' already has a child. Hoverable can only have one child — wrap multiple children in a <flex> or <row>.",
-                        parent_key
-                    );
-                    return false;
-                }
                 render_root.edit_widget(parent_wid, |mut parent_widget| {
                     let mut hoverable = parent_widget.downcast::<Hoverable>();
                     Hoverable::set_child(&mut hoverable, new_widget);
@@ -99,7 +261,7 @@ pub fn add_to_parent(
             }
             other => {
                 eprintln!(
-                    "[UI] Cannot add child to widget '{}' of kind {:?} — only Flex/Container/SizedBox/ZStack/Hoverable can have children",
+                    "[UI] Cannot add child to widget '{}' of kind {:?} — only Flex/Container/Portal/ReorderableList/SizedBox/ZStack/Hoverable can have children",
                     parent_key, other
                 );
                 false
@@ -110,3 +272,41 @@ pub fn add_to_parent(
         false
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::widget_manager::WidgetInfo;
+    use masonry::core::WidgetId;
+
+    #[test]
+    fn test_single_child_slot_rejects_second_child() {
+        let mut manager = WidgetManager::new();
+        let register = |manager: &mut WidgetManager, id: &str, kind, parent: Option<&str>| {
+            manager.register_widget(
+                id.to_string(),
+                WidgetInfo {
+                    widget_id: WidgetId::next(),
+                    kind,
+                    parent_id: parent.map(String::from),
+                    child_index: 0,
+                },
+            );
+        };
+        register(&mut manager, "box", WidgetKind::SizedBox, None);
+        register(&mut manager, "col", WidgetKind::Flex, None);
+
+        let sized_box = Some("box".to_string());
+        assert!(check_single_child_slot(&manager, &sized_box).is_ok());
+        register(&mut manager, "first", WidgetKind::Label, Some("box"));
+        assert!(check_single_child_slot(&manager, &sized_box).is_err());
+
+        // Multi-child containers and the root are never restricted.
+        register(&mut manager, "a", WidgetKind::Label, Some("col"));
+        assert!(check_single_child_slot(&manager, &Some("col".to_string())).is_ok());
+        assert!(check_single_child_slot(&manager, &None).is_ok());
+
+        manager.remove_widget_subtree("first");
+        assert!(check_single_child_slot(&manager, &sized_box).is_ok());
+    }
+}
@@ -4,9 +4,11 @@ use masonry::core::{NewWidget, WidgetOptions};
 use crate::ipc::{BoxStyle, WidgetData, WidgetKind};
 use crate::ui::styles::build_box_properties;
 use crate::ui::widget_manager::{WidgetInfo, WidgetManager};
-use crate::ui::widgets::utils::add_to_parent;
+use crate::ui::widgets::image::decode_image_bytes;
+use crate::ui::widgets::utils::{WrapperOptions, add_to_parent};
 use crate::ui::widgets::video_widget_impl::VideoWidget;
 
+#[allow(clippy::too_many_arguments)]
 pub fn create(
     render_root: &mut RenderRoot,
     widget_manager: &mut WidgetManager,
@@ -15,10 +17,11 @@ pub fn create(
     style: Option<BoxStyle>,
     data: Option<WidgetData>,
     child_index: usize,
+    wrappers: WrapperOptions,
 ) {
     // Extract src from WidgetData
-    let src = match &data {
-        Some(WidgetData::Video { src }) => src.as_str(),
+    let (src, poster) = match &data {
+        Some(WidgetData::Video { src, poster }) => (src.as_str(), poster.as_deref()),
         _ => {
             eprintln!("[UI] Video widget '{}' missing src in WidgetData", id);
             return;
@@ -28,7 +31,14 @@ pub fn create(
     let style_ref = style.as_ref();
     let props = style_ref.map(build_box_properties).unwrap_or_default();
 
-    let mut video_widget = VideoWidget::new(src);
+    let poster = poster.and_then(|bytes| {
+        let image = decode_image_bytes(bytes);
+        if image.is_none() {
+            eprintln!("[UI] Video widget '{}' poster is not a supported image", id);
+        }
+        image
+    });
+    let mut video_widget = VideoWidget::new(src).with_poster(poster);
     if let Some(s) = style_ref {
         video_widget = video_widget.with_width(s.width).with_height(s.height);
     }
@@ -42,6 +52,7 @@ pub fn create(
         &parent_id,
         new_widget,
         style_ref.and_then(|s| s.flex),
+        wrappers,
     ) {
         widget_manager.register_widget(
             id,
@@ -0,0 +1,245 @@
+// Zero-Copy Video Frames
+// With the `dmabuf` feature on Linux, a VA-API pipeline hands frames over as
+// DMA-BUFs instead of RGBA in system memory. Each one is imported as a Vulkan
+// image over the same memory and copied into the widget's texture on the GPU,
+// so no frame crosses the CPU. This needs wgpu on Vulkan with the DMA-BUF
+// import extensions enabled; anything else (or a failed import, after which
+// zero-copy stays off for the run) keeps the `write_texture` upload path.
+
+use std::os::fd::{FromRawFd, IntoRawFd, OwnedFd};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use ash::vk;
+use masonry::vello::wgpu;
+use wgpu::hal::api::Vulkan;
+
+/// DRM fourcc "AB24" (`DRM_FORMAT_ABGR8888`): bytes in R, G, B, A order.
+const DRM_FORMAT_RGBA: &str = "AB24";
+const DRM_FORMAT_MOD_LINEAR: u64 = 0;
+
+static FAILED: AtomicBool = AtomicBool::new(false);
+
+/// One frame's single RGBA plane.
+pub struct DmaBufFrame {
+    pub fd: OwnedFd,
+    pub width: u32,
+    pub height: u32,
+    pub offset: u64,
+    pub stride: u64,
+    pub modifier: u64,
+}
+
+/// Parse a `drm-format` caps field such as "AB24" or
+/// "AB24:0x0100000000000002" into its modifier (linear when absent). Only
+/// RGBA is accepted, the one format the widget's texture uses.
+pub fn parse_drm_format(field: &str) -> Option<u64> {
+    let (fourcc, modifier) = match field.split_once(':') {
+        Some((fourcc, modifier)) => {
+            let hex = modifier.strip_prefix("0x").unwrap_or(modifier);
+            (fourcc, u64::from_str_radix(hex, 16).ok()?)
+        }
+        None => (field, DRM_FORMAT_MOD_LINEAR),
+    };
+    (fourcc == DRM_FORMAT_RGBA).then_some(modifier)
+}
+
+/// Whether frames can be imported on this device (and no import failed yet).
+pub fn supported(device: &wgpu::Device) -> bool {
+    if FAILED.load(Ordering::Acquire) {
+        return false;
+    }
+    // SAFETY: only the extension list is read from the HAL device.
+    let Some(hal_device) = (unsafe { device.as_hal::<Vulkan>() }) else {
+        return false;
+    };
+    let enabled = hal_device.enabled_device_extensions();
+    [
+        ash::khr::external_memory_fd::NAME,
+        ash::ext::external_memory_dma_buf::NAME,
+        ash::ext::image_drm_format_modifier::NAME,
+    ]
+    .iter()
+    .all(|extension| enabled.contains(extension))
+}
+
+/// Stop using zero-copy for the rest of the run.
+pub fn mark_failed() {
+    FAILED.store(true, Ordering::Release);
+}
+
+pub fn failed() -> bool {
+    FAILED.load(Ordering::Acquire)
+}
+
+/// Copy `frame` into `target` (same size, RGBA) on the GPU. Returns once the
+/// copy has finished, since the decoder reuses the buffer after that.
+pub fn copy_into(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    frame: DmaBufFrame,
+    target: &wgpu::Texture,
+) -> Result<(), String> {
+    let size = wgpu::Extent3d {
+        width: frame.width,
+        height: frame.height,
+        depth_or_array_layers: 1,
+    };
+    let source = import(device, frame, size)?;
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("VideoWidget_DmaBufCopy"),
+    });
+    encoder.copy_texture_to_texture(source.as_image_copy(), target.as_image_copy(), size);
+    let index = queue.submit([encoder.finish()]);
+    device
+        .poll(wgpu::PollType::Wait {
+            submission_index: Some(index),
+            timeout: None,
+        })
+        .map(|_| ())
+        .map_err(|e| format!("waiting for the frame copy failed: {e}"))
+}
+
+/// Create a Vulkan image over the DMA-BUF's memory and wrap it as a wgpu
+/// texture that frees both when dropped.
+fn import(
+    device: &wgpu::Device,
+    frame: DmaBufFrame,
+    size: wgpu::Extent3d,
+) -> Result<wgpu::Texture, String> {
+    let hal_desc = wgpu::hal::TextureDescriptor {
+        label: Some("VideoWidget_DmaBuf"),
+        size,
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::Rgba8Unorm,
+        usage: wgpu::TextureUses::COPY_SRC,
+        memory_flags: wgpu::hal::MemoryFlags::empty(),
+        view_formats: Vec::new(),
+    };
+
+    // SAFETY: every Vulkan object created here is destroyed on the error
+    // paths or handed to the texture's drop callback, and the fd is owned by
+    // Vulkan once `allocate_memory` succeeds.
+    let hal_texture = unsafe {
+        let hal_device = device.as_hal::<Vulkan>().ok_or("not a Vulkan device")?;
+        let raw = hal_device.raw_device();
+        let instance = hal_device.shared_instance().raw_instance();
+
+        let plane = vk::SubresourceLayout {
+            offset: frame.offset,
+            size: 0,
+            row_pitch: frame.stride,
+            array_pitch: 0,
+            depth_pitch: 0,
+        };
+        let mut modifier_info = vk::ImageDrmFormatModifierExplicitCreateInfoEXT::default()
+            .drm_format_modifier(frame.modifier)
+            .plane_layouts(std::slice::from_ref(&plane));
+        let mut external_info = vk::ExternalMemoryImageCreateInfo::default()
+            .handle_types(vk::ExternalMemoryHandleTypeFlags::DMA_BUF_EXT);
+        let image_info = vk::ImageCreateInfo::default()
+            .image_type(vk::ImageType::TYPE_2D)
+            .format(vk::Format::R8G8B8A8_UNORM)
+            .extent(vk::Extent3D {
+                width: frame.width,
+                height: frame.height,
+                depth: 1,
+            })
+            .mip_levels(1)
+            .array_layers(1)
+            .samples(vk::SampleCountFlags::TYPE_1)
+            .tiling(vk::ImageTiling::DRM_FORMAT_MODIFIER_EXT)
+            .usage(vk::ImageUsageFlags::TRANSFER_SRC)
+            .sharing_mode(vk::SharingMode::EXCLUSIVE)
+            .initial_layout(vk::ImageLayout::UNDEFINED)
+            .push_next(&mut external_info)
+            .push_next(&mut modifier_info);
+        let image = raw
+            .create_image(&image_info, None)
+            .map_err(|e| format!("creating the import image failed: {e}"))?;
+
+        let fd = frame.fd.into_raw_fd();
+        let fd_device = ash::khr::external_memory_fd::Device::new(instance, raw);
+        let mut fd_properties = vk::MemoryFdPropertiesKHR::default();
+        let requirements = raw.get_image_memory_requirements(image);
+        let memory_types = match fd_device.get_memory_fd_properties(
+            vk::ExternalMemoryHandleTypeFlags::DMA_BUF_EXT,
+            fd,
+            &mut fd_properties,
+        ) {
+            Ok(()) => requirements.memory_type_bits & fd_properties.memory_type_bits,
+            Err(_) => 0,
+        };
+        if memory_types == 0 {
+            drop(OwnedFd::from_raw_fd(fd));
+            raw.destroy_image(image, None);
+            return Err("no memory type can import the DMA-BUF".to_string());
+        }
+
+        let mut import_info = vk::ImportMemoryFdInfoKHR::default()
+            .handle_type(vk::ExternalMemoryHandleTypeFlags::DMA_BUF_EXT)
+            .fd(fd);
+        let mut dedicated_info = vk::MemoryDedicatedAllocateInfo::default().image(image);
+        let allocate_info = vk::MemoryAllocateInfo::default()
+            .allocation_size(requirements.size)
+            .memory_type_index(memory_types.trailing_zeros())
+            .push_next(&mut import_info)
+            .push_next(&mut dedicated_info);
+        let memory = match raw.allocate_memory(&allocate_info, None) {
+            Ok(memory) => memory,
+            Err(e) => {
+                drop(OwnedFd::from_raw_fd(fd));
+                raw.destroy_image(image, None);
+                return Err(format!("importing the DMA-BUF failed: {e}"));
+            }
+        };
+        if let Err(e) = raw.bind_image_memory(image, memory, 0) {
+            raw.destroy_image(image, None);
+            raw.free_memory(memory, None);
+            return Err(format!("binding the DMA-BUF failed: {e}"));
+        }
+
+        let owner = raw.clone();
+        let drop_callback: wgpu::hal::DropCallback = Box::new(move || {
+            owner.destroy_image(image, None);
+            owner.free_memory(memory, None);
+        });
+        hal_device.texture_from_raw(image, &hal_desc, Some(drop_callback))
+    };
+
+    // SAFETY: the texture was created on this device with `hal_desc`, which
+    // matches the descriptor below.
+    Ok(unsafe {
+        device.create_texture_from_hal::<Vulkan>(
+            hal_texture,
+            &wgpu::TextureDescriptor {
+                label: Some("VideoWidget_DmaBuf"),
+                size,
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: wgpu::TextureFormat::Rgba8Unorm,
+                usage: wgpu::TextureUsages::COPY_SRC,
+                view_formats: &[],
+            },
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_drm_format() {
+        assert_eq!(parse_drm_format("AB24"), Some(DRM_FORMAT_MOD_LINEAR));
+        assert_eq!(
+            parse_drm_format("AB24:0x0100000000000002"),
+            Some(0x0100_0000_0000_0002)
+        );
+        // NV12 would need a conversion pass; only RGBA is imported.
+        assert_eq!(parse_drm_format("NV12:0x0"), None);
+        assert_eq!(parse_drm_format("AB24:zz"), None);
+    }
+}
@@ -0,0 +1,356 @@
+// FFmpeg Video Fallback
+// Used when the runtime is built without the `gstreamer` feature, or when
+// GStreamer can't open a source. Decoding runs in an `ffmpeg` executable
+// (VELLUM_FFMPEG, or the one on PATH) that streams raw RGBA frames over a
+// pipe at the video's own pace; `ffprobe` (VELLUM_FFPROBE) reads the frame
+// size first. Pausing stops the decoder and playing restarts it from the
+// same position. The fallback plays video only, without audio.
+
+use std::io::Read;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{Receiver, RecvTimeoutError, SyncSender, sync_channel};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+
+use crate::ipc::VideoDecoder;
+use crate::ui::widgets::video_widget_impl::{VideoFrame, VideoSource};
+
+/// How long `pull` waits for a frame before letting the worker loop again.
+const FRAME_WAIT: Duration = Duration::from_millis(16);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct StreamInfo {
+    width: u32,
+    height: u32,
+    fps: f64,
+}
+
+struct Frame {
+    width: u32,
+    height: u32,
+    data: Vec<u8>,
+}
+
+impl Frame {
+    fn as_video_frame(&self) -> VideoFrame<'_> {
+        VideoFrame::Rgba {
+            width: self.width,
+            height: self.height,
+            stride: self.width * 4,
+            data: &self.data,
+        }
+    }
+}
+
+/// One ffmpeg process decoding from `start`; dropping it stops the process.
+struct Run {
+    /// Frames, or why decoding stopped early
+    frames: Receiver<Result<Frame, String>>,
+    alive: Arc<AtomicBool>,
+    start: f64,
+    received: u64,
+}
+
+impl Drop for Run {
+    fn drop(&mut self) {
+        self.alive.store(false, Ordering::Release);
+    }
+}
+
+struct State {
+    run: Option<Run>,
+    playing: bool,
+    /// Where the next run starts when no run is active
+    position: f64,
+    /// Shown again when a paused widget asks for a refresh
+    last_frame: Option<Frame>,
+}
+
+pub struct FfmpegSource {
+    src: String,
+    info: Arc<OnceLock<Option<StreamInfo>>>,
+    state: Mutex<State>,
+}
+
+impl FfmpegSource {
+    pub fn open(src: &str) -> Self {
+        let src = src.strip_prefix("file://").unwrap_or(src).to_string();
+        Self {
+            src,
+            info: Arc::new(OnceLock::new()),
+            state: Mutex::new(State {
+                run: None,
+                playing: false,
+                position: 0.0,
+                last_frame: None,
+            }),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Seconds into the video of the next frame the active run will deliver.
+    fn position_of(&self, state: &State) -> f64 {
+        match (&state.run, self.info.get().copied().flatten()) {
+            (Some(run), Some(info)) if info.fps > 0.0 => run.start + run.received as f64 / info.fps,
+            (Some(run), _) => run.start,
+            (None, _) => state.position,
+        }
+    }
+
+    /// Start decoding at `start`, paced in real time, or just `frames`
+    /// frames as fast as possible (a paused preview).
+    fn start_run(&self, start: f64, frames: Option<u32>) -> Run {
+        let (tx, rx) = sync_channel(2);
+        let alive = Arc::new(AtomicBool::new(true));
+        let src = self.src.clone();
+        let info = self.info.clone();
+        let alive_for_thread = alive.clone();
+        let spawned = std::thread::Builder::new()
+            .name("video-ffmpeg".to_string())
+            .spawn(move || decode(&src, &info, start, frames, &tx, &alive_for_thread));
+        if let Err(e) = spawned {
+            eprintln!("[VideoWidget] Failed to spawn the ffmpeg reader: {e}");
+        }
+        Run {
+            frames: rx,
+            alive,
+            start,
+            received: 0,
+        }
+    }
+}
+
+fn ffmpeg() -> String {
+    std::env::var("VELLUM_FFMPEG").unwrap_or_else(|_| "ffmpeg".to_string())
+}
+
+fn ffprobe() -> String {
+    std::env::var("VELLUM_FFPROBE").unwrap_or_else(|_| "ffprobe".to_string())
+}
+
+/// Parse `ffprobe -of csv=p=0` output for `stream=width,height,r_frame_rate`,
+/// e.g. "1920,1080,30000/1001".
+fn parse_stream_info(output: &str) -> Option<StreamInfo> {
+    let mut fields = output.lines().next()?.trim().split(',');
+    let width = fields.next()?.parse().ok().filter(|w| *w > 0)?;
+    let height = fields.next()?.parse().ok().filter(|h| *h > 0)?;
+    // Usually a fraction, but some ffprobe builds print a plain "30".
+    let rate = fields.next()?;
+    let fps = match rate.split_once('/') {
+        Some((num, den)) => num.parse::<f64>().ok()? / den.parse::<f64>().ok()?,
+        None => rate.parse().ok()?,
+    };
+    Some(StreamInfo {
+        width,
+        height,
+        fps: if fps.is_finite() && fps > 0.0 {
+            fps
+        } else {
+            30.0
+        },
+    })
+}
+
+fn probe(src: &str) -> Option<StreamInfo> {
+    let output = Command::new(ffprobe())
+        .args(["-v", "error", "-select_streams", "v:0"])
+        .args(["-show_entries", "stream=width,height,r_frame_rate"])
+        .args(["-of", "csv=p=0", src])
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| eprintln!("[VideoWidget] Could not run ffprobe: {e}"))
+        .ok()?;
+    parse_stream_info(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Body of a run's reader thread.
+fn decode(
+    src: &str,
+    info: &OnceLock<Option<StreamInfo>>,
+    start: f64,
+    frames: Option<u32>,
+    tx: &SyncSender<Result<Frame, String>>,
+    alive: &AtomicBool,
+) {
+    let Some(info) = *info.get_or_init(|| probe(src)) else {
+        let _ = tx.send(Err(format!("no playable video stream in {src}")));
+        return;
+    };
+    let mut command = Command::new(ffmpeg());
+    command.args(["-v", "error", "-nostdin"]);
+    if frames.is_none() {
+        command.arg("-re");
+    }
+    command
+        .args(["-ss", &format!("{start:.3}"), "-i", src])
+        .args(["-an", "-f", "rawvideo", "-pix_fmt", "rgba"]);
+    if let Some(count) = frames {
+        command.args(["-frames:v", &count.to_string()]);
+    }
+    let mut child = match command
+        .arg("-")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(e) => {
+            let _ = tx.send(Err(format!("could not start ffmpeg: {e}")));
+            return;
+        }
+    };
+
+    if let Some(mut stdout) = child.stdout.take() {
+        let len = info.width as usize * info.height as usize * 4;
+        while alive.load(Ordering::Acquire) {
+            let mut data = vec![0_u8; len];
+            if stdout.read_exact(&mut data).is_err() {
+                break;
+            }
+            let frame = Frame {
+                width: info.width,
+                height: info.height,
+                data,
+            };
+            if tx.send(Ok(frame)).is_err() {
+                break;
+            }
+        }
+    }
+    // Stopped by us: whatever ffmpeg says now is not an error.
+    if !alive.load(Ordering::Acquire) {
+        let _ = child.kill();
+        let _ = child.wait();
+        return;
+    }
+    let mut stderr = String::new();
+    if let Some(mut pipe) = child.stderr.take() {
+        let _ = pipe.read_to_string(&mut stderr);
+    }
+    if let Ok(status) = child.wait()
+        && !status.success()
+    {
+        let detail = stderr
+            .lines()
+            .last()
+            .unwrap_or("no output")
+            .trim()
+            .to_string();
+        let _ = tx.send(Err(format!("ffmpeg failed ({status}): {detail}")));
+    }
+}
+
+impl VideoSource for FfmpegSource {
+    fn set_playing(&self, playing: bool) -> Result<(), String> {
+        let mut state = self.lock();
+        if state.playing == playing {
+            return Ok(());
+        }
+        let position = self.position_of(&state);
+        state.playing = playing;
+        state.position = position;
+        state.run = playing.then(|| self.start_run(position, None));
+        Ok(())
+    }
+
+    fn seek(&self, time_secs: f64) -> Result<(), String> {
+        let mut state = self.lock();
+        let start = time_secs.max(0.0);
+        state.position = start;
+        state.last_frame = None;
+        // A paused video decodes the one frame it now shows.
+        let frames = (!state.playing).then_some(1);
+        state.run = Some(self.start_run(start, frames));
+        Ok(())
+    }
+
+    fn position(&self) -> Option<f64> {
+        Some(self.position_of(&self.lock()))
+    }
+
+    fn stop(&self) {
+        let mut state = self.lock();
+        state.playing = false;
+        state.run = None;
+    }
+
+    /// The fallback has no audio.
+    fn set_volume(&self, _volume: f64, _muted: bool) {}
+
+    fn decoder(&self) -> Option<VideoDecoder> {
+        Some(VideoDecoder {
+            name: "ffmpeg".to_string(),
+            hardware: false,
+        })
+    }
+
+    fn pull(&self, playing: bool, upload: &mut dyn FnMut(VideoFrame<'_>)) -> Result<(), String> {
+        let mut state = self.lock();
+        let received = state.run.as_mut().map(|run| {
+            let result = run.frames.recv_timeout(FRAME_WAIT);
+            if matches!(result, Ok(Ok(_))) {
+                run.received += 1;
+            }
+            result
+        });
+        match received {
+            Some(Ok(Ok(frame))) => {
+                upload(frame.as_video_frame());
+                state.last_frame = Some(frame);
+                return Ok(());
+            }
+            Some(Ok(Err(message))) => {
+                state.run = None;
+                return Err(message);
+            }
+            Some(Err(RecvTimeoutError::Timeout)) => return Ok(()),
+            // The run ended (end of stream, or ffmpeg failed): hold the
+            // position so playing again resumes from there.
+            Some(Err(RecvTimeoutError::Disconnected)) => {
+                state.position = self.position_of(&state);
+                state.run = None;
+            }
+            None => {}
+        }
+        if !playing && let Some(frame) = &state.last_frame {
+            upload(frame.as_video_frame());
+            return Ok(());
+        }
+        drop(state);
+        std::thread::sleep(FRAME_WAIT);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_stream_info() {
+        assert_eq!(
+            parse_stream_info("1920,1080,30000/1001\n"),
+            Some(StreamInfo {
+                width: 1920,
+                height: 1080,
+                fps: 30000.0 / 1001.0,
+            })
+        );
+        assert_eq!(
+            parse_stream_info("640,360,25").map(|info| info.fps),
+            Some(25.0)
+        );
+        // A stream that reports no rate still plays, at 30fps.
+        assert_eq!(
+            parse_stream_info("640,360,0/0").map(|info| info.fps),
+            Some(30.0)
+        );
+        assert_eq!(parse_stream_info(""), None);
+        assert_eq!(parse_stream_info("0,360,25"), None);
+    }
+}
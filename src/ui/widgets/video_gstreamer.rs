@@ -0,0 +1,495 @@
+// GStreamer Video
+// `playbin` decodes into an RGBA appsink. When the registry has a platform's
+// hardware decoders (VA-API, NVDEC, D3D11, VideoToolbox) they are ranked
+// above the software ones, and the conversion to RGBA runs on the GPU where
+// the platform has a converter for it, so 4K playback doesn't spend its CPU
+// in `videoconvert`. A hardware pipeline that fails before its first frame
+// ranks the hardware decoders out for the rest of the run and is rebuilt in
+// software. `VELLUM_VIDEO_HW=0` starts in software.
+//
+// With the `dmabuf` feature, VA-API pipelines also ask for DMA-BUF output so
+// frames reach the texture without a CPU copy (see `video_dmabuf`).
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+use gstreamer as gst;
+use gstreamer::prelude::*;
+use gstreamer_app as gst_app;
+use gstreamer_video as gst_video;
+
+use crate::ipc::VideoDecoder;
+#[cfg(all(feature = "dmabuf", target_os = "linux"))]
+use crate::ui::widgets::video_dmabuf::{self, DmaBufFrame};
+use crate::ui::widgets::video_widget_impl::{VideoFrame, VideoSource};
+
+/// One platform's hardware decoders, and the elements that take their
+/// output to system memory (RGBA is forced right after).
+struct HardwareBackend {
+    name: &'static str,
+    decoders: &'static [&'static str],
+    convert: &'static str,
+}
+
+#[cfg(target_os = "linux")]
+const BACKENDS: &[HardwareBackend] = &[
+    HardwareBackend {
+        name: "va",
+        decoders: &[
+            "vah264dec",
+            "vah265dec",
+            "vavp9dec",
+            "vaav1dec",
+            "vampeg2dec",
+        ],
+        convert: "vapostproc",
+    },
+    HardwareBackend {
+        name: "vaapi",
+        decoders: &["vaapih264dec", "vaapih265dec", "vaapivp9dec", "vaapiav1dec"],
+        convert: "vaapipostproc",
+    },
+    HardwareBackend {
+        name: "nvcodec",
+        decoders: &["nvh264dec", "nvh265dec", "nvvp9dec", "nvav1dec"],
+        convert: "cudaupload ! cudaconvert ! cudadownload",
+    },
+];
+
+#[cfg(windows)]
+const BACKENDS: &[HardwareBackend] = &[HardwareBackend {
+    name: "d3d11",
+    decoders: &["d3d11h264dec", "d3d11h265dec", "d3d11vp9dec", "d3d11av1dec"],
+    convert: "d3d11convert ! d3d11download",
+}];
+
+#[cfg(target_os = "macos")]
+const BACKENDS: &[HardwareBackend] = &[HardwareBackend {
+    name: "videotoolbox",
+    decoders: &["vtdec_hw", "vtdec"],
+    // vtdec hands frames over in system memory already.
+    convert: "",
+}];
+
+#[cfg(not(any(target_os = "linux", windows, target_os = "macos")))]
+const BACKENDS: &[HardwareBackend] = &[];
+
+/// The backend whose decoders were ranked up, probed once per run.
+static BACKEND: OnceLock<Option<&'static HardwareBackend>> = OnceLock::new();
+/// Set once a hardware pipeline has failed; later sources use software.
+static HARDWARE_FAILED: AtomicBool = AtomicBool::new(false);
+
+/// Whether every element a description names is in the registry.
+fn has_elements(description: &str) -> bool {
+    description
+        .split('!')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .all(|name| gst::ElementFactory::find(name).is_some())
+}
+
+/// Rank the first backend with any decoders present above the software
+/// decoders. The registry only lists decoders for codecs the device
+/// supports, which makes the lookup the capability probe.
+fn hardware_backend() -> Option<&'static HardwareBackend> {
+    if HARDWARE_FAILED.load(Ordering::Acquire) {
+        return None;
+    }
+    *BACKEND.get_or_init(|| {
+        if std::env::var("VELLUM_VIDEO_HW").as_deref() == Ok("0") {
+            return None;
+        }
+        let registry = gst::Registry::get();
+        let backend = BACKENDS.iter().find(|backend| {
+            has_elements(backend.convert)
+                && backend
+                    .decoders
+                    .iter()
+                    .any(|name| registry.lookup_feature(name).is_some())
+        })?;
+        for name in backend.decoders {
+            if let Some(feature) = registry.lookup_feature(name) {
+                feature.set_rank(gst::Rank::PRIMARY + 1);
+            }
+        }
+        eprintln!(
+            "[VideoWidget] Preferring {} hardware decoders",
+            backend.name
+        );
+        Some(backend)
+    })
+}
+
+/// Keep the hardware decoders from being picked again.
+fn demote_hardware(backend: &HardwareBackend) {
+    HARDWARE_FAILED.store(true, Ordering::Release);
+    let registry = gst::Registry::get();
+    for name in backend.decoders {
+        if let Some(feature) = registry.lookup_feature(name) {
+            feature.set_rank(gst::Rank::NONE);
+        }
+    }
+}
+
+/// Whether a pipeline on `hardware` should ask for DMA-BUF frames.
+#[cfg(all(feature = "dmabuf", target_os = "linux"))]
+fn zero_copy_available(hardware: Option<&'static HardwareBackend>) -> bool {
+    hardware.is_some_and(|backend| backend.name == "va")
+        && crate::ui::global_state::get_wgpu_context()
+            .is_some_and(|wgpu_cx| video_dmabuf::supported(&wgpu_cx.device))
+}
+
+#[cfg(not(all(feature = "dmabuf", target_os = "linux")))]
+fn zero_copy_available(_hardware: Option<&'static HardwareBackend>) -> bool {
+    false
+}
+
+#[cfg(all(feature = "dmabuf", target_os = "linux"))]
+fn zero_copy_failed() -> bool {
+    video_dmabuf::failed()
+}
+
+#[cfg(not(all(feature = "dmabuf", target_os = "linux")))]
+fn zero_copy_failed() -> bool {
+    false
+}
+
+/// The frame's DMA-BUF, when the caps negotiated one in RGBA.
+#[cfg(all(feature = "dmabuf", target_os = "linux"))]
+fn dmabuf_frame(buffer: &gst::BufferRef, caps: &gst::CapsRef) -> Option<DmaBufFrame> {
+    use std::os::fd::BorrowedFd;
+
+    if !caps
+        .features(0)
+        .is_some_and(|features| features.contains("memory:DMABuf"))
+    {
+        return None;
+    }
+    let structure = caps.structure(0)?;
+    let width = u32::try_from(structure.get::<i32>("width").ok()?).ok()?;
+    let height = u32::try_from(structure.get::<i32>("height").ok()?).ok()?;
+    let modifier = video_dmabuf::parse_drm_format(structure.get::<&str>("drm-format").ok()?)?;
+    let memory = buffer
+        .peek_memory(0)
+        .downcast_memory_ref::<gstreamer_allocators::DmaBufMemory>()?;
+    let (offset, stride) = buffer
+        .meta::<gst_video::VideoMeta>()
+        .map(|meta| (meta.offset()[0] as u64, meta.stride()[0] as u64))
+        .unwrap_or((0, u64::from(width) * 4));
+    // SAFETY: the buffer keeps the fd open while we duplicate it.
+    let fd = unsafe { BorrowedFd::borrow_raw(memory.fd()) }
+        .try_clone_to_owned()
+        .ok()?;
+    Some(DmaBufFrame {
+        fd,
+        width,
+        height,
+        offset,
+        stride,
+        modifier,
+    })
+}
+
+struct Pipeline {
+    playbin: gst::Element,
+    appsink: gst_app::AppSink,
+    /// The hardware backend this pipeline was built for, if any
+    hardware: Option<&'static HardwareBackend>,
+    /// Whether the appsink accepts DMA-BUF frames
+    zero_copy: bool,
+    /// Found once the first frame has been decoded
+    decoder: Option<VideoDecoder>,
+}
+
+impl Pipeline {
+    fn build(
+        uri: &str,
+        hardware: Option<&'static HardwareBackend>,
+        zero_copy: bool,
+    ) -> Result<Self, String> {
+        let playbin = gst::ElementFactory::make("playbin")
+            .property("uri", uri)
+            .build()
+            .map_err(|e| format!("pipeline creation error: {e}"))?;
+
+        let convert = match hardware {
+            Some(backend) if !backend.convert.is_empty() => format!("{} ! ", backend.convert),
+            _ => String::new(),
+        };
+        // The hardware converter outputs RGBA itself when frames stay on the GPU.
+        let description = if zero_copy {
+            format!("{convert}appsink name=sink sync=true")
+        } else {
+            format!("{convert}videoconvert ! video/x-raw,format=RGBA ! appsink name=sink sync=true")
+        };
+        let video_sink = gst::parse::bin_from_description(&description, true)
+            .map_err(|e| format!("video sink creation error: {e}"))?;
+        playbin.set_property("video-sink", &video_sink);
+        if let Some(audio_sink) = crate::audio::output_sink() {
+            playbin.set_property("audio-sink", &audio_sink);
+        }
+
+        let appsink = video_sink
+            .dynamic_cast_ref::<gst::Bin>()
+            .and_then(|bin| bin.by_name("sink"))
+            .and_then(|sink| sink.dynamic_cast::<gst_app::AppSink>().ok())
+            .ok_or("video sink has no appsink")?;
+
+        let caps = if zero_copy {
+            "video/x-raw(memory:DMABuf),format=DMA_DRM,drm-format=AB24; video/x-raw,format=RGBA"
+                .parse::<gst::Caps>()
+                .map_err(|e| format!("zero-copy caps error: {e}"))?
+        } else {
+            gst_video::VideoCapsBuilder::new()
+                .format(gst_video::VideoFormat::Rgba)
+                .build()
+        };
+        appsink.set_caps(Some(&caps));
+        appsink.set_max_buffers(1);
+        appsink.set_drop(true);
+        appsink.set_property("enable-last-sample", false);
+
+        Ok(Self {
+            playbin,
+            appsink,
+            hardware,
+            zero_copy,
+            decoder: None,
+        })
+    }
+
+    fn set_volume(&self, volume: f64, muted: bool) {
+        self.playbin.set_property("volume", volume);
+        self.playbin.set_property("mute", muted);
+    }
+
+    /// The video decoder playbin plugged in.
+    fn find_decoder(&self) -> Option<VideoDecoder> {
+        let bin = self.playbin.dynamic_cast_ref::<gst::Bin>()?;
+        bin.iterate_recurse()
+            .into_iter()
+            .flatten()
+            .find_map(|element| {
+                let factory = element.factory()?;
+                let klass = factory.klass();
+                (klass.contains("Decoder") && klass.contains("Video")).then(|| VideoDecoder {
+                    name: factory.name().to_string(),
+                    hardware: klass.contains("Hardware")
+                        || self.hardware.is_some_and(|backend| {
+                            backend.decoders.contains(&factory.name().as_str())
+                        }),
+                })
+            })
+    }
+}
+
+/// Plays a file path or HTTP URL through a `playbin` whose video sink is an
+/// RGBA appsink (audio goes to the default output).
+pub struct GstSource {
+    uri: String,
+    pipeline: Mutex<Pipeline>,
+    /// Last requested state, restored if the pipeline is rebuilt
+    playing: AtomicBool,
+    /// Last volume and mute, restored if the pipeline is rebuilt
+    volume: Mutex<(f64, bool)>,
+}
+
+impl GstSource {
+    pub fn open(src: &str) -> Result<Self, String> {
+        // Safe to call multiple times
+        gst::init().map_err(|e| format!("failed to initialize GStreamer: {e}"))?;
+        let uri = normalize_uri(src);
+        let hardware = hardware_backend();
+        let pipeline = Pipeline::build(&uri, hardware, zero_copy_available(hardware))?;
+        Ok(Self {
+            uri,
+            pipeline: Mutex::new(pipeline),
+            playing: AtomicBool::new(false),
+            volume: Mutex::new((1.0, false)),
+        })
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Pipeline> {
+        self.pipeline.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Replace the pipeline, resuming at the old one's position and state.
+    fn rebuild(
+        &self,
+        pipeline: &mut Pipeline,
+        hardware: Option<&'static HardwareBackend>,
+        zero_copy: bool,
+    ) -> Result<(), String> {
+        let position = pipeline.playbin.query_position::<gst::ClockTime>();
+        let _ = pipeline.playbin.set_state(gst::State::Null);
+        *pipeline = Pipeline::build(&self.uri, hardware, zero_copy)?;
+        let (volume, muted) = *self.volume.lock().unwrap_or_else(|e| e.into_inner());
+        pipeline.set_volume(volume, muted);
+        pipeline
+            .playbin
+            .set_state(gst_state(self.playing.load(Ordering::Acquire)))
+            .map_err(|e| e.to_string())?;
+        if let Some(position) = position.filter(|position| !position.is_zero()) {
+            // Seeking needs the new pipeline prerolled.
+            let _ = pipeline.playbin.state(gst::ClockTime::from_seconds(1));
+            let _ = pipeline
+                .playbin
+                .seek_simple(gst::SeekFlags::FLUSH | gst::SeekFlags::KEY_UNIT, position);
+        }
+        Ok(())
+    }
+}
+
+/// Normalize a source string into a proper GStreamer URI.
+fn normalize_uri(src: &str) -> String {
+    if src.starts_with("http://") || src.starts_with("https://") || src.starts_with("file://") {
+        src.to_string()
+    } else {
+        let abs_path = std::path::Path::new(src)
+            .canonicalize()
+            .unwrap_or_else(|_| std::path::PathBuf::from(src));
+        let path_str = abs_path.to_string_lossy().replace('\\', "/");
+        let path_str = path_str.strip_prefix("//?/").unwrap_or(&path_str);
+        format!("file:///{}", path_str.trim_start_matches('/'))
+    }
+}
+
+fn gst_state(playing: bool) -> gst::State {
+    if playing {
+        gst::State::Playing
+    } else {
+        gst::State::Paused
+    }
+}
+
+impl VideoSource for GstSource {
+    fn set_playing(&self, playing: bool) -> Result<(), String> {
+        self.playing.store(playing, Ordering::Release);
+        self.lock()
+            .playbin
+            .set_state(gst_state(playing))
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+
+    fn seek(&self, time_secs: f64) -> Result<(), String> {
+        let time = gst::ClockTime::from_nseconds((time_secs * 1_000_000_000.0) as u64);
+        let pipeline = self.lock();
+        // A pipeline that was just started can't seek until it prerolls.
+        let _ = pipeline.playbin.state(gst::ClockTime::from_seconds(1));
+        pipeline
+            .playbin
+            .seek_simple(gst::SeekFlags::FLUSH | gst::SeekFlags::KEY_UNIT, time)
+            .map_err(|e| e.to_string())
+    }
+
+    fn position(&self) -> Option<f64> {
+        self.lock()
+            .playbin
+            .query_position::<gst::ClockTime>()
+            .map(|position| position.nseconds() as f64 / 1_000_000_000.0)
+    }
+
+    fn stop(&self) {
+        let _ = self.lock().playbin.set_state(gst::State::Null);
+    }
+
+    fn set_volume(&self, volume: f64, muted: bool) {
+        *self.volume.lock().unwrap_or_else(|e| e.into_inner()) = (volume, muted);
+        self.lock().set_volume(volume, muted);
+    }
+
+    fn decoder(&self) -> Option<VideoDecoder> {
+        self.lock().decoder.clone()
+    }
+
+    fn pull(&self, playing: bool, upload: &mut dyn FnMut(VideoFrame<'_>)) -> Result<(), String> {
+        let mut pipeline = self.lock();
+        // An import failed in the worker: go back to RGBA in system memory.
+        if pipeline.zero_copy && zero_copy_failed() {
+            let hardware = pipeline.hardware;
+            return self.rebuild(&mut pipeline, hardware, false);
+        }
+        if let Some(bus) = pipeline.playbin.bus()
+            && let Some(message) = bus.pop_filtered(&[gst::MessageType::Error])
+            && let gst::MessageView::Error(err) = message.view()
+        {
+            let error = err.error().to_string();
+            // Only a hardware element failing before the first frame is
+            // worth a software retry; a missing file fails either way.
+            let factory = err
+                .src()
+                .and_then(|src| src.downcast_ref::<gst::Element>())
+                .and_then(|element| element.factory());
+            if pipeline.zero_copy && pipeline.decoder.is_none() {
+                eprintln!("[VideoWidget] Zero-copy pipeline failed ({error}), retrying without it");
+                #[cfg(all(feature = "dmabuf", target_os = "linux"))]
+                video_dmabuf::mark_failed();
+                let hardware = pipeline.hardware;
+                return self.rebuild(&mut pipeline, hardware, false);
+            }
+            let Some(backend) = pipeline.hardware.filter(|backend| {
+                pipeline.decoder.is_none()
+                    && factory.as_ref().is_some_and(|factory| {
+                        let name = factory.name();
+                        backend.decoders.contains(&name.as_str())
+                            || backend.convert.contains(name.as_str())
+                    })
+            }) else {
+                return Err(error);
+            };
+            eprintln!(
+                "[VideoWidget] {} hardware decoding failed ({error}), retrying in software",
+                backend.name
+            );
+            demote_hardware(backend);
+            return self.rebuild(&mut pipeline, None, false);
+        }
+
+        let sample = if playing {
+            pipeline
+                .appsink
+                .try_pull_sample(gst::ClockTime::from_mseconds(16))
+        } else {
+            pipeline
+                .appsink
+                .try_pull_preroll(gst::ClockTime::from_mseconds(16))
+        };
+        let Some(sample) = sample else {
+            return Ok(());
+        };
+        let (Some(buffer), Some(caps)) = (sample.buffer(), sample.caps()) else {
+            return Ok(());
+        };
+        if pipeline.decoder.is_none() {
+            pipeline.decoder = pipeline.find_decoder();
+        }
+        #[cfg(all(feature = "dmabuf", target_os = "linux"))]
+        if let Some(frame) = dmabuf_frame(buffer, caps) {
+            upload(VideoFrame::DmaBuf(frame));
+            return Ok(());
+        }
+        let Ok(video_info) = gst_video::VideoInfo::from_caps(caps) else {
+            return Ok(());
+        };
+        let Ok(map) = buffer.map_readable() else {
+            return Ok(());
+        };
+
+        let width = video_info.width();
+        let height = video_info.height();
+        let min_row_bytes = width.saturating_mul(4);
+        let stride = buffer
+            .meta::<gst_video::VideoMeta>()
+            .map(|meta| meta.stride()[0] as u32)
+            .filter(|stride| *stride >= min_row_bytes)
+            .unwrap_or(min_row_bytes);
+        upload(VideoFrame::Rgba {
+            width,
+            height,
+            stride,
+            data: map.as_slice(),
+        });
+        Ok(())
+    }
+}
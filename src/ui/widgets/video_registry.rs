@@ -0,0 +1,188 @@
+// Video Registry
+// Keeps pages with many `<video>` widgets from exhausting the GPU. After a
+// layout that may have moved a video (a Portal scrolled, a video was added),
+// the driver sweeps the tree: a video is in view when its bounds meet the
+// window and every enclosing Portal's viewport. Only videos in view hold a
+// decoding pipeline and texture, and at most `VELLUM_MAX_VIDEOS` of them
+// (default 4), playing ones first, then in tree order. The rest are
+// suspended: their pipeline and texture are dropped, the poster shows, and
+// they pick up at the same position when they get a slot back.
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use masonry::app::RenderRoot;
+use masonry::core::{Widget, WidgetId, WidgetRef};
+use masonry::kurbo::Rect;
+use masonry_winit::app::MasonryUserEvent;
+
+use crate::ui::global_state::get_event_loop_proxy;
+use crate::ui::widget_manager::ROOT_STACK_TAG;
+use crate::ui::widgets::scroll_area_impl::ScrollArea;
+use crate::ui::widgets::video_widget_impl::VideoWidget;
+
+const DEFAULT_MAX_VIDEOS: usize = 4;
+/// Videos this close to a viewport count as in view, so a scroll back and
+/// forth across the edge doesn't reopen them every time.
+const VIEW_MARGIN: f64 = 200.0;
+
+/// Sent to the driver when videos may have moved in or out of view.
+#[derive(Clone, Copy, Debug)]
+pub struct SweepVideos;
+
+/// Video widgets in the tree; nothing to sweep for while zero.
+static VIDEO_COUNT: AtomicUsize = AtomicUsize::new(0);
+/// A sweep is already on its way to the driver.
+static SWEEP_PENDING: AtomicBool = AtomicBool::new(false);
+
+pub fn register() {
+    VIDEO_COUNT.fetch_add(1, Ordering::Relaxed);
+    request_sweep();
+}
+
+pub fn unregister() {
+    VIDEO_COUNT.fetch_sub(1, Ordering::Relaxed);
+    request_sweep();
+}
+
+/// Ask the driver for a sweep once the current passes are done.
+pub fn request_sweep() {
+    if VIDEO_COUNT.load(Ordering::Relaxed) == 0 || SWEEP_PENDING.swap(true, Ordering::AcqRel) {
+        return;
+    }
+    match get_event_loop_proxy() {
+        Some((proxy, win_id)) => {
+            let _ = proxy.send_event(MasonryUserEvent::AsyncAction(win_id, Box::new(SweepVideos)));
+        }
+        None => SWEEP_PENDING.store(false, Ordering::Release),
+    }
+}
+
+fn max_videos() -> usize {
+    std::env::var("VELLUM_MAX_VIDEOS")
+        .ok()
+        .and_then(|value| value.trim().parse().ok())
+        .unwrap_or(DEFAULT_MAX_VIDEOS)
+}
+
+/// A video found by the sweep.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Candidate {
+    id: WidgetId,
+    in_view: bool,
+    playing: bool,
+    active: bool,
+}
+
+/// Which videos (in tree order) should hold a pipeline: those in view,
+/// playing ones first, up to `limit`.
+fn allocate(candidates: &[Candidate], limit: usize) -> Vec<bool> {
+    let mut ranked: Vec<usize> = (0..candidates.len())
+        .filter(|&index| candidates[index].in_view)
+        .collect();
+    // Stable, so tree order breaks ties.
+    ranked.sort_by_key(|&index| !candidates[index].playing);
+    let mut active = vec![false; candidates.len()];
+    for index in ranked.into_iter().take(limit) {
+        active[index] = true;
+    }
+    active
+}
+
+fn window_rect(widget: &WidgetRef<'_, dyn Widget>) -> Rect {
+    let ctx = widget.ctx();
+    ctx.window_transform()
+        .transform_rect_bbox(ctx.size().to_rect())
+}
+
+fn collect(widget: WidgetRef<'_, dyn Widget>, view: Rect, found: &mut Vec<Candidate>) {
+    if widget.ctx().is_stashed() {
+        return;
+    }
+    let rect = window_rect(&widget);
+    if let Some(video) = widget.downcast::<VideoWidget>() {
+        let overlap = rect.intersect(view);
+        found.push(Candidate {
+            id: widget.id(),
+            // A failed video has nothing to decode.
+            in_view: overlap.width() > 0.0 && overlap.height() > 0.0 && !video.inner().failed(),
+            playing: video.inner().wants_playing(),
+            active: video.inner().is_active(),
+        });
+        return;
+    }
+    let view = if widget.downcast::<ScrollArea>().is_some() {
+        view.intersect(rect.inflate(VIEW_MARGIN, VIEW_MARGIN))
+    } else {
+        view
+    };
+    for child in widget.children() {
+        collect(child, view, found);
+    }
+}
+
+/// Suspend the videos that lost their slot and resume the ones that gained
+/// one.
+pub fn sweep(render_root: &mut RenderRoot) {
+    SWEEP_PENDING.store(false, Ordering::Release);
+    if VIDEO_COUNT.load(Ordering::Relaxed) == 0 {
+        return;
+    }
+    let Some(root) = render_root.get_widget_with_tag(ROOT_STACK_TAG) else {
+        return;
+    };
+    let root_id = root.id();
+    let mut candidates = Vec::new();
+    if let Some(root) = render_root.get_widget(root_id) {
+        let view = window_rect(&root).inflate(VIEW_MARGIN, VIEW_MARGIN);
+        collect(root, view, &mut candidates);
+    }
+    let wanted = allocate(&candidates, max_videos());
+
+    // Free pipelines before opening new ones.
+    for (candidate, _) in candidates
+        .iter()
+        .zip(&wanted)
+        .filter(|(candidate, wanted)| candidate.active && !**wanted)
+    {
+        render_root.edit_widget(candidate.id, |mut widget| {
+            VideoWidget::suspend(&mut widget.downcast::<VideoWidget>());
+        });
+    }
+    for (candidate, _) in candidates
+        .iter()
+        .zip(&wanted)
+        .filter(|(candidate, wanted)| !candidate.active && **wanted)
+    {
+        render_root.edit_widget(candidate.id, |mut widget| {
+            VideoWidget::resume(&mut widget.downcast::<VideoWidget>());
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(in_view: bool, playing: bool) -> Candidate {
+        Candidate {
+            id: WidgetId::next(),
+            in_view,
+            playing,
+            active: false,
+        }
+    }
+
+    #[test]
+    fn test_allocate_prefers_visible_playing_videos() {
+        let candidates = [
+            candidate(true, false),
+            candidate(false, true),
+            candidate(true, true),
+            candidate(true, false),
+        ];
+        assert_eq!(allocate(&candidates, 2), vec![true, false, true, false]);
+        // Offscreen videos never get a pipeline, however many slots are free.
+        assert_eq!(allocate(&candidates, 8), vec![true, false, true, true]);
+        assert_eq!(allocate(&candidates, 0), vec![false; 4]);
+    }
+}
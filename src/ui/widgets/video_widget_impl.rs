@@ -1,25 +1,31 @@
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
 
-use gstreamer as gst;
-use gstreamer::prelude::*;
-use gstreamer_app as gst_app;
-use gstreamer_video as gst_video;
-
 use masonry::accesskit::{Node, Role};
 use masonry::core::{
-    AccessCtx, ChildrenIds, ErasedAction, LayoutCtx, MeasureCtx, NoAction, PaintCtx, PropertiesMut,
-    PropertiesRef, RegisterCtx, Update, UpdateCtx, Widget, WidgetId, WidgetMut,
+    AccessCtx, ChildrenIds, ErasedAction, LayoutCtx, MeasureCtx, NewWidget, NoAction, PaintCtx,
+    PropertiesMut, PropertiesRef, PropertySet, RegisterCtx, Update, UpdateCtx, Widget, WidgetId,
+    WidgetMut, WidgetOptions, WidgetPod,
 };
-use masonry::kurbo::{Affine, Size};
-use masonry::peniko::{Blob, ImageAlphaType, ImageBrush, ImageData, ImageFormat};
+use masonry::kurbo::{Affine, Point, Rect, Size};
+use masonry::layout::SizeDef;
+use masonry::peniko::{Blob, Color, Fill, ImageAlphaType, ImageBrush, ImageData, ImageFormat};
+use masonry::properties::ContentColor;
 use masonry::vello::Scene;
 use masonry::vello::wgpu;
+use masonry::widgets::Label;
 use std::sync::mpsc::{Receiver, channel};
 use std::thread::JoinHandle;
 use std::time::Duration;
 
+use crate::ipc::VideoDecoder;
 use crate::ui::global_state::{get_event_loop_proxy, get_wgpu_context};
+#[cfg(all(feature = "dmabuf", target_os = "linux"))]
+use crate::ui::widgets::video_dmabuf::{self, DmaBufFrame};
+use crate::ui::widgets::video_ffmpeg::FfmpegSource;
+#[cfg(feature = "gstreamer")]
+use crate::ui::widgets::video_gstreamer::GstSource;
+use crate::ui::widgets::video_registry;
 use masonry_winit::app::MasonryUserEvent;
 
 // --- MARK: TYPES
@@ -30,17 +36,77 @@ pub enum VideoAction {
     SetOverride(ImageData, Arc<wgpu::Texture>),
     ClearOverride(ImageData),
     FrameReady(WidgetId),
+    /// The source failed for good; the widget shows the message.
+    Failed(WidgetId, String),
+}
+
+/// Behind the poster and the error message, and where no frame has arrived.
+const PLACEHOLDER_COLOR: Color = Color::from_rgb8(0x18, 0x18, 0x1b);
+
+/// A decoder the widget's worker thread pulls RGBA frames from: GStreamer
+/// when the `gstreamer` feature is on, otherwise an ffmpeg process.
+pub trait VideoSource: Send + Sync {
+    fn set_playing(&self, playing: bool) -> Result<(), String>;
+    fn seek(&self, time_secs: f64) -> Result<(), String>;
+    /// Seconds into the video, where a reopened source should resume.
+    fn position(&self) -> Option<f64>;
+    /// Stop decoding for good; called before the widget drops the source.
+    fn stop(&self);
+    /// `volume` is linear, 0.0 to 1.0.
+    fn set_volume(&self, volume: f64, muted: bool);
+    /// The decoder in use, once known (reported by `GetMetrics`).
+    fn decoder(&self) -> Option<VideoDecoder>;
+    /// Wait briefly for the next frame (the current one when paused) and hand
+    /// it to `upload`. An error ends playback.
+    fn pull(&self, playing: bool, upload: &mut dyn FnMut(VideoFrame<'_>)) -> Result<(), String>;
+}
+
+/// A decoded frame, as the source has it.
+pub enum VideoFrame<'a> {
+    /// RGBA rows in system memory, `stride` bytes apart
+    Rgba {
+        width: u32,
+        height: u32,
+        stride: u32,
+        data: &'a [u8],
+    },
+    /// RGBA in GPU memory, copied into the texture without touching the CPU
+    #[cfg(all(feature = "dmabuf", target_os = "linux"))]
+    DmaBuf(DmaBufFrame),
+}
+
+impl VideoFrame<'_> {
+    fn size(&self) -> (u32, u32) {
+        match self {
+            VideoFrame::Rgba { width, height, .. } => (*width, *height),
+            #[cfg(all(feature = "dmabuf", target_os = "linux"))]
+            VideoFrame::DmaBuf(frame) => (frame.width, frame.height),
+        }
+    }
+}
+
+/// Open `src` with GStreamer if it is compiled in and can play it, falling
+/// back to ffmpeg. `VELLUM_VIDEO_BACKEND=ffmpeg` skips GStreamer.
+fn open_source(src: &str) -> Arc<dyn VideoSource> {
+    #[cfg(feature = "gstreamer")]
+    if std::env::var("VELLUM_VIDEO_BACKEND").as_deref() != Ok("ffmpeg") {
+        match GstSource::open(src) {
+            Ok(source) => return Arc::new(source),
+            Err(e) => eprintln!("[VideoWidget] GStreamer unavailable ({e}), using ffmpeg"),
+        }
+    }
+    Arc::new(FfmpegSource::open(src))
 }
 
 static VIDEO_WIDGET_COUNTER: AtomicUsize = AtomicUsize::new(1);
 
 struct VideoPipelineRuntime {
-    pipeline: gst::Element,
+    source: Arc<dyn VideoSource>,
     worker_alive: Arc<AtomicBool>,
     worker: JoinHandle<()>,
 }
 
-fn create_unique_overlay_key(width: u32, height: u32) -> ImageData {
+pub fn create_unique_overlay_key(width: u32, height: u32) -> ImageData {
     let id = VIDEO_WIDGET_COUNTER.fetch_add(1, Ordering::Relaxed);
     let len = (width as usize) * (height as usize) * 4;
     let mut vec = vec![0_u8; len];
@@ -57,20 +123,36 @@ fn create_unique_overlay_key(width: u32, height: u32) -> ImageData {
     }
 }
 
-/// A widget that plays video from a file path or HTTP URL using GStreamer.
+/// A widget that plays video from a file path or HTTP URL.
+///
+/// The pipeline is opened and dropped by the video registry as the widget
+/// comes into and goes out of view (see `video_registry`).
 pub struct VideoWidget {
-    pipeline: Option<gst::Element>,
+    src: String,
+    /// None while suspended
+    source: Option<Arc<dyn VideoSource>>,
+    /// Where the next pipeline starts, kept while suspended
+    resume_at: Option<f64>,
+    /// Counted by the video registry since `WidgetAdded`
+    registered: bool,
+    volume: f64,
+    muted: bool,
 
     overlay_key: ImageData,
     current_image: ImageBrush,
 
+    /// Shown until the first frame, and behind the error message
+    poster: Option<ImageData>,
+    /// Why playback failed, once it has
+    error: Option<WidgetPod<Label>>,
+
     // We store dimensions so we can layout correctly before the first frame
-    // These are updated via a channel from the GStreamer thread since it parses the caps
+    // These are updated via a channel from the worker thread once frames arrive
     dim_receiver: Option<Receiver<(u32, u32, ImageData)>>,
     video_width: u32,
     video_height: u32,
 
-    // Provide the pipeline with our WidgetId so it can target redraws
+    // Provide the worker with our WidgetId so it can target redraws
     shared_widget_id: Arc<Mutex<Option<WidgetId>>>,
     frame_ready_pending: Arc<AtomicBool>,
     paused_refresh_requested: Arc<AtomicBool>,
@@ -81,106 +163,64 @@ pub struct VideoWidget {
     style_width: Option<f64>,
     style_height: Option<f64>,
     last_size: Size,
-    started: bool,
 }
 
 // --- MARK: BUILDERS
 impl VideoWidget {
-    /// Create a new `VideoWidget` with the given source.
+    /// Create a new `VideoWidget` with the given source. It starts playing
+    /// once the registry gives it a pipeline.
     pub fn new(src: &str) -> Self {
-        // Initialize GStreamer (safe to call multiple times)
-        if let Err(e) = gst::init() {
-            eprintln!("[VideoWidget] Failed to initialize GStreamer: {}", e);
-            return Self::empty();
-        }
-
         let overlay_key = create_unique_overlay_key(1, 1);
         let current_image = ImageBrush::from(overlay_key.clone());
 
-        let uri = Self::normalize_uri(src);
-
-        let (dim_tx, dim_rx) = channel();
-
-        let shared_widget_id = Arc::new(Mutex::new(None));
-        let frame_ready_pending = Arc::new(AtomicBool::new(false));
-        let paused_refresh_requested = Arc::new(AtomicBool::new(true));
-        let playback_active = Arc::new(AtomicBool::new(true));
-
-        let runtime = Self::build_pipeline(
-            &uri,
-            dim_tx,
-            shared_widget_id.clone(),
-            frame_ready_pending.clone(),
-            paused_refresh_requested.clone(),
-            playback_active.clone(),
-        );
-
-        let (pipeline, worker_alive, worker) = if let Some(runtime) = runtime {
-            (
-                Some(runtime.pipeline),
-                runtime.worker_alive,
-                Some(runtime.worker),
-            )
-        } else {
-            (None, Arc::new(AtomicBool::new(false)), None)
-        };
-
-        Self {
-            pipeline,
-            dim_receiver: Some(dim_rx),
-            overlay_key,
-            current_image,
-            video_width: 0,
-            video_height: 0,
-            shared_widget_id,
-            frame_ready_pending,
-            paused_refresh_requested,
-            playback_active,
-            worker_alive,
-            worker,
-            style_width: None,
-            style_height: None,
-            last_size: Size::ZERO,
-            started: false,
-        }
-    }
-
-    /// Create an empty (non-playing) video widget used as fallback.
-    fn empty() -> Self {
-        let overlay_key = create_unique_overlay_key(1, 1);
-        let current_image = ImageBrush::from(overlay_key.clone());
         Self {
-            pipeline: None,
+            src: src.to_string(),
+            source: None,
+            resume_at: None,
+            registered: false,
+            volume: 1.0,
+            muted: false,
             dim_receiver: None,
             overlay_key,
             current_image,
+            poster: None,
+            error: None,
             video_width: 0,
             video_height: 0,
             shared_widget_id: Arc::new(Mutex::new(None)),
             frame_ready_pending: Arc::new(AtomicBool::new(false)),
             paused_refresh_requested: Arc::new(AtomicBool::new(true)),
-            playback_active: Arc::new(AtomicBool::new(false)),
+            playback_active: Arc::new(AtomicBool::new(true)),
             worker_alive: Arc::new(AtomicBool::new(false)),
             worker: None,
             style_width: None,
             style_height: None,
             last_size: Size::ZERO,
-            started: false,
         }
     }
 
-    /// Normalize a source string into a proper GStreamer URI.
-    fn normalize_uri(src: &str) -> String {
-        if src.starts_with("http://") || src.starts_with("https://") || src.starts_with("file://") {
-            src.to_string()
-        } else {
-            let abs_path = std::path::Path::new(src)
-                .canonicalize()
-                .unwrap_or_else(|_| std::path::PathBuf::from(src));
-            let path_str = abs_path.to_string_lossy().replace('\\', "/");
-            let path_str = path_str.strip_prefix("//?/").unwrap_or(&path_str);
-            format!("file:///{}", path_str.trim_start_matches('/'))
-        }
+    /// The decoder playing the current source, once the first frame is in.
+    pub fn decoder(&self) -> Option<VideoDecoder> {
+        self.source.as_ref().and_then(|source| source.decoder())
+    }
+
+    /// Whether the widget holds a pipeline (it isn't suspended).
+    pub fn is_active(&self) -> bool {
+        self.source.is_some()
+    }
+
+    /// Whether JS wants the video playing, suspended or not.
+    pub fn wants_playing(&self) -> bool {
+        self.playback_active.load(Ordering::Acquire)
+    }
+
+    pub fn failed(&self) -> bool {
+        self.error.is_some()
+    }
+
+    pub fn with_poster(mut self, poster: Option<ImageData>) -> Self {
+        self.poster = poster;
+        self
     }
 
     pub fn with_width(mut self, w: Option<f64>) -> Self {
@@ -193,58 +233,20 @@ impl VideoWidget {
         self
     }
 
-    /// Build the GStreamer pipeline
-    fn build_pipeline(
-        uri: &str,
+    /// Start the worker thread that uploads the source's frames to a texture.
+    fn spawn_worker(
+        source: Arc<dyn VideoSource>,
         dim_tx: std::sync::mpsc::Sender<(u32, u32, ImageData)>,
         shared_id: Arc<Mutex<Option<WidgetId>>>,
         frame_ready_pending: Arc<AtomicBool>,
         paused_refresh_requested: Arc<AtomicBool>,
         playback_active: Arc<AtomicBool>,
-    ) -> Option<VideoPipelineRuntime> {
-        let pipeline = gst::ElementFactory::make("playbin")
-            .property("uri", uri)
-            .build();
-
-        let pipeline = match pipeline {
-            Ok(p) => p,
-            Err(e) => {
-                eprintln!("[VideoWidget] Pipeline creation error: {}", e);
-                return None;
-            }
-        };
-
-        let video_sink = gst::parse::bin_from_description(
-            "videoconvert ! video/x-raw,format=RGBA ! appsink name=sink sync=true",
-            true,
-        );
-
-        let video_sink = match video_sink {
-            Ok(s) => s,
-            Err(e) => {
-                eprintln!("[VideoWidget] Video sink creation error: {}", e);
-                return None;
-            }
-        };
-
-        pipeline.set_property("video-sink", &video_sink);
-
-        let sink = video_sink.dynamic_cast_ref::<gst::Bin>()?.by_name("sink")?;
-        let appsink = sink.dynamic_cast::<gst_app::AppSink>().ok()?;
-
-        let caps = gst_video::VideoCapsBuilder::new()
-            .format(gst_video::VideoFormat::Rgba)
-            .build();
-        appsink.set_caps(Some(&caps));
-
-        appsink.set_max_buffers(1);
-        appsink.set_drop(true);
-        appsink.set_property("enable-last-sample", false);
-
+        start_at: Option<f64>,
+    ) -> VideoPipelineRuntime {
         let worker_alive = Arc::new(AtomicBool::new(true));
         let worker_alive_for_thread = worker_alive.clone();
 
-        let appsink_for_thread = appsink.clone();
+        let source_for_thread = source.clone();
         let shared_id_for_thread = shared_id.clone();
         let frame_ready_pending_for_thread = frame_ready_pending.clone();
         let paused_refresh_requested_for_thread = paused_refresh_requested.clone();
@@ -258,6 +260,13 @@ impl VideoWidget {
             let mut cached_proxy_context = get_event_loop_proxy();
             let mut paused_frame_uploaded = false;
 
+            // Off the UI thread, since GStreamer waits for the pipeline to preroll.
+            if let Some(time_secs) = start_at
+                && let Err(e) = source_for_thread.seek(time_secs)
+            {
+                eprintln!("[VideoWidget] Resuming at {time_secs}s failed: {e}");
+            }
+
             while worker_alive_for_thread.load(Ordering::Acquire) {
                 let is_playing = playback_active_for_thread.load(Ordering::Acquire);
 
@@ -274,118 +283,105 @@ impl VideoWidget {
                     continue;
                 }
 
-                let sample = if is_playing {
-                    appsink_for_thread.try_pull_sample(gst::ClockTime::from_mseconds(16))
-                } else {
-                    appsink_for_thread.try_pull_preroll(gst::ClockTime::from_mseconds(16))
-                };
+                let pulled = source_for_thread.pull(is_playing, &mut |frame| {
+                    let (width, height) = frame.size();
+                    // If we don't have a texture yet, or it's the wrong size, create a new one.
+                    if wgpu_texture.is_none()
+                        || wgpu_texture
+                            .as_ref()
+                            .is_some_and(|tex| tex.width() != width)
+                        || wgpu_texture
+                            .as_ref()
+                            .is_some_and(|tex| tex.height() != height)
+                    {
+                        if cached_wgpu_context.is_none() {
+                            cached_wgpu_context = get_wgpu_context();
+                        }
+                        if cached_proxy_context.is_none() {
+                            cached_proxy_context = get_event_loop_proxy();
+                        }
 
-                let Some(sample) = sample else {
-                    continue;
-                };
+                        if let (Some(wgpu_cx), Some((proxy, win_id))) =
+                            (cached_wgpu_context.as_ref(), cached_proxy_context.as_ref())
+                        {
+                            let texture_desc = wgpu::TextureDescriptor {
+                                size: wgpu::Extent3d {
+                                    width,
+                                    height,
+                                    depth_or_array_layers: 1,
+                                },
+                                mip_level_count: 1,
+                                sample_count: 1,
+                                dimension: wgpu::TextureDimension::D2,
+                                format: wgpu::TextureFormat::Rgba8Unorm,
+                                usage: wgpu::TextureUsages::COPY_DST
+                                    | wgpu::TextureUsages::TEXTURE_BINDING
+                                    | wgpu::TextureUsages::COPY_SRC,
+                                label: Some("VideoWidget_Texture"),
+                                view_formats: &[],
+                            };
+
+                            let tex = Arc::new(wgpu_cx.device.create_texture(&texture_desc));
+                            wgpu_texture = Some(tex.clone());
+
+                            let new_overlay = create_unique_overlay_key(width, height);
+                            let _ = dim_tx_for_thread.send((width, height, new_overlay.clone()));
+
+                            let action = VideoAction::SetOverride(new_overlay, tex);
+                            let erased: ErasedAction = Box::new(action);
+                            let _ =
+                                proxy.send_event(MasonryUserEvent::AsyncAction(*win_id, erased));
+                        }
+                    }
 
-                let Some(buffer) = sample.buffer() else {
-                    continue;
-                };
-                let Some(caps) = sample.caps() else {
-                    continue;
-                };
-                let Ok(video_info) = gst_video::VideoInfo::from_caps(caps) else {
-                    continue;
-                };
-
-                let width = video_info.width();
-                let height = video_info.height();
-
-                // If we don't have a texture yet, or it's the wrong size, create a new one.
-                if wgpu_texture.is_none()
-                    || wgpu_texture
-                        .as_ref()
-                        .is_some_and(|tex| tex.width() != width)
-                    || wgpu_texture
-                        .as_ref()
-                        .is_some_and(|tex| tex.height() != height)
-                {
                     if cached_wgpu_context.is_none() {
                         cached_wgpu_context = get_wgpu_context();
                     }
-                    if cached_proxy_context.is_none() {
-                        cached_proxy_context = get_event_loop_proxy();
-                    }
-
-                    if let (Some(wgpu_cx), Some((proxy, win_id))) =
-                        (cached_wgpu_context.as_ref(), cached_proxy_context.as_ref())
-                    {
-                        let texture_desc = wgpu::TextureDescriptor {
-                            size: wgpu::Extent3d {
-                                width,
-                                height,
-                                depth_or_array_layers: 1,
-                            },
-                            mip_level_count: 1,
-                            sample_count: 1,
-                            dimension: wgpu::TextureDimension::D2,
-                            format: wgpu::TextureFormat::Rgba8Unorm,
-                            usage: wgpu::TextureUsages::COPY_DST
-                                | wgpu::TextureUsages::TEXTURE_BINDING
-                                | wgpu::TextureUsages::COPY_SRC,
-                            label: Some("VideoWidget_Texture"),
-                            view_formats: &[],
-                        };
-
-                        let tex = Arc::new(wgpu_cx.device.create_texture(&texture_desc));
-                        wgpu_texture = Some(tex.clone());
-
-                        let new_overlay = create_unique_overlay_key(width, height);
-                        let _ = dim_tx_for_thread.send((width, height, new_overlay.clone()));
-
-                        let action = VideoAction::SetOverride(new_overlay, tex);
-                        let erased: ErasedAction = Box::new(action);
-                        let _ = proxy.send_event(MasonryUserEvent::AsyncAction(*win_id, erased));
-                    }
-                }
-
-                if cached_wgpu_context.is_none() {
-                    cached_wgpu_context = get_wgpu_context();
-                }
 
-                if let (Some(tex), Some(wgpu_cx)) = (&wgpu_texture, cached_wgpu_context.as_ref()) {
+                    let (Some(tex), Some(wgpu_cx)) = (&wgpu_texture, cached_wgpu_context.as_ref())
+                    else {
+                        return;
+                    };
                     // Backpressure: if a repaint is already pending, skip this frame upload.
                     // This bounds CPU->GPU transfer work to the UI consumption rate.
                     if frame_ready_pending_for_thread.load(Ordering::Acquire) {
-                        continue;
+                        return;
                     }
 
-                    let Ok(map) = buffer.map_readable() else {
-                        continue;
-                    };
-                    let data = map.as_slice();
-                    let min_row_bytes = width.saturating_mul(4);
-                    let stride_bytes = buffer
-                        .meta::<gst_video::VideoMeta>()
-                        .map(|meta| meta.stride()[0] as u32)
-                        .filter(|stride| *stride >= min_row_bytes)
-                        .unwrap_or(min_row_bytes);
-
-                    wgpu_cx.queue.write_texture(
-                        masonry::vello::wgpu::TexelCopyTextureInfo {
-                            texture: tex,
-                            mip_level: 0,
-                            origin: wgpu::Origin3d::ZERO,
-                            aspect: wgpu::TextureAspect::All,
-                        },
-                        data,
-                        masonry::vello::wgpu::TexelCopyBufferLayout {
-                            offset: 0,
-                            bytes_per_row: Some(stride_bytes),
-                            rows_per_image: Some(height),
-                        },
-                        wgpu::Extent3d {
-                            width,
-                            height,
-                            depth_or_array_layers: 1,
-                        },
-                    );
+                    match frame {
+                        VideoFrame::Rgba { stride, data, .. } => {
+                            wgpu_cx.queue.write_texture(
+                                masonry::vello::wgpu::TexelCopyTextureInfo {
+                                    texture: tex,
+                                    mip_level: 0,
+                                    origin: wgpu::Origin3d::ZERO,
+                                    aspect: wgpu::TextureAspect::All,
+                                },
+                                data,
+                                masonry::vello::wgpu::TexelCopyBufferLayout {
+                                    offset: 0,
+                                    bytes_per_row: Some(stride),
+                                    rows_per_image: Some(height),
+                                },
+                                wgpu::Extent3d {
+                                    width,
+                                    height,
+                                    depth_or_array_layers: 1,
+                                },
+                            );
+                        }
+                        #[cfg(all(feature = "dmabuf", target_os = "linux"))]
+                        VideoFrame::DmaBuf(frame) => {
+                            if let Err(e) =
+                                video_dmabuf::copy_into(&wgpu_cx.device, &wgpu_cx.queue, frame, tex)
+                            {
+                                // The source sees this and goes back to RGBA uploads.
+                                eprintln!("[VideoWidget] Zero-copy import failed: {e}");
+                                video_dmabuf::mark_failed();
+                                return;
+                            }
+                        }
+                    }
 
                     // Wake the UI to redraw if we know the WidgetId
                     if let Ok(id_lock) = shared_id_for_thread.lock()
@@ -408,15 +404,37 @@ impl VideoWidget {
                     if !is_playing {
                         paused_frame_uploaded = true;
                     }
+                });
+
+                if let Err(message) = pulled {
+                    eprintln!("[VideoWidget] Playback failed: {message}");
+                    // The widget may not be in the tree yet to hear about it.
+                    let id = loop {
+                        if !worker_alive_for_thread.load(Ordering::Acquire) {
+                            return;
+                        }
+                        if let Ok(id_lock) = shared_id_for_thread.lock()
+                            && let Some(id) = *id_lock
+                        {
+                            break id;
+                        }
+                        std::thread::sleep(Duration::from_millis(16));
+                    };
+                    if let Some((proxy, win_id)) = get_event_loop_proxy() {
+                        let action = VideoAction::Failed(id, message);
+                        let _ = proxy
+                            .send_event(MasonryUserEvent::AsyncAction(win_id, Box::new(action)));
+                    }
+                    return;
                 }
             }
         });
 
-        Some(VideoPipelineRuntime {
-            pipeline,
+        VideoPipelineRuntime {
+            source,
             worker_alive,
             worker,
-        })
+        }
     }
 
     fn stop_worker(&mut self) {
@@ -426,20 +444,54 @@ impl VideoWidget {
         }
     }
 
-    /// Start playback.
-    fn start_playback(&mut self) {
-        if let Some(ref pipeline) = self.pipeline
-            && let Err(e) = pipeline.set_state(gst::State::Playing)
-        {
+    /// Open `src` and start the worker, playing or paused as JS last asked.
+    fn open(&mut self) {
+        let (dim_tx, dim_rx) = channel();
+        let runtime = Self::spawn_worker(
+            open_source(&self.src),
+            dim_tx,
+            self.shared_widget_id.clone(),
+            self.frame_ready_pending.clone(),
+            self.paused_refresh_requested.clone(),
+            self.playback_active.clone(),
+            self.resume_at.take(),
+        );
+        runtime.source.set_volume(self.volume, self.muted);
+        if let Err(e) = runtime.source.set_playing(self.wants_playing()) {
             eprintln!("[VideoWidget] Failed to start playback: {}", e);
         }
+        self.source = Some(runtime.source);
+        self.worker_alive = runtime.worker_alive;
+        self.worker = Some(runtime.worker);
+        self.dim_receiver = Some(dim_rx);
+        self.frame_ready_pending.store(false, Ordering::Release);
+        self.paused_refresh_requested.store(true, Ordering::Release);
+    }
+
+    /// Stop playback and drop the pipeline and its texture.
+    fn release(&mut self) {
+        self.stop_playback();
+        self.source = None;
+        self.dim_receiver = None;
+
+        // Remove old texture override and make a new dummy key
+        if let Some((proxy, win_id)) = get_event_loop_proxy() {
+            let action = VideoAction::ClearOverride(self.overlay_key.clone());
+            let erased: ErasedAction = Box::new(action);
+            let _ = proxy.send_event(MasonryUserEvent::AsyncAction(win_id, erased));
+        }
+        self.overlay_key = create_unique_overlay_key(1, 1);
+        self.current_image = ImageBrush::from(self.overlay_key.clone());
+        self.video_width = 0;
+        self.video_height = 0;
+        self.frame_ready_pending.store(false, Ordering::Release);
     }
 
     /// Stop playback and clean up.
     fn stop_playback(&mut self) {
         self.stop_worker();
-        if let Some(ref pipeline) = self.pipeline {
-            let _ = pipeline.set_state(gst::State::Null);
+        if let Some(ref source) = self.source {
+            source.stop();
         }
     }
 }
@@ -479,6 +531,23 @@ impl VideoWidget {
         this.ctx.request_paint_only();
     }
 
+    /// Replace the picture with `message` over the poster (or placeholder).
+    pub fn set_error(this: &mut WidgetMut<'_, Self>, message: &str) {
+        if let Some(old) = this.widget.error.take() {
+            this.ctx.remove_child(old);
+        }
+        let label = Label::new(format!("Video unavailable: {message}"));
+        let label = NewWidget::new_with(
+            label,
+            None,
+            WidgetOptions::default(),
+            PropertySet::new().with(ContentColor::new(Color::from_rgb8(0xe6, 0xe6, 0xe6))),
+        );
+        this.widget.error = Some(label.to_pod());
+        this.ctx.children_changed();
+        this.ctx.request_render();
+    }
+
     pub fn set_width(this: &mut WidgetMut<'_, Self>, w: Option<f64>) {
         this.widget.style_width = w;
         this.ctx.request_layout();
@@ -489,106 +558,106 @@ impl VideoWidget {
         this.ctx.request_layout();
     }
 
-    /// Set a new video source on an existing widget.
+    /// Set a new video source on an existing widget. The registry opens it
+    /// if the widget is in view.
     #[allow(dead_code)]
     pub fn set_src(this: &mut WidgetMut<'_, Self>, src: &str) {
-        // Stop old pipeline
-        this.widget.stop_playback();
-        this.widget.pipeline = None;
+        this.widget.release();
+        if let Some(old) = this.widget.error.take() {
+            this.ctx.remove_child(old);
+        }
+        this.widget.src = src.to_string();
+        this.widget.resume_at = None;
+        this.widget.playback_active.store(false, Ordering::Release);
+        video_registry::request_sweep();
 
-        // Remove old texture override and make a new dummy key
-        if let Some((proxy, win_id)) = get_event_loop_proxy() {
-            let action = VideoAction::ClearOverride(this.widget.overlay_key.clone());
-            let erased: ErasedAction = Box::new(action);
-            let _ = proxy.send_event(MasonryUserEvent::AsyncAction(win_id, erased));
+        this.ctx.request_layout();
+        this.ctx.request_render();
+    }
+
+    /// Give up the pipeline and texture (out of view, or over the limit),
+    /// remembering the position for `resume`.
+    pub fn suspend(this: &mut WidgetMut<'_, Self>) {
+        let position = this.widget.source.as_ref().and_then(|s| s.position());
+        this.widget.release();
+        this.widget.resume_at = position;
+        this.ctx.request_render();
+    }
+
+    /// Reopen the pipeline where `suspend` left it.
+    pub fn resume(this: &mut WidgetMut<'_, Self>) {
+        if this.widget.source.is_none() {
+            this.widget.open();
         }
+    }
 
-        this.widget.overlay_key = create_unique_overlay_key(1, 1);
-        this.widget.current_image = ImageBrush::from(this.widget.overlay_key.clone());
-        this.widget.video_width = 0;
-        this.widget.video_height = 0;
-
-        // Initialize new pipeline asynchronously
-        if gst::init().is_ok() {
-            let uri = Self::normalize_uri(src);
-
-            let (dim_tx, dim_rx) = channel();
-            let shared_id_clone = this.widget.shared_widget_id.clone();
-            let frame_ready_pending_clone = this.widget.frame_ready_pending.clone();
-            let paused_refresh_requested_clone = this.widget.paused_refresh_requested.clone();
-            let playback_active_clone = this.widget.playback_active.clone();
-
-            let runtime = Self::build_pipeline(
-                &uri,
-                dim_tx,
-                shared_id_clone,
-                frame_ready_pending_clone,
-                paused_refresh_requested_clone,
-                playback_active_clone,
-            );
+    /// Drop and reopen the pipeline at the same position, so it picks up a
+    /// new audio output.
+    pub fn reload(this: &mut WidgetMut<'_, Self>) {
+        if this.widget.source.is_some() {
+            Self::suspend(this);
+            Self::resume(this);
+        }
+    }
 
-            if let Some(runtime) = runtime {
-                this.widget.pipeline = Some(runtime.pipeline);
-                this.widget.worker_alive = runtime.worker_alive;
-                this.widget.worker = Some(runtime.worker);
-            } else {
-                this.widget.pipeline = None;
-                this.widget.worker_alive = Arc::new(AtomicBool::new(false));
-                this.widget.worker = None;
-            }
-            this.widget.dim_receiver = Some(dim_rx);
-            this.widget
-                .frame_ready_pending
-                .store(false, Ordering::Release);
-            this.widget
-                .paused_refresh_requested
-                .store(true, Ordering::Release);
-            this.widget.playback_active.store(false, Ordering::Release);
-            this.widget.started = false;
+    pub fn set_volume(this: &mut WidgetMut<'_, Self>, volume: f64) {
+        this.widget.volume = volume.clamp(0.0, 1.0);
+        if let Some(source) = &this.widget.source {
+            source.set_volume(this.widget.volume, this.widget.muted);
         }
+    }
 
-        this.ctx.request_layout();
-        this.ctx.request_render();
+    pub fn set_muted(this: &mut WidgetMut<'_, Self>, muted: bool) {
+        this.widget.muted = muted;
+        if let Some(source) = &this.widget.source {
+            source.set_volume(this.widget.volume, this.widget.muted);
+        }
     }
 
     pub fn play(this: &mut WidgetMut<'_, Self>) {
-        this.widget.started = true;
+        let was_playing = this.widget.wants_playing();
         this.widget.playback_active.store(true, Ordering::Release);
         this.widget
             .paused_refresh_requested
             .store(true, Ordering::Release);
-        if let Some(ref pipeline) = this.widget.pipeline
-            && let Err(e) = pipeline.set_state(gst::State::Playing)
+        if let Some(ref source) = this.widget.source
+            && let Err(e) = source.set_playing(true)
         {
             eprintln!("[VideoWidget] Failed to play: {}", e);
         }
+        // Playing videos come first for a pipeline.
+        if !was_playing {
+            video_registry::request_sweep();
+        }
     }
 
     pub fn pause(this: &mut WidgetMut<'_, Self>) {
-        this.widget.started = false;
+        let was_playing = this.widget.wants_playing();
         this.widget.playback_active.store(false, Ordering::Release);
         this.widget
             .paused_refresh_requested
             .store(true, Ordering::Release);
-        if let Some(ref pipeline) = this.widget.pipeline
-            && let Err(e) = pipeline.set_state(gst::State::Paused)
+        if let Some(ref source) = this.widget.source
+            && let Err(e) = source.set_playing(false)
         {
             eprintln!("[VideoWidget] Failed to pause: {}", e);
         }
+        if was_playing {
+            video_registry::request_sweep();
+        }
     }
 
     pub fn seek(this: &mut WidgetMut<'_, Self>, time_secs: f64) {
         this.widget
             .paused_refresh_requested
             .store(true, Ordering::Release);
-        if let Some(ref pipeline) = this.widget.pipeline {
-            let time = gst::ClockTime::from_nseconds((time_secs * 1_000_000_000.0) as u64);
-            if pipeline
-                .seek_simple(gst::SeekFlags::FLUSH | gst::SeekFlags::KEY_UNIT, time)
-                .is_err()
-            {
-                eprintln!("[VideoWidget] Seek to {}s failed", time_secs);
+        match &this.widget.source {
+            Some(source) => {
+                if let Err(e) = source.seek(time_secs) {
+                    eprintln!("[VideoWidget] Seek to {}s failed: {}", time_secs, e);
+                }
             }
+            None => this.widget.resume_at = Some(time_secs),
         }
     }
 }
@@ -601,6 +670,9 @@ impl Drop for VideoWidget {
         if let Ok(mut id_lock) = self.shared_widget_id.lock() {
             *id_lock = None;
         }
+        if self.registered {
+            video_registry::unregister();
+        }
         if let Some((proxy, win_id)) = get_event_loop_proxy() {
             let action = VideoAction::ClearOverride(self.overlay_key.clone());
             let erased: ErasedAction = Box::new(action);
@@ -617,7 +689,11 @@ impl Widget for VideoWidget {
         false
     }
 
-    fn register_children(&mut self, _ctx: &mut RegisterCtx<'_>) {}
+    fn register_children(&mut self, ctx: &mut RegisterCtx<'_>) {
+        if let Some(error) = &mut self.error {
+            ctx.register_child(error);
+        }
+    }
 
     fn on_anim_frame(
         &mut self,
@@ -633,19 +709,18 @@ impl Widget for VideoWidget {
             ctx.request_paint_only();
         }
 
-        // Wait... how does the appsink know the widget_id? We don't have it on creation!
-        // The easiest way is for appsink to not know widget_id, OR we find it out here and pass it back.
-        // Actually, appsink needs the WidgetId to send FrameReady. We don't have it in `new()`.
+        // The worker needs the WidgetId to send FrameReady, and it isn't
+        // known in `new()`.
         if event == &Update::WidgetAdded {
-            if !self.started {
-                self.start_playback();
-                self.started = true;
-            }
-
-            // Store our WidgetId so the Gstreamer thread can trigger redraws
+            // Store our WidgetId so the worker thread can trigger redraws
             if let Ok(mut id_lock) = self.shared_widget_id.lock() {
                 *id_lock = Some(ctx.widget_id());
             }
+            // The registry's sweep opens the pipeline if we are in view.
+            if !self.registered {
+                self.registered = true;
+                video_registry::register();
+            }
         }
     }
 
@@ -662,31 +737,50 @@ impl Widget for VideoWidget {
 
     fn layout(
         &mut self,
-        _ctx: &mut LayoutCtx<'_>,
+        ctx: &mut LayoutCtx<'_>,
         _props: &PropertiesRef<'_>,
         size: masonry::kurbo::Size,
     ) {
         self.last_size = size;
+        // A resize can move us into or out of view.
+        video_registry::request_sweep();
+        if let Some(error) = &mut self.error {
+            let error_size = ctx.compute_size(error, SizeDef::fit(size), size.into());
+            ctx.run_layout(error, error_size);
+            let origin = Point::new(
+                ((size.width - error_size.width) * 0.5).max(0.0),
+                ((size.height - error_size.height) * 0.5).max(0.0),
+            );
+            ctx.place_child(error, origin);
+        }
     }
 
     fn paint(&mut self, _ctx: &mut PaintCtx<'_>, _props: &PropertiesRef<'_>, scene: &mut Scene) {
         let content_size = self.last_size;
+        let has_frame = self.video_width > 0 && self.video_height > 0;
+
+        if !has_frame || self.error.is_some() {
+            scene.fill(
+                Fill::NonZero,
+                Affine::IDENTITY,
+                PLACEHOLDER_COLOR,
+                None,
+                &Rect::from_origin_size(Point::ORIGIN, content_size),
+            );
+            if let Some(poster) = &self.poster
+                && let Some(transform) = contain(content_size, poster.width, poster.height)
+            {
+                let alpha = if self.error.is_some() { 0.35 } else { 1.0 };
+                scene.draw_image(
+                    &ImageBrush::from(poster.clone()).with_alpha(alpha),
+                    transform,
+                );
+            }
+            return;
+        }
 
-        // Scale the video to fit the widget bounds (contain mode)
-        let img_w = self.video_width as f64;
-        let img_h = self.video_height as f64;
-
-        if img_w > 0.0 && img_h > 0.0 {
-            let scale_x = content_size.width / img_w;
-            let scale_y = content_size.height / img_h;
-            let scale = scale_x.min(scale_y);
-
-            let offset_x = (content_size.width - img_w * scale) * 0.5;
-            let offset_y = (content_size.height - img_h * scale) * 0.5;
-
-            let transform = Affine::translate((offset_x, offset_y)) * Affine::scale(scale);
-
-            // Vello will automatically replace `current_image` data with the override texture!
+        // Vello will automatically replace `current_image` data with the override texture!
+        if let Some(transform) = contain(content_size, self.video_width, self.video_height) {
             scene.draw_image(&self.current_image, transform);
         }
     }
@@ -704,6 +798,33 @@ impl Widget for VideoWidget {
     }
 
     fn children_ids(&self) -> ChildrenIds {
-        ChildrenIds::new()
+        self.error.iter().map(|error| error.id()).collect()
+    }
+}
+
+/// Scale an image to fit `size` (contain mode), centered.
+fn contain(size: Size, width: u32, height: u32) -> Option<Affine> {
+    let (img_w, img_h) = (width as f64, height as f64);
+    if img_w <= 0.0 || img_h <= 0.0 {
+        return None;
+    }
+    let scale = (size.width / img_w).min(size.height / img_h);
+    let offset_x = (size.width - img_w * scale) * 0.5;
+    let offset_y = (size.height - img_h * scale) * 0.5;
+    Some(Affine::translate((offset_x, offset_y)) * Affine::scale(scale))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contain_centers_the_image() {
+        let transform = contain(Size::new(200.0, 100.0), 100, 100).unwrap();
+        assert_eq!(
+            transform,
+            Affine::translate((50.0, 0.0)) * Affine::scale(1.0)
+        );
+        assert!(contain(Size::new(200.0, 100.0), 0, 100).is_none());
     }
 }
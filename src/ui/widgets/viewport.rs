@@ -0,0 +1,241 @@
+// Viewport Sweep
+// After a layout that may have moved widgets in or out of view (a Portal
+// scrolled, the window resized, the tree changed), the driver sweeps the
+// tree:
+//
+// - Rows of a Portal far outside its viewport are culled (stashed, so they
+//   are neither laid out nor painted), rows back in range are restored, and
+//   each row that has just scrolled into view is reported to JS as
+//   `EnteredViewport`, so images and other heavy content can be loaded
+//   lazily. A row keeps the height of its last layout while culled.
+// - Widgets observed with `ObserveVisibility` get a `VisibilityChanged` with
+//   the fraction of their area that shows, clipped by the window and every
+//   enclosing Portal, whenever it crosses their threshold, like an
+//   IntersectionObserver.
+// - Widgets observed with `ObserveResize` get a `WidgetResized` whenever
+//   their laid out size changes, like a ResizeObserver. Hidden and culled
+//   widgets keep their last size.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use masonry::app::RenderRoot;
+use masonry::core::{Widget, WidgetId, WidgetRef};
+use masonry::kurbo::{Rect, Size};
+use masonry_winit::app::MasonryUserEvent;
+
+use crate::ipc::{UiEvent, WidgetActionKind};
+use crate::ui::global_state::get_event_loop_proxy;
+use crate::ui::widget_manager::{ROOT_STACK_TAG, WidgetManager};
+use crate::ui::widgets::scroll_area_impl::ScrollArea;
+
+/// Sent to the driver when widgets may have moved in or out of view.
+#[derive(Clone, Copy, Debug)]
+pub struct SweepViewport;
+
+/// A sweep is already on its way to the driver.
+static SWEEP_PENDING: AtomicBool = AtomicBool::new(false);
+/// Some widget is observed, so every layout needs a sweep.
+static OBSERVING: AtomicBool = AtomicBool::new(false);
+
+/// An `ObserveVisibility` registration.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VisibilityObserver {
+    pub threshold: f64,
+    /// Whether the widget met the threshold at the last report; None until
+    /// the first sweep after it was observed.
+    pub visible: Option<bool>,
+}
+
+/// Ask the driver for a sweep once the current passes are done.
+pub fn request_sweep() {
+    if SWEEP_PENDING.swap(true, Ordering::AcqRel) {
+        return;
+    }
+    match get_event_loop_proxy() {
+        Some((proxy, win_id)) => {
+            let _ = proxy.send_event(MasonryUserEvent::AsyncAction(
+                win_id,
+                Box::new(SweepViewport),
+            ));
+        }
+        None => SWEEP_PENDING.store(false, Ordering::Release),
+    }
+}
+
+/// Called by the root after every layout: sweep if anything is observed.
+pub fn layout_changed() {
+    if OBSERVING.load(Ordering::Relaxed) {
+        request_sweep();
+    }
+}
+
+/// Keep the root's sweeps in step with the observers after registering or
+/// dropping one.
+pub fn set_observing(observing: bool) {
+    OBSERVING.store(observing, Ordering::Relaxed);
+    if observing {
+        request_sweep();
+    }
+}
+
+/// Whether `ratio` of a widget showing meets `threshold`. A threshold of 0
+/// means any part at all.
+fn meets(ratio: f64, threshold: f64) -> bool {
+    if threshold <= 0.0 {
+        ratio > 0.0
+    } else {
+        ratio >= threshold
+    }
+}
+
+fn window_rect(widget: &WidgetRef<'_, dyn Widget>) -> Rect {
+    let ctx = widget.ctx();
+    ctx.window_transform()
+        .transform_rect_bbox(ctx.size().to_rect())
+}
+
+/// What the walk found out about an observed widget.
+#[derive(Clone, Copy)]
+struct Measured {
+    /// Fraction of the widget within the view
+    ratio: f64,
+    size: Size,
+}
+
+/// Walk the visible tree, listing Portals and measuring each observed widget
+/// against `view`.
+fn collect(
+    widget: WidgetRef<'_, dyn Widget>,
+    view: Rect,
+    observed: &HashSet<WidgetId>,
+    portals: &mut Vec<WidgetId>,
+    measured: &mut HashMap<WidgetId, Measured>,
+) {
+    if widget.ctx().is_stashed() {
+        return;
+    }
+    let rect = window_rect(&widget);
+    if observed.contains(&widget.id()) {
+        let area = rect.area();
+        let shown = rect.intersect(view);
+        let ratio = if area > 0.0 && shown.width() > 0.0 && shown.height() > 0.0 {
+            (shown.area() / area).min(1.0)
+        } else {
+            0.0
+        };
+        let size = widget.ctx().size();
+        measured.insert(widget.id(), Measured { ratio, size });
+    }
+    let view = if widget.downcast::<ScrollArea>().is_some() {
+        portals.push(widget.id());
+        view.intersect(rect)
+    } else {
+        view
+    };
+    for child in widget.children() {
+        collect(child, view, observed, portals, measured);
+    }
+}
+
+/// Update culling in every Portal and re-measure observed widgets. Returns
+/// the events to send to JS.
+pub fn sweep(render_root: &mut RenderRoot, widget_manager: &mut WidgetManager) -> Vec<UiEvent> {
+    SWEEP_PENDING.store(false, Ordering::Release);
+    let Some(root) = render_root.get_widget_with_tag(ROOT_STACK_TAG) else {
+        return Vec::new();
+    };
+    let root_id = root.id();
+
+    let observed: HashSet<WidgetId> = widget_manager
+        .visibility_observers
+        .keys()
+        .chain(widget_manager.resize_observers.keys())
+        .filter_map(|id| Some(widget_manager.widgets.get(id)?.widget_id))
+        .collect();
+    let mut portals = Vec::new();
+    let mut measured = HashMap::new();
+    if let Some(root) = render_root.get_widget(root_id) {
+        let view = window_rect(&root);
+        collect(root, view, &observed, &mut portals, &mut measured);
+    }
+
+    let mut events = Vec::new();
+    for portal in portals {
+        let mut indices = Vec::new();
+        render_root.edit_widget(portal, |mut widget| {
+            indices = ScrollArea::update_visibility(&mut widget.downcast::<ScrollArea>());
+        });
+        if indices.is_empty() {
+            continue;
+        }
+        // Portals made by JS are registered under their ScrollArea's id.
+        let Some(rows) = widget_manager
+            .widgets
+            .iter()
+            .find(|(_, info)| info.widget_id == portal)
+            .and_then(|(id, _)| widget_manager.parent_to_children.get(id))
+        else {
+            continue;
+        };
+        events.extend(indices.into_iter().filter_map(|index| {
+            Some(UiEvent::WidgetAction {
+                widget_id: rows.get(index)?.clone(),
+                action: WidgetActionKind::EnteredViewport,
+            })
+        }));
+    }
+
+    for (id, observer) in &mut widget_manager.visibility_observers {
+        let Some(info) = widget_manager.widgets.get(id) else {
+            continue;
+        };
+        // Widgets the walk didn't reach are hidden or culled.
+        let ratio = measured
+            .get(&info.widget_id)
+            .map_or(0.0, |measured| measured.ratio);
+        let visible = meets(ratio, observer.threshold);
+        if observer.visible != Some(visible) {
+            observer.visible = Some(visible);
+            events.push(UiEvent::VisibilityChanged {
+                id: id.clone(),
+                ratio,
+            });
+        }
+    }
+
+    for (id, last) in &mut widget_manager.resize_observers {
+        let Some(size) = widget_manager
+            .widgets
+            .get(id)
+            .and_then(|info| measured.get(&info.widget_id))
+            .map(|measured| measured.size)
+        else {
+            continue;
+        };
+        if *last != Some(size) {
+            *last = Some(size);
+            events.push(UiEvent::WidgetResized {
+                id: id.clone(),
+                width: size.width,
+                height: size.height,
+            });
+        }
+    }
+    OBSERVING.store(widget_manager.is_observing(), Ordering::Relaxed);
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_threshold_zero_means_any_part() {
+        assert!(!meets(0.0, 0.0));
+        assert!(meets(0.01, 0.0));
+        assert!(!meets(0.49, 0.5));
+        assert!(meets(0.5, 0.5));
+        assert!(meets(1.0, 1.0));
+    }
+}
@@ -1,11 +1,12 @@
 use masonry::app::RenderRoot;
 use masonry::core::{NewWidget, WidgetOptions};
-use masonry::widgets::ZStack;
+use masonry::properties::types::{CrossAxisAlignment, MainAxisAlignment};
 
 use crate::ipc::{BoxStyle, WidgetKind};
 use crate::ui::styles::build_box_properties;
-use crate::ui::widget_manager::{WidgetInfo, WidgetManager};
-use crate::ui::widgets::utils::add_to_parent;
+use crate::ui::widget_manager::{WidgetInfo, WidgetManager, layer_tag};
+use crate::ui::widgets::layer_impl::Layer;
+use crate::ui::widgets::utils::{WrapperOptions, add_to_parent};
 
 pub fn create(
     render_root: &mut RenderRoot,
@@ -14,9 +15,11 @@ pub fn create(
     parent_id: Option<String>,
     style: Option<BoxStyle>,
     child_index: usize,
+    wrappers: WrapperOptions,
 ) {
     let style_ref = style.as_ref();
-    let zstack = ZStack::new();
+    // A stack is a layer that centers its children.
+    let zstack = Layer::new(MainAxisAlignment::Center, CrossAxisAlignment::Center);
 
     let props = style_ref.map(build_box_properties).unwrap_or_default();
     let new_widget = NewWidget::new_with(zstack, None, WidgetOptions::default(), props);
@@ -28,6 +31,7 @@ pub fn create(
         &parent_id,
         new_widget,
         style_ref.and_then(|s| s.flex),
+        wrappers,
     ) {
         widget_manager.register_widget(
             id,
@@ -40,3 +44,37 @@ pub fn create(
         );
     }
 }
+
+/// Restack widget `id` among its siblings (`zIndex`). Only children of a
+/// ZStack or a named layer have a stacking order.
+pub fn set_z_index(
+    render_root: &mut RenderRoot,
+    widget_manager: &WidgetManager,
+    id: &str,
+    z_index: i32,
+) -> Result<(), String> {
+    let info = widget_manager
+        .widgets
+        .get(id)
+        .ok_or_else(|| format!("Widget '{id}' not found for zIndex"))?;
+    let parent_key = info.parent_id.as_deref().unwrap_or("__root__");
+    let index = info.child_index;
+    if let Some(tag) = layer_tag(parent_key) {
+        render_root.edit_widget_with_tag(tag, |mut layer| {
+            Layer::set_z_index(&mut layer, index, z_index);
+        });
+        return Ok(());
+    }
+    match widget_manager.widgets.get(parent_key) {
+        Some(parent) if matches!(parent.kind, WidgetKind::ZStack) => {
+            render_root.edit_widget(parent.widget_id, |mut widget| {
+                let mut stack = widget.downcast::<Layer>();
+                Layer::set_z_index(&mut stack, index, z_index);
+            });
+            Ok(())
+        }
+        _ => Err(format!(
+            "zIndex on '{id}' needs a <zstack> or layer parent, not '{parent_key}'"
+        )),
+    }
+}
@@ -0,0 +1,501 @@
+// Auto-Update
+// A packaged app (see bundle.rs) can update itself from a JSON feed it hosts:
+//
+//   { "version": "1.4.0",
+//     "notes": "Faster sync",
+//     "bundle": { "url": "https://example.com/notes-1.4.0.vlm", "sha256": "…",
+//                 "signature": "…", "size": 81920 },
+//     "binaries": { "macos-aarch64": { "url": "…", "sha256": "…", "signature": "…" } } }
+//
+// `CheckForUpdate` fetches the feed and offers its version if it is newer
+// than the one JS runs. `DownloadUpdate` fetches the offered app bundle
+// (`vellum build --bundle-only`) and, when the feed lists one for this
+// `<os>-<arch>`, a new runtime binary, checks their SHA-256 and their Ed25519
+// signatures against the `update-public-key` of the app's manifest, and
+// stages the combined executable as `<exe>.update`. A signature covers the
+// feed's version as well as the file (`<version>\n<file>`), so an old file
+// can't be offered under a newer version. An app without a key
+// cannot update. What is not downloaded is kept from the running executable.
+// The staged executable replaces the running one when the app restarts, at
+// once with `RestartToUpdate` or at the next launch.
+//
+// Downloads go through `curl`, which ships with macOS, Windows 10 and most
+// Linux distributions.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use ed25519_dalek::{Signature, VerifyingKey};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use crate::bundle;
+use crate::ipc::{UiEvent, UiEventSender};
+
+/// How often the size of a download in progress is reported.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+/// A file the feed offers.
+#[derive(Debug, Clone, Deserialize)]
+struct Artifact {
+    url: String,
+    sha256: String,
+    /// Ed25519 signature of `<version>\n<file>`, as hex
+    signature: String,
+    #[serde(default)]
+    size: Option<u64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Feed {
+    version: String,
+    #[serde(default)]
+    notes: Option<String>,
+    #[serde(default)]
+    bundle: Option<Artifact>,
+    /// Runtime binaries keyed by `<os>-<arch>`, e.g. `windows-x86_64`
+    #[serde(default)]
+    binaries: HashMap<String, Artifact>,
+}
+
+/// The feed of the last check that found a newer version.
+static OFFER: Mutex<Option<Feed>> = Mutex::new(None);
+/// A download is running.
+static DOWNLOADING: AtomicBool = AtomicBool::new(false);
+/// `RestartToUpdate` was called; relaunch once the UI has closed.
+static RELAUNCH: AtomicBool = AtomicBool::new(false);
+/// The manifest's `update-public-key`, which downloads must be signed with.
+static PUBLIC_KEY: Mutex<Option<VerifyingKey>> = Mutex::new(None);
+
+/// The version's numeric parts and pre-release tag: `v1.4.0-beta.2` is
+/// `([1, 4, 0], Some("beta.2"))`.
+fn parse_version(version: &str) -> Option<(Vec<u64>, Option<&str>)> {
+    let version = version.trim().trim_start_matches('v');
+    let (release, pre) = match version.split_once('-') {
+        Some((release, pre)) => (release, Some(pre)),
+        None => (version, None),
+    };
+    let parts = release
+        .split('.')
+        .map(|part| part.parse().ok())
+        .collect::<Option<Vec<u64>>>()?;
+    Some((parts, pre))
+}
+
+/// Order pre-release tags as SemVer does: identifier by identifier, numeric
+/// ones as numbers and before alphanumeric ones, and a tag that runs out
+/// first before a longer one (`beta.9` < `beta.10` < `beta.10.1` < `rc`).
+fn compare_pre_release(a: &str, b: &str) -> std::cmp::Ordering {
+    use std::cmp::Ordering;
+    let mut a_ids = a.split('.');
+    let mut b_ids = b.split('.');
+    loop {
+        let order = match (a_ids.next(), b_ids.next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(a), Some(b)) => match (a.parse::<u64>(), b.parse::<u64>()) {
+                (Ok(a), Ok(b)) => a.cmp(&b),
+                (Ok(_), Err(_)) => Ordering::Less,
+                (Err(_), Ok(_)) => Ordering::Greater,
+                (Err(_), Err(_)) => a.cmp(b),
+            },
+        };
+        if order.is_ne() {
+            return order;
+        }
+    }
+}
+
+/// Whether `candidate` is a later version than `current`. A pre-release comes
+/// before its release; versions that don't parse are never newer.
+fn is_newer(candidate: &str, current: &str) -> bool {
+    let (Some((mut new, new_pre)), Some((mut old, old_pre))) =
+        (parse_version(candidate), parse_version(current))
+    else {
+        return false;
+    };
+    let len = new.len().max(old.len());
+    new.resize(len, 0);
+    old.resize(len, 0);
+    match new.cmp(&old) {
+        std::cmp::Ordering::Equal => match (new_pre, old_pre) {
+            (None, Some(_)) => true,
+            (Some(new_pre), Some(old_pre)) => compare_pre_release(new_pre, old_pre).is_gt(),
+            _ => false,
+        },
+        order => order.is_gt(),
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Exactly `N` bytes written as hex, in either case.
+fn from_hex<const N: usize>(hex: &str) -> Option<[u8; N]> {
+    let hex = hex.trim().as_bytes();
+    if hex.len() != N * 2 {
+        return None;
+    }
+    let mut bytes = [0; N];
+    for (byte, pair) in bytes.iter_mut().zip(hex.chunks_exact(2)) {
+        *byte = u8::from_str_radix(std::str::from_utf8(pair).ok()?, 16).ok()?;
+    }
+    Some(bytes)
+}
+
+/// SHA-256 of `data` as lowercase hex.
+fn sha256_hex(data: &[u8]) -> String {
+    to_hex(&Sha256::digest(data))
+}
+
+/// An Ed25519 public key written as 64 hex digits.
+pub fn parse_public_key(hex: &str) -> Result<VerifyingKey, String> {
+    from_hex(hex)
+        .and_then(|bytes| VerifyingKey::from_bytes(&bytes).ok())
+        .ok_or_else(|| "update-public-key must be an Ed25519 public key in hex".to_string())
+}
+
+/// Set at launch from the manifest; without a key, downloads are refused.
+pub fn set_public_key(key: VerifyingKey) {
+    *PUBLIC_KEY.lock().unwrap_or_else(|e| e.into_inner()) = Some(key);
+}
+
+/// Whether `signature` (hex) is `key`'s signature of `data` released as
+/// `version`.
+fn verify_signature(key: &VerifyingKey, version: &str, data: &[u8], signature: &str) -> bool {
+    let Some(signature) = from_hex(signature).map(|bytes| Signature::from_bytes(&bytes)) else {
+        return false;
+    };
+    let mut signed = Vec::with_capacity(version.len() + 1 + data.len());
+    signed.extend_from_slice(version.trim().as_bytes());
+    signed.push(b'\n');
+    signed.extend_from_slice(data);
+    key.verify_strict(&signed, &signature).is_ok()
+}
+
+/// `<exe>.update`: the staged executable.
+fn staged_path(exe: &Path) -> PathBuf {
+    let mut path = exe.as_os_str().to_owned();
+    path.push(".update");
+    PathBuf::from(path)
+}
+
+/// `<exe>.old`: the executable an update replaced, removed at the next launch.
+fn replaced_path(exe: &Path) -> PathBuf {
+    let mut path = exe.as_os_str().to_owned();
+    path.push(".old");
+    PathBuf::from(path)
+}
+
+/// Fetch the feed at `url`; the newer version and its notes, if it offers one.
+pub fn check(url: &str, current_version: &str) -> Result<Option<(String, Option<String>)>, String> {
+    let output = Command::new("curl")
+        .args(["-fsSL", url])
+        .stderr(Stdio::piped())
+        .output()
+        .map_err(|e| format!("could not start curl: {e}"))?;
+    if !output.status.success() {
+        return Err(format!(
+            "could not fetch {url}: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    let feed: Feed =
+        serde_json::from_slice(&output.stdout).map_err(|e| format!("invalid update feed: {e}"))?;
+    if !is_newer(&feed.version, current_version) {
+        return Ok(None);
+    }
+    let found = (feed.version.clone(), feed.notes.clone());
+    *OFFER.lock().unwrap_or_else(|e| e.into_inner()) = Some(feed);
+    Ok(Some(found))
+}
+
+/// Download `artifact` of `version` into memory and check its hash and
+/// signature, reporting progress as `done` plus what has arrived so far.
+fn fetch(
+    artifact: &Artifact,
+    version: &str,
+    key: &VerifyingKey,
+    dir: &Path,
+    done: u64,
+    total: Option<u64>,
+    events: &UiEventSender,
+) -> Result<Vec<u8>, String> {
+    let part = dir.join(format!("vellum-update-{}.part", std::process::id()));
+    let mut child = Command::new("curl")
+        .args(["-fsSL", "-o"])
+        .arg(&part)
+        .arg(&artifact.url)
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("could not start curl: {e}"))?;
+    let mut reported = None;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) => {}
+            Err(e) => return Err(format!("download failed: {e}")),
+        }
+        let downloaded = done + fs::metadata(&part).map_or(0, |meta| meta.len());
+        if reported != Some(downloaded) {
+            reported = Some(downloaded);
+            let _ = events.send(UiEvent::UpdateProgress { downloaded, total });
+        }
+        std::thread::sleep(PROGRESS_INTERVAL);
+    };
+    let data = fs::read(&part);
+    let _ = fs::remove_file(&part);
+    if !status.success() {
+        let stderr = child
+            .wait_with_output()
+            .map(|output| String::from_utf8_lossy(&output.stderr).trim().to_string())
+            .unwrap_or_default();
+        return Err(format!("could not download {}: {stderr}", artifact.url));
+    }
+    let data = data.map_err(|e| format!("could not read the download: {e}"))?;
+    if !sha256_hex(&data).eq_ignore_ascii_case(artifact.sha256.trim()) {
+        return Err(format!("{} does not match its SHA-256", artifact.url));
+    }
+    if !verify_signature(key, version, &data, &artifact.signature) {
+        return Err(format!(
+            "{} is not signed as version {version} with the app's update key",
+            artifact.url
+        ));
+    }
+    let _ = events.send(UiEvent::UpdateProgress {
+        downloaded: done + data.len() as u64,
+        total,
+    });
+    Ok(data)
+}
+
+/// Download what the last check offered and stage it. Returns the staged
+/// version.
+pub fn download(events: &UiEventSender) -> Result<String, String> {
+    if DOWNLOADING.swap(true, Ordering::AcqRel) {
+        return Err("an update is already downloading".to_string());
+    }
+    let result = download_offer(events);
+    DOWNLOADING.store(false, Ordering::Release);
+    result
+}
+
+fn download_offer(events: &UiEventSender) -> Result<String, String> {
+    let feed = OFFER
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .ok_or("no update has been found; call CheckForUpdate first")?;
+    let key = PUBLIC_KEY
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .ok_or("the app's manifest has no update-public-key to verify updates with")?;
+    let exe = std::env::current_exe().map_err(|e| format!("cannot locate the executable: {e}"))?;
+    let current = fs::read(&exe).map_err(|e| format!("cannot read {}: {e}", exe.display()))?;
+    let (runtime, Some(archive)) = bundle::split_appended(&current).map_err(|e| e.to_string())?
+    else {
+        return Err("only packaged apps (vellum build) can update themselves".to_string());
+    };
+
+    let platform = format!("{}-{}", std::env::consts::OS, std::env::consts::ARCH);
+    let binary = feed.binaries.get(&platform);
+    if feed.bundle.is_none() && binary.is_none() {
+        return Err(format!(
+            "version {} has nothing for {platform}",
+            feed.version
+        ));
+    }
+    let artifacts: Vec<&Artifact> = feed.bundle.iter().chain(binary).collect();
+    let total = artifacts.iter().map(|artifact| artifact.size).sum();
+
+    let dir = exe.parent().unwrap_or(Path::new("."));
+    let mut done = 0;
+    let new_archive = match &feed.bundle {
+        Some(artifact) => {
+            let data = fetch(artifact, &feed.version, &key, dir, done, total, events)?;
+            bundle::read_archive(&data).map_err(|e| format!("invalid app bundle: {e}"))?;
+            done += data.len() as u64;
+            Some(data)
+        }
+        None => None,
+    };
+    let new_runtime = match binary {
+        Some(artifact) => {
+            let data = fetch(artifact, &feed.version, &key, dir, done, total, events)?;
+            let (bare, _) = bundle::split_appended(&data).map_err(|e| e.to_string())?;
+            Some(bare.to_vec())
+        }
+        None => None,
+    };
+
+    let mut image = new_runtime.unwrap_or_else(|| runtime.to_vec());
+    image.extend_from_slice(new_archive.as_deref().unwrap_or(archive));
+    stage(&exe, &image).map_err(|e| format!("could not stage the update: {e}"))?;
+    println!("[Update] Version {} is ready", feed.version);
+    Ok(feed.version)
+}
+
+/// Write `image` beside `exe` with the same permissions.
+fn stage(exe: &Path, image: &[u8]) -> io::Result<()> {
+    let staged = staged_path(exe);
+    fs::write(&staged, image)?;
+    fs::set_permissions(&staged, fs::metadata(exe)?.permissions())
+}
+
+/// Whether an update is staged beside the running executable.
+pub fn is_staged() -> bool {
+    std::env::current_exe().is_ok_and(|exe| staged_path(&exe).is_file())
+}
+
+/// Move the staged executable into place. The running one is renamed out of
+/// the way rather than overwritten, which Windows does not allow.
+fn swap(exe: &Path) -> io::Result<bool> {
+    let staged = staged_path(exe);
+    if !staged.is_file() {
+        return Ok(false);
+    }
+    let replaced = replaced_path(exe);
+    let _ = fs::remove_file(&replaced);
+    fs::rename(exe, &replaced)?;
+    if let Err(e) = fs::rename(&staged, exe) {
+        // Put the old executable back so the app still starts.
+        let _ = fs::rename(&replaced, exe);
+        return Err(e);
+    }
+    Ok(true)
+}
+
+/// Run the updated executable with this launch's arguments.
+fn spawn(exe: &Path) -> io::Result<std::process::Child> {
+    Command::new(exe).args(std::env::args_os().skip(1)).spawn()
+}
+
+/// Called first thing at launch: clean up after the last update and install
+/// one staged by an earlier run, restarting into it. Returns the updated app's
+/// exit code if it ran, which this launch should then exit with.
+pub fn install_staged() -> Option<i32> {
+    let Ok(exe) = std::env::current_exe() else {
+        return None;
+    };
+    let _ = fs::remove_file(replaced_path(&exe));
+    match swap(&exe) {
+        Ok(true) => {
+            println!("[Update] Installed the staged update; restarting");
+            match spawn(&exe).and_then(|mut child| child.wait()) {
+                Ok(status) => return Some(status.code().unwrap_or(1)),
+                Err(e) => eprintln!("[Update] Could not start the updated app: {e}"),
+            }
+        }
+        Ok(false) => {}
+        Err(e) => eprintln!("[Update] Could not install the staged update: {e}"),
+    }
+    None
+}
+
+/// Relaunch into the staged update once the UI closes.
+pub fn request_relaunch() {
+    RELAUNCH.store(true, Ordering::Relaxed);
+}
+
+/// Called after the UI and Bun have shut down: if `RestartToUpdate` asked for
+/// it, install the staged update and start it.
+pub fn relaunch_if_requested() {
+    if !RELAUNCH.load(Ordering::Relaxed) {
+        return;
+    }
+    let Ok(exe) = std::env::current_exe() else {
+        return;
+    };
+    match swap(&exe) {
+        Ok(true) => {
+            println!("[Update] Restarting into the update");
+            if let Err(e) = spawn(&exe) {
+                eprintln!("[Update] Could not restart the app: {e}");
+            }
+        }
+        Ok(false) => eprintln!("[Update] No update is staged; not restarting"),
+        Err(e) => eprintln!("[Update] Could not install the update: {e}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ed25519_dalek::{Signer, SigningKey};
+
+    use super::*;
+
+    #[test]
+    fn test_newer_versions() {
+        assert!(is_newer("1.4.0", "1.3.9"));
+        assert!(is_newer("v1.10", "1.9.2"));
+        assert!(is_newer("2.0.0", "2.0.0-beta.1"));
+        assert!(is_newer("2.0.0-rc.1", "2.0.0-beta.3"));
+        assert!(is_newer("2.0.0-beta.10", "2.0.0-beta.9"));
+        assert!(!is_newer("2.0.0-beta.2", "2.0.0-beta.10"));
+        assert!(is_newer("2.0.0-beta.1.1", "2.0.0-beta.1"));
+        assert!(is_newer("2.0.0-beta", "2.0.0-1"));
+        assert!(!is_newer("1.4", "1.4.0"));
+        assert!(!is_newer("1.3.0", "1.4.0"));
+        assert!(!is_newer("2.0.0-beta.1", "2.0.0"));
+        assert!(!is_newer("latest", "1.0.0"));
+    }
+
+    #[test]
+    fn test_sha256_known_digests() {
+        assert_eq!(
+            sha256_hex(b""),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        assert_eq!(
+            sha256_hex(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        // Two blocks once padded
+        assert_eq!(
+            sha256_hex(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"),
+            "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"
+        );
+    }
+
+    #[test]
+    fn test_signatures_are_checked_against_the_key() {
+        let signer = SigningKey::from_bytes(&[7; 32]);
+        let key = parse_public_key(&to_hex(signer.verifying_key().as_bytes())).unwrap();
+        let signature = to_hex(&signer.sign(b"1.4.0\nbundle").to_bytes());
+        assert!(verify_signature(&key, "1.4.0", b"bundle", &signature));
+        assert!(verify_signature(
+            &key,
+            "1.4.0",
+            b"bundle",
+            &signature.to_uppercase()
+        ));
+        assert!(!verify_signature(&key, "1.4.0", b"bundlf", &signature));
+        assert!(!verify_signature(&key, "1.4.0", b"bundle", &signature[2..]));
+        // An old file relabelled as a newer version
+        assert!(!verify_signature(&key, "9.9.9", b"bundle", &signature));
+
+        let other = SigningKey::from_bytes(&[8; 32]).verifying_key();
+        assert!(!verify_signature(&other, "1.4.0", b"bundle", &signature));
+        assert!(parse_public_key("00").is_err());
+        assert!(parse_public_key(&"zz".repeat(32)).is_err());
+    }
+
+    #[test]
+    fn test_feed_parses_without_optional_fields() {
+        let feed: Feed = serde_json::from_str(
+            r#"{"version": "1.4.0", "binaries": {"linux-x86_64": {"url": "https://example.com/vellum", "sha256": "00", "signature": "00"}}}"#,
+        )
+        .unwrap();
+        assert!(feed.bundle.is_none());
+        assert!(feed.notes.is_none());
+        assert_eq!(feed.binaries["linux-x86_64"].size, None);
+    }
+}